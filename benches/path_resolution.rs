@@ -52,15 +52,20 @@ fn create_test_metadata(
             lib_c_type: None,
             package_type: "jdk".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: Some(true),
+            release_date: None,
         },
         installation_metadata: InstallationMetadata {
             java_home_suffix: java_home_suffix.to_string(),
             structure_type: structure_type.to_string(),
             platform: "macos".to_string(),
             metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
         },
     }
 }
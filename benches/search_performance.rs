@@ -84,6 +84,7 @@ fn create_realistic_cache() -> MetadataCache {
                                     None
                                 },
                                 javafx_bundled: false,
+                                features: Vec::new(),
                                 term_of_support: if major == 8
                                     || major == 11
                                     || major == 17
@@ -95,6 +96,7 @@ fn create_realistic_cache() -> MetadataCache {
                                 },
                                 release_status: Some("ga".to_string()),
                                 latest_build_available: Some(true),
+                                release_date: None,
                             });
                         }
                     }
@@ -140,6 +142,7 @@ fn create_cache_with_size(size: usize) -> MetadataCache {
             size: 100_000_000,
             lib_c_type: Some("glibc".to_string()),
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: if major == 8 || major == 11 || major == 17 || major == 21 {
                 Some("lts".to_string())
             } else {
@@ -147,6 +150,7 @@ fn create_cache_with_size(size: usize) -> MetadataCache {
             },
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         });
     }
 
@@ -285,9 +289,11 @@ pub fn bench_search_performance(c: &mut Criterion) {
             size: 100_000_000,
             lib_c_type: Some("glibc".to_string()),
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         };
         b.iter(|| {
             // Simulate conversion by cloning
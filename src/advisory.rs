@@ -0,0 +1,414 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Vulnerability advisories for `kopi audit`.
+//!
+//! The advisory feed is a JSON document listing CVEs together with the
+//! release lines they affect and the first patched version of each line. It
+//! is cached under `~/.kopi/cache/advisories.json` and re-downloaded once it
+//! is older than `audit.max_age_hours`, much like the metadata cache.
+
+use crate::config::AuditConfig;
+use crate::error::{KopiError, Result};
+use crate::paths::cache::advisory_cache_file;
+use crate::platform::file_ops::atomic_rename;
+use crate::security::tls;
+use crate::storage::InstalledJdk;
+use crate::user_agent;
+use crate::version::Version;
+use attohttpc::Session;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Severity {
+    type Err = KopiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "low" => Ok(Severity::Low),
+            "medium" | "moderate" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            other => Err(KopiError::ValidationError(format!(
+                "Unknown severity '{other}'. Expected low, medium, high, or critical"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdvisoryFeed {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    #[serde(default)]
+    pub advisories: Vec<Advisory>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Advisory {
+    /// CVE or vendor advisory identifier
+    pub id: String,
+    pub severity: Severity,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub affected: Vec<AffectedRange>,
+}
+
+/// A release line affected by an advisory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AffectedRange {
+    /// Distributions affected; empty means every distribution
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distributions: Vec<String>,
+    pub major: u32,
+    /// First patched version of the line; `None` while no fix is released
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<String>,
+}
+
+impl AffectedRange {
+    fn affects(&self, jdk: &InstalledJdk) -> bool {
+        if self.major != jdk.version.major() {
+            return false;
+        }
+        if !self.distributions.is_empty()
+            && !self
+                .distributions
+                .iter()
+                .any(|dist| dist.eq_ignore_ascii_case(&jdk.distribution))
+        {
+            return false;
+        }
+
+        match self.fixed.as_deref().map(Version::from_str) {
+            None => true,
+            Some(Ok(fixed)) => precedes_fix(&jdk.version, &fixed),
+            Some(Err(e)) => {
+                warn!("Ignoring advisory range with unparsable fixed version: {e}");
+                false
+            }
+        }
+    }
+}
+
+/// Installed builds of the fixed version still count as patched; an
+/// early-access build of it does not
+fn precedes_fix(installed: &Version, fixed: &Version) -> bool {
+    let len = installed.components.len().max(fixed.components.len());
+    let component = |version: &Version, index: usize| -> u32 {
+        version.components.get(index).copied().unwrap_or(0)
+    };
+    for index in 0..len {
+        match component(installed, index).cmp(&component(fixed, index)) {
+            Ordering::Less => return true,
+            Ordering::Greater => return false,
+            Ordering::Equal => {}
+        }
+    }
+    installed.is_early_access() && !fixed.is_early_access()
+}
+
+/// An advisory that applies to an installed JDK
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AdvisoryMatch {
+    pub id: String,
+    pub severity: Severity,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub fixed: Option<String>,
+}
+
+/// Audit result for one installed JDK
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditEntry {
+    pub distribution: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub advisories: Vec<AdvisoryMatch>,
+    /// Highest severity among `advisories`
+    pub severity: Option<Severity>,
+    /// Lowest version of the line that fixes every advisory with a released fix
+    pub fixed_in: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn is_vulnerable(&self) -> bool {
+        !self.advisories.is_empty()
+    }
+}
+
+/// Match each installed JDK against the feed
+pub fn audit_installed(feed: &AdvisoryFeed, installed: &[InstalledJdk]) -> Vec<AuditEntry> {
+    installed
+        .iter()
+        .map(|jdk| {
+            let mut advisories: Vec<AdvisoryMatch> = feed
+                .advisories
+                .iter()
+                .filter_map(|advisory| {
+                    let range = advisory.affected.iter().find(|range| range.affects(jdk))?;
+                    Some(AdvisoryMatch {
+                        id: advisory.id.clone(),
+                        severity: advisory.severity,
+                        summary: advisory.summary.clone(),
+                        url: advisory.url.clone(),
+                        fixed: range.fixed.clone(),
+                    })
+                })
+                .collect();
+            advisories.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.id.cmp(&b.id)));
+
+            let fixed_in = advisories
+                .iter()
+                .filter_map(|advisory| advisory.fixed.as_deref())
+                .filter_map(|fixed| Version::from_str(fixed).ok())
+                .max_by(|a, b| a.components.cmp(&b.components))
+                .map(|version| version.to_string());
+
+            AuditEntry {
+                distribution: jdk.distribution.clone(),
+                version: jdk.version.to_string(),
+                path: jdk.path.clone(),
+                severity: advisories.iter().map(|advisory| advisory.severity).max(),
+                advisories,
+                fixed_in,
+            }
+        })
+        .collect()
+}
+
+/// Load the advisory feed, downloading it when the cache is missing, stale, or
+/// `refresh` is set. A stale cache is used when the download fails.
+pub fn load_feed(config: &AuditConfig, kopi_home: &Path, refresh: bool) -> Result<AdvisoryFeed> {
+    let cache_path = advisory_cache_file(kopi_home);
+    let max_age = Duration::from_secs(config.max_age_hours.saturating_mul(3600));
+
+    if !refresh && is_fresh(&cache_path, max_age) {
+        match read_feed(&cache_path) {
+            Ok(feed) => return Ok(feed),
+            Err(e) => warn!("Ignoring unreadable advisory cache: {e}"),
+        }
+    }
+
+    match fetch_feed(&config.feed_url) {
+        Ok(content) => {
+            let feed = parse_feed(&content)?;
+            if let Err(e) = write_cache(&cache_path, &content) {
+                warn!("Failed to cache advisory feed: {e}");
+            }
+            Ok(feed)
+        }
+        Err(e) if cache_path.exists() => {
+            warn!("Failed to refresh advisory feed, using cached copy: {e}");
+            read_feed(&cache_path)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn is_fresh(path: &Path, max_age: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < max_age)
+}
+
+fn read_feed(path: &Path) -> Result<AdvisoryFeed> {
+    parse_feed(&fs::read_to_string(path)?)
+}
+
+fn parse_feed(content: &str) -> Result<AdvisoryFeed> {
+    serde_json::from_str(content)
+        .map_err(|e| KopiError::MetadataFetch(format!("Failed to parse advisory feed: {e}")))
+}
+
+fn fetch_feed(url: &str) -> Result<String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        debug!("Reading advisory feed from {path}");
+        return fs::read_to_string(path).map_err(|e| {
+            KopiError::MetadataFetch(format!("Failed to read advisory feed {path}: {e}"))
+        });
+    }
+
+    crate::security::verify_https_security(url)?;
+    debug!("Downloading advisory feed from {url}");
+
+    let mut client = Session::new();
+    tls::configure_session(&mut client);
+    client.header("User-Agent", user_agent::metadata_client());
+
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| KopiError::MetadataFetch(format!("Failed to fetch advisory feed: {e}")))?;
+    if !response.is_success() {
+        return Err(KopiError::MetadataFetch(format!(
+            "Failed to fetch advisory feed: HTTP {}",
+            response.status()
+        )));
+    }
+    response
+        .text()
+        .map_err(|e| KopiError::MetadataFetch(format!("Failed to read advisory feed: {e}")))
+}
+
+fn write_cache(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let staging = path.with_extension("json.tmp");
+    fs::write(&staging, content)?;
+    atomic_rename(&staging, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn jdk(distribution: &str, version: &str) -> InstalledJdk {
+        InstalledJdk::new(
+            distribution.to_string(),
+            Version::from_str(version).unwrap(),
+            PathBuf::from(format!("/kopi/jdks/{distribution}-{version}")),
+            false,
+        )
+    }
+
+    fn feed() -> AdvisoryFeed {
+        serde_json::from_str(
+            r#"{
+                "updated": "2025-01-21T00:00:00Z",
+                "advisories": [
+                    {
+                        "id": "CVE-2025-0001",
+                        "severity": "critical",
+                        "summary": "Remote code execution in Hotspot",
+                        "affected": [
+                            {"major": 21, "fixed": "21.0.6"},
+                            {"major": 17, "fixed": "17.0.14"}
+                        ]
+                    },
+                    {
+                        "id": "CVE-2025-0002",
+                        "severity": "medium",
+                        "summary": "TLS handshake issue",
+                        "affected": [{"distributions": ["zulu"], "major": 21, "fixed": "21.0.7"}]
+                    },
+                    {
+                        "id": "CVE-2025-0003",
+                        "severity": "high",
+                        "summary": "Unpatched JMX issue",
+                        "affected": [{"major": 11}]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_audit_matches_release_lines() {
+        let installed = vec![
+            jdk("temurin", "21.0.5"),
+            jdk("zulu", "21.0.6"),
+            jdk("temurin", "17.0.14+7"),
+            jdk("corretto", "11.0.25"),
+        ];
+        let entries = audit_installed(&feed(), &installed);
+
+        let temurin_21 = &entries[0];
+        assert_eq!(temurin_21.severity, Some(Severity::Critical));
+        assert_eq!(temurin_21.advisories.len(), 1);
+        assert_eq!(temurin_21.fixed_in.as_deref(), Some("21.0.6"));
+
+        let zulu_21 = &entries[1];
+        assert_eq!(zulu_21.severity, Some(Severity::Medium));
+        assert_eq!(zulu_21.advisories[0].id, "CVE-2025-0002");
+        assert_eq!(zulu_21.fixed_in.as_deref(), Some("21.0.7"));
+
+        assert!(!entries[2].is_vulnerable());
+
+        let corretto_11 = &entries[3];
+        assert_eq!(corretto_11.severity, Some(Severity::High));
+        assert_eq!(corretto_11.fixed_in, None);
+    }
+
+    #[test]
+    fn test_precedes_fix() {
+        let v = |s: &str| Version::from_str(s).unwrap();
+        assert!(precedes_fix(&v("21.0.5"), &v("21.0.6")));
+        assert!(!precedes_fix(&v("21.0.6+7"), &v("21.0.6")));
+        assert!(!precedes_fix(&v("21.0.7"), &v("21.0.6")));
+        assert!(precedes_fix(&v("21.0.6-ea"), &v("21.0.6")));
+    }
+
+    #[test]
+    fn test_load_feed_caches_file_feed() {
+        let temp_dir = TempDir::new().unwrap();
+        let feed_path = temp_dir.path().join("feed.json");
+        fs::write(&feed_path, serde_json::to_string(&feed()).unwrap()).unwrap();
+        let kopi_home = temp_dir.path().join("home");
+
+        let config = AuditConfig {
+            feed_url: format!("file://{}", feed_path.display()),
+            max_age_hours: 24,
+        };
+        let loaded = load_feed(&config, &kopi_home, false).unwrap();
+        assert_eq!(loaded.advisories.len(), 3);
+        assert!(advisory_cache_file(&kopi_home).exists());
+
+        // A fresh cache is used even when the feed disappears
+        fs::remove_file(&feed_path).unwrap();
+        let cached = load_feed(&config, &kopi_home, false).unwrap();
+        assert_eq!(cached, loaded);
+
+        // Forced refresh falls back to the cache when the download fails
+        let fallback = load_feed(&config, &kopi_home, true).unwrap();
+        assert_eq!(fallback, loaded);
+    }
+}
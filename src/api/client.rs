@@ -16,10 +16,11 @@ use crate::api::query::PackageQuery;
 use crate::error::{KopiError, Result};
 use crate::models::api::*;
 use crate::platform::get_foojay_libc_type;
+use crate::security::tls;
 use crate::user_agent;
 use attohttpc::{RequestBuilder, Session};
+use chrono::{DateTime, Utc};
 use log::{debug, trace};
-use retry::{OperationResult, delay::Exponential, retry_with_index};
 use std::thread;
 use std::time::Duration;
 
@@ -28,6 +29,27 @@ pub const API_VERSION: &str = "v3.0";
 const DEFAULT_TIMEOUT: u64 = 30;
 const MAX_RETRIES: usize = 3;
 const INITIAL_BACKOFF_MS: u64 = 1000;
+/// Longest `Retry-After` the client honours; longer requests are cut short
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Packages fetched one distribution at a time by
+/// [`ApiClient::get_packages_by_distribution`]
+#[derive(Debug, Default)]
+pub struct PartialPackages {
+    pub packages: Vec<Package>,
+    /// Distributions whose request still failed after retrying, with the error
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Outcome of a single request attempt
+enum Attempt<T> {
+    Done(Result<T>),
+    /// A transient failure; retry after the server's `Retry-After`, if any
+    Retry {
+        error: KopiError,
+        retry_after: Option<Duration>,
+    },
+}
 
 #[derive(Debug, Clone)]
 pub struct ApiClient {
@@ -41,6 +63,7 @@ impl ApiClient {
         session.header("User-Agent", user_agent::api_client());
         session.timeout(Duration::from_secs(DEFAULT_TIMEOUT));
         session.proxy_settings(attohttpc::ProxySettings::from_env());
+        tls::configure_session(&mut session);
 
         Self {
             session,
@@ -211,6 +234,39 @@ impl ApiClient {
         })
     }
 
+    /// Run `query` once for each distribution the API lists, so a rate limited
+    /// or failing request loses only that distribution. Fails when the
+    /// distribution list cannot be fetched or every distribution failed.
+    pub fn get_packages_by_distribution(&self, query: PackageQuery) -> Result<PartialPackages> {
+        let distributions = self.get_distributions()?;
+        let mut partial = PartialPackages::default();
+        for distribution in &distributions {
+            let query = query
+                .clone()
+                .distribution(distribution.api_parameter.clone());
+            match self.get_packages(Some(query)) {
+                Ok(packages) => partial.packages.extend(packages),
+                Err(e) => {
+                    debug!(
+                        "Failed to fetch packages for {}: {e}",
+                        distribution.api_parameter
+                    );
+                    partial
+                        .skipped
+                        .push((distribution.api_parameter.clone(), e.to_string()));
+                }
+            }
+        }
+
+        if !distributions.is_empty() && partial.skipped.len() == distributions.len() {
+            let (_, error) = &partial.skipped[0];
+            return Err(KopiError::MetadataFetch(format!(
+                "Failed to fetch packages for every distribution: {error}"
+            )));
+        }
+        Ok(partial)
+    }
+
     pub fn get_distributions(&self) -> Result<Vec<Distribution>> {
         let url = format!("{}/{API_VERSION}/distributions", self.base_url);
         self.execute_with_retry(move || self.session.get(&url))
@@ -308,68 +364,91 @@ impl ApiClient {
         })
     }
 
+    /// Send the request, retrying network errors, rate limits (429) and
+    /// transient server errors (500, 502, 503, 504). A `Retry-After` header
+    /// replaces the exponential backoff, capped at [`MAX_RETRY_AFTER`].
     fn execute_with_retry_raw<T, F, P>(&self, request_builder: F, parser: P) -> Result<T>
     where
         F: Fn() -> RequestBuilder,
         P: Fn(String) -> Result<T>,
     {
-        let result = retry_with_index(
-            Exponential::from_millis(INITIAL_BACKOFF_MS).take(MAX_RETRIES),
-            |current_try| {
-                let response = match request_builder().send() {
-                    Ok(resp) => resp,
-                    Err(e) => {
-                        let user_error = KopiError::MetadataFetch(format!(
-                            "Network error connecting to foojay.io API v{API_VERSION}: {e}. \
-                             Please check your internet connection and try again."
-                        ));
-
-                        if current_try < (MAX_RETRIES - 1) as u64 {
-                            return OperationResult::Retry(user_error);
-                        }
-                        return OperationResult::Err(user_error);
-                    }
+        let mut backoff = Duration::from_millis(INITIAL_BACKOFF_MS);
+        for attempt in 1..=MAX_RETRIES {
+            let (error, retry_after) = match self.attempt(&request_builder, &parser) {
+                Attempt::Done(result) => return result,
+                Attempt::Retry { error, retry_after } => (error, retry_after),
+            };
+            if attempt == MAX_RETRIES {
+                return Err(error);
+            }
+
+            let delay = retry_after.map_or(backoff, |delay| delay.min(MAX_RETRY_AFTER));
+            debug!(
+                "{error} Retrying in {}ms (attempt {attempt} of {MAX_RETRIES})",
+                delay.as_millis()
+            );
+            thread::sleep(delay);
+            backoff *= 2;
+        }
+        unreachable!("the last attempt always returns")
+    }
+
+    fn attempt<T, F, P>(&self, request_builder: &F, parser: &P) -> Attempt<T>
+    where
+        F: Fn() -> RequestBuilder,
+        P: Fn(String) -> Result<T>,
+    {
+        let response = match request_builder().send() {
+            Ok(resp) => resp,
+            Err(e) => {
+                return Attempt::Retry {
+                    error: KopiError::MetadataFetch(format!(
+                        "Network error connecting to foojay.io API v{API_VERSION}: {e}. \
+                         Please check your internet connection and try again."
+                    )),
+                    retry_after: None,
                 };
+            }
+        };
 
-                if response.status() == attohttpc::StatusCode::TOO_MANY_REQUESTS
-                    && current_try < (MAX_RETRIES - 1) as u64
-                {
-                    if let Some(retry_after) = response.headers().get("Retry-After")
-                        && let Ok(retry_str) = retry_after.to_str()
-                        && let Ok(seconds) = retry_str.parse::<u64>()
-                    {
-                        thread::sleep(Duration::from_secs(seconds));
-                    }
-                    return OperationResult::Retry(KopiError::MetadataFetch(
-                        "Too many requests. Waiting before retrying...".to_string(),
-                    ));
-                }
+        let status = response.status();
+        if matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504) {
+            let retry_after = response
+                .headers()
+                .get("Retry-After")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, Utc::now()));
+            let error = if status.as_u16() == 429 {
+                format!(
+                    "Rate limited by foojay.io API v{API_VERSION} (HTTP 429). Please try again \
+                     later."
+                )
+            } else {
+                format!(
+                    "Server error ({}) occurred on foojay.io API v{API_VERSION}. Please try \
+                     again later.",
+                    status.as_u16()
+                )
+            };
+            return Attempt::Retry {
+                error: KopiError::MetadataFetch(error),
+                retry_after,
+            };
+        }
 
-                if !response.is_success() {
-                    let status = response.status();
-
-                    // Try to parse error response body for more specific error message
-                    let error_msg = if status.as_u16() == 400 {
-                        match response.text() {
-                            Ok(body) => {
-                                // Try to parse as API error response
-                                match serde_json::from_str::<crate::api::ApiErrorResponse>(&body) {
-                                    Ok(error_response) => {
-                                        // Check if the message indicates version not found
-                                        if error_response.message.contains("not released yet") {
-                                            format!(
-                                                "Version not available: {}",
-                                                error_response.message
-                                            )
-                                        } else {
-                                            format!("Bad request: {}", error_response.message)
-                                        }
-                                    }
-                                    Err(_) => format!(
-                                        "HTTP error ({}) from foojay.io API v{API_VERSION}: {}",
-                                        status.as_u16(),
-                                        status.canonical_reason().unwrap_or("Unknown error")
-                                    ),
+        if !response.is_success() {
+            // Try to parse error response body for more specific error message
+            let error_msg = if status.as_u16() == 400 {
+                match response.text() {
+                    Ok(body) => {
+                        // Try to parse as API error response
+                        match serde_json::from_str::<crate::api::ApiErrorResponse>(&body) {
+                            Ok(error_response) => {
+                                // Check if the message indicates version not found
+                                if error_response.message.contains("not released yet") {
+                                    format!("Version not available: {}", error_response.message)
+                                } else {
+                                    format!("Bad request: {}", error_response.message)
                                 }
                             }
                             Err(_) => format!(
@@ -378,45 +457,62 @@ impl ApiClient {
                                 status.canonical_reason().unwrap_or("Unknown error")
                             ),
                         }
-                    } else {
-                        match status.as_u16() {
-                            404 => format!(
-                                "The requested resource was not found on foojay.io API \
-                                 v{API_VERSION}. The API endpoint may have changed."
-                            ),
-                            500..=599 => format!(
-                                "Server error occurred on foojay.io API v{API_VERSION}. Please \
-                                 try again later."
-                            ),
-                            401 | 403 => format!(
-                                "Authentication failed for foojay.io API v{API_VERSION}. Please \
-                                 check your credentials."
-                            ),
-                            _ => format!(
-                                "HTTP error ({}) from foojay.io API v{API_VERSION}: {}",
-                                status.as_u16(),
-                                status.canonical_reason().unwrap_or("Unknown error")
-                            ),
-                        }
-                    };
-                    return OperationResult::Err(KopiError::MetadataFetch(error_msg));
+                    }
+                    Err(_) => format!(
+                        "HTTP error ({}) from foojay.io API v{API_VERSION}: {}",
+                        status.as_u16(),
+                        status.canonical_reason().unwrap_or("Unknown error")
+                    ),
                 }
-
-                // Try to get response text for debugging
-                match response.text() {
-                    Ok(body) => match parser(body) {
-                        Ok(data) => OperationResult::Ok(data),
-                        Err(e) => OperationResult::Err(e),
-                    },
-                    Err(e) => OperationResult::Err(KopiError::MetadataFetch(format!(
-                        "Failed to read response body: {e}"
-                    ))),
+            } else {
+                match status.as_u16() {
+                    404 => format!(
+                        "The requested resource was not found on foojay.io API \
+                         v{API_VERSION}. The API endpoint may have changed."
+                    ),
+                    500..=599 => format!(
+                        "Server error occurred on foojay.io API v{API_VERSION}. Please \
+                         try again later."
+                    ),
+                    401 | 403 => format!(
+                        "Authentication failed for foojay.io API v{API_VERSION}. Please \
+                         check your credentials."
+                    ),
+                    _ => format!(
+                        "HTTP error ({}) from foojay.io API v{API_VERSION}: {}",
+                        status.as_u16(),
+                        status.canonical_reason().unwrap_or("Unknown error")
+                    ),
                 }
+            };
+            return Attempt::Done(Err(KopiError::MetadataFetch(error_msg)));
+        }
+
+        // Try to get response text for debugging
+        match response.text() {
+            Ok(body) => match parser(body) {
+                Ok(data) => Attempt::Done(Ok(data)),
+                Err(e) => Attempt::Done(Err(e)),
             },
-        );
+            Err(e) => Attempt::Done(Err(KopiError::MetadataFetch(format!(
+                "Failed to read response body: {e}"
+            )))),
+        }
+    }
+}
 
-        result.map_err(|e| e.error)
+/// `Retry-After` as a delay: either seconds or an HTTP date relative to `now`
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
     }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
 }
 
 impl Default for ApiClient {
@@ -420,9 +420,11 @@ fn test_package_model_with_new_fields() {
         lib_c_type: Some("glibc".to_string()),
         package_type: "jdk".to_string(),
         javafx_bundled: false,
+        feature: Vec::new(),
         term_of_support: Some("lts".to_string()),
         release_status: Some("ga".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     };
 
     // Serialize to JSON
@@ -469,3 +471,111 @@ fn test_package_model_optional_fields() {
     assert_eq!(package.latest_build_available, None);
     assert_eq!(package.lib_c_type, None);
 }
+
+#[cfg(test)]
+fn distributions_body(names: &[&str]) -> String {
+    let distributions: Vec<String> = names
+        .iter()
+        .map(|name| {
+            format!(
+                r#"{{"name":"{name}","api_parameter":"{name}","maintained":true,"available":true,
+                "build_of_openjdk":true,"build_of_graalvm":false,"synonyms":[],"versions":[]}}"#
+            )
+        })
+        .collect();
+    format!(r#"{{"result":[{}]}}"#, distributions.join(","))
+}
+
+#[cfg(test)]
+fn packages_body(distribution: &str) -> String {
+    format!(
+        r#"{{"result":[{{"id":"{distribution}-21","archive_type":"tar.gz",
+        "distribution":"{distribution}","major_version":21,"java_version":"21.0.1",
+        "distribution_version":"21.0.1","jdk_version":21,"directly_downloadable":true,
+        "filename":"{distribution}.tar.gz","links":{{"pkg_download_redirect":"https://example.com"}},
+        "free_use_in_production":true,"tck_tested":"yes","size":1,"operating_system":"linux",
+        "package_type":"jdk","javafx_bundled":false}}]}}"#
+    )
+}
+
+#[test]
+fn test_parse_retry_after() {
+    use crate::api::client::parse_retry_after;
+    use chrono::{TimeZone, Utc};
+    use std::time::Duration;
+
+    let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+    assert_eq!(
+        parse_retry_after("120", now),
+        Some(Duration::from_secs(120))
+    );
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+        Some(Duration::from_secs(30))
+    );
+    // A date in the past means retry right away
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(parse_retry_after("soon", now), None);
+}
+
+#[test]
+fn test_rate_limited_request_is_retried() {
+    let mut server = mockito::Server::new();
+    // Mocks that still expect hits are served first
+    let limited = server
+        .mock("GET", "/v3.0/distributions")
+        .with_status(429)
+        .with_header("Retry-After", "0")
+        .expect(1)
+        .create();
+    let ok = server
+        .mock("GET", "/v3.0/distributions")
+        .with_body(distributions_body(&["temurin"]))
+        .expect(1)
+        .create();
+
+    let client = ApiClient::new().with_base_url(server.url());
+    let distributions = client.get_distributions().unwrap();
+
+    assert_eq!(distributions[0].api_parameter, "temurin");
+    limited.assert();
+    ok.assert();
+}
+
+#[test]
+fn test_packages_by_distribution_skips_failing_distributions() {
+    use mockito::Matcher;
+
+    let mut server = mockito::Server::new();
+    server
+        .mock("GET", "/v3.0/distributions")
+        .with_body(distributions_body(&["temurin", "zulu"]))
+        .create();
+    server
+        .mock("GET", "/v3.0/packages")
+        .match_query(Matcher::UrlEncoded("distribution".into(), "temurin".into()))
+        .with_body(packages_body("temurin"))
+        .create();
+    let unavailable = server
+        .mock("GET", "/v3.0/packages")
+        .match_query(Matcher::UrlEncoded("distribution".into(), "zulu".into()))
+        .with_status(503)
+        .with_header("Retry-After", "0")
+        .expect(3)
+        .create();
+
+    let client = ApiClient::new().with_base_url(server.url());
+    let partial = client
+        .get_packages_by_distribution(PackageQuery::new())
+        .unwrap();
+
+    assert_eq!(partial.packages.len(), 1);
+    assert_eq!(partial.packages[0].distribution, "temurin");
+    assert_eq!(partial.skipped.len(), 1);
+    assert_eq!(partial.skipped[0].0, "zulu");
+    assert!(partial.skipped[0].1.contains("503"));
+    unavailable.assert();
+}
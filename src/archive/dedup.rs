@@ -0,0 +1,187 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Copy-on-write sharing of identical files after extraction.
+//!
+//! JDK archives ship some byte-identical files. On filesystems with reflink
+//! support the duplicates are replaced with clones of the first copy so that
+//! they share data blocks; elsewhere this pass is skipped entirely.
+
+use crate::error::Result;
+use crate::platform::file_ops::{self, CloneMethod};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Files smaller than this gain little from sharing a block or two.
+const MIN_SHARED_FILE_SIZE: u64 = 64 * 1024;
+
+/// Replace duplicate files under `root` with copy-on-write clones.
+///
+/// Returns how many files now share data with an identical sibling. Does
+/// nothing when `root` is on a filesystem without reflink support.
+pub(super) fn share_identical_files(root: &Path) -> Result<usize> {
+    if !file_ops::supports_reflink(root) {
+        return Ok(0);
+    }
+
+    let mut shared = 0;
+    for (original, duplicate) in find_duplicate_files(root, MIN_SHARED_FILE_SIZE)? {
+        match replace_with_clone(&original, &duplicate) {
+            Ok(CloneMethod::Reflink) => shared += 1,
+            Ok(CloneMethod::Copy) => {}
+            Err(err) => log::debug!(
+                "Could not share {} with {}: {err}",
+                duplicate.display(),
+                original.display()
+            ),
+        }
+    }
+
+    if shared > 0 {
+        log::info!("Shared data blocks for {shared} identical extracted files");
+    }
+    Ok(shared)
+}
+
+/// Find regular files of at least `min_size` bytes whose content matches an
+/// earlier file, as `(original, duplicate)` pairs.
+fn find_duplicate_files(root: &Path, min_size: u64) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let mut by_size: BTreeMap<u64, Vec<PathBuf>> = BTreeMap::new();
+    for entry in WalkDir::new(root).sort_by_file_name() {
+        let entry = entry.map_err(io::Error::from)?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let size = entry.metadata().map_err(io::Error::from)?.len();
+        if size >= min_size {
+            by_size.entry(size).or_default().push(entry.into_path());
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for paths in by_size.into_values().filter(|paths| paths.len() > 1) {
+        let mut seen: HashMap<Vec<u8>, PathBuf> = HashMap::new();
+        for path in paths {
+            let digest = hash_file(&path)?;
+            match seen.get(&digest) {
+                Some(original) => duplicates.push((original.clone(), path)),
+                None => {
+                    seen.insert(digest, path);
+                }
+            }
+        }
+    }
+
+    Ok(duplicates)
+}
+
+fn hash_file(path: &Path) -> io::Result<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Swap `duplicate` for a clone of `original`, keeping its permissions and mtime.
+fn replace_with_clone(original: &Path, duplicate: &Path) -> io::Result<CloneMethod> {
+    let file_name = duplicate
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let staging = duplicate.with_file_name(format!(".{file_name}.kopi-clone"));
+    let metadata = fs::metadata(duplicate)?;
+
+    let result = file_ops::clone_file(original, &staging).and_then(|method| {
+        fs::set_permissions(&staging, metadata.permissions())?;
+        File::options()
+            .write(true)
+            .open(&staging)?
+            .set_modified(metadata.modified()?)?;
+        file_ops::atomic_rename(&staging, duplicate)?;
+        Ok(method)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&staging);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_duplicate_files_matches_content_not_just_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let legal = temp_dir.path().join("legal");
+        fs::create_dir_all(legal.join("java.base")).unwrap();
+        fs::create_dir_all(legal.join("java.xml")).unwrap();
+
+        fs::write(legal.join("java.base/LICENSE"), b"same license").unwrap();
+        fs::write(legal.join("java.xml/LICENSE"), b"same license").unwrap();
+        fs::write(legal.join("java.xml/NOTICE"), b"diff license").unwrap();
+        fs::write(legal.join("tiny"), b"same").unwrap();
+
+        let duplicates = find_duplicate_files(temp_dir.path(), 5).unwrap();
+        assert_eq!(
+            duplicates,
+            vec![(
+                legal.join("java.base/LICENSE"),
+                legal.join("java.xml/LICENSE")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_share_identical_files_preserves_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = vec![7u8; MIN_SHARED_FILE_SIZE as usize];
+        fs::write(temp_dir.path().join("a.jmod"), &content).unwrap();
+        fs::write(temp_dir.path().join("b.jmod"), &content).unwrap();
+
+        // Shares blocks on btrfs/XFS/APFS and is a no-op elsewhere
+        share_identical_files(temp_dir.path()).unwrap();
+
+        assert_eq!(fs::read(temp_dir.path().join("a.jmod")).unwrap(), content);
+        assert_eq!(fs::read(temp_dir.path().join("b.jmod")).unwrap(), content);
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_replace_with_clone_keeps_permissions() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = temp_dir.path().join("original");
+        let duplicate = temp_dir.path().join("duplicate");
+        fs::write(&original, b"payload").unwrap();
+        fs::write(&duplicate, b"payload").unwrap();
+        file_ops::set_permissions_from_mode(&duplicate, 0o700).unwrap();
+
+        replace_with_clone(&original, &duplicate).unwrap();
+
+        assert_eq!(fs::read(&duplicate).unwrap(), b"payload");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(
+                fs::metadata(&duplicate).unwrap().permissions().mode() & 0o777,
+                0o700
+            );
+        }
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+}
@@ -12,15 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod dedup;
+mod normalize;
+mod strip;
+
 use crate::error::{KopiError, Result};
+use crate::indicator::{ProgressConfig, ProgressIndicator, ProgressStyle, SilentProgress};
+use crate::locking::CancellationToken;
 use crate::paths::install;
 use crate::platform::file_ops;
+use std::cell::Cell;
 use std::fs::{self, File};
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use tar::Archive as TarArchive;
 use zip::ZipArchive;
 
+pub use normalize::{NormalizeRule, NormalizeSummary, normalize_extracted, rules_for};
+pub use strip::{StripSummary, strip_paths};
+
 pub enum ArchiveType {
     TarGz,
     Zip,
@@ -32,8 +43,31 @@ pub struct ArchiveInfo {
     pub uncompressed_size: u64,
 }
 
+/// Entries extracted between progress updates and log lines
+const PROGRESS_INTERVAL: usize = 100;
+
 /// Extract a JDK archive to the specified destination
 pub fn extract_archive(archive_path: &Path, destination: &Path) -> Result<()> {
+    extract_archive_with_progress(
+        archive_path,
+        destination,
+        &mut SilentProgress,
+        &CancellationToken::new(),
+    )
+}
+
+/// Extract a JDK archive, reporting progress to `progress` and stopping with
+/// `KopiError::Cancelled` between entries once `cancellation` fires. The
+/// destination is left partially populated; the caller owns its cleanup.
+pub fn extract_archive_with_progress(
+    archive_path: &Path,
+    destination: &Path,
+    progress: &mut dyn ProgressIndicator,
+    cancellation: &CancellationToken,
+) -> Result<()> {
+    // Deeply nested JDK entries can exceed MAX_PATH on Windows
+    let destination = &file_ops::extended_length_path(destination);
+
     // Ensure destination directory exists
     fs::create_dir_all(destination)?;
 
@@ -43,10 +77,21 @@ pub fn extract_archive(archive_path: &Path, destination: &Path) -> Result<()> {
     // Verify archive integrity before extraction
     verify_integrity(archive_path, &archive_type)?;
 
-    match archive_type {
-        ArchiveType::TarGz => extract_tar_gz(archive_path, destination),
-        ArchiveType::Zip => extract_zip(archive_path, destination),
+    let extracted = match archive_type {
+        ArchiveType::TarGz => extract_tar_gz(archive_path, destination, progress, cancellation),
+        ArchiveType::Zip => extract_zip(archive_path, destination, progress, cancellation),
+    };
+    match &extracted {
+        Ok(count) => progress.complete(Some(format!("Extracted {count} files"))),
+        Err(e) => progress.error(format!("Extraction stopped: {e}")),
+    }
+    extracted?;
+
+    // Sharing blocks is an optimisation only, so failures never fail the install
+    if let Err(e) = dedup::share_identical_files(destination) {
+        log::debug!("Skipping copy-on-write sharing of extracted files: {e}");
     }
+    Ok(())
 }
 
 fn detect_archive_type(path: &Path) -> Result<ArchiveType> {
@@ -122,10 +167,23 @@ fn verify_integrity(archive_path: &Path, archive_type: &ArchiveType) -> Result<(
     }
 }
 
-fn extract_tar_gz(archive_path: &Path, destination: &Path) -> Result<()> {
+fn extract_tar_gz(
+    archive_path: &Path,
+    destination: &Path,
+    progress: &mut dyn ProgressIndicator,
+    cancellation: &CancellationToken,
+) -> Result<usize> {
     let file = File::open(archive_path)?;
-    let gz = flate2::read::GzDecoder::new(file);
+    // The entry count is unknown up front, so report compressed bytes consumed
+    let total_bytes = file.metadata()?.len();
+    let bytes_read = Rc::new(Cell::new(0u64));
+    let reader = CountingReader {
+        inner: file,
+        count: Rc::clone(&bytes_read),
+    };
+    let gz = flate2::read::GzDecoder::new(reader);
     let mut archive = TarArchive::new(gz);
+    progress.start(ProgressConfig::new(ProgressStyle::Bytes).with_total(total_bytes));
 
     // Configure archive extraction
     archive.set_preserve_permissions(true);
@@ -137,6 +195,7 @@ fn extract_tar_gz(archive_path: &Path, destination: &Path) -> Result<()> {
     let entries = archive.entries()?;
 
     for entry in entries {
+        check_cancelled(cancellation, extracted_count)?;
         let mut entry = entry?;
         let path = entry.path()?;
 
@@ -162,23 +221,33 @@ fn extract_tar_gz(archive_path: &Path, destination: &Path) -> Result<()> {
         entry.unpack(&dest_path)?;
         extracted_count += 1;
 
-        // Log extraction progress for large archives
-        if extracted_count % 100 == 0 {
+        // Report extraction progress for large archives
+        if extracted_count % PROGRESS_INTERVAL == 0 {
             log::debug!("Extracted {extracted_count} files...");
+            progress.update(bytes_read.get(), Some(total_bytes));
+            progress.set_message(format!("Extracted {extracted_count} files"));
         }
     }
 
+    progress.update(total_bytes, Some(total_bytes));
     log::info!("Extracted {extracted_count} files from tar.gz archive");
-    Ok(())
+    Ok(extracted_count)
 }
 
-fn extract_zip(archive_path: &Path, destination: &Path) -> Result<()> {
+fn extract_zip(
+    archive_path: &Path,
+    destination: &Path,
+    progress: &mut dyn ProgressIndicator,
+    cancellation: &CancellationToken,
+) -> Result<usize> {
     let file = File::open(archive_path)?;
     let mut archive = ZipArchive::new(file)?;
 
     let total_files = archive.len();
+    progress.start(ProgressConfig::new(ProgressStyle::Count).with_total(total_files as u64));
 
     for i in 0..total_files {
+        check_cancelled(cancellation, i)?;
         let mut file = archive.by_index(i)?;
         let outpath = match file.enclosed_name() {
             Some(path) => {
@@ -250,16 +319,42 @@ fn extract_zip(archive_path: &Path, destination: &Path) -> Result<()> {
             file_ops::set_permissions_from_mode(&outpath, mode)?;
         }
 
-        // Log extraction progress for large archives
-        if (i + 1) % 100 == 0 {
+        // Report extraction progress for large archives
+        if (i + 1) % PROGRESS_INTERVAL == 0 {
             log::debug!("Extracted {}/{} files...", i + 1, total_files);
+            progress.update((i + 1) as u64, Some(total_files as u64));
+            progress.set_message(format!("Extracted {} of {total_files} files", i + 1));
         }
     }
 
+    progress.update(total_files as u64, Some(total_files as u64));
     log::info!("Extracted {total_files} files from zip archive");
+    Ok(total_files)
+}
+
+fn check_cancelled(cancellation: &CancellationToken, extracted: usize) -> Result<()> {
+    if cancellation.is_cancelled() {
+        return Err(KopiError::Cancelled(format!(
+            "archive extraction stopped after {extracted} files"
+        )));
+    }
     Ok(())
 }
 
+/// Reader that records how many bytes passed through it
+struct CountingReader<R> {
+    inner: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count.set(self.count.get() + read as u64);
+        Ok(read)
+    }
+}
+
 fn validate_entry_path(entry_path: &Path) -> Result<()> {
     // Ensure the entry path doesn't contain any parent directory references
     for component in entry_path.components() {
@@ -428,6 +523,18 @@ pub struct JdkStructureInfo {
     pub java_home_suffix: String,
 }
 
+impl JdkStructureInfo {
+    /// JAVA_HOME inside the extraction directory, which `install.strip` paths are relative to
+    pub fn java_home(&self) -> PathBuf {
+        let nested = self.jdk_root.join(&self.java_home_suffix);
+        if !self.java_home_suffix.is_empty() && nested.is_dir() {
+            nested
+        } else {
+            self.jdk_root.clone()
+        }
+    }
+}
+
 /// Detect the JDK root directory and its structure type from an extracted archive
 ///
 /// This function analyzes the directory structure of an extracted JDK archive to determine
@@ -756,6 +863,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_extract_archive_cancelled() -> Result<()> {
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+
+        for archive in [create_test_tar_gz()?, create_test_zip()?] {
+            let dest_dir = tempdir()?;
+            let result = extract_archive_with_progress(
+                &archive.path,
+                dest_dir.path(),
+                &mut SilentProgress,
+                &cancellation,
+            );
+
+            assert!(matches!(result, Err(KopiError::Cancelled(_))));
+            assert!(!dest_dir.path().join("test.txt").exists());
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_validate_entry_path() {
         // Valid paths
@@ -0,0 +1,408 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Repair of vendor archive quirks after extraction.
+//!
+//! Some archives unpack into layouts the rest of kopi does not expect: an
+//! extra directory level, tools in `bin/` without exec bits, entries named
+//! with Windows path separators. Each fix is a [`NormalizeRule`] and
+//! [`RULES`] says which distributions it applies to, so a new vendor quirk
+//! needs a rule and a table row rather than changes to extraction.
+
+use super::{JdkStructureInfo, detect_jdk_root};
+use crate::error::Result;
+use crate::paths::install;
+use crate::platform::file_ops;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// A fix applied to an extracted JDK
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizeRule {
+    /// Collapse a chain of directories that each hold only one directory
+    FlattenNestedDirectories,
+    /// Move entries named like `bin\java` to the nested path `bin/java`
+    SplitBackslashPaths,
+    /// Add exec bits to files in `bin/` and to `lib/jspawnhelper`
+    FixExecutablePermissions,
+    /// Rewrite broken symlinks whose targets use `\` separators
+    RepairSymlinks,
+}
+
+/// Rules per distribution id; the `*` row applies to every distribution
+const RULES: &[(&str, &[NormalizeRule])] = &[
+    (
+        "*",
+        &[
+            NormalizeRule::FixExecutablePermissions,
+            NormalizeRule::RepairSymlinks,
+        ],
+    ),
+    (
+        "dragonwell",
+        &[
+            NormalizeRule::FlattenNestedDirectories,
+            NormalizeRule::SplitBackslashPaths,
+        ],
+    ),
+    ("kona", &[NormalizeRule::FlattenNestedDirectories]),
+    (
+        "trava",
+        &[
+            NormalizeRule::FlattenNestedDirectories,
+            NormalizeRule::SplitBackslashPaths,
+        ],
+    ),
+];
+
+/// Directory a flattened chain is moved through
+const FLATTEN_STAGING_DIR: &str = ".kopi-flatten";
+
+/// Rules registered for `distribution`, including those for every distribution
+pub fn rules_for(distribution: &str) -> Vec<NormalizeRule> {
+    let mut rules = Vec::new();
+    for (name, entries) in RULES {
+        if *name == "*" || *name == distribution {
+            for rule in *entries {
+                if !rules.contains(rule) {
+                    rules.push(*rule);
+                }
+            }
+        }
+    }
+    rules
+}
+
+/// Fixes made by [`normalize_extracted`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizeSummary {
+    /// One description per rule that changed something
+    pub fixes: Vec<String>,
+}
+
+/// Detect the JDK root in `extracted_dir` and apply `distribution`'s rules.
+///
+/// Layout rules (flattening and backslash paths) also run on the whole
+/// extraction directory when detection fails, after which detection is
+/// retried once.
+pub fn normalize_extracted(
+    distribution: &str,
+    extracted_dir: &Path,
+) -> Result<(JdkStructureInfo, NormalizeSummary)> {
+    let rules = rules_for(distribution);
+    let mut summary = NormalizeSummary::default();
+
+    let structure = match detect_jdk_root(extracted_dir) {
+        Ok(structure) => structure,
+        Err(e) => {
+            if rules.contains(&NormalizeRule::SplitBackslashPaths) {
+                record(
+                    &mut summary,
+                    split_backslash_paths(extracted_dir)?,
+                    "entry name(s) with Windows separators split",
+                );
+            }
+            if rules.contains(&NormalizeRule::FlattenNestedDirectories)
+                && flatten_nested_directories(extracted_dir)?
+            {
+                summary
+                    .fixes
+                    .push("redundant directory levels flattened".to_string());
+            }
+            if summary.fixes.is_empty() {
+                return Err(e);
+            }
+            log::debug!("Retrying JDK root detection after {:?}", summary.fixes);
+            detect_jdk_root(extracted_dir)?
+        }
+    };
+
+    if rules.contains(&NormalizeRule::SplitBackslashPaths) {
+        record(
+            &mut summary,
+            split_backslash_paths(&structure.jdk_root)?,
+            "entry name(s) with Windows separators split",
+        );
+    }
+    if rules.contains(&NormalizeRule::FixExecutablePermissions) {
+        record(
+            &mut summary,
+            fix_executable_permissions(&structure.java_home())?,
+            "tool(s) made executable",
+        );
+    }
+    if rules.contains(&NormalizeRule::RepairSymlinks) {
+        record(
+            &mut summary,
+            repair_symlinks(&structure.jdk_root)?,
+            "symlink(s) repaired",
+        );
+    }
+
+    Ok((structure, summary))
+}
+
+fn record(summary: &mut NormalizeSummary, count: usize, what: &str) {
+    if count > 0 {
+        summary.fixes.push(format!("{count} {what}"));
+    }
+}
+
+/// Hoist the innermost directory of a chain like `a/b/bin` to `a/bin`.
+///
+/// A single wrapping directory is left alone; root detection already looks
+/// one level down.
+fn flatten_nested_directories(extracted_dir: &Path) -> Result<bool> {
+    let mut chain = Vec::new();
+    let mut innermost = extracted_dir.to_path_buf();
+    while !install::bin_directory(&innermost).exists() {
+        match sole_subdirectory(&innermost)? {
+            Some(only) => {
+                chain.push(only.clone());
+                innermost = only;
+            }
+            None => break,
+        }
+    }
+    if chain.len() < 2 {
+        return Ok(false);
+    }
+
+    let top = &chain[0];
+    let staging = extracted_dir.join(FLATTEN_STAGING_DIR);
+    log::debug!("Flattening {} into {}", innermost.display(), top.display());
+    fs::rename(&innermost, &staging)?;
+    fs::remove_dir_all(top)?;
+    fs::rename(&staging, top)?;
+    Ok(true)
+}
+
+fn sole_subdirectory(dir: &Path) -> Result<Option<PathBuf>> {
+    let entries: Vec<_> = fs::read_dir(dir)?.collect::<std::io::Result<_>>()?;
+    match entries.as_slice() {
+        [entry] if entry.file_type()?.is_dir() => Ok(Some(entry.path())),
+        _ => Ok(None),
+    }
+}
+
+/// Move each entry under `root` whose name contains `\` to the nested path
+/// its name describes. Names that would leave their directory are skipped.
+fn split_backslash_paths(root: &Path) -> Result<usize> {
+    if cfg!(windows) {
+        // `\` cannot appear in a file name on Windows
+        return Ok(0);
+    }
+
+    let misnamed: Vec<PathBuf> = WalkDir::new(root)
+        .min_depth(1)
+        .contents_first(true)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().contains('\\'))
+        .map(|entry| entry.into_path())
+        .collect();
+
+    let mut moved = 0;
+    for path in misnamed {
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            continue;
+        };
+        let name = name.to_string_lossy();
+        let relative: PathBuf = name.split('\\').filter(|part| !part.is_empty()).collect();
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            log::warn!("Not splitting suspicious entry name {}", path.display());
+            continue;
+        }
+
+        let target = parent.join(&relative);
+        if fs::symlink_metadata(&target).is_ok() {
+            log::warn!(
+                "Not splitting {}: {} already exists",
+                path.display(),
+                target.display()
+            );
+            continue;
+        }
+        if let Some(target_parent) = target.parent() {
+            fs::create_dir_all(target_parent)?;
+        }
+        fs::rename(&path, &target)?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// Give read-but-not-executable launchers the exec bits they need
+fn fix_executable_permissions(java_home: &Path) -> Result<usize> {
+    if cfg!(windows) {
+        // Windows decides executability by extension
+        return Ok(0);
+    }
+
+    let mut candidates: Vec<PathBuf> = match fs::read_dir(install::bin_directory(java_home)) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    candidates.push(java_home.join("lib").join("jspawnhelper"));
+
+    let mut fixed = 0;
+    for path in candidates {
+        let is_file = fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.is_file());
+        if is_file && !file_ops::is_executable(&path)? {
+            file_ops::make_executable(&path)?;
+            log::debug!("Made {} executable", path.display());
+            fixed += 1;
+        }
+    }
+    Ok(fixed)
+}
+
+/// Point broken symlinks written with `\` separators at their intended target
+#[cfg(unix)]
+fn repair_symlinks(root: &Path) -> Result<usize> {
+    let canonical_root = fs::canonicalize(root)?;
+    let broken: Vec<(PathBuf, PathBuf)> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path_is_symlink() && fs::metadata(entry.path()).is_err())
+        .filter_map(|entry| {
+            let target = fs::read_link(entry.path()).ok()?;
+            Some((entry.into_path(), target))
+        })
+        .collect();
+
+    let mut repaired = 0;
+    for (link, target) in broken {
+        let target = target.to_string_lossy();
+        if !target.contains('\\') {
+            log::debug!("Leaving broken symlink {} -> {target}", link.display());
+            continue;
+        }
+        let fixed = PathBuf::from(target.replace('\\', "/"));
+        let parent = link.parent().unwrap_or(root);
+        let inside_root = fs::canonicalize(parent.join(&fixed))
+            .is_ok_and(|resolved| resolved.starts_with(&canonical_root));
+        if fixed.is_absolute() || !inside_root {
+            log::debug!("Leaving broken symlink {} -> {target}", link.display());
+            continue;
+        }
+
+        fs::remove_file(&link)?;
+        std::os::unix::fs::symlink(&fixed, &link)?;
+        log::debug!("Repaired symlink {} -> {}", link.display(), fixed.display());
+        repaired += 1;
+    }
+    Ok(repaired)
+}
+
+/// Extraction does not create symlinks on Windows, so there is nothing to repair
+#[cfg(windows)]
+fn repair_symlinks(_root: &Path) -> Result<usize> {
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::with_executable_extension;
+    use tempfile::TempDir;
+
+    fn write_java(java_home: &Path) {
+        let bin = install::bin_directory(java_home);
+        fs::create_dir_all(&bin).unwrap();
+        let java = bin.join(with_executable_extension("java"));
+        fs::write(&java, b"java").unwrap();
+        file_ops::make_executable(&java).unwrap();
+    }
+
+    #[test]
+    fn test_rules_for_merges_wildcard_and_distribution_rows() {
+        assert_eq!(
+            rules_for("temurin"),
+            [
+                NormalizeRule::FixExecutablePermissions,
+                NormalizeRule::RepairSymlinks
+            ]
+        );
+        assert!(rules_for("kona").contains(&NormalizeRule::FlattenNestedDirectories));
+        assert!(rules_for("kona").contains(&NormalizeRule::FixExecutablePermissions));
+    }
+
+    #[test]
+    fn test_normalize_extracted_flattens_nested_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let extracted = temp_dir.path();
+        let java_home = extracted.join("jdk-21").join("jdk-21");
+        write_java(&java_home);
+        fs::create_dir_all(java_home.join("lib")).unwrap();
+
+        // Without the rule, detection fails as before
+        assert!(normalize_extracted("temurin", extracted).is_err());
+
+        let (structure, summary) = normalize_extracted("kona", extracted).unwrap();
+        assert_eq!(structure.jdk_root, extracted.join("jdk-21"));
+        assert_eq!(summary.fixes, ["redundant directory levels flattened"]);
+        assert!(extracted.join("jdk-21").join("lib").is_dir());
+        assert!(!extracted.join(FLATTEN_STAGING_DIR).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_normalize_extracted_fixes_permissions_and_backslash_paths() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let extracted = temp_dir.path();
+        let java_home = extracted.join("jdk");
+        write_java(&java_home);
+        let javac = java_home.join("bin").join("javac");
+        fs::write(&javac, b"javac").unwrap();
+        fs::set_permissions(&javac, fs::Permissions::from_mode(0o644)).unwrap();
+        fs::write(java_home.join("legal\\java.base\\LICENSE"), b"license").unwrap();
+
+        let (structure, summary) = normalize_extracted("trava", extracted).unwrap();
+        assert_eq!(structure.jdk_root, java_home);
+        assert_eq!(
+            summary.fixes,
+            [
+                "1 entry name(s) with Windows separators split",
+                "1 tool(s) made executable"
+            ]
+        );
+        assert!(file_ops::is_executable(&javac).unwrap());
+        assert!(java_home.join("legal/java.base/LICENSE").is_file());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_repair_symlinks_rewrites_backslash_targets_inside_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("lib/server")).unwrap();
+        fs::write(root.join("lib/server/libjvm.so"), b"jvm").unwrap();
+        std::os::unix::fs::symlink("lib\\server\\libjvm.so", root.join("libjvm.so")).unwrap();
+        std::os::unix::fs::symlink("..\\..\\outside", root.join("lib/escape")).unwrap();
+
+        assert_eq!(repair_symlinks(root).unwrap(), 1);
+        assert_eq!(fs::read(root.join("libjvm.so")).unwrap(), b"jvm");
+        assert!(fs::symlink_metadata(root.join("lib/escape")).is_ok());
+    }
+}
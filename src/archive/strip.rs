@@ -0,0 +1,132 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Removal of unneeded JDK content after extraction.
+//!
+//! `install.strip` lists paths relative to JAVA_HOME (man pages, demos,
+//! `lib/src.zip`, ...) that images such as CI runners never use. Entries that
+//! do not exist in a given JDK are ignored.
+
+use crate::error::{KopiError, Result};
+use std::fs;
+use std::io;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// What a strip pass removed from a JDK
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StripSummary {
+    /// Configured entries that existed and were removed
+    pub stripped: Vec<String>,
+    /// Apparent size of the removed files in bytes
+    pub bytes_freed: u64,
+}
+
+/// Remove each of `entries` from `java_home`.
+///
+/// Entries must already be validated as relative paths without `..`; any
+/// entry that resolves outside `java_home` through a symlink is rejected.
+pub fn strip_paths(java_home: &Path, entries: &[String]) -> Result<StripSummary> {
+    let mut summary = StripSummary::default();
+    if entries.is_empty() {
+        return Ok(summary);
+    }
+
+    let canonical_home = fs::canonicalize(java_home)?;
+    for entry in entries {
+        let target = java_home.join(entry);
+        let Ok(metadata) = fs::symlink_metadata(&target) else {
+            log::debug!("Nothing to strip at {}", target.display());
+            continue;
+        };
+
+        let parent = target.parent().unwrap_or(java_home);
+        if !fs::canonicalize(parent)?.starts_with(&canonical_home) {
+            return Err(KopiError::SecurityError(format!(
+                "Refusing to strip '{entry}': it resolves outside {}",
+                java_home.display()
+            )));
+        }
+
+        let size = if metadata.is_dir() {
+            let size = tree_size(&target)?;
+            fs::remove_dir_all(&target)?;
+            size
+        } else {
+            fs::remove_file(&target)?;
+            metadata.len()
+        };
+
+        log::debug!("Stripped {} ({size} bytes)", target.display());
+        summary.bytes_freed += size;
+        summary.stripped.push(entry.clone());
+    }
+
+    Ok(summary)
+}
+
+fn tree_size(root: &Path) -> io::Result<u64> {
+    let mut total = 0;
+    for entry in WalkDir::new(root) {
+        let entry = entry.map_err(io::Error::from)?;
+        if entry.file_type().is_file() {
+            total += entry.metadata().map_err(io::Error::from)?.len();
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_paths_removes_existing_entries_and_reports_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path();
+        fs::create_dir_all(home.join("bin")).unwrap();
+        fs::create_dir_all(home.join("man/man1")).unwrap();
+        fs::create_dir_all(home.join("lib")).unwrap();
+        fs::write(home.join("bin/java"), b"java").unwrap();
+        fs::write(home.join("man/man1/java.1"), vec![0u8; 100]).unwrap();
+        fs::write(home.join("lib/src.zip"), vec![0u8; 50]).unwrap();
+
+        let entries = ["man", "demo", "lib/src.zip"].map(String::from);
+        let summary = strip_paths(home, &entries).unwrap();
+
+        assert_eq!(summary.stripped, ["man", "lib/src.zip"]);
+        assert_eq!(summary.bytes_freed, 150);
+        assert!(!home.join("man").exists());
+        assert!(!home.join("lib/src.zip").exists());
+        assert!(home.join("bin/java").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_strip_paths_rejects_symlinked_escape() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path().join("jdk");
+        let outside = temp_dir.path().join("outside");
+        fs::create_dir_all(&home).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("keep.txt"), b"keep").unwrap();
+        std::os::unix::fs::symlink(&outside, home.join("link")).unwrap();
+
+        let result = strip_paths(&home, &["link/keep.txt".to_string()]);
+
+        assert!(matches!(result, Err(KopiError::SecurityError(_))));
+        assert!(outside.join("keep.txt").exists());
+    }
+}
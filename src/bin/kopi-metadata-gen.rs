@@ -14,6 +14,9 @@
 
 use clap::{Parser, Subcommand};
 use kopi::error::{format_error_with_color, get_exit_code};
+use kopi::metadata::generator::publish::{
+    self, DEFAULT_CACHE_CONTROL, DEFAULT_INDEX_CACHE_CONTROL, PublishDestination, PublishOptions,
+};
 use kopi::metadata::{GeneratorConfig, MetadataGenConfigFile, MetadataGenerator, Platform};
 use std::io::IsTerminal;
 use std::path::PathBuf;
@@ -103,6 +106,40 @@ enum Commands {
         input: PathBuf,
     },
 
+    /// Upload generated metadata to object storage or a directory
+    Publish {
+        /// Directory with generated metadata
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Destination: s3://bucket/prefix, gs://bucket/prefix,
+        /// https://endpoint/bucket/prefix (S3-compatible), or a directory.
+        /// Object storage credentials come from AWS_ACCESS_KEY_ID and
+        /// AWS_SECRET_ACCESS_KEY
+        #[arg(short, long)]
+        target: String,
+
+        /// Signing region (default: AWS_REGION, then us-east-1; gs:// uses auto)
+        #[arg(long)]
+        region: Option<String>,
+
+        /// Cache-Control for platform and delta files
+        #[arg(long, default_value = DEFAULT_CACHE_CONTROL)]
+        cache_control: String,
+
+        /// Cache-Control for index.json
+        #[arg(long, default_value = DEFAULT_INDEX_CACHE_CONTROL)]
+        index_cache_control: String,
+
+        /// Store files gzip-compressed with Content-Encoding: gzip
+        #[arg(long)]
+        gzip: bool,
+
+        /// Dry run - show what would be uploaded without uploading
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Generate example configuration file
     GenerateConfig {
         /// Output path for configuration file
@@ -255,6 +292,25 @@ fn main() {
             let generator = MetadataGenerator::new(config);
             generator.validate(&input)
         }
+        Commands::Publish {
+            input,
+            target,
+            region,
+            cache_control,
+            index_cache_control,
+            gzip,
+            dry_run,
+        } => (|| -> kopi::error::Result<()> {
+            let destination = PublishDestination::parse(&target, region.as_deref())?;
+            let options = PublishOptions {
+                cache_control,
+                index_cache_control,
+                gzip,
+                dry_run,
+            };
+            let target = destination.open()?;
+            publish::publish(&input, target.as_ref(), &options).map(|_| ())
+        })(),
         Commands::GenerateConfig { output } => (|| -> kopi::error::Result<()> {
             let example_config = MetadataGenConfigFile::default_example();
             let toml_content = toml::to_string_pretty(&example_config).map_err(|e| {
@@ -0,0 +1,250 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pruning the metadata cache down to what is still useful.
+
+use crate::error::Result;
+use crate::models::metadata::JdkMetadata;
+use log::debug;
+use std::collections::{BTreeSet, HashMap};
+
+use super::MetadataCache;
+
+/// What [`clean_cache`] removed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanReport {
+    /// Distributions dropped because nothing references them
+    pub removed_distributions: Vec<String>,
+    /// Packages dropped from the remaining distributions to meet the budget
+    pub removed_packages: usize,
+    /// Serialized size before and after cleaning
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// Set when the budget could not be met without dropping referenced
+    /// distributions
+    pub over_budget: bool,
+}
+
+/// Remove distributions not named in `keep`, then shrink the cache until it
+/// serializes to at most `max_bytes`.
+///
+/// Shrinking first drops packages for other platforms, then every build but
+/// the newest of each feature release. `keep` holds canonical distribution
+/// names; synonyms are resolved through the cache's synonym map.
+pub fn clean_cache(
+    cache: &mut MetadataCache,
+    keep: &BTreeSet<String>,
+    max_bytes: Option<u64>,
+    platform: (&str, &str),
+) -> Result<CleanReport> {
+    let bytes_before = serialized_size(cache)?;
+    let keep: BTreeSet<String> = keep
+        .iter()
+        .map(|name| {
+            cache
+                .get_canonical_name(name)
+                .map_or_else(|| name.clone(), str::to_string)
+        })
+        .collect();
+
+    let mut removed_distributions: Vec<String> = cache
+        .distributions
+        .keys()
+        .filter(|name| !keep.contains(*name))
+        .cloned()
+        .collect();
+    removed_distributions.sort();
+    for name in &removed_distributions {
+        cache.distributions.remove(name);
+    }
+
+    let over = |cache: &MetadataCache| -> Result<bool> {
+        Ok(max_bytes.is_some_and(|max| serialized_size(cache).is_ok_and(|size| size > max)))
+    };
+    let (os, arch) = platform;
+    let mut removed_packages = 0;
+    if over(cache)? {
+        debug!("Cache over budget; dropping packages for other platforms");
+        removed_packages += retain_packages(cache, |package| {
+            package.operating_system.to_string() == os && package.architecture.to_string() == arch
+        });
+    }
+    if over(cache)? {
+        debug!("Cache over budget; keeping only the newest build per feature release");
+        removed_packages += keep_newest_per_release(cache);
+    }
+
+    Ok(CleanReport {
+        removed_distributions,
+        removed_packages,
+        bytes_before,
+        bytes_after: serialized_size(cache)?,
+        over_budget: over(cache)?,
+    })
+}
+
+fn serialized_size(cache: &MetadataCache) -> Result<u64> {
+    Ok(super::index::serialize_indexed(cache)?.0.len() as u64)
+}
+
+fn retain_packages(cache: &mut MetadataCache, keep: impl Fn(&JdkMetadata) -> bool) -> usize {
+    let mut removed = 0;
+    for dist_cache in cache.distributions.values_mut() {
+        let before = dist_cache.packages.len();
+        dist_cache.packages.retain(&keep);
+        removed += before - dist_cache.packages.len();
+    }
+    removed
+}
+
+fn keep_newest_per_release(cache: &mut MetadataCache) -> usize {
+    let mut removed = 0;
+    for dist_cache in cache.distributions.values_mut() {
+        let mut newest: HashMap<_, &JdkMetadata> = HashMap::new();
+        for package in &dist_cache.packages {
+            let key = (
+                package.version.major(),
+                package.operating_system.to_string(),
+                package.architecture.to_string(),
+                package.package_type.to_string(),
+                package.javafx_bundled,
+                package.lib_c_type.clone(),
+            );
+            let slot = newest.entry(key).or_insert(package);
+            if package.version > slot.version {
+                *slot = package;
+            }
+        }
+        let kept: BTreeSet<String> = newest.values().map(|package| package.id.clone()).collect();
+
+        let before = dist_cache.packages.len();
+        dist_cache
+            .packages
+            .retain(|package| kept.contains(&package.id));
+        removed += before - dist_cache.packages.len();
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::DistributionCache;
+    use crate::models::distribution::Distribution;
+    use crate::models::package::{ArchiveType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::version::Version;
+    use chrono::Utc;
+    use std::str::FromStr;
+
+    fn package(id: &str, version: &str, os: OperatingSystem) -> JdkMetadata {
+        let version = Version::from_str(version).unwrap();
+        JdkMetadata {
+            id: id.to_string(),
+            distribution: "temurin".to_string(),
+            version: version.clone(),
+            distribution_version: version,
+            architecture: Architecture::X64,
+            operating_system: os,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: Some(format!("https://example.com/{id}.tar.gz")),
+            checksum: None,
+            checksum_type: None,
+            size: 0,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }
+    }
+
+    fn cache() -> MetadataCache {
+        let mut cache = MetadataCache {
+            version: 3,
+            last_updated: Utc::now(),
+            distributions: HashMap::new(),
+            synonym_map: HashMap::from([("adoptium".to_string(), "temurin".to_string())]),
+        };
+        cache.distributions.insert(
+            "temurin".to_string(),
+            DistributionCache {
+                distribution: Distribution::Temurin,
+                display_name: "Temurin".to_string(),
+                packages: vec![
+                    package("t21-1", "21.0.4", OperatingSystem::Linux),
+                    package("t21-2", "21.0.5", OperatingSystem::Linux),
+                    package("t17", "17.0.13", OperatingSystem::Linux),
+                    package("t21-mac", "21.0.5", OperatingSystem::MacOS),
+                ],
+            },
+        );
+        cache.distributions.insert(
+            "zulu".to_string(),
+            DistributionCache {
+                distribution: Distribution::Zulu,
+                display_name: "Zulu".to_string(),
+                packages: vec![package("z21", "21.0.5", OperatingSystem::Linux)],
+            },
+        );
+        cache
+    }
+
+    fn ids(cache: &MetadataCache) -> Vec<String> {
+        let mut ids: Vec<String> = cache
+            .distributions
+            .values()
+            .flat_map(|dist| dist.packages.iter().map(|package| package.id.clone()))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_clean_cache_drops_unreferenced_distributions() {
+        let mut cache = cache();
+        let keep = BTreeSet::from(["adoptium".to_string()]);
+
+        let report = clean_cache(&mut cache, &keep, None, ("linux", "x64")).unwrap();
+        assert_eq!(report.removed_distributions, vec!["zulu"]);
+        assert_eq!(report.removed_packages, 0);
+        assert!(report.bytes_after < report.bytes_before);
+        assert!(!report.over_budget);
+        assert_eq!(ids(&cache), vec!["t17", "t21-1", "t21-2", "t21-mac"]);
+    }
+
+    #[test]
+    fn test_clean_cache_shrinks_to_budget() {
+        let keep = BTreeSet::from(["temurin".to_string()]);
+
+        // Dropping other platforms is enough for a budget just under the full size
+        let mut cache = cache();
+        let mut probe = cache.clone();
+        probe.distributions.remove("zulu");
+        let budget = serialized_size(&probe).unwrap() - 1;
+        let report = clean_cache(&mut cache, &keep, Some(budget), ("linux", "x64")).unwrap();
+        assert_eq!(report.removed_packages, 1);
+        assert_eq!(ids(&cache), vec!["t17", "t21-1", "t21-2"]);
+
+        // A tighter budget keeps only the newest build per feature release
+        let mut cache = self::cache();
+        let report = clean_cache(&mut cache, &keep, Some(1), ("linux", "x64")).unwrap();
+        assert_eq!(report.removed_packages, 2);
+        assert_eq!(ids(&cache), vec!["t17", "t21-2"]);
+        assert!(report.over_budget);
+    }
+}
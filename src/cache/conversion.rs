@@ -16,10 +16,11 @@ use crate::cache::{DistributionCache, MetadataCache};
 use crate::error::Result;
 use crate::models::api::ApiMetadata;
 use crate::models::distribution::Distribution as JdkDistribution;
-use crate::models::metadata::JdkMetadata;
+use crate::models::metadata::{JdkMetadata, normalize_features};
 use crate::models::package::{ArchiveType, ChecksumType, PackageType};
 use crate::models::platform::{Architecture, OperatingSystem};
 use crate::version::Version;
+use chrono::{DateTime, NaiveDate, Utc};
 use std::str::FromStr;
 
 /// Parse architecture from filename patterns
@@ -39,6 +40,7 @@ pub fn parse_architecture_from_filename(filename: &str) -> Option<Architecture>
         ("ppc64", "ppc64"),
         ("s390x", "s390x"),
         ("sparcv9", "sparcv9"),
+        ("riscv64", "riscv64"),
     ];
 
     for (pattern, arch_str) in patterns.iter() {
@@ -50,6 +52,33 @@ pub fn parse_architecture_from_filename(filename: &str) -> Option<Architecture>
     None
 }
 
+/// Architecture of an API package, preferring the value foojay reports and
+/// falling back to the filename
+pub fn parse_package_architecture(
+    api_package: &crate::models::api::Package,
+) -> Option<Architecture> {
+    api_package
+        .architecture
+        .as_deref()
+        .and_then(|arch| Architecture::from_str(arch).ok())
+        .or_else(|| parse_architecture_from_filename(&api_package.filename))
+}
+
+/// Parse a foojay release date, accepting full timestamps and plain dates
+/// (taken as midnight UTC)
+pub fn parse_release_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc())
+        })
+}
+
 /// Convert an API package to JDK metadata
 pub fn convert_package_to_jdk_metadata(
     api_package: crate::models::api::Package,
@@ -62,9 +91,8 @@ pub fn convert_package_to_jdk_metadata(
     let distribution_version =
         Version::from_str(&api_package.distribution_version).unwrap_or_else(|_| version.clone());
 
-    // Parse architecture from filename
-    let architecture =
-        parse_architecture_from_filename(&api_package.filename).unwrap_or(Architecture::X64);
+    // Parse architecture, falling back to the filename
+    let architecture = parse_package_architecture(&api_package).unwrap_or(Architecture::X64);
 
     // Parse operating system
     let operating_system =
@@ -91,9 +119,14 @@ pub fn convert_package_to_jdk_metadata(
         size: api_package.size,
         lib_c_type: api_package.lib_c_type,
         javafx_bundled: api_package.javafx_bundled,
+        features: normalize_features(&api_package.feature),
         term_of_support: api_package.term_of_support,
         release_status: api_package.release_status,
         latest_build_available: api_package.latest_build_available,
+        release_date: api_package
+            .release_date
+            .as_deref()
+            .and_then(parse_release_date),
     };
 
     Ok(jdk_metadata)
@@ -166,6 +199,16 @@ mod tests {
             parse_architecture_from_filename("amazon-corretto-21.0.1.12.1-linux-x86_64.tar.gz"),
             Some(Architecture::X64)
         );
+        assert_eq!(
+            parse_architecture_from_filename(
+                "OpenJDK21U-jdk_riscv64_linux_hotspot_21.0.1_12.tar.gz"
+            ),
+            Some(Architecture::Riscv64)
+        );
+        assert_eq!(
+            parse_architecture_from_filename("microsoft-jdk-21.0.1-windows-aarch64.zip"),
+            Some(Architecture::Aarch64)
+        );
         assert_eq!(
             parse_architecture_from_filename("some_file_without_arch.tar.gz"),
             None
@@ -196,9 +239,11 @@ mod tests {
             size: 195000000,
             lib_c_type: Some("glibc".to_string()),
             javafx_bundled: false,
+            feature: vec!["CRaC".to_string()],
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         };
 
         let result = convert_package_to_jdk_metadata(api_package);
@@ -208,7 +253,43 @@ mod tests {
         assert_eq!(jdk_metadata.id, "test123");
         assert_eq!(jdk_metadata.distribution, "temurin");
         assert_eq!(jdk_metadata.version.major(), 21);
-        // Architecture is parsed from filename
+        // Architecture comes from the reported value
         assert_eq!(jdk_metadata.architecture.to_string(), "x64");
+        assert_eq!(jdk_metadata.features, vec!["crac"]);
+    }
+
+    #[test]
+    fn test_parse_package_architecture_prefers_reported_value() {
+        let mut api_package: Package = serde_json::from_value(serde_json::json!({
+            "id": "riscv",
+            "archive_type": "tar.gz",
+            "distribution": "temurin",
+            "major_version": 21,
+            "java_version": "21.0.1",
+            "distribution_version": "21.0.1+12",
+            "jdk_version": 21,
+            "directly_downloadable": true,
+            "filename": "jdk-21.0.1-linux.tar.gz",
+            "links": {"pkg_download_redirect": "https://example.com/download"},
+            "free_use_in_production": true,
+            "tck_tested": "yes",
+            "size": 1,
+            "operating_system": "linux",
+            "architecture": "riscv64",
+            "package_type": "jdk",
+            "javafx_bundled": false
+        }))
+        .unwrap();
+        assert_eq!(
+            parse_package_architecture(&api_package),
+            Some(Architecture::Riscv64)
+        );
+
+        api_package.architecture = None;
+        api_package.filename = "jdk-21.0.1-windows-aarch64.zip".to_string();
+        assert_eq!(
+            parse_package_architecture(&api_package),
+            Some(Architecture::Aarch64)
+        );
     }
 }
@@ -0,0 +1,291 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! On-disk index over the metadata cache.
+//!
+//! The cache file is written with every package as a standalone JSON object
+//! whose byte range is recorded in `metadata.index.json`, grouped by
+//! distribution and major version. Searches and install resolution that
+//! target one major version read the small index plus only the packages they
+//! need instead of parsing the entire cache. The index is bound to the cache
+//! file's size and modification time; whenever they disagree, or anything
+//! fails to parse, callers fall back to loading the full cache.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::error::{KopiError, Result};
+use crate::models::distribution::Distribution as JdkDistribution;
+use crate::models::metadata::JdkMetadata;
+use crate::platform;
+
+use super::{DistributionCache, MetadataCache};
+
+const INDEX_FORMAT_VERSION: u32 = 1;
+const INDEX_EXTENSION: &str = "index.json";
+
+/// Byte range of one serialized package inside the cache file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackageSpan(pub u64, pub u64);
+
+/// Package spans of each distribution keyed by major version
+pub type PackageSpans = BTreeMap<String, BTreeMap<u32, Vec<PackageSpan>>>;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CacheIndex {
+    format: u32,
+    /// Size and modification time of the cache file the spans point into
+    cache_size: u64,
+    cache_modified: (u64, u32),
+    cache_version: u32,
+    last_updated: DateTime<Utc>,
+    synonym_map: HashMap<String, String>,
+    distributions: BTreeMap<String, IndexedDistribution>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct IndexedDistribution {
+    distribution: JdkDistribution,
+    display_name: String,
+    majors: BTreeMap<u32, Vec<PackageSpan>>,
+}
+
+/// Path of the index that accompanies `cache_path`
+pub fn index_path(cache_path: &Path) -> PathBuf {
+    cache_path.with_extension(INDEX_EXTENSION)
+}
+
+/// Serialize `cache` as compact JSON, one package per line, recording where
+/// each package lands. The output deserializes to the same `MetadataCache`.
+pub(super) fn serialize_indexed(cache: &MetadataCache) -> Result<(Vec<u8>, PackageSpans)> {
+    fn write<T: Serialize + ?Sized>(out: &mut Vec<u8>, value: &T) -> Result<()> {
+        serde_json::to_writer(out, value).map_err(|_e| KopiError::InvalidMetadata)
+    }
+
+    let mut out = Vec::new();
+    let mut spans = PackageSpans::new();
+
+    out.extend_from_slice(b"{\"version\":");
+    write(&mut out, &cache.version)?;
+    out.extend_from_slice(b",\"last_updated\":");
+    write(&mut out, &cache.last_updated)?;
+    out.extend_from_slice(b",\"synonym_map\":");
+    write(&mut out, &cache.synonym_map)?;
+    out.extend_from_slice(b",\"distributions\":{");
+
+    let mut names: Vec<&String> = cache.distributions.keys().collect();
+    names.sort();
+    for (dist_index, name) in names.into_iter().enumerate() {
+        let dist = &cache.distributions[name];
+        if dist_index > 0 {
+            out.push(b',');
+        }
+        out.push(b'\n');
+        write(&mut out, name)?;
+        out.extend_from_slice(b":{\"distribution\":");
+        write(&mut out, &dist.distribution)?;
+        out.extend_from_slice(b",\"display_name\":");
+        write(&mut out, &dist.display_name)?;
+        out.extend_from_slice(b",\"packages\":[");
+
+        let majors = spans.entry(name.clone()).or_default();
+        for (package_index, package) in dist.packages.iter().enumerate() {
+            if package_index > 0 {
+                out.push(b',');
+            }
+            out.push(b'\n');
+            let start = out.len();
+            write(&mut out, package)?;
+            majors
+                .entry(package.version.major())
+                .or_default()
+                .push(PackageSpan(start as u64, (out.len() - start) as u64));
+        }
+        out.extend_from_slice(b"]}");
+    }
+    out.extend_from_slice(b"}}\n");
+
+    Ok((out, spans))
+}
+
+/// Write the index for a cache file that was just saved with `spans`.
+pub(super) fn write_index(
+    cache_path: &Path,
+    cache: &MetadataCache,
+    mut spans: PackageSpans,
+) -> Result<()> {
+    let (cache_size, cache_modified) = file_stamp(&fs::metadata(cache_path)?)?;
+    let distributions = cache
+        .distributions
+        .iter()
+        .map(|(name, dist)| {
+            let entry = IndexedDistribution {
+                distribution: dist.distribution.clone(),
+                display_name: dist.display_name.clone(),
+                majors: spans.remove(name).unwrap_or_default(),
+            };
+            (name.clone(), entry)
+        })
+        .collect();
+
+    let index = CacheIndex {
+        format: INDEX_FORMAT_VERSION,
+        cache_size,
+        cache_modified,
+        cache_version: cache.version,
+        last_updated: cache.last_updated,
+        synonym_map: cache.synonym_map.clone(),
+        distributions,
+    };
+
+    let path = index_path(cache_path);
+    let temp_path = path.with_extension("json.tmp");
+    let json = serde_json::to_vec(&index).map_err(|_e| KopiError::InvalidMetadata)?;
+    fs::write(&temp_path, json)?;
+    platform::file_ops::atomic_rename(&temp_path, &path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })?;
+    Ok(())
+}
+
+/// Delete the index of `cache_path`, e.g. when the cache itself is removed.
+pub fn remove_index(cache_path: &Path) -> Result<()> {
+    match fs::remove_file(index_path(cache_path)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Load the packages of `distribution` (or every distribution) whose major
+/// version is `major` (or any major) through the index.
+///
+/// The result only contains the selected distributions and packages, so it
+/// must not be saved back. Returns `None` when the index is missing, stale,
+/// or unreadable.
+pub fn load_cache_subset(
+    cache_path: &Path,
+    distribution: Option<&str>,
+    major: Option<u32>,
+) -> Option<MetadataCache> {
+    match try_load_subset(cache_path, distribution, major) {
+        Ok(cache) => cache,
+        Err(e) => {
+            log::debug!("Ignoring metadata cache index: {e}");
+            None
+        }
+    }
+}
+
+fn try_load_subset(
+    cache_path: &Path,
+    distribution: Option<&str>,
+    major: Option<u32>,
+) -> Result<Option<MetadataCache>> {
+    let index_file = index_path(cache_path);
+    if !index_file.exists() {
+        return Ok(None);
+    }
+    let index: CacheIndex =
+        serde_json::from_slice(&fs::read(&index_file)?).map_err(|_e| KopiError::InvalidMetadata)?;
+    // Older cache formats are upgraded by the full load
+    if index.format != INDEX_FORMAT_VERSION || index.cache_version != super::CACHE_FORMAT_VERSION {
+        return Ok(None);
+    }
+
+    let mut file = File::open(cache_path)?;
+    if file_stamp(&file.metadata()?)? != (index.cache_size, index.cache_modified) {
+        log::debug!("Metadata cache index is stale");
+        return Ok(None);
+    }
+
+    let wanted = distribution.map(|name| {
+        index
+            .synonym_map
+            .get(name)
+            .map(String::as_str)
+            .unwrap_or(name)
+    });
+
+    let mut distributions = HashMap::new();
+    let mut buffer = Vec::new();
+    for (name, entry) in &index.distributions {
+        if wanted.is_some_and(|wanted| wanted != name) {
+            continue;
+        }
+
+        let mut spans: Vec<PackageSpan> = entry
+            .majors
+            .iter()
+            .filter(|(entry_major, _)| major.is_none_or(|major| **entry_major == major))
+            .flat_map(|(_, spans)| spans.iter().copied())
+            .collect();
+        // Keep the packages in the order they were cached
+        spans.sort_by_key(|span| span.0);
+
+        let mut packages = Vec::with_capacity(spans.len());
+        for PackageSpan(offset, len) in spans {
+            buffer.resize(len as usize, 0);
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut buffer)?;
+            let package: JdkMetadata =
+                serde_json::from_slice(&buffer).map_err(|_e| KopiError::InvalidMetadata)?;
+            packages.push(package);
+        }
+
+        distributions.insert(
+            name.clone(),
+            DistributionCache {
+                distribution: entry.distribution.clone(),
+                display_name: entry.display_name.clone(),
+                packages,
+            },
+        );
+    }
+
+    Ok(Some(MetadataCache {
+        version: index.cache_version,
+        last_updated: index.last_updated,
+        distributions,
+        synonym_map: index.synonym_map,
+    }))
+}
+
+fn file_stamp(metadata: &fs::Metadata) -> Result<(u64, (u64, u32))> {
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok((
+        metadata.len(),
+        (modified.as_secs(), modified.subsec_nanos()),
+    ))
+}
+
+/// Like [`load_cache_subset`], falling back to the full cache when the index
+/// cannot be used. Callers must still filter the result themselves.
+pub fn load_cache_for(
+    cache_path: &Path,
+    distribution: Option<&str>,
+    major: Option<u32>,
+) -> Result<MetadataCache> {
+    match load_cache_subset(cache_path, distribution, major) {
+        Some(cache) => Ok(cache),
+        None => super::load_cache(cache_path),
+    }
+}
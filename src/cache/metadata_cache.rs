@@ -13,9 +13,11 @@
 // limitations under the License.
 
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
+use std::str::FromStr;
 use std::time::Duration;
 
 use crate::error::Result;
@@ -23,11 +25,13 @@ use crate::locking::LockTimeoutValue;
 use crate::models::distribution::Distribution as JdkDistribution;
 use crate::models::metadata::JdkMetadata;
 use crate::models::package::PackageType;
+use crate::version::file::VersionTrack;
+use crate::version::matching;
 use crate::version::parser::ParsedVersionRequest;
 
 use super::models::{PlatformFilter, SearchResult, VersionSearchType};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct MetadataCache {
     pub version: u32,
     pub last_updated: DateTime<Utc>,
@@ -37,7 +41,7 @@ pub struct MetadataCache {
     pub synonym_map: HashMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct DistributionCache {
     pub distribution: JdkDistribution,
     pub display_name: String,
@@ -47,7 +51,7 @@ pub struct DistributionCache {
 impl MetadataCache {
     pub fn new() -> Self {
         Self {
-            version: 1,
+            version: super::storage::CACHE_FORMAT_VERSION,
             last_updated: Utc::now(),
             distributions: HashMap::new(),
             synonym_map: HashMap::new(),
@@ -106,7 +110,16 @@ impl MetadataCache {
         request: &ParsedVersionRequest,
         version_type: VersionSearchType,
     ) -> Result<Vec<SearchResult>> {
-        let platform_filter = PlatformFilter::default();
+        self.search_with_platform(request, version_type, &PlatformFilter::default())
+    }
+
+    /// Search for packages matching the given request and platform filter
+    pub fn search_with_platform(
+        &self,
+        request: &ParsedVersionRequest,
+        version_type: VersionSearchType,
+        platform_filter: &PlatformFilter,
+    ) -> Result<Vec<SearchResult>> {
         let mut results = Vec::new();
 
         // Pre-compute version string if needed to avoid repeated conversions
@@ -137,9 +150,10 @@ impl MetadataCache {
                 let mut latest_package: Option<&JdkMetadata> = None;
 
                 for package in &dist_cache.packages {
-                    // Apply package type filter if specified
-                    if let Some(ref package_type) = request.package_type
-                        && package.package_type != *package_type
+                    // Apply package type filter; companion archives only match on request
+                    if !package
+                        .package_type
+                        .satisfies(request.package_type.as_ref())
                     {
                         continue;
                     }
@@ -150,7 +164,7 @@ impl MetadataCache {
                         request,
                         version_str.as_deref(),
                         actual_version_type,
-                        &platform_filter,
+                        platform_filter,
                     ) {
                         continue;
                     }
@@ -181,7 +195,7 @@ impl MetadataCache {
                         request,
                         version_str.as_deref(),
                         actual_version_type,
-                        &platform_filter,
+                        platform_filter,
                     ) {
                         continue;
                     }
@@ -225,7 +239,8 @@ impl MetadataCache {
         VersionSearchType::JavaVersion
     }
 
-    /// Look up a specific package by distribution, version, and platform
+    /// Look up a specific standard (non-feature) package by distribution,
+    /// version, and platform
     pub fn lookup(
         &self,
         distribution: &JdkDistribution,
@@ -234,6 +249,32 @@ impl MetadataCache {
         operating_system: &str,
         package_type: Option<&PackageType>,
         javafx_bundled: Option<bool>,
+    ) -> Option<JdkMetadata> {
+        let platform = PlatformFilter {
+            architecture: Some(architecture.to_string()),
+            operating_system: Some(operating_system.to_string()),
+            lib_c_type: None,
+        };
+        self.lookup_for_platform(
+            distribution,
+            version,
+            &platform,
+            package_type,
+            javafx_bundled,
+            &[],
+        )
+    }
+
+    /// Look up a specific package for an explicit target platform, which may
+    /// differ from the one Kopi is running on
+    pub fn lookup_for_platform(
+        &self,
+        distribution: &JdkDistribution,
+        version: &str,
+        platform: &PlatformFilter,
+        package_type: Option<&PackageType>,
+        javafx_bundled: Option<bool>,
+        features: &[String],
     ) -> Option<JdkMetadata> {
         use crate::models::package::ArchiveType;
         // Look up distribution by its API name, resolving synonyms
@@ -243,19 +284,21 @@ impl MetadataCache {
         let dist_cache = self.distributions.get(canonical_name)?;
 
         // On macOS, prefer tar.gz to preserve symbolic links
-        let is_macos = operating_system == "macos" || operating_system == "mac_os";
+        let is_macos = platform
+            .operating_system
+            .as_deref()
+            .is_some_and(|os| os == "macos" || os == "mac_os");
 
         // Collect all matching packages
         let mut matches: Vec<&JdkMetadata> = dist_cache
             .packages
             .iter()
             .filter(|pkg| {
-                pkg.version.matches_pattern(version)
-                    && pkg.architecture.to_string() == architecture
-                    && pkg.operating_system.to_string() == operating_system
-                    && (package_type.is_none() || Some(&pkg.package_type) == package_type)
+                matching::matches_pattern(&pkg.version, version)
+                    && self.matches_target(pkg, platform)
+                    && pkg.package_type.satisfies(package_type)
                     && (javafx_bundled.is_none() || Some(pkg.javafx_bundled) == javafx_bundled)
-                    && self.matches_platform_libc(&pkg.lib_c_type)
+                    && pkg.has_features(features)
                     && if is_macos {
                         // On macOS, accept both tar.gz and zip
                         matches!(pkg.archive_type, ArchiveType::TarGz | ArchiveType::Zip)
@@ -302,6 +345,158 @@ impl MetadataCache {
         matches.first().cloned().cloned()
     }
 
+    /// Find the newest package on a version track for the given platform
+    pub fn lookup_track(
+        &self,
+        distribution: &JdkDistribution,
+        track: VersionTrack,
+        platform: &PlatformFilter,
+        package_type: Option<&PackageType>,
+        javafx_bundled: Option<bool>,
+    ) -> Option<JdkMetadata> {
+        use crate::models::package::ArchiveType;
+        let canonical_name = self
+            .get_canonical_name(distribution.id())
+            .unwrap_or(distribution.id());
+        let dist_cache = self.distributions.get(canonical_name)?;
+
+        dist_cache
+            .packages
+            .iter()
+            .filter(|pkg| {
+                track.matches(&pkg.version)
+                    && self.matches_target(pkg, platform)
+                    && pkg.package_type.satisfies(package_type)
+                    && (javafx_bundled.is_none() || Some(pkg.javafx_bundled) == javafx_bundled)
+                    && pkg.features.is_empty()
+                    && matches!(pkg.archive_type, ArchiveType::TarGz | ArchiveType::Zip)
+            })
+            .max_by(|a, b| VersionTrack::compare(&a.version, &b.version))
+            .cloned()
+    }
+
+    /// Find the newest package in one feature release line (e.g. every 21.x
+    /// build). When foojay flags some candidates with `latest_build_available`,
+    /// only those are considered.
+    pub fn lookup_latest_build(
+        &self,
+        distribution: &JdkDistribution,
+        major: u32,
+        platform: &PlatformFilter,
+        package_type: Option<&PackageType>,
+        javafx_bundled: Option<bool>,
+        include_early_access: bool,
+    ) -> Option<JdkMetadata> {
+        let candidates = self.builds_in_line(
+            distribution,
+            major,
+            platform,
+            package_type,
+            javafx_bundled,
+            include_early_access,
+        );
+
+        let flagged_latest = candidates
+            .iter()
+            .any(|pkg| pkg.latest_build_available == Some(true));
+
+        candidates
+            .into_iter()
+            .filter(|pkg| !flagged_latest || pkg.latest_build_available == Some(true))
+            .max_by(|a, b| VersionTrack::compare(&a.distribution_version, &b.distribution_version))
+            .cloned()
+    }
+
+    /// Installable packages of one feature release line for `platform`
+    pub fn builds_in_line(
+        &self,
+        distribution: &JdkDistribution,
+        major: u32,
+        platform: &PlatformFilter,
+        package_type: Option<&PackageType>,
+        javafx_bundled: Option<bool>,
+        include_early_access: bool,
+    ) -> Vec<&JdkMetadata> {
+        use crate::models::package::ArchiveType;
+        let canonical_name = self
+            .get_canonical_name(distribution.id())
+            .unwrap_or(distribution.id());
+        let Some(dist_cache) = self.distributions.get(canonical_name) else {
+            return Vec::new();
+        };
+
+        dist_cache
+            .packages
+            .iter()
+            .filter(|pkg| {
+                pkg.version.major() == major
+                    && (include_early_access || !pkg.version.is_early_access())
+                    && self.matches_target(pkg, platform)
+                    && pkg.package_type.satisfies(package_type)
+                    && (javafx_bundled.is_none() || Some(pkg.javafx_bundled) == javafx_bundled)
+                    && pkg.features.is_empty()
+                    && matches!(pkg.archive_type, ArchiveType::TarGz | ArchiveType::Zip)
+            })
+            .collect()
+    }
+
+    /// Check a package against the architecture, operating system, and libc of a
+    /// target platform
+    /// Distributions with a `version` build for `platform`, sorted by id
+    pub fn distributions_with_build(
+        &self,
+        version: &str,
+        platform: &PlatformFilter,
+        package_type: Option<&PackageType>,
+        javafx_bundled: Option<bool>,
+        features: &[String],
+    ) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .distributions
+            .keys()
+            .filter(|name| {
+                let distribution = JdkDistribution::from_str(name)
+                    .unwrap_or_else(|_| JdkDistribution::Other(name.to_string()));
+                self.lookup_for_platform(
+                    &distribution,
+                    version,
+                    platform,
+                    package_type,
+                    javafx_bundled,
+                    features,
+                )
+                .is_some()
+            })
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn matches_target(&self, package: &JdkMetadata, platform_filter: &PlatformFilter) -> bool {
+        if let Some(ref arch) = platform_filter.architecture
+            && package.architecture.to_string() != *arch
+        {
+            return false;
+        }
+
+        if let Some(ref os) = platform_filter.operating_system
+            && package.operating_system.to_string() != *os
+        {
+            return false;
+        }
+
+        match &platform_filter.lib_c_type {
+            // Packages that don't specify lib_c_type are skipped when filtering
+            Some(lib_c) => package.lib_c_type.as_deref() == Some(lib_c.as_str()),
+            // Without an explicit filter, still check compatibility with this host
+            None if !platform_filter.targets_other_os() => {
+                self.matches_platform_libc(&package.lib_c_type)
+            }
+            None => true,
+        }
+    }
+
     /// Check if the package's lib_c_type is compatible with the current platform
     fn matches_platform_libc(&self, lib_c_type: &Option<String>) -> bool {
         match lib_c_type {
@@ -321,16 +516,15 @@ impl MetadataCache {
         // Check version match if version is specified
         if let Some(version_pattern) = version_str {
             let matches = match version_type {
-                VersionSearchType::JavaVersion => package.version.matches_pattern(version_pattern),
+                VersionSearchType::JavaVersion => {
+                    matching::matches_pattern(&package.version, version_pattern)
+                }
                 VersionSearchType::DistributionVersion => {
-                    // Use Version's matches_pattern method for distribution_version
-                    package
-                        .distribution_version
-                        .matches_pattern(version_pattern)
+                    matching::matches_pattern(&package.distribution_version, version_pattern)
                 }
                 VersionSearchType::Auto => {
                     // This shouldn't happen as Auto is resolved earlier, but handle it
-                    package.version.matches_pattern(version_pattern)
+                    matching::matches_pattern(&package.version, version_pattern)
                 }
             };
 
@@ -339,9 +533,10 @@ impl MetadataCache {
             }
         }
 
-        // Check package type if specified
-        if let Some(ref package_type) = request.package_type
-            && package.package_type != *package_type
+        // Check package type; companion archives only match on request
+        if !package
+            .package_type
+            .satisfies(request.package_type.as_ref())
         {
             return false;
         }
@@ -353,35 +548,12 @@ impl MetadataCache {
             return false;
         }
 
-        // Apply platform filters if set
-        if let Some(ref arch) = platform_filter.architecture
-            && package.architecture.to_string() != *arch
-        {
-            return false;
-        }
-
-        if let Some(ref os) = platform_filter.operating_system
-            && package.operating_system.to_string() != *os
-        {
+        // Feature builds such as CRaC only match when their suffix is given
+        if !package.has_features(&request.features) {
             return false;
         }
 
-        if let Some(ref lib_c) = platform_filter.lib_c_type {
-            if let Some(ref pkg_lib_c) = package.lib_c_type
-                && pkg_lib_c != lib_c
-            {
-                return false;
-            } else if package.lib_c_type.is_none() {
-                // Package doesn't specify lib_c_type, skip it if we're filtering
-                return false;
-            }
-        } else {
-            // No explicit lib_c_type filter, but we should still check platform compatibility
-            if !self.matches_platform_libc(&package.lib_c_type) {
-                return false;
-            }
-        }
-
-        true
+        // Apply platform filters if set
+        self.matches_target(package, platform_filter)
     }
 }
@@ -12,30 +12,38 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod clean;
 mod conversion;
+mod index;
 mod metadata_cache;
 mod models;
 mod storage;
+mod verify;
 
 #[cfg(test)]
 mod tests;
 
 use chrono::Utc;
-use log::{info, warn};
+use log::{debug, info, warn};
 use std::str::FromStr;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+use std::time::Duration;
 
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
 use crate::indicator::{ProgressIndicator, ProgressRendererKind, SilentProgress, StatusReporter};
-use crate::locking::CacheWriterLockGuard;
+use crate::locking::{CacheWriterLockGuard, CancellationToken, LockScope, global_token};
+use crate::metadata::PartialMetadata;
 use crate::metadata::provider::MetadataProvider;
 use crate::models::distribution::Distribution as JdkDistribution;
 use crate::models::metadata::JdkMetadata;
 use crate::models::package::ChecksumType;
+use crate::perf::{Phase, TimingCollector};
 
 // Re-export commonly used types from search functionality
-pub use models::{PlatformFilter, SearchResult, VersionSearchType};
+pub use models::{PlatformFilter, ReleaseDateFilter, SearchResult, VersionSearchType};
 
 // Re-export metadata cache types
 pub use metadata_cache::{DistributionCache, MetadataCache};
@@ -46,10 +54,27 @@ pub use crate::platform::{get_current_architecture, get_current_os, get_current_
 // Re-export conversion functions
 pub use conversion::{
     convert_api_to_cache, convert_package_to_jdk_metadata, parse_architecture_from_filename,
+    parse_package_architecture, parse_release_date,
 };
 
 // Re-export storage functions
-pub use storage::{load_cache, save_cache};
+pub use storage::{
+    CACHE_FORMAT_VERSION, is_incompatible_cache, load_cache, load_cache_upgrading, save_cache,
+};
+
+// Re-export the cache index
+pub use index::{index_path, load_cache_for, load_cache_subset, remove_index};
+
+// Re-export cache cleaning
+pub use clean::{CleanReport, clean_cache};
+
+// Re-export cache verification
+pub use verify::{
+    DistributionReport, DriftKind, PackageDrift, live_source, prune_drift, verify_distribution,
+};
+
+/// Upper bound on concurrent per-distribution fetches during a refresh.
+const MAX_PARALLEL_FETCHES: usize = 4;
 
 // Helper functions for metadata operations
 
@@ -60,7 +85,7 @@ pub fn get_metadata(requested_version: Option<&str>, config: &KopiConfig) -> Res
 
     // Try to use cache if it exists
     if cache_path.exists() {
-        match load_cache(&cache_path) {
+        match load_cache_upgrading(&cache_path, config) {
             Ok(loaded_cache) => {
                 // If specific version requested and not in cache, try API
                 if let Some(version) = requested_version
@@ -90,12 +115,44 @@ pub fn get_metadata(requested_version: Option<&str>, config: &KopiConfig) -> Res
     fetch_and_cache_metadata_with_progress(config, &mut progress, &mut current_step)
 }
 
+/// Load the metadata cache, refreshing it first when it is missing or older
+/// than `metadata.cache.max_age_hours`
+pub fn get_fresh_metadata(config: &KopiConfig) -> Result<MetadataCache> {
+    let max_age = Duration::from_secs(config.metadata.cache.max_age_hours * 3600);
+    let metadata = get_metadata(None, config)?;
+    if !metadata.is_stale(max_age) {
+        return Ok(metadata);
+    }
+
+    debug!("Metadata cache is stale, refreshing");
+    let mut progress = SilentProgress;
+    let mut current_step = 0u64;
+    fetch_and_cache_metadata_with_progress(config, &mut progress, &mut current_step)
+}
+
 /// Fetch metadata from API and cache it with progress reporting
 pub fn fetch_and_cache_metadata_with_progress(
     config: &KopiConfig,
     progress: &mut dyn ProgressIndicator,
     current_step: &mut u64,
 ) -> Result<MetadataCache> {
+    fetch_and_cache_metadata_with_timings(
+        config,
+        progress,
+        current_step,
+        &TimingCollector::disabled(),
+    )
+}
+
+/// Fetch metadata from API and cache it, attributing network and cache write
+/// time to the given collector
+pub fn fetch_and_cache_metadata_with_timings(
+    config: &KopiConfig,
+    progress: &mut dyn ProgressIndicator,
+    current_step: &mut u64,
+    timings: &TimingCollector,
+) -> Result<MetadataCache> {
+    let cancellation = global_token();
     let renderer_kind = progress.renderer_kind();
     let cache_lock_guard = match renderer_kind {
         ProgressRendererKind::Silent => {
@@ -117,9 +174,10 @@ pub fn fetch_and_cache_metadata_with_progress(
     let provider = MetadataProvider::from_config(config)?;
 
     // Step: Fetching from sources (handled by provider)
-    let metadata = provider
-        .fetch_all(progress)
+    let PartialMetadata { metadata, skipped } = timings
+        .time(Phase::Network, || provider.fetch_all_partial(progress))
         .map_err(|e| KopiError::MetadataFetch(format!("Failed to fetch metadata from API: {e}")))?;
+    check_interrupted(&cancellation)?;
 
     // Step: Processing metadata
     *current_step += 1;
@@ -154,6 +212,33 @@ pub fn fetch_and_cache_metadata_with_progress(
         new_cache.distributions.insert(dist_name, dist_cache);
     }
 
+    let cache_path = config.metadata_cache_path()?;
+    if !skipped.is_empty() {
+        // Keep what the previous refresh cached for distributions skipped this time
+        let previous = load_cache(&cache_path).ok();
+        for (name, _) in &skipped {
+            if let Some(dist_cache) = previous
+                .as_ref()
+                .and_then(|cache| cache.distributions.get(name))
+            {
+                new_cache
+                    .distributions
+                    .entry(name.clone())
+                    .or_insert_with(|| dist_cache.clone());
+            }
+        }
+        warn!(
+            "Skipped {} distribution(s) the metadata source failed to return, keeping their \
+             previously cached packages: {}",
+            skipped.len(),
+            skipped
+                .iter()
+                .map(|(name, error)| format!("{name} ({error})"))
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
+    }
+
     new_cache.last_updated = Utc::now();
 
     // Step: Saving to cache
@@ -161,8 +246,12 @@ pub fn fetch_and_cache_metadata_with_progress(
     progress.update(*current_step, None);
     progress.set_message("Saving metadata to cache...".to_string());
 
-    let cache_path = config.metadata_cache_path()?;
-    new_cache.save(&cache_path, config.locking.timeout_value())?;
+    timings.time(Phase::MetadataWrite, || {
+        new_cache.save(
+            &cache_path,
+            config.locking.timeout_for(&LockScope::CacheWriter).value,
+        )
+    })?;
 
     // Step: Completion
     *current_step += 1;
@@ -183,7 +272,31 @@ pub fn fetch_and_cache_distribution(
     config: &KopiConfig,
     progress: &mut dyn ProgressIndicator,
     current_step: &mut u64,
+    timings: &TimingCollector,
+) -> Result<MetadataCache> {
+    fetch_and_cache_distributions(
+        &[distribution_name.to_string()],
+        config,
+        progress,
+        current_step,
+        timings,
+    )
+}
+
+/// Fetch metadata for several distributions in parallel and merge them into
+/// the cache, leaving every other distribution untouched.
+///
+/// Uses one progress step for loading the cache, one per distribution, and one
+/// for saving. Distributions that were fetched are saved even when others
+/// fail; the failures are then reported as a single error.
+pub fn fetch_and_cache_distributions(
+    distribution_names: &[String],
+    config: &KopiConfig,
+    progress: &mut dyn ProgressIndicator,
+    current_step: &mut u64,
+    timings: &TimingCollector,
 ) -> Result<MetadataCache> {
+    let cancellation = global_token();
     let renderer_kind = progress.renderer_kind();
     let cache_lock_guard = match renderer_kind {
         ProgressRendererKind::Silent => {
@@ -196,7 +309,8 @@ pub fn fetch_and_cache_distribution(
         }
     };
     info!(
-        "Acquired cache writer lock for distribution {distribution_name} via {:?} backend after {:.3}s",
+        "Acquired cache writer lock for distributions {distribution_names:?} via {:?} backend \
+         after {:.3}s",
         cache_lock_guard.backend(),
         cache_lock_guard.waited().as_secs_f32()
     );
@@ -208,7 +322,14 @@ pub fn fetch_and_cache_distribution(
 
     let cache_path = config.metadata_cache_path()?;
     let mut result_cache = if cache_path.exists() {
-        load_cache(&cache_path)?
+        match load_cache(&cache_path) {
+            Ok(cache) => cache,
+            Err(e) if is_incompatible_cache(&e) => {
+                warn!("Discarding unreadable metadata cache: {e}");
+                MetadataCache::new()
+            }
+            Err(e) => return Err(e),
+        }
     } else {
         MetadataCache::new()
     };
@@ -216,48 +337,140 @@ pub fn fetch_and_cache_distribution(
     // Create metadata provider from config
     let provider = MetadataProvider::from_config(config)?;
 
-    // Step: Fetching distribution metadata
-    *current_step += 1;
-    progress.update(*current_step, None);
-    progress.set_message(format!("Fetching metadata for {distribution_name}..."));
-
-    let packages = provider
-        .fetch_distribution(distribution_name, progress)
-        .map_err(|e| {
-            KopiError::MetadataFetch(format!(
-                "Failed to fetch packages for {distribution_name}: {e}"
-            ))
-        })?;
+    // Steps: Fetching each distribution (one step per completed fetch)
+    progress.set_message(format!(
+        "Fetching metadata for {}...",
+        distribution_names.join(", ")
+    ));
+    let fetched = timings.time(Phase::Network, || {
+        fetch_distributions_parallel(
+            &provider,
+            distribution_names,
+            &cancellation,
+            |name, count| {
+                *current_step += 1;
+                progress.update(*current_step, None);
+                progress.set_message(format!("Fetched {name} ({count} packages)"));
+            },
+        )
+    });
+    check_interrupted(&cancellation)?;
+
+    let mut failures = Vec::new();
+    for (distribution_name, result) in fetched {
+        match result {
+            Ok(packages) => {
+                let dist_cache = DistributionCache {
+                    distribution: JdkDistribution::from_str(&distribution_name)
+                        .unwrap_or(JdkDistribution::Other(distribution_name.clone())),
+                    display_name: distribution_name.clone(), // For now, use dist name as display name
+                    packages,
+                };
+                result_cache
+                    .distributions
+                    .insert(distribution_name, dist_cache);
+            }
+            Err(e) => failures.push(format!("{distribution_name}: {e}")),
+        }
+    }
 
-    // Step: Processing distribution
-    *current_step += 1;
-    progress.update(*current_step, None);
-    progress.set_message(format!("Processing {} packages...", packages.len()));
-
-    // Create DistributionCache
-    let dist_cache = DistributionCache {
-        distribution: JdkDistribution::from_str(distribution_name)
-            .unwrap_or(JdkDistribution::Other(distribution_name.to_string())),
-        display_name: distribution_name.to_string(), // For now, use dist name as display name
-        packages,
-    };
+    if failures.len() < distribution_names.len() {
+        result_cache.last_updated = Utc::now();
 
-    // Update cache with this distribution
-    result_cache
-        .distributions
-        .insert(distribution_name.to_string(), dist_cache);
-    result_cache.last_updated = Utc::now();
+        // Step: Saving updated cache
+        *current_step += 1;
+        progress.update(*current_step, None);
+        progress.set_message("Saving updated cache...".to_string());
 
-    // Step: Saving updated cache
-    *current_step += 1;
-    progress.update(*current_step, None);
-    progress.set_message("Saving updated cache...".to_string());
+        timings.time(Phase::MetadataWrite, || {
+            result_cache.save(
+                &cache_path,
+                config.locking.timeout_for(&LockScope::CacheWriter).value,
+            )
+        })?;
+    }
 
-    result_cache.save(&cache_path, config.locking.timeout_value())?;
+    if !failures.is_empty() {
+        return Err(KopiError::MetadataFetch(format!(
+            "Failed to fetch packages for {}",
+            failures.join("; ")
+        )));
+    }
 
     Ok(result_cache)
 }
 
+/// Stop before the cache is written when Ctrl+C arrived during a fetch, so
+/// an interrupted refresh leaves the previous cache in place
+fn check_interrupted(cancellation: &CancellationToken) -> Result<()> {
+    if cancellation.is_cancelled() {
+        return Err(KopiError::Cancelled(
+            "metadata refresh interrupted; the cache was left unchanged".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fetch distributions on at most `MAX_PARALLEL_FETCHES` worker threads,
+/// calling `on_fetched` on this thread as each one succeeds. Results keep the
+/// order of `distribution_names`; workers take no new distribution once
+/// `cancellation` fires.
+fn fetch_distributions_parallel(
+    provider: &MetadataProvider,
+    distribution_names: &[String],
+    cancellation: &CancellationToken,
+    mut on_fetched: impl FnMut(&str, usize),
+) -> Vec<(String, Result<Vec<JdkMetadata>>)> {
+    let next = AtomicUsize::new(0);
+    let workers = distribution_names.len().clamp(1, MAX_PARALLEL_FETCHES);
+    let (sender, receiver) = mpsc::channel();
+
+    let mut results: Vec<Option<Result<Vec<JdkMetadata>>>> =
+        distribution_names.iter().map(|_| None).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || {
+                while !cancellation.is_cancelled() {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(name) = distribution_names.get(index) else {
+                        break;
+                    };
+                    debug!("Fetching distribution {name}");
+                    let result = provider.fetch_distribution(name, &mut SilentProgress);
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        for (index, result) in receiver {
+            if let Ok(packages) = &result {
+                on_fetched(&distribution_names[index], packages.len());
+            }
+            results[index] = Some(result);
+        }
+    });
+
+    distribution_names
+        .iter()
+        .cloned()
+        .zip(results)
+        .map(|(name, result)| {
+            let result = result.unwrap_or_else(|| {
+                Err(KopiError::MetadataFetch(format!(
+                    "Fetch worker for {name} exited early"
+                )))
+            });
+            (name, result)
+        })
+        .collect()
+}
+
 /// Fetch checksum for a specific JDK package (uses SilentProgress internally)
 pub fn fetch_package_checksum(
     package_id: &str,
@@ -296,6 +509,15 @@ pub fn fetch_package_checksum(
             })?;
     }
 
+    // Mirrors may publish checksums as files next to the archive instead
+    if metadata.checksum.is_none()
+        && let Some((checksum, checksum_type)) =
+            MetadataProvider::from_config(config)?.fetch_checksum(&metadata)
+    {
+        metadata.checksum = Some(checksum);
+        metadata.checksum_type = Some(checksum_type);
+    }
+
     // Extract checksum and type
     let checksum = metadata.checksum.ok_or_else(|| {
         KopiError::MetadataFetch(format!(
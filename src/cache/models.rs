@@ -12,7 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::cache::parse_release_date;
+use crate::error::{KopiError, Result};
 use crate::models::metadata::JdkMetadata;
+use crate::models::platform::{Architecture, OperatingSystem};
+use crate::uninstall::unused::parse_age;
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VersionSearchType {
@@ -27,6 +33,54 @@ impl Default for VersionSearchType {
     }
 }
 
+/// Window of release dates a search result must fall into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReleaseDateFilter {
+    /// Inclusive lower bound
+    pub after: Option<DateTime<Utc>>,
+    /// Exclusive upper bound
+    pub before: Option<DateTime<Utc>>,
+}
+
+impl ReleaseDateFilter {
+    /// Build a filter from bounds written as dates (`2024-06-01`), RFC 3339
+    /// timestamps, or ages counted back from `now` (`30d`, `12w`)
+    pub fn from_bounds(
+        after: Option<&str>,
+        before: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Self> {
+        let parse = |value: &str| {
+            parse_release_date(value)
+                .or_else(|| parse_age(value).ok().map(|age| now - age))
+                .ok_or_else(|| {
+                    KopiError::ValidationError(format!(
+                        "Invalid release date '{value}'. Use YYYY-MM-DD or an age such as 30d"
+                    ))
+                })
+        };
+        Ok(Self {
+            after: after.map(parse).transpose()?,
+            before: before.map(parse).transpose()?,
+        })
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.after.is_some() || self.before.is_some()
+    }
+
+    /// Packages whose release date is unknown only match an inactive filter
+    pub fn matches(&self, release_date: Option<DateTime<Utc>>) -> bool {
+        if !self.is_active() {
+            return true;
+        }
+        release_date.is_some_and(|date| {
+            self.after.is_none_or(|after| date >= after)
+                && self.before.is_none_or(|before| date < before)
+        })
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct PlatformFilter {
     pub architecture: Option<String>,
@@ -34,6 +88,78 @@ pub struct PlatformFilter {
     pub lib_c_type: Option<String>,
 }
 
+impl PlatformFilter {
+    /// Build a filter from user-supplied names, normalizing aliases such as
+    /// `amd64` or `darwin` to the names used in metadata
+    pub fn from_names(architecture: Option<&str>, operating_system: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            architecture: architecture
+                .map(|arch| Architecture::from_str(arch).map(|arch| arch.to_string()))
+                .transpose()?,
+            operating_system: operating_system
+                .map(|os| OperatingSystem::from_str(os).map(|os| os.to_string()))
+                .transpose()?,
+            lib_c_type: None,
+        })
+    }
+
+    /// Filter for the platform Kopi is running on
+    pub fn current() -> Self {
+        Self {
+            architecture: Some(crate::platform::get_current_architecture()),
+            operating_system: Some(crate::platform::get_current_os()),
+            lib_c_type: None,
+        }
+    }
+
+    /// Parse a target platform written as `<os>-<arch>[-<libc>]`, e.g.
+    /// `linux-x64`, `macos-aarch64`, or `linux-x64-musl`. Docker-style
+    /// `linux/aarch64` is accepted as well.
+    ///
+    /// Linux targets other than the current host default to glibc so that the
+    /// choice between glibc and musl builds is deterministic.
+    pub fn parse_target(spec: &str) -> Result<Self> {
+        let mut parts = spec.splitn(3, ['-', '/']);
+        let (Some(os), Some(arch)) = (parts.next(), parts.next()) else {
+            return Err(KopiError::InvalidConfig(format!(
+                "Invalid platform '{spec}': expected <os>-<arch>[-<libc>] (e.g. linux-x64 or \
+                 linux/aarch64)"
+            )));
+        };
+
+        let mut filter = Self::from_names(Some(arch), Some(os))?;
+        filter.lib_c_type = match parts.next() {
+            Some(libc) => Some(libc.to_string()),
+            None if filter.targets_other_os() && os.eq_ignore_ascii_case("linux") => {
+                Some("glibc".to_string())
+            }
+            None => None,
+        };
+        Ok(filter)
+    }
+
+    /// Platform label in the form accepted by [`PlatformFilter::parse_target`]
+    pub fn label(&self) -> String {
+        let mut label = format!(
+            "{}-{}",
+            self.operating_system.as_deref().unwrap_or("any"),
+            self.architecture.as_deref().unwrap_or("any")
+        );
+        if let Some(libc) = &self.lib_c_type {
+            label.push('-');
+            label.push_str(libc);
+        }
+        label
+    }
+
+    /// Whether the filter targets an operating system other than the current one
+    pub fn targets_other_os(&self) -> bool {
+        self.operating_system
+            .as_deref()
+            .is_some_and(|os| os != crate::platform::get_current_os())
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct SearchResult {
     pub distribution: String,
@@ -13,9 +13,12 @@
 // limitations under the License.
 
 use crate::cache::MetadataCache;
+use crate::cache::index;
+use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
-use crate::locking::LockTimeoutValue;
+use crate::locking::{LockController, LockScope, LockTimeoutValue};
 use crate::platform;
+use serde_json::{Map, Value};
 use std::cmp::min;
 use std::fs::{self, OpenOptions};
 use std::io::{self, Write};
@@ -30,14 +33,143 @@ const INITIAL_RENAME_BACKOFF: Duration = Duration::from_millis(50);
 const MAX_RENAME_BACKOFF: Duration = Duration::from_millis(1_000);
 const CACHE_TEMP_EXTENSION: &str = "tmp";
 
-/// Load metadata cache from a file
+/// Format version written to the `version` field of the metadata cache
+pub const CACHE_FORMAT_VERSION: u32 = 2;
+
+/// Upgrade from format N to N + 1, indexed by N - 1
+type Migration = fn(&mut Map<String, Value>);
+const MIGRATIONS: [Migration; (CACHE_FORMAT_VERSION - 1) as usize] = [migrate_v1_to_v2];
+
+/// Load metadata cache from a file, upgrading older formats in memory.
+///
+/// Fails with [`KopiError::SchemaViolation`] on the `version` field when the
+/// snapshot has a format no migration can read, e.g. one written by a newer
+/// kopi; callers then refetch instead of using it.
 pub fn load_cache(path: &Path) -> Result<MetadataCache> {
+    read_cache(path).map(|(cache, _)| cache)
+}
+
+/// Like [`load_cache`], also rewriting an upgraded snapshot in the current
+/// format so later loads (and the cache index) skip the migration.
+///
+/// The rewrite is best-effort: it is skipped when another process holds the
+/// cache writer lock and failures are only logged.
+pub fn load_cache_upgrading(path: &Path, config: &KopiConfig) -> Result<MetadataCache> {
+    let (cache, found) = read_cache(path)?;
+    if found < CACHE_FORMAT_VERSION
+        && let Err(e) = persist_upgrade(&cache, path, config)
+    {
+        log::warn!("Failed to rewrite the upgraded metadata cache: {e}");
+    }
+    Ok(cache)
+}
+
+/// Whether `error` means the cache can never be read by this kopi, as
+/// opposed to e.g. an I/O failure worth reporting
+pub fn is_incompatible_cache(error: &KopiError) -> bool {
+    matches!(error, KopiError::SchemaViolation { .. })
+}
+
+fn read_cache(path: &Path) -> Result<(MetadataCache, u32)> {
     let contents = fs::read_to_string(path)
         .map_err(|e| KopiError::ConfigError(format!("Failed to read cache file: {e}")))?;
 
-    let cache: MetadataCache =
-        serde_json::from_str(&contents).map_err(|_e| KopiError::InvalidMetadata)?;
-    Ok(cache)
+    let mut snapshot: Value = crate::schema::from_json_str(path, &contents)?;
+    let found = migrate(path, &mut snapshot)?;
+    let mut cache: MetadataCache = serde_path_to_error::deserialize(snapshot)
+        .map_err(|e| crate::schema::schema_violation(&path.display().to_string(), e))?;
+    cache.version = CACHE_FORMAT_VERSION;
+    Ok((cache, found))
+}
+
+/// Bring `snapshot` up to [`CACHE_FORMAT_VERSION`], returning the format it
+/// was written in
+fn migrate(path: &Path, snapshot: &mut Value) -> Result<u32> {
+    let incompatible = |message: String| KopiError::SchemaViolation {
+        file: path.display().to_string(),
+        path: "version".to_string(),
+        message,
+    };
+
+    let Some(object) = snapshot.as_object_mut() else {
+        // Let deserialization report the actual problem
+        return Ok(CACHE_FORMAT_VERSION);
+    };
+    let found = match object.get("version") {
+        // Snapshots have always carried a version; treat a missing one as the first format
+        None => 1,
+        Some(value) => value
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| incompatible(format!("unknown cache format {value}")))?,
+    };
+    if found > CACHE_FORMAT_VERSION {
+        return Err(incompatible(format!(
+            "cache format {found} is newer than the supported format {CACHE_FORMAT_VERSION}"
+        )));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(found as usize - 1) {
+        log::debug!(
+            "Upgrading metadata cache {} from format {} to {}",
+            path.display(),
+            from + 1,
+            from + 2
+        );
+        migration(object);
+    }
+    object.insert("version".to_string(), Value::from(CACHE_FORMAT_VERSION));
+    Ok(found)
+}
+
+/// Format 1 snapshots, written before every package field was mandatory,
+/// may lack the synonym map and the `distribution_version`, `size`, and
+/// `javafx_bundled` package fields
+fn migrate_v1_to_v2(cache: &mut Map<String, Value>) {
+    cache
+        .entry("synonym_map")
+        .or_insert_with(|| Value::Object(Map::new()));
+
+    let Some(distributions) = cache
+        .get_mut("distributions")
+        .and_then(Value::as_object_mut)
+    else {
+        return;
+    };
+    let packages = distributions
+        .values_mut()
+        .filter_map(|dist| dist.get_mut("packages").and_then(Value::as_array_mut))
+        .flatten()
+        .filter_map(Value::as_object_mut);
+    for package in packages {
+        if let Some(version) = package.get("version").cloned() {
+            package.entry("distribution_version").or_insert(version);
+        }
+        package.entry("size").or_insert(Value::from(0));
+        package
+            .entry("javafx_bundled")
+            .or_insert(Value::Bool(false));
+    }
+}
+
+fn persist_upgrade(cache: &MetadataCache, path: &Path, config: &KopiConfig) -> Result<()> {
+    let controller =
+        LockController::with_default_inspector(config.kopi_home().to_path_buf(), &config.locking);
+    let Some(acquisition) = controller.try_acquire(LockScope::CacheWriter)? else {
+        log::debug!("Cache writer lock is busy; leaving the upgraded cache for the next save");
+        return Ok(());
+    };
+    let result = save_cache(cache, path, config.locking.timeout_value());
+    if let Err(e) = acquisition.release() {
+        log::warn!("Failed to release cache writer lock: {e}");
+    }
+    result?;
+    log::info!(
+        "Upgraded metadata cache {} to format {CACHE_FORMAT_VERSION}",
+        path.display()
+    );
+    Ok(())
 }
 
 /// Save metadata cache to a file
@@ -52,7 +184,7 @@ pub fn save_cache(
         })?;
     }
 
-    let json = serde_json::to_vec_pretty(cache).map_err(|_e| KopiError::InvalidMetadata)?;
+    let (json, spans) = index::serialize_indexed(cache)?;
 
     // Write to temporary file first for atomic operation
     let temp_path = path.with_extension(CACHE_TEMP_EXTENSION);
@@ -134,6 +266,12 @@ pub fn save_cache(
         ))
     })?;
 
+    // The index only speeds up reads, so a cache without one is still usable
+    if let Err(e) = index::write_index(path, cache, spans) {
+        log::warn!("Failed to write metadata cache index: {e}");
+        let _ = index::remove_index(path);
+    }
+
     Ok(())
 }
 
@@ -221,6 +359,10 @@ mod tests {
     use super::*;
     use crate::cache::DistributionCache;
     use crate::models::distribution::Distribution as JdkDistribution;
+    use crate::models::metadata::JdkMetadata;
+    use crate::models::package::{ArchiveType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::version::Version;
     use tempfile::TempDir;
 
     #[test]
@@ -274,6 +416,102 @@ mod tests {
         );
     }
 
+    fn write_v1_snapshot(path: &Path) {
+        let package = JdkMetadata {
+            id: "temurin-21".to_string(),
+            distribution: "temurin".to_string(),
+            version: Version::new(21, 0, 5),
+            distribution_version: Version::new(21, 0, 5),
+            architecture: Architecture::X64,
+            operating_system: OperatingSystem::Linux,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: Some("https://example.com/temurin-21.tar.gz".to_string()),
+            checksum: None,
+            checksum_type: None,
+            size: 0,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: Some("lts".to_string()),
+            release_status: Some("ga".to_string()),
+            latest_build_available: Some(true),
+            release_date: None,
+        };
+        let mut package = serde_json::to_value(package).unwrap();
+        let fields = package.as_object_mut().unwrap();
+        for field in ["distribution_version", "size", "javafx_bundled"] {
+            fields.remove(field);
+        }
+
+        let snapshot = serde_json::json!({
+            "version": 1,
+            "last_updated": "2025-01-01T00:00:00Z",
+            "distributions": {
+                "temurin": {
+                    "distribution": "temurin",
+                    "display_name": "Eclipse Temurin",
+                    "packages": [package],
+                }
+            }
+        });
+        fs::write(path, serde_json::to_vec(&snapshot).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_load_cache_migrates_v1_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("metadata.json");
+        write_v1_snapshot(&cache_path);
+
+        let cache = load_cache(&cache_path).unwrap();
+        assert_eq!(cache.version, CACHE_FORMAT_VERSION);
+        assert!(cache.synonym_map.is_empty());
+        let package = &cache.distributions["temurin"].packages[0];
+        assert_eq!(package.distribution_version, Version::new(21, 0, 5));
+        assert_eq!(package.size, 0);
+        assert!(!package.javafx_bundled);
+
+        // Loading alone never touches the file
+        let on_disk: Value = serde_json::from_slice(&fs::read(&cache_path).unwrap()).unwrap();
+        assert_eq!(on_disk["version"], 1);
+    }
+
+    #[test]
+    fn test_load_cache_upgrading_rewrites_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let cache_path = temp_dir.path().join("metadata.json");
+        write_v1_snapshot(&cache_path);
+
+        let cache = load_cache_upgrading(&cache_path, &config).unwrap();
+        assert_eq!(cache.distributions["temurin"].packages.len(), 1);
+
+        let contents = fs::read_to_string(&cache_path).unwrap();
+        assert!(contents.starts_with(&format!("{{\"version\":{CACHE_FORMAT_VERSION}")));
+        assert!(index::index_path(&cache_path).exists());
+    }
+
+    #[test]
+    fn test_load_cache_rejects_newer_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("metadata.json");
+        let mut cache = MetadataCache::new();
+        cache.version = CACHE_FORMAT_VERSION + 1;
+        save_cache(&cache, &cache_path, LockTimeoutValue::from_secs(2)).unwrap();
+
+        let error = load_cache(&cache_path).unwrap_err();
+        assert!(is_incompatible_cache(&error));
+        match error {
+            KopiError::SchemaViolation { path, message, .. } => {
+                assert_eq!(path, "version");
+                assert!(message.contains("newer than the supported format"));
+            }
+            other => panic!("expected SchemaViolation, got {other:?}"),
+        }
+        assert!(index::load_cache_subset(&cache_path, None, None).is_none());
+    }
+
     #[test]
     fn rename_retries_on_sharing_violation() {
         use std::sync::atomic::{AtomicUsize, Ordering};
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::cache::models::VersionSearchType;
-use crate::cache::{DistributionCache, MetadataCache};
+use crate::cache::{DistributionCache, MetadataCache, PlatformFilter};
 use crate::config::KopiConfig;
 use crate::models::distribution::Distribution;
 use crate::models::metadata::JdkMetadata;
@@ -21,6 +21,7 @@ use crate::models::package::{ArchiveType, ChecksumType, PackageType};
 use crate::models::platform::{Architecture, OperatingSystem};
 use crate::platform::{get_current_architecture, get_current_os, get_foojay_libc_type};
 use crate::version::Version;
+use crate::version::file::VersionTrack;
 use crate::version::parser::{ParsedVersionRequest, VersionParser};
 use std::str::FromStr;
 
@@ -65,9 +66,11 @@ fn create_test_cache() -> MetadataCache {
             size: 100_000_000,
             lib_c_type: Some(current_libc.to_string()),
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         JdkMetadata {
             id: "test-17".to_string(),
@@ -85,9 +88,11 @@ fn create_test_cache() -> MetadataCache {
             size: 90_000_000,
             lib_c_type: Some(current_libc.to_string()),
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
     ];
 
@@ -169,6 +174,38 @@ fn test_lookup() {
     assert_eq!(package.unwrap().version.to_string(), "21.0.1");
 }
 
+#[test]
+fn test_lookup_track() {
+    let cache = create_test_cache();
+
+    let latest_lts = cache.lookup_track(
+        &Distribution::Temurin,
+        VersionTrack::LatestLts,
+        &PlatformFilter::current(),
+        None,
+        None,
+    );
+    assert_eq!(latest_lts.unwrap().version.to_string(), "21.0.1");
+
+    let feature = cache.lookup_track(
+        &Distribution::Temurin,
+        VersionTrack::FeatureLatest(17),
+        &PlatformFilter::current(),
+        None,
+        None,
+    );
+    assert_eq!(feature.unwrap().version.to_string(), "17.0.9");
+
+    let missing = cache.lookup_track(
+        &Distribution::Temurin,
+        VersionTrack::FeatureLatest(25),
+        &PlatformFilter::current(),
+        None,
+        None,
+    );
+    assert!(missing.is_none());
+}
+
 #[test]
 fn test_search_distribution_only() {
     let cache = create_test_cache();
@@ -179,6 +216,7 @@ fn test_search_distribution_only() {
         package_type: None,
         latest: false,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     let results = cache
@@ -198,6 +236,7 @@ fn test_search_latest() {
         package_type: None,
         latest: true,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     let results = cache
@@ -217,6 +256,7 @@ fn test_search_latest_with_distribution() {
         package_type: None,
         latest: true,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     let results = cache
@@ -237,6 +277,7 @@ fn test_search_with_package_type_filter() {
         package_type: Some(PackageType::Jdk),
         latest: false,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     let results = cache
@@ -508,6 +549,7 @@ fn test_latest_with_version_filter() {
         package_type: None,
         latest: true,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     let results = cache
@@ -551,9 +593,11 @@ fn test_lookup_with_javafx_filter() {
             size: 200_000_000,
             lib_c_type: Some(current_libc.to_string()),
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         JdkMetadata {
             id: "liberica-21-with-fx".to_string(),
@@ -571,9 +615,11 @@ fn test_lookup_with_javafx_filter() {
             size: 250_000_000, // JavaFX version is larger
             lib_c_type: Some(current_libc.to_string()),
             javafx_bundled: true,
+            features: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
     ];
 
@@ -626,6 +672,79 @@ fn test_lookup_with_javafx_filter() {
     // It will return one of them (order depends on vector order)
 }
 
+#[test]
+fn test_lookup_and_search_with_feature_suffix() {
+    let mut cache = MetadataCache::new();
+    let current_arch = get_current_architecture();
+    let current_os = get_current_os();
+    let archive_type = if current_os == "windows" {
+        ArchiveType::Zip
+    } else {
+        ArchiveType::TarGz
+    };
+
+    let package = |id: &str, features: &[&str]| JdkMetadata {
+        id: id.to_string(),
+        distribution: "zulu".to_string(),
+        version: Version::new(21, 0, 5),
+        distribution_version: Version::from_str("21.38.21").unwrap(),
+        architecture: Architecture::from_str(&current_arch).unwrap_or(Architecture::X64),
+        operating_system: OperatingSystem::from_str(&current_os).unwrap_or(OperatingSystem::Linux),
+        package_type: PackageType::Jdk,
+        archive_type,
+        download_url: Some(format!("https://example.com/{id}.tar.gz")),
+        checksum: None,
+        checksum_type: Some(ChecksumType::Sha256),
+        size: 200_000_000,
+        lib_c_type: Some(get_foojay_libc_type().to_string()),
+        javafx_bundled: false,
+        features: features.iter().map(|feature| feature.to_string()).collect(),
+        term_of_support: Some("lts".to_string()),
+        release_status: Some("ga".to_string()),
+        latest_build_available: Some(true),
+        release_date: None,
+    };
+    cache.distributions.insert(
+        "zulu".to_string(),
+        DistributionCache {
+            distribution: Distribution::Zulu,
+            display_name: "Azul Zulu".to_string(),
+            packages: vec![package("zulu-21-crac", &["crac"]), package("zulu-21", &[])],
+        },
+    );
+
+    let (test_arch, test_os) = get_test_platform();
+    let platform = PlatformFilter {
+        architecture: Some(test_arch),
+        operating_system: Some(test_os),
+        lib_c_type: None,
+    };
+    let lookup = |features: &[String]| {
+        cache
+            .lookup_for_platform(&Distribution::Zulu, "21", &platform, None, None, features)
+            .map(|package| package.id)
+    };
+    assert_eq!(lookup(&[]).as_deref(), Some("zulu-21"));
+    assert_eq!(
+        lookup(&["crac".to_string()]).as_deref(),
+        Some("zulu-21-crac")
+    );
+    assert_eq!(lookup(&["leyden".to_string()]), None);
+
+    let config = KopiConfig::new(std::env::temp_dir()).unwrap();
+    let parser = VersionParser::new(&config);
+    let search = |query: &str| -> Vec<String> {
+        cache
+            .search(&parser.parse(query).unwrap(), VersionSearchType::Auto)
+            .unwrap()
+            .into_iter()
+            .map(|result| result.package.id)
+            .collect()
+    };
+    assert_eq!(search("zulu@21+crac"), vec!["zulu-21-crac"]);
+    assert_eq!(search("zulu@21"), vec!["zulu-21"]);
+}
+
 #[test]
 fn test_detect_version_type() {
     // Standard Java versions should be detected as JavaVersion
@@ -697,6 +816,7 @@ fn test_search_by_distribution_version() {
         package_type: None,
         latest: false,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     let results = cache.search(&request, VersionSearchType::Auto).unwrap();
@@ -723,6 +843,7 @@ fn test_search_by_distribution_version() {
         package_type: None,
         latest: false,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     let results = cache
@@ -754,6 +875,7 @@ fn test_search_forced_java_version() {
         package_type: None,
         latest: false,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     // Force java_version search - should find both packages
@@ -799,9 +921,11 @@ fn test_distribution_version_boundary_matching() {
             size: 100_000_000,
             lib_c_type: Some(current_libc.to_string()),
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         };
 
         let mut pkg1 = base_pkg.clone();
@@ -827,6 +951,7 @@ fn test_distribution_version_boundary_matching() {
         package_type: None,
         latest: false,
         javafx_bundled: None,
+        features: Vec::new(),
     };
 
     let results = cache
@@ -849,3 +974,293 @@ fn test_distribution_version_boundary_matching() {
             .any(|r| r.package.distribution_version == Version::from_str("21.0.71").unwrap())
     );
 }
+
+#[test]
+fn test_search_with_platform_filter_for_other_os() {
+    use crate::cache::PlatformFilter;
+
+    let mut cache = create_test_cache();
+    let (other_os, other_libc) = if get_current_os() == "linux" {
+        (OperatingSystem::Windows, "c_std_lib")
+    } else {
+        (OperatingSystem::Linux, "glibc")
+    };
+    let mut foreign = cache.distributions["temurin"].packages[0].clone();
+    foreign.id = "test-21-foreign".to_string();
+    foreign.architecture = Architecture::Aarch64;
+    foreign.operating_system = other_os;
+    foreign.lib_c_type = Some(other_libc.to_string());
+    cache
+        .distributions
+        .get_mut("temurin")
+        .unwrap()
+        .packages
+        .push(foreign);
+
+    let config = create_test_config();
+    let parser = VersionParser::new(&config);
+    let request = parser.parse("21").unwrap();
+
+    // Without filters only packages usable on this machine are listed
+    let results = cache.search(&request, VersionSearchType::Auto).unwrap();
+    assert!(results.iter().all(|r| r.package.id != "test-21-foreign"));
+
+    let filter = PlatformFilter::from_names(Some("arm64"), Some(&other_os.to_string())).unwrap();
+    let results = cache
+        .search_with_platform(&request, VersionSearchType::Auto, &filter)
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].package.id, "test-21-foreign");
+}
+
+#[test]
+fn test_platform_filter_rejects_unknown_names() {
+    use crate::cache::PlatformFilter;
+
+    let filter = PlatformFilter::from_names(Some("amd64"), Some("darwin")).unwrap();
+    assert_eq!(filter.architecture.as_deref(), Some("x64"));
+    assert_eq!(filter.operating_system.as_deref(), Some("macos"));
+    assert!(PlatformFilter::from_names(Some("mips"), None).is_err());
+}
+
+/// Source that returns one package per distribution and fails for "broken"
+struct PerDistributionSource;
+
+impl crate::metadata::MetadataSource for PerDistributionSource {
+    fn id(&self) -> &str {
+        "per-distribution"
+    }
+
+    fn name(&self) -> &str {
+        "Per-distribution test source"
+    }
+
+    fn is_available(&self) -> crate::error::Result<bool> {
+        Ok(true)
+    }
+
+    fn fetch_all(
+        &self,
+        _progress: &mut dyn crate::indicator::ProgressIndicator,
+    ) -> crate::error::Result<Vec<JdkMetadata>> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_distribution(
+        &self,
+        distribution: &str,
+        _progress: &mut dyn crate::indicator::ProgressIndicator,
+    ) -> crate::error::Result<Vec<JdkMetadata>> {
+        if distribution == "broken" {
+            return Err(crate::error::KopiError::MetadataFetch(
+                "unavailable".to_string(),
+            ));
+        }
+        let mut package = create_test_cache().distributions["temurin"].packages[0].clone();
+        package.id = format!("{distribution}-21");
+        package.distribution = distribution.to_string();
+        Ok(vec![package])
+    }
+
+    fn fetch_package_details(
+        &self,
+        _package_id: &str,
+        _progress: &mut dyn crate::indicator::ProgressIndicator,
+    ) -> crate::error::Result<crate::metadata::PackageDetails> {
+        Err(crate::error::KopiError::MetadataFetch(
+            "not supported".to_string(),
+        ))
+    }
+
+    fn last_updated(&self) -> crate::error::Result<Option<chrono::DateTime<chrono::Utc>>> {
+        Ok(None)
+    }
+}
+
+#[test]
+fn test_fetch_distributions_parallel_keeps_order_and_failures() {
+    let provider =
+        crate::metadata::MetadataProvider::new_with_source(Box::new(PerDistributionSource));
+    let names: Vec<String> = [
+        "temurin", "broken", "zulu", "corretto", "liberica", "semeru",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+
+    let mut fetched_names = Vec::new();
+    let cancellation = crate::locking::CancellationToken::new();
+    let results =
+        super::fetch_distributions_parallel(&provider, &names, &cancellation, |name, count| {
+            assert_eq!(count, 1);
+            fetched_names.push(name.to_string());
+        });
+
+    let result_names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        result_names,
+        vec![
+            "temurin", "broken", "zulu", "corretto", "liberica", "semeru"
+        ]
+    );
+    for (name, result) in &results {
+        if name == "broken" {
+            assert!(result.is_err());
+        } else {
+            assert_eq!(result.as_ref().unwrap()[0].id, format!("{name}-21"));
+        }
+    }
+    assert_eq!(fetched_names.len(), 5);
+    assert!(!fetched_names.contains(&"broken".to_string()));
+}
+
+#[test]
+fn test_fetch_distributions_parallel_stops_when_cancelled() {
+    let provider =
+        crate::metadata::MetadataProvider::new_with_source(Box::new(PerDistributionSource));
+    let names = vec!["temurin".to_string(), "zulu".to_string()];
+    let cancellation = crate::locking::CancellationToken::new();
+    cancellation.cancel();
+
+    let results = super::fetch_distributions_parallel(&provider, &names, &cancellation, |_, _| {
+        panic!("nothing should be fetched after cancellation")
+    });
+    assert!(results.iter().all(|(_, result)| result.is_err()));
+    assert!(matches!(
+        super::check_interrupted(&cancellation),
+        Err(crate::error::KopiError::Cancelled(_))
+    ));
+}
+
+#[test]
+fn test_indexed_cache_round_trips() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let cache_path = temp_dir.path().join("metadata.json");
+    let cache = create_test_cache();
+
+    crate::cache::save_cache(
+        &cache,
+        &cache_path,
+        crate::locking::LockTimeoutValue::from_secs(2),
+    )
+    .unwrap();
+
+    assert!(crate::cache::index_path(&cache_path).exists());
+    let loaded = crate::cache::load_cache(&cache_path).unwrap();
+    assert_eq!(
+        serde_json::to_value(&loaded).unwrap(),
+        serde_json::to_value(&cache).unwrap()
+    );
+}
+
+#[test]
+fn test_load_cache_subset_by_major_and_distribution() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let cache_path = temp_dir.path().join("metadata.json");
+    let mut cache = create_test_cache();
+    cache
+        .synonym_map
+        .insert("adoptium".to_string(), "temurin".to_string());
+    crate::cache::save_cache(
+        &cache,
+        &cache_path,
+        crate::locking::LockTimeoutValue::from_secs(2),
+    )
+    .unwrap();
+
+    let subset = crate::cache::load_cache_subset(&cache_path, Some("adoptium"), Some(17)).unwrap();
+    assert_eq!(subset.last_updated, cache.last_updated);
+    let packages = &subset.distributions["temurin"].packages;
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].id, "test-17");
+
+    let subset = crate::cache::load_cache_subset(&cache_path, Some("zulu"), None).unwrap();
+    assert!(subset.distributions.is_empty());
+
+    let subset = crate::cache::load_cache_subset(&cache_path, None, None).unwrap();
+    assert_eq!(subset.distributions["temurin"].packages.len(), 2);
+}
+
+#[test]
+fn test_stale_cache_index_is_ignored() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let cache_path = temp_dir.path().join("metadata.json");
+    let cache = create_test_cache();
+    crate::cache::save_cache(
+        &cache,
+        &cache_path,
+        crate::locking::LockTimeoutValue::from_secs(2),
+    )
+    .unwrap();
+
+    // Rewrite the cache behind the index's back
+    let mut other = MetadataCache::new();
+    other.distributions.insert(
+        "zulu".to_string(),
+        DistributionCache {
+            distribution: Distribution::Zulu,
+            display_name: "Azul Zulu".to_string(),
+            packages: Vec::new(),
+        },
+    );
+    std::fs::write(&cache_path, serde_json::to_vec_pretty(&other).unwrap()).unwrap();
+
+    assert!(crate::cache::load_cache_subset(&cache_path, None, Some(21)).is_none());
+    let loaded = crate::cache::load_cache_for(&cache_path, None, Some(21)).unwrap();
+    assert!(loaded.distributions.contains_key("zulu"));
+
+    crate::cache::remove_index(&cache_path).unwrap();
+    assert!(!crate::cache::index_path(&cache_path).exists());
+    assert!(crate::cache::load_cache_subset(&cache_path, None, Some(21)).is_none());
+}
+
+#[test]
+fn test_parse_release_date() {
+    use chrono::TimeZone;
+
+    assert_eq!(
+        crate::cache::parse_release_date("2024-10-15"),
+        Some(chrono::Utc.with_ymd_and_hms(2024, 10, 15, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        crate::cache::parse_release_date("2024-10-15T09:30:00+02:00"),
+        Some(
+            chrono::Utc
+                .with_ymd_and_hms(2024, 10, 15, 7, 30, 0)
+                .unwrap()
+        )
+    );
+    assert_eq!(crate::cache::parse_release_date("October 2024"), None);
+}
+
+#[test]
+fn test_release_date_filter() {
+    use crate::cache::ReleaseDateFilter;
+    use chrono::TimeZone;
+
+    let now = chrono::Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap();
+    let date = |day| {
+        Some(
+            chrono::Utc
+                .with_ymd_and_hms(2024, 10, day, 12, 0, 0)
+                .unwrap(),
+        )
+    };
+
+    let inactive = ReleaseDateFilter::from_bounds(None, None, now).unwrap();
+    assert!(!inactive.is_active());
+    assert!(inactive.matches(None));
+
+    let filter = ReleaseDateFilter::from_bounds(Some("2024-10-10"), Some("7d"), now).unwrap();
+    assert_eq!(
+        filter.before,
+        Some(chrono::Utc.with_ymd_and_hms(2024, 10, 25, 0, 0, 0).unwrap())
+    );
+    assert!(filter.matches(date(10)));
+    assert!(filter.matches(date(24)));
+    assert!(!filter.matches(date(9)));
+    assert!(!filter.matches(date(25)));
+    assert!(!filter.matches(None));
+
+    assert!(ReleaseDateFilter::from_bounds(Some("last week"), None, now).is_err());
+}
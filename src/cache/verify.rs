@@ -0,0 +1,440 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cross-checking cached packages against a live metadata source.
+
+use crate::config::{KopiConfig, SourceConfig};
+use crate::indicator::SilentProgress;
+use crate::metadata::foojay::FoojayMetadataSource;
+use crate::metadata::source::MetadataSource;
+use crate::models::metadata::JdkMetadata;
+use log::debug;
+use std::collections::HashSet;
+
+use super::{DistributionCache, MetadataCache};
+
+/// How a cached package differs from the live source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriftKind {
+    /// The package is no longer listed upstream.
+    Removed,
+    /// The upstream checksum differs from the cached one.
+    ChecksumChanged { cached: String, live: String },
+    /// The upstream download URL differs from the cached one.
+    DownloadUrlChanged { cached: String, live: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageDrift {
+    pub package_id: String,
+    /// Human-readable package summary, e.g. `21.0.1+12 linux-x64 jdk`
+    pub label: String,
+    pub kind: DriftKind,
+    /// Live checksum details to store when pruning a changed package
+    live: Option<LiveDetails>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LiveDetails {
+    download_url: String,
+    checksum: Option<String>,
+    checksum_type: Option<crate::models::package::ChecksumType>,
+}
+
+/// Result of verifying one distribution.
+#[derive(Debug, Clone, Default)]
+pub struct DistributionReport {
+    pub distribution: String,
+    /// Packages in the cache for this distribution
+    pub cached: usize,
+    /// Packages whose checksum and download URL were compared
+    pub checked: usize,
+    /// Packages listed upstream that the cache does not have
+    pub upstream_only: usize,
+    pub drift: Vec<PackageDrift>,
+    /// Set when the distribution could not be fetched at all
+    pub error: Option<String>,
+}
+
+impl DistributionReport {
+    pub fn has_drift(&self) -> bool {
+        !self.drift.is_empty()
+    }
+}
+
+/// Build the Foojay source used for verification, honouring a configured base URL.
+pub fn live_source(config: &KopiConfig) -> FoojayMetadataSource {
+    let base_url = config
+        .metadata
+        .sources
+        .iter()
+        .find_map(|source| match source {
+            SourceConfig::Foojay { base_url, .. } => Some(base_url.clone()),
+            _ => None,
+        });
+
+    match base_url {
+        Some(base_url) => FoojayMetadataSource::new().with_base_url(base_url),
+        None => FoojayMetadataSource::new(),
+    }
+}
+
+/// Compare a cached distribution with `source`.
+///
+/// Every cached package is checked for removal using one listing request.
+/// Checksums and download URLs are compared for `sample` packages spread evenly
+/// across the cache, or for all of them when `sample` is `None`.
+pub fn verify_distribution(
+    source: &dyn MetadataSource,
+    name: &str,
+    cached: &DistributionCache,
+    sample: Option<usize>,
+) -> DistributionReport {
+    let mut report = DistributionReport {
+        distribution: name.to_string(),
+        cached: cached.packages.len(),
+        ..Default::default()
+    };
+
+    let live = match source.fetch_distribution(name, &mut SilentProgress) {
+        Ok(live) => live,
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return report;
+        }
+    };
+
+    let live_ids: HashSet<&str> = live.iter().map(|package| package.id.as_str()).collect();
+    let cached_ids: HashSet<&str> = cached
+        .packages
+        .iter()
+        .map(|package| package.id.as_str())
+        .collect();
+    report.upstream_only = live_ids.difference(&cached_ids).count();
+
+    let mut comparable = Vec::new();
+    for package in &cached.packages {
+        if !live_ids.contains(package.id.as_str()) {
+            report.drift.push(PackageDrift {
+                package_id: package.id.clone(),
+                label: package_label(package),
+                kind: DriftKind::Removed,
+                live: None,
+            });
+        } else if package.download_url.is_some() || package.checksum.is_some() {
+            comparable.push(package);
+        }
+    }
+
+    for package in sample_evenly(&comparable, sample) {
+        report.checked += 1;
+        let details = match source.fetch_package_details(&package.id, &mut SilentProgress) {
+            Ok(details) => details,
+            Err(e) => {
+                debug!("Could not fetch details for {}: {e}", package.id);
+                continue;
+            }
+        };
+        let live = LiveDetails {
+            download_url: details.download_url,
+            checksum: details.checksum,
+            checksum_type: details.checksum_type,
+        };
+
+        let kind = match (&package.checksum, &live.checksum) {
+            (Some(cached), Some(current)) if !cached.eq_ignore_ascii_case(current) => {
+                Some(DriftKind::ChecksumChanged {
+                    cached: cached.clone(),
+                    live: current.clone(),
+                })
+            }
+            _ => match &package.download_url {
+                Some(cached) if *cached != live.download_url => {
+                    Some(DriftKind::DownloadUrlChanged {
+                        cached: cached.clone(),
+                        live: live.download_url.clone(),
+                    })
+                }
+                _ => None,
+            },
+        };
+
+        if let Some(kind) = kind {
+            report.drift.push(PackageDrift {
+                package_id: package.id.clone(),
+                label: package_label(package),
+                kind,
+                live: Some(live),
+            });
+        }
+    }
+
+    report
+}
+
+/// Drop removed packages from `cache` and store the live details of changed
+/// ones. Returns the number of packages touched.
+pub fn prune_drift(cache: &mut MetadataCache, reports: &[DistributionReport]) -> usize {
+    let mut touched = 0;
+
+    for report in reports {
+        let Some(dist_cache) = cache.distributions.get_mut(&report.distribution) else {
+            continue;
+        };
+
+        let removed: HashSet<&str> = report
+            .drift
+            .iter()
+            .filter(|drift| drift.kind == DriftKind::Removed)
+            .map(|drift| drift.package_id.as_str())
+            .collect();
+        let before = dist_cache.packages.len();
+        dist_cache
+            .packages
+            .retain(|package| !removed.contains(package.id.as_str()));
+        touched += before - dist_cache.packages.len();
+
+        for drift in &report.drift {
+            let Some(live) = &drift.live else {
+                continue;
+            };
+            if let Some(package) = dist_cache
+                .packages
+                .iter_mut()
+                .find(|package| package.id == drift.package_id)
+            {
+                package.download_url = Some(live.download_url.clone());
+                package.checksum = live.checksum.clone();
+                package.checksum_type = live.checksum_type;
+                touched += 1;
+            }
+        }
+    }
+
+    touched
+}
+
+fn package_label(package: &JdkMetadata) -> String {
+    format!(
+        "{} {}-{} {}",
+        package.distribution_version,
+        package.operating_system,
+        package.architecture,
+        package.package_type
+    )
+}
+
+/// Pick up to `count` items spread evenly across `items`, or all of them.
+fn sample_evenly<'a, T>(items: &[&'a T], count: Option<usize>) -> Vec<&'a T> {
+    match count {
+        Some(count) if count < items.len() => {
+            (0..count).map(|i| items[i * items.len() / count]).collect()
+        }
+        _ => items.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{KopiError, Result};
+    use crate::indicator::ProgressIndicator;
+    use crate::metadata::source::PackageDetails;
+    use crate::models::distribution::Distribution;
+    use crate::models::package::{ArchiveType, ChecksumType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::version::Version;
+    use std::collections::HashMap;
+
+    struct LiveSource {
+        packages: Vec<JdkMetadata>,
+        checksums: HashMap<String, String>,
+    }
+
+    impl MetadataSource for LiveSource {
+        fn id(&self) -> &str {
+            "live"
+        }
+
+        fn name(&self) -> &str {
+            "Live"
+        }
+
+        fn is_available(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn fetch_all(&self, _progress: &mut dyn ProgressIndicator) -> Result<Vec<JdkMetadata>> {
+            Ok(self.packages.clone())
+        }
+
+        fn fetch_distribution(
+            &self,
+            _distribution: &str,
+            _progress: &mut dyn ProgressIndicator,
+        ) -> Result<Vec<JdkMetadata>> {
+            Ok(self.packages.clone())
+        }
+
+        fn fetch_package_details(
+            &self,
+            package_id: &str,
+            _progress: &mut dyn ProgressIndicator,
+        ) -> Result<PackageDetails> {
+            let checksum = self
+                .checksums
+                .get(package_id)
+                .cloned()
+                .ok_or_else(|| KopiError::MetadataFetch(format!("unknown {package_id}")))?;
+            Ok(PackageDetails {
+                download_url: format!("https://example.com/{package_id}.tar.gz"),
+                checksum: Some(checksum),
+                checksum_type: Some(ChecksumType::Sha256),
+            })
+        }
+
+        fn last_updated(&self) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+            Ok(None)
+        }
+    }
+
+    fn package(id: &str, checksum: Option<&str>) -> JdkMetadata {
+        JdkMetadata {
+            id: id.to_string(),
+            distribution: "temurin".to_string(),
+            version: Version::new(21, 0, 1),
+            distribution_version: Version::new(21, 0, 1),
+            architecture: Architecture::X64,
+            operating_system: OperatingSystem::Linux,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: checksum.map(|_| format!("https://example.com/{id}.tar.gz")),
+            checksum: checksum.map(str::to_string),
+            checksum_type: checksum.map(|_| ChecksumType::Sha256),
+            size: 0,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }
+    }
+
+    fn cached(packages: Vec<JdkMetadata>) -> DistributionCache {
+        DistributionCache {
+            distribution: Distribution::Temurin,
+            display_name: "Temurin".to_string(),
+            packages,
+        }
+    }
+
+    fn live_source() -> LiveSource {
+        LiveSource {
+            packages: vec![
+                package("kept", None),
+                package("changed", None),
+                package("new", None),
+            ],
+            checksums: HashMap::from([
+                ("kept".to_string(), "aaa".to_string()),
+                ("changed".to_string(), "new-sum".to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_verify_distribution_reports_removed_and_changed() {
+        let dist_cache = cached(vec![
+            package("kept", Some("AAA")),
+            package("changed", Some("old-sum")),
+            package("gone", Some("ccc")),
+        ]);
+
+        let report = verify_distribution(&live_source(), "temurin", &dist_cache, None);
+
+        assert_eq!(report.cached, 3);
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.upstream_only, 1);
+        let kinds: Vec<_> = report
+            .drift
+            .iter()
+            .map(|drift| (drift.package_id.as_str(), drift.kind.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("gone", DriftKind::Removed),
+                (
+                    "changed",
+                    DriftKind::ChecksumChanged {
+                        cached: "old-sum".to_string(),
+                        live: "new-sum".to_string(),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_distribution_sample_limits_detail_checks() {
+        let dist_cache = cached(vec![
+            package("kept", Some("aaa")),
+            package("changed", Some("old-sum")),
+            package("gone", Some("ccc")),
+        ]);
+
+        let report = verify_distribution(&live_source(), "temurin", &dist_cache, Some(1));
+
+        // Removal is always checked; only one package gets a detail lookup
+        assert_eq!(report.checked, 1);
+        assert_eq!(report.drift[0].kind, DriftKind::Removed);
+    }
+
+    #[test]
+    fn test_prune_drift_removes_and_updates_packages() {
+        let mut cache = MetadataCache::new();
+        cache.distributions.insert(
+            "temurin".to_string(),
+            cached(vec![
+                package("kept", Some("aaa")),
+                package("changed", Some("old-sum")),
+                package("gone", Some("ccc")),
+            ]),
+        );
+        let report = verify_distribution(
+            &live_source(),
+            "temurin",
+            &cache.distributions["temurin"],
+            None,
+        );
+
+        assert_eq!(prune_drift(&mut cache, &[report]), 2);
+
+        let packages = &cache.distributions["temurin"].packages;
+        let ids: Vec<_> = packages.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["kept", "changed"]);
+        assert_eq!(packages[1].checksum.as_deref(), Some("new-sum"));
+    }
+
+    #[test]
+    fn test_sample_evenly_spreads_picks() {
+        let items = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let refs: Vec<&i32> = items.iter().collect();
+        assert_eq!(sample_evenly(&refs, Some(3)), vec![&0, &3, &6]);
+        assert_eq!(sample_evenly(&refs, Some(20)).len(), 10);
+        assert_eq!(sample_evenly(&refs, None).len(), 10);
+    }
+}
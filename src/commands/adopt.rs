@@ -0,0 +1,102 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::StatusReporter;
+use crate::installation::adopt::{self, AdoptOutcome, DetectedJdk};
+use crate::storage::JdkRepository;
+use log::info;
+use std::path::PathBuf;
+
+/// Registers JDKs installed by the system or other tools so shims and
+/// `kopi env` can use them in place
+pub struct AdoptCommand<'a> {
+    config: &'a KopiConfig,
+    no_progress: bool,
+}
+
+impl<'a> AdoptCommand<'a> {
+    pub fn new(config: &'a KopiConfig, no_progress: bool) -> Result<Self> {
+        Ok(Self {
+            config,
+            no_progress,
+        })
+    }
+
+    /// Adopt the JDKs at `paths`, plus those found in the usual locations
+    /// when `scan` is set. `dry_run` only reports what was found.
+    pub fn execute(&self, paths: &[PathBuf], scan: bool, dry_run: bool) -> Result<()> {
+        if paths.is_empty() && !scan {
+            return Err(KopiError::ValidationError(
+                "Give the JAVA_HOME of a JDK to adopt, or use --scan to search common locations"
+                    .to_string(),
+            ));
+        }
+
+        let mut jdks = paths
+            .iter()
+            .map(|path| adopt::detect_at(path))
+            .collect::<Result<Vec<DetectedJdk>>>()?;
+        if scan {
+            for jdk in adopt::scan(&adopt::default_scan_roots()) {
+                if !jdks.iter().any(|known| known.java_home == jdk.java_home) {
+                    jdks.push(jdk);
+                }
+            }
+        }
+
+        let status = StatusReporter::new(self.no_progress);
+        if jdks.is_empty() {
+            status.step("No JDKs found outside kopi");
+            return Ok(());
+        }
+
+        let repository = JdkRepository::new(self.config);
+        let mut adopted = 0;
+        for jdk in &jdks {
+            let name = format!("{}@{}", jdk.distribution.id(), jdk.version);
+            if dry_run {
+                status.step(&format!(
+                    "Would adopt {name} from {}",
+                    jdk.java_home.display()
+                ));
+                continue;
+            }
+
+            info!("Adopting {name} from {}", jdk.java_home.display());
+            match adopt::adopt(&repository, jdk)? {
+                AdoptOutcome::Adopted(_) => {
+                    adopted += 1;
+                    status.success(&format!("Adopted {name} from {}", jdk.java_home.display()));
+                }
+                AdoptOutcome::AlreadyAdopted => {
+                    status.step(&format!("{name} is already adopted"));
+                }
+                AdoptOutcome::Conflict(existing) => {
+                    status.error(&format!(
+                        "Skipping {} because {name} is already installed at {}",
+                        jdk.java_home.display(),
+                        existing.display()
+                    ));
+                }
+            }
+        }
+
+        if adopted > 0 {
+            status.step("Adopted JDKs stay where they are; uninstall only unregisters them");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::advisory::{self, AuditEntry, Severity};
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::storage::JdkRepository;
+use comfy_table::{Cell, Color, Table};
+use std::str::FromStr;
+
+/// Exit status when an installed JDK has an advisory at or above `--fail-on`
+pub const VULNERABLE_EXIT_CODE: i32 = 11;
+
+/// Severity that fails the audit unless `--fail-on` says otherwise
+pub const DEFAULT_FAIL_ON: &str = "critical";
+
+pub struct AuditCommand<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> AuditCommand<'a> {
+    pub fn new(config: &'a KopiConfig) -> Result<Self> {
+        Ok(Self { config })
+    }
+
+    pub fn execute(&self, json: bool, refresh: bool, fail_on: &str) -> Result<()> {
+        let fail_on = Severity::from_str(fail_on)?;
+
+        let repository = JdkRepository::new(self.config);
+        let installed = repository.list_installed_jdks()?;
+        if installed.is_empty() {
+            if json {
+                println!("[]");
+            } else {
+                println!("No JDKs installed");
+            }
+            return Ok(());
+        }
+
+        let feed = advisory::load_feed(&self.config.audit, self.config.kopi_home(), refresh)?;
+        let entries = advisory::audit_installed(&feed, &installed);
+
+        if json {
+            let output = serde_json::to_string_pretty(&entries).map_err(|e| {
+                KopiError::SystemError(format!("Failed to serialize audit report: {e}"))
+            })?;
+            println!("{output}");
+        } else {
+            print_report(&entries);
+        }
+
+        if entries
+            .iter()
+            .any(|entry| entry.severity.is_some_and(|severity| severity >= fail_on))
+        {
+            std::process::exit(VULNERABLE_EXIT_CODE);
+        }
+        Ok(())
+    }
+}
+
+fn severity_cell(severity: Option<Severity>) -> Cell {
+    match severity {
+        None => Cell::new("ok").fg(Color::Green),
+        Some(severity @ Severity::Critical) => Cell::new(severity).fg(Color::Red),
+        Some(severity @ Severity::High) => Cell::new(severity).fg(Color::Magenta),
+        Some(severity) => Cell::new(severity).fg(Color::Yellow),
+    }
+}
+
+fn print_report(entries: &[AuditEntry]) {
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec![
+        Cell::new("Distribution"),
+        Cell::new("Version"),
+        Cell::new("Severity"),
+        Cell::new("Advisories"),
+        Cell::new("Fixed in"),
+    ]);
+
+    for entry in entries {
+        let ids: Vec<&str> = entry
+            .advisories
+            .iter()
+            .map(|advisory| advisory.id.as_str())
+            .collect();
+        let fixed_in = match (&entry.fixed_in, entry.is_vulnerable()) {
+            (Some(fixed), _) => fixed.as_str(),
+            (None, true) => "no fix yet",
+            (None, false) => "-",
+        };
+
+        table.add_row(vec![
+            Cell::new(&entry.distribution),
+            Cell::new(&entry.version),
+            severity_cell(entry.severity),
+            Cell::new(if ids.is_empty() {
+                "-".to_string()
+            } else {
+                ids.join(", ")
+            }),
+            Cell::new(fixed_in),
+        ]);
+    }
+
+    println!("{table}");
+
+    let vulnerable: Vec<&AuditEntry> = entries.iter().filter(|e| e.is_vulnerable()).collect();
+    if vulnerable.is_empty() {
+        return;
+    }
+
+    println!();
+    println!(
+        "{} installed JDK(s) are affected by known advisories.",
+        vulnerable.len()
+    );
+    for entry in vulnerable {
+        if let Some(fixed) = &entry.fixed_in {
+            println!(
+                "  {} {}: kopi install {}@{fixed}",
+                entry.distribution, entry.version, entry.distribution
+            );
+        }
+    }
+}
@@ -14,24 +14,55 @@
 
 use crate::cache;
 use crate::cache::get_current_platform;
+use crate::cache::{PlatformFilter, ReleaseDateFilter};
 use crate::config::KopiConfig;
-use crate::error::Result;
-use crate::indicator::{ProgressConfig, ProgressFactory, ProgressStyle as IndicatorStyle};
+use crate::download::DownloadCache;
+use crate::error::{KopiError, Result};
+use crate::indicator::{
+    ProgressConfig, ProgressFactory, ProgressStyle as IndicatorStyle, StatusReporter,
+};
+use crate::locking::{CacheWriterLockGuard, LockScope};
+use crate::models::distribution::Distribution;
+use crate::models::package::PackageType;
+use crate::perf::{Phase, TimingCollector};
+use crate::storage::{InstalledJdk, JdkRepository};
+use crate::version::fuzzy::{DistributionCorrection, correct_distribution};
 use crate::version::parser::VersionParser;
-use chrono::Local;
-use clap::Subcommand;
+use chrono::{Local, Utc};
+use clap::{Args, Subcommand};
 use colored::*;
 use comfy_table::{Cell, CellAlignment, Color, Table};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
 
 #[derive(Subcommand, Debug)]
 pub enum CacheCommand {
     /// Refresh metadata from foojay.io API
-    Refresh,
+    Refresh {
+        /// Only refresh these distributions (e.g., temurin corretto)
+        #[arg(conflicts_with = "only_installed")]
+        distributions: Vec<String>,
+        /// Only refresh distributions that have an installed JDK
+        #[arg(long)]
+        only_installed: bool,
+    },
     /// Show cache information
     Info,
     /// Clear all cached data
     Clear,
+    /// Drop distributions nothing refers to and shrink the cache to its size budget
+    Clean {
+        /// Report what would be removed without changing the cache
+        #[arg(long)]
+        dry_run: bool,
+        /// Size budget in MB (default: metadata.cache.max_size_mb; 0 means none)
+        #[arg(long, value_name = "MB")]
+        max_size_mb: Option<u64>,
+        /// Clean the download cache instead: remove every cached archive, or
+        /// only the least recently used ones beyond --max-size-mb
+        #[arg(long)]
+        downloads: bool,
+    },
     /// Search for available JDK versions
     Search {
         /// Query to search for (e.g., "21", "17.0.9", "corretto@21", "corretto", "latest")
@@ -45,6 +76,9 @@ pub enum CacheCommand {
         /// Output results as JSON for programmatic use
         #[arg(long, conflicts_with_all = ["compact", "detailed"])]
         json: bool,
+        /// Show when each package was released (always shown with --detailed)
+        #[arg(long, conflicts_with = "json")]
+        release_date: bool,
         /// Filter to show only LTS versions
         #[arg(long)]
         lts_only: bool,
@@ -54,9 +88,56 @@ pub enum CacheCommand {
         /// Force search by distribution_version field
         #[arg(long, conflicts_with = "java_version")]
         distribution_version: bool,
+        #[command(flatten)]
+        filters: SearchFilterArgs,
     },
     /// List all available distributions in cache
     ListDistributions,
+    /// Check cached packages against the live foojay API
+    Verify {
+        /// Only verify these distributions (default: every cached distribution)
+        distributions: Vec<String>,
+        /// Number of packages per distribution whose checksums are compared
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_VERIFY_SAMPLE, conflicts_with = "all")]
+        sample: usize,
+        /// Compare checksums of every cached package
+        #[arg(long)]
+        all: bool,
+        /// Remove packages gone upstream and update changed checksums in the cache
+        #[arg(long)]
+        prune: bool,
+    },
+}
+
+/// Packages per distribution whose details `kopi cache verify` compares by default
+const DEFAULT_VERIFY_SAMPLE: usize = 5;
+
+/// Package filters shared by `kopi cache search` and `kopi search`
+#[derive(Args, Debug, Clone, Default)]
+pub struct SearchFilterArgs {
+    /// Only show packages for this CPU architecture (e.g., x64, aarch64)
+    #[arg(long, value_name = "ARCH")]
+    pub arch: Option<String>,
+    /// Only show packages for this operating system (e.g., linux, macos, windows)
+    #[arg(long, value_name = "OS")]
+    pub os: Option<String>,
+    /// Only show JRE packages
+    #[arg(long, conflicts_with = "jdk")]
+    pub jre: bool,
+    /// Only show JDK packages
+    #[arg(long, conflicts_with = "jre")]
+    pub jdk: bool,
+    /// Only show packages bundled with JavaFX
+    #[arg(long)]
+    pub javafx: bool,
+    /// Only show packages released on or after a date (YYYY-MM-DD) or within
+    /// an age (e.g., 30d, 8w)
+    #[arg(long, value_name = "DATE|AGE")]
+    pub released_after: Option<String>,
+    /// Only show packages released before a date (YYYY-MM-DD) or longer ago
+    /// than an age (e.g., 30d, 8w)
+    #[arg(long, value_name = "DATE|AGE")]
+    pub released_before: Option<String>,
 }
 
 #[derive(Debug)]
@@ -65,43 +146,89 @@ struct SearchOptions {
     compact: bool,
     detailed: bool,
     json: bool,
+    release_date: bool,
     lts_only: bool,
     force_java_version: bool,
     force_distribution_version: bool,
+    filters: SearchFilterArgs,
 }
 
 impl CacheCommand {
     pub fn execute(self, config: &KopiConfig, no_progress: bool) -> Result<()> {
+        self.execute_with_timings(config, no_progress, &TimingCollector::disabled())
+    }
+
+    /// Execute the command, recording refresh and search spans for `--timings`
+    pub fn execute_with_timings(
+        self,
+        config: &KopiConfig,
+        no_progress: bool,
+        timings: &TimingCollector,
+    ) -> Result<()> {
         match self {
-            CacheCommand::Refresh => refresh_cache(config, no_progress),
+            CacheCommand::Refresh {
+                distributions,
+                only_installed,
+            } => {
+                if only_installed {
+                    refresh_installed_distributions(config, no_progress, timings)
+                } else if distributions.is_empty() {
+                    refresh_cache(config, no_progress, timings)
+                } else {
+                    refresh_distributions(&distributions, config, no_progress, timings)
+                }
+            }
             CacheCommand::Info => show_cache_info(config, no_progress),
             CacheCommand::Clear => clear_cache(config, no_progress),
+            CacheCommand::Clean {
+                dry_run,
+                max_size_mb,
+                downloads: true,
+            } => clean_downloads(config, dry_run, max_size_mb, no_progress),
+            CacheCommand::Clean {
+                dry_run,
+                max_size_mb,
+                downloads: false,
+            } => clean_cache(config, dry_run, max_size_mb, no_progress),
             CacheCommand::Search {
                 version,
                 compact,
                 detailed,
                 json,
+                release_date,
                 lts_only,
                 java_version,
                 distribution_version,
+                filters,
             } => {
                 let options = SearchOptions {
                     version_string: version,
                     compact,
                     detailed,
                     json,
+                    release_date,
                     lts_only,
                     force_java_version: java_version,
                     force_distribution_version: distribution_version,
+                    filters,
                 };
-                search_cache(options, config)
+                search_cache(options, config, timings)
             }
             CacheCommand::ListDistributions => list_distributions(config),
+            CacheCommand::Verify {
+                distributions,
+                sample,
+                all,
+                prune,
+            } => {
+                let sample = if all { None } else { Some(sample) };
+                verify_cache(&distributions, sample, prune, config, no_progress)
+            }
         }
     }
 }
 
-fn refresh_cache(config: &KopiConfig, no_progress: bool) -> Result<()> {
+fn refresh_cache(config: &KopiConfig, no_progress: bool, timings: &TimingCollector) -> Result<()> {
     // Create metadata provider to get source count
     let provider = crate::metadata::provider::MetadataProvider::from_config(config)?;
 
@@ -130,10 +257,11 @@ fn refresh_cache(config: &KopiConfig, no_progress: bool) -> Result<()> {
 
     // Fetch metadata from API - this will handle steps 2-N internally (one per source)
     // and steps N+1 to N+4 (processing steps)
-    let cache = match cache::fetch_and_cache_metadata_with_progress(
+    let cache = match cache::fetch_and_cache_metadata_with_timings(
         config,
         progress.as_mut(),
         &mut current_step,
+        timings,
     ) {
         Ok(cache) => cache,
         Err(e) => {
@@ -157,6 +285,217 @@ fn refresh_cache(config: &KopiConfig, no_progress: bool) -> Result<()> {
     Ok(())
 }
 
+fn refresh_installed_distributions(
+    config: &KopiConfig,
+    no_progress: bool,
+    timings: &TimingCollector,
+) -> Result<()> {
+    let repository = JdkRepository::new(config);
+    let installed = repository.list_installed_jdks()?;
+    let distributions = installed_distributions(&installed);
+
+    if distributions.is_empty() {
+        let progress = ProgressFactory::create(no_progress);
+        progress.println("No JDKs installed; nothing to refresh")?;
+        return Ok(());
+    }
+
+    refresh_distributions(&distributions, config, no_progress, timings)
+}
+
+/// Unique distribution names of the installed JDKs, in sorted order
+fn installed_distributions(installed: &[InstalledJdk]) -> Vec<String> {
+    let names: BTreeSet<String> = installed
+        .iter()
+        .map(|jdk| jdk.distribution.clone())
+        .collect();
+    names.into_iter().collect()
+}
+
+/// Resolve user-supplied distribution names to the canonical names used by the
+/// metadata sources, dropping duplicates while keeping the given order.
+fn canonical_distribution_names(names: &[String], cache: &cache::MetadataCache) -> Vec<String> {
+    let mut seen = HashSet::new();
+    names
+        .iter()
+        .filter_map(|name| {
+            let distribution = Distribution::from_str(name).ok()?;
+            let id = distribution.id();
+            let canonical = cache.get_canonical_name(id).unwrap_or(id).to_string();
+            seen.insert(canonical.clone()).then_some(canonical)
+        })
+        .collect()
+}
+
+fn refresh_distributions(
+    names: &[String],
+    config: &KopiConfig,
+    no_progress: bool,
+    timings: &TimingCollector,
+) -> Result<()> {
+    let cache_path = config.metadata_cache_path()?;
+    let existing = if cache_path.exists() {
+        cache::load_cache(&cache_path)?
+    } else {
+        cache::MetadataCache::new()
+    };
+    let distributions = canonical_distribution_names(names, &existing);
+
+    // Steps: loading the cache, one per distribution, saving, completion
+    let total_steps = distributions.len() as u64 + 3;
+
+    let mut progress = ProgressFactory::create(no_progress);
+    progress.start(ProgressConfig::new(IndicatorStyle::Count).with_total(total_steps));
+
+    let mut current_step = 0u64;
+    let cache = match cache::fetch_and_cache_distributions(
+        &distributions,
+        config,
+        progress.as_mut(),
+        &mut current_step,
+        timings,
+    ) {
+        Ok(cache) => cache,
+        Err(e) => {
+            progress.error(format!("Failed to refresh cache: {e}"));
+            return Err(e);
+        }
+    };
+
+    progress.complete(Some("Cache refreshed successfully".to_string()));
+    progress.success(&format!("Refreshed {}", distributions.join(", ")))?;
+
+    let refreshed_packages: usize = distributions
+        .iter()
+        .filter_map(|name| cache.distributions.get(name))
+        .map(|d| d.packages.len())
+        .sum();
+    progress.println(&format!("{refreshed_packages} JDK packages refreshed"))?;
+
+    Ok(())
+}
+
+fn verify_cache(
+    names: &[String],
+    sample: Option<usize>,
+    prune: bool,
+    config: &KopiConfig,
+    no_progress: bool,
+) -> Result<()> {
+    let cache_path = config.metadata_cache_path()?;
+    if !cache_path.exists() {
+        return Err(KopiError::CacheNotFound);
+    }
+    let metadata_cache = cache::load_cache(&cache_path)?;
+
+    let mut distributions: Vec<String> = if names.is_empty() {
+        metadata_cache.distributions.keys().cloned().collect()
+    } else {
+        canonical_distribution_names(names, &metadata_cache)
+    };
+    distributions.sort();
+    distributions.retain(|name| {
+        let cached = metadata_cache.distributions.contains_key(name);
+        if !cached {
+            eprintln!("{} {name} is not in the cache; skipping", "!".yellow());
+        }
+        cached
+    });
+
+    let source = cache::live_source(config);
+    let mut progress = ProgressFactory::create(no_progress);
+    progress
+        .start(ProgressConfig::new(IndicatorStyle::Count).with_total(distributions.len() as u64));
+    let mut reports = Vec::new();
+    for (index, name) in distributions.iter().enumerate() {
+        progress.update(index as u64 + 1, None);
+        progress.set_message(format!("Verifying {name}..."));
+        reports.push(cache::verify_distribution(
+            &source,
+            name,
+            &metadata_cache.distributions[name],
+            sample,
+        ));
+    }
+    progress.complete(Some("Verification finished".to_string()));
+
+    for report in &reports {
+        print_verify_report(report);
+    }
+
+    let drifted = reports.iter().filter(|report| report.has_drift()).count();
+    if drifted == 0 {
+        progress.success("Cache matches the live API")?;
+    } else if prune {
+        let _lock = CacheWriterLockGuard::acquire_with_status_reporter(
+            config,
+            &StatusReporter::new(no_progress),
+        )?;
+        // Reload under the lock so concurrent refreshes are not overwritten
+        let mut latest = cache::load_cache(&cache_path)?;
+        let pruned = cache::prune_drift(&mut latest, &reports);
+        latest.save(
+            &cache_path,
+            config.locking.timeout_for(&LockScope::CacheWriter).value,
+        )?;
+        progress.success(&format!("Pruned {pruned} stale cache entries"))?;
+    } else {
+        progress.println(&format!(
+            "\n{drifted} distribution(s) drifted. Run {} to fix the cache.",
+            "'kopi cache verify --prune'".cyan()
+        ))?;
+    }
+
+    let failures: Vec<String> = reports
+        .iter()
+        .filter_map(|report| {
+            let error = report.error.as_ref()?;
+            Some(format!("{}: {error}", report.distribution))
+        })
+        .collect();
+    if !failures.is_empty() {
+        return Err(KopiError::MetadataFetch(format!(
+            "Failed to verify {}",
+            failures.join("; ")
+        )));
+    }
+
+    Ok(())
+}
+
+fn print_verify_report(report: &cache::DistributionReport) {
+    if let Some(error) = &report.error {
+        println!("{} {}: {error}", "✗".red(), report.distribution);
+        return;
+    }
+
+    let mut summary = format!(
+        "{}: {} cached, {} checked",
+        report.distribution, report.cached, report.checked
+    );
+    if report.upstream_only > 0 {
+        summary.push_str(&format!(", {} new upstream", report.upstream_only));
+    }
+    if !report.has_drift() {
+        println!("{} {summary}, no drift", "✓".green());
+        return;
+    }
+
+    println!("{} {summary}, {} drifted", "✗".red(), report.drift.len());
+    for drift in &report.drift {
+        let detail = match &drift.kind {
+            cache::DriftKind::Removed => "removed upstream".to_string(),
+            cache::DriftKind::ChecksumChanged { cached, live } => {
+                format!("checksum changed ({cached} -> {live})")
+            }
+            cache::DriftKind::DownloadUrlChanged { cached, live } => {
+                format!("download URL changed ({cached} -> {live})")
+            }
+        };
+        println!("    {} [{}]: {detail}", drift.label, drift.package_id);
+    }
+}
+
 fn show_cache_info(config: &KopiConfig, _no_progress: bool) -> Result<()> {
     let cache_path = config.metadata_cache_path()?;
 
@@ -200,6 +539,7 @@ fn clear_cache(config: &KopiConfig, no_progress: bool) -> Result<()> {
 
     if cache_path.exists() {
         std::fs::remove_file(&cache_path)?;
+        cache::remove_index(&cache_path)?;
         progress.success("Cache cleared successfully")?;
     } else {
         progress.println("No cache to clear")?;
@@ -208,29 +548,188 @@ fn clear_cache(config: &KopiConfig, no_progress: bool) -> Result<()> {
     Ok(())
 }
 
-fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
+/// Distributions the cache must keep: those with installed JDKs plus the
+/// configured default, additional and custom distributions
+fn referenced_distributions(config: &KopiConfig) -> Result<BTreeSet<String>> {
+    let repository = JdkRepository::new(config);
+    let mut keep: BTreeSet<String> = installed_distributions(&repository.list_installed_jdks()?)
+        .into_iter()
+        .collect();
+    keep.insert(config.default_distribution.clone());
+    keep.extend(config.additional_distributions.iter().cloned());
+    keep.extend(
+        config
+            .custom_distributions
+            .iter()
+            .map(|custom| custom.name.clone()),
+    );
+    Ok(keep
+        .into_iter()
+        .map(|name| {
+            Distribution::from_str(&name)
+                .map(|distribution| distribution.id().to_string())
+                .unwrap_or(name)
+        })
+        .collect())
+}
+
+fn clean_cache(
+    config: &KopiConfig,
+    dry_run: bool,
+    max_size_mb: Option<u64>,
+    no_progress: bool,
+) -> Result<()> {
+    let cache_path = config.metadata_cache_path()?;
+    if !cache_path.exists() {
+        return Err(KopiError::CacheNotFound);
+    }
+    let max_bytes = match max_size_mb.unwrap_or(config.metadata.cache.max_size_mb) {
+        0 => None,
+        mb => Some(mb * 1024 * 1024),
+    };
+    let keep = referenced_distributions(config)?;
+    let platform = (cache::get_current_os(), cache::get_current_architecture());
+
+    let progress = ProgressFactory::create(no_progress);
+    let _lock = if dry_run {
+        None
+    } else {
+        Some(CacheWriterLockGuard::acquire_with_status_reporter(
+            config,
+            &StatusReporter::new(no_progress),
+        )?)
+    };
+    // Load under the lock so concurrent refreshes are not overwritten
+    let mut metadata_cache = cache::load_cache(&cache_path)?;
+    let size_before = std::fs::metadata(&cache_path)?.len();
+    let report = cache::clean_cache(
+        &mut metadata_cache,
+        &keep,
+        max_bytes,
+        (&platform.0, &platform.1),
+    )?;
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    for name in &report.removed_distributions {
+        progress.println(&format!("  {verb} {name}"))?;
+    }
+    if report.removed_packages > 0 {
+        progress.println(&format!(
+            "  {verb} {} package(s) to meet the size budget",
+            report.removed_packages
+        ))?;
+    }
+
+    let reclaimed = if dry_run {
+        size_before.saturating_sub(report.bytes_after)
+    } else {
+        metadata_cache.save(
+            &cache_path,
+            config.locking.timeout_for(&LockScope::CacheWriter).value,
+        )?;
+        size_before.saturating_sub(std::fs::metadata(&cache_path)?.len())
+    };
+    let summary = format!(
+        "{} {} distribution(s), {} KB reclaimed",
+        if dry_run { "Would clean" } else { "Cleaned" },
+        report.removed_distributions.len(),
+        reclaimed / 1024
+    );
+    progress.success(&summary)?;
+
+    if report.over_budget {
+        eprintln!(
+            "{} The cache is still over its size budget; only distributions in use are left",
+            "!".yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// Remove cached archives, keeping the most recently used ones that fit in
+/// `max_size_mb` when given
+fn clean_downloads(
+    config: &KopiConfig,
+    dry_run: bool,
+    max_size_mb: Option<u64>,
+    no_progress: bool,
+) -> Result<()> {
+    let cache = DownloadCache::new(config.download_cache_dir(), None);
+    let removed = cache.over_budget(max_size_mb.unwrap_or(0) * 1024 * 1024)?;
+
+    let progress = ProgressFactory::create(no_progress);
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    for entry in &removed {
+        progress.println(&format!("  {verb} {}", entry.path.display()))?;
+    }
+    if !dry_run {
+        cache.prune(&removed)?;
+    }
+
+    let reclaimed: u64 = removed.iter().map(|entry| entry.size).sum();
+    progress.success(&format!(
+        "{} {} archive(s), {} MB reclaimed",
+        if dry_run { "Would clean" } else { "Cleaned" },
+        removed.len(),
+        reclaimed / (1024 * 1024)
+    ))?;
+    Ok(())
+}
+
+fn search_cache(
+    options: SearchOptions,
+    config: &KopiConfig,
+    timings: &TimingCollector,
+) -> Result<()> {
     let SearchOptions {
         version_string,
         compact: _compact,
         detailed,
         json,
+        release_date,
         lts_only,
         force_java_version,
         force_distribution_version,
+        filters,
     } = options;
+    let platform_filter =
+        PlatformFilter::from_names(filters.arch.as_deref(), filters.os.as_deref())?;
+    let release_filter = ReleaseDateFilter::from_bounds(
+        filters.released_after.as_deref(),
+        filters.released_before.as_deref(),
+        Utc::now(),
+    )?;
+    let show_release_date = detailed || release_date;
     let cache_path = config.metadata_cache_path()?;
 
-    // Load cache or create new one if it doesn't exist
-    let mut cache = if cache_path.exists() {
-        cache::load_cache(&cache_path)?
-    } else {
-        // If no cache exists, create an empty one
-        cache::MetadataCache::new()
-    };
-
     // Parse the version string to check if distribution was specified
     let parser = VersionParser::new(config);
-    let parsed_request = match parser.parse(&version_string) {
+    let version_string =
+        match correct_distribution(&version_string, &parser.known_distribution_names()) {
+            Some(DistributionCorrection::Resolved { from, to, spec }) => {
+                eprintln!("Unknown distribution '{from}', showing results for '{to}'");
+                spec
+            }
+            Some(DistributionCorrection::Ambiguous { name, candidates }) => {
+                if json {
+                    println!("[]");
+                } else {
+                    let candidates = candidates
+                        .iter()
+                        .map(|candidate| format!("'{candidate}'"))
+                        .collect::<Vec<_>>()
+                        .join(" or ");
+                    println!(
+                        "{} Unknown distribution: {name}. Did you mean {candidates}?",
+                        "✗".red()
+                    );
+                }
+                return Ok(());
+            }
+            None => version_string,
+        };
+    let mut parsed_request = match parser.parse(&version_string) {
         Ok(req) => req,
         Err(e) => {
             if json {
@@ -268,6 +767,52 @@ fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
         }
     };
 
+    // Explicit filter flags take precedence over prefixes/suffixes in the query
+    if filters.jre {
+        parsed_request.package_type = Some(PackageType::Jre);
+    } else if filters.jdk {
+        parsed_request.package_type = Some(PackageType::Jdk);
+    }
+    if filters.javafx {
+        parsed_request.javafx_bundled = Some(true);
+    }
+
+    // Determine version search type based on flags
+    let version_type = if force_java_version {
+        crate::cache::VersionSearchType::JavaVersion
+    } else if force_distribution_version {
+        crate::cache::VersionSearchType::DistributionVersion
+    } else {
+        crate::cache::VersionSearchType::Auto
+    };
+
+    // Java version searches only ever match their own major version, so the
+    // cache index lets us skip reading every other package
+    let indexed_major = parsed_request
+        .version
+        .as_ref()
+        .filter(|version| {
+            !parsed_request.latest
+                && match version_type {
+                    crate::cache::VersionSearchType::Auto => {
+                        cache::MetadataCache::detect_version_type(&version.to_string())
+                            == crate::cache::VersionSearchType::JavaVersion
+                    }
+                    other => other == crate::cache::VersionSearchType::JavaVersion,
+                }
+        })
+        .map(|version| version.major());
+    let requested_distribution = parsed_request.distribution.as_ref().map(|dist| dist.id());
+
+    // Load cache or create new one if it doesn't exist
+    let mut cache = if !cache_path.exists() {
+        cache::MetadataCache::new()
+    } else if indexed_major.is_some() || requested_distribution.is_some() {
+        cache::load_cache_for(&cache_path, requested_distribution, indexed_major)?
+    } else {
+        cache::load_cache(&cache_path)?
+    };
+
     // Check if a specific distribution was requested and if it's in cache
     if let Some(ref dist) = parsed_request.distribution {
         let dist_id = dist.id();
@@ -289,6 +834,7 @@ fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
                 config,
                 &mut progress,
                 &mut current_step,
+                timings,
             ) {
                 Ok(updated_cache) => {
                     cache = updated_cache;
@@ -317,16 +863,9 @@ fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
         }
     }
 
-    // Determine version search type based on flags
-    let version_type = if force_java_version {
-        crate::cache::VersionSearchType::JavaVersion
-    } else if force_distribution_version {
-        crate::cache::VersionSearchType::DistributionVersion
-    } else {
-        crate::cache::VersionSearchType::Auto
-    };
-
-    let mut results = cache.search(&parsed_request, version_type)?;
+    let mut results = timings.time(Phase::Resolution, || {
+        cache.search_with_platform(&parsed_request, version_type, &platform_filter)
+    })?;
 
     // Apply LTS filtering if requested
     if lts_only {
@@ -340,6 +879,8 @@ fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
         });
     }
 
+    results.retain(|result| release_filter.matches(result.package.release_date));
+
     if results.is_empty() {
         if json {
             println!("[]");
@@ -361,6 +902,11 @@ fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
             println!("  - The cache might be outdated");
             println!("  - The version might not exist");
             println!("  - The distribution name might be incorrect");
+            if release_filter.is_active() {
+                println!(
+                    "  - Packages without a known release date are hidden by the date filters"
+                );
+            }
 
             println!("\n{}", "Try these:".yellow().bold());
             println!(
@@ -450,6 +996,9 @@ fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
         ]
     };
 
+    if show_release_date {
+        headers.push(Cell::new("Released"));
+    }
     if has_javafx {
         headers.push(Cell::new("JavaFX"));
     }
@@ -469,11 +1018,9 @@ fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
             // Add separator row between distributions (except for the first one)
             if !is_first_distribution {
                 // Create a separator row that will be replaced with proper line later
-                let num_cols = if detailed {
-                    8 + if has_javafx { 1 } else { 0 }
-                } else {
-                    3 + if has_javafx { 1 } else { 0 }
-                };
+                let num_cols = if detailed { 8 } else { 3 }
+                    + usize::from(show_release_date)
+                    + usize::from(has_javafx);
 
                 let separator_row: Vec<Cell> =
                     (0..num_cols).map(|_| Cell::new("SEPARATOR")).collect();
@@ -672,6 +1219,14 @@ fn search_cache(options: SearchOptions, config: &KopiConfig) -> Result<()> {
                         ]
                     };
 
+                    if show_release_date {
+                        row.push(Cell::new(
+                            package
+                                .release_date
+                                .map(|date| date.format("%Y-%m-%d").to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                        ));
+                    }
                     if has_javafx {
                         row.push(
                             Cell::new(if package.javafx_bundled { "✓" } else { "" })
@@ -889,12 +1444,14 @@ mod tests {
             compact: false,
             detailed: false,
             json: false,
+            release_date: false,
             lts_only: true,
             force_java_version: false,
             force_distribution_version: false,
+            filters: SearchFilterArgs::default(),
         };
         let config = crate::config::KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
-        let result = search_cache(options, &config);
+        let result = search_cache(options, &config, &TimingCollector::disabled());
         assert!(result.is_ok());
 
         unsafe {
@@ -958,6 +1515,7 @@ mod tests {
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://example.com/sap-download".to_string()),
             checksum: None,
             checksum_type: Some(ChecksumType::Sha256),
@@ -966,6 +1524,7 @@ mod tests {
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: None,
+            release_date: None,
         };
 
         let dist = DistributionCache {
@@ -982,7 +1541,10 @@ mod tests {
         let cache_path = cache_paths::metadata_cache_file(temp_dir.path());
         let config = crate::config::KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
         cache
-            .save(&cache_path, config.locking.timeout_value())
+            .save(
+                &cache_path,
+                config.locking.timeout_for(&LockScope::CacheWriter).value,
+            )
             .unwrap();
 
         // Test searching with the synonym "sapmachine"
@@ -991,11 +1553,13 @@ mod tests {
             compact: false,
             detailed: false,
             json: true,
+            release_date: false,
             lts_only: false,
             force_java_version: false,
             force_distribution_version: false,
+            filters: SearchFilterArgs::default(),
         };
-        let result = search_cache(options, &config);
+        let result = search_cache(options, &config, &TimingCollector::disabled());
         assert!(result.is_ok(), "Search should succeed with synonym");
 
         unsafe {
@@ -1023,8 +1587,12 @@ mod tests {
         std::fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
         std::fs::write(
             &cache_path,
-            r#"{"version":3,"last_updated":"2024-01-01T00:00:00Z","distributions":{},"synonym_map":{}}"#,
-        ).unwrap();
+            format!(
+                r#"{{"version":{},"last_updated":"2024-01-01T00:00:00Z","distributions":{{}},"synonym_map":{{}}}}"#,
+                crate::cache::CACHE_FORMAT_VERSION
+            ),
+        )
+        .unwrap();
 
         // Verify the function runs without panicking
         let result = show_cache_info(&config, false);
@@ -1052,4 +1620,51 @@ mod tests {
         silent_progress.start(config);
         silent_progress.complete(None);
     }
+
+    #[test]
+    fn test_installed_distributions_are_unique_and_sorted() {
+        use crate::version::Version;
+        use std::path::PathBuf;
+
+        let jdk = |dist: &str, version: &str| {
+            InstalledJdk::new(
+                dist.to_string(),
+                Version::from_str(version).unwrap(),
+                PathBuf::from(format!("/jdks/{dist}-{version}")),
+                false,
+            )
+        };
+        let installed = vec![
+            jdk("temurin", "21.0.1"),
+            jdk("corretto", "17.0.9"),
+            jdk("temurin", "17.0.9"),
+        ];
+
+        assert_eq!(
+            installed_distributions(&installed),
+            vec!["corretto".to_string(), "temurin".to_string()]
+        );
+        assert!(installed_distributions(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_canonical_distribution_names_resolves_synonyms() {
+        let mut cache = cache::MetadataCache::new();
+        cache
+            .synonym_map
+            .insert("sap_machine".to_string(), "sap_machine".to_string());
+        cache
+            .synonym_map
+            .insert("sapmachine".to_string(), "sap_machine".to_string());
+
+        let names = vec![
+            "Temurin".to_string(),
+            "sapmachine".to_string(),
+            "temurin".to_string(),
+        ];
+        assert_eq!(
+            canonical_distribution_names(&names, &cache),
+            vec!["temurin".to_string(), "sap_machine".to_string()]
+        );
+    }
 }
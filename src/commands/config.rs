@@ -0,0 +1,404 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::{
+    ConfigEntry, ConfigLayer, KopiConfig, find_project_config, is_project_key, system_config_path,
+};
+use crate::error::{KopiError, Result};
+use crate::locking::{LockController, LockScope};
+use crate::schema::SchemaDocument;
+use crate::version::resolver::PROJECT_CONFIG_FILE;
+use clap::{Args, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table};
+
+const MAX_ALIGN_WIDTH: usize = 72;
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the effective configuration after all layers are merged
+    Show {
+        /// Also print the layer (default, system, user, project, environment or
+        /// command line) that set each value
+        #[arg(long)]
+        origin: bool,
+    },
+
+    /// Print the effective value of a key, e.g. `auto_install.enabled`
+    Get {
+        /// Dotted configuration key, or a section to print all of its keys
+        key: String,
+    },
+
+    /// Write a key to a configuration file, keeping its comments and layout
+    Set {
+        /// Dotted configuration key, e.g. `auto_install.enabled`
+        key: String,
+        /// Value in TOML syntax (`true`, `30`, `["man", "demo"]`); bare words are strings
+        value: String,
+        #[command(flatten)]
+        target: ConfigTarget,
+    },
+
+    /// Remove a key from a configuration file so lower layers apply again
+    Unset {
+        /// Dotted configuration key, e.g. `auto_install.enabled`
+        key: String,
+        #[command(flatten)]
+        target: ConfigTarget,
+    },
+
+    /// Print the JSON Schema of `config.toml` and `.kopi.toml`, for editors
+    /// and CI validation
+    Schema,
+}
+
+/// Which file `kopi config set`/`unset` edits; the user config by default
+#[derive(Args)]
+pub struct ConfigTarget {
+    /// Edit the nearest .kopi.toml instead (created in the current directory if none exists)
+    #[arg(long, conflicts_with = "system")]
+    project: bool,
+    /// Edit the system-wide configuration file instead
+    #[arg(long)]
+    system: bool,
+}
+
+impl ConfigTarget {
+    fn path(&self, config: &KopiConfig) -> Result<PathBuf> {
+        if self.system {
+            return Ok(system_config_path());
+        }
+        if self.project {
+            let current_dir = std::env::current_dir().map_err(|e| {
+                KopiError::SystemError(format!("Failed to get current directory: {e}"))
+            })?;
+            return Ok(find_project_config(&current_dir)
+                .unwrap_or_else(|| current_dir.join(PROJECT_CONFIG_FILE)));
+        }
+        Ok(config.config_path())
+    }
+}
+
+impl ConfigCommand {
+    pub fn execute(&self, config: &KopiConfig) -> Result<()> {
+        match self {
+            ConfigCommand::Show { origin } => {
+                for line in render_entries(&config.entries()?, *origin) {
+                    println!("{line}");
+                }
+                Ok(())
+            }
+            ConfigCommand::Get { key } => {
+                let entries = config.entries()?;
+                let matching: Vec<&ConfigEntry> = entries
+                    .iter()
+                    .filter(|entry| entry.key == *key || entry.key.starts_with(&format!("{key}.")))
+                    .collect();
+                match matching.as_slice() {
+                    [] => Err(KopiError::InvalidConfig(format!(
+                        "Unknown configuration key '{key}'. Run 'kopi config show' to list the \
+                         available keys."
+                    ))),
+                    [entry] if entry.key == *key => {
+                        println!("{}", entry.value);
+                        Ok(())
+                    }
+                    entries => {
+                        for entry in entries {
+                            println!("{} = {}", entry.key, entry.value);
+                        }
+                        Ok(())
+                    }
+                }
+            }
+            ConfigCommand::Set { key, value, target } => {
+                if target.project && !is_project_key(key) {
+                    return Err(KopiError::InvalidConfig(format!(
+                        "'{key}' cannot be set in .kopi.toml; only default_distribution, pin.* \
+                         and tools.* are read from project files"
+                    )));
+                }
+                let path = target.path(config)?;
+                with_config_lock(config, || set_value(&path, key, value))?;
+                println!("Set {key} in {}", path.display());
+                warn_if_overridden(config, key, &path);
+                Ok(())
+            }
+            ConfigCommand::Unset { key, target } => {
+                let path = target.path(config)?;
+                if with_config_lock(config, || unset_value(&path, key))? {
+                    println!("Removed {key} from {}", path.display());
+                    warn_if_overridden(config, key, &path);
+                } else {
+                    println!("{key} is not set in {}", path.display());
+                }
+                Ok(())
+            }
+            ConfigCommand::Schema => {
+                let schema = SchemaDocument::Config.schema();
+                println!("{}", serde_json::to_string_pretty(&schema)?);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Run `edit` while holding the global configuration lock
+fn with_config_lock<T>(config: &KopiConfig, edit: impl FnOnce() -> Result<T>) -> Result<T> {
+    let controller =
+        LockController::with_default_inspector(config.kopi_home().to_path_buf(), &config.locking);
+    let acquisition = controller.acquire(LockScope::GlobalConfig)?;
+    let result = edit();
+    controller.release(acquisition)?;
+    result
+}
+
+/// Set `key` in the file at `path`, creating the file if needed.
+///
+/// The raw value is read as TOML when possible; if the schema rejects that
+/// reading (e.g. `21` for a string setting), it is retried as a string.
+fn set_value(path: &Path, key: &str, raw: &str) -> Result<()> {
+    let document = read_document(path)?;
+    let mut candidates: Vec<toml_edit::Value> = Vec::new();
+    if let Ok(mut parsed) = raw.parse::<toml_edit::Value>() {
+        parsed.decor_mut().clear();
+        candidates.push(parsed);
+    }
+    if !candidates.iter().any(toml_edit::Value::is_str) {
+        candidates.push(raw.into());
+    }
+
+    let mut first_error = None;
+    for candidate in candidates {
+        let mut edited = document.clone();
+        let item = item_mut(&mut edited, key)?;
+        let mut candidate = candidate;
+        // Keep an end-of-line comment on the value being replaced
+        if let Some(existing) = item.as_value() {
+            *candidate.decor_mut() = existing.decor().clone();
+        }
+        *item = Item::Value(candidate);
+        let content = edited.to_string();
+        match KopiConfig::validate_file_content(&content, key) {
+            Ok(()) => return write_document(path, &content),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(first_error.expect("at least one candidate value"))
+}
+
+/// Remove `key` from the file at `path`; returns whether it was present
+fn unset_value(path: &Path, key: &str) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    let mut document = read_document(path)?;
+    let (parent, leaf) = match key.rsplit_once('.') {
+        Some((parent, leaf)) => (Some(parent), leaf),
+        None => (None, key),
+    };
+
+    let table = match parent {
+        None => Some(document.as_table_mut() as &mut dyn toml_edit::TableLike),
+        Some(parent) => parent
+            .split('.')
+            .try_fold(document.as_item_mut(), |item, segment| {
+                item.get_mut(segment)
+            })
+            .and_then(Item::as_table_like_mut),
+    };
+    let removed = table.and_then(|table| table.remove(leaf)).is_some();
+    if removed {
+        write_document(path, &document.to_string())?;
+    }
+    Ok(removed)
+}
+
+/// The item for a dotted key, creating intermediate tables as needed
+fn item_mut<'a>(document: &'a mut DocumentMut, key: &str) -> Result<&'a mut Item> {
+    let mut item = document.as_item_mut();
+    for segment in key.split('.') {
+        if segment.is_empty() {
+            return Err(KopiError::InvalidConfig(format!(
+                "Invalid configuration key '{key}'"
+            )));
+        }
+        if item.is_none() {
+            // New sections become `[section]` headers rather than inline tables
+            let mut table = Table::new();
+            table.set_implicit(true);
+            *item = Item::Table(table);
+        }
+        let Some(table) = item.as_table_like_mut() else {
+            return Err(KopiError::InvalidConfig(format!(
+                "Cannot set '{key}': a parent of it is not a table"
+            )));
+        };
+        item = table.entry(segment).or_insert(Item::None);
+    }
+    Ok(item)
+}
+
+fn read_document(path: &Path) -> Result<DocumentMut> {
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    fs::read_to_string(path)?
+        .parse::<DocumentMut>()
+        .map_err(|e| KopiError::ConfigError(format!("Failed to parse {}: {e}", path.display())))
+}
+
+fn write_document(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    log::debug!("Wrote configuration to {path:?}");
+    Ok(())
+}
+
+/// Tell the user when a higher layer still decides the effective value
+fn warn_if_overridden(config: &KopiConfig, key: &str, path: &Path) {
+    let Ok(reloaded) = KopiConfig::new(config.kopi_home().to_path_buf()) else {
+        return;
+    };
+    let Ok(entries) = reloaded.entries() else {
+        return;
+    };
+    let Some(entry) = entries.iter().find(|entry| entry.key == key) else {
+        return;
+    };
+    let decided_here = matches!(
+        &entry.origin,
+        ConfigLayer::System(origin) | ConfigLayer::User(origin) | ConfigLayer::Project(origin)
+            if origin == path
+    );
+    if !decided_here && entry.origin != ConfigLayer::Default {
+        eprintln!(
+            "Note: the effective value of {key} is {} from {}",
+            entry.value, entry.origin
+        );
+    }
+}
+
+/// `key = value` lines, with the origin as an aligned TOML comment when requested
+fn render_entries(entries: &[ConfigEntry], origin: bool) -> Vec<String> {
+    let assignments: Vec<String> = entries
+        .iter()
+        .map(|entry| format!("{} = {}", entry.key, entry.value))
+        .collect();
+    if !origin {
+        return assignments;
+    }
+
+    // Very long values (metadata sources) are left out of the column alignment
+    let width = assignments
+        .iter()
+        .map(String::len)
+        .filter(|len| *len <= MAX_ALIGN_WIDTH)
+        .max()
+        .unwrap_or(0);
+    assignments
+        .into_iter()
+        .zip(entries)
+        .map(|(assignment, entry)| format!("{assignment:<width$}  # {}", entry.origin))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_and_unset_preserve_comments() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "# team settings\ndefault_distribution = \"zulu\" # agreed default\n",
+        )
+        .unwrap();
+
+        set_value(&path, "auto_install.enabled", "false").unwrap();
+        // default_distribution is a string setting, so `21` is stored as "21"
+        set_value(&path, "default_distribution", "21").unwrap();
+        set_value(&path, "locking.timeout", "infinite").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# team settings\ndefault_distribution = \"21\" # agreed default\n\n\
+             [auto_install]\nenabled = false\n\n[locking]\ntimeout = \"infinite\"\n"
+        );
+
+        assert!(unset_value(&path, "locking.timeout").unwrap());
+        assert!(!unset_value(&path, "locking.timeout").unwrap());
+        assert!(!fs::read_to_string(&path).unwrap().contains("timeout"));
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+
+        assert!(matches!(
+            set_value(&path, "auto_install.enabld", "true"),
+            Err(KopiError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            set_value(&path, "auto_install.timeout_secs", "soon"),
+            Err(KopiError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            set_value(&path, "install.strip", "[\"../etc\"]"),
+            Err(KopiError::InvalidConfig(_))
+        ));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_render_entries_with_origin() {
+        let entries = vec![
+            ConfigEntry {
+                key: "auto_install.enabled".to_string(),
+                value: "true".to_string(),
+                origin: ConfigLayer::Default,
+            },
+            ConfigEntry {
+                key: "default_distribution".to_string(),
+                value: "\"zulu\"".to_string(),
+                origin: ConfigLayer::Project(PathBuf::from("/work/app/.kopi.toml")),
+            },
+        ];
+
+        assert_eq!(
+            render_entries(&entries, false),
+            vec![
+                "auto_install.enabled = true",
+                "default_distribution = \"zulu\""
+            ]
+        );
+        assert_eq!(
+            render_entries(&entries, true),
+            vec![
+                "auto_install.enabled = true    # default",
+                "default_distribution = \"zulu\"  # project (/work/app/.kopi.toml)"
+            ]
+        );
+    }
+}
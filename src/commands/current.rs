@@ -14,7 +14,9 @@
 
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
+use crate::shim::{external, shadowing};
 use crate::storage::JdkRepository;
+use crate::version::VersionRequest;
 use crate::version::resolver::{VersionResolver, VersionSource};
 use serde::Serialize;
 use std::path::PathBuf;
@@ -27,6 +29,8 @@ struct CurrentOutput {
     installed: bool,
     installation_path: Option<String>,
     distribution: Option<String>,
+    /// The version spec requires a build with JavaFX bundled (`+fx`)
+    javafx_bundled: bool,
 }
 
 pub struct CurrentCommand<'a> {
@@ -38,7 +42,7 @@ impl<'a> CurrentCommand<'a> {
         Ok(Self { config })
     }
 
-    pub fn execute(&self, quiet: bool, json: bool) -> Result<()> {
+    pub fn execute(&self, quiet: bool, json: bool, verbose: bool) -> Result<()> {
         // Create version resolver
         let resolver = VersionResolver::new(self.config);
 
@@ -46,21 +50,8 @@ impl<'a> CurrentCommand<'a> {
         let (version_request, source) = match resolver.resolve_version() {
             Ok(result) => result,
             Err(KopiError::NoLocalVersion { searched_paths }) => {
-                if json {
-                    let output = serde_json::json!({
-                        "error": "no_version_configured",
-                        "message": "No JDK version configured",
-                        "searched_paths": searched_paths,
-                        "hints": [
-                            "Use 'kopi local <version>' to set a project version",
-                            "Use 'kopi global <version>' to set a default"
-                        ]
-                    });
-                    println!("{}", serde_json::to_string_pretty(&output)?);
-                } else if quiet {
-                    // In quiet mode, output nothing on error
-                    return Err(KopiError::NoLocalVersion { searched_paths });
-                } else {
+                // JSON and quiet runs leave reporting to the caller
+                if !json && !quiet {
                     eprintln!("No JDK version configured");
                     eprintln!("Hint: Use 'kopi local <version>' to set a project version");
                     eprintln!("      or 'kopi global <version>' to set a default");
@@ -77,14 +68,30 @@ impl<'a> CurrentCommand<'a> {
         let mut install_path = None;
         let mut is_installed = false;
 
-        // Get matching JDKs and use the last one from the results
-        if let Ok(matching_jdks) = repository.find_matching_jdks(&version_request)
+        // The system JDK is whichever java the shims would fall through to
+        if version_request.is_system() {
+            install_path = external::system_java_home(self.config.kopi_home());
+            is_installed = install_path.is_some();
+        } else if let Ok(matching_jdks) = repository.find_matching_jdks(&version_request)
             && let Some(jdk) = matching_jdks.last()
         {
             install_path = Some(jdk.path.clone());
             is_installed = true;
         }
 
+        // A `+fx` spec is never satisfied by a build without JavaFX, so point
+        // out one that is installed rather than leaving the user puzzled
+        let non_javafx = if !is_installed && version_request.javafx_bundled == Some(true) {
+            let mut request = version_request.clone();
+            request.javafx_bundled = None;
+            repository
+                .find_matching_jdks(&request)
+                .ok()
+                .and_then(|mut jdks| jdks.pop())
+        } else {
+            None
+        };
+
         // Format and display output
         if json {
             print_json_output(&version_request, &source, is_installed, &install_path)?;
@@ -92,6 +99,18 @@ impl<'a> CurrentCommand<'a> {
             println!("{}", version_request.version_pattern);
         } else {
             print_standard_output(&version_request, &source, is_installed)?;
+            if let Some(jdk) = non_javafx {
+                eprintln!(
+                    "Note: {}@{} is installed but does not bundle JavaFX, which {} requires",
+                    jdk.distribution,
+                    jdk.version,
+                    format_version_request(&version_request)
+                );
+            }
+            if verbose {
+                print_resolution_chain(&resolver.resolve_all_sources()?);
+            }
+            shadowing::warn_once(self.config.kopi_home());
         }
 
         Ok(())
@@ -99,14 +118,15 @@ impl<'a> CurrentCommand<'a> {
 }
 
 fn print_json_output(
-    version_request: &crate::version::VersionRequest,
+    version_request: &VersionRequest,
     source: &VersionSource,
     is_installed: bool,
     install_path: &Option<PathBuf>,
 ) -> Result<()> {
     let (source_name, source_path) = match source {
+        VersionSource::Override(value) => ("KOPI_USE".to_string(), Some(value.clone())),
         VersionSource::Environment(value) => ("KOPI_JAVA_VERSION".to_string(), Some(value.clone())),
-        VersionSource::ProjectFile(path) => {
+        VersionSource::ProjectFile(path) | VersionSource::ToolOverride(path) => {
             let file_name = path
                 .file_name()
                 .map(|n| n.to_string_lossy().to_string())
@@ -126,6 +146,7 @@ fn print_json_output(
         installed: is_installed,
         installation_path: install_path.as_ref().map(|p| p.display().to_string()),
         distribution: version_request.distribution.clone(),
+        javafx_bundled: version_request.javafx_bundled == Some(true),
     };
 
     println!("{}", serde_json::to_string_pretty(&output)?);
@@ -133,13 +154,14 @@ fn print_json_output(
 }
 
 fn print_standard_output(
-    version_request: &crate::version::VersionRequest,
+    version_request: &VersionRequest,
     source: &VersionSource,
     is_installed: bool,
 ) -> Result<()> {
     let source_display = match source {
+        VersionSource::Override(_) => "set by KOPI_USE".to_string(),
         VersionSource::Environment(_) => "set by KOPI_JAVA_VERSION".to_string(),
-        VersionSource::ProjectFile(path) => {
+        VersionSource::ProjectFile(path) | VersionSource::ToolOverride(path) => {
             // Try to make the path relative to current directory for better readability
             let display_path = if let Ok(current_dir) = std::env::current_dir() {
                 path.strip_prefix(&current_dir)
@@ -153,14 +175,13 @@ fn print_standard_output(
         VersionSource::GlobalDefault(_) => "set by global default".to_string(),
     };
 
-    let version_display = if let Some(dist) = &version_request.distribution {
-        format!("{dist}@{}", version_request.version_pattern)
-    } else {
-        version_request.version_pattern.clone()
-    };
+    let version_display = format_version_request(version_request);
 
     if is_installed {
         println!("{version_display} ({source_display})");
+    } else if version_request.is_system() {
+        println!("{version_display} ({source_display}) [NOT FOUND]");
+        eprintln!("Warning: no java found on PATH outside the kopi shims directory");
     } else {
         println!("{version_display} ({source_display}) [NOT INSTALLED]");
         eprintln!("Warning: JDK version {version_display} is configured but not installed");
@@ -170,6 +191,63 @@ fn print_standard_output(
     Ok(())
 }
 
+fn print_resolution_chain(sources: &[(VersionRequest, VersionSource)]) {
+    println!();
+    println!("Resolution order (highest precedence first):");
+
+    let override_use = sources
+        .iter()
+        .find(|(_, source)| matches!(source, VersionSource::Override(_)));
+    let environment = sources
+        .iter()
+        .find(|(_, source)| matches!(source, VersionSource::Environment(_)));
+    let project = sources
+        .iter()
+        .find(|(_, source)| matches!(source, VersionSource::ProjectFile(_)));
+    let global = sources
+        .iter()
+        .find(|(_, source)| matches!(source, VersionSource::GlobalDefault(_)));
+
+    let active = sources.first().map(|(_, source)| source);
+    let layers = [
+        ("KOPI_USE", override_use),
+        ("KOPI_JAVA_VERSION", environment),
+        ("project file", project),
+        ("global default", global),
+    ];
+
+    for (index, (label, entry)) in layers.iter().enumerate() {
+        let detail = match entry {
+            Some((version_request, source)) => {
+                let location = match source {
+                    VersionSource::Override(_) | VersionSource::Environment(_) => String::new(),
+                    VersionSource::ToolOverride(path)
+                    | VersionSource::ProjectFile(path)
+                    | VersionSource::GlobalDefault(path) => {
+                        format!(" ({})", path.display())
+                    }
+                };
+                let status = if Some(source) == active {
+                    "active"
+                } else {
+                    "overridden"
+                };
+                format!(
+                    "{}{location} [{status}]",
+                    format_version_request(version_request)
+                )
+            }
+            None => "not set".to_string(),
+        };
+        println!("  {}. {label:<18} {detail}", index + 1);
+    }
+}
+
+/// `distribution@version`, with `+fx` when JavaFX is required
+fn format_version_request(version_request: &VersionRequest) -> String {
+    version_request.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
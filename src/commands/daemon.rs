@@ -0,0 +1,48 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::KopiConfig;
+use crate::daemon::{DaemonClient, DaemonServer};
+use crate::error::Result;
+
+pub struct DaemonCommand<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> DaemonCommand<'a> {
+    pub fn new(config: &'a KopiConfig) -> Result<Self> {
+        Ok(Self { config })
+    }
+
+    /// Run the daemon in the foreground, or ask a running one to stop
+    pub fn execute(&self, stop: bool) -> Result<()> {
+        if stop {
+            match DaemonClient::connect(self.config.kopi_home()) {
+                Some(mut client) => {
+                    client.shutdown()?;
+                    println!("Stopped kopi daemon");
+                }
+                None => println!("No kopi daemon is running"),
+            }
+            return Ok(());
+        }
+
+        let server = DaemonServer::new(self.config.clone());
+        println!(
+            "kopi daemon listening on {}",
+            server.socket_path().display()
+        );
+        server.run()
+    }
+}
@@ -16,15 +16,41 @@ use crate::config::KopiConfig;
 use crate::doctor::formatters::{format_human_readable, format_json};
 use crate::doctor::{CheckCategory, DiagnosticEngine, DiagnosticSummary};
 use crate::error::Result;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 pub struct DoctorCommand<'a> {
     config: &'a KopiConfig,
+    deep: bool,
+    trace: Option<String>,
+    timeout: Option<Duration>,
 }
 
 impl<'a> DoctorCommand<'a> {
     pub fn new(config: &'a KopiConfig) -> Result<Self> {
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            deep: false,
+            trace: None,
+            timeout: None,
+        })
+    }
+
+    /// Include the deep JDK integrity scan
+    pub fn with_deep(mut self, deep: bool) -> Self {
+        self.deep = deep;
+        self
+    }
+
+    /// Tool to run through its shim in the `shims` category
+    pub fn with_trace(mut self, tool: Option<String>) -> Self {
+        self.trace = tool;
+        self
+    }
+
+    /// Per-check timeout overriding the engine default
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
     }
 
     pub fn execute(&self, json: bool, verbose: bool, check: Option<&str>) -> Result<()> {
@@ -37,7 +63,8 @@ impl<'a> DoctorCommand<'a> {
                 None => {
                     eprintln!("Invalid check category: {category_str}");
                     eprintln!(
-                        "Valid categories: installation, shell, jdks, permissions, network, cache"
+                        "Valid categories: installation, shell, jdks, permissions, network, \
+                         cache, shims"
                     );
                     return Err(crate::error::KopiError::InvalidConfig(format!(
                         "Invalid check category: {category_str}"
@@ -47,9 +74,19 @@ impl<'a> DoctorCommand<'a> {
         } else {
             None
         };
+        if self.trace.is_some() && categories != Some(vec![CheckCategory::Shims]) {
+            return Err(crate::error::KopiError::InvalidConfig(
+                "--trace only applies to '--check shims'".to_string(),
+            ));
+        }
 
         // Create diagnostic engine with config - all checks are initialized internally
-        let engine = DiagnosticEngine::new(self.config);
+        let mut engine = DiagnosticEngine::new(self.config)
+            .with_deep(self.deep)
+            .with_trace(self.trace.clone());
+        if let Some(timeout) = self.timeout {
+            engine = engine.with_check_timeout(timeout);
+        }
 
         // Run checks with progress display (only when not in JSON mode)
         let results = engine.run_checks(categories, !json);
@@ -89,4 +126,18 @@ mod tests {
         let result = command.execute(false, false, Some("invalid_category"));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_trace_requires_shims_category() {
+        let config = KopiConfig::new(PathBuf::from("/tmp/test")).unwrap();
+        let command = DoctorCommand::new(&config)
+            .unwrap()
+            .with_trace(Some("javac".to_string()));
+
+        let result = command.execute(false, false, Some("jdks"));
+        assert!(matches!(
+            result,
+            Err(crate::error::KopiError::InvalidConfig(_))
+        ));
+    }
 }
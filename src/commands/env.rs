@@ -14,24 +14,256 @@
 
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
-use crate::platform::shell::{Shell, detect_shell, parse_shell_name};
-use crate::storage::JdkRepository;
+use crate::paths::{home, install, shims};
+use crate::platform::shell::{Shell, detect_shell, find_shell_in_path, parse_shell_name};
+use crate::platform::wsl;
+use crate::shim::java_homes::java_home_variables;
+use crate::shim::{external, shadowing};
+use crate::storage::{InstalledJdk, JdkRepository};
 use crate::version::VersionRequest;
 use crate::version::resolver::{VersionResolver, VersionSource};
+use std::env;
+use std::ffi::OsString;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 pub struct EnvCommand<'a> {
     config: &'a KopiConfig,
+    wsl_interop: bool,
 }
 
 impl<'a> EnvCommand<'a> {
     pub fn new(config: &'a KopiConfig) -> Result<Self> {
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            wsl_interop: false,
+        })
+    }
+
+    /// Also emit the Windows form of `JAVA_HOME` when running under WSL
+    pub fn with_wsl_interop(mut self, enabled: bool) -> Self {
+        self.wsl_interop = enabled;
+        self
     }
 
     pub fn execute(&self, version: Option<&str>, shell: Option<&str>, export: bool) -> Result<()> {
-        // Resolve version
+        let java_home = self.resolve_java_home(version)?;
+        shadowing::warn_once(self.config.kopi_home());
+
+        // Detect or parse shell
+        let shell_type = resolve_shell(shell)?;
+
+        let output = self.format_script(shell_type, &java_home, export)?;
+        write_stdout(&output)
+    }
+
+    /// Evaluate the script `kopi env` prints in a real shell and check that
+    /// `JAVA_HOME` and the `java` on `PATH` both select the resolved JDK
+    pub fn validate(&self, version: Option<&str>, shell: Option<&str>) -> Result<EnvValidation> {
+        let java_home = self.resolve_java_home(version)?;
+        let shell_type = resolve_shell(shell)?;
+        let shell_path = find_shell_in_path(&shell_type)?;
+
+        let mut script = self.format_script(shell_type.clone(), &java_home, true)?;
+        script.push_str(probe_commands(&shell_type));
+        let output = run_in_shell(&shell_type, &shell_path, &script)?;
+
+        let shims_dir = shims::shims_root(self.config.kopi_home());
+        Ok(analyze_probe(
+            shell_type, shell_path, &java_home, &shims_dir, &output,
+        ))
+    }
+
+    /// `kopi env --validate`: print what the shell saw and fail on any discrepancy
+    pub fn execute_validate(&self, version: Option<&str>, shell: Option<&str>) -> Result<()> {
+        let validation = self.validate(version, shell)?;
+        let unset = || "(unset)".to_string();
+        let mut output = format!(
+            "Shell:      {} ({})\n",
+            validation.shell.get_shell_name(),
+            validation.shell_path.display()
+        );
+        output.push_str(&format!(
+            "Expected:   {}\n",
+            validation.expected_java_home.display()
+        ));
+        output.push_str(&format!(
+            "JAVA_HOME:  {}\n",
+            validation.java_home.clone().unwrap_or_else(unset)
+        ));
+        output.push_str(&format!(
+            "java:       {}\n",
+            validation
+                .java
+                .as_ref()
+                .map(|java| java.display().to_string())
+                .unwrap_or_else(unset)
+        ));
+        output.push_str(&format!(
+            "java.home:  {}\n",
+            validation
+                .runtime_java_home
+                .as_ref()
+                .map(|home| home.display().to_string())
+                .unwrap_or_else(unset)
+        ));
+        write_stdout(&output)?;
+
+        if validation.problems.is_empty() {
+            println!(
+                "OK: the {} environment selects the expected JDK",
+                validation.shell.get_shell_name()
+            );
+            return Ok(());
+        }
+        Err(KopiError::ValidationError(format!(
+            "The {} environment does not select {}:\n  - {}",
+            validation.shell.get_shell_name(),
+            validation.expected_java_home.display(),
+            validation.problems.join("\n  - ")
+        )))
+    }
+
+    /// The assignments `kopi env` prints for `java_home` in `shell_type`
+    fn format_script(&self, shell_type: Shell, java_home: &Path, export: bool) -> Result<String> {
+        let formatter = EnvFormatter::new(shell_type, export);
+        let mut output = formatter.format_env(java_home)?;
+        if self.wsl_interop {
+            output.push_str(&format_wsl_interop(&formatter, java_home)?);
+        }
+        Ok(output)
+    }
+
+    /// Print one `JAVA_HOME` variable per installed major version, named by
+    /// `env.java_home_template` (e.g. `JAVA_HOME_17_X64`), for CI matrix builds
+    pub fn execute_all_installed(&self, shell: Option<&str>, export: bool) -> Result<()> {
+        let formatter = EnvFormatter::new(resolve_shell(shell)?, export);
+        let mut output = String::new();
+        for (name, java_home) in java_home_variables(self.config)? {
+            output.push_str(&formatter.format_var(&name, &java_home.to_string_lossy())?);
+        }
+        write_stdout(&output)
+    }
+
+    /// Print a complete, self-contained environment for hermetic builds.
+    ///
+    /// `PATH` holds only the JDK `bin` directory followed by the system
+    /// directories from [`system_path_entries`], and the locale and time zone
+    /// are pinned, so the output is identical for every user on a machine and
+    /// can seed an `env -i` shell without leaking the caller's `PATH`.
+    pub fn execute_pure(
+        &self,
+        version: Option<&str>,
+        shell: Option<&str>,
+        export: bool,
+    ) -> Result<()> {
+        let java_home = self.resolve_java_home(version)?;
+        let formatter = EnvFormatter::new(resolve_shell(shell)?, export);
+
+        let mut output = formatter.format_env(&java_home)?;
+        for (name, value) in pure_variables() {
+            output.push_str(&formatter.format_var(name, &value)?);
+        }
+        output.push_str(&formatter.format_path(&pure_path(&java_home)?)?);
+
+        write_stdout(&output)
+    }
+
+    /// Quick path used by the `kopi init` shell hook on every directory change.
+    ///
+    /// Prints nothing when the shell's `JAVA_HOME` already points at the
+    /// resolved JDK, so the hook costs one version-file walk and one
+    /// directory listing. Missing JDKs produce a one-line warning rather than
+    /// an error so that `cd` never fails.
+    pub fn execute_changed_only(&self, shell: Option<&str>) -> Result<()> {
+        let target = match self.configured_jdk()? {
+            ConfiguredJdk::Installed(java_home) => Some(java_home),
+            ConfiguredJdk::Unconfigured => None,
+            ConfiguredJdk::Missing => return Ok(()),
+        };
+
+        let current = env::var_os("JAVA_HOME").map(PathBuf::from);
+        let jdks_dir = home::jdks_dir(self.config.kopi_home());
+        let Some(update) =
+            plan_hook_update(&jdks_dir, current, env::var_os("PATH"), target.as_deref())
+        else {
+            return Ok(());
+        };
+
+        let formatter = EnvFormatter::new(resolve_shell(shell)?, true);
+        let mut output = match &update.java_home {
+            Some(java_home) => formatter.format_env(java_home)?,
+            None => formatter.format_unset()?,
+        };
+        if let Some(path) = &update.path {
+            output.push_str(&formatter.format_path(path)?);
+        }
+
+        write_stdout(&output)
+    }
+
+    /// Print only the statements that move the live `JAVA_HOME` and `PATH` to
+    /// the target JDK: nothing when both already match, and unset statements
+    /// when leaving a project that no longer configures a version.
+    pub fn execute_diff(&self, version: Option<&str>, shell: Option<&str>) -> Result<()> {
+        let target = match version {
+            Some(ver) => Some(self.find_java_home(&ver.parse()?)?),
+            None => match self.configured_jdk()? {
+                ConfiguredJdk::Installed(java_home) => Some(java_home),
+                ConfiguredJdk::Unconfigured => None,
+                ConfiguredJdk::Missing => return Ok(()),
+            },
+        };
+
+        let jdks_dir = home::jdks_dir(self.config.kopi_home());
+        let diff = plan_env_diff(
+            &jdks_dir,
+            env::var_os("JAVA_HOME").map(PathBuf::from),
+            env::var_os("PATH"),
+            target.as_deref(),
+        );
+
+        let formatter = EnvFormatter::new(resolve_shell(shell)?, true);
+        let mut output = match &diff.java_home {
+            Some(JavaHomeChange::Set(java_home)) => formatter.format_env(java_home)?,
+            Some(JavaHomeChange::Unset) => formatter.format_unset()?,
+            None => String::new(),
+        };
+        if let Some(path) = &diff.path {
+            output.push_str(&formatter.format_path(path)?);
+        }
+
+        write_stdout(&output)
+    }
+
+    /// Resolve the configured version for shell hooks, warning on stderr
+    /// rather than failing when it is not installed
+    fn configured_jdk(&self) -> Result<ConfiguredJdk> {
+        match VersionResolver::new(self.config).resolve_version() {
+            // Stepping aside for the system JDK leaves the user's own environment alone
+            Ok((version_request, _source)) if version_request.is_system() => {
+                Ok(ConfiguredJdk::Unconfigured)
+            }
+            Ok((version_request, _source)) => match self.find_installed(&version_request) {
+                Ok(jdk) => Ok(ConfiguredJdk::Installed(jdk.resolve_java_home())),
+                Err(KopiError::JdkNotInstalled { jdk_spec, .. }) => {
+                    eprintln!(
+                        "kopi: {jdk_spec} is configured but not installed (run 'kopi install \
+                         {jdk_spec}')"
+                    );
+                    Ok(ConfiguredJdk::Missing)
+                }
+                Err(e) => Err(e),
+            },
+            Err(KopiError::NoLocalVersion { .. }) => Ok(ConfiguredJdk::Unconfigured),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// `JAVA_HOME` for an explicit version, or for the one the resolver picks
+    /// for this directory
+    fn resolve_java_home(&self, version: Option<&str>) -> Result<PathBuf> {
         let (version_request, _source) = if let Some(ver) = version {
             // Version explicitly provided
             let request = ver.parse::<VersionRequest>()?;
@@ -43,46 +275,422 @@ impl<'a> EnvCommand<'a> {
         };
 
         // Verify JDK is installed
+        self.find_java_home(&version_request)
+    }
+
+    /// `JAVA_HOME` of the installed JDK for `version_request`, or of the
+    /// system JDK on `PATH` for `system`
+    fn find_java_home(&self, version_request: &VersionRequest) -> Result<PathBuf> {
+        if version_request.is_system() {
+            return external::system_java_home(self.config.kopi_home()).ok_or_else(|| {
+                KopiError::NotFound(
+                    "No system JDK: java is not on PATH outside the kopi shims directory"
+                        .to_string(),
+                )
+            });
+        }
+        Ok(self.find_installed(version_request)?.resolve_java_home())
+    }
+
+    fn find_installed(&self, version_request: &VersionRequest) -> Result<InstalledJdk> {
         let repository = JdkRepository::new(self.config);
-        let matching_jdks = repository.find_matching_jdks(&version_request)?;
+        let mut matching_jdks = repository.find_matching_jdks(version_request)?;
 
-        let jdk = matching_jdks.last().ok_or_else(|| {
-            let version_display = if let Some(dist) = &version_request.distribution {
-                format!("{dist}@{}", version_request.version_pattern)
-            } else {
-                version_request.version_pattern.clone()
-            };
-            KopiError::JdkNotInstalled {
-                jdk_spec: version_display,
+        matching_jdks
+            .pop()
+            .ok_or_else(|| KopiError::JdkNotInstalled {
+                jdk_spec: version_request.to_string(),
                 version: Some(version_request.version_pattern.clone()),
                 distribution: version_request.distribution.clone(),
                 auto_install_enabled: false,
                 auto_install_failed: None,
                 user_declined: false,
                 install_in_progress: false,
-            }
-        })?;
+            })
+    }
+}
 
-        // Detect or parse shell
-        let shell_type = if let Some(shell_name) = shell {
-            parse_shell_name(shell_name)?
-        } else {
-            let (shell, _path) = detect_shell()?;
-            shell
-        };
+/// What a shell saw after evaluating the `kopi env` script
+#[derive(Debug)]
+pub struct EnvValidation {
+    pub shell: Shell,
+    pub shell_path: PathBuf,
+    pub expected_java_home: PathBuf,
+    /// `JAVA_HOME` after the script ran; `None` when empty or unset
+    pub java_home: Option<String>,
+    /// The `java` the shell found on `PATH`
+    pub java: Option<PathBuf>,
+    /// `java.home` reported by that `java`
+    pub runtime_java_home: Option<PathBuf>,
+    /// Discrepancies between what the shell saw and the expected JDK
+    pub problems: Vec<String>,
+}
 
-        // Format environment variables
-        let formatter = EnvFormatter::new(shell_type, export);
-        let java_home = jdk.resolve_java_home();
-        let output = formatter.format_env(&java_home)?;
+const PROBE_JAVA_HOME: &str = "KOPI_PROBE_JAVA_HOME=";
+const PROBE_JAVA: &str = "KOPI_PROBE_JAVA=";
+
+/// Commands appended to the script that report `JAVA_HOME`, the `java` on
+/// `PATH`, and the `java.home` that `java` runs with
+fn probe_commands(shell: &Shell) -> &'static str {
+    match shell {
+        Shell::Bash | Shell::Zsh | Shell::Unknown(_) => {
+            "printf '%s\\n' \"KOPI_PROBE_JAVA_HOME=$JAVA_HOME\" \"KOPI_PROBE_JAVA=$(command -v java)\"\n\
+             java -XshowSettings:properties -version 2>&1\n"
+        }
+        Shell::Fish => {
+            "printf '%s\\n' \"KOPI_PROBE_JAVA_HOME=$JAVA_HOME\" \"KOPI_PROBE_JAVA=\"(command -v java)\n\
+             java -XshowSettings:properties -version 2>&1\n"
+        }
+        Shell::PowerShell => {
+            "\"KOPI_PROBE_JAVA_HOME=$env:JAVA_HOME\"\n\
+             \"KOPI_PROBE_JAVA=$((Get-Command java -CommandType Application \
+             -ErrorAction SilentlyContinue | Select-Object -First 1).Source)\"\n\
+             java -XshowSettings:properties -version 2>&1 | ForEach-Object { \"$_\" }\n"
+        }
+        Shell::Cmd => {
+            "echo KOPI_PROBE_JAVA_HOME=%JAVA_HOME%\n\
+             for %%i in (java.exe) do echo KOPI_PROBE_JAVA=%%~$PATH:i\n\
+             java -XshowSettings:properties -version 2>&1\n"
+        }
+    }
+}
+
+/// Run `script` in a non-interactive `shell` that skips the user's startup
+/// files, returning its combined output
+fn run_in_shell(shell: &Shell, shell_path: &Path, script: &str) -> Result<String> {
+    let mut command = Command::new(shell_path);
+    // Cmd cannot run multi-line scripts from the command line
+    let _batch_dir;
+    match shell {
+        Shell::Bash => command.args(["--noprofile", "--norc", "-c", script]),
+        Shell::Zsh => command.args(["-f", "-c", script]),
+        Shell::Fish => command.args(["--no-config", "-c", script]),
+        Shell::PowerShell => command.args(["-NoProfile", "-NonInteractive", "-Command", script]),
+        Shell::Cmd => {
+            let dir = tempfile::tempdir()?;
+            let batch = dir.path().join("kopi-env-validate.cmd");
+            std::fs::write(
+                &batch,
+                format!("@echo off\r\n{}", script.replace('\n', "\r\n")),
+            )?;
+            command.args(["/D", "/C"]).arg(&batch);
+            _batch_dir = dir;
+            &mut command
+        }
+        Shell::Unknown(_) => command.args(["-c", script]),
+    };
+
+    let output = command.stdin(Stdio::null()).output().map_err(|e| {
+        KopiError::SystemError(format!("Failed to run {}: {e}", shell_path.display()))
+    })?;
+    let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(combined)
+}
+
+/// Compare the probe output of a shell against the JDK the script selects
+fn analyze_probe(
+    shell: Shell,
+    shell_path: PathBuf,
+    expected: &Path,
+    shims_dir: &Path,
+    output: &str,
+) -> EnvValidation {
+    let probe = |prefix: &str| {
+        output
+            .lines()
+            .find_map(|line| line.trim_end_matches('\r').strip_prefix(prefix))
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let java_home = probe(PROBE_JAVA_HOME);
+    let java = probe(PROBE_JAVA).map(PathBuf::from);
+    let runtime_java_home = output.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "java.home").then(|| PathBuf::from(value.trim()))
+    });
+
+    let mut problems = Vec::new();
+    let expected_str = expected.to_string_lossy();
+    match &java_home {
+        Some(value) if *value == expected_str => {}
+        Some(value) => problems.push(format!(
+            "JAVA_HOME is '{value}' after evaluating the script, expected '{expected_str}'; \
+             the path is not quoted correctly for {}",
+            shell.get_shell_name()
+        )),
+        None => problems.push(format!(
+            "JAVA_HOME is not set after evaluating the script in {}",
+            shell.get_shell_name()
+        )),
+    }
+
+    match (&java, &runtime_java_home) {
+        (None, _) => problems.push(format!(
+            "No java on PATH; add {} or {} to PATH",
+            shims_dir.display(),
+            install::bin_directory(expected).display()
+        )),
+        (Some(java), None) => problems.push(format!(
+            "{} did not report java.home: {}",
+            java.display(),
+            output
+                .lines()
+                .find(|line| !line.starts_with("KOPI_PROBE_"))
+                .unwrap_or("no output")
+                .trim()
+        )),
+        (Some(java), Some(runtime)) if !same_java_home(expected, runtime) => {
+            problems.push(if java.starts_with(shims_dir) {
+                format!(
+                    "The kopi shim {} runs {}; the version configured for this directory \
+                     differs from the one passed to kopi env",
+                    java.display(),
+                    runtime.display()
+                )
+            } else {
+                format!(
+                    "{} comes first on PATH and runs {}; move {} or {} before it",
+                    java.display(),
+                    runtime.display(),
+                    shims_dir.display(),
+                    install::bin_directory(expected).display()
+                )
+            })
+        }
+        _ => {}
+    }
+
+    EnvValidation {
+        shell,
+        shell_path,
+        expected_java_home: expected.to_path_buf(),
+        java_home,
+        java,
+        runtime_java_home,
+        problems,
+    }
+}
+
+/// Whether `runtime`, a JDK's `java.home`, is the JDK at `expected`. Java 8
+/// reports its bundled `jre` directory.
+fn same_java_home(expected: &Path, runtime: &Path) -> bool {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let runtime = canonical(runtime);
+    let expected = canonical(expected);
+    runtime == expected || runtime == expected.join("jre")
+}
+
+fn resolve_shell(shell: Option<&str>) -> Result<Shell> {
+    if let Some(shell_name) = shell {
+        parse_shell_name(shell_name)
+    } else {
+        let (shell, _path) = detect_shell()?;
+        Ok(shell)
+    }
+}
+
+/// Windows-side assignments for a JDK inside WSL: `JAVA_HOME_WINDOWS` holds
+/// the translated path and `WSLENV` gains `JAVA_HOME/p`, so Windows programs
+/// started from this shell receive a usable `JAVA_HOME`.
+fn format_wsl_interop(formatter: &EnvFormatter, java_home: &Path) -> Result<String> {
+    if !wsl::is_wsl() {
+        log::info!("Not running under WSL; --wsl-interop has no effect");
+        return Ok(String::new());
+    }
+
+    let windows_java_home = wsl::to_windows_path(java_home)?;
+    let wslenv = wsl::merge_wslenv(env::var("WSLENV").ok().as_deref(), "JAVA_HOME/p");
+
+    let mut output = formatter.format_var("JAVA_HOME_WINDOWS", &windows_java_home)?;
+    output.push_str(&formatter.format_var("WSLENV", &wslenv)?);
+    Ok(output)
+}
+
+/// Locale and time zone settings emitted by `kopi env --pure`, plus
+/// `SystemRoot` on Windows where many system programs fail without it
+fn pure_variables() -> Vec<(&'static str, String)> {
+    #[allow(unused_mut)]
+    let mut vars = vec![
+        ("LANG", "C".to_string()),
+        ("LC_ALL", "C".to_string()),
+        ("TZ", "UTC".to_string()),
+    ];
+    #[cfg(windows)]
+    vars.push((
+        "SystemRoot",
+        windows_system_root().to_string_lossy().into_owned(),
+    ));
+    vars
+}
+
+/// `PATH` for `kopi env --pure`: the JDK `bin` directory, then system directories
+fn pure_path(java_home: &Path) -> Result<OsString> {
+    let entries = std::iter::once(install::bin_directory(java_home)).chain(system_path_entries());
+    env::join_paths(entries).map_err(|e| {
+        KopiError::SystemError(format!(
+            "Cannot build PATH from '{}': {e}",
+            java_home.display()
+        ))
+    })
+}
+
+/// System directories every build may rely on, independent of the caller's `PATH`
+#[cfg(unix)]
+fn system_path_entries() -> Vec<PathBuf> {
+    ["/usr/bin", "/bin", "/usr/sbin", "/sbin"]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// System directories every build may rely on, independent of the caller's `PATH`
+#[cfg(windows)]
+fn system_path_entries() -> Vec<PathBuf> {
+    let root = windows_system_root();
+    vec![
+        root.join("System32"),
+        root.clone(),
+        root.join("System32").join("Wbem"),
+        root.join("System32").join("WindowsPowerShell").join("v1.0"),
+    ]
+}
 
-        // Output to stdout
-        let mut stdout = std::io::stdout();
-        stdout.write_all(output.as_bytes())?;
-        stdout.flush()?;
+#[cfg(windows)]
+fn windows_system_root() -> PathBuf {
+    env::var_os("SystemRoot")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(r"C:\Windows"))
+}
+
+fn escape_posix_double_quoted(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('`', "\\`")
+}
+
+fn write_stdout(output: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(output.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Environment changes the shell hook has to apply
+#[derive(Debug, PartialEq)]
+struct HookUpdate {
+    /// New `JAVA_HOME`, or `None` to unset it
+    java_home: Option<PathBuf>,
+    /// New `PATH` with the previous Kopi JDK `bin` swapped for the new one
+    path: Option<OsString>,
+}
 
-        Ok(())
+/// Work out what the hook must change to move from `current` to `target`.
+///
+/// Only a `JAVA_HOME` under Kopi's `jdks` directory is ever unset or removed
+/// from `PATH`; a user-managed `JAVA_HOME` is left alone when no version is
+/// configured.
+fn plan_hook_update(
+    jdks_dir: &Path,
+    current: Option<PathBuf>,
+    current_path: Option<OsString>,
+    target: Option<&Path>,
+) -> Option<HookUpdate> {
+    if current.as_deref() == target {
+        return None;
     }
+
+    let managed = current.filter(|java_home| java_home.starts_with(jdks_dir));
+    if target.is_none() && managed.is_none() {
+        return None;
+    }
+
+    let stale_bin = managed.as_deref().map(install::bin_directory);
+    let mut entries: Vec<PathBuf> = current_path
+        .as_deref()
+        .map(|path| env::split_paths(path).collect())
+        .unwrap_or_default();
+    entries.retain(|entry| Some(entry) != stale_bin.as_ref());
+    if let Some(target) = target {
+        entries.insert(0, install::bin_directory(target));
+    }
+
+    Some(HookUpdate {
+        java_home: target.map(Path::to_path_buf),
+        path: env::join_paths(entries).ok(),
+    })
+}
+
+/// Outcome of resolving the configured version for a shell hook
+enum ConfiguredJdk {
+    Installed(PathBuf),
+    /// No version file or global default applies
+    Unconfigured,
+    /// A version is configured but not installed; already reported on stderr
+    Missing,
+}
+
+#[derive(Debug, PartialEq)]
+enum JavaHomeChange {
+    Set(PathBuf),
+    Unset,
+}
+
+/// Minimal set of mutations reported by `kopi env --diff`
+#[derive(Debug, Default, PartialEq)]
+struct EnvDiff {
+    java_home: Option<JavaHomeChange>,
+    /// New `PATH`, present only when it differs from the live value
+    path: Option<OsString>,
+}
+
+/// Compare `JAVA_HOME` and `PATH` independently against the target JDK.
+///
+/// As with [`plan_hook_update`], only a Kopi-managed `JAVA_HOME` is ever unset
+/// or has its `bin` directory removed from `PATH`.
+fn plan_env_diff(
+    jdks_dir: &Path,
+    current: Option<PathBuf>,
+    current_path: Option<OsString>,
+    target: Option<&Path>,
+) -> EnvDiff {
+    let managed = current
+        .as_deref()
+        .filter(|java_home| java_home.starts_with(jdks_dir) && Some(*java_home) != target);
+
+    let java_home = match target {
+        Some(target) if current.as_deref() != Some(target) => {
+            Some(JavaHomeChange::Set(target.to_path_buf()))
+        }
+        None if managed.is_some() => Some(JavaHomeChange::Unset),
+        _ => None,
+    };
+
+    let original: Vec<PathBuf> = current_path
+        .as_deref()
+        .map(|path| env::split_paths(path).collect())
+        .unwrap_or_default();
+    let stale_bin = managed.map(install::bin_directory);
+    let target_bin = target.map(install::bin_directory);
+
+    let mut entries = original.clone();
+    entries.retain(|entry| Some(entry) != stale_bin.as_ref());
+    if let Some(target_bin) = target_bin
+        && entries.first() != Some(&target_bin)
+    {
+        entries.retain(|entry| *entry != target_bin);
+        entries.insert(0, target_bin);
+    }
+
+    let path = if entries == original {
+        None
+    } else {
+        env::join_paths(entries).ok()
+    };
+
+    EnvDiff { java_home, path }
 }
 
 struct EnvFormatter {
@@ -96,57 +704,102 @@ impl EnvFormatter {
     }
 
     fn format_env(&self, jdk_path: &Path) -> Result<String> {
-        let java_home = jdk_path.to_string_lossy();
+        self.format_var("JAVA_HOME", &jdk_path.to_string_lossy())
+    }
 
+    /// Assign `value` to the variable `name`
+    fn format_var(&self, name: &str, value: &str) -> Result<String> {
         match self.shell_type {
             Shell::Bash | Shell::Zsh => {
-                // Escape double quotes and backslashes in the path
-                let escaped_path = java_home.replace('\\', "\\\\").replace('"', "\\\"");
+                // Escape characters that stay special inside double quotes; `$`
+                // appears in legacy `\\wsl$\` paths
+                let escaped = escape_posix_double_quoted(value);
                 if self.export {
-                    Ok(format!("export JAVA_HOME=\"{escaped_path}\"\n"))
+                    Ok(format!("export {name}=\"{escaped}\"\n"))
                 } else {
-                    Ok(format!("JAVA_HOME=\"{escaped_path}\"\n"))
+                    Ok(format!("{name}=\"{escaped}\"\n"))
                 }
             }
             Shell::Fish => {
                 // Fish also needs quote escaping
-                let escaped_path = java_home.replace('\\', "\\\\").replace('"', "\\\"");
+                let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
                 if self.export {
-                    Ok(format!("set -gx JAVA_HOME \"{escaped_path}\"\n"))
+                    Ok(format!("set -gx {name} \"{escaped}\"\n"))
                 } else {
-                    Ok(format!("set -g JAVA_HOME \"{escaped_path}\"\n"))
+                    Ok(format!("set -g {name} \"{escaped}\"\n"))
                 }
             }
             Shell::PowerShell => {
                 // PowerShell uses backtick for escaping
-                let escaped_path = java_home.replace('"', "`\"");
-                Ok(format!("$env:JAVA_HOME = \"{escaped_path}\"\n"))
+                let escaped = value.replace('"', "`\"");
+                Ok(format!("$env:{name} = \"{escaped}\"\n"))
             }
             Shell::Cmd => {
                 // CMD is more complex - spaces and special chars need quotes
-                if java_home.contains(' ')
-                    || java_home.contains('&')
-                    || java_home.contains('(')
-                    || java_home.contains(')')
+                if value.contains(' ')
+                    || value.contains('&')
+                    || value.contains('(')
+                    || value.contains(')')
                 {
                     // Use quotes and escape internal quotes
-                    let escaped_path = java_home.replace('"', "\"\"");
-                    Ok(format!("set JAVA_HOME=\"{escaped_path}\"\n"))
+                    let escaped = value.replace('"', "\"\"");
+                    Ok(format!("set {name}=\"{escaped}\"\n"))
                 } else {
-                    Ok(format!("set JAVA_HOME={java_home}\n"))
+                    Ok(format!("set {name}={value}\n"))
                 }
             }
             Shell::Unknown(_) => {
                 // Default to bash-style export with escaping
-                let escaped_path = java_home.replace('\\', "\\\\").replace('"', "\\\"");
+                let escaped = escape_posix_double_quoted(value);
                 if self.export {
-                    Ok(format!("export JAVA_HOME=\"{escaped_path}\"\n"))
+                    Ok(format!("export {name}=\"{escaped}\"\n"))
                 } else {
-                    Ok(format!("JAVA_HOME=\"{escaped_path}\"\n"))
+                    Ok(format!("{name}=\"{escaped}\"\n"))
                 }
             }
         }
     }
+
+    /// Remove `JAVA_HOME` from the environment
+    fn format_unset(&self) -> Result<String> {
+        match self.shell_type {
+            Shell::Bash | Shell::Zsh | Shell::Unknown(_) => Ok("unset JAVA_HOME\n".to_string()),
+            Shell::Fish => Ok("set -e JAVA_HOME\n".to_string()),
+            Shell::PowerShell => {
+                Ok("Remove-Item Env:JAVA_HOME -ErrorAction SilentlyContinue\n".to_string())
+            }
+            Shell::Cmd => Ok("set JAVA_HOME=\n".to_string()),
+        }
+    }
+
+    /// Replace `PATH` with the given value
+    fn format_path(&self, path: &OsString) -> Result<String> {
+        match self.shell_type {
+            Shell::Bash | Shell::Zsh | Shell::Unknown(_) => {
+                let escaped = escape_posix_double_quoted(&path.to_string_lossy());
+                Ok(format!("export PATH=\"{escaped}\"\n"))
+            }
+            Shell::Fish => {
+                // Fish treats PATH as a list, one quoted element per directory
+                let entries: Vec<String> = env::split_paths(path)
+                    .map(|entry| {
+                        let value = entry.to_string_lossy();
+                        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+                    })
+                    .collect();
+                Ok(format!("set -gx PATH {}\n", entries.join(" ")))
+            }
+            Shell::PowerShell => {
+                let value = path.to_string_lossy();
+                let escaped = value
+                    .replace('`', "``")
+                    .replace('"', "`\"")
+                    .replace('$', "`$");
+                Ok(format!("$env:PATH = \"{escaped}\"\n"))
+            }
+            Shell::Cmd => Ok(format!("set PATH={}\n", path.to_string_lossy())),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -159,6 +812,185 @@ mod tests {
     use std::str::FromStr;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_plan_hook_update_skips_when_unchanged() {
+        let jdks = PathBuf::from("/home/user/.kopi/jdks");
+        let java_home = jdks.join("temurin-21");
+        assert_eq!(
+            plan_hook_update(
+                &jdks,
+                Some(java_home.clone()),
+                Some(OsString::from("/usr/bin")),
+                Some(&java_home)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_plan_hook_update_swaps_kopi_bin_directory() {
+        let jdks = PathBuf::from("/home/user/.kopi/jdks");
+        let old_home = jdks.join("temurin-17");
+        let new_home = jdks.join("temurin-21");
+        let path = env::join_paths([install::bin_directory(&old_home), PathBuf::from("/usr/bin")])
+            .unwrap();
+
+        let update = plan_hook_update(&jdks, Some(old_home), Some(path), Some(&new_home)).unwrap();
+        assert_eq!(update.java_home, Some(new_home.clone()));
+        let entries: Vec<PathBuf> = env::split_paths(&update.path.unwrap()).collect();
+        assert_eq!(
+            entries,
+            vec![install::bin_directory(&new_home), PathBuf::from("/usr/bin")]
+        );
+    }
+
+    #[test]
+    fn test_plan_hook_update_leaves_user_java_home_alone() {
+        let jdks = PathBuf::from("/home/user/.kopi/jdks");
+        let user_home = PathBuf::from("/opt/java");
+        assert_eq!(
+            plan_hook_update(
+                &jdks,
+                Some(user_home),
+                Some(OsString::from("/usr/bin")),
+                None
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_plan_hook_update_unsets_kopi_java_home() {
+        let jdks = PathBuf::from("/home/user/.kopi/jdks");
+        let old_home = jdks.join("temurin-17");
+        let path = env::join_paths([install::bin_directory(&old_home), PathBuf::from("/usr/bin")])
+            .unwrap();
+
+        let update = plan_hook_update(&jdks, Some(old_home), Some(path), None).unwrap();
+        assert_eq!(update.java_home, None);
+        assert_eq!(update.path, Some(OsString::from("/usr/bin")));
+    }
+
+    #[test]
+    fn test_plan_env_diff_is_empty_when_environment_matches() {
+        let jdks = PathBuf::from("/home/user/.kopi/jdks");
+        let java_home = jdks.join("temurin-21");
+        let path = env::join_paths([
+            install::bin_directory(&java_home),
+            PathBuf::from("/usr/bin"),
+        ])
+        .unwrap();
+
+        let diff = plan_env_diff(&jdks, Some(java_home.clone()), Some(path), Some(&java_home));
+        assert_eq!(diff, EnvDiff::default());
+    }
+
+    #[test]
+    fn test_plan_env_diff_only_fixes_path() {
+        let jdks = PathBuf::from("/home/user/.kopi/jdks");
+        let java_home = jdks.join("temurin-21");
+
+        let diff = plan_env_diff(
+            &jdks,
+            Some(java_home.clone()),
+            Some(OsString::from("/usr/bin")),
+            Some(&java_home),
+        );
+        assert_eq!(diff.java_home, None);
+        let entries: Vec<PathBuf> = env::split_paths(&diff.path.unwrap()).collect();
+        assert_eq!(
+            entries,
+            vec![
+                install::bin_directory(&java_home),
+                PathBuf::from("/usr/bin")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_env_diff_only_sets_java_home() {
+        let jdks = PathBuf::from("/home/user/.kopi/jdks");
+        let java_home = jdks.join("temurin-21");
+        let path = env::join_paths([
+            install::bin_directory(&java_home),
+            PathBuf::from("/usr/bin"),
+        ])
+        .unwrap();
+
+        let diff = plan_env_diff(&jdks, None, Some(path), Some(&java_home));
+        assert_eq!(diff.java_home, Some(JavaHomeChange::Set(java_home)));
+        assert_eq!(diff.path, None);
+    }
+
+    #[test]
+    fn test_plan_env_diff_unsets_when_leaving_project() {
+        let jdks = PathBuf::from("/home/user/.kopi/jdks");
+        let old_home = jdks.join("temurin-17");
+        let path = env::join_paths([install::bin_directory(&old_home), PathBuf::from("/usr/bin")])
+            .unwrap();
+
+        let diff = plan_env_diff(&jdks, Some(old_home), Some(path), None);
+        assert_eq!(diff.java_home, Some(JavaHomeChange::Unset));
+        assert_eq!(diff.path, Some(OsString::from("/usr/bin")));
+
+        // A user-managed JAVA_HOME is never touched
+        let diff = plan_env_diff(
+            &jdks,
+            Some(PathBuf::from("/opt/java")),
+            Some(OsString::from("/opt/java/bin:/usr/bin")),
+            None,
+        );
+        assert_eq!(diff, EnvDiff::default());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pure_path_ignores_caller_path() {
+        let java_home = PathBuf::from("/home/user/.kopi/jdks/temurin-21");
+        let path = pure_path(&java_home).unwrap();
+        assert_eq!(
+            path,
+            OsString::from(format!(
+                "{}:/usr/bin:/bin:/usr/sbin:/sbin",
+                install::bin_directory(&java_home).display()
+            ))
+        );
+
+        let formatter = EnvFormatter::new(Shell::Bash, true);
+        let vars: Vec<String> = pure_variables()
+            .into_iter()
+            .map(|(name, value)| formatter.format_var(name, &value).unwrap())
+            .collect();
+        assert_eq!(
+            vars.concat(),
+            "export LANG=\"C\"\nexport LC_ALL=\"C\"\nexport TZ=\"UTC\"\n"
+        );
+    }
+
+    #[test]
+    fn test_unset_and_path_formatters() {
+        let path = OsString::from("/opt/jdk/bin");
+        let bash = EnvFormatter::new(Shell::Bash, true);
+        assert_eq!(bash.format_unset().unwrap(), "unset JAVA_HOME\n");
+        assert_eq!(
+            bash.format_path(&path).unwrap(),
+            "export PATH=\"/opt/jdk/bin\"\n"
+        );
+
+        let fish = EnvFormatter::new(Shell::Fish, true);
+        assert_eq!(fish.format_unset().unwrap(), "set -e JAVA_HOME\n");
+        assert_eq!(
+            fish.format_path(&path).unwrap(),
+            "set -gx PATH \"/opt/jdk/bin\"\n"
+        );
+
+        let powershell = EnvFormatter::new(Shell::PowerShell, true);
+        assert_eq!(
+            powershell.format_path(&path).unwrap(),
+            "$env:PATH = \"/opt/jdk/bin\"\n"
+        );
+    }
+
     #[test]
     fn test_bash_formatter() {
         let formatter = EnvFormatter::new(Shell::Bash, true);
@@ -222,6 +1054,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bash_formatter_windows_path_variable() {
+        let formatter = EnvFormatter::new(Shell::Bash, true);
+        let output = formatter
+            .format_var("JAVA_HOME_WINDOWS", r"\\wsl$\Ubuntu\jdks\temurin-21")
+            .unwrap();
+        assert_eq!(
+            output,
+            "export JAVA_HOME_WINDOWS=\"\\\\\\\\wsl\\$\\\\Ubuntu\\\\jdks\\\\temurin-21\"\n"
+        );
+    }
+
     #[test]
     fn test_bash_formatter_with_quotes() {
         let formatter = EnvFormatter::new(Shell::Bash, true);
@@ -479,4 +1323,87 @@ mod tests {
                 .contains("bin directory not found")
         );
     }
+
+    fn probe_output(java_home: &str, java: &str, runtime: &str) -> String {
+        format!(
+            "KOPI_PROBE_JAVA_HOME={java_home}\nKOPI_PROBE_JAVA={java}\nProperty settings:\n    \
+             java.home = {runtime}\nopenjdk version \"21.0.1\"\n"
+        )
+    }
+
+    fn analyze(output: &str) -> EnvValidation {
+        analyze_probe(
+            Shell::Bash,
+            PathBuf::from("/bin/bash"),
+            Path::new("/home/user/.kopi/jdks/temurin-21"),
+            Path::new("/home/user/.kopi/shims"),
+            output,
+        )
+    }
+
+    #[test]
+    fn test_analyze_probe_accepts_matching_environment() {
+        let validation = analyze(&probe_output(
+            "/home/user/.kopi/jdks/temurin-21",
+            "/home/user/.kopi/shims/java",
+            "/home/user/.kopi/jdks/temurin-21",
+        ));
+        assert!(validation.problems.is_empty(), "{:?}", validation.problems);
+        assert_eq!(
+            validation.java,
+            Some(PathBuf::from("/home/user/.kopi/shims/java"))
+        );
+
+        // Java 8 reports its bundled JRE
+        let validation = analyze(&probe_output(
+            "/home/user/.kopi/jdks/temurin-21",
+            "/home/user/.kopi/shims/java",
+            "/home/user/.kopi/jdks/temurin-21/jre",
+        ));
+        assert!(validation.problems.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_probe_reports_quoting_bug() {
+        let validation = analyze(&probe_output(
+            "/home/user/.kopi/jdks/temurin",
+            "/home/user/.kopi/shims/java",
+            "/home/user/.kopi/jdks/temurin-21",
+        ));
+        assert_eq!(validation.problems.len(), 1);
+        assert!(validation.problems[0].contains("not quoted correctly for bash"));
+    }
+
+    #[test]
+    fn test_analyze_probe_reports_path_ordering() {
+        let validation = analyze(&probe_output(
+            "/home/user/.kopi/jdks/temurin-21",
+            "/usr/bin/java",
+            "/usr/lib/jvm/java-17",
+        ));
+        assert_eq!(validation.problems.len(), 1);
+        assert!(
+            validation.problems[0].starts_with("/usr/bin/java comes first on PATH"),
+            "{}",
+            validation.problems[0]
+        );
+
+        let validation = analyze(&probe_output(
+            "/home/user/.kopi/jdks/temurin-21",
+            "/home/user/.kopi/shims/java",
+            "/home/user/.kopi/jdks/temurin-17",
+        ));
+        assert!(validation.problems[0].starts_with("The kopi shim"));
+    }
+
+    #[test]
+    fn test_analyze_probe_reports_missing_java() {
+        let validation = analyze(
+            "KOPI_PROBE_JAVA_HOME=/home/user/.kopi/jdks/temurin-21\nKOPI_PROBE_JAVA=\n\
+             bash: java: command not found\n",
+        );
+        assert_eq!(validation.java, None);
+        assert_eq!(validation.problems.len(), 1);
+        assert!(validation.problems[0].starts_with("No java on PATH"));
+    }
 }
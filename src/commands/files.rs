@@ -0,0 +1,231 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::storage::formatting::format_size;
+use crate::storage::{InstallManifest, InstalledJdk, JdkRepository};
+use crate::version::VersionRequest;
+use serde::Serialize;
+use std::str::FromStr;
+
+#[derive(Serialize)]
+struct FilesOutput {
+    distribution: String,
+    version: String,
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<FileEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symlinks: Option<Vec<SymlinkEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    orphans: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct FileEntry {
+    path: String,
+    size: u64,
+    /// Octal permission bits such as `0755`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    sha256: String,
+}
+
+#[derive(Serialize)]
+struct SymlinkEntry {
+    path: String,
+    target: String,
+}
+
+/// Lists the files recorded when a JDK was installed
+pub struct FilesCommand<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> FilesCommand<'a> {
+    pub fn new(config: &'a KopiConfig) -> Result<Self> {
+        Ok(Self { config })
+    }
+
+    /// Print the install manifest of the JDK matching `version`, or with
+    /// `orphans` the files in its directory the manifest does not list
+    pub fn execute(&self, version: &str, orphans: bool, json: bool) -> Result<()> {
+        let repository = JdkRepository::new(self.config);
+        let jdk = find_single_jdk(&repository, version)?;
+        let manifest = repository.load_install_manifest(&jdk)?.ok_or_else(|| {
+            KopiError::NotFound(format!(
+                "No install manifest recorded for {}@{}; reinstall with 'kopi install {}@{} \
+                 --force' to record one",
+                jdk.distribution, jdk.version, jdk.distribution, jdk.version
+            ))
+        })?;
+
+        let mut output = FilesOutput {
+            distribution: jdk.distribution.clone(),
+            version: jdk.version.to_string(),
+            path: jdk.path.display().to_string(),
+            files: None,
+            symlinks: None,
+            orphans: None,
+        };
+        if orphans {
+            output.orphans = Some(manifest.orphans(&jdk.path));
+        } else {
+            let (files, symlinks) = manifest_entries(&manifest);
+            output.files = Some(files);
+            output.symlinks = Some(symlinks);
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            print_text(&output);
+        }
+        Ok(())
+    }
+}
+
+/// The one installed JDK matching `version`
+fn find_single_jdk(repository: &JdkRepository, version: &str) -> Result<InstalledJdk> {
+    let request = VersionRequest::from_str(version)?;
+    let mut matching = repository.find_matching_jdks(&request)?;
+    match matching.len() {
+        0 => Err(KopiError::JdkNotInstalled {
+            jdk_spec: request.to_string(),
+            version: Some(request.version_pattern.clone()),
+            distribution: request.distribution.clone(),
+            auto_install_enabled: false,
+            auto_install_failed: None,
+            user_declined: false,
+            install_in_progress: false,
+        }),
+        1 => Ok(matching.remove(0)),
+        _ => Err(KopiError::ValidationError(format!(
+            "Multiple JDKs match version '{}'\n\nFound:\n  {}\n\nPlease specify the full \
+             version or distribution",
+            request.version_pattern,
+            matching
+                .iter()
+                .map(|jdk| format!("{}@{}", jdk.distribution, jdk.version))
+                .collect::<Vec<_>>()
+                .join("\n  ")
+        ))),
+    }
+}
+
+fn manifest_entries(manifest: &InstallManifest) -> (Vec<FileEntry>, Vec<SymlinkEntry>) {
+    let files = manifest
+        .files
+        .iter()
+        .map(|(path, file)| FileEntry {
+            path: path.clone(),
+            size: file.size,
+            mode: file.mode.map(|mode| format!("{mode:04o}")),
+            sha256: file.sha256.clone(),
+        })
+        .collect();
+    let symlinks = manifest
+        .symlinks
+        .iter()
+        .map(|(path, target)| SymlinkEntry {
+            path: path.clone(),
+            target: target.clone(),
+        })
+        .collect();
+    (files, symlinks)
+}
+
+fn print_text(output: &FilesOutput) {
+    let jdk = format!("{}@{}", output.distribution, output.version);
+    if let Some(orphans) = &output.orphans {
+        if orphans.is_empty() {
+            println!("No orphaned files in {jdk} ({})", output.path);
+        } else {
+            println!(
+                "{} files in {} are not part of the {jdk} installation:",
+                orphans.len(),
+                output.path
+            );
+            for orphan in orphans {
+                println!("  {orphan}");
+            }
+        }
+        return;
+    }
+
+    let files = output.files.as_deref().unwrap_or_default();
+    let symlinks = output.symlinks.as_deref().unwrap_or_default();
+    let total: u64 = files.iter().map(|file| file.size).sum();
+    println!(
+        "{jdk} ({}): {} files, {} symlinks, {}",
+        output.path,
+        files.len(),
+        symlinks.len(),
+        format_size(total)
+    );
+    for file in files {
+        println!(
+            "{:>4}  {:>10}  {}",
+            file.mode.as_deref().unwrap_or("-"),
+            format_size(file.size),
+            file.path
+        );
+    }
+    for link in symlinks {
+        println!(
+            "{:>4}  {:>10}  {} -> {}",
+            "link", "-", link.path, link.target
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::install;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_single_jdk_and_manifest_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let jdks_dir = config.jdks_dir().unwrap();
+        let jdk_path = jdks_dir.join("temurin-21.0.5");
+        std::fs::create_dir_all(jdk_path.join("bin")).unwrap();
+        std::fs::write(jdk_path.join("bin/java"), b"java").unwrap();
+        std::fs::create_dir_all(jdks_dir.join("corretto-21.0.7.6.1")).unwrap();
+
+        let repository = JdkRepository::new(&config);
+        assert!(matches!(
+            find_single_jdk(&repository, "21"),
+            Err(KopiError::ValidationError(_))
+        ));
+        assert!(matches!(
+            find_single_jdk(&repository, "11"),
+            Err(KopiError::JdkNotInstalled { .. })
+        ));
+        let jdk = find_single_jdk(&repository, "temurin@21").unwrap();
+        assert_eq!(jdk.path, jdk_path);
+        assert!(repository.load_install_manifest(&jdk).unwrap().is_none());
+
+        repository.save_install_manifest(&jdk_path).unwrap();
+        assert!(install::manifest_file(config.kopi_home(), "temurin-21.0.5").exists());
+        let manifest = repository.load_install_manifest(&jdk).unwrap().unwrap();
+        let (files, symlinks) = manifest_entries(&manifest);
+        assert_eq!(files.len(), 1);
+        assert_eq!((files[0].path.as_str(), files[0].size), ("bin/java", 4));
+        assert!(symlinks.is_empty());
+    }
+}
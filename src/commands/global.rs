@@ -15,10 +15,15 @@
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
 use crate::installation::auto::{AutoInstaller, InstallationResult};
-use crate::storage::JdkRepository;
+use crate::storage::{InstalledJdk, JdkRepository};
 use crate::version::VersionRequest;
+use crate::version::file::{
+    remove_version_file, restore_version_file, version_file_pin, write_pin,
+};
+use crate::version::resolver::VersionResolver;
 use log::{debug, info};
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 pub struct GlobalCommand<'a> {
@@ -34,6 +39,12 @@ impl<'a> GlobalCommand<'a> {
         })
     }
 
+    /// Pin a version track such as `latest-lts` instead of a fixed version
+    pub fn execute_track(&self, track_spec: &str) -> Result<()> {
+        VersionRequest::parse_track(track_spec)?;
+        self.execute(track_spec)
+    }
+
     pub fn execute(&self, version_spec: &str) -> Result<()> {
         info!("Setting global JDK version to {version_spec}");
 
@@ -43,6 +54,16 @@ impl<'a> GlobalCommand<'a> {
         let version_request = VersionRequest::from_str(version_spec)?;
         debug!("Parsed version request: {version_request:?}");
 
+        // Nothing to install or look up: the shims find the system JDK at run time
+        if version_request.is_system() {
+            write_pin(
+                &self.global_version_path(self.config)?,
+                &version_request.to_string(),
+            )?;
+            println!("Global JDK version set to system; shims will run the JDK found on PATH");
+            return Ok(());
+        }
+
         // Create storage repository
         let repository = JdkRepository::new(self.config);
 
@@ -65,8 +86,9 @@ impl<'a> GlobalCommand<'a> {
                     matching_jdks = repository.find_matching_jdks(&version_request)?;
                 }
                 InstallationResult::UserDeclined => {
+                    print_pin_candidates(&repository, &version_request)?;
                     return Err(KopiError::JdkNotInstalled {
-                        jdk_spec: version_request.version_pattern.clone(),
+                        jdk_spec: version_request.to_string(),
                         version: Some(version_request.version_pattern.clone()),
                         distribution: version_request.distribution.clone(),
                         auto_install_enabled: true,
@@ -76,8 +98,9 @@ impl<'a> GlobalCommand<'a> {
                     });
                 }
                 InstallationResult::AutoInstallDisabled => {
+                    print_pin_candidates(&repository, &version_request)?;
                     return Err(KopiError::JdkNotInstalled {
-                        jdk_spec: version_request.version_pattern.clone(),
+                        jdk_spec: version_request.to_string(),
                         version: Some(version_request.version_pattern.clone()),
                         distribution: version_request.distribution.clone(),
                         auto_install_enabled: false,
@@ -93,7 +116,7 @@ impl<'a> GlobalCommand<'a> {
         let selected_jdk = matching_jdks
             .last()
             .ok_or_else(|| KopiError::JdkNotInstalled {
-                jdk_spec: version_request.version_pattern.clone(),
+                jdk_spec: version_request.to_string(),
                 version: Some(version_request.version_pattern.clone()),
                 distribution: version_request.distribution.clone(),
                 auto_install_enabled: false,
@@ -104,7 +127,25 @@ impl<'a> GlobalCommand<'a> {
 
         // Write version file using the selected JDK
         let version_file = self.global_version_path(self.config)?;
-        selected_jdk.write_to(&version_file)?;
+        if version_request.track.is_some() {
+            switch_pin(
+                &repository,
+                &version_file,
+                &version_request.to_string(),
+                selected_jdk,
+            )?;
+            println!(
+                "Global JDK version set to track {version_request} (currently {}@{})",
+                selected_jdk.distribution, selected_jdk.version
+            );
+            return Ok(());
+        }
+        switch_pin(
+            &repository,
+            &version_file,
+            &selected_jdk.pin_spec_with(&self.config.pin),
+            selected_jdk,
+        )?;
 
         println!(
             "Global JDK version set to {}@{}",
@@ -114,14 +155,86 @@ impl<'a> GlobalCommand<'a> {
         Ok(())
     }
 
+    /// Remove the global default so that only project files and environment
+    /// variables select a JDK
+    pub fn execute_unset(&self) -> Result<()> {
+        let version_file = self.global_version_path(self.config)?;
+        if remove_version_file(&version_file)? {
+            println!("Global JDK version unset ({})", version_file.display());
+        } else {
+            println!("No global JDK version is set");
+        }
+        Ok(())
+    }
+
+    /// Print the global default and the file it is stored in
+    pub fn execute_show(&self) -> Result<()> {
+        match VersionResolver::new(self.config).get_global_default()? {
+            Some((version_request, path)) => {
+                println!("{version_request} ({})", path.display());
+            }
+            None => println!("No global JDK version is set"),
+        }
+        Ok(())
+    }
+
     fn global_version_path(&self, config: &crate::config::KopiConfig) -> Result<PathBuf> {
         Ok(config.kopi_home().join("version"))
     }
 }
 
+/// Atomically point the version file at `path` to `pin`, then check that the
+/// written pin still selects `selected`. On failure the previous pin is put
+/// back, so a half-finished switch never leaves a pin that resolves to nothing.
+pub(crate) fn switch_pin(
+    repository: &JdkRepository,
+    path: &Path,
+    pin: &str,
+    selected: &InstalledJdk,
+) -> Result<()> {
+    let previous = write_pin(path, pin)?;
+    let verified = fs::read_to_string(path)
+        .map_err(KopiError::from)
+        .and_then(|written| VersionRequest::from_str(version_file_pin(&written).unwrap_or("")))
+        .and_then(|request| repository.find_matching_jdks(&request))
+        .and_then(|matching| {
+            if matching.iter().any(|jdk| jdk.path == selected.path) {
+                Ok(())
+            } else {
+                Err(KopiError::ValidationError(format!(
+                    "The pin '{pin}' no longer selects {}@{}",
+                    selected.distribution, selected.version
+                )))
+            }
+        });
+
+    if let Err(e) = verified {
+        restore_version_file(path, previous)?;
+        debug!("Restored the previous pin in {}", path.display());
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// List installed JDKs close to `request` so a missing pin can be corrected
+pub(crate) fn print_pin_candidates(
+    repository: &JdkRepository,
+    request: &VersionRequest,
+) -> Result<()> {
+    let candidates = repository.pin_candidates(request)?;
+    if !candidates.is_empty() {
+        eprintln!("Installed JDKs you could pin instead:");
+        for jdk in candidates {
+            eprintln!("  {}", jdk.pin_spec());
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::paths::install;
     use tempfile::TempDir;
 
     #[test]
@@ -141,4 +254,61 @@ mod tests {
         let version_path = command.global_version_path(&config).unwrap();
         assert_eq!(version_path, temp_dir.path().join("version"));
     }
+
+    #[test]
+    fn test_global_unset_removes_version_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = crate::config::KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let command = GlobalCommand::new(&config, false).unwrap();
+        let version_path = temp_dir.path().join("version");
+        std::fs::write(&version_path, "temurin@21").unwrap();
+
+        command.execute_unset().unwrap();
+        assert!(!version_path.exists());
+
+        // Unsetting again is a no-op
+        command.execute_unset().unwrap();
+    }
+
+    #[test]
+    fn test_switch_pin_writes_pin_that_selects_the_jdk() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = crate::config::KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let repository = JdkRepository::new(&config);
+        let jdk_path = install::installation_directory(temp_dir.path(), "temurin-24.0.2.12");
+        fs::create_dir_all(&jdk_path).unwrap();
+        let jdk = repository.list_installed_jdks().unwrap().remove(0);
+        let version_path = temp_dir.path().join("version");
+
+        switch_pin(&repository, &version_path, &jdk.pin_spec(), &jdk).unwrap();
+        assert_eq!(
+            fs::read_to_string(&version_path).unwrap(),
+            "temurin@24.0.2+12"
+        );
+    }
+
+    #[test]
+    fn test_switch_pin_restores_previous_pin_on_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = crate::config::KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let repository = JdkRepository::new(&config);
+        fs::create_dir_all(install::installation_directory(
+            temp_dir.path(),
+            "temurin-21.0.5",
+        ))
+        .unwrap();
+        let jdk = repository.list_installed_jdks().unwrap().remove(0);
+        let version_path = temp_dir.path().join("version");
+
+        // A pin that does not select the JDK puts the old pin back
+        fs::write(&version_path, "corretto@17").unwrap();
+        let result = switch_pin(&repository, &version_path, "zulu@21", &jdk);
+        assert!(matches!(result, Err(KopiError::ValidationError(_))));
+        assert_eq!(fs::read_to_string(&version_path).unwrap(), "corretto@17");
+
+        // With no previous pin, the file is removed again
+        fs::remove_file(&version_path).unwrap();
+        assert!(switch_pin(&repository, &version_path, "zulu@21", &jdk).is_err());
+        assert!(!version_path.exists());
+    }
 }
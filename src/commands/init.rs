@@ -0,0 +1,312 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commands::integrate::{execute_gradle, execute_maven};
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::StatusReporter;
+use crate::platform::shell::{Shell, parse_shell_name};
+use crate::storage::JdkRepository;
+use crate::version::VersionRequest;
+use crate::version::build_file;
+use crate::version::file::write_pin;
+use crate::version::resolver::PROJECT_CONFIG_FILE;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+const KOPI_VERSION_FILE: &str = ".kopi-version";
+
+/// Build tool whose toolchain configuration `kopi init` sets up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildTool {
+    Gradle,
+    Maven,
+}
+
+/// What `kopi init` scaffolds in a project directory
+#[derive(Debug, Clone, Default)]
+pub struct ProjectTemplate {
+    pub distribution: Option<String>,
+    pub version: Option<String>,
+    /// Take the version from the toolchain declared in `pom.xml` or
+    /// `build.gradle(.kts)` instead of `version`
+    pub from_release_file: bool,
+    pub build_tool: Option<BuildTool>,
+    /// Also write a starter `.kopi.toml`
+    pub kopi_toml: bool,
+    /// Overwrite files that already exist
+    pub force: bool,
+}
+
+pub struct InitCommand<'a> {
+    config: &'a KopiConfig,
+    no_progress: bool,
+}
+
+impl<'a> InitCommand<'a> {
+    pub fn new(config: &'a KopiConfig, no_progress: bool) -> Result<Self> {
+        Ok(Self {
+            config,
+            no_progress,
+        })
+    }
+
+    /// Print the shell hook for `shell`, to be evaluated from the shell's rc file
+    pub fn execute(&self, shell: &str) -> Result<()> {
+        let shell_type = parse_shell_name(shell)?;
+        let kopi = env::current_exe()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| "kopi".to_string());
+
+        let mut stdout = std::io::stdout();
+        stdout.write_all(hook_script(&shell_type, &kopi)?.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Pin a JDK for the project in the current directory, optionally set up
+    /// the build tool's toolchains, and print what to do next
+    pub fn execute_project(&self, template: &ProjectTemplate) -> Result<()> {
+        let dir = env::current_dir()?;
+        let status = StatusReporter::new(self.no_progress);
+
+        let version = match (&template.version, template.from_release_file) {
+            (Some(version), _) => version.clone(),
+            (None, true) => {
+                let detected = build_file::find_java_version(&dir)?
+                    .ok_or_else(|| build_file::no_declaration(&dir))?;
+                status.step(&format!("Detected {detected}"));
+                detected.major.to_string()
+            }
+            (None, false) => {
+                return Err(KopiError::ValidationError(
+                    "Pass --version or --from-release-file to choose the project's JDK".to_string(),
+                ));
+            }
+        };
+        let spec = match &template.distribution {
+            Some(distribution) => format!("{distribution}@{version}"),
+            None => version,
+        };
+        let request = VersionRequest::from_str(&spec)?;
+
+        let version_file = dir.join(KOPI_VERSION_FILE);
+        ensure_absent(&version_file, template.force)?;
+        write_pin(&version_file, &request.to_string())?;
+        status.success(&format!("Created {KOPI_VERSION_FILE} for {request}"));
+
+        if template.kopi_toml {
+            let path = dir.join(PROJECT_CONFIG_FILE);
+            ensure_absent(&path, template.force)?;
+            fs::write(&path, kopi_toml(template.distribution.as_deref()))?;
+            status.success(&format!("Created {PROJECT_CONFIG_FILE}"));
+        }
+
+        match template.build_tool {
+            Some(BuildTool::Gradle) => execute_gradle(self.config, &status)?,
+            Some(BuildTool::Maven) => execute_maven(self.config, &status)?,
+            None => {}
+        }
+
+        let installed = !request.is_system()
+            && !JdkRepository::new(self.config)
+                .find_matching_jdks(&request)?
+                .is_empty();
+        let mut steps = Vec::new();
+        if !installed && !request.is_system() {
+            steps.push((format!("kopi install {request}"), "install the pinned JDK"));
+        }
+        steps.push((
+            format!("git add {KOPI_VERSION_FILE}"),
+            "share the pin with your team",
+        ));
+        if template.build_tool.is_none() {
+            steps.push((
+                "kopi integrate gradle".to_string(),
+                "or 'maven', to expose kopi JDKs as toolchains",
+            ));
+        }
+        steps.push((
+            "eval \"$(kopi init bash)\"".to_string(),
+            "switch JDKs on cd (zsh, fish, powershell too)",
+        ));
+
+        let width = steps
+            .iter()
+            .map(|(command, _)| command.len())
+            .max()
+            .unwrap_or(0);
+        println!();
+        println!("Next steps:");
+        for (command, purpose) in steps {
+            println!("  {command:<width$}  # {purpose}");
+        }
+        Ok(())
+    }
+}
+
+fn ensure_absent(path: &Path, force: bool) -> Result<()> {
+    if !force && path.exists() {
+        return Err(KopiError::AlreadyExists(format!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Starter `.kopi.toml` with the project-level settings most projects change
+fn kopi_toml(distribution: Option<&str>) -> String {
+    let distribution = match distribution {
+        Some(distribution) => format!("default_distribution = \"{distribution}\"\n"),
+        None => "# default_distribution = \"temurin\"\n".to_string(),
+    };
+    format!(
+        "# Project settings for kopi; they override ~/.kopi/config.toml in this directory.\n\
+         # The JDK itself is pinned in .kopi-version.\n\
+         {distribution}\n\
+         # Pin individual tools to another JDK, e.g. GraalVM for native-image:\n\
+         [tools]\n\
+         # native-image = \"graalvm@21\"\n"
+    )
+}
+
+/// Build the hook that runs `kopi env --changed-only` whenever the working
+/// directory changes, and once immediately for the starting directory.
+fn hook_script(shell: &Shell, kopi: &str) -> Result<String> {
+    match shell {
+        Shell::Bash => {
+            let kopi = posix_quote(kopi);
+            Ok(format!(
+                r#"_kopi_hook() {{
+  local previous_exit_status=$?
+  if [[ "${{_KOPI_LAST_PWD:-}}" != "$PWD" ]]; then
+    _KOPI_LAST_PWD="$PWD"
+    eval "$({kopi} env --changed-only --shell bash)"
+  fi
+  return $previous_exit_status
+}}
+if [[ ";${{PROMPT_COMMAND[*]:-}};" != *";_kopi_hook;"* ]]; then
+  PROMPT_COMMAND="_kopi_hook${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}"
+fi
+"#
+            ))
+        }
+        Shell::Zsh => {
+            let kopi = posix_quote(kopi);
+            Ok(format!(
+                r#"_kopi_hook() {{
+  eval "$({kopi} env --changed-only --shell zsh)"
+}}
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd _kopi_hook
+_kopi_hook
+"#
+            ))
+        }
+        Shell::Fish => {
+            let kopi = posix_quote(kopi);
+            Ok(format!(
+                r#"function _kopi_hook --on-variable PWD
+    {kopi} env --changed-only --shell fish | source
+end
+_kopi_hook
+"#
+            ))
+        }
+        Shell::PowerShell => {
+            let kopi = kopi.replace('\'', "''");
+            Ok(format!(
+                r#"$global:__KopiLastPwd = $null
+$global:__KopiOriginalPrompt = $function:prompt
+function global:prompt {{
+    if ($global:__KopiLastPwd -ne $PWD.Path) {{
+        $global:__KopiLastPwd = $PWD.Path
+        $kopiEnv = & '{kopi}' env --changed-only --shell powershell
+        if ($kopiEnv) {{ ($kopiEnv -join "`n") | Invoke-Expression }}
+    }}
+    & $global:__KopiOriginalPrompt
+}}
+"#
+            ))
+        }
+        Shell::Cmd | Shell::Unknown(_) => Err(KopiError::UnsupportedShell(format!(
+            "{} (directory change hooks are available for bash, zsh, fish, and powershell)",
+            shell.get_shell_name()
+        ))),
+    }
+}
+
+/// Single-quote a word for POSIX-style shells (fish accepts the same form)
+fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bash_hook_runs_on_directory_change() {
+        let script = hook_script(&Shell::Bash, "/usr/bin/kopi").unwrap();
+        assert!(script.contains("'/usr/bin/kopi' env --changed-only --shell bash"));
+        assert!(script.contains("PROMPT_COMMAND"));
+        assert!(script.contains("_KOPI_LAST_PWD"));
+    }
+
+    #[test]
+    fn zsh_and_fish_hooks_use_native_events() {
+        let zsh = hook_script(&Shell::Zsh, "kopi").unwrap();
+        assert!(zsh.contains("add-zsh-hook chpwd _kopi_hook"));
+
+        let fish = hook_script(&Shell::Fish, "kopi").unwrap();
+        assert!(fish.contains("--on-variable PWD"));
+        assert!(fish.contains("--shell fish | source"));
+    }
+
+    #[test]
+    fn powershell_hook_wraps_prompt() {
+        let script = hook_script(&Shell::PowerShell, r"C:\Tools\kopi.exe").unwrap();
+        assert!(script.contains(r"& 'C:\Tools\kopi.exe' env --changed-only --shell powershell"));
+        assert!(script.contains("__KopiOriginalPrompt"));
+    }
+
+    #[test]
+    fn cmd_has_no_hook() {
+        assert!(matches!(
+            hook_script(&Shell::Cmd, "kopi"),
+            Err(KopiError::UnsupportedShell(_))
+        ));
+    }
+
+    #[test]
+    fn kopi_toml_sets_the_distribution() {
+        let template = kopi_toml(Some("zulu"));
+        assert!(template.contains("\ndefault_distribution = \"zulu\"\n"));
+        let parsed: toml::Value = toml::from_str(&template).unwrap();
+        assert_eq!(parsed["default_distribution"].as_str(), Some("zulu"));
+        assert!(parsed["tools"].as_table().unwrap().is_empty());
+
+        let parsed: toml::Value = toml::from_str(&kopi_toml(None)).unwrap();
+        assert!(parsed.get("default_distribution").is_none());
+    }
+
+    #[test]
+    fn posix_quote_escapes_single_quotes() {
+        assert_eq!(posix_quote("/opt/o'neil/kopi"), r"'/opt/o'\''neil/kopi'");
+    }
+}
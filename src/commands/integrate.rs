@@ -0,0 +1,209 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::StatusReporter;
+use crate::integrations::vscode::{self, TrackedSettings};
+use crate::integrations::{self, gradle, intellij, maven};
+use crate::paths::home;
+use crate::storage::JdkRepository;
+use clap::Subcommand;
+use std::env;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum IntegrateCommand {
+    /// Register kopi JDKs as IntelliJ IDEA SDKs
+    Intellij {
+        /// Also set the current project's SDK to its pinned JDK (.idea/misc.xml)
+        #[arg(long)]
+        project: bool,
+
+        /// IntelliJ IDEA configuration directory to update instead of every
+        /// detected one (e.g. ~/.config/JetBrains/IntelliJIdea2024.2)
+        #[arg(long, value_name = "DIR")]
+        config_dir: Option<PathBuf>,
+    },
+
+    /// Map kopi JDKs to Java runtimes in VS Code settings
+    Vscode {
+        /// Update the current project's .vscode/settings.json (default)
+        #[arg(long, conflicts_with = "user")]
+        workspace: bool,
+
+        /// Update the VS Code user settings instead of the workspace settings
+        #[arg(long)]
+        user: bool,
+    },
+
+    /// List kopi JDKs for Gradle toolchain detection (gradle.properties)
+    Gradle,
+
+    /// Add kopi JDKs to the Maven toolchains (~/.m2/toolchains.xml)
+    Maven,
+}
+
+impl IntegrateCommand {
+    pub fn execute(&self, config: &KopiConfig, no_progress: bool) -> Result<()> {
+        let status = StatusReporter::new(no_progress);
+        match self {
+            IntegrateCommand::Intellij {
+                project,
+                config_dir,
+            } => execute_intellij(config, &status, *project, config_dir.clone()),
+            IntegrateCommand::Vscode { user, .. } => execute_vscode(config, &status, *user),
+            IntegrateCommand::Gradle => execute_gradle(config, &status),
+            IntegrateCommand::Maven => execute_maven(config, &status),
+        }
+    }
+}
+
+fn execute_intellij(
+    config: &KopiConfig,
+    status: &StatusReporter,
+    project: bool,
+    config_dir: Option<PathBuf>,
+) -> Result<()> {
+    // Resolve the project first so a missing pin fails before anything is written
+    let pinned = if project {
+        Some(integrations::pinned_jdk(config, &env::current_dir()?)?)
+    } else {
+        None
+    };
+
+    let config_dirs = match config_dir {
+        Some(dir) => vec![dir],
+        None => {
+            let root = intellij::config_root()?;
+            let dirs = intellij::config_dirs(&root)?;
+            if dirs.is_empty() {
+                return Err(KopiError::NotFound(format!(
+                    "No IntelliJ IDEA configuration found in {}. Start IntelliJ IDEA once or \
+                     pass --config-dir",
+                    root.display()
+                )));
+            }
+            dirs
+        }
+    };
+
+    let jdks = JdkRepository::new(config).list_installed_jdks()?;
+    for dir in &config_dirs {
+        let table = intellij::register_jdks(dir, &jdks)?;
+        status.success(&format!(
+            "Registered {} JDK{} in {}",
+            jdks.len(),
+            if jdks.len() == 1 { "" } else { "s" },
+            table.display()
+        ));
+    }
+
+    if let Some(pinned) = pinned {
+        let misc = intellij::set_project_sdk(&pinned.project_root, &pinned.jdk)?;
+        status.success(&format!(
+            "Set project SDK to {} in {}",
+            intellij::sdk_name(&pinned.jdk),
+            misc.display()
+        ));
+    }
+
+    status.step(
+        "Restart IntelliJ IDEA to load the changes; a running IDE overwrites the SDK table on exit",
+    );
+    Ok(())
+}
+
+fn execute_vscode(config: &KopiConfig, status: &StatusReporter, user: bool) -> Result<()> {
+    let path = if user {
+        vscode::user_settings_path()?
+    } else {
+        // Settings belong next to the version file when the project pins one
+        let current_dir = env::current_dir()?;
+        let project_root = integrations::pinned_jdk(config, &current_dir)
+            .map(|pinned| pinned.project_root)
+            .unwrap_or(current_dir);
+        vscode::workspace_settings_path(&project_root)
+    };
+
+    let jdks = JdkRepository::new(config).list_installed_jdks()?;
+    let default = integrations::vscode_default_jdk(config, &path);
+    let jdks_root = home::jdks_dir(config.kopi_home());
+    if vscode::update_settings(&path, &jdks, &jdks_root, default.as_ref())? {
+        status.success(&format!("Updated Java runtimes in {}", path.display()));
+    } else {
+        status.success(&format!(
+            "Java runtimes in {} are up to date",
+            path.display()
+        ));
+    }
+
+    let mut tracked = TrackedSettings::load(config.kopi_home())?;
+    if tracked.track(&path) {
+        tracked.save(config.kopi_home())?;
+    }
+    status.step("kopi updates this file when JDKs are installed or uninstalled");
+    Ok(())
+}
+
+/// Export installed JDKs to the user-wide Gradle properties
+pub fn execute_gradle(config: &KopiConfig, status: &StatusReporter) -> Result<()> {
+    let path = gradle::user_properties_path()?;
+    let jdks = JdkRepository::new(config).list_installed_jdks()?;
+    let jdks_root = home::jdks_dir(config.kopi_home());
+    if gradle::export_jdks(&path, &jdks, &jdks_root)? {
+        status.success(&format!(
+            "Listed {} JDK{} in {} of {}",
+            jdks.len(),
+            if jdks.len() == 1 { "" } else { "s" },
+            gradle::INSTALLATIONS_PATHS_KEY,
+            path.display()
+        ));
+    } else if jdks.is_empty() && !gradle::is_exported(&path) {
+        status.step(&format!(
+            "No JDKs are installed yet; run 'kopi integrate gradle' again after installing one \
+             to list it in {}",
+            path.display()
+        ));
+        return Ok(());
+    } else {
+        status.success(&format!(
+            "Gradle toolchain paths in {} are up to date",
+            path.display()
+        ));
+    }
+    status.step("kopi updates this list when JDKs are installed or uninstalled");
+    Ok(())
+}
+
+/// Export installed JDKs to the Maven toolchains file
+pub fn execute_maven(config: &KopiConfig, status: &StatusReporter) -> Result<()> {
+    let path = maven::toolchains_path()?;
+    let jdks = JdkRepository::new(config).list_installed_jdks()?;
+    if maven::export_jdks(&path, &jdks)? {
+        status.success(&format!(
+            "Added {} JDK toolchain{} to {}",
+            jdks.len(),
+            if jdks.len() == 1 { "" } else { "s" },
+            path.display()
+        ));
+    } else {
+        status.success(&format!(
+            "Maven toolchains in {} are up to date",
+            path.display()
+        ));
+    }
+    status.step("kopi updates these toolchains when JDKs are installed or uninstalled");
+    Ok(())
+}
@@ -0,0 +1,355 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::StatusReporter;
+use crate::installation::jlink::{
+    self, JlinkRequest, RuntimeImage, RuntimeImages, load_runtime_images, save_runtime_images,
+};
+use crate::integrations;
+use crate::paths::home;
+use crate::storage::formatting::format_size;
+use crate::storage::{InstalledJdk, JdkLister, JdkRepository};
+use crate::version::VersionRequest;
+use chrono::Utc;
+use clap::Subcommand;
+use comfy_table::{Cell, Color, Table};
+use serde::Serialize;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Subcommand)]
+pub enum JlinkCommand {
+    /// Link a trimmed runtime image from an installed JDK
+    Create {
+        /// Name to list and remove the image by
+        name: String,
+
+        /// JDK to link from (default: the JDK pinned for the current project)
+        #[arg(long, value_name = "VERSION")]
+        jdk: Option<String>,
+
+        /// Modules to include, comma-separated; may be repeated
+        #[arg(long, value_name = "MODULES", required_unless_present = "jar")]
+        modules: Vec<String>,
+
+        /// Also include the modules this jar needs, found with jdeps; may be repeated
+        #[arg(long, value_name = "JAR")]
+        jar: Vec<PathBuf>,
+
+        /// Directory to write the image to (default: ~/.kopi/runtimes/<NAME>)
+        #[arg(long, value_name = "DIR")]
+        output: Option<PathBuf>,
+
+        /// Keep debug information instead of stripping it
+        #[arg(long)]
+        keep_debug: bool,
+
+        /// Replace an existing image with the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List the runtime images built by kopi jlink
+    List {
+        /// Print the images as a JSON array
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Delete a runtime image and forget it
+    Remove {
+        /// Name of the image
+        name: String,
+    },
+}
+
+impl JlinkCommand {
+    pub fn execute(&self, config: &KopiConfig, no_progress: bool) -> Result<()> {
+        let status = StatusReporter::new(no_progress);
+        match self {
+            JlinkCommand::Create {
+                name,
+                jdk,
+                modules,
+                jar,
+                output,
+                keep_debug,
+                force,
+            } => {
+                let options = CreateOptions {
+                    name,
+                    jdk: jdk.as_deref(),
+                    modules,
+                    jars: jar,
+                    output: output.clone(),
+                    keep_debug: *keep_debug,
+                    force: *force,
+                };
+                execute_create(config, &status, options)
+            }
+            JlinkCommand::List { json } => execute_list(config, *json),
+            JlinkCommand::Remove { name } => execute_remove(config, &status, name),
+        }
+    }
+}
+
+struct CreateOptions<'a> {
+    name: &'a str,
+    jdk: Option<&'a str>,
+    modules: &'a [String],
+    jars: &'a [PathBuf],
+    output: Option<PathBuf>,
+    keep_debug: bool,
+    force: bool,
+}
+
+fn execute_create(
+    config: &KopiConfig,
+    status: &StatusReporter,
+    options: CreateOptions,
+) -> Result<()> {
+    let kopi_home = config.kopi_home();
+    jlink::validate_image_name(options.name)?;
+    let mut images = load_runtime_images(kopi_home);
+    if let Some(existing) = images.get(options.name)
+        && !options.force
+    {
+        return Err(KopiError::AlreadyExists(format!(
+            "Runtime '{}' already exists at {}; use --force to replace it",
+            options.name,
+            existing.path.display()
+        )));
+    }
+
+    let jdk = select_jdk(config, options.jdk)?;
+    let jdk_label = format!("{}@{}", jdk.distribution, jdk.version);
+
+    let mut modules = jlink::parse_modules(options.modules.iter().map(String::as_str))?;
+    for jar in options.jars {
+        status.step(&format!("Scanning {} with jdeps", jar.display()));
+        modules.extend(jlink::scan_jar_modules(&jdk, jar)?);
+    }
+
+    let output = match options.output {
+        Some(dir) if dir.is_absolute() => dir,
+        Some(dir) => env::current_dir()?.join(dir),
+        None => home::runtimes_dir(kopi_home).join(options.name),
+    };
+
+    ensure_not_jdk(config, &output)?;
+    if options.force {
+        let previous = images.get(options.name).map(|image| image.path.clone());
+        for dir in previous.iter().chain([&output]) {
+            if !dir.exists() {
+                continue;
+            }
+            ensure_not_jdk(config, dir)?;
+            if !is_kopi_runtime(kopi_home, &images, dir) || !jlink::remove_image_dir(dir)? {
+                return Err(KopiError::AlreadyExists(format!(
+                    "{} exists and is not a runtime image created by kopi; remove it first",
+                    dir.display()
+                )));
+            }
+        }
+    }
+
+    let request = JlinkRequest {
+        modules: modules.into_iter().collect(),
+        output,
+        keep_debug: options.keep_debug,
+    };
+    status.operation(
+        "Linking",
+        &format!("{} modules from {jdk_label}", request.modules.len()),
+    );
+    jlink::link_runtime(&jdk, &request)?;
+
+    let size = JdkLister::get_jdk_size(&request.output).unwrap_or(0);
+    status.success(&format!(
+        "Created runtime '{}' at {} ({}): {}",
+        options.name,
+        request.output.display(),
+        format_size(size),
+        request.modules.join(", ")
+    ));
+
+    images.insert(
+        options.name.to_string(),
+        RuntimeImage {
+            path: request.output,
+            distribution: jdk.distribution.clone(),
+            version: jdk.version.to_string(),
+            modules: request.modules,
+            created_at: Utc::now(),
+        },
+    );
+    save_runtime_images(kopi_home, &images)
+}
+
+/// Refuse `dir` when it is, contains or lies inside an installed JDK,
+/// including adopted JDKs and those in the system JDK directory
+fn ensure_not_jdk(config: &KopiConfig, dir: &Path) -> Result<()> {
+    let dir = resolve(dir);
+    let repository = JdkRepository::new(config);
+    let jdk_roots = [Some(config.jdks_dir()?), config.system_jdks_dir()]
+        .into_iter()
+        .flatten()
+        .map(|root| resolve(&root))
+        .chain(
+            repository
+                .list_installed_jdks()?
+                .into_iter()
+                .map(|jdk| resolve(&jdk.path)),
+        );
+    for root in jdk_roots {
+        if dir.starts_with(&root) || root.starts_with(&dir) {
+            return Err(KopiError::ValidationError(format!(
+                "Refusing to write a runtime image to {}: it overlaps the installed JDKs at {}",
+                dir.display(),
+                root.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `dir` is a runtime image kopi created: one recorded in
+/// runtimes.json or one under the runtimes directory
+fn is_kopi_runtime(kopi_home: &Path, images: &RuntimeImages, dir: &Path) -> bool {
+    let dir = resolve(dir);
+    let runtimes_dir = resolve(&home::runtimes_dir(kopi_home));
+    (dir.starts_with(&runtimes_dir) && dir != runtimes_dir)
+        || images.values().any(|image| resolve(&image.path) == dir)
+}
+
+/// `path` with symlinks resolved, as far as it exists
+fn resolve(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => resolve(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The JDK named by `spec`, or the one pinned for the current directory
+fn select_jdk(config: &KopiConfig, spec: Option<&str>) -> Result<InstalledJdk> {
+    let Some(spec) = spec else {
+        return Ok(integrations::pinned_jdk(config, &env::current_dir()?)?.jdk);
+    };
+
+    let request = VersionRequest::from_str(spec)?;
+    JdkRepository::new(config)
+        .find_matching_jdks(&request)?
+        .pop()
+        .ok_or_else(|| KopiError::JdkNotInstalled {
+            jdk_spec: spec.to_string(),
+            version: Some(request.version_pattern.clone()),
+            distribution: request.distribution.clone(),
+            auto_install_enabled: false,
+            auto_install_failed: None,
+            user_declined: false,
+            install_in_progress: false,
+        })
+}
+
+#[derive(Serialize)]
+struct RuntimeEntry<'a> {
+    name: &'a str,
+    #[serde(flatten)]
+    image: &'a RuntimeImage,
+    exists: bool,
+}
+
+fn execute_list(config: &KopiConfig, json: bool) -> Result<()> {
+    let images = load_runtime_images(config.kopi_home());
+    let entries: Vec<RuntimeEntry> = images
+        .iter()
+        .map(|(name, image)| RuntimeEntry {
+            name,
+            image,
+            exists: image.path.join("release").is_file(),
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No runtime images. Create one with 'kopi jlink create <NAME> --modules ...'");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec![
+        Cell::new("Name"),
+        Cell::new("JDK"),
+        Cell::new("Modules"),
+        Cell::new("Path"),
+        Cell::new("Created"),
+    ]);
+    for entry in &entries {
+        let path = if entry.exists {
+            Cell::new(entry.image.path.display())
+        } else {
+            Cell::new(format!("{} (missing)", entry.image.path.display())).fg(Color::Red)
+        };
+        table.add_row(vec![
+            Cell::new(entry.name),
+            Cell::new(format!(
+                "{}@{}",
+                entry.image.distribution, entry.image.version
+            )),
+            Cell::new(entry.image.modules.join(", ")),
+            path,
+            Cell::new(entry.image.created_at.format("%Y-%m-%d")),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+fn execute_remove(config: &KopiConfig, status: &StatusReporter, name: &str) -> Result<()> {
+    let kopi_home = config.kopi_home();
+    let mut images = load_runtime_images(kopi_home);
+    let Some(image) = images.remove(name) else {
+        return Err(KopiError::NotFound(format!(
+            "No runtime named '{name}'; run 'kopi jlink list' to see the runtimes kopi built"
+        )));
+    };
+
+    if jlink::remove_image_dir(&image.path)? {
+        status.success(&format!(
+            "Removed runtime '{name}' from {}",
+            image.path.display()
+        ));
+    } else if image.path.exists() {
+        status.step(&format!(
+            "Left {} in place: it no longer looks like a runtime image",
+            image.path.display()
+        ));
+    } else {
+        status.step(&format!(
+            "Runtime '{name}' was already deleted from {}",
+            image.path.display()
+        ));
+    }
+    save_runtime_images(kopi_home, &images)
+}
@@ -12,11 +12,64 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::cache::{self, MetadataCache, PlatformFilter};
+use crate::commands::outdated::{OutdatedEntry, collect_outdated, line_label, status_cell};
 use crate::config::KopiConfig;
-use crate::error::Result;
-use crate::storage::JdkRepository;
+use crate::error::{KopiError, Result};
+use crate::models::package::PackageType;
+use crate::shim::find_jdk_installation;
 use crate::storage::formatting::format_size;
+use crate::storage::{InstalledJdk, JdkRepository, ReleaseInfo};
+use crate::version::file::VersionTrack;
+use crate::version::resolver::VersionResolver;
+use crate::version::{Version, VersionRequest};
+use comfy_table::{Cell, Table};
 use log::debug;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// One row of `kopi list`, shared with the daemon's `list` method
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstalledJdkSummary {
+    pub distribution: String,
+    pub version: String,
+    pub javafx_bundled: bool,
+    pub path: PathBuf,
+    pub size: u64,
+    /// Selected by the global default version file
+    #[serde(default)]
+    pub global: bool,
+    /// Selected by the project version file for the current directory
+    #[serde(default)]
+    pub local: bool,
+    /// The JDK a shim would run right now
+    #[serde(default)]
+    pub active: bool,
+    /// Protected with `kopi pin-protect`
+    #[serde(default)]
+    pub protected: bool,
+    /// Adopted with `kopi adopt`; its files live outside kopi's home
+    #[serde(default)]
+    pub external: bool,
+    /// Pre-installed in the read-only system JDK directory
+    #[serde(default)]
+    pub system: bool,
+    /// Properties from the JDK's `release` file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release: Option<ReleaseInfo>,
+}
+
+/// Newest cached build of a release line, as listed by `kopi list --remote`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemoteLine {
+    pub distribution: String,
+    pub major: u32,
+    pub latest: String,
+    /// Newest installed build of the line; `None` when none is installed
+    pub installed: Option<String>,
+}
 
 pub struct ListCommand<'a> {
     config: &'a KopiConfig,
@@ -27,49 +80,326 @@ impl<'a> ListCommand<'a> {
         Ok(Self { config })
     }
 
-    pub fn execute(&self) -> Result<()> {
+    pub fn execute(&self, json: bool, detailed: bool) -> Result<()> {
         let repository = JdkRepository::new(self.config);
+        self.render(collect_installed(&repository)?, json, detailed)
+    }
 
-        // List installed JDKs
-        let installed_jdks = repository.list_installed_jdks()?;
-
-        if installed_jdks.is_empty() {
-            println!("No JDKs installed");
-            println!("Use 'kopi install <version>' to install a JDK");
+    /// List the newest cached build of every release line for this platform,
+    /// or with `installed_only_diff` compare each installed line with it
+    pub fn execute_remote(&self, json: bool, installed_only_diff: bool) -> Result<()> {
+        let repository = JdkRepository::new(self.config);
+        let installed = repository.list_installed_jdks()?;
+        if installed_only_diff && installed.is_empty() {
+            if json {
+                println!("[]");
+            } else {
+                println!("No JDKs installed");
+            }
             return Ok(());
         }
 
-        // Calculate disk usage for each JDK and display
-        println!("Installed JDKs:");
-        let mut total_size = 0u64;
-
-        for jdk in &installed_jdks {
-            let size = repository.get_jdk_size(&jdk.path)?;
-            total_size += size;
-
-            debug!("JDK {} size: {} bytes", jdk.path.display(), size);
-
-            // Display format: "  temurin@21.0.1 (1.2 GB)" or "  liberica@21.0.5+fx (1.2 GB)"
-            let javafx_suffix = if jdk.javafx_bundled { "+fx" } else { "" };
-            println!(
-                "  {}@{}{} ({})",
-                jdk.distribution,
-                jdk.version,
-                javafx_suffix,
-                format_size(size)
-            );
+        let metadata = cache::get_fresh_metadata(self.config)?;
+        let platform = PlatformFilter::current();
+        let output = if installed_only_diff {
+            let entries = collect_outdated(&repository, &installed, &metadata, &platform);
+            if !json {
+                print_diff(&entries);
+                return Ok(());
+            }
+            serde_json::to_string_pretty(&entries)
+        } else {
+            let lines = collect_remote_lines(&metadata, &installed, &platform);
+            if !json {
+                print_remote(&lines);
+                return Ok(());
+            }
+            serde_json::to_string_pretty(&lines)
+        };
+        let output = output.map_err(|e| {
+            KopiError::SystemError(format!("Failed to serialize remote listing: {e}"))
+        })?;
+        println!("{output}");
+        Ok(())
+    }
+
+    /// Mark resolution status for the current directory and print the listing.
+    ///
+    /// Also used for listings from the daemon, whose working directory differs.
+    pub fn render(
+        &self,
+        mut installed_jdks: Vec<InstalledJdkSummary>,
+        json: bool,
+        detailed: bool,
+    ) -> Result<()> {
+        mark_resolution(self.config, &mut installed_jdks);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&installed_jdks)?);
+        } else {
+            print_installed(&installed_jdks, detailed);
         }
+        Ok(())
+    }
+}
 
-        // Show total disk usage
-        println!();
+/// Flag the JDKs selected by the global default, the project file, and the
+/// active resolution, picking installations the same way the shim does
+pub fn mark_resolution(config: &KopiConfig, installed_jdks: &mut [InstalledJdkSummary]) {
+    let resolver = VersionResolver::new(config);
+    let repository = JdkRepository::new(config);
+    let selected = |request: Option<VersionRequest>| -> Option<PathBuf> {
+        let request = request?;
+        find_jdk_installation(&repository, &request)
+            .ok()
+            .map(|jdk| jdk.path)
+    };
+
+    let global = selected(
+        resolver
+            .get_global_default()
+            .ok()
+            .flatten()
+            .map(|(request, _)| request),
+    );
+    let local = selected(
+        resolver
+            .find_project_version()
+            .ok()
+            .flatten()
+            .map(|(request, _)| request),
+    );
+    let active = selected(resolver.resolve_version().ok().map(|(request, _)| request));
+
+    for jdk in installed_jdks {
+        jdk.global = global.as_ref() == Some(&jdk.path);
+        jdk.local = local.as_ref() == Some(&jdk.path);
+        jdk.active = active.as_ref() == Some(&jdk.path);
+    }
+}
+
+/// List installed JDKs together with their disk usage
+pub fn collect_installed(repository: &JdkRepository) -> Result<Vec<InstalledJdkSummary>> {
+    let installed_jdks = repository.list_installed_jdks()?;
+    let paths: Vec<&Path> = installed_jdks
+        .iter()
+        .map(|jdk| jdk.path.as_path())
+        .collect();
+    let sizes = paths
+        .par_iter()
+        .map(|path| repository.get_jdk_size(path))
+        .collect::<Result<Vec<u64>>>()?;
+    let mut summaries = Vec::with_capacity(installed_jdks.len());
+
+    for (jdk, size) in installed_jdks.into_iter().zip(sizes) {
+        debug!("JDK {} size: {} bytes", jdk.path.display(), size);
+        let protected = repository.is_protected(&jdk);
+        let external = repository.is_external(&jdk);
+        let system = repository.is_system(&jdk);
+        // JDKs installed before release properties were recorded, and adopted
+        // ones, are read from disk instead
+        let release = jdk
+            .installation_metadata()
+            .and_then(|metadata| metadata.release)
+            .or_else(|| ReleaseInfo::read(&jdk.resolve_java_home()));
+
+        summaries.push(InstalledJdkSummary {
+            distribution: jdk.distribution,
+            version: jdk.version.to_string(),
+            javafx_bundled: jdk.javafx_bundled,
+            path: jdk.path,
+            size,
+            global: false,
+            local: false,
+            active: false,
+            protected,
+            external,
+            system,
+            release,
+        });
+    }
+
+    Ok(summaries)
+}
+
+/// Print the listing; `detailed` adds the `release` file properties of each JDK
+pub fn print_installed(installed_jdks: &[InstalledJdkSummary], detailed: bool) {
+    if installed_jdks.is_empty() {
+        println!("No JDKs installed");
+        println!("Use 'kopi install <version>' to install a JDK");
+        return;
+    }
+
+    println!("Installed JDKs:");
+    let mut total_size = 0u64;
+
+    for jdk in installed_jdks {
+        total_size += jdk.size;
+
+        // Display format: "  temurin@21.0.1 (1.2 GB)" or "* liberica@21.0.5+fx (1.2 GB) [global]"
+        let javafx_suffix = if jdk.javafx_bundled { "+fx" } else { "" };
+        let marker = if jdk.active { "*" } else { " " };
+        let labels: Vec<&str> = [
+            (jdk.global, "global"),
+            (jdk.local, "local"),
+            (jdk.protected, "protected"),
+            (jdk.external, "external"),
+            (jdk.system, "system"),
+        ]
+        .into_iter()
+        .filter_map(|(set, label)| set.then_some(label))
+        .collect();
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", labels.join(", "))
+        };
         println!(
-            "Total disk usage: {} ({} JDK{})",
-            format_size(total_size),
-            installed_jdks.len(),
-            if installed_jdks.len() == 1 { "" } else { "s" }
+            "{marker} {}@{}{} ({}){labels}",
+            jdk.distribution,
+            jdk.version,
+            javafx_suffix,
+            format_size(jdk.size)
         );
+        if detailed {
+            print_release(jdk.release.as_ref());
+        }
+    }
 
-        Ok(())
+    // Show total disk usage
+    println!();
+    println!(
+        "Total disk usage: {} ({} JDK{})",
+        format_size(total_size),
+        installed_jdks.len(),
+        if installed_jdks.len() == 1 { "" } else { "s" }
+    );
+}
+
+/// The newest GA JDK build of every distribution/major line in the metadata
+/// cache for `platform`, with the newest installed build of the line
+pub fn collect_remote_lines(
+    metadata: &MetadataCache,
+    installed: &[InstalledJdk],
+    platform: &PlatformFilter,
+) -> Vec<RemoteLine> {
+    let mut distributions: Vec<_> = metadata.distributions.values().collect();
+    distributions.sort_by(|a, b| a.distribution.id().cmp(b.distribution.id()));
+
+    let mut lines = Vec::new();
+    for dist_cache in distributions {
+        let majors: BTreeSet<u32> = dist_cache
+            .packages
+            .iter()
+            .filter(|package| !package.version.is_early_access())
+            .map(|package| package.version.major())
+            .collect();
+        for major in majors.into_iter().rev() {
+            let Some(latest) = metadata.lookup_latest_build(
+                &dist_cache.distribution,
+                major,
+                platform,
+                Some(&PackageType::Jdk),
+                Some(false),
+                false,
+            ) else {
+                continue;
+            };
+            let installed = installed
+                .iter()
+                .filter(|jdk| {
+                    jdk.distribution == dist_cache.distribution.id() && jdk.version.major() == major
+                })
+                .map(|jdk| &jdk.version)
+                .max_by(|a, b| VersionTrack::compare(a, b))
+                .map(Version::to_string);
+            lines.push(RemoteLine {
+                distribution: dist_cache.distribution.id().to_string(),
+                major,
+                latest: latest.distribution_version.to_string(),
+                installed,
+            });
+        }
+    }
+    lines
+}
+
+fn print_remote(lines: &[RemoteLine]) {
+    if lines.is_empty() {
+        println!("No builds for this platform in the metadata cache");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec![
+        Cell::new("Distribution"),
+        Cell::new("Major"),
+        Cell::new("Latest"),
+        Cell::new("Installed"),
+    ]);
+    for line in lines {
+        table.add_row(vec![
+            Cell::new(&line.distribution),
+            Cell::new(line.major),
+            Cell::new(&line.latest),
+            Cell::new(line.installed.as_deref().unwrap_or("-")),
+        ]);
+    }
+    println!("{table}");
+}
+
+fn print_diff(entries: &[OutdatedEntry]) {
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec![
+        Cell::new("Distribution"),
+        Cell::new("Major"),
+        Cell::new("Installed"),
+        Cell::new("Newest cached"),
+        Cell::new("Behind"),
+        Cell::new("Status"),
+    ]);
+    for entry in entries {
+        let behind = if entry.releases_behind > 0 {
+            format!("+{}", entry.releases_behind)
+        } else {
+            "-".to_string()
+        };
+        table.add_row(vec![
+            Cell::new(line_label(entry)),
+            Cell::new(entry.major),
+            Cell::new(&entry.current),
+            Cell::new(entry.latest.as_deref().unwrap_or("-")),
+            Cell::new(behind),
+            status_cell(entry),
+        ]);
+    }
+    println!("{table}");
+
+    let outdated = entries.iter().filter(|entry| entry.outdated).count();
+    if outdated > 0 {
+        println!();
+        println!("{outdated} release line(s) have newer builds. Install them with 'kopi install'.");
+    }
+}
+
+fn print_release(release: Option<&ReleaseInfo>) {
+    let Some(release) = release else {
+        println!("      (no release file)");
+        return;
+    };
+    let fields = [
+        ("Implementor", release.implementor.as_deref()),
+        ("Java version", release.java_version.as_deref()),
+        ("Architecture", release.os_arch.as_deref()),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            println!("      {label}: {value}");
+        }
+    }
+    if !release.modules.is_empty() {
+        println!("      Modules: {}", release.modules.len());
     }
 }
 
@@ -93,7 +423,7 @@ mod tests {
 
         // This would need proper testing infrastructure to capture stdout
         // For now, we just test that the command can be created and executed
-        let result = command.execute();
+        let result = command.execute(false, false);
         assert!(result.is_ok());
     }
 
@@ -117,12 +447,139 @@ mod tests {
         // Create some mock files to give the JDKs size
         fs::write(jdk1_path.join("mock_file"), "test content").unwrap();
         fs::write(jdk2_path.join("mock_file"), "test content").unwrap();
+        fs::write(
+            jdk1_path.join("release"),
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"21.0.1\"\n",
+        )
+        .unwrap();
 
         let command = ListCommand::new(&config).unwrap();
 
         // This would need proper testing infrastructure to capture stdout
         // For now, we just test that the command can be created and executed
-        let result = command.execute();
+        let result = command.execute(false, false);
         assert!(result.is_ok());
+        assert!(command.execute(false, true).is_ok());
+
+        let installed = collect_installed(&JdkRepository::new(&config)).unwrap();
+        let release = |distribution: &str| {
+            installed
+                .iter()
+                .find(|jdk| jdk.distribution == distribution)
+                .unwrap()
+                .release
+                .clone()
+        };
+        let temurin = release("temurin").unwrap();
+        assert_eq!(temurin.implementor.as_deref(), Some("Eclipse Adoptium"));
+        assert_eq!(temurin.java_version.as_deref(), Some("21.0.1"));
+        assert_eq!(release("corretto"), None);
+    }
+
+    #[test]
+    fn test_collect_remote_lines() {
+        use crate::cache::DistributionCache;
+        use crate::models::distribution::Distribution;
+        use crate::models::metadata::JdkMetadata;
+        use crate::models::package::ArchiveType;
+        use crate::models::platform::{Architecture, OperatingSystem};
+        use std::str::FromStr;
+
+        let package = |version: &str| JdkMetadata {
+            id: format!("temurin-{version}"),
+            distribution: "temurin".to_string(),
+            version: Version::from_str(version).unwrap(),
+            distribution_version: Version::from_str(version).unwrap(),
+            architecture: Architecture::Ppc64,
+            operating_system: OperatingSystem::Aix,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: None,
+            checksum: None,
+            checksum_type: None,
+            size: 0,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        };
+        let mut metadata = MetadataCache::new();
+        metadata.distributions.insert(
+            "temurin".to_string(),
+            DistributionCache {
+                distribution: Distribution::Temurin,
+                display_name: "Eclipse Temurin".to_string(),
+                packages: vec![package("21.0.5"), package("21.0.4"), package("17.0.9")],
+            },
+        );
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let jdks_dir = config.jdks_dir().unwrap();
+        fs::create_dir_all(jdks_dir.join("temurin-21.0.3")).unwrap();
+        fs::create_dir_all(jdks_dir.join("temurin-21.0.4")).unwrap();
+        let installed = JdkRepository::new(&config).list_installed_jdks().unwrap();
+
+        let platform = PlatformFilter::parse_target("aix-ppc64").unwrap();
+        let lines = collect_remote_lines(&metadata, &installed, &platform);
+        assert_eq!(
+            lines,
+            vec![
+                RemoteLine {
+                    distribution: "temurin".to_string(),
+                    major: 21,
+                    latest: "21.0.5".to_string(),
+                    installed: Some("21.0.4".to_string()),
+                },
+                RemoteLine {
+                    distribution: "temurin".to_string(),
+                    major: 17,
+                    latest: "17.0.9".to_string(),
+                    installed: None,
+                },
+            ]
+        );
+
+        let diff = collect_outdated(
+            &JdkRepository::new(&config),
+            &installed,
+            &metadata,
+            &platform,
+        );
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].current, "21.0.4");
+        assert_eq!(diff[0].releases_behind, 1);
+        assert!(
+            collect_remote_lines(
+                &metadata,
+                &installed,
+                &PlatformFilter::parse_target("linux-x64").unwrap()
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_mark_resolution_flags_global_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let jdks_dir = config.jdks_dir().unwrap();
+        fs::create_dir_all(jdks_dir.join("temurin-21.0.1")).unwrap();
+        fs::create_dir_all(jdks_dir.join("temurin-17.0.9")).unwrap();
+        fs::write(temp_dir.path().join("version"), "temurin@17").unwrap();
+
+        let repository = JdkRepository::new(&config);
+        let mut installed = collect_installed(&repository).unwrap();
+        mark_resolution(&config, &mut installed);
+
+        let global: Vec<_> = installed
+            .iter()
+            .filter(|jdk| jdk.global)
+            .map(|jdk| jdk.version.as_str())
+            .collect();
+        assert_eq!(global, vec!["17.0.9"]);
     }
 }
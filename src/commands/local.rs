@@ -12,11 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::commands::global::{print_pin_candidates, switch_pin};
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
 use crate::installation::auto::{AutoInstaller, InstallationResult};
 use crate::storage::JdkRepository;
 use crate::version::VersionRequest;
+use crate::version::build_file;
+use crate::version::file::{remove_version_file, write_pin};
+use crate::version::resolver::VersionResolver;
 use log::{debug, info};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -34,6 +38,21 @@ impl<'a> LocalCommand<'a> {
         })
     }
 
+    /// Pin a version track such as `latest-lts` instead of a fixed version
+    pub fn execute_track(&self, track_spec: &str) -> Result<()> {
+        VersionRequest::parse_track(track_spec)?;
+        self.execute(track_spec)
+    }
+
+    /// Pin the Java version the project's Maven or Gradle build declares
+    pub fn execute_auto(&self) -> Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let detected = build_file::find_java_version(&current_dir)?
+            .ok_or_else(|| build_file::no_declaration(&current_dir))?;
+        println!("Detected {detected}");
+        self.execute(&detected.major.to_string())
+    }
+
     pub fn execute(&self, version_spec: &str) -> Result<()> {
         info!("Setting local JDK version to {version_spec}");
 
@@ -41,6 +60,13 @@ impl<'a> LocalCommand<'a> {
         let version_request = VersionRequest::from_str(version_spec)?;
         debug!("Parsed version request: {version_request:?}");
 
+        // Nothing to install or look up: the shims find the system JDK at run time
+        if version_request.is_system() {
+            write_pin(&self.local_version_path()?, &version_request.to_string())?;
+            println!("Created .kopi-version file for system; shims will run the JDK found on PATH");
+            return Ok(());
+        }
+
         // Create storage repository
         let repository = JdkRepository::new(self.config);
 
@@ -63,8 +89,9 @@ impl<'a> LocalCommand<'a> {
                     matching_jdks = repository.find_matching_jdks(&version_request)?;
                 }
                 InstallationResult::UserDeclined => {
+                    print_pin_candidates(&repository, &version_request)?;
                     return Err(KopiError::JdkNotInstalled {
-                        jdk_spec: version_request.version_pattern.clone(),
+                        jdk_spec: version_request.to_string(),
                         version: None,
                         distribution: version_request.distribution.clone(),
                         auto_install_enabled: true,
@@ -75,19 +102,19 @@ impl<'a> LocalCommand<'a> {
                 }
                 InstallationResult::AutoInstallDisabled => {
                     // When auto-install is disabled, still create the .kopi-version file
-                    // but show a warning about the JDK not being installed
+                    // but show a warning about the JDK not being installed; project
+                    // files are shared, so pinning ahead of installing is legitimate
                     let version_file = self.local_version_path()?;
-                    std::fs::write(&version_file, version_request.to_string())?;
+                    write_pin(&version_file, &version_request.to_string())?;
 
                     println!("Created .kopi-version file for {version_request}");
-                    println!(
-                        "Warning: JDK {} is not installed",
-                        version_request.version_pattern
-                    );
-                    println!(
-                        "Run 'kopi install {}' to install this JDK",
-                        version_request.version_pattern
-                    );
+                    println!("Warning: JDK {version_request} is not installed");
+                    print_pin_candidates(&repository, &version_request)?;
+                    if version_request.track.is_some() {
+                        println!("Run 'kopi upgrade' to install the newest matching JDK");
+                    } else {
+                        println!("Run 'kopi install {version_request}' to install this JDK");
+                    }
 
                     return Ok(());
                 }
@@ -98,7 +125,7 @@ impl<'a> LocalCommand<'a> {
         let selected_jdk = matching_jdks
             .last()
             .ok_or_else(|| KopiError::JdkNotInstalled {
-                jdk_spec: version_request.version_pattern.clone(),
+                jdk_spec: version_request.to_string(),
                 version: None,
                 distribution: version_request.distribution.clone(),
                 auto_install_enabled: false,
@@ -109,7 +136,25 @@ impl<'a> LocalCommand<'a> {
 
         // Write version file using the selected JDK
         let version_file = self.local_version_path()?;
-        selected_jdk.write_to(&version_file)?;
+        if version_request.track.is_some() {
+            switch_pin(
+                &repository,
+                &version_file,
+                &version_request.to_string(),
+                selected_jdk,
+            )?;
+            println!(
+                "Created .kopi-version file tracking {version_request} (currently {}@{})",
+                selected_jdk.distribution, selected_jdk.version
+            );
+            return Ok(());
+        }
+        switch_pin(
+            &repository,
+            &version_file,
+            &selected_jdk.pin_spec_with(&self.config.pin),
+            selected_jdk,
+        )?;
 
         println!(
             "Created .kopi-version file for {}@{}",
@@ -119,6 +164,38 @@ impl<'a> LocalCommand<'a> {
         Ok(())
     }
 
+    /// Remove the `.kopi-version` file in the current directory.
+    ///
+    /// Pins in parent directories and `.java-version` files, which other
+    /// tools also read, are reported but left alone.
+    pub fn execute_unset(&self) -> Result<()> {
+        let version_file = self.local_version_path()?;
+        if remove_version_file(&version_file)? {
+            println!("Removed {}", version_file.display());
+            return Ok(());
+        }
+
+        match VersionResolver::new(self.config).find_project_version()? {
+            Some((_, path)) => println!(
+                "No .kopi-version file in the current directory; the project version comes from {}",
+                path.display()
+            ),
+            None => println!("No local JDK version is set"),
+        }
+        Ok(())
+    }
+
+    /// Print the project version in effect here and the file that sets it
+    pub fn execute_show(&self) -> Result<()> {
+        match VersionResolver::new(self.config).find_project_version()? {
+            Some((version_request, path)) => {
+                println!("{version_request} ({})", path.display());
+            }
+            None => println!("No local JDK version is set"),
+        }
+        Ok(())
+    }
+
     fn local_version_path(&self) -> Result<PathBuf> {
         let current_dir = std::env::current_dir()
             .map_err(|e| KopiError::SystemError(format!("Failed to get current directory: {e}")))?;
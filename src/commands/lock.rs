@@ -0,0 +1,271 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::cache::{self, MetadataCache, PlatformFilter};
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::SilentProgress;
+use crate::lockfile::{LOCK_FILE_NAME, LockedPackage, ProjectLock};
+use crate::metadata::MetadataProvider;
+use crate::models::distribution::Distribution;
+use crate::models::metadata::JdkMetadata;
+use crate::version::VersionRequest;
+use crate::version::resolver::{VersionResolver, VersionSource};
+use log::{debug, warn};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Writes `kopi.lock` for the project's version specification
+pub struct LockCommand<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> LockCommand<'a> {
+    pub fn new(config: &'a KopiConfig) -> Result<Self> {
+        Ok(Self { config })
+    }
+
+    pub fn execute(&self, version_spec: Option<&str>, platforms: &[String]) -> Result<()> {
+        let (version_request, lock_dir) = match version_spec {
+            Some(spec) => (VersionRequest::from_str(spec)?, current_dir()?),
+            None => {
+                let (request, source) = VersionResolver::new(self.config).resolve_version()?;
+                let dir = match source {
+                    VersionSource::ProjectFile(path) => match path.parent() {
+                        Some(dir) => dir.to_path_buf(),
+                        None => current_dir()?,
+                    },
+                    _ => current_dir()?,
+                };
+                (request, dir)
+            }
+        };
+        debug!("Locking {version_request:?} for platforms {platforms:?}");
+
+        if version_request.is_system() {
+            return Err(KopiError::ValidationError(
+                "'system' uses the JDK on PATH, which kopi does not manage and cannot lock"
+                    .to_string(),
+            ));
+        }
+
+        let targets = if platforms.is_empty() {
+            vec![PlatformFilter::current()]
+        } else {
+            platforms
+                .iter()
+                .map(|platform| PlatformFilter::parse_target(platform))
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let distribution = match &version_request.distribution {
+            Some(dist) => Distribution::from_str(dist)?,
+            None => Distribution::from_str(&self.config.default_distribution)
+                .unwrap_or(Distribution::Temurin),
+        };
+
+        let metadata = cache::get_fresh_metadata(self.config)?;
+        let resolved = resolve_packages(&metadata, &version_request, &distribution, &targets)?;
+
+        let provider = MetadataProvider::from_config(self.config)?;
+        let mut packages = Vec::with_capacity(resolved.len());
+        for mut package in resolved {
+            provider.ensure_complete(&mut package, &mut SilentProgress)?;
+            if package.checksum.is_none() {
+                match cache::fetch_package_checksum(&package.id, self.config) {
+                    Ok((checksum, checksum_type)) => {
+                        package.checksum = Some(checksum);
+                        package.checksum_type = Some(checksum_type);
+                    }
+                    Err(e) => warn!(
+                        "No checksum available for {}: {e}. The locked download will not be \
+                         verified.",
+                        package.id
+                    ),
+                }
+            }
+            packages.push(LockedPackage::from_metadata(&package)?);
+        }
+
+        let lock = ProjectLock::new(version_request.to_string(), packages);
+        let lock_path = lock_dir.join(LOCK_FILE_NAME);
+        lock.save(&lock_path)?;
+
+        println!("Locked {} in {}", lock.spec, lock_path.display());
+        for package in &lock.packages {
+            println!(
+                "  {}: {}@{} ({})",
+                package.platform(),
+                package.distribution,
+                package.distribution_version,
+                package.id
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Pick one package per target platform. The first target decides the version
+/// and every other target must provide that exact version, so all platforms
+/// run the same release.
+pub(crate) fn resolve_packages(
+    metadata: &MetadataCache,
+    version_request: &VersionRequest,
+    distribution: &Distribution,
+    targets: &[PlatformFilter],
+) -> Result<Vec<JdkMetadata>> {
+    let package_type = version_request.package_type.as_ref();
+    let javafx_bundled = version_request.javafx_bundled;
+    let features = version_request.features.as_slice();
+    let mut packages: Vec<JdkMetadata> = Vec::with_capacity(targets.len());
+
+    for target in targets {
+        let package = match (packages.first(), version_request.track) {
+            (Some(primary), _) => metadata.lookup_for_platform(
+                distribution,
+                &primary.version.to_string(),
+                target,
+                package_type,
+                javafx_bundled,
+                features,
+            ),
+            (None, Some(track)) => {
+                metadata.lookup_track(distribution, track, target, package_type, javafx_bundled)
+            }
+            (None, None) => metadata.lookup_for_platform(
+                distribution,
+                &version_request.version_pattern,
+                target,
+                package_type,
+                javafx_bundled,
+                features,
+            ),
+        };
+
+        let package = package.ok_or_else(|| {
+            let version = packages
+                .first()
+                .map(|primary| primary.version.to_string())
+                .unwrap_or_else(|| version_request.version_pattern.clone());
+            KopiError::VersionNotAvailable(format!(
+                "{}@{version} for {}",
+                distribution.id(),
+                target.label()
+            ))
+        })?;
+        packages.push(package);
+    }
+
+    Ok(packages)
+}
+
+fn current_dir() -> Result<PathBuf> {
+    std::env::current_dir()
+        .map_err(|e| KopiError::SystemError(format!("Failed to get current directory: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::DistributionCache;
+    use crate::models::package::{ArchiveType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::version::Version;
+
+    fn package(version: &str, os: OperatingSystem, arch: Architecture) -> JdkMetadata {
+        JdkMetadata {
+            id: format!("temurin-{version}-{os}-{arch}"),
+            distribution: "temurin".to_string(),
+            version: Version::from_str(version).unwrap(),
+            distribution_version: Version::from_str(version).unwrap(),
+            architecture: arch,
+            operating_system: os,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: None,
+            checksum: None,
+            checksum_type: None,
+            size: 0,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }
+    }
+
+    fn metadata(packages: Vec<JdkMetadata>) -> MetadataCache {
+        let mut cache = MetadataCache::new();
+        cache.distributions.insert(
+            "temurin".to_string(),
+            DistributionCache {
+                distribution: Distribution::Temurin,
+                display_name: "Eclipse Temurin".to_string(),
+                packages,
+            },
+        );
+        cache
+    }
+
+    #[test]
+    fn test_resolve_packages_pins_one_version_across_platforms() {
+        let cache = metadata(vec![
+            package("21.0.5", OperatingSystem::Aix, Architecture::Ppc64),
+            package("21.0.4", OperatingSystem::Aix, Architecture::Ppc64),
+            package("21.0.4", OperatingSystem::Solaris, Architecture::Sparcv9),
+        ]);
+        let request = VersionRequest::from_str("21").unwrap();
+        let aix = PlatformFilter::parse_target("aix-ppc64").unwrap();
+        let solaris = PlatformFilter::parse_target("solaris-sparcv9").unwrap();
+
+        let packages = resolve_packages(
+            &cache,
+            &request,
+            &Distribution::Temurin,
+            std::slice::from_ref(&aix),
+        )
+        .unwrap();
+        assert_eq!(packages[0].version.to_string(), "21.0.5");
+
+        // Solaris has no 21.0.5 build, so the lock cannot cover both
+        let result = resolve_packages(
+            &cache,
+            &request,
+            &Distribution::Temurin,
+            &[aix.clone(), solaris.clone()],
+        );
+        assert!(matches!(result, Err(KopiError::VersionNotAvailable(_))));
+
+        let packages =
+            resolve_packages(&cache, &request, &Distribution::Temurin, &[solaris, aix]).unwrap();
+        assert_eq!(packages.len(), 2);
+        assert!(packages.iter().all(|p| p.version.to_string() == "21.0.4"));
+    }
+
+    #[test]
+    fn test_resolve_packages_for_track() {
+        let cache = metadata(vec![
+            package("21.0.5", OperatingSystem::Aix, Architecture::Ppc64),
+            package("23.0.1", OperatingSystem::Aix, Architecture::Ppc64),
+        ]);
+        let request = VersionRequest::from_str("latest-lts").unwrap();
+        let aix = PlatformFilter::parse_target("aix-ppc64").unwrap();
+
+        let packages = resolve_packages(&cache, &request, &Distribution::Temurin, &[aix]).unwrap();
+        assert_eq!(packages[0].version.to_string(), "21.0.5");
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::StatusReporter;
+use crate::platform::macos;
+use crate::storage::{InstalledJdk, JdkRepository};
+use crate::version::VersionRequest;
+use clap::Subcommand;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum MacosCommand {
+    /// Make a JDK visible to /usr/libexec/java_home and IDEs
+    Register {
+        /// JDK to register (e.g. 21, temurin@21.0.5)
+        #[arg(required_unless_present = "all")]
+        version: Option<String>,
+
+        /// Register every installed JDK and drop registrations of removed ones
+        #[arg(long, conflicts_with = "version")]
+        all: bool,
+    },
+
+    /// Remove a JDK registered with `kopi macos register`
+    Unregister {
+        /// JDK to unregister (e.g. 21, temurin@21.0.5)
+        #[arg(required_unless_present = "all")]
+        version: Option<String>,
+
+        /// Unregister every JDK kopi registered
+        #[arg(long, conflicts_with = "version")]
+        all: bool,
+    },
+}
+
+impl MacosCommand {
+    pub fn execute(&self, config: &KopiConfig, no_progress: bool) -> Result<()> {
+        ensure_macos()?;
+        let status = StatusReporter::new(no_progress);
+        let jvm_dir = macos::user_jvm_dir()?;
+
+        match self {
+            MacosCommand::Register { version, all } => {
+                let registered = if *all {
+                    register_installed(config, &status)?
+                } else {
+                    let jdk = find_jdks(config, version.as_deref().unwrap_or_default())?
+                        .pop()
+                        .expect("find_jdks never returns an empty list");
+                    let bundle = macos::register(&jdk, &jvm_dir)?;
+                    status.success(&format!(
+                        "Registered {}@{} as {}",
+                        jdk.distribution,
+                        jdk.version,
+                        bundle.display()
+                    ));
+                    vec![bundle]
+                };
+                if !registered.is_empty() {
+                    status.step("Run '/usr/libexec/java_home -V' to list registered JDKs");
+                }
+            }
+            MacosCommand::Unregister { version, all } => {
+                let jdks = if *all {
+                    JdkRepository::new(config).list_installed_jdks()?
+                } else {
+                    find_jdks(config, version.as_deref().unwrap_or_default())?
+                };
+
+                let mut removed = 0;
+                for jdk in &jdks {
+                    if macos::unregister(jdk, &jvm_dir)? {
+                        status.success(&format!(
+                            "Unregistered {}@{}",
+                            jdk.distribution, jdk.version
+                        ));
+                        removed += 1;
+                    }
+                }
+                if *all {
+                    removed += macos::prune(&jvm_dir)?.len();
+                }
+                if removed == 0 {
+                    status.step("No registered JDKs matched");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Register every installed JDK with `java_home` and remove stale
+/// registrations, returning the bundles created
+pub fn register_installed(config: &KopiConfig, status: &StatusReporter) -> Result<Vec<PathBuf>> {
+    let jvm_dir = macos::user_jvm_dir()?;
+    for stale in macos::prune(&jvm_dir)? {
+        status.step(&format!("Removed stale registration {}", stale.display()));
+    }
+
+    let mut bundles = Vec::new();
+    for jdk in JdkRepository::new(config).list_installed_jdks()? {
+        let bundle = macos::register(&jdk, &jvm_dir)?;
+        status.success(&format!(
+            "Registered {}@{} as {}",
+            jdk.distribution,
+            jdk.version,
+            bundle.display()
+        ));
+        bundles.push(bundle);
+    }
+
+    if bundles.is_empty() {
+        status.step("No installed JDKs to register");
+    }
+    Ok(bundles)
+}
+
+/// java_home integration only makes sense where `/usr/libexec/java_home` exists
+pub fn ensure_macos() -> Result<()> {
+    if cfg!(target_os = "macos") {
+        Ok(())
+    } else {
+        Err(KopiError::ValidationError(
+            "java_home registration is only available on macOS".to_string(),
+        ))
+    }
+}
+
+/// Installed JDKs matching `version`, oldest first; never empty
+fn find_jdks(config: &KopiConfig, version: &str) -> Result<Vec<InstalledJdk>> {
+    let request = version.parse::<VersionRequest>()?;
+    let jdks = JdkRepository::new(config).find_matching_jdks(&request)?;
+    if jdks.is_empty() {
+        return Err(KopiError::JdkNotInstalled {
+            jdk_spec: version.to_string(),
+            version: Some(request.version_pattern.clone()),
+            distribution: request.distribution.clone(),
+            auto_install_enabled: false,
+            auto_install_failed: None,
+            user_declined: false,
+            install_in_progress: false,
+        });
+    }
+    Ok(jdks)
+}
@@ -0,0 +1,48 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::{KopiError, Result};
+use crate::schema::SchemaDocument;
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum MetadataCommand {
+    /// Print the JSON Schema of an installed JDK's `.meta.json` or of the
+    /// metadata cache
+    Schema {
+        /// `jdk` for `<slug>.meta.json`, `cache` for the metadata cache
+        #[arg(default_value = "jdk")]
+        file: String,
+    },
+}
+
+impl MetadataCommand {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            MetadataCommand::Schema { file } => {
+                let document = match file.as_str() {
+                    "jdk" => SchemaDocument::Jdk,
+                    "cache" => SchemaDocument::Cache,
+                    other => {
+                        return Err(KopiError::ValidationError(format!(
+                            "Unknown metadata file '{other}'; expected 'jdk' or 'cache'"
+                        )));
+                    }
+                };
+                println!("{}", serde_json::to_string_pretty(&document.schema())?);
+                Ok(())
+            }
+        }
+    }
+}
@@ -12,16 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod adopt;
+pub mod audit;
 pub mod cache;
+pub mod config;
 pub mod current;
+pub mod daemon;
 pub mod doctor;
 pub mod env;
+pub mod files;
 pub mod global;
+pub mod init;
 pub mod install;
+pub mod integrate;
+pub mod jlink;
 pub mod list;
 pub mod local;
+pub mod lock;
+pub mod macos;
+pub mod metadata;
+pub mod notify;
+pub mod outdated;
+pub mod overlay;
+pub mod pin_protect;
+pub mod resolve;
+pub mod serve_metadata;
 pub mod setup;
 pub mod shell;
 pub mod shim;
 pub mod uninstall;
+pub mod upgrade;
 pub mod which;
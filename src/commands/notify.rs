@@ -0,0 +1,189 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Patch notifications for pinned JDKs, cheap enough for a shell prompt.
+//!
+//! Checks use only the metadata cache on disk and run at most once per
+//! [`CHECK_INTERVAL`]; the outcome is kept in a state file that prompt
+//! frameworks can read without starting kopi.
+
+use crate::cache::{self, MetadataCache, PlatformFilter};
+use crate::commands::outdated::collect_outdated;
+use crate::config::KopiConfig;
+use crate::error::Result;
+use crate::paths::cache::{ensure_cache_root, metadata_cache_file, notify_state_file};
+use crate::shim::installation_candidates;
+use crate::storage::JdkRepository;
+use crate::version::resolver::VersionResolver;
+use chrono::{DateTime, TimeDelta, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Minimum time between two checks unless `--force` is given
+pub const CHECK_INTERVAL: TimeDelta = TimeDelta::days(1);
+
+/// Outcome of the last check, stored in `cache/notify.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotifyState {
+    pub checked_at: DateTime<Utc>,
+    /// Pinned JDKs with a newer patch in the metadata cache
+    pub updates: Vec<PatchUpdate>,
+}
+
+/// A pinned JDK whose release line has a newer build
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatchUpdate {
+    pub distribution: String,
+    pub current: String,
+    pub latest: String,
+    /// The version file that pins `current`
+    pub pinned_by: PathBuf,
+}
+
+impl PatchUpdate {
+    fn message(&self) -> String {
+        format!(
+            "kopi: {}@{} is available ({} is pinned by {}); run 'kopi install {}@{}'",
+            self.distribution,
+            self.latest,
+            self.current,
+            self.pinned_by.display(),
+            self.distribution,
+            self.latest
+        )
+    }
+}
+
+pub struct NotifyCommand<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> NotifyCommand<'a> {
+    pub fn new(config: &'a KopiConfig) -> Result<Self> {
+        Ok(Self { config })
+    }
+
+    /// Check the pins unless the last check is recent, then print one line
+    /// per available patch unless `quiet`.
+    ///
+    /// Never fails on a missing or unreadable cache so that prompts stay quiet.
+    pub fn execute_check(&self, force: bool, quiet: bool) -> Result<()> {
+        let Some(state) = self.check(Utc::now(), force)? else {
+            debug!("Skipping notification check; last check is recent");
+            return Ok(());
+        };
+        if !quiet {
+            print_updates(&state);
+        }
+        Ok(())
+    }
+
+    /// Print the updates found by the last check without checking again
+    pub fn execute_show(&self) -> Result<()> {
+        if let Some(state) = read_state(self.config.kopi_home()) {
+            print_updates(&state);
+        }
+        Ok(())
+    }
+
+    /// Run a check and record it, or return `None` when one ran within
+    /// [`CHECK_INTERVAL`] of `now` and `force` is not set
+    pub fn check(&self, now: DateTime<Utc>, force: bool) -> Result<Option<NotifyState>> {
+        let kopi_home = self.config.kopi_home();
+        if !force
+            && read_state(kopi_home).is_some_and(|state| now - state.checked_at < CHECK_INTERVAL)
+        {
+            return Ok(None);
+        }
+
+        let state = NotifyState {
+            checked_at: now,
+            updates: match load_cached_metadata(kopi_home) {
+                Some(metadata) => self.find_updates(&metadata)?,
+                None => Vec::new(),
+            },
+        };
+
+        ensure_cache_root(kopi_home)?;
+        fs::write(
+            notify_state_file(kopi_home),
+            serde_json::to_string_pretty(&state)?,
+        )?;
+        Ok(Some(state))
+    }
+
+    /// The project pin and the global pin, each compared with the newest
+    /// build of its release line
+    fn find_updates(&self, metadata: &MetadataCache) -> Result<Vec<PatchUpdate>> {
+        let resolver = VersionResolver::new(self.config);
+        let pins = [
+            resolver.find_project_version().unwrap_or_default(),
+            resolver.get_global_default().unwrap_or_default(),
+        ];
+
+        let repository = JdkRepository::new(self.config);
+        let platform = PlatformFilter::current();
+        let mut updates: Vec<PatchUpdate> = Vec::new();
+        for (request, pinned_by) in pins.into_iter().flatten() {
+            let (_, candidates) = installation_candidates(&repository, &request)?;
+            // A pin nothing satisfies is reported by the shims, not here
+            let Some((jdk, _)) = candidates.into_iter().find(|(_, matches)| *matches) else {
+                continue;
+            };
+            if updates.iter().any(|update| {
+                update.distribution == jdk.distribution && update.current == jdk.version.to_string()
+            }) {
+                continue;
+            }
+
+            for entry in collect_outdated(&repository, &[jdk], metadata, &platform) {
+                if let (true, Some(latest)) = (entry.outdated, entry.latest) {
+                    updates.push(PatchUpdate {
+                        distribution: entry.distribution,
+                        current: entry.current,
+                        latest,
+                        pinned_by: pinned_by.clone(),
+                    });
+                }
+            }
+        }
+        Ok(updates)
+    }
+}
+
+fn load_cached_metadata(kopi_home: &Path) -> Option<MetadataCache> {
+    let cache_path = metadata_cache_file(kopi_home);
+    if !cache_path.exists() {
+        debug!("No metadata cache; nothing to compare pins against");
+        return None;
+    }
+    cache::load_cache(&cache_path)
+        .map_err(|e| debug!("Ignoring unreadable metadata cache: {e}"))
+        .ok()
+}
+
+/// The last recorded check, if any
+pub fn read_state(kopi_home: &Path) -> Option<NotifyState> {
+    fs::read_to_string(notify_state_file(kopi_home))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn print_updates(state: &NotifyState) {
+    for update in &state.updates {
+        println!("{}", update.message());
+    }
+}
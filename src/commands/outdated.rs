@@ -0,0 +1,346 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::cache::{self, MetadataCache, PlatformFilter};
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::models::distribution::Distribution;
+use crate::models::package::PackageType;
+use crate::storage::{InstalledJdk, JdkRepository};
+use crate::version::Version;
+use crate::version::file::VersionTrack;
+use comfy_table::{Cell, Color, Table};
+use log::debug;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+/// Exit status when at least one installed release line has a newer build
+pub const UPDATES_AVAILABLE_EXIT_CODE: i32 = 10;
+
+/// One distribution/major release line with its newest installed build
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutdatedEntry {
+    pub distribution: String,
+    pub major: u32,
+    pub package_type: PackageType,
+    pub javafx_bundled: bool,
+    pub current: String,
+    /// Newest build in the metadata cache; `None` when the line is not listed
+    pub latest: Option<String>,
+    pub outdated: bool,
+    /// Distinct cached builds newer than `current`, up to `latest`
+    pub releases_behind: usize,
+}
+
+pub struct OutdatedCommand<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> OutdatedCommand<'a> {
+    pub fn new(config: &'a KopiConfig) -> Result<Self> {
+        Ok(Self { config })
+    }
+
+    pub fn execute(&self, json: bool) -> Result<()> {
+        let repository = JdkRepository::new(self.config);
+        let installed = repository.list_installed_jdks()?;
+        if installed.is_empty() {
+            if json {
+                println!("[]");
+            } else {
+                println!("No JDKs installed");
+            }
+            return Ok(());
+        }
+
+        let metadata = cache::get_fresh_metadata(self.config)?;
+        let entries = collect_outdated(
+            &repository,
+            &installed,
+            &metadata,
+            &PlatformFilter::current(),
+        );
+
+        if json {
+            let output = serde_json::to_string_pretty(&entries).map_err(|e| {
+                KopiError::SystemError(format!("Failed to serialize outdated report: {e}"))
+            })?;
+            println!("{output}");
+        } else {
+            print_table(&entries);
+        }
+
+        if entries.iter().any(|entry| entry.outdated) {
+            std::process::exit(UPDATES_AVAILABLE_EXIT_CODE);
+        }
+        Ok(())
+    }
+}
+
+/// Compare the newest installed build of each release line with the newest
+/// build in the metadata cache
+pub fn collect_outdated(
+    repository: &JdkRepository,
+    installed: &[InstalledJdk],
+    metadata: &MetadataCache,
+    platform: &PlatformFilter,
+) -> Vec<OutdatedEntry> {
+    let mut newest: BTreeMap<(String, u32, PackageType, bool), &InstalledJdk> = BTreeMap::new();
+    for jdk in installed {
+        let key = (
+            jdk.distribution.clone(),
+            jdk.version.major(),
+            installed_package_type(repository, jdk),
+            jdk.javafx_bundled,
+        );
+        newest
+            .entry(key)
+            .and_modify(|current| {
+                if VersionTrack::compare(&jdk.version, &current.version) == Ordering::Greater {
+                    *current = jdk;
+                }
+            })
+            .or_insert(jdk);
+    }
+
+    newest
+        .into_iter()
+        .map(
+            |((distribution, major, package_type, javafx_bundled), jdk)| {
+                let dist = Distribution::from_str(&distribution).ok();
+                let latest = dist
+                    .as_ref()
+                    .and_then(|dist| {
+                        metadata.lookup_latest_build(
+                            dist,
+                            major,
+                            platform,
+                            Some(&package_type),
+                            Some(javafx_bundled),
+                            jdk.version.is_early_access(),
+                        )
+                    })
+                    .map(|package| package.distribution_version);
+                let releases_behind = match (&dist, &latest) {
+                    (Some(dist), Some(latest)) => {
+                        let newer: BTreeSet<String> = metadata
+                            .builds_in_line(
+                                dist,
+                                major,
+                                platform,
+                                Some(&package_type),
+                                Some(javafx_bundled),
+                                jdk.version.is_early_access(),
+                            )
+                            .into_iter()
+                            .map(|package| &package.distribution_version)
+                            .filter(|version| {
+                                is_newer(version, &jdk.version) && !is_newer(version, latest)
+                            })
+                            .map(Version::to_string)
+                            .collect();
+                        newer.len()
+                    }
+                    _ => 0,
+                };
+                debug!(
+                    "{distribution} {major}: installed {}, latest {latest:?}",
+                    jdk.version
+                );
+
+                OutdatedEntry {
+                    outdated: latest
+                        .as_ref()
+                        .is_some_and(|latest| is_newer(latest, &jdk.version)),
+                    distribution,
+                    major,
+                    package_type,
+                    javafx_bundled,
+                    current: jdk.version.to_string(),
+                    latest: latest.map(|version| version.to_string()),
+                    releases_behind,
+                }
+            },
+        )
+        .collect()
+}
+
+fn is_newer(latest: &Version, current: &Version) -> bool {
+    VersionTrack::compare(latest, current) == Ordering::Greater
+}
+
+/// Package type recorded at install time; installs without metadata are JDKs
+fn installed_package_type(repository: &JdkRepository, jdk: &InstalledJdk) -> PackageType {
+    repository
+        .load_installed_metadata(jdk)
+        .ok()
+        .and_then(|snapshot| snapshot.metadata)
+        .and_then(|metadata| PackageType::from_str(&metadata.package.package_type).ok())
+        .unwrap_or(PackageType::Jdk)
+}
+
+/// Distribution of a release line, tagged `(jre)` and `+fx` when applicable
+pub(crate) fn line_label(entry: &OutdatedEntry) -> String {
+    let mut distribution = entry.distribution.clone();
+    if entry.package_type == PackageType::Jre {
+        distribution.push_str(" (jre)");
+    }
+    if entry.javafx_bundled {
+        distribution.push_str(" +fx");
+    }
+    distribution
+}
+
+pub(crate) fn status_cell(entry: &OutdatedEntry) -> Cell {
+    match (&entry.latest, entry.outdated) {
+        (None, _) => Cell::new("unknown").fg(Color::DarkGrey),
+        (Some(_), true) => Cell::new("update available").fg(Color::Yellow),
+        (Some(_), false) => Cell::new("up to date").fg(Color::Green),
+    }
+}
+
+fn print_table(entries: &[OutdatedEntry]) {
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec![
+        Cell::new("Distribution"),
+        Cell::new("Major"),
+        Cell::new("Current"),
+        Cell::new("Latest"),
+        Cell::new("Status"),
+    ]);
+
+    for entry in entries {
+        table.add_row(vec![
+            Cell::new(line_label(entry)),
+            Cell::new(entry.major),
+            Cell::new(&entry.current),
+            Cell::new(entry.latest.as_deref().unwrap_or("-")),
+            status_cell(entry),
+        ]);
+    }
+
+    println!("{table}");
+
+    let outdated = entries.iter().filter(|entry| entry.outdated).count();
+    if outdated > 0 {
+        println!();
+        println!("{outdated} release line(s) have newer builds. Install them with 'kopi install'.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::DistributionCache;
+    use crate::models::metadata::JdkMetadata;
+    use crate::models::package::ArchiveType;
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn package(version: &str, latest_build_available: Option<bool>) -> JdkMetadata {
+        JdkMetadata {
+            id: format!("temurin-{version}"),
+            distribution: "temurin".to_string(),
+            version: Version::from_str(version).unwrap(),
+            distribution_version: Version::from_str(version).unwrap(),
+            architecture: Architecture::Ppc64,
+            operating_system: OperatingSystem::Aix,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: None,
+            checksum: None,
+            checksum_type: None,
+            size: 0,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available,
+            release_date: None,
+        }
+    }
+
+    fn metadata(packages: Vec<JdkMetadata>) -> MetadataCache {
+        let mut cache = MetadataCache::new();
+        cache.distributions.insert(
+            "temurin".to_string(),
+            DistributionCache {
+                distribution: Distribution::Temurin,
+                display_name: "Eclipse Temurin".to_string(),
+                packages,
+            },
+        );
+        cache
+    }
+
+    #[test]
+    fn test_collect_outdated() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let repository = JdkRepository::new(&config);
+        let jdks_dir = config.jdks_dir().unwrap();
+        for dir in [
+            "temurin-21.0.4",
+            "temurin-21.0.3",
+            "temurin-17.0.9",
+            "zulu-11.0.2",
+        ] {
+            fs::create_dir_all(jdks_dir.join(dir)).unwrap();
+        }
+        let installed = repository.list_installed_jdks().unwrap();
+
+        let cache = metadata(vec![
+            package("21.0.5", Some(true)),
+            package("21.0.4", Some(false)),
+            package("17.0.9", None),
+            package("17.0.8", None),
+        ]);
+        let platform = PlatformFilter::parse_target("aix-ppc64").unwrap();
+        let entries = collect_outdated(&repository, &installed, &cache, &platform);
+
+        assert_eq!(entries.len(), 3);
+        let temurin_17 = &entries[0];
+        assert_eq!((temurin_17.major, temurin_17.outdated), (17, false));
+        assert_eq!(temurin_17.latest.as_deref(), Some("17.0.9"));
+
+        let temurin_21 = &entries[1];
+        assert_eq!(temurin_21.current, "21.0.4");
+        assert_eq!(temurin_21.latest.as_deref(), Some("21.0.5"));
+        assert!(temurin_21.outdated);
+        assert_eq!(temurin_21.releases_behind, 1);
+
+        let zulu = &entries[2];
+        assert_eq!(zulu.latest, None);
+        assert!(!zulu.outdated);
+    }
+
+    #[test]
+    fn test_latest_build_flag_wins_over_newer_version() {
+        // A newer build without the flag (e.g. a withdrawn release) is ignored
+        let cache = metadata(vec![
+            package("21.0.6", Some(false)),
+            package("21.0.5", Some(true)),
+        ]);
+        let platform = PlatformFilter::parse_target("aix-ppc64").unwrap();
+        let latest = cache
+            .lookup_latest_build(&Distribution::Temurin, 21, &platform, None, None, false)
+            .unwrap();
+        assert_eq!(latest.distribution_version.to_string(), "21.0.5");
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::StatusReporter;
+use crate::installation::overlay::{Overlay, apply_overlay};
+use crate::storage::JdkRepository;
+use crate::version::VersionRequest;
+use log::info;
+use std::str::FromStr;
+
+/// Re-applies `install.certificates` and `install.security_properties` to
+/// JDKs installed before the configuration changed
+pub struct OverlayCommand<'a> {
+    config: &'a KopiConfig,
+    no_progress: bool,
+}
+
+impl<'a> OverlayCommand<'a> {
+    pub fn new(config: &'a KopiConfig, no_progress: bool) -> Result<Self> {
+        Ok(Self {
+            config,
+            no_progress,
+        })
+    }
+
+    /// Apply the overlay to the JDKs matching `version_spec`, or to every
+    /// installed JDK. Adopted and system JDKs are left untouched.
+    pub fn execute(&self, version_spec: Option<&str>) -> Result<()> {
+        let repository = JdkRepository::new(self.config);
+        let jdks = match version_spec {
+            Some(spec) => {
+                let request = VersionRequest::from_str(spec)?;
+                let jdks = repository.find_matching_jdks(&request)?;
+                if jdks.is_empty() {
+                    return Err(KopiError::JdkNotInstalled {
+                        jdk_spec: spec.to_string(),
+                        version: Some(request.version_pattern.clone()),
+                        distribution: request.distribution.clone(),
+                        auto_install_enabled: false,
+                        auto_install_failed: None,
+                        user_declined: false,
+                        install_in_progress: false,
+                    });
+                }
+                jdks
+            }
+            None => repository.list_installed_jdks()?,
+        };
+
+        let status = StatusReporter::new(self.no_progress);
+        for jdk in &jdks {
+            let name = format!("{}@{}", jdk.distribution, jdk.version);
+            if repository.is_external(jdk) || repository.is_system(jdk) {
+                status.step(&format!("Skipped {name}: not managed by kopi"));
+                continue;
+            }
+
+            info!("Applying the install overlay to {name}");
+            let overlay = Overlay::for_distribution(self.config, &jdk.distribution);
+            let summary = apply_overlay(&jdk.resolve_java_home(), &overlay)?;
+            // Keep `kopi doctor --deep` from reporting the edited files
+            if repository.load_install_manifest(jdk)?.is_some() {
+                repository.save_install_manifest(&jdk.path)?;
+            }
+
+            status.success(&format!(
+                "Updated {name}: {} certificates, {} security properties",
+                summary.certificates.len(),
+                summary.properties
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::install;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_overlay_updates_installed_jdks() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        config.install.security_properties = BTreeMap::from([(
+            "jdk.tls.client.protocols".to_string(),
+            "TLSv1.3".to_string(),
+        )]);
+
+        let jdk_dir = install::installation_directory(temp_dir.path(), "temurin-21.0.5");
+        fs::create_dir_all(jdk_dir.join("conf/security")).unwrap();
+        fs::write(jdk_dir.join("conf/security/java.security"), "a=1\n").unwrap();
+        fs::write(
+            install::metadata_file(temp_dir.path(), "temurin-21.0.5"),
+            "{}",
+        )
+        .unwrap();
+
+        let command = OverlayCommand::new(&config, true).unwrap();
+        command.execute(None).unwrap();
+        assert!(
+            fs::read_to_string(jdk_dir.join("conf/security/java.security"))
+                .unwrap()
+                .contains("jdk.tls.client.protocols=TLSv1.3\n")
+        );
+
+        assert!(matches!(
+            command.execute(Some("zulu@11")),
+            Err(KopiError::JdkNotInstalled { .. })
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::StatusReporter;
+use crate::storage::JdkRepository;
+use crate::version::VersionRequest;
+use log::info;
+use std::str::FromStr;
+
+/// Protects installed JDKs from `uninstall`, `uninstall --unused`, and
+/// `upgrade` until `--force` is given
+pub struct PinProtectCommand<'a> {
+    config: &'a KopiConfig,
+    no_progress: bool,
+}
+
+impl<'a> PinProtectCommand<'a> {
+    pub fn new(config: &'a KopiConfig, no_progress: bool) -> Result<Self> {
+        Ok(Self {
+            config,
+            no_progress,
+        })
+    }
+
+    /// Protect every installed JDK matching `version_spec`, or lift the
+    /// protection when `remove` is set
+    pub fn execute(&self, version_spec: &str, remove: bool) -> Result<()> {
+        let request = VersionRequest::from_str(version_spec)?;
+        let repository = JdkRepository::new(self.config);
+        let jdks = repository.find_matching_jdks(&request)?;
+        if jdks.is_empty() {
+            return Err(KopiError::JdkNotInstalled {
+                jdk_spec: version_spec.to_string(),
+                version: Some(request.version_pattern.clone()),
+                distribution: request.distribution.clone(),
+                auto_install_enabled: false,
+                auto_install_failed: None,
+                user_declined: false,
+                install_in_progress: false,
+            });
+        }
+
+        let status = StatusReporter::new(self.no_progress);
+        for jdk in &jdks {
+            info!(
+                "{} {}@{}",
+                if remove { "Unprotecting" } else { "Protecting" },
+                jdk.distribution,
+                jdk.version
+            );
+            repository.set_protected(jdk, !remove)?;
+            status.success(&format!(
+                "{} {}@{}",
+                if remove { "Unprotected" } else { "Protected" },
+                jdk.distribution,
+                jdk.version
+            ));
+        }
+
+        if !remove {
+            status.step("uninstall and upgrade now skip it unless --force is given");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::install;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pin_protect_marks_matching_jdks() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        for slug in ["temurin-17.0.9", "temurin-21.0.5"] {
+            fs::create_dir_all(install::installation_directory(temp_dir.path(), slug)).unwrap();
+            fs::write(install::metadata_file(temp_dir.path(), slug), "{}").unwrap();
+        }
+
+        let command = PinProtectCommand::new(&config, true).unwrap();
+        command.execute("temurin@17", false).unwrap();
+
+        let repository = JdkRepository::new(&config);
+        let protected: Vec<String> = repository
+            .list_installed_jdks()
+            .unwrap()
+            .iter()
+            .filter(|jdk| repository.is_protected(jdk))
+            .map(|jdk| jdk.version.to_string())
+            .collect();
+        assert_eq!(protected, vec!["17.0.9"]);
+
+        command.execute("temurin@17", true).unwrap();
+        let jdks = repository.list_installed_jdks().unwrap();
+        assert!(jdks.iter().all(|jdk| !repository.is_protected(jdk)));
+
+        assert!(matches!(
+            command.execute("zulu@11", false),
+            Err(KopiError::JdkNotInstalled { .. })
+        ));
+    }
+}
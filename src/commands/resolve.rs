@@ -0,0 +1,208 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commands::which::format_source;
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::platform::with_executable_extension;
+use crate::shim::external;
+use crate::shim::installation_candidates;
+use crate::shim::security::SecurityValidator;
+use crate::storage::JdkRepository;
+use crate::version::resolver::VersionResolver;
+use colored::*;
+use serde::Serialize;
+
+/// Every step a shim takes to pick the executable for a tool
+#[derive(Debug, Default, Serialize)]
+struct ResolveTrace {
+    tool: String,
+    /// `jdk` for tools shipped in the JDK, `external` for registered tools
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_kind: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version_source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version_request: Option<String>,
+    /// The request with the default distribution applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effective_request: Option<String>,
+    candidates: Vec<CandidateTrace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chosen: Option<CandidateTrace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_path: Option<String>,
+    /// Why resolution stopped, if it did not reach a tool path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CandidateTrace {
+    distribution: String,
+    version: String,
+    path: String,
+    matches: bool,
+}
+
+/// Performs a shim's resolution for a tool without running it
+pub struct ResolveCommand<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> ResolveCommand<'a> {
+    pub fn new(config: &'a KopiConfig) -> Result<Self> {
+        Ok(Self { config })
+    }
+
+    /// Print each resolution step for `tool`. Fails with the shim's error
+    /// when resolution stops early; the trace up to that point is still shown.
+    pub fn execute(&self, tool: &str, json: bool) -> Result<()> {
+        let mut trace = ResolveTrace {
+            tool: tool.to_string(),
+            ..Default::default()
+        };
+        let result = self.resolve(tool, &mut trace);
+        if let Err(e) = &result {
+            trace.error = Some(e.to_string());
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&trace)?);
+        } else {
+            print_trace(&trace);
+        }
+        result
+    }
+
+    /// Mirror of the shim's steps, recording each outcome in `trace`
+    fn resolve(&self, tool: &str, trace: &mut ResolveTrace) -> Result<()> {
+        let security_validator = SecurityValidator::new(self.config);
+
+        let external_tool = external::find_external_tool(self.config.kopi_home(), tool);
+        if external_tool.is_none() {
+            security_validator.validate_tool(tool)?;
+        }
+        trace.tool_kind = Some(if external_tool.is_some() {
+            "external"
+        } else {
+            "jdk"
+        });
+
+        let resolver = VersionResolver::new(self.config);
+        let (version_request, version_source) = resolver.resolve_tool_version(tool)?;
+        trace.version_source = Some(format_source(&version_source));
+        trace.version_request = Some(version_request.to_string());
+
+        security_validator.validate_version(&version_request.version_pattern)?;
+        if let Some(dist) = &version_request.distribution {
+            security_validator.validate_version(dist)?;
+        }
+
+        let repository = JdkRepository::new(self.config);
+        let (request, candidates) = installation_candidates(&repository, &version_request)?;
+        trace.effective_request = Some(request.to_string());
+        trace.candidates = candidates
+            .iter()
+            .map(|(jdk, matches)| CandidateTrace {
+                distribution: jdk.distribution.clone(),
+                version: jdk.version.to_string(),
+                path: jdk.path.display().to_string(),
+                matches: *matches,
+            })
+            .collect();
+
+        let Some((jdk, _)) = candidates.into_iter().find(|(_, matches)| *matches) else {
+            let distribution = request.distribution.clone().unwrap_or_default();
+            return Err(KopiError::JdkNotInstalled {
+                jdk_spec: request.to_string(),
+                version: Some(request.version_pattern.clone()),
+                distribution: Some(distribution),
+                auto_install_enabled: false,
+                auto_install_failed: None,
+                user_declined: false,
+                install_in_progress: false,
+            });
+        };
+        trace.chosen = trace.candidates.iter().find(|c| c.matches).cloned();
+
+        let tool_path = match external_tool {
+            Some(executable) => executable,
+            None => {
+                let tool_path = jdk
+                    .resolve_bin_path()?
+                    .join(with_executable_extension(tool));
+                if !tool_path.exists() {
+                    return Err(KopiError::ToolNotFound {
+                        tool: tool.to_string(),
+                        jdk_path: jdk.path.display().to_string(),
+                        available_tools: Vec::new(),
+                    });
+                }
+                security_validator.validate_path(&tool_path)?;
+                tool_path
+            }
+        };
+        trace.tool_path = Some(tool_path.display().to_string());
+        Ok(())
+    }
+}
+
+fn print_trace(trace: &ResolveTrace) {
+    let step = |label: &str, value: &str| println!("{:<18} {value}", format!("{label}:"));
+
+    let kind = match trace.tool_kind {
+        Some("external") => " (external tool)",
+        Some(_) => " (JDK tool)",
+        None => "",
+    };
+    step("Tool", &format!("{}{kind}", trace.tool));
+    if let Some(source) = &trace.version_source {
+        step("Version source", source);
+    }
+    if let Some(request) = &trace.version_request {
+        step("Version request", request);
+    }
+    if let Some(request) = &trace.effective_request {
+        step("Matching", request);
+        if trace.candidates.is_empty() {
+            step("Candidates", "no JDKs installed");
+        } else {
+            println!("Candidates:");
+            for candidate in &trace.candidates {
+                let marker = if candidate.matches {
+                    "✓".green()
+                } else {
+                    "✗".red()
+                };
+                println!(
+                    "  {marker} {}@{}  {}",
+                    candidate.distribution, candidate.version, candidate.path
+                );
+            }
+        }
+    }
+    if let Some(chosen) = &trace.chosen {
+        step(
+            "Chosen JDK",
+            &format!("{}@{}", chosen.distribution, chosen.version),
+        );
+    }
+    if let Some(tool_path) = &trace.tool_path {
+        step("Tool path", tool_path);
+    }
+    if let Some(error) = &trace.error {
+        step("Stopped", &error.red().to_string());
+    }
+}
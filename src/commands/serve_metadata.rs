@@ -0,0 +1,79 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::cache;
+use crate::config::KopiConfig;
+use crate::download::DownloadCache;
+use crate::error::{KopiError, Result};
+use crate::metadata::MetadataMirror;
+use log::warn;
+use std::net::TcpListener;
+
+/// Serves the local metadata cache to other machines as an HTTP metadata source
+pub struct ServeMetadataCommand<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> ServeMetadataCommand<'a> {
+    pub fn new(config: &'a KopiConfig) -> Result<Self> {
+        Ok(Self { config })
+    }
+
+    /// Serve on `bind` until interrupted. With `downloads_url`, cached
+    /// archives are served too and advertised under that base URL.
+    pub fn execute(&self, bind: &str, downloads_url: Option<&str>) -> Result<()> {
+        let cache_path = self.config.metadata_cache_path()?;
+        if !cache_path.exists() {
+            return Err(KopiError::CacheNotFound);
+        }
+        let metadata = cache::load_cache(&cache_path)?;
+
+        if let Some(url) = downloads_url
+            && !url.starts_with("https://")
+        {
+            warn!(
+                "Kopi only downloads JDKs over HTTPS; serve {url} through a TLS-terminating proxy \
+                 so clients can use the mirrored archives"
+            );
+        }
+        // Serve whatever is cached even when the cache no longer stores new archives
+        let download_cache = downloads_url.map(|_| {
+            DownloadCache::from_config(self.config)
+                .unwrap_or_else(|| DownloadCache::new(self.config.download_cache_dir(), None))
+        });
+        let mirror = MetadataMirror::new(&metadata, download_cache.as_ref().zip(downloads_url))?;
+
+        let listener = TcpListener::bind(bind)
+            .map_err(|e| KopiError::SystemError(format!("Failed to listen on {bind}: {e}")))?;
+        let address = listener.local_addr()?;
+        println!(
+            "Serving {} packages from {} on http://{address}",
+            mirror.packages,
+            cache_path.display()
+        );
+        if download_cache.is_some() {
+            println!("  {} cached archives under /downloads/", mirror.archives);
+        }
+        if mirror.incomplete > 0 {
+            println!(
+                "  Skipped {} packages without a download URL; install them once on this machine \
+                 or publish full metadata with kopi-metadata-gen",
+                mirror.incomplete
+            );
+        }
+        println!("Press Ctrl-C to stop");
+
+        mirror.run(listener)
+    }
+}
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::commands::macos;
 use crate::config::KopiConfig;
 use crate::error::Result;
 use crate::indicator::StatusReporter;
+use crate::paths::home;
 use crate::platform::file_ops::make_executable;
 use crate::platform::shell::{Shell, detect_shell};
 use crate::platform::shim_binary_name;
@@ -27,9 +29,22 @@ use std::path::{Path, PathBuf};
 #[cfg(debug_assertions)]
 use std::process::Command;
 
+/// Exit status of `kopi setup --check` when setup is incomplete
+pub const SETUP_INCOMPLETE_EXIT_CODE: i32 = 12;
+
+/// Outcome of one `kopi setup --check` item
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupCheck {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
 pub struct SetupCommand<'a> {
     config: &'a KopiConfig,
     status: StatusReporter,
+    non_interactive: bool,
+    register_java_home: bool,
 }
 
 impl<'a> SetupCommand<'a> {
@@ -37,10 +52,131 @@ impl<'a> SetupCommand<'a> {
         Ok(Self {
             config,
             status: StatusReporter::new(no_progress),
+            non_interactive: false,
+            register_java_home: false,
         })
     }
 
+    /// Never prompt and print plain output suitable for provisioning
+    /// scripts and Dockerfiles
+    pub fn with_non_interactive(mut self, non_interactive: bool) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Also register installed JDKs with macOS `/usr/libexec/java_home`
+    pub fn with_java_home_registration(mut self, register: bool) -> Self {
+        self.register_java_home = register;
+        self
+    }
+
+    /// Report whether setup is complete without changing anything, exiting
+    /// with [`SETUP_INCOMPLETE_EXIT_CODE`] when it is not
+    pub fn execute_check(&self) -> Result<()> {
+        let checks = self.collect_checks()?;
+        for check in &checks {
+            let mark = if check.ok { "✓".green() } else { "✗".red() };
+            println!("{mark} {}: {}", check.name, check.detail);
+        }
+
+        if checks.iter().any(|check| !check.ok) {
+            println!("\nSetup is incomplete. Run 'kopi setup' to fix it.");
+            std::process::exit(SETUP_INCOMPLETE_EXIT_CODE);
+        }
+        println!("\nSetup is complete");
+        Ok(())
+    }
+
+    /// Inspect directories, the shim binary, default shims and PATH
+    pub fn collect_checks(&self) -> Result<Vec<SetupCheck>> {
+        let mut checks = Vec::new();
+        // The config accessors create directories, so use the bare paths
+        let kopi_home = self.config.kopi_home();
+        let bin_dir = home::bin_dir(kopi_home);
+        let shims_dir = home::shims_dir(kopi_home);
+
+        let dirs = [
+            kopi_home.to_path_buf(),
+            home::jdks_dir(kopi_home),
+            bin_dir.clone(),
+            shims_dir.clone(),
+            home::cache_dir(kopi_home),
+        ];
+        let missing: Vec<String> = dirs
+            .iter()
+            .filter(|dir| !dir.is_dir())
+            .map(|dir| dir.display().to_string())
+            .collect();
+        checks.push(SetupCheck {
+            name: "Directories".to_string(),
+            ok: missing.is_empty(),
+            detail: if missing.is_empty() {
+                "all present".to_string()
+            } else {
+                format!("missing {}", missing.join(", "))
+            },
+        });
+
+        let shim_binary = bin_dir.join(shim_binary_name());
+        let shim_binary_ok = shim_binary.is_file();
+        checks.push(SetupCheck {
+            name: "kopi-shim binary".to_string(),
+            ok: shim_binary_ok,
+            detail: if shim_binary_ok {
+                shim_binary.display().to_string()
+            } else {
+                format!("not found at {}", shim_binary.display())
+            },
+        });
+
+        let installer = ShimInstaller::new(self.config.kopi_home())
+            .with_wrappers(&self.config.shims.windows_wrappers);
+        let installed = installer.list_shims()?;
+        let broken: Vec<String> = installer
+            .verify_shims()?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        let problems: Vec<String> = default_shim_tools()
+            .into_iter()
+            .filter_map(|tool| {
+                if !installed.iter().any(|name| name == tool) {
+                    Some(format!("{tool} (missing)"))
+                } else if broken.iter().any(|name| name == tool) {
+                    Some(format!("{tool} (broken)"))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        checks.push(SetupCheck {
+            name: "Default shims".to_string(),
+            ok: problems.is_empty(),
+            detail: if problems.is_empty() {
+                default_shim_tools().join(", ")
+            } else {
+                problems.join(", ")
+            },
+        });
+
+        let in_path = self.shims_dir_in_path();
+        checks.push(SetupCheck {
+            name: "PATH".to_string(),
+            ok: in_path,
+            detail: if in_path {
+                format!("{} is on PATH", shims_dir.display())
+            } else {
+                format!("{} is not on PATH", shims_dir.display())
+            },
+        });
+
+        Ok(checks)
+    }
+
     pub fn execute(&self, force: bool) -> Result<()> {
+        if self.register_java_home {
+            macos::ensure_macos()?;
+        }
         self.status.operation("Setting up", "Kopi");
 
         // Step 1: Create directories
@@ -52,7 +188,14 @@ impl<'a> SetupCommand<'a> {
         // Step 3: Install default shims
         self.install_default_shims(force)?;
 
-        // Step 4: Generate PATH update instructions
+        // Step 4: Register JDKs with java_home when asked
+        if self.register_java_home {
+            self.status
+                .step("Registering JDKs with /usr/libexec/java_home");
+            macos::register_installed(self.config, &self.status)?;
+        }
+
+        // Step 5: Generate PATH update instructions
         self.show_path_instructions()?;
 
         self.status.success("Setup completed successfully!");
@@ -196,13 +339,27 @@ impl<'a> SetupCommand<'a> {
     fn install_default_shims(&self, force: bool) -> Result<()> {
         self.status.step("Installing default shims");
 
-        let installer = ShimInstaller::new(self.config.kopi_home());
+        let installer = ShimInstaller::new(self.config.kopi_home())
+            .with_wrappers(&self.config.shims.windows_wrappers);
+        let existing = installer.list_shims()?;
 
         // Get core tools that should be installed by default
         let core_tools = default_shim_tools();
 
         for tool_name in core_tools {
-            match installer.create_shim(tool_name) {
+            // Re-running setup leaves existing shims alone unless forced
+            let exists = existing.iter().any(|name| name == tool_name);
+            if exists && !force {
+                self.status.step(&format!("✓ {tool_name} (exists)"));
+                continue;
+            }
+
+            let result = if exists {
+                installer.repair_shim(tool_name)
+            } else {
+                installer.create_shim(tool_name)
+            };
+            match result {
                 Ok(_) => self.status.step(&format!("✓ {tool_name}")),
                 Err(e) => {
                     if !force {
@@ -217,22 +374,36 @@ impl<'a> SetupCommand<'a> {
         Ok(())
     }
 
+    fn shims_dir_in_path(&self) -> bool {
+        let Ok(shims_dir) = home::shims_dir(self.config.kopi_home()).canonicalize() else {
+            return false;
+        };
+
+        env::var_os("PATH").is_some_and(|path_env| {
+            // Normalize paths for comparison
+            env::split_paths(&path_env).any(|p| p.canonicalize().ok().as_ref() == Some(&shims_dir))
+        })
+    }
+
     fn show_path_instructions(&self) -> Result<()> {
         let shims_dir = self.config.shims_dir()?;
 
         // Check if shims directory is already in PATH
-        if let Ok(path_env) = env::var("PATH") {
-            let is_in_path = env::split_paths(&path_env).any(|p| {
-                // Normalize paths for comparison
-                p.canonicalize().ok() == shims_dir.canonicalize().ok()
-            });
-
-            if is_in_path {
-                println!("\n{}", "PATH is already configured ✓".green().bold());
-                println!("The shims directory is already in your PATH:");
-                println!("  {}", shims_dir.display().to_string().bold());
+        if self.shims_dir_in_path() {
+            if self.non_interactive {
+                println!("PATH already contains {}", shims_dir.display());
                 return Ok(());
             }
+            println!("\n{}", "PATH is already configured ✓".green().bold());
+            println!("The shims directory is already in your PATH:");
+            println!("  {}", shims_dir.display().to_string().bold());
+            return Ok(());
+        }
+
+        // Scripts only need the directory, not a walkthrough
+        if self.non_interactive {
+            println!("Add {} to PATH", shims_dir.display());
+            return Ok(());
         }
 
         // If not in PATH, show configuration instructions
@@ -324,7 +495,7 @@ impl<'a> SetupCommand<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::paths::{cache as cache_paths, home};
+    use crate::paths::cache as cache_paths;
     use tempfile::TempDir;
 
     #[test]
@@ -334,6 +505,8 @@ mod tests {
         let setup = SetupCommand {
             config: &config,
             status: StatusReporter::new(true), // Use silent mode for tests
+            non_interactive: false,
+            register_java_home: false,
         };
 
         setup.create_directories().unwrap();
@@ -351,10 +524,35 @@ mod tests {
         let setup = SetupCommand {
             config: &config,
             status: StatusReporter::new(true), // Use silent mode for tests
+            non_interactive: false,
+            register_java_home: false,
         };
 
         // This should not fail even if shell detection fails
         let result = setup.show_path_instructions();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_collect_checks_reports_incomplete_setup() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let setup = SetupCommand::new(&config, true).unwrap();
+
+        let checks = setup.collect_checks().unwrap();
+        let names: Vec<&str> = checks.iter().map(|check| check.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Directories", "kopi-shim binary", "Default shims", "PATH"]
+        );
+        assert!(!checks[1].ok);
+        assert!(!checks[2].ok);
+        assert!(checks[2].detail.contains("java (missing)"));
+        // Checking must not create anything
+        assert!(!home::shims_dir(temp_dir.path()).exists());
+
+        setup.create_directories().unwrap();
+        let checks = setup.collect_checks().unwrap();
+        assert!(checks[0].ok, "{}", checks[0].detail);
+    }
 }
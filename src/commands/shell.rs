@@ -18,11 +18,83 @@ use crate::installation::auto::{AutoInstaller, InstallationResult};
 use crate::platform::process::launch_shell_with_env;
 use crate::platform::shell::{Shell, detect_shell, find_shell_in_path};
 use crate::storage::JdkRepository;
-use crate::version::VersionRequest;
+use crate::version::{SYSTEM_VERSION, VersionRequest};
 use log::{debug, info};
+use std::env;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+const VERSION_ENV: &str = "KOPI_JAVA_VERSION";
+
+/// Versions replaced by nested `kopi shell` invocations, oldest first
+pub const SHELL_STACK_ENV: &str = "KOPI_SHELL_STACK";
+
+const STACK_SEPARATOR: char = ',';
+
+/// Stack entry for a shell that had no `KOPI_JAVA_VERSION`
+const NO_VERSION_ENTRY: &str = "-";
+
+/// The shell versions `kopi shell --restore` can return to. Each launched
+/// shell carries the stack in `KOPI_SHELL_STACK`, so it survives nesting
+/// without any state on disk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShellStack {
+    entries: Vec<Option<String>>,
+}
+
+impl ShellStack {
+    pub fn parse(value: &str) -> Self {
+        let entries = value
+            .split(STACK_SEPARATOR)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| (entry != NO_VERSION_ENTRY).then(|| entry.to_string()))
+            .collect();
+        Self { entries }
+    }
+
+    pub fn from_env() -> Self {
+        env::var(SHELL_STACK_ENV)
+            .map(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+
+    pub fn push(&mut self, version: Option<String>) {
+        self.entries.push(version);
+    }
+
+    /// Remove the most recent entry; the inner `None` means the shell had
+    /// no version set
+    pub fn pop(&mut self) -> Option<Option<String>> {
+        self.entries.pop()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries from the most recent to the oldest
+    pub fn iter_recent(&self) -> impl Iterator<Item = Option<&str>> {
+        self.entries.iter().rev().map(|entry| entry.as_deref())
+    }
+
+    /// The value for `KOPI_SHELL_STACK`, or `None` when the stack is empty
+    pub fn to_env_value(&self) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let entries: Vec<&str> = self
+            .entries
+            .iter()
+            .map(|entry| entry.as_deref().unwrap_or(NO_VERSION_ENTRY))
+            .collect();
+        Some(entries.join(&STACK_SEPARATOR.to_string()))
+    }
+}
+
+fn current_shell_version() -> Option<String> {
+    env::var(VERSION_ENV).ok().filter(|value| !value.is_empty())
+}
+
 pub struct ShellCommand<'a> {
     config: &'a KopiConfig,
     no_progress: bool,
@@ -43,6 +115,16 @@ impl<'a> ShellCommand<'a> {
         let version_request = VersionRequest::from_str(version_spec)?;
         debug!("Parsed version request: {version_request:?}");
 
+        // The system JDK is found by the shims at run time, so there is nothing to look up
+        if version_request.is_system() {
+            let shell_path = self.resolve_shell(shell_override)?;
+            let mut stack = ShellStack::from_env();
+            stack.push(current_shell_version());
+
+            println!("Launching shell with the system JDK (kopi-managed JDKs disabled)");
+            return self.launch_shell(&shell_path, Some(SYSTEM_VERSION), &stack);
+        }
+
         // Create storage repository
         let repository = JdkRepository::new(self.config);
 
@@ -102,24 +184,74 @@ impl<'a> ShellCommand<'a> {
                 install_in_progress: false,
             })?;
 
-        // Detect or override shell
-        let (shell_type, shell_path) = if let Some(shell_name) = shell_override {
-            self.get_shell_override(shell_name)?
-        } else {
-            detect_shell()?
-        };
-
-        info!("Using shell: {shell_type:?} at {shell_path:?}");
+        let shell_path = self.resolve_shell(shell_override)?;
 
-        // Launch shell with KOPI_JAVA_VERSION set
+        // Launch shell with KOPI_JAVA_VERSION set, remembering the version
+        // it replaces
         let version_str = format!("{}@{}", selected_jdk.distribution, selected_jdk.version);
+        let mut stack = ShellStack::from_env();
+        stack.push(current_shell_version());
 
         println!(
             "Launching shell with JDK {}@{}",
             selected_jdk.distribution, selected_jdk.version
         );
 
-        self.launch_shell(&shell_path, &version_str)
+        self.launch_shell(&shell_path, Some(&version_str), &stack)
+    }
+
+    /// Launch a shell with the version that was active before the most
+    /// recent `kopi shell`
+    pub fn execute_restore(&self, shell_override: Option<&str>) -> Result<()> {
+        let mut stack = ShellStack::from_env();
+        let previous = stack.pop().ok_or_else(|| {
+            KopiError::ValidationError(
+                "No previous shell version to restore; this shell was not started by \
+                 'kopi shell'"
+                    .to_string(),
+            )
+        })?;
+
+        let shell_path = self.resolve_shell(shell_override)?;
+        match &previous {
+            Some(version) => println!("Restoring shell with JDK {version}"),
+            None => println!("Restoring shell without a shell JDK version"),
+        }
+
+        self.launch_shell(&shell_path, previous.as_deref(), &stack)
+    }
+
+    /// Print the current shell version followed by the versions
+    /// `--restore` would return to, most recent first
+    pub fn execute_print_stack(&self) -> Result<()> {
+        let current = current_shell_version();
+        let stack = ShellStack::from_env();
+
+        if current.is_none() && stack.is_empty() {
+            println!("No shell JDK version is set");
+            return Ok(());
+        }
+
+        println!(
+            "* {} (current)",
+            current.as_deref().unwrap_or("(no shell version)")
+        );
+        for entry in stack.iter_recent() {
+            println!("  {}", entry.unwrap_or("(no shell version)"));
+        }
+        Ok(())
+    }
+
+    fn resolve_shell(&self, shell_override: Option<&str>) -> Result<PathBuf> {
+        // Detect or override shell
+        let (shell_type, shell_path) = if let Some(shell_name) = shell_override {
+            self.get_shell_override(shell_name)?
+        } else {
+            detect_shell()?
+        };
+
+        info!("Using shell: {shell_type:?} at {shell_path:?}");
+        Ok(shell_path)
     }
 
     fn get_shell_override(&self, shell_name: &str) -> Result<(Shell, PathBuf)> {
@@ -136,15 +268,27 @@ impl<'a> ShellCommand<'a> {
         Ok((shell_type, shell_path))
     }
 
-    fn launch_shell(&self, shell_path: &PathBuf, version_str: &str) -> Result<()> {
+    fn launch_shell(
+        &self,
+        shell_path: &PathBuf,
+        version_str: Option<&str>,
+        stack: &ShellStack,
+    ) -> Result<()> {
         info!(
             "Launching {} with KOPI_JAVA_VERSION={}",
             shell_path.display(),
-            version_str
+            version_str.unwrap_or("<unset>")
         );
 
         // Use platform-specific shell launching
-        launch_shell_with_env(shell_path, "KOPI_JAVA_VERSION", version_str)
+        let stack_value = stack.to_env_value();
+        launch_shell_with_env(
+            shell_path,
+            &[
+                (VERSION_ENV, version_str),
+                (SHELL_STACK_ENV, stack_value.as_deref()),
+            ],
+        )
     }
 }
 
@@ -183,4 +327,26 @@ mod tests {
             .unwrap_or((Shell::PowerShell, PathBuf::new()));
         assert!(matches!(shell_type, Shell::PowerShell));
     }
+
+    #[test]
+    fn test_shell_stack_round_trip() {
+        let mut stack = ShellStack::parse("");
+        assert!(stack.is_empty());
+        assert_eq!(stack.to_env_value(), None);
+
+        stack.push(None);
+        stack.push(Some("temurin@21.0.1".to_string()));
+        let value = stack.to_env_value().unwrap();
+        assert_eq!(value, "-,temurin@21.0.1");
+
+        let mut parsed = ShellStack::parse(&value);
+        assert_eq!(parsed, stack);
+        assert_eq!(
+            parsed.iter_recent().collect::<Vec<_>>(),
+            vec![Some("temurin@21.0.1"), None]
+        );
+        assert_eq!(parsed.pop(), Some(Some("temurin@21.0.1".to_string())));
+        assert_eq!(parsed.pop(), Some(None));
+        assert_eq!(parsed.pop(), None);
+    }
 }
@@ -15,11 +15,15 @@
 use crate::config::KopiConfig;
 use crate::error::Result;
 use crate::indicator::StatusReporter;
-use crate::shim::installer::ShimInstaller;
+use crate::shim::discovery::discover_installed_tools;
+use crate::shim::external;
+use crate::shim::installer::{ShimInstaller, ShimIssue};
 use crate::shim::tools::{ToolCategory, ToolRegistry};
+use crate::storage::JdkRepository;
 use clap::Subcommand;
 use colored::Colorize;
 use comfy_table::{Table, presets::UTF8_FULL};
+use std::path::{Path, PathBuf};
 
 #[derive(Subcommand)]
 pub enum ShimCommand {
@@ -31,6 +35,14 @@ pub enum ShimCommand {
         /// Force creation even if shim already exists
         #[arg(short, long)]
         force: bool,
+
+        /// Wrap this executable, running it with JAVA_HOME and PATH set to the resolved JDK
+        #[arg(long, value_name = "PATH", conflicts_with = "from_path")]
+        exec: Option<PathBuf>,
+
+        /// Like --exec, using the executable named <TOOL> found on PATH
+        #[arg(long)]
+        from_path: bool,
     },
 
     /// Remove a shim for a specific tool
@@ -50,25 +62,44 @@ pub enum ShimCommand {
         distribution: Option<String>,
     },
 
-    /// Verify and repair shims
+    /// Verify shims against the current kopi binary and installed JDKs
     Verify {
-        /// Fix any issues found
+        /// Fix any issues found (same as `kopi shim repair`)
         #[arg(long)]
         fix: bool,
     },
+
+    /// Recreate outdated or broken shims and remove orphaned ones
+    Repair,
 }
 
 impl ShimCommand {
     pub fn execute(&self, config: &KopiConfig) -> Result<()> {
         let status = StatusReporter::new(false);
         match self {
-            ShimCommand::Add { tool, force } => self.add_shim(config, &status, tool, *force),
+            ShimCommand::Add {
+                tool,
+                force,
+                exec,
+                from_path,
+            } => {
+                let target = if *from_path {
+                    Some(external::find_on_path(config.kopi_home(), tool)?)
+                } else {
+                    exec.clone()
+                };
+                match target {
+                    Some(target) => self.add_external_shim(config, &status, tool, &target, *force),
+                    None => self.add_shim(config, &status, tool, *force),
+                }
+            }
             ShimCommand::Remove { tool } => self.remove_shim(config, &status, tool),
             ShimCommand::List {
                 available,
                 distribution,
             } => self.list_shims(config, &status, *available, distribution.as_deref()),
             ShimCommand::Verify { fix } => self.verify_shims(config, &status, *fix),
+            ShimCommand::Repair => self.verify_shims(config, &status, true),
         }
     }
 
@@ -79,12 +110,14 @@ impl ShimCommand {
         tool_name: &str,
         force: bool,
     ) -> Result<()> {
-        let installer = ShimInstaller::new(config.kopi_home());
+        let installer =
+            ShimInstaller::new(config.kopi_home()).with_wrappers(&config.shims.windows_wrappers);
         let registry = ToolRegistry::new();
 
         // If force is true, remove existing shim first
         if force {
             let _ = installer.remove_shim(tool_name); // Ignore error if shim doesn't exist
+            external::unregister_external_tool(config.kopi_home(), tool_name)?;
         }
 
         // Try to find the tool in the registry
@@ -105,14 +138,49 @@ impl ShimCommand {
         Ok(())
     }
 
+    fn add_external_shim(
+        &self,
+        config: &KopiConfig,
+        status: &StatusReporter,
+        tool_name: &str,
+        target: &Path,
+        force: bool,
+    ) -> Result<()> {
+        let installer =
+            ShimInstaller::new(config.kopi_home()).with_wrappers(&config.shims.windows_wrappers);
+
+        if force {
+            let _ = installer.remove_shim(tool_name); // Ignore error if shim doesn't exist
+        }
+
+        installer.create_shim(tool_name)?;
+        let executable =
+            match external::register_external_tool(config.kopi_home(), tool_name, target) {
+                Ok(executable) => executable,
+                Err(e) => {
+                    let _ = installer.remove_shim(tool_name);
+                    return Err(e);
+                }
+            };
+
+        status.success(&format!("Created shim for '{tool_name}'"));
+        status.step(&format!(
+            "Runs {} with JAVA_HOME set to the resolved JDK",
+            executable.display()
+        ));
+        Ok(())
+    }
+
     fn remove_shim(
         &self,
         config: &KopiConfig,
         status: &StatusReporter,
         tool_name: &str,
     ) -> Result<()> {
-        let installer = ShimInstaller::new(config.kopi_home());
+        let installer =
+            ShimInstaller::new(config.kopi_home()).with_wrappers(&config.shims.windows_wrappers);
         installer.remove_shim(tool_name)?;
+        external::unregister_external_tool(config.kopi_home(), tool_name)?;
         status.success(&format!("Removed shim for '{tool_name}'"));
         Ok(())
     }
@@ -132,8 +200,10 @@ impl ShimCommand {
     }
 
     fn list_installed_shims(&self, config: &KopiConfig) -> Result<()> {
-        let installer = ShimInstaller::new(config.kopi_home());
+        let installer =
+            ShimInstaller::new(config.kopi_home()).with_wrappers(&config.shims.windows_wrappers);
         let shims = installer.list_shims()?;
+        let external_tools = external::load_external_tools(config.kopi_home());
 
         if shims.is_empty() {
             println!("No shims installed.");
@@ -157,9 +227,14 @@ impl ShimCommand {
                 "✗ Invalid".red().to_string()
             };
 
+            // External tools show the executable they wrap
+            let target = external_tools
+                .get(shim_name)
+                .map_or(shim_path.as_path(), PathBuf::as_path);
+
             table.add_row(vec![
                 shim_name.clone(),
-                shim_path.display().to_string(),
+                target.display().to_string(),
                 status,
             ]);
         }
@@ -220,10 +295,15 @@ impl ShimCommand {
     }
 
     fn verify_shims(&self, config: &KopiConfig, _status: &StatusReporter, fix: bool) -> Result<()> {
-        let installer = ShimInstaller::new(config.kopi_home());
-        let shims = installer.list_shims()?;
-
-        if shims.is_empty() {
+        let installer =
+            ShimInstaller::new(config.kopi_home()).with_wrappers(&config.shims.windows_wrappers);
+        let repository = JdkRepository::new(config);
+        let mut provided_tools = discover_installed_tools(&repository.list_installed_jdks()?)?;
+        // External tools are never orphaned; they do not come from a JDK
+        provided_tools.extend(external::load_external_tools(config.kopi_home()).into_keys());
+        let inspections = installer.inspect_shims(&provided_tools)?;
+
+        if inspections.is_empty() {
             println!("No shims to verify.");
             return Ok(());
         }
@@ -234,28 +314,43 @@ impl ShimCommand {
         let mut issues_found = 0;
         let mut issues_fixed = 0;
 
-        for shim_name in &shims {
-            let shim_path = config.shims_dir()?.join(shim_name);
-            let is_valid = shim_path.exists();
-
-            if !is_valid {
-                issues_found += 1;
-                println!("  {} {}", "✗".red(), shim_name);
-                println!("    Issue: Shim file not found");
-
-                if fix {
-                    match installer.create_shim(shim_name) {
-                        Ok(_) => {
-                            println!("    {} Fixed", "✓".green());
-                            issues_fixed += 1;
-                        }
-                        Err(e) => {
-                            println!("    {} Failed to fix: {}", "✗".red(), e);
-                        }
-                    }
+        for inspection in &inspections {
+            let tool = &inspection.tool;
+            let Some(issue) = &inspection.issue else {
+                println!("  {} {}", "✓".green(), tool);
+                continue;
+            };
+
+            issues_found += 1;
+            println!("  {} {}", "✗".red(), tool);
+            match issue {
+                ShimIssue::Broken(reason) => println!("    Issue: Broken shim ({reason})"),
+                ShimIssue::Outdated => {
+                    println!("    Issue: Points to a different kopi-shim binary")
+                }
+                ShimIssue::Orphaned => println!("    Issue: No installed JDK provides this tool"),
+            }
+
+            if !fix {
+                continue;
+            }
+
+            let result = match issue {
+                ShimIssue::Broken(_) | ShimIssue::Outdated => installer.repair_shim(tool),
+                ShimIssue::Orphaned => installer.remove_shim(tool),
+            };
+            match result {
+                Ok(()) => {
+                    let action = match issue {
+                        ShimIssue::Orphaned => "Removed",
+                        _ => "Recreated",
+                    };
+                    println!("    {} {action}", "✓".green());
+                    issues_fixed += 1;
+                }
+                Err(e) => {
+                    println!("    {} Failed to fix: {}", "✗".red(), e);
                 }
-            } else {
-                println!("  {} {}", "✓".green(), shim_name);
             }
         }
 
@@ -284,7 +379,7 @@ impl ShimCommand {
             println!(
                 "Found {} issues. Run {} to fix them.",
                 issues_found.to_string().red(),
-                "kopi shim verify --fix".cyan()
+                "kopi shim repair".cyan()
             );
         }
 
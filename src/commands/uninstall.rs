@@ -14,22 +14,39 @@
 
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
+use crate::integrations;
 use crate::storage::JdkRepository;
 use crate::uninstall::UninstallHandler;
 use crate::uninstall::batch::BatchUninstaller;
 use crate::uninstall::cleanup::UninstallCleanup;
 use crate::uninstall::feedback::{display_uninstall_confirmation, display_uninstall_summary};
+use crate::uninstall::unused::{find_unused_jdks, parse_age};
 use crate::version::VersionRequest;
+use chrono::{Local, Utc};
 use log::{debug, info};
 use std::str::FromStr;
 
+/// Age used by `kopi uninstall --unused` when `--older-than` is not given
+pub const DEFAULT_UNUSED_AGE: &str = "90d";
+
 pub struct UninstallCommand<'a> {
     config: &'a KopiConfig,
+    keep_archives: bool,
 }
 
 impl<'a> UninstallCommand<'a> {
     pub fn new(config: &'a KopiConfig, _no_progress: bool) -> Result<Self> {
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            keep_archives: false,
+        })
+    }
+
+    /// Leave the kept install archive and companion archives behind so the
+    /// JDK can be reinstalled offline
+    pub fn with_keep_archives(mut self, keep_archives: bool) -> Self {
+        self.keep_archives = keep_archives;
+        self
     }
 
     pub fn execute(
@@ -44,7 +61,8 @@ impl<'a> UninstallCommand<'a> {
         debug!("Uninstall options: force={force}, dry_run={dry_run}, all={all}, cleanup={cleanup}");
 
         let repository = JdkRepository::new(self.config);
-        let handler = UninstallHandler::new(&repository, no_progress);
+        let handler =
+            UninstallHandler::new(&repository, no_progress).with_keep_archives(self.keep_archives);
 
         // Execute normal uninstall if version is specified
         if let Some(version) = version_spec {
@@ -76,6 +94,59 @@ impl<'a> UninstallCommand<'a> {
             self.execute_cleanup(force, dry_run, &handler)?;
         }
 
+        if !dry_run {
+            integrations::refresh_after_change(self.config);
+        }
+
+        Ok(())
+    }
+
+    /// Remove JDKs that no shim has used within `older_than` and that no
+    /// version file pins
+    pub fn execute_unused(
+        &self,
+        older_than: &str,
+        force: bool,
+        dry_run: bool,
+        no_progress: bool,
+    ) -> Result<()> {
+        let age = parse_age(older_than)?;
+        info!("Uninstalling JDKs unused for {older_than}");
+
+        let repository = JdkRepository::new(self.config);
+        let candidates = find_unused_jdks(self.config, &repository, age, Utc::now())?;
+
+        if candidates.is_empty() {
+            println!("No unpinned JDKs unused for {older_than}.");
+            return Ok(());
+        }
+
+        println!("JDKs unused for {older_than}:");
+        for candidate in &candidates {
+            let last_used = match (candidate.last_used, candidate.source) {
+                (Some(time), Some(source)) => format!(
+                    "last used {} ({source})",
+                    time.with_timezone(&Local).format("%Y-%m-%d")
+                ),
+                _ => "never used".to_string(),
+            };
+            println!(
+                "  {}@{}  {last_used}",
+                candidate.jdk.distribution, candidate.jdk.version
+            );
+        }
+        println!();
+
+        let jdks = candidates
+            .into_iter()
+            .map(|candidate| candidate.jdk)
+            .collect();
+        let batch_uninstaller = BatchUninstaller::new(self.config, &repository, no_progress);
+        batch_uninstaller.uninstall_batch(jdks, force, dry_run)?;
+
+        if !dry_run {
+            integrations::refresh_after_change(self.config);
+        }
         Ok(())
     }
 
@@ -131,6 +202,7 @@ impl<'a> UninstallCommand<'a> {
         }
 
         let jdk = &jdks_to_remove[0];
+        repository.ensure_not_system(jdk)?;
 
         // Calculate disk space to be freed
         let disk_space = repository.get_jdk_size(&jdk.path)?;
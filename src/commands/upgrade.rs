@@ -0,0 +1,228 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::cache::{self, PlatformFilter};
+use crate::commands::install::InstallCommand;
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::models::distribution::Distribution;
+use crate::models::metadata::JdkMetadata;
+use crate::storage::{InstalledJdk, JdkRepository};
+use crate::version::VersionRequest;
+use crate::version::file::VersionTrack;
+use crate::version::resolver::VersionResolver;
+use log::{debug, info};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// Installs newer releases for version files pinned to a track
+pub struct UpgradeCommand<'a> {
+    config: &'a KopiConfig,
+    no_progress: bool,
+    rate_limit: Option<u64>,
+}
+
+impl<'a> UpgradeCommand<'a> {
+    pub fn new(config: &'a KopiConfig, no_progress: bool) -> Result<Self> {
+        Ok(Self {
+            config,
+            no_progress,
+            rate_limit: None,
+        })
+    }
+
+    /// Cap download bandwidth instead of using `download.max_rate`
+    pub fn with_rate_limit(mut self, bytes_per_sec: Option<u64>) -> Self {
+        self.rate_limit = bytes_per_sec;
+        self
+    }
+
+    pub fn execute(&self, version_spec: Option<&str>, dry_run: bool, force: bool) -> Result<()> {
+        let version_request = match version_spec {
+            Some(spec) => VersionRequest::from_str(spec)?,
+            None => VersionResolver::new(self.config).resolve_version()?.0,
+        };
+        debug!("Upgrading version request: {version_request:?}");
+
+        let track = version_request.track.ok_or_else(|| {
+            KopiError::InvalidVersionFormat(format!(
+                "'{version_request}' is a fixed version. Only tracks such as 'latest-lts', \
+                 'latest-ga', or '21-latest' can be upgraded"
+            ))
+        })?;
+
+        let distribution = match &version_request.distribution {
+            Some(dist) => Distribution::from_str(dist)?,
+            None => Distribution::from_str(&self.config.default_distribution)
+                .unwrap_or(Distribution::Temurin),
+        };
+
+        let installed = self.newest_installed(&version_request, &distribution)?;
+        let cache = cache::get_fresh_metadata(self.config)?;
+        let candidate = cache
+            .lookup_track(
+                &distribution,
+                track,
+                &PlatformFilter::current(),
+                version_request.package_type.as_ref(),
+                version_request.javafx_bundled,
+            )
+            .ok_or_else(|| {
+                KopiError::VersionNotAvailable(format!("{}@{track}", distribution.id()))
+            })?;
+
+        if !is_upgrade(&candidate, installed.as_ref()) {
+            println!(
+                "{}@{track} is up to date ({}@{})",
+                distribution.id(),
+                distribution.id(),
+                candidate.version
+            );
+            return Ok(());
+        }
+
+        if !force
+            && let Some(jdk) = &installed
+            && JdkRepository::new(self.config).is_protected(jdk)
+        {
+            return Err(KopiError::ValidationError(format!(
+                "{}@{} is protected, so {}@{track} stays on it. Run 'kopi pin-protect --remove \
+                 {}@{}' or pass --force to upgrade anyway",
+                jdk.distribution,
+                jdk.version,
+                distribution.id(),
+                jdk.distribution,
+                jdk.version
+            )));
+        }
+
+        match &installed {
+            Some(jdk) => println!(
+                "Upgrading {}@{track}: {} -> {}",
+                distribution.id(),
+                jdk.version,
+                candidate.version
+            ),
+            None => println!(
+                "Installing {}@{track}: {}",
+                distribution.id(),
+                candidate.version
+            ),
+        }
+
+        let install_spec = install_spec(&version_request, &distribution, &candidate);
+        info!("Installing {install_spec} for track {track}");
+        InstallCommand::new(self.config, self.no_progress)?
+            .with_rate_limit(self.rate_limit)
+            .execute(&install_spec, false, dry_run, None)
+    }
+
+    fn newest_installed(
+        &self,
+        version_request: &VersionRequest,
+        distribution: &Distribution,
+    ) -> Result<Option<InstalledJdk>> {
+        let scoped = version_request
+            .clone()
+            .with_distribution(distribution.id().to_string());
+        let repository = JdkRepository::new(self.config);
+        Ok(repository.find_matching_jdks(&scoped)?.pop())
+    }
+}
+
+fn is_upgrade(candidate: &JdkMetadata, installed: Option<&InstalledJdk>) -> bool {
+    installed.is_none_or(|jdk| {
+        VersionTrack::compare(&candidate.version, &jdk.version) == Ordering::Greater
+    })
+}
+
+fn install_spec(
+    version_request: &VersionRequest,
+    distribution: &Distribution,
+    candidate: &JdkMetadata,
+) -> String {
+    let mut spec = match &version_request.package_type {
+        Some(package_type) => format!("{package_type}@{}@{}", candidate.version, distribution.id()),
+        None => format!("{}@{}", distribution.id(), candidate.version),
+    };
+    if version_request.javafx_bundled == Some(true) {
+        spec.push_str("+fx");
+    }
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::package::{ArchiveType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::version::Version;
+    use std::path::PathBuf;
+
+    fn metadata(version: &str) -> JdkMetadata {
+        JdkMetadata {
+            id: format!("temurin-{version}"),
+            distribution: "temurin".to_string(),
+            version: Version::from_str(version).unwrap(),
+            distribution_version: Version::from_str(version).unwrap(),
+            architecture: Architecture::X64,
+            operating_system: OperatingSystem::Linux,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: None,
+            checksum: None,
+            checksum_type: None,
+            size: 0,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }
+    }
+
+    fn installed(version: &str) -> InstalledJdk {
+        InstalledJdk::new(
+            "temurin".to_string(),
+            Version::from_str(version).unwrap(),
+            PathBuf::from(format!("/tmp/temurin-{version}")),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_is_upgrade() {
+        assert!(is_upgrade(&metadata("21.0.5"), None));
+        assert!(is_upgrade(&metadata("21.0.5"), Some(&installed("21.0.4"))));
+        assert!(!is_upgrade(&metadata("21.0.5"), Some(&installed("21.0.5"))));
+        assert!(is_upgrade(&metadata("25"), Some(&installed("25-ea"))));
+    }
+
+    #[test]
+    fn test_install_spec() {
+        let request = VersionRequest::from_str("latest-lts").unwrap();
+        assert_eq!(
+            install_spec(&request, &Distribution::Temurin, &metadata("21.0.5")),
+            "temurin@21.0.5"
+        );
+
+        let request = VersionRequest::from_str("jre@21-latest@temurin+fx").unwrap();
+        assert_eq!(
+            install_spec(&request, &Distribution::Temurin, &metadata("21.0.5")),
+            "jre@21.0.5@temurin+fx"
+        );
+    }
+}
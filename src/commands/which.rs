@@ -14,12 +14,17 @@
 
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
-use crate::paths::install;
-use crate::platform::{executable_extension, with_executable_extension};
+use crate::models::package::PackageType;
+use crate::paths::{install, shims};
+use crate::platform::executable_extensions;
+use crate::platform::file_ops::{self, find_executable};
+use crate::shim::external;
+use crate::storage::formatting::format_size;
 use crate::storage::{InstalledJdk, JdkRepository};
-use crate::version::VersionRequest;
 use crate::version::resolver::{VersionResolver, VersionSource};
+use crate::version::{SYSTEM_VERSION, Version, VersionRequest, matching};
 use serde::Serialize;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -31,19 +36,73 @@ struct WhichOutput {
     tool_path: String,
     jdk_home: String,
     source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sources: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    javadoc: Option<String>,
+}
+
+/// `--tool` value that lists every tool of the resolved JDK
+pub const ALL_TOOLS: &str = "all";
+
+#[derive(Serialize)]
+struct ToolInventoryOutput {
+    distribution: String,
+    version: String,
+    jdk_home: String,
+    source: String,
+    tools: Vec<ToolEntry>,
+}
+
+/// An executable in a JDK's `bin` directory
+#[derive(Debug, Serialize)]
+struct ToolEntry {
+    /// Name without the executable extension, as typed on the command line
+    name: String,
+    path: String,
+    size: u64,
+    /// A kopi shim with this name exists
+    shimmed: bool,
+}
+
+#[derive(Serialize)]
+struct ToolProviderOutput {
+    distribution: String,
+    version: String,
+    tool: String,
+    tool_path: String,
+    jdk_home: String,
+    preferred: bool,
 }
 
 pub struct WhichCommand<'a> {
     config: &'a KopiConfig,
+    companion: Option<PackageType>,
 }
 
 impl<'a> WhichCommand<'a> {
     pub fn new(config: &'a KopiConfig) -> Result<Self> {
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            companion: None,
+        })
+    }
+
+    /// Print the path of this companion archive (sources, javadoc) instead
+    /// of a tool or the JDK home
+    pub fn with_companion(mut self, companion: Option<PackageType>) -> Self {
+        self.companion = companion;
+        self
     }
 
     pub fn execute(&self, version: Option<&str>, tool: &str, home: bool, json: bool) -> Result<()> {
         let repo = JdkRepository::new(self.config);
+        let all_tools = tool == ALL_TOOLS;
+        if all_tools && (home || self.companion.is_some()) {
+            return Err(KopiError::ValidationError(format!(
+                "--tool {ALL_TOOLS} cannot be combined with --home, --sources or --javadoc"
+            )));
+        }
 
         // Resolve JDK spec
         let (version_request, source) = if let Some(version) = version {
@@ -53,11 +112,17 @@ impl<'a> WhichCommand<'a> {
         } else {
             // Use current version resolution
             let resolver = VersionResolver::new(self.config);
-            let (version_request, version_source) = resolver.resolve_version()?;
+            // The inventory describes the JDK `java` runs from
+            let resolve_as = if all_tools { "java" } else { tool };
+            let (version_request, version_source) = resolver.resolve_tool_version(resolve_as)?;
             let source = format_source(&version_source);
             (version_request, source)
         };
 
+        if version_request.is_system() {
+            return self.execute_system(tool, home, json, &source);
+        }
+
         // Find installed JDK
         let matching_jdks = repo.find_matching_jdks(&version_request)?;
         let installation = if matching_jdks.is_empty() {
@@ -86,8 +151,31 @@ impl<'a> WhichCommand<'a> {
             )));
         };
 
+        if all_tools {
+            let java_home = installation.resolve_java_home();
+            return self.output_inventory(
+                &installation.distribution,
+                &installation.version.to_string(),
+                &java_home,
+                &source,
+                json,
+            );
+        }
+
         // Determine output path
-        let output_path = if home {
+        let output_path = if let Some(kind) = self.companion {
+            repo.find_companion_archive(&installation.path, kind)?
+                .ok_or_else(|| {
+                    KopiError::NotFound(format!(
+                        "No {kind} archive installed for {}@{}; reinstall with \
+                         'kopi install {}@{} --force --with-{kind}'",
+                        installation.distribution,
+                        installation.version,
+                        installation.distribution,
+                        installation.version
+                    ))
+                })?
+        } else if home {
             installation.path.clone()
         } else {
             get_tool_path(&installation, tool)?
@@ -95,7 +183,7 @@ impl<'a> WhichCommand<'a> {
 
         // Output result
         if json {
-            output_json(&installation, tool, &output_path, &source)?;
+            output_json(&repo, &installation, tool, &output_path, &source)?;
         } else {
             println!("{}", output_path.display());
         }
@@ -104,9 +192,207 @@ impl<'a> WhichCommand<'a> {
     }
 }
 
-fn format_source(source: &VersionSource) -> String {
+impl WhichCommand<'_> {
+    /// Locate `tool` for the `system` alias: the executable the shims fall
+    /// through to on `PATH`
+    fn execute_system(&self, tool: &str, home: bool, json: bool, source: &str) -> Result<()> {
+        let kopi_home = self.config.kopi_home();
+        if self.companion.is_some() {
+            return Err(KopiError::ValidationError(
+                "Source and javadoc archives are only recorded for kopi-managed JDKs".to_string(),
+            ));
+        }
+
+        let java_home = external::system_java_home(kopi_home);
+        if tool == ALL_TOOLS {
+            let java_home = java_home.ok_or_else(|| {
+                KopiError::NotFound(
+                    "No system JDK: java is not on PATH outside the kopi shims directory"
+                        .to_string(),
+                )
+            })?;
+            return self.output_inventory(SYSTEM_VERSION, SYSTEM_VERSION, &java_home, source, json);
+        }
+        let output_path = if home {
+            java_home.clone().ok_or_else(|| {
+                KopiError::NotFound(
+                    "No system JDK: java is not on PATH outside the kopi shims directory"
+                        .to_string(),
+                )
+            })?
+        } else {
+            external::find_on_path(kopi_home, tool)?
+        };
+
+        if json {
+            let output = WhichOutput {
+                distribution: SYSTEM_VERSION.to_string(),
+                version: SYSTEM_VERSION.to_string(),
+                tool: tool.to_string(),
+                tool_path: output_path.display().to_string(),
+                jdk_home: java_home
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+                source: source.to_string(),
+                sources: None,
+                javadoc: None,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            println!("{}", output_path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Print every tool in `java_home`'s `bin` directory with its size and
+    /// whether a kopi shim exists for it
+    fn output_inventory(
+        &self,
+        distribution: &str,
+        version: &str,
+        java_home: &Path,
+        source: &str,
+        json: bool,
+    ) -> Result<()> {
+        let tools = tool_inventory(self.config.kopi_home(), java_home)?;
+
+        if json {
+            let output = ToolInventoryOutput {
+                distribution: distribution.to_string(),
+                version: version.to_string(),
+                jdk_home: java_home.display().to_string(),
+                source: source.to_string(),
+                tools,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(());
+        }
+
+        let width = tools.iter().map(|tool| tool.name.len()).max().unwrap_or(0);
+        for tool in &tools {
+            let shimmed = if tool.shimmed { "  (shimmed)" } else { "" };
+            println!(
+                "{:<width$}  {:>9}  {}{shimmed}",
+                tool.name,
+                format_size(tool.size),
+                tool.path
+            );
+        }
+        Ok(())
+    }
+
+    /// Search every installed JDK for `tool`, listing the providers with the
+    /// best match for the requested (or project) version line first.
+    pub fn execute_find_any(
+        &self,
+        version: Option<&str>,
+        tool: &str,
+        home: bool,
+        json: bool,
+    ) -> Result<()> {
+        if tool == ALL_TOOLS {
+            return Err(KopiError::ValidationError(format!(
+                "--tool {ALL_TOOLS} lists one JDK's tools and cannot be combined with --find-any"
+            )));
+        }
+        let repo = JdkRepository::new(self.config);
+
+        // The version only orders the results, so an unresolvable project is fine
+        let preferred = match version {
+            Some(version) => Some(VersionRequest::from_str(version)?),
+            None => VersionResolver::new(self.config)
+                .resolve_tool_version(tool)
+                .ok()
+                .map(|(request, _)| request),
+        };
+
+        let installed = repo.list_installed_jdks()?;
+        let searched = installed.len();
+        let providers = rank_tool_providers(installed, tool, preferred.as_ref());
+        if providers.is_empty() {
+            return Err(KopiError::NotFound(format!(
+                "No installed JDK provides '{tool}' (searched {searched} installation(s))"
+            )));
+        }
+
+        if json {
+            let output: Vec<ToolProviderOutput> = providers
+                .iter()
+                .enumerate()
+                .map(|(index, (jdk, tool_path))| ToolProviderOutput {
+                    distribution: jdk.distribution.clone(),
+                    version: jdk.version.to_string(),
+                    tool: tool.to_string(),
+                    tool_path: tool_path.display().to_string(),
+                    jdk_home: jdk.path.display().to_string(),
+                    preferred: index == 0,
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(());
+        }
+
+        let labels: Vec<String> = providers
+            .iter()
+            .map(|(jdk, _)| format!("{}@{}", jdk.distribution, jdk.version))
+            .collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0);
+        for (index, ((jdk, tool_path), label)) in providers.iter().zip(&labels).enumerate() {
+            let marker = if index == 0 { '*' } else { ' ' };
+            let path = if home { &jdk.path } else { tool_path };
+            println!("{marker} {label:<width$}  {}", path.display());
+        }
+
+        Ok(())
+    }
+}
+
+/// Installed JDKs that ship `tool`, with the tool's path.
+///
+/// JDKs matching `preferred` come first, then others on the same major
+/// version line, then the rest; newest first within each group.
+fn rank_tool_providers(
+    installed: Vec<InstalledJdk>,
+    tool: &str,
+    preferred: Option<&VersionRequest>,
+) -> Vec<(InstalledJdk, PathBuf)> {
+    let preferred_major = preferred
+        .and_then(|request| Version::from_str(&request.version_pattern).ok())
+        .map(|version| version.major());
+
+    let mut providers: Vec<(u8, InstalledJdk, PathBuf)> = installed
+        .into_iter()
+        .filter_map(|jdk| {
+            let tool_path = find_executable(&install::bin_directory(&jdk.path), tool)?;
+
+            let rank = match preferred {
+                Some(request) if matching::matches_installed(request, &jdk) => 0,
+                _ if preferred_major == Some(jdk.version.major()) => 1,
+                _ => 2,
+            };
+            Some((rank, jdk, tool_path))
+        })
+        .collect();
+
+    providers.sort_by(|(rank_a, jdk_a, _), (rank_b, jdk_b, _)| {
+        rank_a
+            .cmp(rank_b)
+            .then_with(|| jdk_b.version.cmp(&jdk_a.version))
+    });
+    providers
+        .into_iter()
+        .map(|(_, jdk, tool_path)| (jdk, tool_path))
+        .collect()
+}
+
+pub(crate) fn format_source(source: &VersionSource) -> String {
     match source {
+        VersionSource::Override(_) => "KOPI_USE override".to_string(),
         VersionSource::Environment(_) => "environment".to_string(),
+        VersionSource::ToolOverride(path) => {
+            format!("tool override: {}", path.display())
+        }
         VersionSource::ProjectFile(path) => {
             format!("project file: {}", path.display())
         }
@@ -115,51 +401,92 @@ fn format_source(source: &VersionSource) -> String {
 }
 
 fn get_tool_path(installation: &InstalledJdk, tool: &str) -> Result<PathBuf> {
-    let tool_name = with_executable_extension(tool);
     let bin_dir = install::bin_directory(&installation.path);
-    let tool_path = bin_dir.join(&tool_name);
-
-    if !tool_path.exists() {
-        // Get list of available tools in the bin directory
-        let mut available_tools = Vec::new();
-
-        if let Ok(entries) = std::fs::read_dir(&bin_dir) {
-            for entry in entries.flatten() {
-                if let Ok(file_type) = entry.file_type()
-                    && (file_type.is_file() || file_type.is_symlink())
-                    && let Some(name) = entry.file_name().to_str()
-                {
-                    // Remove executable extension for cleaner listing
-                    let tool_name = if !executable_extension().is_empty()
-                        && name.ends_with(executable_extension())
-                    {
-                        &name[..name.len() - executable_extension().len()]
-                    } else {
-                        name
-                    };
-                    available_tools.push(tool_name.to_string());
-                }
-            }
-        }
-
-        available_tools.sort();
+    let Some(tool_path) = find_executable(&bin_dir, tool) else {
+        let available_tools = list_tools(&bin_dir)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
 
         return Err(KopiError::ToolNotFound {
             tool: tool.to_string(),
             jdk_path: installation.path.display().to_string(),
             available_tools,
         });
-    }
+    };
 
     Ok(tool_path)
 }
 
+/// Executables in `bin_dir` as (name without extension, path), sorted by
+/// name. Libraries that Windows JDKs keep next to the tools are left out.
+fn list_tools(bin_dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(bin_dir) else {
+        return Vec::new();
+    };
+
+    let mut tools: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_ok_and(|file_type| file_type.is_file() || file_type.is_symlink())
+        })
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_str()?.to_string();
+            let extensions = executable_extensions();
+            let name = if extensions.is_empty() {
+                file_ops::is_executable(&entry.path())
+                    .unwrap_or(false)
+                    .then_some(file_name)?
+            } else {
+                // Windows tools are any file with a PATHEXT extension
+                let (stem, extension) = file_name.rsplit_once('.')?;
+                let extension = format!(".{}", extension.to_ascii_lowercase());
+                extensions.contains(&extension).then(|| stem.to_string())?
+            };
+            Some((name, entry.path()))
+        })
+        .collect();
+    tools.sort();
+    tools
+}
+
+/// Every tool in `java_home`'s `bin` directory with its size and shim status
+fn tool_inventory(kopi_home: &Path, java_home: &Path) -> Result<Vec<ToolEntry>> {
+    let bin_dir = install::bin_directory(java_home);
+    if !bin_dir.is_dir() {
+        return Err(KopiError::SystemError(format!(
+            "JDK bin directory not found: {}",
+            bin_dir.display()
+        )));
+    }
+
+    Ok(list_tools(&bin_dir)
+        .into_iter()
+        .map(|(name, path)| ToolEntry {
+            shimmed: shims::tool_shim_path(kopi_home, &name).exists(),
+            size: fs::metadata(&path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0),
+            path: path.display().to_string(),
+            name,
+        })
+        .collect())
+}
+
 fn output_json(
+    repo: &JdkRepository,
     installation: &InstalledJdk,
     tool: &str,
     tool_path: &Path,
     source: &str,
 ) -> Result<()> {
+    let companion = |kind| -> Result<Option<String>> {
+        Ok(repo
+            .find_companion_archive(&installation.path, kind)?
+            .map(|path| path.display().to_string()))
+    };
     let output = WhichOutput {
         distribution: installation.distribution.clone(),
         version: installation.version.to_string(),
@@ -167,6 +494,8 @@ fn output_json(
         tool_path: tool_path.display().to_string(),
         jdk_home: installation.path.display().to_string(),
         source: source.to_string(),
+        sources: companion(PackageType::Sources)?,
+        javadoc: companion(PackageType::Javadoc)?,
     };
 
     println!("{}", serde_json::to_string_pretty(&output)?);
@@ -178,6 +507,7 @@ mod tests {
     use super::*;
     use crate::config::KopiConfig;
     use crate::paths::install;
+    use crate::platform::with_executable_extension;
     use crate::version::Version;
     use std::fs;
     use std::str::FromStr;
@@ -304,6 +634,29 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_which_companion_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = setup_test_environment(&temp_dir, "temurin", "21.0.5+11");
+
+        let command = WhichCommand::new(&config)
+            .unwrap()
+            .with_companion(Some(PackageType::Sources));
+        assert!(matches!(
+            command.execute(Some("temurin@21"), "java", false, false),
+            Err(KopiError::NotFound(_))
+        ));
+
+        let archive =
+            install::companion_file(temp_dir.path(), "temurin-21.0.5+11", "sources", "zip");
+        fs::write(&archive, b"sources").unwrap();
+        assert!(
+            command
+                .execute(Some("temurin@21"), "java", false, false)
+                .is_ok()
+        );
+    }
+
     #[test]
     fn test_ambiguous_version() {
         let temp_dir = TempDir::new().unwrap();
@@ -433,6 +786,111 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rank_tool_providers_prefers_version_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let graal_21 = create_test_jdk(&temp_dir, "graalvm", "21.0.2");
+        let graal_17 = create_test_jdk(&temp_dir, "graalvm", "17.0.9");
+        create_test_jdk(&temp_dir, "temurin", "21.0.5");
+        for jdk in [&graal_21, &graal_17] {
+            let tool = install::bin_directory(jdk).join(with_executable_extension("native-image"));
+            fs::write(tool, "").unwrap();
+        }
+
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let installed = JdkRepository::new(&config).list_installed_jdks().unwrap();
+
+        let names = |providers: Vec<(InstalledJdk, PathBuf)>| {
+            providers
+                .into_iter()
+                .map(|(jdk, _)| format!("{}@{}", jdk.distribution, jdk.version))
+                .collect::<Vec<_>>()
+        };
+
+        // Without a preference the newest provider wins; temurin lacks the tool
+        assert_eq!(
+            names(rank_tool_providers(installed.clone(), "native-image", None)),
+            vec!["graalvm@21.0.2", "graalvm@17.0.9"]
+        );
+
+        let request = VersionRequest::from_str("17").unwrap();
+        assert_eq!(
+            names(rank_tool_providers(
+                installed.clone(),
+                "native-image",
+                Some(&request)
+            )),
+            vec!["graalvm@17.0.9", "graalvm@21.0.2"]
+        );
+
+        // A different distribution on the same line still ranks by version line
+        let request = VersionRequest::from_str("temurin@17").unwrap();
+        let providers = rank_tool_providers(installed, "native-image", Some(&request));
+        assert_eq!(providers[0].0.version, Version::from_str("17.0.9").unwrap());
+    }
+
+    #[test]
+    fn test_which_find_any_without_provider() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = setup_test_environment(&temp_dir, "temurin", "21.0.5+11");
+        let command = WhichCommand::new(&config).unwrap();
+
+        let result = command.execute_find_any(None, "native-image", false, false);
+        assert!(matches!(result, Err(KopiError::NotFound(_))));
+
+        command
+            .execute_find_any(Some("21"), "jshell", false, true)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_tool_inventory() {
+        let temp_dir = TempDir::new().unwrap();
+        let jdk_path = create_test_jdk(&temp_dir, "temurin", "21.0.5");
+        let bin_dir = install::bin_directory(&jdk_path);
+        // Not a tool: no exec bit on unix, not an .exe on Windows
+        fs::write(bin_dir.join("jli.dll"), "").unwrap();
+        shims::ensure_shims_root(temp_dir.path()).unwrap();
+        fs::write(shims::tool_shim_path(temp_dir.path(), "javac"), "").unwrap();
+
+        let tools = tool_inventory(temp_dir.path(), &jdk_path).unwrap();
+        let names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+        assert_eq!(names, vec!["jar", "java", "javac", "jshell"]);
+
+        let javac = &tools[2];
+        assert!(javac.shimmed);
+        assert!(!tools[1].shimmed);
+        assert_eq!(javac.size, "#!/bin/sh\necho test".len() as u64);
+        assert_eq!(
+            PathBuf::from(&javac.path),
+            bin_dir.join(with_executable_extension("javac"))
+        );
+
+        assert!(matches!(
+            tool_inventory(temp_dir.path(), &temp_dir.path().join("missing")),
+            Err(KopiError::SystemError(_))
+        ));
+    }
+
+    #[test]
+    fn test_which_all_tools() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = setup_test_environment(&temp_dir, "temurin", "21.0.5+11");
+        let command = WhichCommand::new(&config).unwrap();
+
+        command
+            .execute(Some("temurin@21"), ALL_TOOLS, false, true)
+            .unwrap();
+        assert!(matches!(
+            command.execute(Some("temurin@21"), ALL_TOOLS, true, false),
+            Err(KopiError::ValidationError(_))
+        ));
+        assert!(matches!(
+            command.execute_find_any(None, ALL_TOOLS, false, false),
+            Err(KopiError::ValidationError(_))
+        ));
+    }
+
     #[test]
     fn test_which_not_installed() {
         let temp_dir = TempDir::new().unwrap();
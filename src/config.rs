@@ -13,30 +13,46 @@
 // limitations under the License.
 
 use crate::error::{KopiError, Result};
+use crate::locking::LockScope;
 use crate::locking::timeout::{
     LockTimeoutParseError, LockTimeoutResolution, LockTimeoutResolver, LockTimeoutSource,
     LockTimeoutValue, parse_timeout_override,
 };
 use crate::paths::{cache, home};
-use config::{Config, ConfigError, Environment, File};
+use crate::version::Version;
+use crate::version::resolver::PROJECT_CONFIG_FILE;
+use config::{Config, ConfigError, Environment, File, FileFormat, Source};
 use dirs::home_dir;
 use log::warn;
+use schemars::JsonSchema;
 use serde::de::{self, Deserializer};
 use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::time::Duration;
 
 const CONFIG_FILE_NAME: &str = "config.toml";
+const SYSTEM_CONFIG_ENV: &str = "KOPI_SYSTEM_CONFIG";
+const SYSTEM_JDKS_ENV: &str = "KOPI_SYSTEM_JDKS";
+const SHARED_HOME_ENV: &str = "KOPI_SHARED_HOME";
 const DEFAULT_MIN_DISK_SPACE_MB: u64 = 500;
+const DEFAULT_EXPANSION_FACTOR: f64 = 3.0;
 const DEFAULT_LOCK_TIMEOUT_SECS: u64 = 600;
+/// Top-level keys a project `.kopi.toml` may set: tool pins, the default
+/// distribution and the pin format. Everything else (metadata sources,
+/// certificates, auto-install, ...) stays with the user and system layers.
+const PROJECT_KEYS: &[&str] = &["default_distribution", "pin", "tools"];
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct KopiConfig {
     #[serde(skip)]
     kopi_home: PathBuf,
 
+    #[serde(skip)]
+    files: ConfigFiles,
+
     #[serde(default)]
     pub storage: StorageConfig,
 
@@ -46,6 +62,10 @@ pub struct KopiConfig {
     #[serde(default)]
     pub additional_distributions: Vec<String>,
 
+    /// In-house distributions served from their own metadata documents
+    #[serde(default, rename = "custom_distribution")]
+    pub custom_distributions: Vec<CustomDistributionConfig>,
+
     #[serde(default)]
     pub auto_install: AutoInstallConfig,
 
@@ -57,9 +77,39 @@ pub struct KopiConfig {
 
     #[serde(default)]
     pub locking: LockingConfig,
+
+    #[serde(default)]
+    pub network: NetworkConfig,
+
+    #[serde(default)]
+    pub logging: LoggingConfig,
+
+    #[serde(default)]
+    pub crash_reports: CrashReportsConfig,
+
+    #[serde(default)]
+    pub progress: ProgressDisplayConfig,
+
+    #[serde(default)]
+    pub download: DownloadConfig,
+
+    #[serde(default)]
+    pub audit: AuditConfig,
+
+    #[serde(default)]
+    pub install: InstallConfig,
+
+    #[serde(default)]
+    pub policy: PolicyConfig,
+
+    #[serde(default)]
+    pub env: EnvConfig,
+
+    #[serde(default)]
+    pub pin: PinConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MetadataConfig {
     #[serde(default)]
     pub cache: MetadataCacheConfig,
@@ -77,7 +127,7 @@ impl Default for MetadataConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MetadataCacheConfig {
     #[serde(default = "default_metadata_cache_max_age_hours")]
     pub max_age_hours: u64,
@@ -87,6 +137,11 @@ pub struct MetadataCacheConfig {
 
     #[serde(default = "default_true")]
     pub refresh_on_miss: bool,
+
+    /// Size budget for the cache file enforced by `kopi cache clean`; 0 means
+    /// no budget
+    #[serde(default)]
+    pub max_size_mb: u64,
 }
 
 impl Default for MetadataCacheConfig {
@@ -95,11 +150,12 @@ impl Default for MetadataCacheConfig {
             max_age_hours: default_metadata_cache_max_age_hours(),
             auto_refresh: true,
             refresh_on_miss: true,
+            max_size_mb: 0,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum SourceConfig {
     #[serde(rename = "http")]
@@ -112,6 +168,9 @@ pub enum SourceConfig {
         cache_locally: bool,
         #[serde(default = "default_timeout_secs")]
         timeout_secs: u64,
+        /// Look for `<archive>.sha256`/`.sha512` files when a package has no checksum
+        #[serde(default = "default_false")]
+        checksum_sidecars: bool,
     },
     #[serde(rename = "local")]
     Local {
@@ -133,23 +192,102 @@ pub enum SourceConfig {
         #[serde(default = "default_timeout_secs")]
         timeout_secs: u64,
     },
+    /// External executable speaking the JSON protocol in `metadata::command`
+    #[serde(rename = "command")]
+    Command {
+        name: String,
+        #[serde(default = "default_true")]
+        enabled: bool,
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default = "default_timeout_secs")]
+        timeout_secs: u64,
+    },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A `[[custom_distribution]]` entry, e.g. a company-built OpenJDK
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CustomDistributionConfig {
+    /// Distribution name used in version specs, e.g. `acme` for `acme@21`
+    pub name: String,
+    /// `https://` or `file://` URL of a JSON array of packages in kopi's metadata format
+    pub metadata_url: String,
+}
+
+impl CustomDistributionConfig {
+    /// Check that the name can be used in version specs and installation directory names
+    pub fn validate(&self) -> Result<()> {
+        let name = self.name.as_str();
+        let valid_chars = name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_'));
+        let starts_with_letter = name.chars().next().is_some_and(|c| c.is_ascii_lowercase());
+        // `<name>-<version>` directories are split at the first `-` followed by a digit
+        let splits_cleanly = !name
+            .as_bytes()
+            .windows(2)
+            .any(|pair| pair[0] == b'-' && pair[1].is_ascii_digit());
+
+        if !(valid_chars && starts_with_letter && splits_cleanly) {
+            return Err(KopiError::InvalidConfig(format!(
+                "Invalid custom distribution name '{name}': use lowercase letters, digits, '-' \
+                 and '_', starting with a letter and without '-' before a digit"
+            )));
+        }
+
+        if crate::models::distribution::Distribution::known_distributions().contains(&name) {
+            return Err(KopiError::InvalidConfig(format!(
+                "Custom distribution '{name}' conflicts with a built-in distribution"
+            )));
+        }
+
+        if !self.metadata_url.starts_with("https://") && !self.metadata_url.starts_with("file://") {
+            return Err(KopiError::InvalidConfig(format!(
+                "Custom distribution '{name}' needs an https:// or file:// metadata_url, got '{}'",
+                self.metadata_url
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StorageConfig {
     #[serde(default = "default_min_disk_space_mb")]
     pub min_disk_space_mb: u64,
+    /// Multiplier applied to an archive's size to estimate its extracted size
+    #[serde(default = "default_expansion_factor")]
+    pub expansion_factor: f64,
+    /// Read-only directory of pre-installed JDKs listed beneath the user's own;
+    /// `KOPI_SYSTEM_JDKS` takes precedence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_jdks_dir: Option<PathBuf>,
+    /// Root-owned Kopi home shared by every user of the machine: its JDKs
+    /// are listed like `system_jdks_dir` and `kopi install` writes to it;
+    /// `KOPI_SHARED_HOME` takes precedence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_home: Option<PathBuf>,
+    /// Remove leftovers of interrupted installs and uninstalls whenever
+    /// kopi starts, as `kopi uninstall --cleanup` does
+    #[serde(default = "default_false")]
+    pub cleanup_on_startup: bool,
 }
 
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
             min_disk_space_mb: DEFAULT_MIN_DISK_SPACE_MB,
+            expansion_factor: DEFAULT_EXPANSION_FACTOR,
+            system_jdks_dir: None,
+            shared_home: None,
+            cleanup_on_startup: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AutoInstallConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -171,7 +309,7 @@ impl Default for AutoInstallConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ShimsConfig {
     #[serde(default = "default_true")]
     pub auto_create_shims: bool,
@@ -185,6 +323,10 @@ pub struct ShimsConfig {
     pub auto_install_prompt: bool,
     #[serde(default = "default_shim_install_timeout")]
     pub install_timeout: u64,
+    /// Script wrappers written next to each shim on Windows, for tools that
+    /// launch `java.cmd` or PowerShell scripts
+    #[serde(default = "default_windows_wrappers")]
+    pub windows_wrappers: Vec<ShimWrapper>,
 }
 
 impl Default for ShimsConfig {
@@ -196,11 +338,451 @@ impl Default for ShimsConfig {
             auto_install: false,
             auto_install_prompt: true,
             install_timeout: 600,
+            windows_wrappers: default_windows_wrappers(),
+        }
+    }
+}
+
+/// Script that launches a shim on Windows
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ShimWrapper {
+    /// `<tool>.cmd`, run by cmd.exe and by tools that invoke `java.cmd`
+    Cmd,
+    /// `<tool>.ps1`; PowerShell prefers it over `<tool>.exe`, so it only
+    /// works where the execution policy allows local scripts
+    Ps1,
+}
+
+/// TLS trust settings applied to every HTTPS connection kopi makes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct NetworkConfig {
+    /// PEM bundle with additional root certificates (e.g. a corporate proxy CA).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct LoggingConfig {
+    /// Append log records to this file; relative paths resolve against KOPI_HOME.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    #[serde(default)]
+    pub format: LogFormat,
+    /// Rotate the log file once it would grow past this size (0 disables rotation).
+    #[serde(default = "default_log_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Number of rotated files to keep next to the log file (`<file>.1` is the newest).
+    #[serde(default = "default_log_max_files")]
+    pub max_files: u32,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            format: LogFormat::default(),
+            max_size_mb: default_log_max_size_mb(),
+            max_files: default_log_max_files(),
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Resolve the log file, preferring an explicit `--log-file` override
+    pub fn resolve_file(&self, kopi_home: &Path, override_path: Option<&Path>) -> Option<PathBuf> {
+        if let Some(path) = override_path {
+            return Some(path.to_path_buf());
+        }
+        self.file.as_ref().map(|path| {
+            if path.is_absolute() {
+                path.clone()
+            } else {
+                kopi_home.join(path)
+            }
+        })
+    }
+}
+
+/// Local crash reports written when kopi panics; they are never uploaded
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct CrashReportsConfig {
+    /// Write a report under `KOPI_HOME/crash` when kopi or a shim panics.
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+    /// Record the full command line instead of only the subcommand; the
+    /// arguments may hold tokens or private paths.
+    #[serde(default = "default_false")]
+    pub include_command: bool,
+    /// Number of reports to keep; older ones are deleted when a new one is written.
+    #[serde(default = "default_crash_max_reports")]
+    pub max_reports: usize,
+}
+
+impl Default for CrashReportsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            include_command: false,
+            max_reports: default_crash_max_reports(),
+        }
+    }
+}
+
+fn default_crash_max_reports() -> usize {
+    10
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+fn default_log_max_size_mb() -> u64 {
+    10
+}
+
+fn default_log_max_files() -> u32 {
+    5
+}
+
+/// How progress is drawn while kopi works
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ProgressDisplayConfig {
+    #[serde(default)]
+    pub style: ProgressRenderer,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressRenderer {
+    /// Animated bars on interactive terminals, milestone lines elsewhere
+    #[default]
+    Auto,
+    /// Always draw animated bars
+    Bar,
+    /// Always print one line per 10% milestone
+    Line,
+}
+
+/// Limits applied to JDK downloads
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct DownloadConfig {
+    /// Bandwidth cap per download in bytes per second; 0 means unlimited
+    #[serde(default)]
+    pub max_rate: u64,
+    /// Downloads allowed to run at once in one kopi process; 0 means unlimited
+    #[serde(default)]
+    pub max_concurrent: usize,
+    /// Directory of verified archives reused by later installs; defaults to
+    /// `downloads` in the Kopi home. Point several homes at one directory to
+    /// share it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+    /// Size cap of the download cache in MB; 0 turns the cache off
+    #[serde(default = "default_download_cache_max_size_mb")]
+    pub cache_max_size_mb: u64,
+    /// How archives are fetched
+    #[serde(default)]
+    pub backend: DownloadBackend,
+    /// Parallel ranges per archive with the `segmented` backend
+    #[serde(default = "default_download_segments")]
+    pub segments: usize,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            max_rate: 0,
+            max_concurrent: 0,
+            cache_dir: None,
+            cache_max_size_mb: default_download_cache_max_size_mb(),
+            backend: DownloadBackend::default(),
+            segments: default_download_segments(),
+        }
+    }
+}
+
+impl DownloadConfig {
+    /// Most ranges one archive is split into
+    pub const MAX_SEGMENTS: usize = 16;
+
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=Self::MAX_SEGMENTS).contains(&self.segments) {
+            return Err(KopiError::InvalidConfig(format!(
+                "download.segments must be between 1 and {}, got {}",
+                Self::MAX_SEGMENTS,
+                self.segments
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadBackend {
+    /// One connection per archive
+    #[default]
+    Standard,
+    /// Several ranged connections per archive, for high-latency links.
+    /// Needs a build with the `segmented-download` feature.
+    Segmented,
+}
+
+fn default_download_cache_max_size_mb() -> u64 {
+    4096
+}
+
+fn default_download_segments() -> usize {
+    4
+}
+
+/// How `kopi local` and `kopi global` write the selected JDK to a version file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct PinConfig {
+    /// Write `temurin@21.0.5` rather than `21.0.5`
+    #[serde(default = "default_true")]
+    pub include_distribution: bool,
+    #[serde(default)]
+    pub version_format: PinVersionFormat,
+}
+
+impl Default for PinConfig {
+    fn default() -> Self {
+        Self {
+            include_distribution: true,
+            version_format: PinVersionFormat::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PinVersionFormat {
+    /// The installed version, e.g. `21.0.5+11`
+    #[default]
+    Full,
+    /// The installed version without trailing zero components, e.g. `21` for `21.0.0`
+    Minimal,
+    /// Only the feature release, e.g. `21`
+    Major,
+}
+
+/// Per-version `JAVA_HOME` variables printed by `kopi env --all-installed`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct EnvConfig {
+    /// Variable name with `{major}`, `{arch}`, `{ARCH}` and `{distribution}` placeholders
+    #[serde(default = "default_java_home_template")]
+    pub java_home_template: String,
+    /// Also set the per-version variables for every tool a shim runs
+    #[serde(default)]
+    pub shim_java_homes: bool,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            java_home_template: default_java_home_template(),
+            shim_java_homes: false,
+        }
+    }
+}
+
+fn default_java_home_template() -> String {
+    "JAVA_HOME_{major}_{ARCH}".to_string()
+}
+
+/// Vulnerability advisory feed consulted by `kopi audit`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct AuditConfig {
+    /// `https://` or `file://` URL of the advisory feed
+    #[serde(default = "default_advisory_feed_url")]
+    pub feed_url: String,
+    /// Re-download the cached feed once it is older than this
+    #[serde(default = "default_advisory_max_age_hours")]
+    pub max_age_hours: u64,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            feed_url: default_advisory_feed_url(),
+            max_age_hours: default_advisory_max_age_hours(),
+        }
+    }
+}
+
+fn default_advisory_feed_url() -> String {
+    "https://kopi-vm.github.io/metadata/advisories.json".to_string()
+}
+
+fn default_advisory_max_age_hours() -> u64 {
+    24
+}
+
+/// Post-extraction customization applied by `kopi install`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct InstallConfig {
+    /// Paths relative to JAVA_HOME removed after extraction, e.g. `man` or `lib/src.zip`
+    #[serde(default)]
+    pub strip: Vec<String>,
+    /// PEM files of extra CA certificates imported into each installed JDK's
+    /// `cacerts`; relative paths resolve against KOPI_HOME
+    #[serde(default)]
+    pub certificates: Vec<PathBuf>,
+    /// Entries written to each installed JDK's `java.security`, overriding its defaults
+    #[serde(default)]
+    pub security_properties: BTreeMap<String, String>,
+    /// Password of the JDKs' `cacerts` truststore (default `changeit`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truststore_password: Option<String>,
+    /// Per-distribution overrides keyed by distribution id, e.g. `[install.distribution.zulu]`
+    #[serde(default, rename = "distribution")]
+    pub distributions: BTreeMap<String, DistributionInstallConfig>,
+}
+
+/// Install settings for a single distribution
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct DistributionInstallConfig {
+    /// Replaces `install.strip` for this distribution when set
+    #[serde(default)]
+    pub strip: Option<Vec<String>>,
+    /// Certificates imported in addition to `install.certificates`
+    #[serde(default)]
+    pub certificates: Vec<PathBuf>,
+    /// Properties that extend or override `install.security_properties`
+    #[serde(default)]
+    pub security_properties: BTreeMap<String, String>,
+}
+
+impl InstallConfig {
+    /// Paths to strip from a freshly extracted JDK of `distribution`
+    pub fn strip_for(&self, distribution: &str) -> &[String] {
+        self.distributions
+            .get(distribution)
+            .and_then(|dist| dist.strip.as_deref())
+            .unwrap_or(&self.strip)
+    }
+
+    /// Reject entries that could escape the JDK directory
+    pub fn validate(&self) -> Result<()> {
+        let per_distribution = self
+            .distributions
+            .values()
+            .filter_map(|dist| dist.strip.as_ref())
+            .flatten();
+        for entry in self.strip.iter().chain(per_distribution) {
+            let path = Path::new(entry);
+            let contained = !entry.trim().is_empty()
+                && path
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)));
+            if !contained {
+                return Err(KopiError::InvalidConfig(format!(
+                    "Invalid install.strip entry '{entry}': use a path relative to JAVA_HOME \
+                     without '..'"
+                )));
+            }
+        }
+
+        let properties = self.security_properties.iter().chain(
+            self.distributions
+                .values()
+                .flat_map(|dist| &dist.security_properties),
+        );
+        for (key, value) in properties {
+            let valid_key = !key.trim().is_empty()
+                && !key.contains(['=', ':', '\n', '\r', '#', '!'])
+                && !key.contains(char::is_whitespace);
+            if !valid_key || value.contains(['\n', '\r']) {
+                return Err(KopiError::InvalidConfig(format!(
+                    "Invalid install.security_properties entry '{key}': keys need a single \
+                     word without '=' or ':', and values a single line"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Organisation rules on which JDKs `kopi install` and auto-install may fetch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct PolicyConfig {
+    /// Distribution ids that may be installed; empty allows every distribution
+    #[serde(default)]
+    pub allowed_distributions: Vec<String>,
+    /// Java feature releases that may be installed; empty allows every version
+    #[serde(default)]
+    pub allowed_major_versions: Vec<u32>,
+    /// Refuse early-access builds
+    #[serde(default = "default_false")]
+    pub block_ea: bool,
+    /// Whether `kopi install --ignore-policy` may bypass the rules above
+    #[serde(default = "default_true")]
+    pub allow_override: bool,
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            allowed_distributions: Vec::new(),
+            allowed_major_versions: Vec::new(),
+            block_ea: false,
+            allow_override: true,
+        }
+    }
+}
+
+impl PolicyConfig {
+    /// Why the configured rules forbid installing this JDK, if they do
+    pub fn violation(
+        &self,
+        distribution: &str,
+        version: &Version,
+        early_access: bool,
+    ) -> Option<String> {
+        if !self.allowed_distributions.is_empty()
+            && !self
+                .allowed_distributions
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(distribution))
+        {
+            return Some(format!(
+                "distribution '{distribution}' is not in policy.allowed_distributions [{}]",
+                self.allowed_distributions.join(", ")
+            ));
+        }
+
+        if !self.allowed_major_versions.is_empty()
+            && !self.allowed_major_versions.contains(&version.major())
+        {
+            let allowed: Vec<String> = self
+                .allowed_major_versions
+                .iter()
+                .map(u32::to_string)
+                .collect();
+            return Some(format!(
+                "Java {} is not in policy.allowed_major_versions [{}]",
+                version.major(),
+                allowed.join(", ")
+            ));
+        }
+
+        if self.block_ea && early_access {
+            return Some(format!(
+                "{distribution} {version} is an early-access build and policy.block_ea is set"
+            ));
         }
+
+        None
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct LockingConfig {
     #[serde(default = "default_locking_mode")]
     pub mode: LockingMode,
@@ -218,6 +800,51 @@ pub struct LockingConfig {
 
     #[serde(skip, default)]
     timeout_source: LockTimeoutSource,
+
+    /// Per-scope overrides of `timeout` from `[locking.timeouts]`
+    #[serde(default)]
+    pub timeouts: LockScopeTimeouts,
+}
+
+/// `[locking.timeouts]`: how long each kind of lock may be waited for
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct LockScopeTimeouts {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_lock_timeout",
+        serialize_with = "serialize_optional_lock_timeout",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub installation: Option<LockTimeoutValue>,
+
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_lock_timeout",
+        serialize_with = "serialize_optional_lock_timeout",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cache: Option<LockTimeoutValue>,
+
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_lock_timeout",
+        serialize_with = "serialize_optional_lock_timeout",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub config: Option<LockTimeoutValue>,
+}
+
+impl LockScopeTimeouts {
+    /// Configured timeout for `scope` together with its key
+    pub fn for_scope(&self, scope: &LockScope) -> Option<(LockTimeoutValue, &'static str)> {
+        match scope {
+            LockScope::Installation { .. } => {
+                self.installation.map(|value| (value, "installation"))
+            }
+            LockScope::CacheWriter => self.cache.map(|value| (value, "cache")),
+            LockScope::GlobalConfig => self.config.map(|value| (value, "config")),
+        }
+    }
 }
 
 impl LockingConfig {
@@ -243,6 +870,25 @@ impl LockingConfig {
         self.timeout_source
     }
 
+    /// Effective timeout for `scope`. CLI and environment overrides apply to
+    /// every scope; otherwise a `[locking.timeouts]` entry beats `timeout`.
+    pub fn timeout_for(&self, scope: &LockScope) -> LockTimeoutResolution {
+        let overridden = matches!(
+            self.timeout_source,
+            LockTimeoutSource::Cli | LockTimeoutSource::Environment
+        );
+        match self.timeouts.for_scope(scope) {
+            Some((value, key)) if !overridden => LockTimeoutResolution {
+                value,
+                source: LockTimeoutSource::ScopeConfig(key),
+            },
+            _ => LockTimeoutResolution {
+                value: self.effective_timeout,
+                source: self.timeout_source,
+            },
+        }
+    }
+
     pub fn resolve_timeout(
         &mut self,
         cli_override: Option<&str>,
@@ -276,11 +922,12 @@ impl Default for LockingConfig {
             configured_timeout: default_timeout,
             effective_timeout: default_timeout,
             timeout_source: LockTimeoutSource::Default,
+            timeouts: LockScopeTimeouts::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum LockingMode {
     #[default]
@@ -306,10 +953,18 @@ fn default_shim_install_timeout() -> u64 {
     600 // 10 minutes for shim-specific installations
 }
 
+fn default_windows_wrappers() -> Vec<ShimWrapper> {
+    vec![ShimWrapper::Cmd]
+}
+
 fn default_min_disk_space_mb() -> u64 {
     DEFAULT_MIN_DISK_SPACE_MB
 }
 
+fn default_expansion_factor() -> f64 {
+    DEFAULT_EXPANSION_FACTOR
+}
+
 fn default_locking_mode() -> LockingMode {
     LockingMode::Auto
 }
@@ -343,6 +998,28 @@ where
     }
 }
 
+fn deserialize_optional_lock_timeout<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<LockTimeoutValue>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_lock_timeout(deserializer).map(Some)
+}
+
+fn serialize_optional_lock_timeout<S>(
+    value: &Option<LockTimeoutValue>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => serialize_lock_timeout(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 fn serialize_lock_timeout<S>(
     value: &LockTimeoutValue,
     serializer: S,
@@ -368,6 +1045,7 @@ fn default_metadata_sources() -> Vec<SourceConfig> {
             base_url: default_http_base_url(),
             cache_locally: true,
             timeout_secs: 30,
+            checksum_sidecars: false,
         },
         SourceConfig::Foojay {
             name: "foojay-api".to_string(),
@@ -395,29 +1073,147 @@ fn default_foojay_base_url() -> String {
 }
 
 /// Create a new KopiConfig with automatic home directory resolution
-pub fn new_kopi_config() -> Result<KopiConfig> {
-    let kopi_home = resolve_kopi_home()?;
-    KopiConfig::new(kopi_home)
+/// Configuration files merged into a [`KopiConfig`], lowest precedence first.
+///
+/// Environment variables and CLI flags are applied on top of these.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigFiles {
+    /// Machine-wide `/etc/kopi/config.toml` (`%ProgramData%\kopi\config.toml` on Windows)
+    pub system: Option<PathBuf>,
+    /// `config.toml` in the Kopi home
+    pub user: Option<PathBuf>,
+    /// Nearest `.kopi.toml` from the current directory upwards
+    pub project: Option<PathBuf>,
 }
 
-/// Resolve the KOPI home directory from environment variable or default location
-fn resolve_kopi_home() -> Result<PathBuf> {
-    // Check KOPI_HOME environment variable first
-    if let Ok(kopi_home) = std::env::var("KOPI_HOME") {
-        let path = PathBuf::from(&kopi_home);
-        if path.is_absolute() {
-            return Ok(path);
-        } else {
-            let default_path = home_dir().map(|home| home.join(".kopi")).ok_or_else(|| {
-                KopiError::ConfigError("Unable to determine home directory".to_string())
-            })?;
-            warn!(
-                "KOPI_HOME environment variable '{}' is not an absolute path. Ignoring and using \
-                 default path: {}",
-                kopi_home,
-                default_path.display()
-            );
-            return Ok(default_path);
+impl ConfigFiles {
+    /// Where the merged value of `key` came from, for naming it in errors.
+    /// `key` may carry array indices such as `metadata.sources[0].type`.
+    fn source_of(&self, key: &str) -> String {
+        let key = key.split('[').next().unwrap_or(key);
+        let sets = |keys: Result<BTreeSet<String>>| keys.is_ok_and(|keys| sets_key(&keys, key));
+        let environment = environment_source()
+            .collect()
+            .map(|values| values.into_keys().collect())
+            .map_err(KopiError::from);
+        if sets(environment) {
+            return "KOPI_* environment variables".to_string();
+        }
+        for path in [&self.project, &self.user, &self.system]
+            .into_iter()
+            .flatten()
+        {
+            let keys = if Some(path) == self.project.as_ref() {
+                project_keys(Some(path))
+            } else {
+                file_keys(Some(path))
+            };
+            if sets(keys) {
+                return path.display().to_string();
+            }
+        }
+        "configuration".to_string()
+    }
+
+    /// The layers that exist for `kopi_home` and the current directory
+    pub fn discover(kopi_home: &Path) -> Self {
+        let user = kopi_home.join(CONFIG_FILE_NAME);
+        Self {
+            system: Some(system_config_path()).filter(|path| path.is_file()),
+            user: Some(user).filter(|path| path.is_file()),
+            project: std::env::current_dir()
+                .ok()
+                .and_then(|dir| find_project_config(&dir)),
+        }
+    }
+}
+
+/// Machine-wide configuration file, relocatable with `KOPI_SYSTEM_CONFIG`
+pub fn system_config_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(SYSTEM_CONFIG_ENV) {
+        return PathBuf::from(path);
+    }
+
+    #[cfg(windows)]
+    {
+        std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+            .join("kopi")
+            .join(CONFIG_FILE_NAME)
+    }
+
+    #[cfg(not(windows))]
+    {
+        Path::new("/etc/kopi").join(CONFIG_FILE_NAME)
+    }
+}
+
+/// Nearest `.kopi.toml` in `start` or its ancestors
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Where an effective configuration value was set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigLayer {
+    Default,
+    System(PathBuf),
+    User(PathBuf),
+    Project(PathBuf),
+    Environment,
+    Cli,
+}
+
+impl std::fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigLayer::Default => f.write_str("default"),
+            ConfigLayer::System(path) => write!(f, "system ({})", path.display()),
+            ConfigLayer::User(path) => write!(f, "user ({})", path.display()),
+            ConfigLayer::Project(path) => write!(f, "project ({})", path.display()),
+            ConfigLayer::Environment => f.write_str("environment"),
+            ConfigLayer::Cli => f.write_str("command line"),
+        }
+    }
+}
+
+/// One effective setting, as printed by `kopi config show`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigEntry {
+    /// Dotted key, e.g. `auto_install.enabled`
+    pub key: String,
+    /// Value in TOML syntax
+    pub value: String,
+    pub origin: ConfigLayer,
+}
+
+pub fn new_kopi_config() -> Result<KopiConfig> {
+    let kopi_home = resolve_kopi_home()?;
+    KopiConfig::new(kopi_home)
+}
+
+/// Resolve the KOPI home directory from environment variable or default location
+fn resolve_kopi_home() -> Result<PathBuf> {
+    // Check KOPI_HOME environment variable first
+    if let Ok(kopi_home) = std::env::var("KOPI_HOME") {
+        let path = PathBuf::from(&kopi_home);
+        if path.is_absolute() {
+            return Ok(path);
+        } else {
+            let default_path = home_dir().map(|home| home.join(".kopi")).ok_or_else(|| {
+                KopiError::ConfigError("Unable to determine home directory".to_string())
+            })?;
+            warn!(
+                "KOPI_HOME environment variable '{}' is not an absolute path. Ignoring and using \
+                 default path: {}",
+                kopi_home,
+                default_path.display()
+            );
+            return Ok(default_path);
         }
     }
 
@@ -428,14 +1224,24 @@ fn resolve_kopi_home() -> Result<PathBuf> {
 }
 
 impl KopiConfig {
-    /// Create a new KopiConfig from the specified home directory
+    /// Create a new KopiConfig from the specified home directory, layering the
+    /// system, user and project configuration files found for it
     pub fn new(kopi_home: PathBuf) -> Result<Self> {
-        let config_path = kopi_home.join(CONFIG_FILE_NAME);
+        let files = ConfigFiles::discover(&kopi_home);
+        Self::from_files(kopi_home, files)
+    }
 
+    /// Create a KopiConfig from explicit configuration files.
+    ///
+    /// Later files override earlier ones and `KOPI_*` environment variables
+    /// override all files, except that `[policy]` keys set in the system file
+    /// cannot be changed by any other layer.
+    pub fn from_files(kopi_home: PathBuf, files: ConfigFiles) -> Result<Self> {
         // Build the configuration using the config crate
         let mut builder = Config::builder()
             // Set defaults
             .set_default("storage.min_disk_space_mb", DEFAULT_MIN_DISK_SPACE_MB)?
+            .set_default("storage.expansion_factor", DEFAULT_EXPANSION_FACTOR)?
             .set_default("default_distribution", "temurin")?
             .set_default("additional_distributions", Vec::<String>::new())?
             .set_default("auto_install.enabled", true)?
@@ -451,48 +1257,161 @@ impl KopiConfig {
             .set_default("locking.timeout", DEFAULT_LOCK_TIMEOUT_SECS)?
             .set_default("metadata.cache.max_age_hours", 720)?
             .set_default("metadata.cache.auto_refresh", true)?
-            .set_default("metadata.cache.refresh_on_miss", true)?;
+            .set_default("metadata.cache.refresh_on_miss", true)?
+            .set_default("metadata.cache.max_size_mb", 0)?
+            .set_default("logging.format", "text")?
+            .set_default("logging.max_size_mb", default_log_max_size_mb())?
+            .set_default("logging.max_files", default_log_max_files())?
+            .set_default("crash_reports.enabled", false)?
+            .set_default("crash_reports.include_command", false)?
+            .set_default(
+                "crash_reports.max_reports",
+                default_crash_max_reports() as u64,
+            )?
+            .set_default("progress.style", "auto")?
+            .set_default("download.max_rate", 0)?
+            .set_default("download.max_concurrent", 0)?
+            .set_default(
+                "download.cache_max_size_mb",
+                default_download_cache_max_size_mb(),
+            )?
+            .set_default("download.backend", "standard")?
+            .set_default("download.segments", default_download_segments() as u64)?
+            .set_default("audit.feed_url", default_advisory_feed_url())?
+            .set_default("audit.max_age_hours", default_advisory_max_age_hours())?
+            .set_default("install.strip", Vec::<String>::new())?
+            .set_default("env.java_home_template", default_java_home_template())?
+            .set_default("env.shim_java_homes", false)?
+            .set_default("pin.include_distribution", true)?
+            .set_default("pin.version_format", "full")?;
+
+        // Add the config files that exist, system first so that user and project win
+        for path in [&files.system, &files.user].into_iter().flatten() {
+            log::debug!("Loading config from {path:?}");
+            builder = builder.add_source(File::from(path.clone()).required(false));
+        }
+        // A checked-out repository is not trusted beyond its own pins
+        if let Some(path) = &files.project {
+            log::debug!("Loading project config from {path:?}");
+            let (table, ignored) = project_table(path)?;
+            if !ignored.is_empty() {
+                warn!(
+                    "Ignoring {} in {}: only {} can be set per project",
+                    ignored.join(", "),
+                    path.display(),
+                    PROJECT_KEYS.join(", ")
+                );
+            }
+            let content = toml::to_string(&table).map_err(|e| {
+                KopiError::ConfigError(format!("Failed to read {}: {e}", path.display()))
+            })?;
+            builder = builder.add_source(File::from_str(&content, FileFormat::Toml));
+        }
 
-        // Add the config file if it exists
-        if config_path.exists() {
-            log::debug!("Loading config from {config_path:?}");
-            builder = builder.add_source(File::from(config_path.clone()).required(false));
-        } else {
-            log::debug!("Config file not found at {config_path:?}, using defaults");
-        }
-
-        // Add environment variables with KOPI_ prefix
-        // The config crate will automatically map environment variables to config fields
-        // Double underscore (__) is used for nested fields to avoid ambiguity
-        // For example: KOPI_AUTO_INSTALL__ENABLED -> auto_install.enabled
-        builder = builder.add_source(
-            Environment::with_prefix("KOPI")
-                .prefix_separator("_")
-                .separator("__")
-                .list_separator(",")
-                .with_list_parse_key("additional_distributions")
-                .with_list_parse_key("shims.additional_tools")
-                .with_list_parse_key("shims.exclude_tools")
-                .try_parsing(true),
-        );
+        builder = builder.add_source(environment_source());
+
+        // Administrators' policy outranks every later layer, including the environment
+        if let Some(system) = &files.system {
+            let system_policy = Config::builder()
+                .add_source(File::from(system.clone()))
+                .build()?
+                .get_table("policy")
+                .unwrap_or_default();
+            for (key, value) in system_policy {
+                builder = builder.set_override(format!("policy.{key}"), value)?;
+            }
+        }
 
         // Build and deserialize the configuration
         let settings = builder
             .build()
             .map_err(|e| KopiError::ConfigError(format!("Failed to build config: {e}")))?;
 
-        let mut config: KopiConfig = settings
-            .try_deserialize()
-            .map_err(|e| KopiError::ConfigError(format!("Failed to deserialize config: {e}")))?;
+        let mut config: KopiConfig = serde_path_to_error::deserialize(settings).map_err(|e| {
+            let source = files.source_of(&e.path().to_string());
+            match crate::schema::schema_violation(&source, e) {
+                // The config crate appends the key and file, which are already named
+                KopiError::SchemaViolation {
+                    file,
+                    path,
+                    message,
+                } => KopiError::SchemaViolation {
+                    file,
+                    path,
+                    message: message
+                        .split(" for key `")
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                },
+                other => other,
+            }
+        })?;
 
         // Set the kopi_home path
         config.kopi_home = kopi_home;
+        config.files = files;
         config.locking.initialize_effective_timeout();
         let _ = config.apply_lock_timeout_overrides(None)?;
+        config.validate_custom_distributions()?;
+        config.install.validate()?;
+        config.download.validate()?;
 
         Ok(config)
     }
 
+    /// Every effective setting with the layer that set it, sorted by key
+    pub fn entries(&self) -> Result<Vec<ConfigEntry>> {
+        let effective = toml::Value::try_from(self)
+            .map_err(|e| KopiError::ConfigError(format!("Failed to serialize config: {e}")))?;
+        let mut values = BTreeMap::new();
+        flatten_toml("", &effective, &mut values);
+        // The serialized timeout is the configured one, before CLI and environment overrides
+        let timeout = match self.locking.timeout_value() {
+            LockTimeoutValue::Finite(duration) => toml::Value::Integer(duration.as_secs() as i64),
+            LockTimeoutValue::Infinite => toml::Value::String("infinite".to_string()),
+        };
+        values.insert("locking.timeout".to_string(), timeout);
+
+        let system = file_keys(self.files.system.as_deref())?;
+        let user = file_keys(self.files.user.as_deref())?;
+        let project = project_keys(self.files.project.as_deref())?;
+        let environment: BTreeSet<String> = environment_source().collect()?.into_keys().collect();
+
+        let origin_of = |key: &str| {
+            if key == "locking.timeout" {
+                match self.locking.timeout_source() {
+                    LockTimeoutSource::Cli => return ConfigLayer::Cli,
+                    LockTimeoutSource::Environment => return ConfigLayer::Environment,
+                    _ => {}
+                }
+            }
+            let layer_path = |path: &Option<PathBuf>| path.clone().unwrap_or_default();
+            if key.starts_with("policy.") && sets_key(&system, key) {
+                ConfigLayer::System(layer_path(&self.files.system))
+            } else if sets_key(&environment, key) {
+                ConfigLayer::Environment
+            } else if sets_key(&project, key) {
+                ConfigLayer::Project(layer_path(&self.files.project))
+            } else if sets_key(&user, key) {
+                ConfigLayer::User(layer_path(&self.files.user))
+            } else if sets_key(&system, key) {
+                ConfigLayer::System(layer_path(&self.files.system))
+            } else {
+                ConfigLayer::Default
+            }
+        };
+
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| ConfigEntry {
+                origin: origin_of(&key),
+                value: value.to_string(),
+                key,
+            })
+            .collect())
+    }
+
     pub fn apply_lock_timeout_overrides(
         &mut self,
         cli_override: Option<&str>,
@@ -519,11 +1438,73 @@ impl KopiConfig {
         Ok(())
     }
 
+    /// Check that `content` is a well-formed configuration file in which `key`
+    /// is a recognised setting, as `kopi config set` requires before writing
+    pub fn validate_file_content(content: &str, key: &str) -> Result<()> {
+        let config: KopiConfig = toml::from_str(content)
+            .map_err(|e| KopiError::InvalidConfig(format!("{key}: {}", e.message())))?;
+        config.validate_custom_distributions()?;
+        config.install.validate()?;
+        config.download.validate()?;
+
+        let serialized = toml::Value::try_from(&config)
+            .map_err(|e| KopiError::ConfigError(format!("Failed to serialize config: {e}")))?;
+        let mut values = BTreeMap::new();
+        flatten_toml("", &serialized, &mut values);
+        if !values
+            .keys()
+            .any(|set| set == key || set.starts_with(&format!("{key}.")))
+        {
+            return Err(KopiError::InvalidConfig(format!(
+                "Unknown configuration key '{key}'. Run 'kopi config show' to list the \
+                 available keys."
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_custom_distributions(&self) -> Result<()> {
+        for (index, dist) in self.custom_distributions.iter().enumerate() {
+            dist.validate()?;
+            if self.custom_distributions[..index]
+                .iter()
+                .any(|other| other.name == dist.name)
+            {
+                return Err(KopiError::InvalidConfig(format!(
+                    "Custom distribution '{}' is defined more than once",
+                    dist.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Distribution names accepted in version specs besides the built-in ones:
+    /// `additional_distributions` followed by custom distributions
+    pub fn extra_distribution_names(&self) -> impl Iterator<Item = &str> {
+        self.additional_distributions
+            .iter()
+            .map(String::as_str)
+            .chain(
+                self.custom_distributions
+                    .iter()
+                    .map(|dist| dist.name.as_str()),
+            )
+    }
+
     /// Get the KOPI home directory
     pub fn kopi_home(&self) -> &Path {
         &self.kopi_home
     }
 
+    /// These settings with JDKs, caches and locks kept under `kopi_home`
+    pub fn with_kopi_home(&self, kopi_home: PathBuf) -> Self {
+        Self {
+            kopi_home,
+            ..self.clone()
+        }
+    }
+
     /// Get the JDKs directory path and create it if it doesn't exist
     pub fn jdks_dir(&self) -> Result<PathBuf> {
         home::ensure_jdks_dir(&self.kopi_home).map_err(|error| {
@@ -531,6 +1512,34 @@ impl KopiConfig {
         })
     }
 
+    /// Read-only JDK directory layered under [`Self::jdks_dir`], from
+    /// `KOPI_SYSTEM_JDKS` or `storage.system_jdks_dir`, else the JDKs of the
+    /// [shared home](Self::shared_home)
+    pub fn system_jdks_dir(&self) -> Option<PathBuf> {
+        std::env::var_os(SYSTEM_JDKS_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| self.storage.system_jdks_dir.clone())
+            .or_else(|| self.shared_home().map(|shared| home::jdks_dir(&shared)))
+    }
+
+    /// Kopi home shared by all users, from `KOPI_SHARED_HOME` or
+    /// `storage.shared_home`
+    pub fn shared_home(&self) -> Option<PathBuf> {
+        std::env::var_os(SHARED_HOME_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| self.storage.shared_home.clone())
+    }
+
+    /// Directory of the download cache; see [`DownloadConfig::cache_dir`]
+    pub fn download_cache_dir(&self) -> PathBuf {
+        self.download
+            .cache_dir
+            .clone()
+            .unwrap_or_else(|| home::downloads_dir(&self.kopi_home))
+    }
+
     /// Get the cache directory path and create it if it doesn't exist
     pub fn cache_dir(&self) -> Result<PathBuf> {
         home::ensure_cache_dir(&self.kopi_home).map_err(|error| {
@@ -566,6 +1575,92 @@ impl KopiConfig {
     }
 }
 
+/// `KOPI_*` environment variables as configuration keys.
+///
+/// Double underscore (`__`) separates nested fields, so
+/// `KOPI_AUTO_INSTALL__ENABLED` sets `auto_install.enabled`.
+fn environment_source() -> Environment {
+    Environment::with_prefix("KOPI")
+        .prefix_separator("_")
+        .separator("__")
+        .list_separator(",")
+        .with_list_parse_key("additional_distributions")
+        .with_list_parse_key("shims.additional_tools")
+        .with_list_parse_key("shims.exclude_tools")
+        .with_list_parse_key("install.strip")
+        .with_list_parse_key("policy.allowed_distributions")
+        .with_list_parse_key("policy.allowed_major_versions")
+        .try_parsing(true)
+}
+
+/// Dotted keys set by a configuration file
+fn file_keys(path: Option<&Path>) -> Result<BTreeSet<String>> {
+    let Some(path) = path else {
+        return Ok(BTreeSet::new());
+    };
+    let content = fs::read_to_string(path)?;
+    let table: toml::Table = toml::from_str(&content)
+        .map_err(|e| KopiError::ConfigError(format!("Failed to parse {}: {e}", path.display())))?;
+    let mut values = BTreeMap::new();
+    flatten_toml("", &toml::Value::Table(table), &mut values);
+    Ok(values.into_keys().collect())
+}
+
+/// Keys of the project layer that take effect
+fn project_keys(path: Option<&Path>) -> Result<BTreeSet<String>> {
+    let Some(path) = path else {
+        return Ok(BTreeSet::new());
+    };
+    let (table, _) = project_table(path)?;
+    let mut values = BTreeMap::new();
+    flatten_toml("", &toml::Value::Table(table), &mut values);
+    Ok(values.into_keys().collect())
+}
+
+/// Split a `.kopi.toml` into the project-scoped settings and the names of
+/// the top-level keys it is not allowed to set
+fn project_table(path: &Path) -> Result<(toml::Table, Vec<String>)> {
+    let content = fs::read_to_string(path)?;
+    let table: toml::Table = toml::from_str(&content)
+        .map_err(|e| KopiError::ConfigError(format!("Failed to parse {}: {e}", path.display())))?;
+    let (kept, ignored): (toml::Table, toml::Table) = table
+        .into_iter()
+        .partition(|(key, _)| PROJECT_KEYS.contains(&key.as_str()));
+    Ok((kept, ignored.into_iter().map(|(key, _)| key).collect()))
+}
+
+/// Whether a project `.kopi.toml` may set `key`
+pub fn is_project_key(key: &str) -> bool {
+    PROJECT_KEYS
+        .iter()
+        .any(|allowed| key == *allowed || key.starts_with(&format!("{allowed}.")))
+}
+
+/// Whether `keys` sets `key` itself or a table containing it
+fn sets_key(keys: &BTreeSet<String>, key: &str) -> bool {
+    keys.iter()
+        .any(|set| set == key || key.starts_with(&format!("{set}.")))
+}
+
+/// Collect the leaves of `value` under dotted keys; arrays are leaves
+fn flatten_toml(prefix: &str, value: &toml::Value, out: &mut BTreeMap<String, toml::Value>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_toml(&key, value, out);
+            }
+        }
+        leaf => {
+            out.insert(prefix.to_string(), leaf.clone());
+        }
+    }
+}
+
 // Custom error conversion from config::ConfigError
 impl From<ConfigError> for KopiError {
     fn from(err: ConfigError) -> Self {
@@ -579,6 +1674,7 @@ mod tests {
     use crate::paths::{cache, home};
     use serial_test::serial;
     use std::env;
+    use std::str::FromStr;
     use tempfile::TempDir;
 
     #[test]
@@ -595,6 +1691,7 @@ mod tests {
         let kopi_home = resolve_kopi_home().unwrap();
         let config = KopiConfig::new(kopi_home).unwrap();
         assert_eq!(config.storage.min_disk_space_mb, DEFAULT_MIN_DISK_SPACE_MB);
+        assert_eq!(config.storage.expansion_factor, DEFAULT_EXPANSION_FACTOR);
         assert_eq!(config.default_distribution, "temurin");
         assert_eq!(config.locking.mode, LockingMode::Auto);
         assert_eq!(
@@ -655,6 +1752,7 @@ mod tests {
 
         let mut config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
         config.storage.min_disk_space_mb = 1024;
+        config.storage.expansion_factor = 2.5;
         config.default_distribution = "temurin".to_string();
         config.additional_distributions = vec!["mycustom".to_string(), "private-jdk".to_string()];
         config.auto_install.enabled = true;
@@ -669,6 +1767,7 @@ mod tests {
 
         let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
         assert_eq!(loaded.storage.min_disk_space_mb, 1024);
+        assert_eq!(loaded.storage.expansion_factor, 2.5);
         assert_eq!(loaded.default_distribution, "temurin");
         assert_eq!(
             loaded.additional_distributions,
@@ -713,6 +1812,36 @@ mod tests {
         assert!(loaded.additional_distributions.is_empty());
     }
 
+    #[test]
+    #[serial]
+    fn test_invalid_config_names_key_and_file() {
+        unsafe {
+            env::remove_var("KOPI_AUTO_INSTALL__ENABLED");
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(CONFIG_FILE_NAME);
+        fs::write(&config_path, "[auto_install]\nenabled = \"maybe\"\n").unwrap();
+
+        let files = ConfigFiles {
+            user: Some(config_path.clone()),
+            ..Default::default()
+        };
+        match KopiConfig::from_files(temp_dir.path().to_path_buf(), files) {
+            Err(KopiError::SchemaViolation {
+                file,
+                path,
+                message,
+            }) => {
+                assert_eq!(file, config_path.display().to_string());
+                assert_eq!(path, "auto_install.enabled");
+                assert!(message.contains("expected a boolean"), "{message}");
+                assert!(!message.contains("for key"), "{message}");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
     #[test]
     #[serial]
     fn test_config_with_storage_section() {
@@ -743,6 +1872,323 @@ min_disk_space_mb = 2048
         assert_eq!(loaded.additional_distributions, vec!["custom1", "custom2"]);
     }
 
+    #[test]
+    #[serial]
+    fn test_custom_distribution_section() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            r#"
+additional_distributions = ["extra"]
+
+[[custom_distribution]]
+name = "acme"
+metadata_url = "https://jdk.acme.internal/metadata.json"
+
+[[custom_distribution]]
+name = "acme_lts"
+metadata_url = "file:///opt/acme/metadata.json"
+"#,
+        )
+        .unwrap();
+
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(loaded.custom_distributions.len(), 2);
+        assert_eq!(loaded.custom_distributions[0].name, "acme");
+        assert_eq!(
+            loaded.custom_distributions[1].metadata_url,
+            "file:///opt/acme/metadata.json"
+        );
+        assert_eq!(
+            loaded.extra_distribution_names().collect::<Vec<_>>(),
+            vec!["extra", "acme", "acme_lts"]
+        );
+    }
+
+    #[test]
+    fn test_custom_distribution_validation() {
+        let custom = |name: &str, url: &str| CustomDistributionConfig {
+            name: name.to_string(),
+            metadata_url: url.to_string(),
+        };
+
+        assert!(
+            custom("acme", "https://example.com/m.json")
+                .validate()
+                .is_ok()
+        );
+        assert!(custom("acme-jdk", "file:///m.json").validate().is_ok());
+        assert!(
+            custom("Acme", "https://example.com/m.json")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            custom("acme-21", "https://example.com/m.json")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            custom("temurin", "https://example.com/m.json")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            custom("acme", "http://example.com/m.json")
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_duplicate_custom_distribution_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            r#"
+[[custom_distribution]]
+name = "acme"
+metadata_url = "https://a.example.com/m.json"
+
+[[custom_distribution]]
+name = "acme"
+metadata_url = "https://b.example.com/m.json"
+"#,
+        )
+        .unwrap();
+
+        let err = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap_err();
+        assert!(matches!(err, KopiError::InvalidConfig(_)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_network_config_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let defaults = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(defaults.network, NetworkConfig::default());
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            r#"
+[network]
+ca_bundle = "/etc/ssl/corp-ca.pem"
+"#,
+        )
+        .unwrap();
+
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(
+            loaded.network.ca_bundle,
+            Some(PathBuf::from("/etc/ssl/corp-ca.pem"))
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_audit_config_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let defaults = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(defaults.audit, AuditConfig::default());
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            r#"
+[audit]
+feed_url = "file:///srv/kopi/advisories.json"
+max_age_hours = 6
+"#,
+        )
+        .unwrap();
+
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(loaded.audit.feed_url, "file:///srv/kopi/advisories.json");
+        assert_eq!(loaded.audit.max_age_hours, 6);
+    }
+
+    #[test]
+    #[serial]
+    fn test_install_strip_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let defaults = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert!(defaults.install.strip_for("temurin").is_empty());
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            r#"
+[install]
+strip = ["man", "demo", "lib/src.zip"]
+
+[install.distribution.zulu]
+strip = ["man"]
+"#,
+        )
+        .unwrap();
+
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(
+            loaded.install.strip_for("temurin"),
+            ["man", "demo", "lib/src.zip"]
+        );
+        assert_eq!(loaded.install.strip_for("zulu"), ["man"]);
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[install]\nstrip = [\"../bin\"]\n",
+        )
+        .unwrap();
+        let err = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap_err();
+        assert!(matches!(err, KopiError::InvalidConfig(_)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_progress_config_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let defaults = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(defaults.progress.style, ProgressRenderer::Auto);
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[progress]\nstyle = \"line\"\n",
+        )
+        .unwrap();
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(loaded.progress.style, ProgressRenderer::Line);
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[progress]\nstyle = \"fancy\"\n",
+        )
+        .unwrap();
+        assert!(KopiConfig::new(temp_dir.path().to_path_buf()).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_pin_config_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let defaults = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(defaults.pin, PinConfig::default());
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[pin]\ninclude_distribution = false\nversion_format = \"major\"\n",
+        )
+        .unwrap();
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert!(!loaded.pin.include_distribution);
+        assert_eq!(loaded.pin.version_format, PinVersionFormat::Major);
+    }
+
+    #[test]
+    #[serial]
+    fn test_env_config_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let defaults = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(defaults.env, EnvConfig::default());
+        assert_eq!(defaults.env.java_home_template, "JAVA_HOME_{major}_{ARCH}");
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[env]\njava_home_template = \"JDK_{major}\"\nshim_java_homes = true\n",
+        )
+        .unwrap();
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(loaded.env.java_home_template, "JDK_{major}");
+        assert!(loaded.env.shim_java_homes);
+    }
+
+    #[test]
+    #[serial]
+    fn test_download_config_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let defaults = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(defaults.download, DownloadConfig::default());
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[download]\nmax_rate = 1048576\nmax_concurrent = 2\n",
+        )
+        .unwrap();
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(loaded.download.max_rate, 1_048_576);
+        assert_eq!(loaded.download.max_concurrent, 2);
+        assert_eq!(loaded.download.cache_max_size_mb, 4096);
+        assert_eq!(
+            loaded.download_cache_dir(),
+            temp_dir.path().join("downloads")
+        );
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[download]\ncache_dir = \"/srv/kopi-downloads\"\ncache_max_size_mb = 0\n",
+        )
+        .unwrap();
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(loaded.download.cache_max_size_mb, 0);
+        assert_eq!(
+            loaded.download_cache_dir(),
+            PathBuf::from("/srv/kopi-downloads")
+        );
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[download]\nbackend = \"segmented\"\nsegments = 8\n",
+        )
+        .unwrap();
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(loaded.download.backend, DownloadBackend::Segmented);
+        assert_eq!(loaded.download.segments, 8);
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[download]\nsegments = 0\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            KopiConfig::new(temp_dir.path().to_path_buf()),
+            Err(KopiError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_logging_config_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let defaults = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(defaults.logging, LoggingConfig::default());
+        assert_eq!(defaults.logging.resolve_file(temp_dir.path(), None), None);
+
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            r#"
+[logging]
+file = "logs/kopi.log"
+format = "json"
+max_size_mb = 1
+max_files = 2
+"#,
+        )
+        .unwrap();
+
+        let loaded = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(loaded.logging.format, LogFormat::Json);
+        assert_eq!(loaded.logging.max_size_mb, 1);
+        assert_eq!(loaded.logging.max_files, 2);
+        assert_eq!(
+            loaded.logging.resolve_file(temp_dir.path(), None),
+            Some(temp_dir.path().join("logs/kopi.log"))
+        );
+        assert_eq!(
+            loaded
+                .logging
+                .resolve_file(temp_dir.path(), Some(Path::new("/tmp/override.log"))),
+            Some(PathBuf::from("/tmp/override.log"))
+        );
+    }
+
     #[test]
     #[serial]
     fn test_infinite_lock_timeout_from_config() {
@@ -810,6 +2256,206 @@ timeout = "infinite"
         assert_eq!(resolution.source, LockTimeoutSource::Cli);
     }
 
+    #[test]
+    #[serial]
+    fn test_scope_lock_timeouts() {
+        unsafe {
+            env::remove_var("KOPI_LOCK_TIMEOUT");
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            r#"
+[locking]
+timeout = 120
+
+[locking.timeouts]
+cache = 5
+installation = "infinite"
+"#,
+        )
+        .unwrap();
+
+        let mut config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let cache = config.locking.timeout_for(&LockScope::CacheWriter);
+        assert_eq!(cache.value, LockTimeoutValue::from_secs(5));
+        assert_eq!(cache.source, LockTimeoutSource::ScopeConfig("cache"));
+        assert_eq!(
+            cache.source.to_string(),
+            "configuration file (locking.timeouts.cache)"
+        );
+        assert_eq!(
+            config.locking.timeout_for(&LockScope::GlobalConfig),
+            LockTimeoutResolution {
+                value: LockTimeoutValue::from_secs(120),
+                source: LockTimeoutSource::Config,
+            }
+        );
+
+        // --lock-timeout applies to every scope
+        config.apply_lock_timeout_overrides(Some("30")).unwrap();
+        let cache = config.locking.timeout_for(&LockScope::CacheWriter);
+        assert_eq!(cache.value, LockTimeoutValue::from_secs(30));
+        assert_eq!(cache.source, LockTimeoutSource::Cli);
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_layers_and_origins() {
+        unsafe {
+            env::remove_var("KOPI_LOCK_TIMEOUT");
+            env::remove_var("KOPI_DEFAULT_DISTRIBUTION");
+            env::remove_var("KOPI_AUTO_INSTALL__PROMPT");
+            env::remove_var("KOPI_POLICY__ALLOW_OVERRIDE");
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let files = ConfigFiles {
+            system: Some(write(
+                "system.toml",
+                "default_distribution = \"corretto\"\n\n[auto_install]\nprompt = false\n\n\
+                 [policy]\nallow_override = false\n",
+            )),
+            user: Some(write(
+                "user.toml",
+                "default_distribution = \"zulu\"\n\n[policy]\nallow_override = true\n",
+            )),
+            project: Some(write(
+                ".kopi.toml",
+                "default_distribution = \"temurin\"\n\n[tools]\njavac = \"21\"\n",
+            )),
+        };
+
+        let config = KopiConfig::from_files(temp_dir.path().to_path_buf(), files.clone()).unwrap();
+        assert_eq!(config.default_distribution, "temurin");
+        assert!(!config.auto_install.prompt);
+        // System policy cannot be relaxed by the user layer
+        assert!(!config.policy.allow_override);
+
+        let entries = config.entries().unwrap();
+        let origin = |key: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.key == key)
+                .map(|entry| entry.origin.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            origin("default_distribution"),
+            ConfigLayer::Project(files.project.clone().unwrap())
+        );
+        assert_eq!(
+            origin("auto_install.prompt"),
+            ConfigLayer::System(files.system.clone().unwrap())
+        );
+        assert_eq!(
+            origin("policy.allow_override"),
+            ConfigLayer::System(files.system.clone().unwrap())
+        );
+        assert_eq!(origin("auto_install.enabled"), ConfigLayer::Default);
+    }
+
+    #[test]
+    #[serial]
+    fn test_project_config_cannot_set_metadata_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join(".kopi.toml");
+        fs::write(
+            &project,
+            "default_distribution = \"zulu\"\n\n[shims]\nauto_install = true\n\n\
+             [[metadata.sources]]\ntype = \"command\"\nname = \"evil\"\n\
+             command = \"./pwn.sh\"\n",
+        )
+        .unwrap();
+        let files = ConfigFiles {
+            system: None,
+            user: None,
+            project: Some(project.clone()),
+        };
+
+        let defaults =
+            KopiConfig::from_files(temp_dir.path().to_path_buf(), ConfigFiles::default()).unwrap();
+        let config = KopiConfig::from_files(temp_dir.path().to_path_buf(), files).unwrap();
+        assert_eq!(config.default_distribution, "zulu");
+        assert!(
+            !config
+                .metadata
+                .sources
+                .iter()
+                .any(|source| matches!(source, SourceConfig::Command { .. }))
+        );
+        assert_eq!(
+            config.metadata.sources.len(),
+            defaults.metadata.sources.len()
+        );
+        assert_eq!(config.shims.auto_install, defaults.shims.auto_install);
+
+        let entries = config.entries().unwrap();
+        assert!(
+            entries
+                .iter()
+                .filter(|entry| entry.origin == ConfigLayer::Project(project.clone()))
+                .all(|entry| entry.key == "default_distribution")
+        );
+    }
+
+    #[test]
+    fn test_is_project_key() {
+        assert!(is_project_key("default_distribution"));
+        assert!(is_project_key("pin.version_format"));
+        assert!(is_project_key("tools.javac"));
+        assert!(!is_project_key("metadata.sources"));
+        assert!(!is_project_key("network.ca_bundle"));
+        assert!(!is_project_key("pinned"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_policy_config() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            r#"
+[policy]
+allowed_distributions = ["temurin"]
+allowed_major_versions = [17, 21]
+block_ea = true
+allow_override = false
+"#,
+        )
+        .unwrap();
+
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let policy = &config.policy;
+        assert!(!policy.allow_override);
+
+        let ga = Version::from_str("21.0.5").unwrap();
+        assert_eq!(policy.violation("temurin", &ga, false), None);
+        assert_eq!(policy.violation("Temurin", &ga, false), None);
+        assert_eq!(
+            policy.violation("zulu", &ga, false).unwrap(),
+            "distribution 'zulu' is not in policy.allowed_distributions [temurin]"
+        );
+        assert_eq!(
+            policy
+                .violation("temurin", &Version::from_str("11.0.25").unwrap(), false)
+                .unwrap(),
+            "Java 11 is not in policy.allowed_major_versions [17, 21]"
+        );
+        assert!(policy.violation("temurin", &ga, true).is_some());
+
+        assert_eq!(
+            PolicyConfig::default().violation("zulu", &Version::from_str("24-ea").unwrap(), true),
+            None
+        );
+    }
+
     #[test]
     #[serial]
     fn test_resolve_kopi_home_from_env() {
@@ -977,6 +2623,7 @@ timeout = "infinite"
             env::remove_var("KOPI_METADATA__CACHE__MAX_AGE_HOURS");
             env::remove_var("KOPI_METADATA__CACHE__AUTO_REFRESH");
             env::remove_var("KOPI_METADATA__CACHE__REFRESH_ON_MISS");
+            env::remove_var("KOPI_METADATA__CACHE__MAX_SIZE_MB");
         }
 
         let temp_dir = TempDir::new().unwrap();
@@ -986,6 +2633,7 @@ timeout = "infinite"
         assert_eq!(config.metadata.cache.max_age_hours, 720); // 30 days
         assert!(config.metadata.cache.auto_refresh);
         assert!(config.metadata.cache.refresh_on_miss);
+        assert_eq!(config.metadata.cache.max_size_mb, 0);
     }
 
     #[test]
@@ -1059,6 +2707,7 @@ min_disk_space_mb = 500
             env::set_var("KOPI_AUTO_INSTALL__PROMPT", "false");
             env::set_var("KOPI_AUTO_INSTALL__TIMEOUT_SECS", "600");
             env::set_var("KOPI_STORAGE__MIN_DISK_SPACE_MB", "1024");
+            env::set_var("KOPI_STORAGE__EXPANSION_FACTOR", "2.5");
             env::set_var("KOPI_DEFAULT_DISTRIBUTION", "corretto");
         }
 
@@ -1069,6 +2718,7 @@ min_disk_space_mb = 500
         assert!(!loaded.auto_install.prompt);
         assert_eq!(loaded.auto_install.timeout_secs, 600);
         assert_eq!(loaded.storage.min_disk_space_mb, 1024);
+        assert_eq!(loaded.storage.expansion_factor, 2.5);
         assert_eq!(loaded.default_distribution, "corretto");
 
         // Cleanup
@@ -1077,6 +2727,7 @@ min_disk_space_mb = 500
             env::remove_var("KOPI_AUTO_INSTALL__PROMPT");
             env::remove_var("KOPI_AUTO_INSTALL__TIMEOUT_SECS");
             env::remove_var("KOPI_STORAGE__MIN_DISK_SPACE_MB");
+            env::remove_var("KOPI_STORAGE__EXPANSION_FACTOR");
             env::remove_var("KOPI_DEFAULT_DISTRIBUTION");
         }
     }
@@ -0,0 +1,372 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local crash reports.
+//!
+//! When kopi or a shim panics, the hook installed by [`install_panic_hook`]
+//! writes the panic message, backtrace, subcommand, platform and a digest
+//! of the effective configuration to `KOPI_HOME/crash/`. The full command
+//! line is only recorded with `crash_reports.include_command`, since
+//! arguments can carry tokens or private paths. Paths under the
+//! home directory are shortened so the report can be attached to an issue
+//! as is. Nothing is sent anywhere; `kopi doctor` lists recent reports.
+
+use crate::config::KopiConfig;
+use crate::error::Result;
+use crate::paths::home;
+use crate::platform::get_current_platform;
+use chrono::{DateTime, SecondsFormat, Utc};
+use sha2::{Digest, Sha256};
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+
+const REPORT_PREFIX: &str = "crash-";
+const REPORT_EXTENSION: &str = "txt";
+const MESSAGE_FIELD: &str = "Message: ";
+/// Global options of `kopi` whose value is the next argument
+const VALUE_OPTIONS: &[&str] = &["--lock-timeout", "--log-file"];
+
+/// A crash report found in the crash directory
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    pub path: PathBuf,
+    pub written_at: DateTime<Utc>,
+    /// The panic message recorded in the report
+    pub message: String,
+}
+
+/// What a crash report records besides the panic itself
+struct CrashContext {
+    config: KopiConfig,
+    home_dir: Option<PathBuf>,
+}
+
+/// Write a crash report whenever the process panics, after the default
+/// panic message. Does nothing when `crash_reports.enabled` is off.
+pub fn install_panic_hook(config: &KopiConfig) {
+    if !config.crash_reports.enabled {
+        return;
+    }
+
+    let context = CrashContext {
+        config: config.clone(),
+        home_dir: dirs::home_dir(),
+    };
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        match write_report(&context, info) {
+            Ok(path) => eprintln!(
+                "kopi crashed. A crash report was saved to {}\n\
+                 It stays on this machine; attach it to an issue at \
+                 https://github.com/kopi-vm/kopi/issues if you report the crash.",
+                path.display()
+            ),
+            Err(err) => eprintln!("kopi crashed and could not save a crash report: {err}"),
+        }
+    }));
+}
+
+fn write_report(context: &CrashContext, info: &PanicHookInfo) -> Result<PathBuf> {
+    let kopi_home = context.config.kopi_home();
+    let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    };
+    let location = info
+        .location()
+        .map(|location| location.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let args: Vec<String> = std::env::args().collect();
+    let command = if context.config.crash_reports.include_command {
+        args.join(" ")
+    } else {
+        command_summary(&args)
+    };
+    let now = Utc::now();
+
+    let report = render_report(
+        &ReportFields {
+            written_at: now,
+            command: &command,
+            config_digest: &config_digest(&context.config),
+            thread: std::thread::current().name().unwrap_or("<unnamed>"),
+            location: &location,
+            message: &message,
+            backtrace: &Backtrace::force_capture().to_string(),
+        },
+        kopi_home,
+        context.home_dir.as_deref(),
+    );
+
+    let dir = home::ensure_crash_dir(kopi_home)?;
+    let path = dir.join(format!(
+        "{REPORT_PREFIX}{}-{}.{REPORT_EXTENSION}",
+        now.format("%Y%m%dT%H%M%SZ"),
+        std::process::id()
+    ));
+    fs::write(&path, report)?;
+    prune_reports(&dir, context.config.crash_reports.max_reports);
+    Ok(path)
+}
+
+/// The program and, for `kopi` itself, the subcommand, without any
+/// arguments that could carry secrets
+fn command_summary(args: &[String]) -> String {
+    let program = args
+        .first()
+        .and_then(|program| Path::new(program).file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "kopi".to_string());
+    let mut summary = program.clone();
+    let mut recorded = 1;
+    if program == "kopi" {
+        let mut rest = args.iter().skip(1);
+        while let Some(arg) = rest.next() {
+            if VALUE_OPTIONS.contains(&arg.as_str()) {
+                rest.next();
+            } else if !arg.starts_with('-') {
+                summary.push(' ');
+                summary.push_str(arg);
+                recorded += 1;
+                break;
+            }
+        }
+    }
+    if args.len() > recorded {
+        summary.push_str(" <arguments omitted>");
+    }
+    summary
+}
+
+/// The parts of a crash report that vary between crashes
+struct ReportFields<'a> {
+    written_at: DateTime<Utc>,
+    command: &'a str,
+    config_digest: &'a str,
+    thread: &'a str,
+    location: &'a str,
+    message: &'a str,
+    backtrace: &'a str,
+}
+
+fn render_report(fields: &ReportFields, kopi_home: &Path, home_dir: Option<&Path>) -> String {
+    let (arch, os, libc) = get_current_platform();
+    let report = format!(
+        "# kopi crash report\n\
+         \n\
+         Version: {version}\n\
+         Time: {time}\n\
+         Platform: {os} {arch} ({libc})\n\
+         Command: {command}\n\
+         Config digest: sha256:{digest}\n\
+         Thread: {thread}\n\
+         Location: {location}\n\
+         {MESSAGE_FIELD}{message}\n\
+         \n\
+         Backtrace:\n\
+         {backtrace}\n",
+        version = env!("CARGO_PKG_VERSION"),
+        time = fields.written_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        command = fields.command,
+        digest = fields.config_digest,
+        thread = fields.thread,
+        location = fields.location,
+        // Keep the message on one line so `kopi doctor` can read it back
+        message = fields.message.replace('\n', " "),
+        backtrace = fields.backtrace.trim_end(),
+    );
+    anonymize(&report, kopi_home, home_dir)
+}
+
+/// Replace the Kopi home and the user's home directory, which usually
+/// contain the user name, with `$KOPI_HOME` and `~`
+fn anonymize(text: &str, kopi_home: &Path, home_dir: Option<&Path>) -> String {
+    let mut text = text.to_string();
+    let replacements = [(Some(kopi_home), "$KOPI_HOME"), (home_dir, "~")];
+    for (path, replacement) in replacements {
+        if let Some(path) = path.map(|path| path.to_string_lossy())
+            && !path.is_empty()
+        {
+            text = text.replace(path.as_ref(), replacement);
+        }
+    }
+    text
+}
+
+/// Short digest of the effective configuration, so reports can tell whether
+/// two crashes ran with the same settings without revealing them
+fn config_digest(config: &KopiConfig) -> String {
+    let serialized = serde_json::to_vec(config).unwrap_or_default();
+    hex::encode(&Sha256::digest(&serialized)[..8])
+}
+
+/// Delete all but the newest `keep` reports
+fn prune_reports(dir: &Path, keep: usize) {
+    let mut reports = report_paths(dir);
+    if reports.len() <= keep {
+        return;
+    }
+    reports.sort();
+    let excess = reports.len() - keep;
+    for path in reports.into_iter().take(excess) {
+        if let Err(err) = fs::remove_file(&path) {
+            log::debug!(
+                "Failed to remove old crash report {}: {err}",
+                path.display()
+            );
+        }
+    }
+}
+
+fn report_paths(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == REPORT_EXTENSION)
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(REPORT_PREFIX))
+        })
+        .collect()
+}
+
+/// Crash reports in `KOPI_HOME/crash`, newest first
+pub fn list_reports(kopi_home: &Path) -> Vec<CrashReport> {
+    let mut reports: Vec<CrashReport> = report_paths(&home::crash_dir(kopi_home))
+        .into_iter()
+        .filter_map(|path| {
+            let written_at = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+            let message = fs::read_to_string(&path)
+                .ok()?
+                .lines()
+                .find_map(|line| line.strip_prefix(MESSAGE_FIELD).map(str::to_string))
+                .unwrap_or_default();
+            Some(CrashReport {
+                path,
+                written_at: written_at.into(),
+                message,
+            })
+        })
+        .collect();
+    reports.sort_by(|a, b| b.written_at.cmp(&a.written_at));
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fields<'a>(command: &'a str, message: &'a str) -> ReportFields<'a> {
+        ReportFields {
+            written_at: DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            command,
+            config_digest: "0123456789abcdef",
+            thread: "main",
+            location: "src/main.rs:1:1",
+            message,
+            backtrace: "   0: kopi::main\n",
+        }
+    }
+
+    #[test]
+    fn test_render_report_anonymizes_home_paths() {
+        let kopi_home = Path::new("/home/alice/.kopi");
+        let home_dir = Path::new("/home/alice");
+        let report = render_report(
+            &fields(
+                "kopi install --dest /home/alice/jdk temurin@21",
+                "failed to read /home/alice/.kopi/jdks\nsecond line",
+            ),
+            kopi_home,
+            Some(home_dir),
+        );
+
+        assert!(report.starts_with("# kopi crash report\n"));
+        assert!(report.contains("Command: kopi install --dest ~/jdk temurin@21\n"));
+        assert!(report.contains("Message: failed to read $KOPI_HOME/jdks second line\n"));
+        assert!(report.contains("Config digest: sha256:0123456789abcdef\n"));
+        assert!(report.contains("Time: 2025-06-01T12:00:00Z\n"));
+        assert!(report.ends_with("Backtrace:\n   0: kopi::main\n"));
+        assert!(!report.contains("alice"));
+    }
+
+    #[test]
+    fn test_command_summary_omits_arguments() {
+        let args = |line: &str| line.split(' ').map(String::from).collect::<Vec<_>>();
+        assert_eq!(command_summary(&args("/usr/bin/kopi list")), "kopi list");
+        assert_eq!(
+            command_summary(&args(
+                "kopi --lock-timeout 5 -v install --dest /secret temurin@21"
+            )),
+            "kopi install <arguments omitted>"
+        );
+        assert_eq!(
+            command_summary(&args(
+                "/home/alice/.kopi/shims/java -Dtoken=abc -jar app.jar"
+            )),
+            "java <arguments omitted>"
+        );
+        assert_eq!(command_summary(&[]), "kopi");
+    }
+
+    #[test]
+    fn test_list_and_prune_reports() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = home::ensure_crash_dir(temp_dir.path()).unwrap();
+        for (name, message) in [
+            ("crash-20250101T000000Z-1.txt", "first"),
+            ("crash-20250102T000000Z-2.txt", "second"),
+            ("crash-20250103T000000Z-3.txt", "third"),
+        ] {
+            let report = render_report(&fields("kopi", message), temp_dir.path(), None);
+            fs::write(dir.join(name), report).unwrap();
+        }
+        fs::write(dir.join("notes.txt"), "not a report").unwrap();
+
+        prune_reports(&dir, 2);
+        let mut messages: Vec<String> = list_reports(temp_dir.path())
+            .into_iter()
+            .map(|report| report.message)
+            .collect();
+        messages.sort();
+        assert_eq!(messages, ["second", "third"]);
+        assert!(dir.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_config_digest_tracks_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let digest = config_digest(&config);
+        assert_eq!(digest.len(), 16);
+        assert_eq!(digest, config_digest(&config));
+
+        config.default_distribution = "zulu".to_string();
+        assert_ne!(digest, config_digest(&config));
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client side of the daemon socket, used by `--use-daemon`.
+
+use crate::commands::list::InstalledJdkSummary;
+use crate::daemon::progress::ProgressReplay;
+use crate::daemon::protocol::{
+    InstallParams, METHOD_INSTALL, METHOD_LIST, METHOD_PING, METHOD_RESOLVE, METHOD_SHUTDOWN,
+    NOTIFICATION_PROGRESS, ProgressEvent, Resolution, ResolveParams, RpcMessage, RpcRequest,
+    read_line, write_message,
+};
+use crate::error::{KopiError, Result};
+use crate::indicator::ProgressIndicator;
+use log::debug;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+pub struct DaemonClient {
+    reader: Box<dyn BufRead + Send>,
+    writer: Box<dyn Write + Send>,
+    next_id: u64,
+}
+
+impl DaemonClient {
+    /// Connect to the daemon serving `kopi_home`, or `None` if none is running
+    #[cfg(unix)]
+    pub fn connect(kopi_home: &Path) -> Option<Self> {
+        use std::os::unix::net::UnixStream;
+
+        let socket_path = crate::paths::home::daemon_socket_path(kopi_home);
+        let stream = match UnixStream::connect(&socket_path) {
+            Ok(stream) => stream,
+            Err(e) => {
+                debug!("No kopi daemon at {}: {e}", socket_path.display());
+                return None;
+            }
+        };
+        let reader = stream.try_clone().ok()?;
+        Some(Self::from_streams(reader, stream))
+    }
+
+    #[cfg(not(unix))]
+    pub fn connect(_kopi_home: &Path) -> Option<Self> {
+        debug!("kopi daemon is not supported on this platform");
+        None
+    }
+
+    pub fn from_streams<R, W>(reader: R, writer: W) -> Self
+    where
+        R: Read + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        Self {
+            reader: Box::new(BufReader::new(reader)),
+            writer: Box::new(writer),
+            next_id: 1,
+        }
+    }
+
+    /// Send a request and wait for its response, passing any `progress`
+    /// notifications received meanwhile to `on_progress`
+    pub fn call(
+        &mut self,
+        method: &str,
+        params: Value,
+        on_progress: &mut dyn FnMut(ProgressEvent),
+    ) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_message(&mut self.writer, &RpcRequest::new(id, method, params))?;
+
+        loop {
+            let line = read_line(&mut self.reader)?.ok_or_else(|| {
+                KopiError::SystemError("kopi daemon closed the connection".to_string())
+            })?;
+            let message: RpcMessage = serde_json::from_str(&line)?;
+
+            if message.is_notification() {
+                if message.method.as_deref() == Some(NOTIFICATION_PROGRESS)
+                    && let Some(params) = message.params
+                    && let Ok(event) = serde_json::from_value(params)
+                {
+                    on_progress(event);
+                }
+                continue;
+            }
+
+            if message.id != Some(id) {
+                debug!("Ignoring daemon message for request {:?}", message.id);
+                continue;
+            }
+
+            if let Some(error) = message.error {
+                return Err(error.into_kopi_error());
+            }
+            return Ok(message.result.unwrap_or(Value::Null));
+        }
+    }
+
+    pub fn ping(&mut self) -> Result<Value> {
+        self.call(METHOD_PING, Value::Null, &mut |_| {})
+    }
+
+    pub fn resolve(&mut self, params: &ResolveParams) -> Result<Resolution> {
+        let result = self.call(METHOD_RESOLVE, serde_json::to_value(params)?, &mut |_| {})?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    pub fn list(&mut self) -> Result<Vec<InstalledJdkSummary>> {
+        let result = self.call(METHOD_LIST, Value::Null, &mut |_| {})?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Run an install in the daemon, rendering its progress on `progress`
+    pub fn install(
+        &mut self,
+        params: &InstallParams,
+        progress: Box<dyn ProgressIndicator>,
+    ) -> Result<()> {
+        let mut replay = ProgressReplay::new(progress);
+        self.call(
+            METHOD_INSTALL,
+            serde_json::to_value(params)?,
+            &mut |event| replay.apply(event),
+        )?;
+        Ok(())
+    }
+
+    pub fn shutdown(&mut self) -> Result<()> {
+        self.call(METHOD_SHUTDOWN, Value::Null, &mut |_| {})?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::config::KopiConfig;
+    use crate::daemon::server::DaemonServer;
+    use std::fs;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn wait_for_daemon(kopi_home: &Path) -> DaemonClient {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(client) = DaemonClient::connect(kopi_home) {
+                return client;
+            }
+            assert!(Instant::now() < deadline, "daemon did not start");
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn client_talks_to_running_daemon() {
+        let temp_dir = TempDir::new().unwrap();
+        let kopi_home = temp_dir.path().to_path_buf();
+        let config = KopiConfig::new(kopi_home.clone()).unwrap();
+        fs::create_dir_all(config.jdks_dir().unwrap()).unwrap();
+        fs::write(kopi_home.join("version"), "zulu@17").unwrap();
+
+        let server = DaemonServer::new(config);
+        let socket_path = server.socket_path().to_path_buf();
+        let handle = thread::spawn(move || server.run());
+
+        let mut client = wait_for_daemon(&kopi_home);
+        assert!(client.ping().unwrap()["pid"].is_u64());
+        assert!(client.list().unwrap().is_empty());
+
+        let resolution = client
+            .resolve(&ResolveParams {
+                cwd: temp_dir.path().display().to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(resolution.source, "global default");
+        assert_eq!(resolution.distribution.as_deref(), Some("zulu"));
+
+        // A second daemon must refuse to take over the live socket
+        let second = DaemonServer::new(KopiConfig::new(kopi_home.clone()).unwrap());
+        assert!(matches!(second.run(), Err(KopiError::AlreadyExists(_))));
+
+        client.shutdown().unwrap();
+        handle.join().unwrap().unwrap();
+        assert!(!socket_path.exists());
+        assert!(DaemonClient::connect(&kopi_home).is_none());
+    }
+
+    #[test]
+    fn connect_returns_none_without_daemon() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(DaemonClient::connect(temp_dir.path()).is_none());
+    }
+}
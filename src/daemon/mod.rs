@@ -0,0 +1,32 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Long-running JSON-RPC daemon for editor integrations.
+//!
+//! `kopi daemon` listens on a Unix domain socket in `KOPI_HOME` and answers
+//! `resolve`, `list`, and `install` requests without paying process startup
+//! costs on every call. The installed JDK inventory (including disk usage) is
+//! kept in memory and rebuilt whenever the `jdks` directory changes. Package
+//! metadata is not held in memory: `install` reads the relevant part of the
+//! metadata cache from disk through its index, as the CLI does. There is no
+//! Windows transport: named pipes are out of scope, and on Windows the
+//! server and client report the daemon as unsupported.
+
+pub mod client;
+pub mod progress;
+pub mod protocol;
+pub mod server;
+
+pub use client::DaemonClient;
+pub use server::DaemonServer;
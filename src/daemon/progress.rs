@@ -0,0 +1,218 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bridges `ProgressIndicator` calls across the daemon socket.
+
+use crate::daemon::protocol::ProgressEvent;
+use crate::indicator::{ProgressConfig, ProgressIndicator, ProgressRendererKind};
+use std::sync::Arc;
+
+pub type ProgressSink = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
+
+/// Server-side indicator that turns every call into a `progress` notification
+pub struct RpcProgress {
+    sink: ProgressSink,
+    child: bool,
+}
+
+impl RpcProgress {
+    pub fn new(sink: ProgressSink) -> Self {
+        Self { sink, child: false }
+    }
+
+    fn emit(&self, event: ProgressEvent) {
+        (self.sink)(event);
+    }
+}
+
+impl ProgressIndicator for RpcProgress {
+    fn start(&mut self, config: ProgressConfig) {
+        self.emit(ProgressEvent::Start {
+            style: config.style,
+            total: config.total,
+            child: self.child,
+        });
+    }
+
+    fn update(&mut self, current: u64, total: Option<u64>) {
+        self.emit(ProgressEvent::Update {
+            current,
+            total,
+            child: self.child,
+        });
+    }
+
+    fn set_message(&mut self, message: String) {
+        self.emit(ProgressEvent::Message {
+            message,
+            child: self.child,
+        });
+    }
+
+    fn complete(&mut self, message: Option<String>) {
+        self.emit(ProgressEvent::Complete {
+            message,
+            child: self.child,
+        });
+    }
+
+    fn success(&self, message: &str) -> std::io::Result<()> {
+        self.emit(ProgressEvent::Success {
+            message: message.to_string(),
+        });
+        Ok(())
+    }
+
+    fn error(&mut self, message: String) {
+        self.emit(ProgressEvent::Error {
+            message,
+            child: self.child,
+        });
+    }
+
+    fn create_child(&mut self) -> Box<dyn ProgressIndicator> {
+        Box::new(RpcProgress {
+            sink: self.sink.clone(),
+            child: true,
+        })
+    }
+
+    fn suspend(&self, f: &mut dyn FnMut()) {
+        f();
+    }
+
+    fn println(&self, message: &str) -> std::io::Result<()> {
+        self.emit(ProgressEvent::Println {
+            message: message.to_string(),
+        });
+        Ok(())
+    }
+
+    fn renderer_kind(&self) -> ProgressRendererKind {
+        ProgressRendererKind::NonTty
+    }
+}
+
+/// Client-side renderer that replays daemon progress on a local indicator
+pub struct ProgressReplay {
+    root: Box<dyn ProgressIndicator>,
+    child: Option<Box<dyn ProgressIndicator>>,
+}
+
+impl ProgressReplay {
+    pub fn new(root: Box<dyn ProgressIndicator>) -> Self {
+        Self { root, child: None }
+    }
+
+    pub fn apply(&mut self, event: ProgressEvent) {
+        match event {
+            ProgressEvent::Start {
+                style,
+                total,
+                child,
+            } => {
+                let mut config = ProgressConfig::new(style);
+                config.total = total;
+                self.target(child).start(config);
+            }
+            ProgressEvent::Update {
+                current,
+                total,
+                child,
+            } => self.target(child).update(current, total),
+            ProgressEvent::Message { message, child } => self.target(child).set_message(message),
+            ProgressEvent::Complete { message, child } => {
+                self.target(child).complete(message);
+                if child {
+                    self.child = None;
+                }
+            }
+            ProgressEvent::Error { message, child } => self.target(child).error(message),
+            ProgressEvent::Println { message } => {
+                let _ = self.root.println(&message);
+            }
+            ProgressEvent::Success { message } => {
+                let _ = self.root.success(&message);
+            }
+        }
+    }
+
+    fn target(&mut self, child: bool) -> &mut Box<dyn ProgressIndicator> {
+        if !child {
+            return &mut self.root;
+        }
+        let root = &mut self.root;
+        self.child.get_or_insert_with(|| root.create_child())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicator::ProgressStyle;
+    use std::sync::Mutex;
+
+    #[test]
+    fn rpc_progress_emits_events_with_child_flag() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let captured = events.clone();
+        let sink: ProgressSink = Arc::new(move |event| captured.lock().unwrap().push(event));
+
+        let mut progress = RpcProgress::new(sink);
+        progress.start(ProgressConfig::new(ProgressStyle::Count).with_total(6));
+        let mut child = progress.create_child();
+        child.update(10, Some(100));
+        progress.println("Installing").unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            events[0],
+            ProgressEvent::Start {
+                style: ProgressStyle::Count,
+                total: Some(6),
+                child: false
+            }
+        );
+        assert_eq!(
+            events[1],
+            ProgressEvent::Update {
+                current: 10,
+                total: Some(100),
+                child: true
+            }
+        );
+        assert_eq!(
+            events[2],
+            ProgressEvent::Println {
+                message: "Installing".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn replay_drops_child_after_completion() {
+        let mut replay = ProgressReplay::new(Box::new(crate::indicator::SilentProgress::new()));
+        replay.apply(ProgressEvent::Start {
+            style: ProgressStyle::Bytes,
+            total: Some(1024),
+            child: true,
+        });
+        assert!(replay.child.is_some());
+        replay.apply(ProgressEvent::Complete {
+            message: None,
+            child: true,
+        });
+        assert!(replay.child.is_none());
+    }
+}
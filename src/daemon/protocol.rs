@@ -0,0 +1,367 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Wire format for the daemon's JSON-RPC 2.0 API.
+//!
+//! Messages are newline-delimited JSON objects. Requests carry an `id` and get
+//! exactly one response; notifications (such as `progress`) have no `id`.
+
+use crate::error::{ErrorContext, KopiError, Result, get_error_code, get_exit_code};
+use crate::indicator::ProgressStyle;
+use crate::models::package::PackageType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+pub const METHOD_PING: &str = "ping";
+pub const METHOD_RESOLVE: &str = "resolve";
+pub const METHOD_LIST: &str = "list";
+pub const METHOD_INSTALL: &str = "install";
+pub const METHOD_SUBSCRIBE: &str = "progress.subscribe";
+pub const METHOD_SHUTDOWN: &str = "shutdown";
+pub const NOTIFICATION_PROGRESS: &str = "progress";
+
+/// Standard JSON-RPC error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// Application error; `data.exit_code` carries the CLI exit code
+pub const KOPI_ERROR: i64 = -32000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    pub id: u64,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl RpcRequest {
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Wrap a `KopiError`, keeping its exit code and hints so the CLI can
+    /// report it exactly as an in-process run would
+    pub fn from_kopi_error(error: &KopiError) -> Self {
+        let context = ErrorContext::new(error);
+        Self {
+            code: KOPI_ERROR,
+            message: error.to_string(),
+            data: Some(serde_json::json!({
+                "exit_code": get_exit_code(error),
+                "error_code": get_error_code(error),
+                "details": context.details,
+                "suggestion": context.suggestion,
+            })),
+        }
+    }
+
+    pub fn into_kopi_error(self) -> KopiError {
+        let field = |name: &str| {
+            self.data
+                .as_ref()
+                .and_then(|data| data.get(name))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+        let exit_code = self
+            .data
+            .as_ref()
+            .and_then(|data| data.get("exit_code"))
+            .and_then(Value::as_i64)
+            .map(|code| code as i32)
+            .unwrap_or(1);
+        KopiError::Daemon {
+            code: field("error_code").unwrap_or_else(|| "KOPI_E_DAEMON".to_string()),
+            details: field("details"),
+            suggestion: field("suggestion"),
+            message: self.message,
+            exit_code,
+        }
+    }
+}
+
+/// A response or notification sent by the daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcMessage {
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcMessage {
+    pub fn success(id: u64, result: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id),
+            method: None,
+            params: None,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: Option<u64>, error: RpcError) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            method: None,
+            params: None,
+            result: None,
+            error: Some(error),
+        }
+    }
+
+    pub fn notification(method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: None,
+            method: Some(method.to_string()),
+            params: Some(params),
+            result: None,
+            error: None,
+        }
+    }
+
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none() && self.method.is_some()
+    }
+}
+
+/// Parameters for the `resolve` method
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolveParams {
+    /// Directory to resolve from; project files are searched upwards from here
+    pub cwd: String,
+    /// Resolve for a specific tool, honouring `[tools]` pins
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// The caller's `KOPI_JAVA_VERSION`, since the daemon's own environment
+    /// does not reflect the client shell
+    #[serde(default)]
+    pub env_version: Option<String>,
+    /// The caller's `KOPI_USE`, validated and applied as a shim would
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kopi_use: Option<String>,
+}
+
+/// Result of the `resolve` method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resolution {
+    pub version: String,
+    pub distribution: Option<String>,
+    pub source: String,
+    pub source_path: Option<String>,
+    pub installed: bool,
+    pub installation_path: Option<String>,
+}
+
+/// Parameters for the `install` method
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallParams {
+    pub version: String,
+    #[serde(default)]
+    pub force: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    /// Companion archives (sources, javadoc) to install alongside the JDK
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub companions: Vec<PackageType>,
+    /// Bandwidth cap in bytes per second, overriding `download.max_rate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_rate: Option<u64>,
+    /// Keep the verified archive next to the installation
+    #[serde(default)]
+    pub keep_archive: bool,
+    /// SHA-256 digest the archive must match, from `--sha256`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// Payload of `progress` notifications, mirroring `ProgressIndicator` calls
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    Start {
+        style: ProgressStyle,
+        total: Option<u64>,
+        #[serde(default)]
+        child: bool,
+    },
+    Update {
+        current: u64,
+        total: Option<u64>,
+        #[serde(default)]
+        child: bool,
+    },
+    Message {
+        message: String,
+        #[serde(default)]
+        child: bool,
+    },
+    Complete {
+        message: Option<String>,
+        #[serde(default)]
+        child: bool,
+    },
+    Error {
+        message: String,
+        #[serde(default)]
+        child: bool,
+    },
+    Println {
+        message: String,
+    },
+    Success {
+        message: String,
+    },
+}
+
+/// Write one message followed by a newline
+pub fn write_message<W: Write, T: Serialize>(writer: &mut W, message: &T) -> Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read one message, returning `None` when the peer closed the connection
+pub fn read_line<R: BufRead>(reader: &mut R) -> Result<Option<String>> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
+            return Ok(Some(line));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn request_round_trips_through_framing() {
+        let request = RpcRequest::new(7, METHOD_RESOLVE, serde_json::json!({"cwd": "/tmp"}));
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &request).unwrap();
+        assert_eq!(buffer.last(), Some(&b'\n'));
+
+        let mut reader = Cursor::new(buffer);
+        let line = read_line(&mut reader).unwrap().unwrap();
+        let parsed: RpcRequest = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.id, 7);
+        assert_eq!(parsed.method, METHOD_RESOLVE);
+        assert!(read_line(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn error_preserves_exit_code() {
+        let error = RpcError::from_kopi_error(&KopiError::ValidationError("bad".to_string()));
+        match error.into_kopi_error() {
+            KopiError::Daemon {
+                message,
+                code,
+                exit_code,
+                ..
+            } => {
+                assert!(message.contains("bad"));
+                assert_eq!(code, "KOPI_E_VALIDATION");
+                assert_eq!(exit_code, 2);
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+
+    #[test]
+    fn error_carries_suggestion() {
+        let error = RpcError::from_kopi_error(&KopiError::UnsupportedShell("tcsh".to_string()));
+        let remote = error.into_kopi_error();
+        let rendered = crate::error::format_error_chain(&remote);
+        assert!(rendered.contains("Supported shells"), "{rendered}");
+        assert_eq!(get_exit_code(&remote), 7);
+    }
+
+    #[test]
+    fn progress_events_are_tagged() {
+        let event = ProgressEvent::Update {
+            current: 3,
+            total: Some(6),
+            child: false,
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["event"], "update");
+        assert_eq!(value["current"], 3);
+
+        let parsed: ProgressEvent =
+            serde_json::from_value(serde_json::json!({"event": "println", "message": "hi"}))
+                .unwrap();
+        assert_eq!(
+            parsed,
+            ProgressEvent::Println {
+                message: "hi".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn notifications_have_no_id() {
+        let message = RpcMessage::notification(NOTIFICATION_PROGRESS, Value::Null);
+        assert!(message.is_notification());
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(!json.contains("\"id\""));
+    }
+}
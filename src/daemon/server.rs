@@ -0,0 +1,495 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `kopi daemon` request handling and socket loop.
+
+use crate::commands::install::InstallCommand;
+use crate::commands::list::{InstalledJdkSummary, collect_installed};
+use crate::config::KopiConfig;
+use crate::daemon::progress::{ProgressSink, RpcProgress};
+use crate::daemon::protocol::{
+    INVALID_PARAMS, InstallParams, METHOD_INSTALL, METHOD_LIST, METHOD_NOT_FOUND, METHOD_PING,
+    METHOD_RESOLVE, METHOD_SHUTDOWN, METHOD_SUBSCRIBE, NOTIFICATION_PROGRESS, PARSE_ERROR,
+    ProgressEvent, Resolution, ResolveParams, RpcError, RpcMessage, RpcRequest, read_line,
+    write_message,
+};
+use crate::error::{KopiError, Result};
+use crate::paths::home;
+use crate::security::parse_sha256;
+use crate::storage::JdkRepository;
+use crate::version::resolver::{VersionResolver, VersionSource};
+use log::{debug, info, warn};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Write half of a client connection, shared with progress subscribers
+pub type SharedWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+
+/// Installed JDK listing held in memory until the `jdks` directory changes
+struct Inventory {
+    fingerprint: Option<SystemTime>,
+    jdks: Vec<InstalledJdkSummary>,
+}
+
+/// State shared by every connection of a running daemon
+pub struct DaemonState {
+    config: KopiConfig,
+    inventory: Mutex<Option<Inventory>>,
+    subscribers: Arc<Mutex<Vec<SharedWriter>>>,
+    shutdown: AtomicBool,
+}
+
+impl DaemonState {
+    pub fn new(config: KopiConfig) -> Self {
+        Self {
+            config,
+            inventory: Mutex::new(None),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            shutdown: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.load(Ordering::SeqCst)
+    }
+
+    /// Dispatch a single request. Progress notifications for `install` are
+    /// written to `writer` as well as to every `progress.subscribe` client.
+    pub fn handle(&self, request: &RpcRequest, writer: &SharedWriter) -> RpcMessage {
+        debug!("Daemon request {} {}", request.id, request.method);
+
+        let result = match request.method.as_str() {
+            METHOD_PING => Ok(serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "pid": std::process::id(),
+            })),
+            METHOD_RESOLVE => parse_params(&request.params).and_then(|params| self.resolve(params)),
+            METHOD_LIST => self.list().map_err(|e| RpcError::from_kopi_error(&e)),
+            METHOD_INSTALL => {
+                parse_params(&request.params).and_then(|params| self.install(params, writer))
+            }
+            METHOD_SUBSCRIBE => {
+                self.subscribers.lock().unwrap().push(writer.clone());
+                Ok(serde_json::json!({ "subscribed": true }))
+            }
+            METHOD_SHUTDOWN => {
+                info!("Daemon shutdown requested");
+                self.shutdown.store(true, Ordering::SeqCst);
+                Ok(serde_json::json!({ "stopping": true }))
+            }
+            other => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method '{other}'"),
+            )),
+        };
+
+        match result {
+            Ok(value) => RpcMessage::success(request.id, value),
+            Err(error) => RpcMessage::failure(Some(request.id), error),
+        }
+    }
+
+    fn resolve(&self, params: ResolveParams) -> std::result::Result<Value, RpcError> {
+        let resolver = VersionResolver::with_dir(PathBuf::from(&params.cwd), &self.config)
+            .with_environment_version(params.env_version)
+            .with_override_version(params.kopi_use);
+        let resolved = match &params.tool {
+            Some(tool) => resolver.resolve_tool_version(tool),
+            None => resolver.resolve_version(),
+        };
+        let (version_request, source) = resolved.map_err(|e| RpcError::from_kopi_error(&e))?;
+
+        let repository = JdkRepository::new(&self.config);
+        let installation = repository
+            .find_matching_jdks(&version_request)
+            .ok()
+            .and_then(|jdks| jdks.last().map(|jdk| jdk.path.display().to_string()));
+
+        let (source_name, source_path) = match source {
+            VersionSource::Override(value) => ("KOPI_USE".to_string(), Some(value)),
+            VersionSource::Environment(value) => ("KOPI_JAVA_VERSION".to_string(), Some(value)),
+            VersionSource::ProjectFile(path) | VersionSource::ToolOverride(path) => (
+                path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "version file".to_string()),
+                Some(path.display().to_string()),
+            ),
+            VersionSource::GlobalDefault(path) => (
+                "global default".to_string(),
+                Some(path.display().to_string()),
+            ),
+        };
+
+        let resolution = Resolution {
+            version: version_request.version_pattern,
+            distribution: version_request.distribution,
+            source: source_name,
+            source_path,
+            installed: installation.is_some(),
+            installation_path: installation,
+        };
+        serde_json::to_value(resolution).map_err(|e| RpcError::from_kopi_error(&e.into()))
+    }
+
+    fn list(&self) -> Result<Value> {
+        let jdks_dir = home::jdks_dir(self.config.kopi_home());
+        let fingerprint = modified_time(&jdks_dir);
+
+        let mut inventory = self.inventory.lock().unwrap();
+        let stale = inventory
+            .as_ref()
+            .is_none_or(|cached| cached.fingerprint != fingerprint || fingerprint.is_none());
+        if stale {
+            debug!("Refreshing in-memory JDK inventory");
+            let repository = JdkRepository::new(&self.config);
+            *inventory = Some(Inventory {
+                fingerprint,
+                jdks: collect_installed(&repository)?,
+            });
+        }
+
+        let jdks = &inventory.as_ref().expect("inventory populated").jdks;
+        Ok(serde_json::to_value(jdks)?)
+    }
+
+    fn install(
+        &self,
+        params: InstallParams,
+        writer: &SharedWriter,
+    ) -> std::result::Result<Value, RpcError> {
+        let caller = writer.clone();
+        let subscribers = self.subscribers.clone();
+        let sink: ProgressSink = Arc::new(move |event: ProgressEvent| {
+            let Ok(params) = serde_json::to_value(&event) else {
+                return;
+            };
+            let message = RpcMessage::notification(NOTIFICATION_PROGRESS, params);
+            let _ = write_message(&mut *caller.lock().unwrap(), &message);
+            // Subscribers that have disconnected are dropped on the first failed write
+            subscribers
+                .lock()
+                .unwrap()
+                .retain(|target| write_message(&mut *target.lock().unwrap(), &message).is_ok());
+        });
+
+        let sha256 = params
+            .sha256
+            .as_deref()
+            .map(parse_sha256)
+            .transpose()
+            .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {e}")))?;
+        let command = InstallCommand::new(&self.config, false)
+            .map_err(|e| RpcError::from_kopi_error(&e))?
+            .with_progress_source(move || Box::new(RpcProgress::new(sink.clone())))
+            .with_companions(params.companions.clone())
+            .with_rate_limit(params.limit_rate)
+            .with_keep_archive(params.keep_archive)
+            .with_pinned_sha256(sha256);
+        command
+            .execute(
+                &params.version,
+                params.force,
+                params.dry_run,
+                params.timeout,
+            )
+            .map_err(|e| RpcError::from_kopi_error(&e))?;
+
+        Ok(serde_json::json!({ "version": params.version, "dry_run": params.dry_run }))
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: &Value) -> std::result::Result<T, RpcError> {
+    serde_json::from_value(params.clone())
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {e}")))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Serve requests from one connection until it closes or the daemon stops
+pub fn serve_connection<R: BufRead>(state: &DaemonState, mut reader: R, writer: SharedWriter) {
+    loop {
+        let line = match read_line(&mut reader) {
+            Ok(Some(line)) => line,
+            Ok(None) => return,
+            Err(e) => {
+                debug!("Daemon connection read failed: {e}");
+                return;
+            }
+        };
+
+        let response = match serde_json::from_str::<RpcRequest>(&line) {
+            Ok(request) => state.handle(&request, &writer),
+            Err(e) => RpcMessage::failure(
+                None,
+                RpcError::new(PARSE_ERROR, format!("Invalid request: {e}")),
+            ),
+        };
+
+        {
+            let mut stream = writer.lock().unwrap();
+            if let Err(e) = write_message(&mut *stream, &response) {
+                debug!("Daemon connection write failed: {e}");
+                return;
+            }
+        }
+
+        if state.is_shutting_down() {
+            return;
+        }
+    }
+}
+
+/// Foreground daemon bound to `~/.kopi/daemon.sock`
+pub struct DaemonServer {
+    state: Arc<DaemonState>,
+    socket_path: PathBuf,
+}
+
+impl DaemonServer {
+    pub fn new(config: KopiConfig) -> Self {
+        let socket_path = home::daemon_socket_path(config.kopi_home());
+        Self {
+            state: Arc::new(DaemonState::new(config)),
+            socket_path,
+        }
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    #[cfg(unix)]
+    pub fn run(self) -> Result<()> {
+        use std::io::BufReader;
+        use std::os::unix::fs::PermissionsExt;
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        if self.socket_path.exists() {
+            if UnixStream::connect(&self.socket_path).is_ok() {
+                return Err(KopiError::AlreadyExists(format!(
+                    "A kopi daemon is already listening on {}",
+                    self.socket_path.display()
+                )));
+            }
+            debug!("Removing stale socket {}", self.socket_path.display());
+            fs::remove_file(&self.socket_path)?;
+        }
+
+        home::ensure_kopi_home(self.state.config.kopi_home())?;
+        let listener = UnixListener::bind(&self.socket_path)?;
+        let _guard = SocketGuard(self.socket_path.clone());
+        fs::set_permissions(&self.socket_path, fs::Permissions::from_mode(0o600))?;
+        info!("kopi daemon listening on {}", self.socket_path.display());
+
+        for stream in listener.incoming() {
+            if self.state.is_shutting_down() {
+                break;
+            }
+
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Failed to accept daemon connection: {e}");
+                    continue;
+                }
+            };
+
+            let state = self.state.clone();
+            let socket_path = self.socket_path.clone();
+            std::thread::spawn(move || {
+                let reader = match stream.try_clone() {
+                    Ok(clone) => BufReader::new(clone),
+                    Err(e) => {
+                        warn!("Failed to clone daemon connection: {e}");
+                        return;
+                    }
+                };
+                let writer: SharedWriter = Arc::new(Mutex::new(Box::new(stream)));
+                serve_connection(&state, reader, writer);
+
+                if state.is_shutting_down() {
+                    // Wake the accept loop so it can observe the shutdown flag
+                    let _ = UnixStream::connect(&socket_path);
+                }
+            });
+        }
+
+        info!("kopi daemon stopped");
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn run(self) -> Result<()> {
+        Err(KopiError::NotImplemented(
+            "kopi daemon requires Unix domain sockets; named pipe support is not available on \
+             this platform yet"
+                .to_string(),
+        ))
+    }
+}
+
+/// Removes the socket file when the daemon exits
+#[cfg(unix)]
+struct SocketGuard(PathBuf);
+
+#[cfg(unix)]
+impl Drop for SocketGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::protocol::{METHOD_LIST, RpcRequest};
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    fn state() -> (TempDir, DaemonState) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        fs::create_dir_all(config.jdks_dir().unwrap()).unwrap();
+        (temp_dir, DaemonState::new(config))
+    }
+
+    fn sink() -> SharedWriter {
+        Arc::new(Mutex::new(Box::new(Vec::<u8>::new())))
+    }
+
+    #[test]
+    fn unknown_method_is_rejected() {
+        let (_temp, state) = state();
+        let response = state.handle(&RpcRequest::new(1, "bogus", Value::Null), &sink());
+        assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn resolve_uses_client_environment() {
+        let (temp, state) = state();
+        let params = serde_json::json!({
+            "cwd": temp.path(),
+            "env_version": "temurin@21",
+        });
+        let response = state.handle(&RpcRequest::new(2, METHOD_RESOLVE, params), &sink());
+        let resolution: Resolution = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert_eq!(resolution.source, "KOPI_JAVA_VERSION");
+        assert_eq!(resolution.version, "21");
+        assert!(!resolution.installed);
+    }
+
+    #[test]
+    fn resolve_honours_forwarded_kopi_use() {
+        let (temp, state) = state();
+        let params = serde_json::json!({
+            "cwd": temp.path(),
+            "env_version": "temurin@21",
+            "kopi_use": "corretto@17",
+        });
+        let response = state.handle(&RpcRequest::new(2, METHOD_RESOLVE, params), &sink());
+        let resolution: Resolution = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert_eq!(resolution.source, "KOPI_USE");
+        assert_eq!(resolution.distribution.as_deref(), Some("corretto"));
+
+        let params = serde_json::json!({ "cwd": temp.path(), "kopi_use": "zulu@17;rm" });
+        let response = state.handle(&RpcRequest::new(3, METHOD_RESOLVE, params), &sink());
+        assert!(response.error.is_some());
+    }
+
+    #[test]
+    fn resolve_reports_missing_version_as_error() {
+        let (temp, state) = state();
+        let project = temp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        let params = serde_json::json!({ "cwd": project, "env_version": null });
+        let response = state.handle(&RpcRequest::new(3, METHOD_RESOLVE, params), &sink());
+        let error = response.error.unwrap().into_kopi_error();
+        assert_eq!(crate::error::get_exit_code(&error), 3);
+    }
+
+    #[test]
+    fn list_invalidates_when_jdks_directory_changes() {
+        let (_temp, state) = state();
+        let jdks_dir = state.config.jdks_dir().unwrap();
+
+        let response = state.handle(&RpcRequest::new(4, METHOD_LIST, Value::Null), &sink());
+        assert_eq!(response.result.unwrap(), serde_json::json!([]));
+
+        // Ensure the directory mtime moves even on coarse-grained filesystems
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let jdk = jdks_dir.join("temurin-21.0.1");
+        fs::create_dir_all(&jdk).unwrap();
+        fs::write(jdk.join("release"), "JAVA_VERSION=\"21.0.1\"").unwrap();
+        let modified = SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::open(&jdks_dir)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        let response = state.handle(&RpcRequest::new(5, METHOD_LIST, Value::Null), &sink());
+        let jdks: Vec<InstalledJdkSummary> =
+            serde_json::from_value(response.result.unwrap()).unwrap();
+        assert_eq!(jdks.len(), 1);
+        assert_eq!(jdks[0].distribution, "temurin");
+    }
+
+    #[test]
+    fn connection_answers_until_shutdown() {
+        let (_temp, state) = state();
+        let mut input = Vec::new();
+        write_message(&mut input, &RpcRequest::new(1, METHOD_PING, Value::Null)).unwrap();
+        input.extend_from_slice(b"not json\n");
+        write_message(
+            &mut input,
+            &RpcRequest::new(2, METHOD_SHUTDOWN, Value::Null),
+        )
+        .unwrap();
+        write_message(&mut input, &RpcRequest::new(3, METHOD_PING, Value::Null)).unwrap();
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let writer: SharedWriter = Arc::new(Mutex::new(Box::new(SharedBuffer(output.clone()))));
+        serve_connection(&state, Cursor::new(input), writer);
+
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let responses: Vec<RpcMessage> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0].id, Some(1));
+        assert_eq!(responses[1].error.as_ref().unwrap().code, PARSE_ERROR);
+        assert_eq!(responses[2].id, Some(2));
+        assert!(state.is_shutting_down());
+    }
+
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -13,9 +13,11 @@
 // limitations under the License.
 
 use crate::config::KopiConfig;
+use crate::crash;
 use crate::doctor::{CheckCategory, CheckResult, CheckStatus, DiagnosticCheck};
 use crate::platform::shell::{detect_shell, is_in_path};
 use crate::platform::{executable_extension, kopi_binary_name, path_separator};
+use chrono::Utc;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
@@ -293,6 +295,83 @@ impl DiagnosticCheck for ConfigFileCheck<'_> {
     }
 }
 
+/// Crash reports younger than this are reported as warnings
+const RECENT_CRASH_DAYS: i64 = 30;
+
+/// Check for crash reports left by recent panics
+pub struct CrashReportCheck<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> CrashReportCheck<'a> {
+    pub fn new(config: &'a KopiConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl DiagnosticCheck for CrashReportCheck<'_> {
+    fn name(&self) -> &str {
+        "Crash Reports"
+    }
+
+    fn run(&self, start: Instant, category: CheckCategory) -> CheckResult {
+        let cutoff = Utc::now() - chrono::Duration::days(RECENT_CRASH_DAYS);
+        let reports = crash::list_reports(self.config.kopi_home());
+        let recent: Vec<_> = reports
+            .iter()
+            .filter(|report| report.written_at >= cutoff)
+            .collect();
+
+        if recent.is_empty() {
+            let message = if !self.config.crash_reports.enabled {
+                "Crash reports are disabled (enable with crash_reports.enabled)".to_string()
+            } else if reports.is_empty() {
+                "No crash reports".to_string()
+            } else {
+                format!(
+                    "No crash reports in the last {RECENT_CRASH_DAYS} days ({} older)",
+                    reports.len()
+                )
+            };
+            return CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Pass,
+                message,
+                start.elapsed(),
+            );
+        }
+
+        let details = recent
+            .iter()
+            .map(|report| {
+                format!(
+                    "{}  {}\n  {}",
+                    report.written_at.format("%Y-%m-%d %H:%M UTC"),
+                    report.path.display(),
+                    report.message
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        CheckResult::new(
+            self.name(),
+            category,
+            CheckStatus::Warning,
+            format!(
+                "kopi crashed {} time(s) in the last {RECENT_CRASH_DAYS} days",
+                recent.len()
+            ),
+            start.elapsed(),
+        )
+        .with_details(details)
+        .with_suggestion(
+            "Reports stay on this machine. If the crash persists, attach the newest report \
+             to an issue at https://github.com/kopi-vm/kopi/issues",
+        )
+    }
+}
+
 /// Check if shims directory is in PATH
 pub struct ShimsInPathCheck<'a> {
     config: &'a KopiConfig,
@@ -423,6 +502,74 @@ impl DiagnosticCheck for ShimsInPathCheck<'_> {
     }
 }
 
+/// Longest path most Windows APIs accept unless long path support is enabled
+#[cfg(windows)]
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// Check whether Windows accepts paths longer than MAX_PATH
+pub struct LongPathSupportCheck;
+
+impl DiagnosticCheck for LongPathSupportCheck {
+    fn name(&self) -> &str {
+        "Windows Long Path Support"
+    }
+
+    #[cfg(not(windows))]
+    fn run(&self, start: Instant, category: CheckCategory) -> CheckResult {
+        CheckResult::new(
+            self.name(),
+            category,
+            CheckStatus::Skip,
+            "Path length limits only apply on Windows",
+            start.elapsed(),
+        )
+    }
+
+    #[cfg(windows)]
+    fn run(&self, start: Instant, category: CheckCategory) -> CheckResult {
+        use crate::platform::file_ops::windows_long_paths_enabled;
+
+        match windows_long_paths_enabled() {
+            Ok(true) => CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Pass,
+                "Long paths are enabled (LongPathsEnabled = 1)",
+                start.elapsed(),
+            ),
+            Ok(false) => CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Warning,
+                format!(
+                    "Long paths are disabled; JDK tools may fail on files deeper than \
+                     {WINDOWS_MAX_PATH} characters"
+                ),
+                start.elapsed(),
+            )
+            .with_details(
+                "kopi itself uses extended-length paths, but java, javac and other programs \
+                 launched from the JDK rely on the system setting",
+            )
+            .with_suggestion(
+                "Run in an elevated PowerShell: New-ItemProperty -Path \
+                 'HKLM:\\SYSTEM\\CurrentControlSet\\Control\\FileSystem' -Name LongPathsEnabled \
+                 -Value 1 -PropertyType DWORD -Force (or enable the 'Enable Win32 long paths' \
+                 group policy), then sign out and back in. Alternatively, set KOPI_HOME to a \
+                 short path such as C:\\kopi",
+            ),
+            Err(e) => CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Warning,
+                "Cannot read the LongPathsEnabled registry value",
+                start.elapsed(),
+            )
+            .with_details(e.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -453,6 +600,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_crash_report_check() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let result =
+            CrashReportCheck::new(&config).run(Instant::now(), CheckCategory::Installation);
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.message.contains("disabled"));
+
+        config.crash_reports.enabled = true;
+        let check = CrashReportCheck::new(&config);
+        let result = check.run(Instant::now(), CheckCategory::Installation);
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(result.message, "No crash reports");
+
+        let crash_dir = crate::paths::home::ensure_crash_dir(temp_dir.path()).unwrap();
+        let report = crash_dir.join("crash-20250101T000000Z-42.txt");
+        fs::write(
+            &report,
+            "# kopi crash report\n\nMessage: index out of bounds\n",
+        )
+        .unwrap();
+
+        let result = check.run(Instant::now(), CheckCategory::Installation);
+        assert_eq!(result.status, CheckStatus::Warning);
+        let details = result.details.unwrap();
+        assert!(details.contains(&report.display().to_string()));
+        assert!(details.contains("index out of bounds"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_long_path_support_check_skips_off_windows() {
+        let result = LongPathSupportCheck.run(Instant::now(), CheckCategory::Installation);
+
+        assert_eq!(result.status, CheckStatus::Skip);
+    }
+
     #[test]
     fn test_version_check_skip_when_not_found() {
         // Save original PATH
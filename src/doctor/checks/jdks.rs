@@ -18,8 +18,14 @@ use crate::paths::install;
 use crate::platform::with_executable_extension;
 use crate::storage::disk_probe;
 use crate::storage::formatting::format_size;
-use crate::storage::{InstalledJdk, JdkLister};
-use std::time::Instant;
+use crate::storage::manifest::{ManifestVerification, find_broken_symlinks};
+use crate::storage::{InstalledJdk, JdkLister, JdkRepository};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Check if any JDKs are installed
 pub struct JdkInstallationCheck<'a> {
@@ -109,6 +115,11 @@ impl<'a> JdkIntegrityCheck<'a> {
     fn check_jdk_structure(jdk: &InstalledJdk) -> Result<(bool, Vec<String>), std::io::Error> {
         let mut issues = Vec::new();
         let bin_dir = install::bin_directory(&jdk.path);
+        // Files removed on purpose via `install.strip` are not corruption
+        let stripped = |exe_name: &str| {
+            jdk.installation_metadata()
+                .is_some_and(|metadata| metadata.was_stripped(&Path::new("bin").join(exe_name)))
+        };
 
         // Check if bin directory exists
         if !bin_dir.exists() {
@@ -126,6 +137,9 @@ impl<'a> JdkIntegrityCheck<'a> {
             let exe_path = bin_dir.join(&exe_name);
 
             if !exe_path.exists() {
+                if stripped(&exe_name) {
+                    continue;
+                }
                 issues.push(format!("Missing required executable: {exe_name}"));
                 continue;
             }
@@ -148,7 +162,7 @@ impl<'a> JdkIntegrityCheck<'a> {
         for exe in optional_executables {
             let exe_name = with_executable_extension(exe);
             let exe_path = bin_dir.join(&exe_name);
-            if !exe_path.exists() {
+            if !exe_path.exists() && !stripped(&exe_name) {
                 missing_optional.push(exe);
             }
         }
@@ -321,8 +335,16 @@ impl<'a> DiagnosticCheck for JdkDiskSpaceCheck<'a> {
         let mut total_size = 0u64;
         let mut jdk_sizes = Vec::new();
 
-        for jdk in &jdks {
-            match JdkLister::get_jdk_size(&jdk.path) {
+        // Sizes are computed in parallel and reuse the cache `kopi list` keeps
+        let repository = JdkRepository::new(self.config);
+        let paths: Vec<&Path> = jdks.iter().map(|jdk| jdk.path.as_path()).collect();
+        let sizes: Vec<_> = paths
+            .par_iter()
+            .map(|path| repository.get_jdk_size(path))
+            .collect();
+
+        for (jdk, size) in jdks.iter().zip(sizes) {
+            match size {
                 Ok(size) => {
                     total_size += size;
                     jdk_sizes.push((jdk, size));
@@ -530,6 +552,283 @@ impl<'a> DiagnosticCheck for JdkVersionConsistencyCheck<'a> {
     }
 }
 
+/// Hashing every installed file can take minutes on a large JDK collection
+const DEEP_SCAN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Size of the fixed header at the start of a `lib/modules` jimage file
+const JIMAGE_HEADER_SIZE: usize = 28;
+const JIMAGE_MAGIC: u32 = 0xCAFE_DADA;
+
+/// Per-JDK result of a deep integrity scan
+#[derive(Debug, Serialize)]
+struct DeepScanReport {
+    distribution: String,
+    version: String,
+    path: PathBuf,
+    /// `intact`, `damaged`, or `unverified` when no manifest was recorded
+    status: &'static str,
+    manifest: bool,
+    #[serde(flatten)]
+    verification: ManifestVerification,
+    broken_symlinks: Vec<String>,
+    executable_issues: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modules_image: Option<String>,
+}
+
+impl DeepScanReport {
+    fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let groups = [
+            ("Missing file", &self.verification.missing),
+            ("Truncated file", &self.verification.truncated),
+            ("Modified file", &self.verification.modified),
+            ("Changed symlink", &self.verification.changed_symlinks),
+            ("Broken symlink", &self.broken_symlinks),
+        ];
+        for (label, paths) in groups {
+            issues.extend(paths.iter().map(|path| format!("{label}: {path}")));
+        }
+        issues.extend(self.executable_issues.iter().cloned());
+        issues.extend(self.modules_image.iter().cloned());
+        issues
+    }
+}
+
+/// Deep JDK integrity scan against the file manifests recorded at install
+/// time. Only part of the JDKs category when `kopi doctor --deep` is used.
+pub struct JdkDeepIntegrityCheck<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> JdkDeepIntegrityCheck<'a> {
+    pub fn new(config: &'a KopiConfig) -> Self {
+        Self { config }
+    }
+
+    fn scan(&self, repository: &JdkRepository, jdk: &InstalledJdk) -> DeepScanReport {
+        let mut executable_issues = match JdkIntegrityCheck::check_jdk_structure(jdk) {
+            Ok((_, issues)) => issues,
+            Err(e) => vec![format!("Failed to check structure: {e}")],
+        };
+
+        let manifest = match repository.load_install_manifest(jdk) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                executable_issues.push(format!("Cannot read install manifest: {e}"));
+                None
+            }
+        };
+        let verification = manifest
+            .as_ref()
+            .map(|manifest| manifest.verify(&jdk.path))
+            .unwrap_or_default();
+
+        let mut report = DeepScanReport {
+            distribution: jdk.distribution.clone(),
+            version: jdk.version.to_string(),
+            path: jdk.path.clone(),
+            status: "intact",
+            manifest: manifest.is_some(),
+            verification,
+            broken_symlinks: find_broken_symlinks(&jdk.path),
+            executable_issues,
+            modules_image: Self::check_modules_image(jdk),
+        };
+        report.status = if !report.issues().is_empty() {
+            "damaged"
+        } else if !report.manifest {
+            "unverified"
+        } else {
+            "intact"
+        };
+        report
+    }
+
+    /// `lib/modules` holds the whole class library on JDK 9+, so a short
+    /// copy breaks every launch. The manifest catches this too, but the
+    /// header check also covers JDKs installed before manifests existed.
+    fn check_modules_image(jdk: &InstalledJdk) -> Option<String> {
+        let modules = jdk.resolve_java_home().join("lib").join("modules");
+        if !modules.exists() {
+            return (jdk.version.major() >= 9).then(|| "lib/modules is missing".to_string());
+        }
+        inspect_modules_image(&modules).err()
+    }
+}
+
+/// Validate the jimage header of `path` and make sure the file is at least
+/// as long as the index the header describes.
+fn inspect_modules_image(path: &Path) -> Result<(), String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Cannot open lib/modules: {e}"))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Cannot read lib/modules metadata: {e}"))?
+        .len();
+
+    let mut header = [0u8; JIMAGE_HEADER_SIZE];
+    if file.read_exact(&mut header).is_err() {
+        return Err(format!("lib/modules is truncated ({len} bytes)"));
+    }
+
+    // jimage files are written in the byte order of the build platform
+    let magic: [u8; 4] = header[0..4].try_into().unwrap();
+    let read_u32: fn([u8; 4]) -> u32 = if u32::from_le_bytes(magic) == JIMAGE_MAGIC {
+        u32::from_le_bytes
+    } else if u32::from_be_bytes(magic) == JIMAGE_MAGIC {
+        u32::from_be_bytes
+    } else {
+        return Err("lib/modules has an invalid header".to_string());
+    };
+    let field = |index: usize| {
+        u64::from(read_u32(
+            header[index * 4..index * 4 + 4].try_into().unwrap(),
+        ))
+    };
+
+    // Header: magic, version, flags, resource count, table length,
+    // locations size, strings size. The index is followed by the resources.
+    let table_length = field(4);
+    let index_size = JIMAGE_HEADER_SIZE as u64 + table_length * 8 + field(5) + field(6);
+    if len < index_size {
+        return Err(format!(
+            "lib/modules is truncated ({len} bytes, index alone needs {index_size})"
+        ));
+    }
+    Ok(())
+}
+
+impl<'a> DiagnosticCheck for JdkDeepIntegrityCheck<'a> {
+    fn name(&self) -> &str {
+        "JDK Deep Integrity Scan"
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(DEEP_SCAN_TIMEOUT)
+    }
+
+    fn run(&self, start: Instant, category: CheckCategory) -> CheckResult {
+        let jdks_dir = match self.config.jdks_dir() {
+            Ok(dir) => dir,
+            Err(_) => {
+                return CheckResult::new(
+                    self.name(),
+                    category,
+                    CheckStatus::Skip,
+                    "Cannot scan JDKs - JDKs directory not accessible",
+                    start.elapsed(),
+                );
+            }
+        };
+
+        let jdks = match JdkLister::list_installed_jdks(&jdks_dir) {
+            Ok(jdks) => jdks,
+            Err(_) => {
+                return CheckResult::new(
+                    self.name(),
+                    category,
+                    CheckStatus::Skip,
+                    "Cannot scan JDKs - failed to list JDKs",
+                    start.elapsed(),
+                );
+            }
+        };
+
+        if jdks.is_empty() {
+            return CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Skip,
+                "No JDKs installed to scan",
+                start.elapsed(),
+            );
+        }
+
+        let repository = JdkRepository::new(self.config);
+        let reports: Vec<DeepScanReport> =
+            jdks.iter().map(|jdk| self.scan(&repository, jdk)).collect();
+
+        let mut details = Vec::new();
+        let mut damaged = 0;
+        let mut unverified = 0;
+        for report in &reports {
+            let label = format!("{}-{}", report.distribution, report.version);
+            match report.status {
+                "damaged" => {
+                    damaged += 1;
+                    let issues = report.issues();
+                    details.push(format!(
+                        "{label}:\n{}",
+                        issues
+                            .iter()
+                            .map(|i| format!("    - {i}"))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    ));
+                }
+                "unverified" => {
+                    unverified += 1;
+                    details.push(format!(
+                        "{label}: no manifest recorded (installed before manifests were kept)"
+                    ));
+                }
+                _ => details.push(format!(
+                    "{label}: {} files verified",
+                    report.verification.files_checked
+                )),
+            }
+        }
+
+        let data = serde_json::to_value(&reports).ok();
+        let result = if damaged > 0 {
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Fail,
+                format!(
+                    "{damaged} of {} JDK installations are damaged",
+                    reports.len()
+                ),
+                start.elapsed(),
+            )
+            .with_suggestion(
+                "Reinstall damaged JDKs with: kopi install --force <distribution>@<version>",
+            )
+        } else if unverified > 0 {
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Warning,
+                format!(
+                    "{unverified} of {} JDK installations have no install manifest",
+                    reports.len()
+                ),
+                start.elapsed(),
+            )
+            .with_suggestion(
+                "Reinstall them with: kopi install --force <distribution>@<version> to record a manifest",
+            )
+        } else {
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Pass,
+                format!(
+                    "All {} JDK installations match their manifests",
+                    reports.len()
+                ),
+                start.elapsed(),
+            )
+        };
+
+        let result = result.with_details(details.join("\n"));
+        match data {
+            Some(data) => result.with_data(data),
+            None => result,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -625,6 +924,137 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_jdk_integrity_check_ignores_stripped_executables() {
+        let setup = TestSetup::new();
+        setup.create_mock_jdk("temurin-21.0.1");
+        let jdks_dir = setup.config.jdks_dir().unwrap();
+        let jdk_path = jdks_dir.join("temurin-21.0.1");
+        fs::remove_file(install::bin_directory(&jdk_path).join(with_executable_extension("javac")))
+            .unwrap();
+
+        let metadata = serde_json::json!({
+            "id": "test-id",
+            "archive_type": "tar.gz",
+            "distribution": "temurin",
+            "major_version": 21,
+            "java_version": "21.0.1",
+            "distribution_version": "21.0.1+35.1",
+            "jdk_version": 21,
+            "directly_downloadable": true,
+            "filename": "test.tar.gz",
+            "links": {
+                "pkg_download_redirect": "https://example.com",
+                "pkg_info_uri": "https://example.com/info"
+            },
+            "free_use_in_production": true,
+            "tck_tested": "yes",
+            "size": 190000000,
+            "operating_system": "linux",
+            "architecture": "x64",
+            "lib_c_type": null,
+            "package_type": "jdk",
+            "javafx_bundled": false,
+            "term_of_support": null,
+            "release_status": null,
+            "latest_build_available": null,
+            "installation_metadata": {
+                "java_home_suffix": "",
+                "structure_type": "direct",
+                "platform": "linux_x64",
+                "metadata_version": 1,
+                "stripped": [format!("bin/{}", with_executable_extension("javac"))]
+            }
+        });
+        fs::write(
+            jdks_dir.join("temurin-21.0.1.meta.json"),
+            metadata.to_string(),
+        )
+        .unwrap();
+
+        let check = JdkIntegrityCheck::new(&setup.config);
+        let result = check.run(Instant::now(), CheckCategory::Jdks);
+
+        assert_eq!(result.status, CheckStatus::Pass);
+    }
+
+    fn write_modules_image(jdk_path: &Path, index_size: u32, file_len: usize) {
+        let lib_dir = jdk_path.join("lib");
+        fs::create_dir_all(&lib_dir).unwrap();
+        let mut image = Vec::new();
+        // magic, version, flags, resource count, table length, locations, strings
+        for value in [JIMAGE_MAGIC, 0x0001_0000, 0, 0, 0, index_size, 0] {
+            image.extend_from_slice(&value.to_le_bytes());
+        }
+        image.resize(file_len, 0);
+        fs::write(lib_dir.join("modules"), image).unwrap();
+    }
+
+    #[test]
+    fn test_jdk_deep_integrity_check_detects_truncation() {
+        let setup = TestSetup::new();
+        setup.create_mock_jdk("temurin-21.0.1");
+        let jdk_path = setup.config.jdks_dir().unwrap().join("temurin-21.0.1");
+        write_modules_image(&jdk_path, 100, 4096);
+        JdkRepository::new(&setup.config)
+            .save_install_manifest(&jdk_path)
+            .unwrap();
+
+        let check = JdkDeepIntegrityCheck::new(&setup.config);
+        let result = check.run(Instant::now(), CheckCategory::Jdks);
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        // Simulate an interrupted copy of the module image
+        write_modules_image(&jdk_path, 100, 64);
+        let result = check.run(Instant::now(), CheckCategory::Jdks);
+        assert_eq!(result.status, CheckStatus::Fail);
+
+        let data = result.data.unwrap();
+        let report = &data[0];
+        assert_eq!(report["status"], "damaged");
+        assert_eq!(report["manifest"], true);
+        assert_eq!(report["truncated"], serde_json::json!(["lib/modules"]));
+        assert!(
+            report["modules_image"]
+                .as_str()
+                .unwrap()
+                .contains("truncated")
+        );
+    }
+
+    #[test]
+    fn test_jdk_deep_integrity_check_without_manifest() {
+        let setup = TestSetup::new();
+        setup.create_mock_jdk("temurin-21.0.1");
+        let jdk_path = setup.config.jdks_dir().unwrap().join("temurin-21.0.1");
+        write_modules_image(&jdk_path, 0, 1024);
+
+        let check = JdkDeepIntegrityCheck::new(&setup.config);
+        let result = check.run(Instant::now(), CheckCategory::Jdks);
+
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert_eq!(result.data.unwrap()[0]["status"], "unverified");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_jdk_deep_integrity_check_reports_broken_symlinks() {
+        let setup = TestSetup::new();
+        setup.create_mock_jdk("temurin-21.0.1");
+        let jdk_path = setup.config.jdks_dir().unwrap().join("temurin-21.0.1");
+        write_modules_image(&jdk_path, 0, 1024);
+        std::os::unix::fs::symlink("Contents/Home/bin/jar", jdk_path.join("jar")).unwrap();
+
+        let check = JdkDeepIntegrityCheck::new(&setup.config);
+        let result = check.run(Instant::now(), CheckCategory::Jdks);
+
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert_eq!(
+            result.data.unwrap()[0]["broken_symlinks"],
+            serde_json::json!(["jar"])
+        );
+    }
+
     #[test]
     fn test_jdk_disk_space_check() {
         let setup = TestSetup::new();
@@ -18,18 +18,24 @@ pub mod jdks;
 pub mod network;
 pub mod permissions;
 pub mod shell;
+pub mod shims;
 
 pub use cache::{
     CacheFileCheck, CacheFormatCheck, CachePermissionsCheck, CacheSizeCheck, CacheStalenessCheck,
 };
 pub use installation::{
-    ConfigFileCheck, InstallationDirectoryCheck, KopiBinaryCheck, ShimsInPathCheck, VersionCheck,
+    ConfigFileCheck, CrashReportCheck, InstallationDirectoryCheck, KopiBinaryCheck,
+    LongPathSupportCheck, ShimsInPathCheck, VersionCheck,
 };
 pub use jdks::{
-    JdkDiskSpaceCheck, JdkInstallationCheck, JdkIntegrityCheck, JdkVersionConsistencyCheck,
+    JdkDeepIntegrityCheck, JdkDiskSpaceCheck, JdkInstallationCheck, JdkIntegrityCheck,
+    JdkVersionConsistencyCheck,
 };
 pub use network::{
     ApiConnectivityCheck, DnsResolutionCheck, ProxyConfigurationCheck, TlsVerificationCheck,
 };
-pub use permissions::{BinaryPermissionsCheck, DirectoryPermissionsCheck};
-pub use shell::{PathCheck, ShellConfigurationCheck, ShellDetectionCheck, ShimFunctionalityCheck};
+pub use permissions::{BinaryPermissionsCheck, DirectoryPermissionsCheck, SharedHomeCheck};
+pub use shell::{
+    EnvScriptCheck, PathCheck, ShellConfigurationCheck, ShellDetectionCheck, ShimFunctionalityCheck,
+};
+pub use shims::{DEFAULT_TRACE_TOOL, ShimTraceCheck};
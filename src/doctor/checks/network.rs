@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use crate::api::client::{API_VERSION, FOOJAY_API_BASE};
+use crate::config::KopiConfig;
 use crate::doctor::{CheckCategory, CheckResult, CheckStatus, DiagnosticCheck};
+use crate::security::tls::{self, TrustStore};
 use crate::user_agent;
 use std::env;
 use std::time::{Duration, Instant};
@@ -38,6 +40,7 @@ impl DiagnosticCheck for ApiConnectivityCheck {
         let mut session = attohttpc::Session::new();
         session.timeout(NETWORK_TIMEOUT);
         session.header("User-Agent", user_agent::doctor_client());
+        tls::configure_session(&mut session);
 
         match session.get(get_api_health_check_url()).send() {
             Ok(response) => {
@@ -219,9 +222,17 @@ impl DiagnosticCheck for DnsResolutionCheck {
     }
 }
 
-pub struct TlsVerificationCheck;
+pub struct TlsVerificationCheck<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> TlsVerificationCheck<'a> {
+    pub fn new(config: &'a KopiConfig) -> Self {
+        Self { config }
+    }
+}
 
-impl DiagnosticCheck for TlsVerificationCheck {
+impl DiagnosticCheck for TlsVerificationCheck<'_> {
     fn name(&self) -> &str {
         "TLS/SSL Verification"
     }
@@ -229,10 +240,29 @@ impl DiagnosticCheck for TlsVerificationCheck {
     fn run(&self, start: Instant, category: CheckCategory) -> CheckResult {
         let duration = start.elapsed();
 
+        let store = match TrustStore::from_config(&self.config.network) {
+            Ok(store) => store,
+            Err(e) => {
+                return CheckResult::new(
+                    self.name(),
+                    category,
+                    CheckStatus::Fail,
+                    "Configured CA bundle could not be loaded",
+                    duration,
+                )
+                .with_details(e.to_string())
+                .with_suggestion(
+                    "Point network.ca_bundle in config.toml at a readable PEM file containing your CA certificates",
+                );
+            }
+        };
+        let trust_store = format!("Trust store: {}", store.describe());
+
         // Test TLS connection with certificate verification
         let mut client = attohttpc::Session::new();
         client.timeout(NETWORK_TIMEOUT);
         client.header("User-Agent", user_agent::doctor_client());
+        store.apply(&mut client);
 
         match client.head(get_api_health_check_url()).send() {
             Ok(_) => CheckResult::new(
@@ -242,7 +272,9 @@ impl DiagnosticCheck for TlsVerificationCheck {
                 "TLS certificate verification successful",
                 duration,
             )
-            .with_details("Successfully verified api.foojay.io certificate"),
+            .with_details(format!(
+                "{trust_store}\nSuccessfully verified api.foojay.io certificate"
+            )),
             Err(e) => {
                 let error_str = e.to_string();
                 let (message, suggestion) = if error_str.contains("certificate")
@@ -251,7 +283,7 @@ impl DiagnosticCheck for TlsVerificationCheck {
                 {
                     (
                         "TLS/SSL certificate verification failed".to_string(),
-                        "Check system certificate store or proxy MITM certificates",
+                        "Check system certificate store or set network.ca_bundle to your proxy's CA certificate",
                     )
                 } else {
                     (
@@ -261,6 +293,7 @@ impl DiagnosticCheck for TlsVerificationCheck {
                 };
 
                 CheckResult::new(self.name(), category, CheckStatus::Fail, message, duration)
+                    .with_details(trust_store)
                     .with_suggestion(suggestion)
             }
         }
@@ -318,7 +351,9 @@ mod tests {
 
     #[test]
     fn test_tls_verification_check_name() {
-        let check = TlsVerificationCheck;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let check = TlsVerificationCheck::new(&config);
         assert_eq!(check.name(), "TLS/SSL Verification");
     }
 }
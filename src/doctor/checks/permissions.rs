@@ -14,8 +14,11 @@
 
 use crate::config::KopiConfig;
 use crate::doctor::{CheckCategory, CheckResult, CheckStatus, DiagnosticCheck};
+use crate::paths::home;
 use crate::platform::file_ops::check_executable_permissions;
 use crate::platform::{executable_extension, kopi_binary_name, shim_binary_name};
+#[cfg(unix)]
+use crate::storage::shared_home::SHARED_LOCK_DIR_BITS;
 use std::fs;
 use std::path::Path;
 use std::time::Instant;
@@ -193,6 +196,138 @@ impl DiagnosticCheck for BinaryPermissionsCheck<'_> {
     }
 }
 
+/// Check the layout of the shared Kopi home set by `storage.shared_home`
+pub struct SharedHomeCheck<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> SharedHomeCheck<'a> {
+    pub fn new(config: &'a KopiConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl DiagnosticCheck for SharedHomeCheck<'_> {
+    fn name(&self) -> &str {
+        "Shared Kopi Home"
+    }
+
+    fn run(&self, start: Instant, category: CheckCategory) -> CheckResult {
+        let Some(shared_home) = self.config.shared_home() else {
+            return CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Skip,
+                "No shared Kopi home configured",
+                start.elapsed(),
+            );
+        };
+
+        let jdks_dir = home::jdks_dir(&shared_home);
+        let jdk_count = match fs::read_dir(&jdks_dir) {
+            Ok(entries) => entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .count(),
+            Err(e) => {
+                return CheckResult::new(
+                    self.name(),
+                    category,
+                    CheckStatus::Fail,
+                    format!("Cannot read shared JDK directory {}", jdks_dir.display()),
+                    start.elapsed(),
+                )
+                .with_details(e.to_string())
+                .with_suggestion(format!(
+                    "Install a JDK into it with 'sudo env KOPI_HOME={} kopi install <version>', \
+                     or make it readable for all users",
+                    shared_home.display()
+                ));
+            }
+        };
+
+        let mut problems = Vec::new();
+        let mut warnings = Vec::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+            for dir in [&shared_home, &jdks_dir] {
+                if let Ok(metadata) = fs::metadata(dir) {
+                    if metadata.permissions().mode() & 0o002 != 0 {
+                        problems.push(format!(
+                            "{} is world-writable; any user could replace the shared JDKs",
+                            dir.display()
+                        ));
+                    }
+                    if metadata.uid() != 0 {
+                        warnings.push(format!("{} is not owned by root", dir.display()));
+                    }
+                }
+            }
+
+            let locks_dir = home::locks_dir(&shared_home);
+            if let Ok(metadata) = fs::metadata(&locks_dir)
+                && metadata.permissions().mode() & SHARED_LOCK_DIR_BITS != SHARED_LOCK_DIR_BITS
+            {
+                warnings.push(format!(
+                    "{} is not group-writable with the set-group-ID bit",
+                    locks_dir.display()
+                ));
+            }
+        }
+
+        let access = if check_directory_writable(&jdks_dir).is_ok() {
+            "writable by this user"
+        } else {
+            "installs require elevation"
+        };
+        let message = format!(
+            "Shared Kopi home {}: {jdk_count} JDK(s), {access}",
+            shared_home.display()
+        );
+
+        if !problems.is_empty() {
+            problems.extend(warnings);
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Fail,
+                message,
+                start.elapsed(),
+            )
+            .with_details(problems.join("\n"))
+            .with_suggestion(format!(
+                "Restrict it with: sudo chmod o-w {} {}",
+                shared_home.display(),
+                jdks_dir.display()
+            ))
+        } else if !warnings.is_empty() {
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Warning,
+                message,
+                start.elapsed(),
+            )
+            .with_details(warnings.join("\n"))
+            .with_suggestion(format!(
+                "Run 'sudo env KOPI_HOME={} kopi install <version>' to restore the shared lock \
+                 permissions, and keep the shared home owned by root",
+                shared_home.display()
+            ))
+        } else {
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Pass,
+                message,
+                start.elapsed(),
+            )
+        }
+    }
+}
+
 // Helper functions
 
 fn check_directory_writable(path: &Path) -> Result<(), String> {
@@ -285,6 +420,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shared_home_check() {
+        let temp_dir = TempDir::new().unwrap();
+        let shared_home = temp_dir.path().join("shared");
+        let mut config = crate::config::KopiConfig::new(temp_dir.path().join("user")).unwrap();
+        let start = Instant::now();
+
+        let result = SharedHomeCheck::new(&config).run(start, CheckCategory::Permissions);
+        assert_eq!(result.status, CheckStatus::Skip);
+
+        config.storage.shared_home = Some(shared_home.clone());
+        let result = SharedHomeCheck::new(&config).run(start, CheckCategory::Permissions);
+        assert_eq!(result.status, CheckStatus::Fail);
+
+        crate::storage::shared_home::prepare_layout(&shared_home).unwrap();
+        fs::create_dir(home::jdks_dir(&shared_home).join("temurin-21.0.5")).unwrap();
+        let result = SharedHomeCheck::new(&config).run(start, CheckCategory::Permissions);
+        assert!(result.message.contains("1 JDK(s)"));
+        assert_ne!(result.status, CheckStatus::Fail);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&shared_home, fs::Permissions::from_mode(0o777)).unwrap();
+            let result = SharedHomeCheck::new(&config).run(start, CheckCategory::Permissions);
+            assert_eq!(result.status, CheckStatus::Fail);
+            assert!(result.details.unwrap().contains("world-writable"));
+        }
+    }
+
     #[test]
     fn test_check_directory_writable() {
         let temp_dir = TempDir::new().unwrap();
@@ -12,11 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::commands::env::EnvCommand;
 use crate::config::KopiConfig;
 use crate::doctor::{CheckCategory, CheckResult, CheckStatus, DiagnosticCheck};
+use crate::error::KopiError;
+use crate::i18n::{t, t_with};
 use crate::paths::shims;
-use crate::platform::shell::{detect_shell, is_in_path};
-use crate::platform::{path_separator, with_executable_extension};
+use crate::platform::shell::{Shell, detect_shell, is_in_path};
+use crate::shim::shadowing::{ShadowingEntry, find_shadowing, reordered_path, set_path_command};
 use std::env;
 use std::fs;
 use std::path::Path;
@@ -32,46 +35,11 @@ impl<'a> PathCheck<'a> {
         Self { config }
     }
 
-    fn check_path_priority(&self, shims_dir: &Path) -> Option<String> {
-        if let Ok(path_var) = env::var("PATH") {
-            let separator = path_separator();
-            let paths: Vec<&str> = path_var.split(&separator.to_string()).collect();
-
-            let mut shims_index = None;
-            let mut system_java_index = None;
-
-            for (index, path) in paths.iter().enumerate() {
-                if Path::new(path) == shims_dir {
-                    shims_index = Some(index);
-                }
-
-                // Check for system Java installations
-                if (path.contains("/usr/bin")
-                    || path.contains("\\System32")
-                    || path.contains("Java")
-                    || path.contains("java"))
-                    && system_java_index.is_none()
-                {
-                    // Check if this directory contains java executable
-                    let java_path = Path::new(path).join(with_executable_extension("java"));
-                    if java_path.exists() {
-                        system_java_index = Some(index);
-                    }
-                }
-            }
-
-            if let (Some(shims), Some(system)) = (shims_index, system_java_index)
-                && shims > system
-            {
-                return Some(format!(
-                    "Kopi shims directory is in PATH but comes after system Java (position {} \
-                     vs {})",
-                    shims + 1,
-                    system + 1
-                ));
-            }
-        }
-        None
+    /// Earlier `PATH` entries whose `java` runs instead of the shims
+    fn shadowing_entries(&self, shims_dir: &Path) -> Vec<ShadowingEntry> {
+        env::var_os("PATH")
+            .map(|path| find_shadowing(shims_dir, &path))
+            .unwrap_or_default()
     }
 }
 
@@ -99,21 +67,56 @@ impl<'a> DiagnosticCheck for PathCheck<'a> {
                     // Default to bash/zsh style if detection fails
                     format!("export PATH=\"{}:$PATH\"", shims_dir.display())
                 };
-                format!("Add this line to your shell configuration:\n{shell_cmd}")
+                t_with("doctor.shell.add_path_line", &[("command", &shell_cmd)])
             });
         }
 
         // Check PATH priority
-        if let Some(priority_issue) = self.check_path_priority(&shims_dir) {
+        let shadowing = self.shadowing_entries(&shims_dir);
+        if let Some(first) = shadowing.first() {
+            let details: Vec<String> = shadowing
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "PATH entry {}: {} provides {}",
+                        entry.position,
+                        entry.directory.display(),
+                        entry.java.display()
+                    )
+                })
+                .collect();
+            let shell = detect_shell()
+                .map(|(shell, _)| shell)
+                .unwrap_or(Shell::Unknown(String::new()));
+            let suggestion =
+                match env::var_os("PATH").and_then(|path| reordered_path(&shims_dir, &path)) {
+                    Some(reordered) => t_with(
+                        "doctor.shell.move_path_line_or_session",
+                        &[
+                            ("shell", shell.get_shell_name()),
+                            ("command", &set_path_command(&shell, &reordered)),
+                        ],
+                    ),
+                    None => t_with(
+                        "doctor.shell.move_path_line",
+                        &[("shell", shell.get_shell_name())],
+                    ),
+                };
+
             return CheckResult::new(
                 self.name(),
                 category,
                 CheckStatus::Warning,
-                priority_issue,
+                format!(
+                    "Kopi shims directory is in PATH but comes after system Java in {} \
+                     (position {})",
+                    first.directory.display(),
+                    first.position
+                ),
                 start.elapsed(),
             )
-            .with_details("Kopi shims should appear before system Java in PATH")
-            .with_suggestion("Reorder your PATH to ensure ~/.kopi/shims comes first");
+            .with_details(details.join("\n"))
+            .with_suggestion(suggestion);
         }
 
         CheckResult::new(
@@ -160,7 +163,7 @@ impl DiagnosticCheck for ShellDetectionCheck {
                 start.elapsed(),
             )
             .with_details(format!("Error: {e}"))
-            .with_suggestion("Specify your shell type when running 'kopi shell' command"),
+            .with_suggestion(t("doctor.shell.specify_shell")),
         }
     }
 }
@@ -219,10 +222,12 @@ impl DiagnosticCheck for ShellConfigurationCheck {
                 ),
                 start.elapsed(),
             )
-            .with_suggestion(format!(
-                "Create {} and add:\n{}",
-                config_file.display(),
-                shell.get_path_config_command()
+            .with_suggestion(t_with(
+                "doctor.shell.create_config",
+                &[
+                    ("path", &config_file.display().to_string()),
+                    ("command", &shell.get_path_config_command()),
+                ],
             ));
         }
 
@@ -249,10 +254,12 @@ impl DiagnosticCheck for ShellConfigurationCheck {
                         format!("Kopi setup not found in {}", config_file.display()),
                         start.elapsed(),
                     )
-                    .with_suggestion(format!(
-                        "Add to {}:\n{}",
-                        config_file.display(),
-                        shell.get_path_config_command()
+                    .with_suggestion(t_with(
+                        "doctor.shell.add_to_config",
+                        &[
+                            ("path", &config_file.display().to_string()),
+                            ("command", &shell.get_path_config_command()),
+                        ],
                     ))
                 }
             }
@@ -300,7 +307,7 @@ impl<'a> DiagnosticCheck for ShimFunctionalityCheck<'a> {
                 start.elapsed(),
             )
             .with_details(format!("Expected directory: {}", shims_dir.display()))
-            .with_suggestion("Run 'kopi use <version>' to create shims for an installed JDK");
+            .with_suggestion(t("doctor.shell.create_shims"));
         }
 
         // Check if directory is readable
@@ -337,7 +344,7 @@ impl<'a> DiagnosticCheck for ShimFunctionalityCheck<'a> {
                         "Shims directory exists but contains no shims",
                         start.elapsed(),
                     )
-                    .with_suggestion("Run 'kopi use <version>' to activate a JDK and create shims")
+                    .with_suggestion(t("doctor.shell.activate_jdk"))
                 } else if !non_executable_shims.is_empty() {
                     CheckResult::new(
                         self.name(),
@@ -349,9 +356,7 @@ impl<'a> DiagnosticCheck for ShimFunctionalityCheck<'a> {
                         ),
                         start.elapsed(),
                     )
-                    .with_suggestion(
-                        "Fix permissions on shim files or recreate them with 'kopi use <version>'",
-                    )
+                    .with_suggestion(t("doctor.shell.fix_shim_permissions"))
                 } else {
                     CheckResult::new(
                         self.name(),
@@ -369,15 +374,106 @@ impl<'a> DiagnosticCheck for ShimFunctionalityCheck<'a> {
                 format!("Cannot read shims directory: {e}"),
                 start.elapsed(),
             )
-            .with_suggestion("Check directory permissions or recreate with 'kopi use <version>'"),
+            .with_suggestion(t("doctor.shell.check_shims_directory")),
         }
     }
 }
 
+/// Run the `kopi env` script in the detected shell and check that `java`
+/// resolves to the configured JDK
+pub struct EnvScriptCheck<'a> {
+    config: &'a KopiConfig,
+}
+
+impl<'a> EnvScriptCheck<'a> {
+    pub fn new(config: &'a KopiConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<'a> DiagnosticCheck for EnvScriptCheck<'a> {
+    fn name(&self) -> &str {
+        "Environment Script"
+    }
+
+    fn run(&self, start: Instant, category: CheckCategory) -> CheckResult {
+        let Ok((shell, _)) = detect_shell() else {
+            return CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Skip,
+                "Cannot validate the environment script - shell detection failed",
+                start.elapsed(),
+            );
+        };
+        let shell_name = shell.get_shell_name().to_string();
+
+        let validation = match EnvCommand::new(self.config)
+            .and_then(|command| command.validate(None, Some(&shell_name)))
+        {
+            Ok(validation) => validation,
+            Err(
+                e @ (KopiError::NoLocalVersion { .. }
+                | KopiError::JdkNotInstalled { .. }
+                | KopiError::ShellNotFound(_)
+                | KopiError::UnsupportedShell(_)),
+            ) => {
+                return CheckResult::new(
+                    self.name(),
+                    category,
+                    CheckStatus::Skip,
+                    format!("Cannot validate the environment script: {e}"),
+                    start.elapsed(),
+                );
+            }
+            Err(e) => {
+                return CheckResult::new(
+                    self.name(),
+                    category,
+                    CheckStatus::Warning,
+                    format!("Failed to run the environment script in {shell_name}"),
+                    start.elapsed(),
+                )
+                .with_details(format!("Error: {e}"));
+            }
+        };
+
+        if validation.problems.is_empty() {
+            return CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Pass,
+                format!(
+                    "kopi env selects {} in {shell_name}",
+                    validation.expected_java_home.display()
+                ),
+                start.elapsed(),
+            );
+        }
+
+        CheckResult::new(
+            self.name(),
+            category,
+            CheckStatus::Warning,
+            format!(
+                "kopi env does not select {} in {shell_name}",
+                validation.expected_java_home.display()
+            ),
+            start.elapsed(),
+        )
+        .with_details(validation.problems.join("\n"))
+        .with_suggestion(t_with(
+            "doctor.shell.revalidate_env",
+            &[("shell", &shell_name)],
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::KopiConfig;
+    use crate::platform::path_separator;
     use std::fs;
     use tempfile::TempDir;
 
@@ -520,9 +616,24 @@ mod tests {
             env::set_var("PATH", &new_path);
         }
 
-        let priority_issue = check.check_path_priority(&shims_dir);
-        assert!(priority_issue.is_some());
-        assert!(priority_issue.unwrap().contains("comes after system Java"));
+        let result = check.run(Instant::now(), CheckCategory::Shell);
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert!(result.message.contains("comes after system Java"));
+        assert!(result.message.contains("(position 1)"));
+        assert!(
+            result
+                .details
+                .as_deref()
+                .unwrap()
+                .contains(&java_exe.display().to_string())
+        );
+        let suggestion = result.suggestion.unwrap();
+        assert!(suggestion.contains(&format!(
+            "{}{}{}",
+            shims_dir.display(),
+            separator,
+            sys_java_dir.display()
+        )));
 
         // Restore PATH
         unsafe {
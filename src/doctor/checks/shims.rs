@@ -0,0 +1,398 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! End-to-end shim diagnostics.
+//!
+//! Runs a tool through its shim the way a shell would and reports every step
+//! on the way: the shim file, what `PATH` finds first, the JDK the shim should
+//! pick, and the outcome of actually launching it.
+
+use crate::commands::which::format_source;
+use crate::config::KopiConfig;
+use crate::doctor::{CheckCategory, CheckResult, CheckStatus, DiagnosticCheck};
+use crate::paths::shims;
+use crate::platform::file_ops::is_executable;
+use crate::storage::JdkRepository;
+use crate::version::resolver::VersionResolver;
+use serde_json::json;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Tool traced when `--trace` is not given
+pub const DEFAULT_TRACE_TOOL: &str = "java";
+
+/// How long the traced tool may run before it is killed
+const TRACE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Invoke a tool through its shim and report how it was resolved and run
+pub struct ShimTraceCheck<'a> {
+    config: &'a KopiConfig,
+    tool: String,
+    timeout: Duration,
+}
+
+/// Outcome of launching the shim
+struct TraceRun {
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+    elapsed: Duration,
+}
+
+impl<'a> ShimTraceCheck<'a> {
+    pub fn new(config: &'a KopiConfig, tool: impl Into<String>) -> Self {
+        Self {
+            config,
+            tool: tool.into(),
+            timeout: TRACE_TIMEOUT,
+        }
+    }
+
+    /// Arguments that make `tool` print its version and exit
+    fn version_args(&self) -> &'static [&'static str] {
+        match self.tool.as_str() {
+            "java" | "javac" => &["-version"],
+            _ => &["--version"],
+        }
+    }
+
+    /// The JDK the shim should select, as `distribution@version (source)`
+    fn resolve_jdk(&self) -> Result<String, String> {
+        let resolver = VersionResolver::new(self.config);
+        let (request, source) = resolver
+            .resolve_tool_version(&self.tool)
+            .map_err(|e| e.to_string())?;
+        let repository = JdkRepository::new(self.config);
+        let matching = repository
+            .find_matching_jdks(&request)
+            .map_err(|e| e.to_string())?;
+        match matching.last() {
+            Some(jdk) => Ok(format!(
+                "{}@{} ({})",
+                jdk.distribution,
+                jdk.version,
+                format_source(&source)
+            )),
+            None => Err(format!(
+                "{request} is requested by {} but not installed",
+                format_source(&source)
+            )),
+        }
+    }
+
+    /// Launch the shim with auto-install disabled, no stdin and a timeout
+    fn launch(&self, shim_path: &Path) -> std::io::Result<TraceRun> {
+        let start = Instant::now();
+        let mut child = Command::new(shim_path)
+            .args(self.version_args())
+            .env("KOPI_HOME", self.config.kopi_home())
+            .env("KOPI_AUTO_INSTALL__ENABLED", "false")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stdout_reader = thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = stdout.read_to_end(&mut buffer);
+            buffer
+        });
+        let stderr_reader = thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = stderr.read_to_end(&mut buffer);
+            buffer
+        });
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if start.elapsed() >= self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        let elapsed = start.elapsed();
+
+        let stdout = stdout_reader.join().unwrap_or_default();
+        let stderr = stderr_reader.join().unwrap_or_default();
+        Ok(TraceRun {
+            exit_code: status.and_then(|status| status.code()),
+            stdout: String::from_utf8_lossy(&stdout).trim().to_string(),
+            stderr: String::from_utf8_lossy(&stderr).trim().to_string(),
+            elapsed: if status.is_some() {
+                elapsed
+            } else {
+                self.timeout
+            },
+        })
+    }
+}
+
+impl DiagnosticCheck for ShimTraceCheck<'_> {
+    fn name(&self) -> &str {
+        "Shim Trace"
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        // Leave room to report the run after the tool itself is killed
+        Some(self.timeout + Duration::from_secs(5))
+    }
+
+    fn run(&self, start: Instant, category: CheckCategory) -> CheckResult {
+        let shim_path = shims::tool_shim_path(self.config.kopi_home(), &self.tool);
+        let command_line = format!("{} {}", self.tool, self.version_args().join(" "));
+        let mut trace = vec![format!("shim: {}", shim_path.display())];
+
+        if !shim_path.exists() {
+            return CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Fail,
+                format!("No shim for '{}'", self.tool),
+                start.elapsed(),
+            )
+            .with_details(trace.join("\n"))
+            .with_suggestion(format!("Create it with 'kopi shim add {}'", self.tool));
+        }
+        if !is_executable(&shim_path).unwrap_or(false) {
+            return CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Fail,
+                format!("The '{}' shim is not executable", self.tool),
+                start.elapsed(),
+            )
+            .with_details(trace.join("\n"))
+            .with_suggestion("Recreate the shims with 'kopi shim verify --fix'");
+        }
+
+        let path_hit = which::which(&self.tool).ok();
+        let shadowed_by = path_hit
+            .as_ref()
+            .filter(|found| !same_file(found, &shim_path))
+            .cloned();
+        trace.push(match &path_hit {
+            Some(found) => format!("PATH: {} -> {}", self.tool, found.display()),
+            None => format!("PATH: {} not found", self.tool),
+        });
+
+        let resolved = self.resolve_jdk();
+        trace.push(match &resolved {
+            Ok(jdk) => format!("resolved: {jdk}"),
+            Err(e) => format!("resolved: failed: {e}"),
+        });
+
+        let run = match self.launch(&shim_path) {
+            Ok(run) => run,
+            Err(e) => {
+                return CheckResult::new(
+                    self.name(),
+                    category,
+                    CheckStatus::Fail,
+                    format!("Failed to launch the '{}' shim: {e}", self.tool),
+                    start.elapsed(),
+                )
+                .with_details(trace.join("\n"))
+                .with_suggestion(
+                    "Check that the shim is a valid kopi-shim binary, or recreate it with 'kopi \
+                     shim verify --fix'",
+                );
+            }
+        };
+        trace.push(format!(
+            "exec: {command_line} -> {} in {}ms",
+            match run.exit_code {
+                Some(code) => format!("exit {code}"),
+                None => "timed out".to_string(),
+            },
+            run.elapsed.as_millis()
+        ));
+        if !run.stdout.is_empty() {
+            trace.push(format!("stdout:\n{}", run.stdout));
+        }
+        if !run.stderr.is_empty() {
+            trace.push(format!("stderr:\n{}", run.stderr));
+        }
+
+        let data = json!({
+            "tool": self.tool,
+            "shim": shim_path.display().to_string(),
+            "path_resolution": path_hit.as_ref().map(|found| found.display().to_string()),
+            "resolved_jdk": resolved.as_ref().ok(),
+            "resolution_error": resolved.as_ref().err(),
+            "exit_code": run.exit_code,
+            "elapsed_ms": run.elapsed.as_millis() as u64,
+            "stdout": run.stdout,
+            "stderr": run.stderr,
+        });
+
+        let result = if run.exit_code != Some(0) {
+            let suggestion = match &resolved {
+                Err(_) => "Fix the version resolution error above, e.g. install the requested \
+                           JDK or correct the version file"
+                    .to_string(),
+                Ok(_) => format!(
+                    "Run '{command_line}' with RUST_LOG=debug to see how the shim resolved it"
+                ),
+            };
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Fail,
+                match run.exit_code {
+                    Some(code) => format!("'{command_line}' through the shim exited with {code}"),
+                    None => format!(
+                        "'{command_line}' through the shim did not finish within {}s",
+                        self.timeout.as_secs()
+                    ),
+                },
+                start.elapsed(),
+            )
+            .with_suggestion(suggestion)
+        } else if path_hit.is_none() {
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Warning,
+                format!(
+                    "The shim works, but '{}' is not found in PATH so shells will not use it",
+                    self.tool
+                ),
+                start.elapsed(),
+            )
+            .with_suggestion("Add ~/.kopi/shims to PATH with 'kopi setup'")
+        } else if let Some(found) = &shadowed_by {
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Warning,
+                format!(
+                    "The shim works, but '{}' in PATH runs {} instead",
+                    self.tool,
+                    found.display()
+                ),
+                start.elapsed(),
+            )
+            .with_suggestion("Move ~/.kopi/shims before other Java directories in PATH")
+        } else {
+            CheckResult::new(
+                self.name(),
+                category,
+                CheckStatus::Pass,
+                format!(
+                    "'{command_line}' ran through the shim in {}ms{}",
+                    run.elapsed.as_millis(),
+                    resolved
+                        .as_ref()
+                        .map(|jdk| format!(" using {jdk}"))
+                        .unwrap_or_default()
+                ),
+                start.elapsed(),
+            )
+        };
+        result.with_details(trace.join("\n")).with_data(data)
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    canonical(a) == canonical(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_config() -> (TempDir, KopiConfig) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        (temp_dir, config)
+    }
+
+    #[test]
+    fn test_trace_missing_shim() {
+        let (_temp, config) = create_test_config();
+        let check = ShimTraceCheck::new(&config, "jshell");
+        let result = check.run(Instant::now(), CheckCategory::Shims);
+
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.contains("No shim for 'jshell'"));
+        assert!(result.suggestion.unwrap().contains("kopi shim add jshell"));
+    }
+
+    #[cfg(unix)]
+    fn write_shim(config: &KopiConfig, tool: &str, script: &str, mode: u32) {
+        use std::os::unix::fs::PermissionsExt;
+
+        shims::ensure_shims_root(config.kopi_home()).unwrap();
+        let path = shims::tool_shim_path(config.kopi_home(), tool);
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_trace_reports_exec_outcome() {
+        let (_temp, config) = create_test_config();
+
+        write_shim(
+            &config,
+            "java",
+            "#!/bin/sh\necho \"openjdk $1\" >&2\n",
+            0o644,
+        );
+        let result = ShimTraceCheck::new(&config, "java").run(Instant::now(), CheckCategory::Shims);
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.contains("not executable"));
+
+        write_shim(
+            &config,
+            "java",
+            "#!/bin/sh\necho \"openjdk $1\" >&2\n",
+            0o755,
+        );
+        let result = ShimTraceCheck::new(&config, "java").run(Instant::now(), CheckCategory::Shims);
+        assert_ne!(result.status, CheckStatus::Fail);
+        let data = result.data.unwrap();
+        assert_eq!(data["exit_code"], 0);
+        assert_eq!(data["stderr"], "openjdk -version");
+        assert!(
+            result
+                .details
+                .unwrap()
+                .contains("exec: java -version -> exit 0")
+        );
+
+        write_shim(
+            &config,
+            "javac",
+            "#!/bin/sh\necho broken >&2\nexit 3\n",
+            0o755,
+        );
+        let result =
+            ShimTraceCheck::new(&config, "javac").run(Instant::now(), CheckCategory::Shims);
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.contains("exited with 3"));
+        assert!(result.details.unwrap().contains("stderr:\nbroken"));
+    }
+}
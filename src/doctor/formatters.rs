@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::doctor::{CheckCategory, CheckResult, CheckStatus, DiagnosticSummary};
+use crate::i18n::{t, t_with};
 use chrono::{DateTime, Utc};
 use colored::Colorize;
 use serde::Serialize;
@@ -24,8 +25,9 @@ pub fn format_human_readable<W: Write>(
     summary: &DiagnosticSummary,
     verbose: bool,
 ) -> std::io::Result<()> {
-    writeln!(writer, "\nKopi Doctor Report")?;
-    writeln!(writer, "==================")?;
+    let title = t("doctor.report.title");
+    writeln!(writer, "\n{title}")?;
+    writeln!(writer, "{}", "=".repeat(display_width(&title)))?;
     writeln!(writer)?;
 
     let categories = CheckCategory::all();
@@ -39,7 +41,11 @@ pub fn format_human_readable<W: Write>(
         }
 
         writeln!(writer, "{category}")?;
-        writeln!(writer, "{}", "-".repeat(category.to_string().len()))?;
+        writeln!(
+            writer,
+            "{}",
+            "-".repeat(display_width(&category.to_string()))
+        )?;
 
         for result in category_results {
             let status_symbol = match result.status {
@@ -67,7 +73,7 @@ pub fn format_human_readable<W: Write>(
 
                 if let Some(ref suggestion) = result.suggestion {
                     writeln!(writer)?;
-                    writeln!(writer, "    To fix:")?;
+                    writeln!(writer, "    {}", t("doctor.report.to_fix"))?;
                     for line in suggestion.lines() {
                         writeln!(writer, "      {line}")?;
                     }
@@ -75,28 +81,67 @@ pub fn format_human_readable<W: Write>(
             }
 
             if verbose {
-                writeln!(writer, "    Duration: {:?}", result.duration)?;
+                writeln!(
+                    writer,
+                    "    {}",
+                    t_with(
+                        "doctor.report.duration",
+                        &[("duration", &format!("{:?}", result.duration))]
+                    )
+                )?;
             }
         }
         writeln!(writer)?;
     }
 
-    writeln!(writer, "Summary")?;
-    writeln!(writer, "-------")?;
+    let heading = t("doctor.report.summary");
+    writeln!(writer, "{heading}")?;
+    writeln!(writer, "{}", "-".repeat(display_width(&heading)))?;
     writeln!(
         writer,
-        "Total checks: {} (✓ {} passed, ✗ {} failed, ⚠ {} warnings, ○ {} skipped)",
-        summary.total_checks, summary.passed, summary.failed, summary.warnings, summary.skipped
+        "{}",
+        t_with(
+            "doctor.report.total_checks",
+            &[
+                ("total", &summary.total_checks.to_string()),
+                ("passed", &summary.passed.to_string()),
+                ("failed", &summary.failed.to_string()),
+                ("warnings", &summary.warnings.to_string()),
+                ("skipped", &summary.skipped.to_string()),
+            ]
+        )
     )?;
     writeln!(
         writer,
-        "Total time: {:.2}s",
-        summary.total_duration.as_secs_f64()
+        "{}",
+        t_with(
+            "doctor.report.total_time",
+            &[(
+                "seconds",
+                &format!("{:.2}", summary.total_duration.as_secs_f64())
+            )]
+        )
     )?;
 
     Ok(())
 }
 
+/// Terminal columns taken by `text`, counting CJK characters as two so
+/// underlines still match translated headings
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6 => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
 #[derive(Serialize)]
 struct JsonOutput {
     version: String,
@@ -131,6 +176,8 @@ struct JsonCheck {
     details: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     suggestion: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<serde_json::Value>,
     duration_ms: u128,
 }
 
@@ -151,6 +198,7 @@ pub fn format_json<W: Write>(
                 message: r.message.clone(),
                 details: r.details.clone(),
                 suggestion: r.suggestion.clone(),
+                data: r.data.clone(),
                 duration_ms: r.duration.as_millis(),
             })
             .collect();
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crate::indicator::{ProgressConfig, ProgressFactory, ProgressStyle};
@@ -47,6 +50,7 @@ pub enum CheckCategory {
     Permissions,
     Network,
     Cache,
+    Shims,
 }
 
 impl fmt::Display for CheckCategory {
@@ -58,6 +62,7 @@ impl fmt::Display for CheckCategory {
             CheckCategory::Permissions => write!(f, "Permissions"),
             CheckCategory::Network => write!(f, "Network"),
             CheckCategory::Cache => write!(f, "Cache"),
+            CheckCategory::Shims => write!(f, "Shims"),
         }
     }
 }
@@ -71,6 +76,7 @@ impl CheckCategory {
             "permissions" => Some(CheckCategory::Permissions),
             "network" => Some(CheckCategory::Network),
             "cache" => Some(CheckCategory::Cache),
+            "shims" => Some(CheckCategory::Shims),
             _ => None,
         }
     }
@@ -83,9 +89,19 @@ impl CheckCategory {
             CheckCategory::Permissions,
             CheckCategory::Network,
             CheckCategory::Cache,
+            CheckCategory::Shims,
         ]
     }
 
+    /// Categories run when none is selected. `Shims` launches a JDK, so it
+    /// only runs when asked for with `--check shims`.
+    pub fn defaults() -> Vec<CheckCategory> {
+        Self::all()
+            .into_iter()
+            .filter(|category| *category != CheckCategory::Shims)
+            .collect()
+    }
+
     /// Create all diagnostic checks for this category
     pub fn create_checks<'a>(
         &self,
@@ -94,10 +110,11 @@ impl CheckCategory {
         use crate::doctor::checks::{
             ApiConnectivityCheck, BinaryPermissionsCheck, CacheFileCheck, CacheFormatCheck,
             CachePermissionsCheck, CacheSizeCheck, CacheStalenessCheck, ConfigFileCheck,
-            DirectoryPermissionsCheck, DnsResolutionCheck, InstallationDirectoryCheck,
-            JdkDiskSpaceCheck, JdkInstallationCheck, JdkIntegrityCheck, JdkVersionConsistencyCheck,
-            KopiBinaryCheck, PathCheck, ProxyConfigurationCheck, ShellConfigurationCheck,
-            ShellDetectionCheck, ShimFunctionalityCheck, ShimsInPathCheck, TlsVerificationCheck,
+            CrashReportCheck, DirectoryPermissionsCheck, DnsResolutionCheck, EnvScriptCheck,
+            InstallationDirectoryCheck, JdkDiskSpaceCheck, JdkInstallationCheck, JdkIntegrityCheck,
+            JdkVersionConsistencyCheck, KopiBinaryCheck, LongPathSupportCheck, PathCheck,
+            ProxyConfigurationCheck, SharedHomeCheck, ShellConfigurationCheck, ShellDetectionCheck,
+            ShimFunctionalityCheck, ShimTraceCheck, ShimsInPathCheck, TlsVerificationCheck,
             VersionCheck,
         };
 
@@ -108,16 +125,20 @@ impl CheckCategory {
                 Box::new(InstallationDirectoryCheck::new(config)),
                 Box::new(ConfigFileCheck::new(config)),
                 Box::new(ShimsInPathCheck::new(config)),
+                Box::new(LongPathSupportCheck),
+                Box::new(CrashReportCheck::new(config)),
             ],
             CheckCategory::Permissions => vec![
                 Box::new(DirectoryPermissionsCheck::new(config)),
                 Box::new(BinaryPermissionsCheck::new(config)),
+                Box::new(SharedHomeCheck::new(config)),
             ],
             CheckCategory::Shell => vec![
                 Box::new(ShellDetectionCheck) as Box<dyn DiagnosticCheck + 'a>,
                 Box::new(PathCheck::new(config)),
                 Box::new(ShellConfigurationCheck),
                 Box::new(ShimFunctionalityCheck::new(config)),
+                Box::new(EnvScriptCheck::new(config)),
             ],
             CheckCategory::Jdks => vec![
                 Box::new(JdkInstallationCheck::new(config)) as Box<dyn DiagnosticCheck + 'a>,
@@ -129,7 +150,7 @@ impl CheckCategory {
                 Box::new(ApiConnectivityCheck) as Box<dyn DiagnosticCheck + 'a>,
                 Box::new(DnsResolutionCheck),
                 Box::new(ProxyConfigurationCheck),
-                Box::new(TlsVerificationCheck),
+                Box::new(TlsVerificationCheck::new(config)),
             ],
             CheckCategory::Cache => vec![
                 Box::new(CacheFileCheck::new(config)) as Box<dyn DiagnosticCheck + 'a>,
@@ -138,6 +159,12 @@ impl CheckCategory {
                 Box::new(CacheStalenessCheck::new(config)),
                 Box::new(CacheSizeCheck::new(config)),
             ],
+            CheckCategory::Shims => {
+                vec![
+                    Box::new(ShimTraceCheck::new(config, checks::DEFAULT_TRACE_TOOL))
+                        as Box<dyn DiagnosticCheck + 'a>,
+                ]
+            }
         }
     }
 }
@@ -150,6 +177,8 @@ pub struct CheckResult {
     pub message: String,
     pub details: Option<String>,
     pub suggestion: Option<String>,
+    /// Structured per-item results, included in JSON output only
+    pub data: Option<serde_json::Value>,
     pub duration: Duration,
 }
 
@@ -168,6 +197,7 @@ impl CheckResult {
             message: message.into(),
             details: None,
             suggestion: None,
+            data: None,
             duration,
         }
     }
@@ -181,6 +211,11 @@ impl CheckResult {
         self.suggestion = Some(suggestion.into());
         self
     }
+
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
 }
 
 pub struct DiagnosticSummary {
@@ -232,32 +267,94 @@ impl DiagnosticSummary {
 pub trait DiagnosticCheck: Send + Sync {
     fn name(&self) -> &str;
     fn run(&self, start: Instant, category: CheckCategory) -> CheckResult;
+
+    /// How long the check may run before doctor reports it as timed out.
+    /// `None` uses the engine's per-check timeout.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
 }
 
+/// Default time a check may take; keeps `kopi doctor` bounded when, for
+/// example, api.foojay.io does not answer
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Upper bound on the number of checks running at once
+const MAX_WORKERS: usize = 8;
+
 pub struct DiagnosticEngine<'a> {
     config: &'a crate::config::KopiConfig,
+    deep: bool,
+    trace: Option<String>,
+    check_timeout: Duration,
 }
 
 impl<'a> DiagnosticEngine<'a> {
     pub fn new(config: &'a crate::config::KopiConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            deep: false,
+            trace: None,
+            check_timeout: DEFAULT_CHECK_TIMEOUT,
+        }
+    }
+
+    /// Add the slower deep scans, such as verifying JDK files against their
+    /// install manifests
+    pub fn with_deep(mut self, deep: bool) -> Self {
+        self.deep = deep;
+        self
+    }
+
+    /// Run `tool` instead of `java` through its shim in the `shims` category
+    pub fn with_trace(mut self, tool: Option<String>) -> Self {
+        self.trace = tool;
+        self
+    }
+
+    /// Report checks that run longer than `timeout` as timed out
+    pub fn with_check_timeout(mut self, timeout: Duration) -> Self {
+        self.check_timeout = timeout;
+        self
     }
 
+    fn checks_for(&self, category: CheckCategory) -> Vec<Box<dyn DiagnosticCheck + 'a>> {
+        build_checks(self.config, category, self.deep, self.trace.as_deref())
+    }
+
+    /// Run the checks of `categories` (default: [`CheckCategory::defaults`])
+    /// in parallel. Results come back in check order; a check that exceeds
+    /// its timeout is reported as a warning and left running in the
+    /// background.
     pub fn run_checks(
         &self,
         categories: Option<Vec<CheckCategory>>,
         show_progress: bool,
     ) -> Vec<CheckResult> {
-        let mut results = Vec::new();
-
         // Determine which categories to run
-        let categories_to_run = categories.unwrap_or_else(CheckCategory::all);
+        let categories_to_run = categories.unwrap_or_else(CheckCategory::defaults);
 
-        // Count total checks for progress bar
-        let total_checks: usize = categories_to_run
-            .iter()
-            .map(|cat| cat.create_checks(self.config).len())
-            .sum();
+        // Checks borrow the config, so each worker builds its own copy of the
+        // check it runs; a timed-out check may outlive this call
+        let config = Arc::new(self.config.clone());
+        let mut jobs = Vec::new();
+        for category in categories_to_run {
+            for (index, check) in self.checks_for(category).iter().enumerate() {
+                let config = Arc::clone(&config);
+                let deep = self.deep;
+                let trace = self.trace.clone();
+                jobs.push(CheckJob {
+                    name: check.name().to_string(),
+                    category,
+                    timeout: check.timeout().unwrap_or(self.check_timeout),
+                    run: Box::new(move |start| {
+                        let checks = build_checks(&config, category, deep, trace.as_deref());
+                        checks[index].run(start, category)
+                    }),
+                });
+            }
+        }
+        let total_checks = jobs.len() as u64;
 
         // Create progress indicator using the factory
         let mut progress = ProgressFactory::create(!show_progress);
@@ -267,33 +364,21 @@ impl<'a> DiagnosticEngine<'a> {
             eprintln!();
 
             // Initialize progress with configuration
-            let config = ProgressConfig::new(ProgressStyle::Count).with_total(total_checks as u64);
+            let config = ProgressConfig::new(ProgressStyle::Count).with_total(total_checks);
             progress.start(config);
         }
 
-        let mut current_check = 0u64;
-
-        // Create checks for each category and run them
-        for category in categories_to_run {
-            let checks = category.create_checks(self.config);
-
-            for check in checks {
-                // Update progress message
-                if show_progress {
-                    progress.set_message(format!("{}: {}", category, check.name()));
-                }
-
-                let start = Instant::now();
-                let result = check.run(start, category);
-                results.push(result);
-
-                // Update progress counter
-                current_check += 1;
-                if show_progress {
-                    progress.update(current_check, Some(total_checks as u64));
-                }
+        let mut completed = 0u64;
+        let workers = std::thread::available_parallelism()
+            .map_or(4, |n| n.get())
+            .clamp(4, MAX_WORKERS);
+        let results = run_jobs(jobs, workers, |result| {
+            completed += 1;
+            if show_progress {
+                progress.set_message(format!("{}: {}", result.category, result.name));
+                progress.update(completed, Some(total_checks));
             }
-        }
+        });
 
         // Complete progress
         if show_progress {
@@ -305,6 +390,171 @@ impl<'a> DiagnosticEngine<'a> {
     }
 }
 
+fn build_checks<'a>(
+    config: &'a crate::config::KopiConfig,
+    category: CheckCategory,
+    deep: bool,
+    trace: Option<&str>,
+) -> Vec<Box<dyn DiagnosticCheck + 'a>> {
+    if let (CheckCategory::Shims, Some(tool)) = (category, trace) {
+        return vec![Box::new(checks::ShimTraceCheck::new(config, tool))];
+    }
+    let mut checks = category.create_checks(config);
+    if deep && category == CheckCategory::Jdks {
+        checks.push(Box::new(checks::JdkDeepIntegrityCheck::new(config)));
+    }
+    checks
+}
+
+/// A check ready to run on a worker thread
+struct CheckJob {
+    name: String,
+    category: CheckCategory,
+    timeout: Duration,
+    run: Box<dyn FnOnce(Instant) -> CheckResult + Send>,
+}
+
+enum JobEvent {
+    Started(usize, Instant),
+    Finished(usize, CheckResult),
+}
+
+type JobQueue = Arc<Mutex<VecDeque<(usize, Box<dyn FnOnce(Instant) -> CheckResult + Send>)>>>;
+
+/// Run `jobs` on up to `workers` threads and return their results in job
+/// order. Jobs still running after their timeout get a warning result; their
+/// thread is abandoned and replaced so the remaining jobs keep their share of
+/// workers. `on_result` sees each result as it is settled.
+fn run_jobs(
+    jobs: Vec<CheckJob>,
+    workers: usize,
+    mut on_result: impl FnMut(&CheckResult),
+) -> Vec<CheckResult> {
+    let mut meta = Vec::with_capacity(jobs.len());
+    let mut queue = VecDeque::with_capacity(jobs.len());
+    for (index, job) in jobs.into_iter().enumerate() {
+        meta.push((job.name, job.category, job.timeout));
+        queue.push_back((index, job.run));
+    }
+    let queue: JobQueue = Arc::new(Mutex::new(queue));
+    let (sender, receiver) = mpsc::channel();
+    for _ in 0..workers.min(meta.len()) {
+        spawn_worker(&queue, &sender);
+    }
+
+    let mut results: Vec<Option<CheckResult>> = vec![None; meta.len()];
+    let mut running: HashMap<usize, Instant> = HashMap::new();
+    let mut pending = meta.len();
+    while pending > 0 {
+        let deadline = running
+            .iter()
+            .filter_map(|(index, start)| start.checked_add(meta[*index].2))
+            .min();
+        let event = match deadline {
+            Some(deadline) => {
+                match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(event) => Some(event),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            None => match receiver.recv() {
+                Ok(event) => Some(event),
+                Err(_) => break,
+            },
+        };
+
+        match event {
+            Some(JobEvent::Started(index, start)) => {
+                running.insert(index, start);
+            }
+            Some(JobEvent::Finished(index, result)) => {
+                running.remove(&index);
+                // A result arriving after its timeout was already reported
+                if results[index].is_none() {
+                    on_result(&result);
+                    results[index] = Some(result);
+                    pending -= 1;
+                }
+            }
+            None => {
+                let now = Instant::now();
+                let expired: Vec<(usize, Instant)> = running
+                    .iter()
+                    .filter(|(index, start)| now.duration_since(**start) >= meta[**index].2)
+                    .map(|(index, start)| (*index, *start))
+                    .collect();
+                for (index, start) in expired {
+                    running.remove(&index);
+                    let (name, category, timeout) = &meta[index];
+                    let result = timed_out_result(name, *category, *timeout, start.elapsed());
+                    on_result(&result);
+                    results[index] = Some(result);
+                    pending -= 1;
+                    spawn_worker(&queue, &sender);
+                }
+            }
+        }
+    }
+
+    results
+        .into_iter()
+        .zip(meta)
+        .map(|(result, (name, category, _))| {
+            result.unwrap_or_else(|| {
+                CheckResult::new(
+                    name,
+                    category,
+                    CheckStatus::Fail,
+                    "Check did not complete",
+                    Duration::ZERO,
+                )
+            })
+        })
+        .collect()
+}
+
+fn spawn_worker(queue: &JobQueue, sender: &mpsc::Sender<JobEvent>) {
+    let queue = Arc::clone(queue);
+    let sender = sender.clone();
+    std::thread::spawn(move || {
+        loop {
+            let Some((index, run)) = queue.lock().ok().and_then(|mut queue| queue.pop_front())
+            else {
+                break;
+            };
+            let start = Instant::now();
+            if sender.send(JobEvent::Started(index, start)).is_err() {
+                break;
+            }
+            let result = run(start);
+            if sender.send(JobEvent::Finished(index, result)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn timed_out_result(
+    name: &str,
+    category: CheckCategory,
+    timeout: Duration,
+    elapsed: Duration,
+) -> CheckResult {
+    CheckResult::new(
+        name,
+        category,
+        CheckStatus::Warning,
+        format!("Timed out after {:.1}s", elapsed.as_secs_f64()),
+        elapsed,
+    )
+    .with_details(format!(
+        "The check did not finish within its {}s limit and was abandoned",
+        timeout.as_secs_f64()
+    ))
+    .with_suggestion("Re-run with a longer --timeout, or check network connectivity")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,6 +581,8 @@ mod tests {
             Some(CheckCategory::Installation)
         );
         assert_eq!(CheckCategory::parse("SHELL"), Some(CheckCategory::Shell));
+        assert_eq!(CheckCategory::parse("shims"), Some(CheckCategory::Shims));
+        assert!(!CheckCategory::defaults().contains(&CheckCategory::Shims));
         assert_eq!(CheckCategory::parse("invalid"), None);
     }
 
@@ -409,6 +661,58 @@ mod tests {
         assert_eq!(summary.determine_exit_code(), 1);
     }
 
+    fn sleeping_job(name: &str, sleep: Duration, timeout: Duration) -> CheckJob {
+        let job_name = name.to_string();
+        CheckJob {
+            name: name.to_string(),
+            category: CheckCategory::Network,
+            timeout,
+            run: Box::new(move |start| {
+                std::thread::sleep(sleep);
+                CheckResult::new(
+                    job_name,
+                    CheckCategory::Network,
+                    CheckStatus::Pass,
+                    "OK",
+                    start.elapsed(),
+                )
+            }),
+        }
+    }
+
+    #[test]
+    fn test_run_jobs_times_out_hanging_checks() {
+        let timeout = Duration::from_millis(200);
+        let jobs = vec![
+            sleeping_job("hang", Duration::from_secs(30), timeout),
+            sleeping_job("slow", Duration::from_millis(100), timeout),
+            sleeping_job("hang again", Duration::from_secs(30), timeout),
+            sleeping_job("fast", Duration::ZERO, timeout),
+        ];
+
+        let start = Instant::now();
+        let mut settled = 0;
+        // Two workers: the hanging jobs must not starve the other two
+        let results = run_jobs(jobs, 2, |_| settled += 1);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(settled, 4);
+
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["hang", "slow", "hang again", "fast"]);
+        let statuses: Vec<CheckStatus> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                CheckStatus::Warning,
+                CheckStatus::Pass,
+                CheckStatus::Warning,
+                CheckStatus::Pass
+            ]
+        );
+        assert!(results[0].message.starts_with("Timed out after 0."));
+        assert!(results[0].duration >= timeout);
+    }
+
     // Note: DiagnosticEngine tests are now integration tests since it requires
     // a real KopiConfig and initializes all checks internally
 }
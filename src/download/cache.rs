@@ -0,0 +1,375 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Content-addressed cache of downloaded JDK archives.
+//!
+//! Each archive lives in `<root>/<algorithm>-<checksum>/<file name>`, so only
+//! packages with a published checksum are cached and a hit can be verified
+//! before use. Entries are evicted least recently used first once the cache
+//! grows past its size cap.
+
+use crate::config::KopiConfig;
+use crate::error::Result;
+use crate::models::metadata::JdkMetadata;
+use crate::models::package::ChecksumType;
+use crate::security::verify_checksum;
+use log::{debug, warn};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::DownloadResult;
+
+/// Prefix of the directories an archive is staged in before it is published
+const STAGING_PREFIX: &str = ".staging-";
+
+/// One cached archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedArchive {
+    /// The `<algorithm>-<checksum>` directory holding the archive
+    pub path: PathBuf,
+    pub size: u64,
+    /// When the archive was last stored or served
+    pub last_used: SystemTime,
+}
+
+pub struct DownloadCache {
+    root: PathBuf,
+    /// Size cap in bytes; `None` keeps everything
+    max_bytes: Option<u64>,
+}
+
+impl DownloadCache {
+    pub fn new(root: PathBuf, max_bytes: Option<u64>) -> Self {
+        Self { root, max_bytes }
+    }
+
+    /// The cache configured under `[download]`, or `None` when
+    /// `download.cache_max_size_mb` is 0
+    pub fn from_config(config: &KopiConfig) -> Option<Self> {
+        match config.download.cache_max_size_mb {
+            0 => None,
+            mb => Some(Self::new(
+                config.download_cache_dir(),
+                Some(mb * 1024 * 1024),
+            )),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// A private copy of the cached archive for `package`, if one is cached
+    /// and still matches its checksum. Corrupt entries are removed.
+    pub fn lookup(&self, package: &JdkMetadata) -> Option<DownloadResult> {
+        let entry = self.entry_dir(package)?;
+        let archive = single_file(&entry)?;
+        let (checksum, checksum_type) = package.checksum.as_deref().zip(package.checksum_type)?;
+        if let Err(e) = verify_checksum(&archive, checksum, checksum_type) {
+            warn!(
+                "Discarding corrupt cached archive {}: {e}",
+                archive.display()
+            );
+            let _ = fs::remove_dir_all(&entry);
+            return None;
+        }
+
+        match self.checkout(&archive) {
+            Ok(result) => {
+                touch(&archive);
+                debug!("Reusing cached archive {}", archive.display());
+                Some(result)
+            }
+            Err(e) => {
+                debug!("Failed to reuse cached archive {}: {e}", archive.display());
+                None
+            }
+        }
+    }
+
+    /// Path of the cached archive for `package` relative to the cache root,
+    /// e.g. `sha256-<checksum>/<file name>`, without verifying it
+    pub fn cached_archive(&self, package: &JdkMetadata) -> Option<PathBuf> {
+        let entry = self.entry_dir(package)?;
+        let archive = single_file(&entry)?;
+        archive.strip_prefix(&self.root).ok().map(Path::to_path_buf)
+    }
+
+    /// Keep a verified download of `package` for later installs, then evict
+    /// old entries down to the size cap. Packages without a checksum are
+    /// not cached.
+    pub fn store(&self, package: &JdkMetadata, archive: &Path) -> Result<()> {
+        let Some(entry) = self.entry_dir(package) else {
+            return Ok(());
+        };
+        if entry.exists() {
+            return Ok(());
+        }
+        let Some(file_name) = archive.file_name() else {
+            return Ok(());
+        };
+
+        fs::create_dir_all(&self.root)?;
+        let staging = tempfile::Builder::new()
+            .prefix(STAGING_PREFIX)
+            .tempdir_in(&self.root)?;
+        link_or_copy(archive, &staging.path().join(file_name))?;
+        // Another process may have published the same archive meanwhile; the
+        // staging directory is removed when dropped either way
+        if fs::rename(staging.path(), &entry).is_err() && !entry.exists() {
+            return Err(std::io::Error::other(format!(
+                "Failed to publish cached archive {}",
+                entry.display()
+            ))
+            .into());
+        }
+        debug!("Cached archive in {}", entry.display());
+
+        if let Some(max_bytes) = self.max_bytes {
+            self.prune(&self.over_budget(max_bytes)?)?;
+        }
+        Ok(())
+    }
+
+    /// Every cached archive, least recently used first
+    pub fn entries(&self) -> Result<Vec<CachedArchive>> {
+        let mut entries = Vec::new();
+        let dir = match fs::read_dir(&self.root) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(entries),
+            Err(e) => return Err(e.into()),
+        };
+        for dir_entry in dir {
+            let path = dir_entry?.path();
+            let is_staging = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(STAGING_PREFIX));
+            if is_staging || !path.is_dir() {
+                continue;
+            }
+            let Some(metadata) = single_file(&path).and_then(|file| fs::metadata(file).ok()) else {
+                continue;
+            };
+            entries.push(CachedArchive {
+                path,
+                size: metadata.len(),
+                last_used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+        entries.sort_by_key(|entry| entry.last_used);
+        Ok(entries)
+    }
+
+    /// Staging directories left behind by a `store` that never finished
+    pub fn staging_dirs(&self) -> Vec<PathBuf> {
+        let Ok(dir) = fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+        dir.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(STAGING_PREFIX))
+            })
+            .collect()
+    }
+
+    /// The least recently used entries that must go for the cache to fit
+    /// in `max_bytes`
+    pub fn over_budget(&self, max_bytes: u64) -> Result<Vec<CachedArchive>> {
+        let entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|entry| entry.size).sum();
+        Ok(entries
+            .into_iter()
+            .take_while(|entry| {
+                let over = total > max_bytes;
+                total = total.saturating_sub(entry.size);
+                over
+            })
+            .collect())
+    }
+
+    /// Remove `entries` from the cache
+    pub fn prune(&self, entries: &[CachedArchive]) -> Result<()> {
+        for entry in entries {
+            debug!("Evicting cached archive {}", entry.path.display());
+            fs::remove_dir_all(&entry.path)?;
+        }
+        Ok(())
+    }
+
+    /// `<root>/<algorithm>-<checksum>` for packages with a usable checksum
+    fn entry_dir(&self, package: &JdkMetadata) -> Option<PathBuf> {
+        let checksum = package.checksum.as_deref()?;
+        let algorithm = match package.checksum_type? {
+            ChecksumType::Sha1 => "sha1",
+            ChecksumType::Sha256 => "sha256",
+            ChecksumType::Sha512 => "sha512",
+            ChecksumType::Md5 => "md5",
+        };
+        // The checksum comes from remote metadata; never let it name a path
+        if checksum.is_empty() || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(
+            self.root
+                .join(format!("{algorithm}-{}", checksum.to_ascii_lowercase())),
+        )
+    }
+
+    /// Hard-link or copy `archive` into a temporary directory so installs
+    /// never extract from a file the cache may evict
+    fn checkout(&self, archive: &Path) -> Result<DownloadResult> {
+        let temp_dir = tempfile::tempdir()?;
+        let file_name = archive.file_name().unwrap_or_default();
+        let path = temp_dir.path().join(file_name);
+        link_or_copy(archive, &path)?;
+        Ok(DownloadResult::new(path, temp_dir))
+    }
+}
+
+/// The only file in `dir`, i.e. the archive of a cache entry
+fn single_file(dir: &Path) -> Option<PathBuf> {
+    let mut files = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file());
+    let file = files.next()?;
+    files.next().is_none().then_some(file)
+}
+
+fn link_or_copy(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::hard_link(from, to).is_err() {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+/// Mark `archive` as just used so eviction keeps it longest
+fn touch(archive: &Path) {
+    if let Err(e) = File::options()
+        .write(true)
+        .open(archive)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        debug!("Failed to update last use of {}: {e}", archive.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::package::{ArchiveType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::security::calculate_checksum;
+    use crate::version::Version;
+    use std::str::FromStr;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn package(archive: &Path) -> JdkMetadata {
+        let version = Version::from_str("21.0.5").unwrap();
+        JdkMetadata {
+            id: "temurin-21.0.5".to_string(),
+            distribution: "temurin".to_string(),
+            version: version.clone(),
+            distribution_version: version,
+            architecture: Architecture::X64,
+            operating_system: OperatingSystem::Linux,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: Some("https://example.com/jdk.tar.gz".to_string()),
+            checksum: Some(calculate_checksum(archive, ChecksumType::Sha256).unwrap()),
+            checksum_type: Some(ChecksumType::Sha256),
+            size: 0,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }
+    }
+
+    fn archive(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_store_and_lookup_verify_checksum() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DownloadCache::new(temp_dir.path().join("downloads"), None);
+        let download = archive(temp_dir.path(), "jdk.tar.gz", b"archive bytes");
+        let package = package(&download);
+
+        assert!(cache.lookup(&package).is_none());
+        cache.store(&package, &download).unwrap();
+
+        let hit = cache.lookup(&package).unwrap();
+        assert_eq!(hit.path().file_name().unwrap(), "jdk.tar.gz");
+        assert_eq!(fs::read(hit.path()).unwrap(), b"archive bytes");
+
+        // A corrupt entry is discarded instead of being served
+        let entries = cache.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        let cached = entries[0].path.join("jdk.tar.gz");
+        fs::remove_file(&cached).unwrap();
+        fs::write(&cached, b"tampered").unwrap();
+        assert!(cache.lookup(&package).is_none());
+        assert!(cache.entries().unwrap().is_empty());
+
+        // Without a checksum nothing is cached
+        let mut unchecked = package.clone();
+        unchecked.checksum = None;
+        cache.store(&unchecked, &download).unwrap();
+        assert!(cache.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_store_evicts_least_recently_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DownloadCache::new(temp_dir.path().join("downloads"), Some(20));
+        let first = archive(temp_dir.path(), "a.tar.gz", &[1; 10]);
+        let second = archive(temp_dir.path(), "b.tar.gz", &[2; 10]);
+        let third = archive(temp_dir.path(), "c.tar.gz", &[3; 10]);
+
+        cache.store(&package(&first), &first).unwrap();
+        cache.store(&package(&second), &second).unwrap();
+        // Make the first archive the most recently used
+        let entries = cache.entries().unwrap();
+        let older = SystemTime::now() - Duration::from_secs(60);
+        for entry in &entries {
+            let file = single_file(&entry.path).unwrap();
+            File::options()
+                .write(true)
+                .open(file)
+                .unwrap()
+                .set_modified(older)
+                .unwrap();
+        }
+        assert!(cache.lookup(&package(&first)).is_some());
+
+        cache.store(&package(&third), &third).unwrap();
+        assert!(cache.lookup(&package(&first)).is_some());
+        assert!(cache.lookup(&package(&second)).is_none());
+        assert!(cache.lookup(&package(&third)).is_some());
+        assert_eq!(cache.over_budget(10).unwrap().len(), 1);
+    }
+}
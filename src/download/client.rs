@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::error::Result;
+use crate::security::tls;
 use crate::user_agent;
 use attohttpc::{Response, Session};
 use std::io::{self, Read};
@@ -59,6 +60,7 @@ impl HttpClient for AttohttpcClient {
         // Create a new session for each request
         let mut session = Session::new();
         session.proxy_settings(attohttpc::ProxySettings::from_env());
+        tls::configure_session(&mut session);
 
         // Build request with method chaining to avoid lifetime issues
         let mut request_builder = session
@@ -14,7 +14,11 @@
 
 use crate::download::client::{AttohttpcClient, HttpClient, HttpResponse};
 use crate::download::options::DownloadOptions;
+#[cfg(feature = "segmented-download")]
+use crate::download::segmented;
+use crate::download::throttle::RateLimiter;
 use crate::error::{KopiError, Result};
+use crate::locking::CancellationToken;
 use crate::platform;
 use crate::security::verify_checksum;
 use std::fs::{self, File};
@@ -35,6 +39,8 @@ pub trait ProgressReporter: Send + Sync {
 pub struct HttpFileDownloader {
     pub(crate) http_client: Box<dyn HttpClient>,
     progress_reporter: Option<Box<dyn ProgressReporter>>,
+    segments: usize,
+    cancellation: CancellationToken,
 }
 
 impl Default for HttpFileDownloader {
@@ -52,6 +58,8 @@ impl HttpFileDownloader {
         Self {
             http_client,
             progress_reporter: None,
+            segments: 1,
+            cancellation: CancellationToken::new(),
         }
     }
 
@@ -60,6 +68,20 @@ impl HttpFileDownloader {
         self
     }
 
+    /// Stop with [`KopiError::Cancelled`] once `cancellation` fires. The
+    /// partial file is removed; a resumable destination keeps its bytes.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Fetch archives in up to `segments` parallel ranges when the server
+    /// supports it; 1 keeps a single connection
+    pub fn with_segments(mut self, segments: usize) -> Self {
+        self.segments = segments.max(1);
+        self
+    }
+
     pub fn download(
         &mut self,
         url: &str,
@@ -71,6 +93,14 @@ impl HttpFileDownloader {
             fs::create_dir_all(parent)?;
         }
 
+        #[cfg(feature = "segmented-download")]
+        if self.segments > 1
+            && !(options.resume && destination.exists())
+            && let Some(path) = self.download_segmented(url, destination, options)?
+        {
+            return Ok(path);
+        }
+
         // Check if we can resume from existing destination file
         let (download_path, start_byte, is_temp) = if options.resume && destination.exists() {
             // Resume from existing file
@@ -107,8 +137,13 @@ impl HttpFileDownloader {
         }
 
         // Download file
-        let downloaded_path =
-            self.download_to_file(response, &download_path, start_byte, total_size)?;
+        let downloaded_path = self.download_to_file(
+            response,
+            &download_path,
+            start_byte,
+            total_size,
+            options.max_rate,
+        )?;
 
         // Verify checksum if provided
         if let Some(expected_checksum) = &options.checksum
@@ -130,6 +165,62 @@ impl HttpFileDownloader {
         Ok(destination.to_path_buf())
     }
 
+    /// Download `url` in parallel ranges, or return `None` when the server
+    /// does not support ranges or the archive is too small to split
+    #[cfg(feature = "segmented-download")]
+    fn download_segmented(
+        &mut self,
+        url: &str,
+        destination: &Path,
+        options: &DownloadOptions,
+    ) -> Result<Option<PathBuf>> {
+        let Some(total) = segmented::probe_size(self.http_client.as_ref(), url)? else {
+            log::debug!("{url} does not support range requests; using one connection");
+            return Ok(None);
+        };
+        if total > options.max_size {
+            return Err(KopiError::ValidationError(format!(
+                "Download size {total} exceeds maximum allowed size {}",
+                options.max_size
+            )));
+        }
+        let ranges = segmented::split(total, self.segments);
+        if ranges.len() < 2 {
+            return Ok(None);
+        }
+        log::debug!("Downloading {url} in {} segments", ranges.len());
+
+        let temp_file =
+            NamedTempFile::new_in(destination.parent().unwrap_or_else(|| Path::new(".")))?;
+        let temp_path = temp_file.into_temp_path();
+        if let Some(reporter) = &mut self.progress_reporter {
+            reporter.on_start(total);
+        }
+        segmented::fetch(
+            self.http_client.as_ref(),
+            url,
+            &temp_path,
+            &ranges,
+            options.max_rate,
+            &self.cancellation,
+            self.progress_reporter.as_mut(),
+        )?;
+
+        if let Some(expected_checksum) = &options.checksum
+            && let Some(checksum_type) = options.checksum_type
+        {
+            verify_checksum(&temp_path, expected_checksum, checksum_type)?;
+        }
+        platform::file_ops::atomic_rename(&temp_path, destination)?;
+        // The file now lives at `destination`; nothing is left to clean up
+        let _ = temp_path.keep();
+
+        if let Some(reporter) = &mut self.progress_reporter {
+            reporter.on_complete();
+        }
+        Ok(Some(destination.to_path_buf()))
+    }
+
     fn validate_response(&self, response: &dyn HttpResponse, max_size: u64) -> Result<()> {
         let status = response.status();
 
@@ -177,6 +268,7 @@ impl HttpFileDownloader {
         path: &Path,
         start_byte: u64,
         _total_size: u64,
+        max_rate: Option<u64>,
     ) -> Result<PathBuf> {
         let file = if start_byte > 0 {
             fs::OpenOptions::new().append(true).open(path)?
@@ -187,13 +279,22 @@ impl HttpFileDownloader {
         let mut writer = BufWriter::new(file);
         let mut downloaded = start_byte;
         let mut buffer = vec![0; DOWNLOAD_CHUNK_SIZE];
+        let mut limiter = max_rate.map(RateLimiter::new);
 
         loop {
+            if self.cancellation.is_cancelled() {
+                return Err(KopiError::Cancelled(format!(
+                    "download stopped after {downloaded} bytes"
+                )));
+            }
             match response.read(&mut buffer) {
                 Ok(0) => break, // EOF
                 Ok(n) => {
                     writer.write_all(&buffer[..n])?;
                     downloaded += n as u64;
+                    if let Some(limiter) = &mut limiter {
+                        limiter.throttle(n);
+                    }
 
                     if let Some(reporter) = &mut self.progress_reporter {
                         reporter.on_progress(downloaded);
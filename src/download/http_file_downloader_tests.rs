@@ -136,6 +136,32 @@ mod tests {
         assert_eq!(content, test_content);
     }
 
+    #[test]
+    fn test_download_stops_when_cancelled() {
+        let test_content = b"Hello, JDK!";
+        let mock_client = MockHttpClient::new(vec![MockResponse {
+            status: 200,
+            headers: vec![("Content-Length".to_string(), test_content.len().to_string())],
+            body: test_content.to_vec(),
+        }]);
+        let cancellation = crate::locking::CancellationToken::new();
+        cancellation.cancel();
+
+        let mut downloader =
+            HttpFileDownloader::with_client(Box::new(mock_client)).with_cancellation(cancellation);
+        let temp_dir = tempdir().unwrap();
+        let dest_path = temp_dir.path().join("test.jar");
+
+        let result = downloader.download(
+            "http://example.com/jdk.tar.gz",
+            &dest_path,
+            &DownloadOptions::default(),
+        );
+
+        assert!(matches!(result, Err(KopiError::Cancelled(_))));
+        assert!(!dest_path.exists());
+    }
+
     #[test]
     fn test_download_with_checksum_validation() {
         let test_content = b"Hello, JDK!";
@@ -166,6 +192,33 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_download_with_rate_limit() {
+        let test_content = vec![7u8; 4096];
+        let mock_client = MockHttpClient::new(vec![MockResponse {
+            status: 200,
+            headers: vec![("Content-Length".to_string(), test_content.len().to_string())],
+            body: test_content.clone(),
+        }]);
+
+        let mut downloader = HttpFileDownloader::with_client(Box::new(mock_client));
+        let temp_dir = tempdir().unwrap();
+        let dest_path = temp_dir.path().join("test.jar");
+
+        let options = DownloadOptions {
+            max_rate: Some(40_960),
+            ..Default::default()
+        };
+
+        let started = std::time::Instant::now();
+        let result = downloader.download("http://example.com/jdk.tar.gz", &dest_path, &options);
+
+        assert!(result.is_ok());
+        assert_eq!(std::fs::read(&dest_path).unwrap(), test_content);
+        // 4 KiB at 40 KiB/s takes about 100ms
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+
     #[test]
     fn test_download_with_invalid_checksum() {
         let test_content = b"Hello, JDK!";
@@ -189,10 +242,8 @@ mod tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            KopiError::ValidationError(msg) => {
-                assert!(msg.contains("Checksum verification failed"))
-            }
-            _ => panic!("Expected ValidationError"),
+            KopiError::ChecksumMismatch(msg) => assert!(msg.contains("invalid_checksum")),
+            other => panic!("Expected ChecksumMismatch, got {other:?}"),
         }
     }
 
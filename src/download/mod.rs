@@ -12,24 +12,68 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cache;
 mod client;
 mod http_file_downloader;
 mod options;
 mod progress;
+#[cfg(feature = "segmented-download")]
+mod segmented;
+mod throttle;
 
 // Re-export public types and traits
+pub use cache::{CachedArchive, DownloadCache};
 pub use client::{HttpClient, HttpResponse};
 pub use http_file_downloader::{HttpFileDownloader, ProgressReporter};
 pub use options::{DEFAULT_TIMEOUT, DownloadOptions, DownloadResult, MAX_DOWNLOAD_SIZE};
 pub use progress::{DownloadProgressAdapter, IndicatifProgressReporter};
+pub use throttle::{configure, parse_rate};
 
 use crate::error::Result;
 use std::time::Duration;
 
+/// [`download_jdk`], served from `cache` when it holds a verified archive
+/// with the package's checksum. Fresh downloads are added to `cache`.
+pub fn download_jdk_cached(
+    cache: Option<&DownloadCache>,
+    package: &crate::models::metadata::JdkMetadata,
+    no_progress: bool,
+    timeout_secs: Option<u64>,
+    max_rate: Option<u64>,
+    parent_progress: Option<Box<dyn crate::indicator::ProgressIndicator>>,
+) -> Result<DownloadResult> {
+    if let Some(hit) = cache.and_then(|cache| cache.lookup(package)) {
+        log::info!(
+            "Using cached archive for {}@{}",
+            package.distribution,
+            package.version
+        );
+        return Ok(hit);
+    }
+
+    let result = download_jdk(
+        package,
+        no_progress,
+        timeout_secs,
+        max_rate,
+        parent_progress,
+    )?;
+    if let Some(cache) = cache
+        && let Err(e) = cache.store(package, result.path())
+    {
+        log::warn!(
+            "Failed to add the download to {}: {e}",
+            cache.root().display()
+        );
+    }
+    Ok(result)
+}
+
 pub fn download_jdk(
     package: &crate::models::metadata::JdkMetadata,
     no_progress: bool,
     timeout_secs: Option<u64>,
+    max_rate: Option<u64>,
     parent_progress: Option<Box<dyn crate::indicator::ProgressIndicator>>,
 ) -> Result<DownloadResult> {
     // Security validation
@@ -41,7 +85,9 @@ pub fn download_jdk(
     crate::security::verify_https_security(download_url)?;
 
     // Create HTTP file downloader
-    let mut downloader = HttpFileDownloader::new();
+    let mut downloader = HttpFileDownloader::new()
+        .with_segments(throttle::configured_segments())
+        .with_cancellation(crate::locking::global_token());
 
     // Set timeout if provided
     if let Some(timeout) = timeout_secs {
@@ -65,6 +111,10 @@ pub fn download_jdk(
             .map(Duration::from_secs)
             .unwrap_or(DEFAULT_TIMEOUT),
         max_size: MAX_DOWNLOAD_SIZE,
+        // An explicit rate of 0 lifts the configured limit
+        max_rate: max_rate
+            .or_else(throttle::configured_rate)
+            .filter(|rate| *rate > 0),
     };
 
     // Determine download path
@@ -72,7 +122,8 @@ pub fn download_jdk(
     let file_name = download_url.split('/').next_back().unwrap_or("jdk.tar.gz");
     let download_path = temp_dir.path().join(file_name);
 
-    // Download the file
+    // Download the file once a slot under download.max_concurrent is free
+    let _slot = throttle::acquire_slot();
     let result_path = downloader.download(download_url, &download_path, &options)?;
 
     Ok(DownloadResult::new(result_path, temp_dir))
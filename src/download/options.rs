@@ -31,6 +31,9 @@ pub struct DownloadOptions {
     pub timeout: Duration,
 
     pub max_size: u64,
+
+    /// Bandwidth cap in bytes per second
+    pub max_rate: Option<u64>,
 }
 
 impl Default for DownloadOptions {
@@ -41,6 +44,7 @@ impl Default for DownloadOptions {
             resume: true,
             timeout: DEFAULT_TIMEOUT,
             max_size: MAX_DOWNLOAD_SIZE,
+            max_rate: None,
         }
     }
 }
@@ -76,5 +80,6 @@ mod tests {
         assert!(options.resume);
         assert_eq!(options.timeout, DEFAULT_TIMEOUT);
         assert_eq!(options.max_size, MAX_DOWNLOAD_SIZE);
+        assert_eq!(options.max_rate, None);
     }
 }
@@ -0,0 +1,383 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parallel ranged downloads for `download.backend = "segmented"`.
+//!
+//! On high-latency links one connection rarely fills the pipe. The archive
+//! size is probed with a one-byte range request, the archive is split into
+//! byte ranges fetched on their own connections, and each range is written
+//! at its offset in a single file. The caller verifies the checksum of the
+//! reassembled file as for any other download.
+
+use crate::download::client::HttpClient;
+use crate::download::http_file_downloader::{ProgressReporter, parse_content_range};
+use crate::download::throttle::RateLimiter;
+use crate::error::{KopiError, Result};
+use crate::locking::CancellationToken;
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Smallest range worth its own connection
+pub(crate) const MIN_SEGMENT_SIZE: u64 = 4 * 1024 * 1024;
+
+const SEGMENT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Total size of the resource at `url`, or `None` when the server does not
+/// answer range requests
+pub(crate) fn probe_size(client: &dyn HttpClient, url: &str) -> Result<Option<u64>> {
+    let response = client.get(url, vec![("Range".to_string(), "bytes=0-0".to_string())])?;
+    if response.status() != 206 {
+        return Ok(None);
+    }
+    Ok(response
+        .header("Content-Range")
+        .and_then(parse_content_range))
+}
+
+/// Split `total` bytes into at most `segments` inclusive ranges of at least
+/// [`MIN_SEGMENT_SIZE`] bytes each
+pub(crate) fn split(total: u64, segments: usize) -> Vec<(u64, u64)> {
+    let count = (total / MIN_SEGMENT_SIZE).clamp(1, segments.max(1) as u64);
+    let size = total.div_ceil(count);
+    (0..count)
+        .map(|index| index * size)
+        .filter(|start| *start < total)
+        .map(|start| (start, (start + size).min(total) - 1))
+        .collect()
+}
+
+/// Fetch `ranges` of `url` in parallel into `path`. The ranges are
+/// contiguous from byte 0, as [`split`] returns them, and `max_rate` is
+/// shared evenly between the connections.
+pub(crate) fn fetch(
+    client: &dyn HttpClient,
+    url: &str,
+    path: &Path,
+    ranges: &[(u64, u64)],
+    max_rate: Option<u64>,
+    cancellation: &CancellationToken,
+    progress: Option<&mut Box<dyn ProgressReporter>>,
+) -> Result<()> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?
+        .set_len(ranges.last().map_or(0, |(_, end)| end + 1))?;
+
+    let downloaded = AtomicU64::new(0);
+    let progress = Mutex::new(progress);
+    let segment_rate = max_rate.map(|rate| (rate / ranges.len() as u64).max(1));
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = ranges
+            .iter()
+            .map(|&range| {
+                let downloaded = &downloaded;
+                let progress = &progress;
+                scope.spawn(move || {
+                    fetch_range(
+                        client,
+                        url,
+                        path,
+                        range,
+                        segment_rate,
+                        cancellation,
+                        |bytes| {
+                            let so_far = downloaded.fetch_add(bytes, Ordering::Relaxed) + bytes;
+                            if let Ok(mut guard) = progress.lock()
+                                && let Some(reporter) = guard.as_mut()
+                            {
+                                reporter.on_progress(so_far);
+                            }
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker.join().unwrap_or_else(|_| {
+                    Err(KopiError::ThreadPanic(
+                        "Download segment worker panicked".to_string(),
+                    ))
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<()>>()
+    })
+}
+
+fn fetch_range(
+    client: &dyn HttpClient,
+    url: &str,
+    path: &Path,
+    (start, end): (u64, u64),
+    max_rate: Option<u64>,
+    cancellation: &CancellationToken,
+    on_progress: impl Fn(u64),
+) -> Result<()> {
+    let mut response = client.get(
+        url,
+        vec![("Range".to_string(), format!("bytes={start}-{end}"))],
+    )?;
+    let served = response
+        .header("Content-Range")
+        .and_then(|range| range.strip_prefix("bytes "))
+        .and_then(|range| range.split('/').next())
+        .map(str::to_string);
+    if response.status() != 206 || served.as_deref() != Some(&format!("{start}-{end}")) {
+        return Err(KopiError::NetworkError(format!(
+            "Server did not return bytes {start}-{end} of {url} (status {})",
+            response.status()
+        )));
+    }
+
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let expected = end - start + 1;
+    let mut received = 0u64;
+    let mut buffer = vec![0; SEGMENT_CHUNK_SIZE];
+    let mut limiter = max_rate.map(RateLimiter::new);
+    while received < expected {
+        if cancellation.is_cancelled() {
+            return Err(KopiError::Cancelled(format!(
+                "download of range {start}-{end} stopped after {received} bytes"
+            )));
+        }
+        let n = match response.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n.min((expected - received) as usize),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        file.write_all(&buffer[..n])?;
+        received += n as u64;
+        if let Some(limiter) = &mut limiter {
+            limiter.throttle(n);
+        }
+        on_progress(n as u64);
+    }
+
+    if received != expected {
+        return Err(KopiError::NetworkError(format!(
+            "Connection closed after {received} of {expected} bytes of range {start}-{end}"
+        )));
+    }
+    file.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download::HttpResponse;
+    use std::io::Cursor;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Serves `body`, honouring `Range` headers unless `ranges` is false
+    struct RangeClient {
+        body: Vec<u8>,
+        ranges: bool,
+        requests: Mutex<Vec<Option<String>>>,
+    }
+
+    struct RangeResponse {
+        status: u16,
+        content_range: Option<String>,
+        body: Cursor<Vec<u8>>,
+    }
+
+    impl HttpClient for RangeClient {
+        fn get(&self, _url: &str, headers: Vec<(String, String)>) -> Result<Box<dyn HttpResponse>> {
+            let range = headers
+                .into_iter()
+                .find(|(name, _)| name == "Range")
+                .map(|(_, value)| value);
+            self.requests.lock().unwrap().push(range.clone());
+
+            let total = self.body.len() as u64;
+            let bounds = range.filter(|_| self.ranges).and_then(|range| {
+                let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+                Some((start.parse::<u64>().ok()?, end.parse::<u64>().ok()?))
+            });
+            Ok(Box::new(match bounds {
+                Some((start, end)) => RangeResponse {
+                    status: 206,
+                    content_range: Some(format!("bytes {start}-{end}/{total}")),
+                    body: Cursor::new(self.body[start as usize..=end as usize].to_vec()),
+                },
+                None => RangeResponse {
+                    status: 200,
+                    content_range: None,
+                    body: Cursor::new(self.body.clone()),
+                },
+            }))
+        }
+
+        fn set_timeout(&mut self, _timeout: Duration) {}
+    }
+
+    impl Read for RangeResponse {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.body.read(buf)
+        }
+    }
+
+    impl HttpResponse for RangeResponse {
+        fn status(&self) -> u16 {
+            self.status
+        }
+
+        fn header(&self, name: &str) -> Option<&str> {
+            name.eq_ignore_ascii_case("Content-Range")
+                .then_some(self.content_range.as_deref())
+                .flatten()
+        }
+
+        fn final_url(&self) -> Option<&str> {
+            None
+        }
+    }
+
+    fn client(size: usize, ranges: bool) -> RangeClient {
+        RangeClient {
+            body: (0..size).map(|i| (i % 251) as u8).collect(),
+            ranges,
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn test_split_into_ranges() {
+        assert_eq!(split(100, 4), vec![(0, 99)]);
+        let total = 3 * MIN_SEGMENT_SIZE + 10;
+        let ranges = split(total, 4);
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0].0, 0);
+        assert_eq!(ranges[2].1, total - 1);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].1 + 1, pair[1].0);
+        }
+        assert_eq!(split(64 * MIN_SEGMENT_SIZE, 8).len(), 8);
+    }
+
+    #[test]
+    fn test_fetch_reassembles_ranges() {
+        let client = client(2 * MIN_SEGMENT_SIZE as usize + 123, true);
+        let total = probe_size(&client, "https://example.com/jdk.tar.gz")
+            .unwrap()
+            .unwrap();
+        assert_eq!(total, client.body.len() as u64);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("jdk.tar.gz");
+        let ranges = split(total, 4);
+        assert_eq!(ranges.len(), 2);
+        fetch(
+            &client,
+            "https://example.com/jdk.tar.gz",
+            &path,
+            &ranges,
+            None,
+            &CancellationToken::new(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), client.body);
+        assert_eq!(client.requests.lock().unwrap().len(), 3);
+
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        let result = fetch(
+            &client,
+            "https://example.com/jdk.tar.gz",
+            &path,
+            &ranges,
+            None,
+            &cancelled,
+            None,
+        );
+        assert!(matches!(result, Err(KopiError::Cancelled(_))));
+    }
+
+    #[test]
+    fn test_downloader_verifies_reassembled_archive() {
+        use crate::download::{DownloadOptions, HttpFileDownloader};
+        use crate::models::package::ChecksumType;
+        use crate::security::calculate_checksum;
+
+        let size = 3 * MIN_SEGMENT_SIZE as usize;
+        let body = client(size, true).body;
+        let temp_dir = TempDir::new().unwrap();
+        let reference = temp_dir.path().join("reference");
+        std::fs::write(&reference, &body).unwrap();
+        let checksum = calculate_checksum(&reference, ChecksumType::Sha256).unwrap();
+
+        let destination = temp_dir.path().join("out/jdk.tar.gz");
+        let options = DownloadOptions {
+            checksum: Some(checksum),
+            checksum_type: Some(ChecksumType::Sha256),
+            ..DownloadOptions::default()
+        };
+        HttpFileDownloader::with_client(Box::new(client(size, true)))
+            .with_segments(4)
+            .download("https://example.com/jdk.tar.gz", &destination, &options)
+            .unwrap();
+        assert_eq!(std::fs::read(&destination).unwrap(), body);
+
+        let options = DownloadOptions {
+            checksum: Some("0".repeat(64)),
+            ..options
+        };
+        let mismatch = HttpFileDownloader::with_client(Box::new(client(size, true)))
+            .with_segments(4)
+            .download(
+                "https://example.com/jdk.tar.gz",
+                &temp_dir.path().join("out/bad.tar.gz"),
+                &options,
+            );
+        assert!(mismatch.is_err());
+        assert!(!temp_dir.path().join("out/bad.tar.gz").exists());
+    }
+
+    #[test]
+    fn test_server_without_ranges() {
+        let client = client(1024, false);
+        assert_eq!(
+            probe_size(&client, "https://example.com/jdk.zip").unwrap(),
+            None
+        );
+
+        let temp_dir = TempDir::new().unwrap();
+        let result = fetch(
+            &client,
+            "https://example.com/jdk.zip",
+            &temp_dir.path().join("jdk.zip"),
+            &[(0, 511), (512, 1023)],
+            None,
+            &CancellationToken::new(),
+            None,
+        );
+        assert!(matches!(result, Err(KopiError::NetworkError(_))));
+    }
+}
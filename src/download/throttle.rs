@@ -0,0 +1,241 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bandwidth, concurrency and backend settings for downloads.
+
+use crate::config::{DownloadBackend, DownloadConfig};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+static CONFIGURED: OnceLock<DownloadLimits> = OnceLock::new();
+
+struct DownloadLimits {
+    max_rate: u64,
+    slots: DownloadSlots,
+    segments: usize,
+}
+
+/// Apply the `[download]` settings to every download started afterwards.
+///
+/// Only the first call takes effect.
+pub fn configure(config: &DownloadConfig) {
+    let segments = match config.backend {
+        DownloadBackend::Standard => 1,
+        DownloadBackend::Segmented if cfg!(feature = "segmented-download") => config.segments,
+        DownloadBackend::Segmented => {
+            log::warn!(
+                "download.backend = \"segmented\" needs a kopi built with the \
+                 segmented-download feature; downloading over one connection"
+            );
+            1
+        }
+    };
+    let _ = CONFIGURED.set(DownloadLimits {
+        max_rate: config.max_rate,
+        slots: DownloadSlots::new(config.max_concurrent),
+        segments,
+    });
+}
+
+/// Ranges each archive is split into; 1 unless the segmented backend is on
+pub fn configured_segments() -> usize {
+    CONFIGURED.get().map_or(1, |limits| limits.segments)
+}
+
+/// Rate from the `download.max_rate` setting, `None` when unlimited
+pub fn configured_rate() -> Option<u64> {
+    CONFIGURED
+        .get()
+        .map(|limits| limits.max_rate)
+        .filter(|rate| *rate > 0)
+}
+
+/// Wait for a download slot under `download.max_concurrent`; the slot is
+/// released when the guard drops
+pub fn acquire_slot() -> Option<SlotGuard<'static>> {
+    CONFIGURED.get().map(|limits| limits.slots.acquire())
+}
+
+/// Parse a rate such as `500K` or `2M` into bytes per second.
+///
+/// Suffixes are binary (K = 1024) and case-insensitive; `0` means unlimited.
+pub fn parse_rate(value: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid rate '{value}'; use bytes or a K, M, or G suffix");
+    let trimmed = value.trim();
+    let digits_end = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, suffix) = trimmed.split_at(digits_end);
+    let multiplier: u64 = match suffix.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1 << 10,
+        "m" | "mb" => 1 << 20,
+        "g" | "gb" => 1 << 30,
+        _ => return Err(invalid()),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(invalid)
+}
+
+/// Token bucket holding at most one second's worth of bytes
+#[derive(Debug)]
+pub struct RateLimiter {
+    bytes_per_sec: u64,
+    /// Bytes that may be sent right away; negative while in debt
+    available: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec: bytes_per_sec.max(1),
+            available: 0.0,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Sleep until `bytes` more bytes fit within the rate
+    pub fn throttle(&mut self, bytes: usize) {
+        let delay = self.delay_for(bytes, Instant::now());
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+
+    /// Take `bytes` from the bucket at `now` and return how long to wait
+    /// before they are covered
+    fn delay_for(&mut self, bytes: usize, now: Instant) -> Duration {
+        let rate = self.bytes_per_sec as f64;
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * rate).min(rate) - bytes as f64;
+        self.updated = now;
+        if self.available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.available / rate)
+        }
+    }
+}
+
+/// Counting semaphore bounding simultaneous downloads; zero means unbounded
+struct DownloadSlots {
+    max: usize,
+    in_use: Mutex<usize>,
+    released: Condvar,
+}
+
+impl DownloadSlots {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            in_use: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) -> SlotGuard<'_> {
+        let mut in_use = self.in_use.lock().unwrap_or_else(|e| e.into_inner());
+        while self.max > 0 && *in_use >= self.max {
+            log::debug!("Waiting for one of {} download slots", self.max);
+            in_use = self
+                .released
+                .wait(in_use)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *in_use += 1;
+        SlotGuard { slots: self }
+    }
+}
+
+pub struct SlotGuard<'a> {
+    slots: &'a DownloadSlots,
+}
+
+impl Drop for SlotGuard<'_> {
+    fn drop(&mut self) {
+        let mut in_use = self.slots.in_use.lock().unwrap_or_else(|e| e.into_inner());
+        *in_use = in_use.saturating_sub(1);
+        self.slots.released.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("1000").unwrap(), 1000);
+        assert_eq!(parse_rate("500K").unwrap(), 500 * 1024);
+        assert_eq!(parse_rate("2m").unwrap(), 2 * 1024 * 1024);
+        assert_eq!(parse_rate("1GB").unwrap(), 1 << 30);
+        assert_eq!(parse_rate("0").unwrap(), 0);
+        assert!(parse_rate("fast").is_err());
+        assert!(parse_rate("2T").is_err());
+        assert!(parse_rate("").is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_delays_by_debt() {
+        let mut limiter = RateLimiter::new(1000);
+        let start = limiter.updated;
+
+        // The bucket starts empty, so 500 bytes cost half a second
+        assert_eq!(limiter.delay_for(500, start), Duration::from_millis(500));
+
+        // After waiting, the next chunk is paced the same way
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limiter.delay_for(250, later), Duration::from_millis(250));
+
+        // An idle period refills at most one second's worth
+        let idle = later + Duration::from_secs(10);
+        assert_eq!(limiter.delay_for(1000, idle), Duration::ZERO);
+        assert_eq!(limiter.delay_for(100, idle), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_download_slots_bound_concurrency() {
+        let slots = Arc::new(DownloadSlots::new(2));
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..6)
+            .map(|_| {
+                let slots = Arc::clone(&slots);
+                let active = Arc::clone(&active);
+                let peak = Arc::clone(&peak);
+                thread::spawn(move || {
+                    let _slot = slots.acquire();
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(*slots.in_use.lock().unwrap(), 0);
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::{KopiError, Result, get_error_code};
+use comfy_table::{Cell, Color, Table};
+
+/// Exit code of a batch operation where some items succeeded and others failed
+pub const PARTIAL_FAILURE_EXIT_CODE: i32 = 8;
+
+/// One item a batch operation could not process
+#[derive(Debug)]
+pub struct ItemFailure {
+    /// Item label, e.g. `temurin@21.0.5+11`
+    pub item: String,
+    pub error: KopiError,
+}
+
+/// Collects the outcome of every item of a batch install or uninstall, so
+/// one failure does not stop the rest of the batch.
+#[derive(Debug)]
+pub struct BatchErrors {
+    operation: String,
+    succeeded: Vec<String>,
+    failures: Vec<ItemFailure>,
+}
+
+impl BatchErrors {
+    pub fn new(operation: &str) -> Self {
+        Self {
+            operation: operation.to_string(),
+            succeeded: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    pub fn record_success(&mut self, item: impl Into<String>) {
+        self.succeeded.push(item.into());
+    }
+
+    pub fn record_failure(&mut self, item: impl Into<String>, error: KopiError) {
+        self.failures.push(ItemFailure {
+            item: item.into(),
+            error,
+        });
+    }
+
+    /// Record the outcome of `result` for `item`
+    pub fn record<T>(&mut self, item: impl Into<String>, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.record_success(item);
+                Some(value)
+            }
+            Err(error) => {
+                self.record_failure(item, error);
+                None
+            }
+        }
+    }
+
+    pub fn succeeded(&self) -> &[String] {
+        &self.succeeded
+    }
+
+    pub fn failures(&self) -> &[ItemFailure] {
+        &self.failures
+    }
+
+    /// Table of the failed items with their error codes
+    pub fn failure_table(&self) -> Table {
+        failure_table(&self.failures)
+    }
+
+    /// `Ok` when every item succeeded. A single-item batch returns the
+    /// item's own error; larger batches return [`KopiError::BatchFailed`].
+    pub fn into_result(mut self) -> Result<()> {
+        if self.failures.is_empty() {
+            return Ok(());
+        }
+        if self.succeeded.is_empty() && self.failures.len() == 1 {
+            return Err(self.failures.remove(0).error);
+        }
+        Err(KopiError::BatchFailed {
+            operation: self.operation,
+            succeeded: self.succeeded,
+            failures: self.failures,
+        })
+    }
+}
+
+/// Table of `failures` with the item, error code and message of each
+pub fn failure_table(failures: &[ItemFailure]) -> Table {
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec![
+        Cell::new("Item"),
+        Cell::new("Code"),
+        Cell::new("Error"),
+    ]);
+    for failure in failures {
+        table.add_row(vec![
+            Cell::new(&failure.item),
+            Cell::new(get_error_code(&failure.error)).fg(Color::Red),
+            Cell::new(failure.error.to_string()),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::get_exit_code;
+
+    #[test]
+    fn test_batch_errors_into_result() {
+        let mut batch = BatchErrors::new("install");
+        assert_eq!(batch.record("temurin@21", Ok(21)), Some(21));
+        assert!(BatchErrors::new("install").into_result().is_ok());
+
+        batch.record::<()>(
+            "temurin@99",
+            Err(KopiError::VersionNotAvailable("temurin@99".to_string())),
+        );
+        assert_eq!(batch.succeeded(), ["temurin@21"]);
+        assert_eq!(batch.failures()[0].item, "temurin@99");
+        assert!(
+            batch
+                .failure_table()
+                .to_string()
+                .contains("KOPI_E_NO_MATCHING_PACKAGE")
+        );
+
+        let error = batch.into_result().unwrap_err();
+        assert_eq!(error.to_string(), "install failed for 1 of 2 items");
+        assert_eq!(get_exit_code(&error), PARTIAL_FAILURE_EXIT_CODE);
+        assert_eq!(get_error_code(&error), "KOPI_E_PARTIAL_FAILURE");
+    }
+
+    #[test]
+    fn test_batch_errors_all_failed() {
+        let mut single = BatchErrors::new("uninstall");
+        single.record_failure("zulu@17", KopiError::NotFound("zulu@17".to_string()));
+        assert!(matches!(single.into_result(), Err(KopiError::NotFound(_))));
+
+        let mut batch = BatchErrors::new("uninstall");
+        batch.record_failure("zulu@17", KopiError::NotFound("zulu@17".to_string()));
+        batch.record_failure("zulu@21", KopiError::NotFound("zulu@21".to_string()));
+        let error = batch.into_result().unwrap_err();
+        assert_eq!(get_exit_code(&error), 1);
+        assert_eq!(get_error_code(&error), "KOPI_E_BATCH_FAILED");
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::KopiError;
+
+/// Stable, machine-readable code for `error`, reported in `--json` failures.
+///
+/// Codes are part of the public interface: add new ones freely, but never
+/// rename or reuse an existing code.
+pub fn get_error_code(error: &KopiError) -> &str {
+    match error {
+        KopiError::VersionNotAvailable(_) => "KOPI_E_NO_MATCHING_PACKAGE",
+        KopiError::InvalidVersionFormat(_) => "KOPI_E_INVALID_VERSION",
+        KopiError::JdkNotInstalled { .. } => "KOPI_E_JDK_NOT_INSTALLED",
+        KopiError::Download(_) => "KOPI_E_DOWNLOAD_FAILED",
+        KopiError::Extract(_) => "KOPI_E_EXTRACT_FAILED",
+        KopiError::ChecksumMismatch(_) => "KOPI_E_CHECKSUM_MISMATCH",
+        KopiError::NoLocalVersion { .. } => "KOPI_E_NO_VERSION_CONFIGURED",
+        KopiError::ConfigFile(_) => "KOPI_E_CONFIG_FILE",
+        KopiError::InvalidConfig(_) => "KOPI_E_INVALID_CONFIG",
+        KopiError::UnsupportedShell(_) => "KOPI_E_UNSUPPORTED_SHELL",
+        KopiError::ShellDetectionError(_) => "KOPI_E_SHELL_DETECTION",
+        KopiError::ShellNotFound(_) => "KOPI_E_SHELL_NOT_FOUND",
+        KopiError::PathUpdate(_) => "KOPI_E_PATH_UPDATE",
+        KopiError::ShimCreation(_) => "KOPI_E_SHIM_CREATION",
+        KopiError::ToolNotFound { .. } => "KOPI_E_TOOL_NOT_FOUND",
+        KopiError::KopiNotFound { .. } => "KOPI_E_KOPI_NOT_FOUND",
+        KopiError::MetadataFetch(_) => "KOPI_E_METADATA_FETCH",
+        KopiError::InvalidMetadata => "KOPI_E_INVALID_METADATA",
+        KopiError::SchemaViolation { .. } => "KOPI_E_SCHEMA_VIOLATION",
+        KopiError::PermissionDenied(_) | KopiError::ElevationRequired { .. } => {
+            "KOPI_E_PERMISSION_DENIED"
+        }
+        KopiError::DirectoryNotFound(_) => "KOPI_E_DIRECTORY_NOT_FOUND",
+        KopiError::ConfigError(_) => "KOPI_E_CONFIG",
+        KopiError::SecurityError(_) => "KOPI_E_SECURITY",
+        KopiError::PolicyViolation(_) => "KOPI_E_POLICY_VIOLATION",
+        KopiError::NetworkError(_) => "KOPI_E_NETWORK",
+        KopiError::ValidationError(_) => "KOPI_E_VALIDATION",
+        KopiError::AlreadyExists(_) => "KOPI_E_ALREADY_EXISTS",
+        KopiError::DiskSpaceError(_) => "KOPI_E_DISK_SPACE",
+        KopiError::LockingAcquire { .. } => "KOPI_E_LOCK_ACQUIRE",
+        KopiError::LockingTimeout { .. } => "KOPI_E_LOCK_TIMEOUT",
+        KopiError::LockingCancelled { .. } => "KOPI_E_LOCK_CANCELLED",
+        KopiError::Cancelled(_) => "KOPI_E_CANCELLED",
+        KopiError::LockingRelease { .. } => "KOPI_E_LOCK_RELEASE",
+        KopiError::LockingScopeUnavailable { .. } => "KOPI_E_LOCK_SCOPE",
+        KopiError::SystemError(_) => "KOPI_E_SYSTEM",
+        KopiError::Io(_) | KopiError::WalkDir(_) => "KOPI_E_IO",
+        KopiError::Http(_) => "KOPI_E_HTTP",
+        KopiError::Json(_) => "KOPI_E_JSON",
+        KopiError::Nul(_) => "KOPI_E_INVALID_STRING",
+        KopiError::Zip(_) => "KOPI_E_ARCHIVE",
+        KopiError::CacheNotFound => "KOPI_E_CACHE_NOT_FOUND",
+        KopiError::NotFound(_) => "KOPI_E_NOT_FOUND",
+        KopiError::ThreadPanic(_) => "KOPI_E_INTERNAL",
+        KopiError::NotImplemented(_) => "KOPI_E_NOT_IMPLEMENTED",
+        KopiError::GenerationFailed(_) => "KOPI_E_GENERATION_FAILED",
+        KopiError::BatchFailed { succeeded, .. } if succeeded.is_empty() => "KOPI_E_BATCH_FAILED",
+        KopiError::BatchFailed { .. } => "KOPI_E_PARTIAL_FAILURE",
+        KopiError::Daemon { code, .. } => code,
+    }
+}
@@ -12,11 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::KopiError;
+use crate::error::{KopiError, get_error_code};
+use crate::i18n::{t, t_with};
 use std::fmt;
 
 pub struct ErrorContext<'a> {
     pub error: &'a KopiError,
+    /// Stable machine-readable code, e.g. `KOPI_E_NO_MATCHING_PACKAGE`
+    pub code: &'a str,
     pub suggestion: Option<String>,
     pub details: Option<String>,
 }
@@ -24,24 +27,20 @@ pub struct ErrorContext<'a> {
 impl<'a> ErrorContext<'a> {
     pub fn new(error: &'a KopiError) -> Self {
         let (suggestion, details) = match error {
-            KopiError::VersionNotAvailable(msg) => {
-                let suggestion = Some(
-                    "Run 'kopi cache search' to see available versions or 'kopi cache refresh' to \
-                     update the list."
-                        .to_string(),
-                );
-                let details = Some(format!("Version lookup failed: {msg}"));
-                (suggestion, details)
-            }
-            KopiError::InvalidVersionFormat(msg) => {
-                let suggestion = Some(
-                    "Version format should be: '<version>' or '<distribution>@<version>' (e.g., \
-                     '21' or 'corretto@17')."
-                        .to_string(),
-                );
-                let details = Some(format!("Invalid format: {msg}"));
-                (suggestion, details)
-            }
+            KopiError::VersionNotAvailable(msg) => (
+                Some(t("error.version_not_available.suggestion")),
+                Some(t_with(
+                    "error.version_not_available.details",
+                    &[("message", msg)],
+                )),
+            ),
+            KopiError::InvalidVersionFormat(msg) => (
+                Some(t("error.invalid_version_format.suggestion")),
+                Some(t_with(
+                    "error.invalid_version_format.details",
+                    &[("message", msg)],
+                )),
+            ),
             KopiError::JdkNotInstalled {
                 jdk_spec,
                 auto_install_enabled,
@@ -50,312 +49,291 @@ impl<'a> ErrorContext<'a> {
                 install_in_progress,
                 ..
             } => {
+                let spec = [("spec", jdk_spec.as_str())];
                 let suggestion = if *install_in_progress {
-                    Some(
-                        "Another process is currently installing this JDK. Please wait and try \
-                         again."
-                            .to_string(),
-                    )
+                    t("error.jdk_not_installed.in_progress")
                 } else if *user_declined {
-                    Some(format!(
-                        "Installation was declined. To install manually: kopi install {jdk_spec}"
-                    ))
+                    t_with("error.jdk_not_installed.declined", &spec)
                 } else if let Some(reason) = auto_install_failed {
-                    Some(format!(
-                        "Auto-installation failed: {reason}\n\nTo install manually: kopi install \
-                         {jdk_spec}"
-                    ))
+                    t_with(
+                        "error.jdk_not_installed.auto_install_failed",
+                        &[("reason", reason), ("spec", jdk_spec)],
+                    )
                 } else if *auto_install_enabled {
-                    Some(format!(
-                        "Run 'kopi install {jdk_spec}' to install this JDK."
-                    ))
+                    t_with("error.jdk_not_installed.install", &spec)
                 } else {
                     let enable_cmd = if cfg!(windows) {
                         "set KOPI_AUTO_INSTALL__ENABLED=true"
                     } else {
                         "export KOPI_AUTO_INSTALL__ENABLED=true"
                     };
-                    Some(format!(
-                        "Run 'kopi install {jdk_spec}' to install this JDK.\n\nOr enable \
-                         auto-install: {enable_cmd}"
-                    ))
+                    t_with(
+                        "error.jdk_not_installed.install_or_enable",
+                        &[("spec", jdk_spec), ("command", enable_cmd)],
+                    )
                 };
-                let details = None;
-                (suggestion, details)
-            }
-            KopiError::Download(msg) => {
-                let suggestion = Some(
-                    "Check your internet connection and try again. Use --timeout to increase \
-                     timeout if needed."
-                        .to_string(),
-                );
-                let details = Some(format!("Download failed: {msg}"));
-                (suggestion, details)
-            }
-            KopiError::Extract(msg) => {
-                let suggestion =
-                    Some("Ensure you have enough disk space and try again.".to_string());
-                let details = Some(format!("Extraction failed: {msg}"));
-                (suggestion, details)
-            }
-            KopiError::ChecksumMismatch => {
-                let suggestion = Some(
-                    "Try downloading again. If the problem persists, the file may be corrupted at \
-                     the source."
-                        .to_string(),
-                );
-                let details = Some(
-                    "The downloaded file's checksum doesn't match the expected value.".to_string(),
-                );
-                (suggestion, details)
+                // Builds without JavaFX never satisfy a `+fx` request
+                let details = jdk_spec
+                    .ends_with("+fx")
+                    .then(|| t_with("error.jdk_not_installed.javafx", &spec));
+                (Some(suggestion), details)
             }
+            KopiError::Download(msg) => (
+                Some(t("error.download.suggestion")),
+                Some(t_with("error.download.details", &[("message", msg)])),
+            ),
+            KopiError::Extract(msg) => (
+                Some(t("error.extract.suggestion")),
+                Some(t_with("error.extract.details", &[("message", msg)])),
+            ),
+            KopiError::ChecksumMismatch(_) => (
+                Some(t("error.checksum_mismatch.suggestion")),
+                Some(t("error.checksum_mismatch.details")),
+            ),
             KopiError::NoLocalVersion { searched_paths } => {
-                let suggestion = Some(
-                    "To configure a Java version for this project:\n  - Create a .kopi-version \
-                     file: echo 'temurin@21' > .kopi-version\n  - Set for this directory: kopi local temurin@21\n  - Set a global default: kopi global temurin@21"
-                        .to_string(),
-                );
                 let details = if searched_paths.is_empty() {
                     None
                 } else {
-                    Some(format!(
-                        "Searched in:\n{}",
-                        searched_paths
-                            .iter()
-                            .map(|p| format!("  - {p}"))
-                            .collect::<Vec<_>>()
-                            .join("\n")
+                    Some(t_with(
+                        "error.searched_in",
+                        &[("paths", &bullet_list(searched_paths))],
                     ))
                 };
-                (suggestion, details)
+                (Some(t("error.no_local_version.suggestion")), details)
             }
             KopiError::PermissionDenied(path) => {
-                let suggestion = if cfg!(unix) {
-                    Some(format!(
-                        "Try running with sudo or ensure you have write permissions to: {path}"
-                    ))
+                let key = if cfg!(unix) {
+                    "error.permission_denied.suggestion_unix"
                 } else {
-                    Some(format!(
-                        "Run as Administrator or ensure you have write permissions to: {path}"
-                    ))
+                    "error.permission_denied.suggestion_windows"
                 };
-                let details = None;
-                (suggestion, details)
+                (Some(t_with(key, &[("path", path)])), None)
             }
-            KopiError::DiskSpaceError(msg) => {
-                let suggestion = Some(
-                    "Free up disk space and try again. JDK installations typically require \
-                     300-500MB."
-                        .to_string(),
-                );
-                let details = Some(format!("Disk space issue: {msg}"));
-                (suggestion, details)
-            }
-            KopiError::LockingAcquire { scope, details } => {
-                let suggestion = Some(
-                    "Ensure no other Kopi process is operating on the same scope or adjust \
-                     locking.mode/locking.timeout in the configuration."
-                        .to_string(),
-                );
-                let details = Some(format!("Lock acquisition failed for {scope}: {details}"));
-                (suggestion, details)
+            KopiError::ElevationRequired {
+                shared_home,
+                command,
+            } => {
+                let key = if cfg!(unix) {
+                    "error.elevation_required.suggestion_unix"
+                } else {
+                    "error.elevation_required.suggestion_windows"
+                };
+                // The home is pasted into a shell command line
+                let quoted_home = if shared_home
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "/._-+@:,".contains(c))
+                {
+                    shared_home.clone()
+                } else {
+                    format!("\"{shared_home}\"")
+                };
+                (
+                    Some(t_with(
+                        key,
+                        &[("command", command), ("shared_home", &quoted_home)],
+                    )),
+                    Some(t_with(
+                        "error.elevation_required.details",
+                        &[("shared_home", shared_home)],
+                    )),
+                )
             }
+            KopiError::PolicyViolation(_) => (Some(t("error.policy_violation.suggestion")), None),
+            KopiError::DiskSpaceError(msg) => (
+                Some(t("error.disk_space.suggestion")),
+                Some(t_with("error.disk_space.details", &[("message", msg)])),
+            ),
+            KopiError::LockingAcquire { scope, details } => (
+                Some(t("error.locking_acquire.suggestion")),
+                Some(t_with(
+                    "error.locking_acquire.details",
+                    &[("scope", &scope.to_string()), ("details", details)],
+                )),
+            ),
             KopiError::LockingTimeout {
                 scope,
                 waited_secs,
                 timeout_value,
                 timeout_source,
                 details,
-            } => {
-                let suggestion = Some(
-                    "Wait for the other process to finish or override the lock timeout (CLI flag, KOPI_LOCK_TIMEOUT, or configuration file)."
-                        .to_string(),
-                );
-                let detail_message = format!(
-                    "Timed out after {waited_secs:.2}s when acquiring {scope} lock (timeout {timeout_value} from {timeout_source}): {details}"
-                );
-                (suggestion, Some(detail_message))
-            }
-            KopiError::LockingCancelled { scope, waited_secs } => {
-                let suggestion = Some(
-                    "Command cancelled. Re-run when you are ready to continue the operation."
-                        .to_string(),
-                );
-                let detail_message = format!(
-                    "Cancellation received after {waited_secs:.2}s while waiting for {scope} lock"
-                );
-                (suggestion, Some(detail_message))
-            }
-            KopiError::LockingRelease { scope, details } => {
-                let suggestion = Some(
-                    "Verify filesystem permissions and that the lock directory is accessible."
-                        .to_string(),
-                );
-                let details = Some(format!("Lock release failed for {scope}: {details}"));
-                (suggestion, details)
-            }
-            KopiError::NetworkError(msg) => {
-                let suggestion = Some(
-                    "Check your internet connection and proxy settings. Try 'kopi cache refresh' \
-                     to update metadata."
-                        .to_string(),
-                );
-                let details = Some(format!("Network issue: {msg}"));
-                (suggestion, details)
-            }
+            } => (
+                Some(t("error.locking_timeout.suggestion")),
+                Some(t_with(
+                    "error.locking_timeout.details",
+                    &[
+                        ("waited", &format!("{waited_secs:.2}")),
+                        ("scope", &scope.to_string()),
+                        ("timeout", &timeout_value.to_string()),
+                        ("source", &timeout_source.to_string()),
+                        ("details", details),
+                    ],
+                )),
+            ),
+            KopiError::LockingCancelled { scope, waited_secs } => (
+                Some(t("error.cancelled.suggestion")),
+                Some(t_with(
+                    "error.locking_cancelled.details",
+                    &[
+                        ("waited", &format!("{waited_secs:.2}")),
+                        ("scope", &scope.to_string()),
+                    ],
+                )),
+            ),
+            KopiError::Cancelled(details) => (
+                Some(t("error.cancelled.suggestion")),
+                Some(t_with("error.cancelled.details", &[("details", details)])),
+            ),
+            KopiError::LockingRelease { scope, details } => (
+                Some(t("error.locking_release.suggestion")),
+                Some(t_with(
+                    "error.locking_release.details",
+                    &[("scope", &scope.to_string()), ("details", details)],
+                )),
+            ),
+            KopiError::NetworkError(msg) => (
+                Some(t("error.network.suggestion")),
+                Some(t_with("error.network.details", &[("message", msg)])),
+            ),
             KopiError::Http(http_err) => {
                 let error_string = http_err.to_string();
-                let suggestion = if error_string.contains("timeout")
-                    || error_string.contains("Timeout")
-                {
-                    Some(
-                        "Try increasing the timeout with --timeout option (e.g., --timeout 300)."
-                            .to_string(),
-                    )
+                let key = if error_string.contains("timeout") || error_string.contains("Timeout") {
+                    "error.http.timeout"
                 } else if error_string.contains("429") {
-                    Some(
-                        "API rate limit exceeded. Please wait a few minutes and try again."
-                            .to_string(),
-                    )
+                    "error.http.rate_limited"
                 } else if error_string.contains("404") {
-                    Some(
-                        "The requested resource was not found. Try 'kopi cache refresh' to update \
-                         available versions."
-                            .to_string(),
-                    )
+                    "error.http.not_found"
                 } else if error_string.contains("redirect") || error_string.contains("Redirect") {
-                    Some("The download URL has too many redirects. Try again later.".to_string())
+                    "error.http.redirects"
                 } else {
-                    Some("Check your internet connection and try again.".to_string())
+                    "error.http.suggestion"
                 };
-                let details = Some(format!("HTTP error: {http_err}"));
-                (suggestion, details)
+                (
+                    Some(t(key)),
+                    Some(t_with("error.http.details", &[("message", &error_string)])),
+                )
             }
-            KopiError::AlreadyExists(msg) => {
-                let suggestion =
-                    Some("Use --force to overwrite the existing installation.".to_string());
-                let details = Some(msg.clone());
-                (suggestion, details)
-            }
-            KopiError::DirectoryNotFound(dir) => {
-                let suggestion = Some(format!("Ensure the directory exists: {dir}"));
-                let details = None;
-                (suggestion, details)
+            KopiError::AlreadyExists(msg) => (
+                Some(t("error.already_exists.suggestion")),
+                Some(msg.clone()),
+            ),
+            KopiError::DirectoryNotFound(dir) => (
+                Some(t_with(
+                    "error.directory_not_found.suggestion",
+                    &[("path", dir)],
+                )),
+                None,
+            ),
+            KopiError::CacheNotFound => (
+                Some(t("error.cache_not_found.suggestion")),
+                Some(t("error.cache_not_found.details")),
+            ),
+            KopiError::ConfigFile(_) | KopiError::InvalidConfig(_) => {
+                (Some(t("error.config.suggestion")), None)
             }
-            KopiError::CacheNotFound => {
-                let suggestion =
-                    Some("Run 'kopi cache refresh' to fetch the latest JDK metadata.".to_string());
-                let details = Some("No cached metadata found.".to_string());
-                (suggestion, details)
+            KopiError::MetadataFetch(_) => (Some(t("error.metadata_fetch.suggestion")), None),
+            KopiError::SchemaViolation { .. } => {
+                (Some(t("error.schema_violation.suggestion")), None)
             }
             KopiError::Io(io_err) => {
                 let suggestion = match io_err.kind() {
                     std::io::ErrorKind::PermissionDenied => {
                         if cfg!(unix) {
-                            Some("Try running with sudo or check file permissions.".to_string())
+                            Some(t("error.io.permission_denied_unix"))
                         } else {
-                            Some("Run as Administrator or check file permissions.".to_string())
+                            Some(t("error.io.permission_denied_windows"))
                         }
                     }
-                    std::io::ErrorKind::NotFound => Some(
-                        "Ensure the file or directory exists and the path is correct.".to_string(),
-                    ),
-                    std::io::ErrorKind::AlreadyExists => Some(
-                        "The file already exists. Use --force to overwrite if applicable."
-                            .to_string(),
-                    ),
+                    std::io::ErrorKind::NotFound => Some(t("error.io.not_found")),
+                    std::io::ErrorKind::AlreadyExists => Some(t("error.io.already_exists")),
                     _ => None,
                 };
-                let details = Some(format!("I/O error: {io_err}"));
+                let details = Some(t_with(
+                    "error.io.details",
+                    &[("message", &io_err.to_string())],
+                ));
                 (suggestion, details)
             }
             KopiError::ToolNotFound {
-                tool: _,
+                tool,
                 jdk_path,
                 available_tools,
             } => {
                 let suggestion = if available_tools.is_empty() {
-                    Some(format!(
-                        "This JDK installation at {jdk_path} may be corrupted. Try reinstalling \
-                         it."
-                    ))
+                    t_with("error.tool_not_found.corrupted", &[("path", jdk_path)])
                 } else {
-                    Some(format!(
-                        "Available tools in this JDK:\n{}\n\nThis tool may not be available in \
-                         this JDK distribution or version.",
-                        available_tools
-                            .iter()
-                            .map(|t| format!("  - {t}"))
-                            .collect::<Vec<_>>()
-                            .join("\n")
-                    ))
+                    t_with(
+                        "error.tool_not_found.available",
+                        &[("tools", &bullet_list(available_tools)), ("tool", tool)],
+                    )
                 };
-                let details = None;
-                (suggestion, details)
+                (Some(suggestion), None)
             }
             KopiError::KopiNotFound {
                 searched_paths,
                 is_auto_install_context,
             } => {
                 let suggestion = if cfg!(windows) {
-                    Some(
-                        "Verify kopi is installed correctly and add it to your PATH environment \
-                         variable."
-                            .to_string(),
-                    )
+                    t("error.kopi_not_found.suggestion_windows")
                 } else {
-                    Some(
-                        "Verify kopi is installed correctly. Add kopi to your PATH: export \
-                         PATH=\"$HOME/.kopi/bin:$PATH\""
-                            .to_string(),
-                    )
+                    t("error.kopi_not_found.suggestion_unix")
                 };
                 let details = if !searched_paths.is_empty() {
-                    Some(format!(
-                        "{}Searched in:\n{}",
-                        if *is_auto_install_context {
-                            "Cannot auto-install JDK. "
-                        } else {
-                            ""
-                        },
-                        searched_paths
-                            .iter()
-                            .map(|p| format!("  - {p}"))
-                            .collect::<Vec<_>>()
-                            .join("\n")
-                    ))
+                    let searched = t_with(
+                        "error.searched_in",
+                        &[("paths", &bullet_list(searched_paths))],
+                    );
+                    Some(if *is_auto_install_context {
+                        format!(
+                            "{} {searched}",
+                            t("error.kopi_not_found.cannot_auto_install")
+                        )
+                    } else {
+                        searched
+                    })
                 } else {
                     None
                 };
-                (suggestion, details)
+                (Some(suggestion), details)
             }
-            KopiError::ShellDetectionError(msg) => {
-                let suggestion = Some(
-                    "Specify the shell type explicitly with --shell option (e.g., --shell bash, \
-                     --shell powershell)."
-                        .to_string(),
-                );
-                let details = Some(msg.clone());
-                (suggestion, details)
-            }
-            KopiError::ShellNotFound(shell) => {
-                let suggestion = Some(format!(
-                    "Ensure '{shell}' is installed and available in your PATH."
-                ));
-                let details = None;
-                (suggestion, details)
-            }
-            KopiError::UnsupportedShell(shell) => {
-                let suggestion =
-                    Some("Supported shells: bash, zsh, fish, powershell, cmd.".to_string());
-                let details = Some(format!("Shell '{shell}' is not supported."));
-                (suggestion, details)
+            KopiError::ShellDetectionError(msg) => (
+                Some(t("error.shell_detection.suggestion")),
+                Some(msg.clone()),
+            ),
+            KopiError::ShellNotFound(shell) => (
+                Some(t_with(
+                    "error.shell_not_found.suggestion",
+                    &[("shell", shell)],
+                )),
+                None,
+            ),
+            KopiError::UnsupportedShell(shell) => (
+                Some(t("error.unsupported_shell.suggestion")),
+                Some(t_with(
+                    "error.unsupported_shell.details",
+                    &[("shell", shell)],
+                )),
+            ),
+            KopiError::BatchFailed { failures, .. } => {
+                let items: Vec<&str> = failures.iter().map(|f| f.item.as_str()).collect();
+                (
+                    Some(t("error.batch_failed.suggestion")),
+                    Some(t_with(
+                        "error.batch_failed.details",
+                        &[("items", &items.join(", "))],
+                    )),
+                )
             }
+            KopiError::Daemon {
+                details,
+                suggestion,
+                ..
+            } => (suggestion.clone(), details.clone()),
             _ => (None, None),
         };
 
         ErrorContext {
             error,
+            code: get_error_code(error),
             suggestion,
             details,
         }
@@ -374,16 +352,25 @@ impl<'a> ErrorContext<'a> {
 
 impl<'a> fmt::Display for ErrorContext<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Error: {}", self.error)?;
+        write!(f, "{} {}", t("error.label"), self.error)?;
 
         if let Some(details) = &self.details {
-            write!(f, "\n\nDetails: {details}")?;
+            write!(f, "\n\n{} {details}", t("error.details_label"))?;
         }
 
         if let Some(suggestion) = &self.suggestion {
-            write!(f, "\n\nSuggestion: {suggestion}")?;
+            write!(f, "\n\n{} {suggestion}", t("error.suggestion_label"))?;
         }
 
         Ok(())
     }
 }
+
+/// `  - item` lines for a list shown inside a message
+fn bullet_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("  - {item}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
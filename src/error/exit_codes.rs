@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::KopiError;
+use crate::error::{KopiError, PARTIAL_FAILURE_EXIT_CODE};
+use crate::locking::INTERRUPTED_EXIT_CODE;
 
 pub fn get_exit_code(error: &KopiError) -> i32 {
     match error {
@@ -26,13 +27,15 @@ pub fn get_exit_code(error: &KopiError) -> i32 {
 
         KopiError::ToolNotFound { .. } => 5,
 
-        KopiError::PermissionDenied(_) => 13,
+        KopiError::PermissionDenied(_) | KopiError::ElevationRequired { .. } => 13,
+
+        KopiError::PolicyViolation(_) => 77,
 
         KopiError::NetworkError(_) | KopiError::Http(_) | KopiError::MetadataFetch(_) => 20,
 
         KopiError::DiskSpaceError(_) => 28,
 
-        KopiError::LockingCancelled { .. } => 75,
+        KopiError::LockingCancelled { .. } | KopiError::Cancelled(_) => INTERRUPTED_EXIT_CODE,
 
         KopiError::AlreadyExists(_) => 17,
 
@@ -42,6 +45,12 @@ pub fn get_exit_code(error: &KopiError) -> i32 {
         KopiError::ShellNotFound(_) => 127, // Standard "command not found" exit code
         KopiError::UnsupportedShell(_) => 7,
 
+        KopiError::BatchFailed { succeeded, .. } if !succeeded.is_empty() => {
+            PARTIAL_FAILURE_EXIT_CODE
+        }
+
+        KopiError::Daemon { exit_code, .. } => *exit_code,
+
         _ => 1,
     }
 }
@@ -12,14 +12,72 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::{ErrorContext, KopiError};
+use crate::error::{ErrorContext, KopiError, get_error_code};
+use crate::i18n::t;
 use colored::Colorize;
+use serde::Serialize;
 
 pub fn format_error_chain(error: &KopiError) -> String {
     let context = ErrorContext::new(error);
     context.to_string()
 }
 
+/// Error report printed instead of plain text when a command runs with `--json`
+#[derive(Serialize)]
+struct ErrorReport<'a> {
+    code: &'a str,
+    message: String,
+    suggestion: Option<String>,
+    details: Option<String>,
+    /// Items a batch operation completed, only for [`KopiError::BatchFailed`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    succeeded: Option<&'a [String]>,
+    /// Per-item failures, only for [`KopiError::BatchFailed`]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<ItemReport<'a>>,
+}
+
+#[derive(Serialize)]
+struct ItemReport<'a> {
+    item: &'a str,
+    code: &'a str,
+    message: String,
+}
+
+/// Format error as a `{code, message, suggestion, details}` JSON object.
+/// Batch failures add `succeeded` and per-item `failures` with their codes.
+pub fn format_error_json(error: &KopiError) -> String {
+    let context = ErrorContext::new(error);
+    let (succeeded, failures) = match error {
+        KopiError::BatchFailed {
+            succeeded,
+            failures,
+            ..
+        } => (
+            Some(succeeded.as_slice()),
+            failures
+                .iter()
+                .map(|failure| ItemReport {
+                    item: &failure.item,
+                    code: get_error_code(&failure.error),
+                    message: failure.error.to_string(),
+                })
+                .collect(),
+        ),
+        _ => (None, Vec::new()),
+    };
+    let report = ErrorReport {
+        code: context.code,
+        message: error.to_string(),
+        suggestion: context.suggestion,
+        details: context.details,
+        succeeded,
+        failures,
+    };
+    // Only strings are serialized, which cannot fail
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
 /// Format error for display to user with colors and formatting (primarily for shim errors)
 pub fn format_error_with_color(error: &KopiError, use_color: bool) -> String {
     // Control colored output globally
@@ -29,7 +87,7 @@ pub fn format_error_with_color(error: &KopiError, use_color: bool) -> String {
     let mut output = String::new();
 
     // Error header
-    output.push_str(&format!("{} {error}\n", "Error:".red().bold()));
+    output.push_str(&format!("{} {error}\n", t("error.label").red().bold()));
 
     // Details
     if let Some(details) = &context.details {
@@ -38,7 +96,10 @@ pub fn format_error_with_color(error: &KopiError, use_color: bool) -> String {
 
     // Suggestions
     if let Some(suggestion) = &context.suggestion {
-        output.push_str(&format!("\n{}\n", "Suggestions:".yellow().bold()));
+        output.push_str(&format!(
+            "\n{}\n",
+            t("error.suggestions_label").yellow().bold()
+        ));
         // Split suggestion by newlines and add cyan bullet points
         for line in suggestion.lines() {
             if !line.trim().is_empty() {
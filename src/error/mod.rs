@@ -12,15 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod batch;
+mod codes;
 mod context;
 mod exit_codes;
 mod format;
 #[cfg(test)]
 mod tests;
 
+pub use batch::{BatchErrors, ItemFailure, PARTIAL_FAILURE_EXIT_CODE, failure_table};
+pub use codes::get_error_code;
 pub use context::ErrorContext;
 pub use exit_codes::get_exit_code;
-pub use format::{format_error_chain, format_error_with_color};
+pub use format::{format_error_chain, format_error_json, format_error_with_color};
 
 use crate::locking::{LockTimeoutSource, LockTimeoutValue};
 use thiserror::Error;
@@ -50,8 +54,8 @@ pub enum KopiError {
     #[error("Failed to extract archive: {0}")]
     Extract(String),
 
-    #[error("Checksum verification failed")]
-    ChecksumMismatch,
+    #[error("Checksum verification failed: {0}")]
+    ChecksumMismatch(String),
 
     #[error("No JDK configured for current project")]
     NoLocalVersion { searched_paths: Vec<String> },
@@ -96,9 +100,24 @@ pub enum KopiError {
     #[error("Invalid metadata format")]
     InvalidMetadata,
 
+    #[error("Invalid content in {file}{}: {message}", at_path(path))]
+    SchemaViolation {
+        file: String,
+        /// Dotted path of the offending key; empty when the document itself is malformed
+        path: String,
+        message: String,
+    },
+
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 
+    #[error("Writing to the shared Kopi home {shared_home} requires elevated privileges")]
+    ElevationRequired {
+        shared_home: String,
+        /// The command line to re-run with elevated privileges
+        command: String,
+    },
+
     #[error("Directory not found: {0}")]
     DirectoryNotFound(String),
 
@@ -108,6 +127,9 @@ pub enum KopiError {
     #[error("Security error: {0}")]
     SecurityError(String),
 
+    #[error("Installation blocked by policy: {0}")]
+    PolicyViolation(String),
+
     #[error("Network error: {0}")]
     NetworkError(String),
 
@@ -137,6 +159,9 @@ pub enum KopiError {
     #[error("Lock acquisition for {scope} was cancelled after {waited_secs:.2}s")]
     LockingCancelled { scope: String, waited_secs: f64 },
 
+    #[error("Operation cancelled: {0}")]
+    Cancelled(String),
+
     #[error("Failed to release {scope} lock: {details}")]
     LockingRelease { scope: String, details: String },
 
@@ -178,6 +203,36 @@ pub enum KopiError {
 
     #[error("Metadata generation failed: {0}")]
     GenerationFailed(String),
+
+    #[error(
+        "{operation} failed for {} of {} items",
+        .failures.len(),
+        .failures.len() + .succeeded.len()
+    )]
+    BatchFailed {
+        operation: String,
+        /// Labels of the items that were processed successfully
+        succeeded: Vec<String>,
+        failures: Vec<ItemFailure>,
+    },
+
+    #[error("{message}")]
+    Daemon {
+        message: String,
+        /// Error code reported by the daemon, see [`get_error_code`]
+        code: String,
+        exit_code: i32,
+        details: Option<String>,
+        suggestion: Option<String>,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, KopiError>;
+
+fn at_path(path: &str) -> String {
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!(" at '{path}'")
+    }
+}
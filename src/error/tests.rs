@@ -57,6 +57,29 @@ fn test_error_context_jdk_not_installed() {
     let suggestion = context.suggestion.unwrap();
     assert!(suggestion.contains("kopi install temurin@21"));
     assert!(suggestion.contains("KOPI_AUTO_INSTALL__ENABLED=true"));
+    assert!(context.details.is_none());
+}
+
+#[test]
+fn test_error_context_jdk_not_installed_javafx() {
+    let error = KopiError::JdkNotInstalled {
+        jdk_spec: "zulu@21+fx".to_string(),
+        version: Some("21".to_string()),
+        distribution: Some("zulu".to_string()),
+        auto_install_enabled: true,
+        auto_install_failed: None,
+        user_declined: false,
+        install_in_progress: false,
+    };
+    let context = ErrorContext::new(&error);
+
+    assert!(
+        context
+            .suggestion
+            .unwrap()
+            .contains("kopi install zulu@21+fx")
+    );
+    assert!(context.details.unwrap().contains("JavaFX bundled"));
 }
 
 #[test]
@@ -73,6 +96,35 @@ fn test_error_context_permission_denied_unix() {
     }
 }
 
+#[test]
+fn test_error_context_elevation_required() {
+    let error = KopiError::ElevationRequired {
+        shared_home: "/opt/kopi".to_string(),
+        command: "kopi install 21".to_string(),
+    };
+    let context = ErrorContext::new(&error);
+
+    let suggestion = context.suggestion.unwrap();
+    if cfg!(unix) {
+        // sudo drops KOPI_SHARED_HOME and the user's config, so the hint
+        // must name the shared home itself
+        assert!(suggestion.contains("sudo env KOPI_HOME=/opt/kopi kopi install 21"));
+    } else {
+        assert!(suggestion.contains("Administrator"));
+    }
+    assert!(context.details.unwrap().contains("/opt/kopi"));
+    assert_eq!(get_exit_code(&error), 13);
+
+    let error = KopiError::ElevationRequired {
+        shared_home: "/srv/shared jdks".to_string(),
+        command: "kopi install 21".to_string(),
+    };
+    if cfg!(unix) {
+        let suggestion = ErrorContext::new(&error).suggestion.unwrap();
+        assert!(suggestion.contains("KOPI_HOME=\"/srv/shared jdks\" kopi"));
+    }
+}
+
 #[test]
 fn test_error_context_network_error() {
     let error = KopiError::NetworkError("Connection timeout".to_string());
@@ -97,7 +149,7 @@ fn test_error_context_with_custom_suggestion() {
 
 #[test]
 fn test_error_context_display() {
-    let error = KopiError::ChecksumMismatch;
+    let error = KopiError::ChecksumMismatch("archive.tar.gz".to_string());
     let context = ErrorContext::new(&error);
     let output = context.to_string();
 
@@ -142,6 +194,10 @@ fn test_exit_codes() {
         get_exit_code(&KopiError::PermissionDenied("test".to_string())),
         13
     );
+    assert_eq!(
+        get_exit_code(&KopiError::PolicyViolation("test".to_string())),
+        77
+    );
     assert_eq!(
         get_exit_code(&KopiError::NetworkError("test".to_string())),
         20
@@ -166,7 +222,13 @@ fn test_exit_codes() {
             scope: "installation temurin-21".to_string(),
             waited_secs: 12.5,
         }),
-        75
+        130
+    );
+    assert_eq!(
+        get_exit_code(&KopiError::Cancelled(
+            "archive extraction stopped after 10 files".to_string()
+        )),
+        130
     );
     assert_eq!(
         get_exit_code(&KopiError::LockingTimeout {
@@ -211,6 +273,81 @@ fn test_format_error_chain() {
     assert!(formatted.contains("Invalid version format"));
 }
 
+#[test]
+fn test_format_error_json() {
+    let error = KopiError::VersionNotAvailable("temurin@99".to_string());
+    let json: serde_json::Value = serde_json::from_str(&format_error_json(&error)).unwrap();
+
+    assert_eq!(json["code"], "KOPI_E_NO_MATCHING_PACKAGE");
+    assert_eq!(json["message"], "JDK version 'temurin@99' is not available");
+    assert!(
+        json["suggestion"]
+            .as_str()
+            .unwrap()
+            .contains("kopi cache search")
+    );
+    assert_eq!(json["details"], "Version lookup failed: temurin@99");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&format_error_json(&KopiError::NotFound("x".to_string()))).unwrap();
+    assert_eq!(json["code"], "KOPI_E_NOT_FOUND");
+    assert!(json["suggestion"].is_null());
+    assert!(json.get("failures").is_none());
+}
+
+#[test]
+fn test_format_error_json_batch_failures() {
+    let mut batch = BatchErrors::new("install");
+    batch.record_success("temurin@21");
+    batch.record_failure(
+        "temurin@99",
+        KopiError::VersionNotAvailable("temurin@99".to_string()),
+    );
+    let error = batch.into_result().unwrap_err();
+    let json: serde_json::Value = serde_json::from_str(&format_error_json(&error)).unwrap();
+
+    assert_eq!(json["code"], "KOPI_E_PARTIAL_FAILURE");
+    assert_eq!(json["message"], "install failed for 1 of 2 items");
+    assert_eq!(json["details"], "Failed: temurin@99");
+    assert_eq!(json["succeeded"], serde_json::json!(["temurin@21"]));
+    assert_eq!(json["failures"][0]["item"], "temurin@99");
+    assert_eq!(json["failures"][0]["code"], "KOPI_E_NO_MATCHING_PACKAGE");
+}
+
+#[test]
+fn test_error_codes_are_stable_identifiers() {
+    let errors = [
+        KopiError::InvalidVersionFormat("x".to_string()),
+        KopiError::ChecksumMismatch("x".to_string()),
+        KopiError::PolicyViolation("x".to_string()),
+        KopiError::LockingCancelled {
+            scope: "x".to_string(),
+            waited_secs: 0.0,
+        },
+        KopiError::InvalidMetadata,
+    ];
+    for error in &errors {
+        let code = get_error_code(error);
+        assert!(code.starts_with("KOPI_E_"), "{code}");
+        assert!(
+            code.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'),
+            "{code}"
+        );
+        assert_eq!(ErrorContext::new(error).code, code);
+    }
+    assert_eq!(
+        get_error_code(&KopiError::Daemon {
+            message: "remote".to_string(),
+            code: "KOPI_E_VALIDATION".to_string(),
+            exit_code: 2,
+            details: None,
+            suggestion: None,
+        }),
+        "KOPI_E_VALIDATION"
+    );
+}
+
 #[test]
 #[serial]
 fn test_format_error_with_color_reset() {
@@ -273,8 +410,12 @@ fn test_error_config_file() {
     let error = KopiError::ConfigFile("Invalid TOML format".to_string());
     let context = ErrorContext::new(&error);
 
-    // ConfigFile errors don't have specific context in the current implementation
-    assert!(context.suggestion.is_none());
+    assert!(
+        context
+            .suggestion
+            .unwrap()
+            .contains("kopi config show --origin")
+    );
     assert!(context.details.is_none());
 }
 
@@ -283,8 +424,7 @@ fn test_error_invalid_config() {
     let error = KopiError::InvalidConfig("Missing required field 'storage.path'".to_string());
     let context = ErrorContext::new(&error);
 
-    // InvalidConfig errors don't have specific context in the current implementation
-    assert!(context.suggestion.is_none());
+    assert!(context.suggestion.unwrap().contains("kopi config schema"));
     assert!(context.details.is_none());
 }
 
@@ -340,8 +480,12 @@ fn test_error_metadata_fetch() {
     let error = KopiError::MetadataFetch("Failed to fetch from foojay.io".to_string());
     let context = ErrorContext::new(&error);
 
-    // MetadataFetch errors don't have specific context in the current implementation
-    assert!(context.suggestion.is_none());
+    assert!(
+        context
+            .suggestion
+            .unwrap()
+            .contains("kopi doctor --check network")
+    );
     assert!(context.details.is_none());
 }
 
@@ -462,7 +606,10 @@ fn test_error_generation_failed() {
 fn test_exit_code_coverage() {
     // Test additional exit codes not covered by other tests
     assert_eq!(get_exit_code(&KopiError::Extract("test".to_string())), 1);
-    assert_eq!(get_exit_code(&KopiError::ChecksumMismatch), 1);
+    assert_eq!(
+        get_exit_code(&KopiError::ChecksumMismatch("x".to_string())),
+        1
+    );
     assert_eq!(get_exit_code(&KopiError::ConfigFile("test".to_string())), 1);
     assert_eq!(get_exit_code(&KopiError::PathUpdate("test".to_string())), 1);
     assert_eq!(
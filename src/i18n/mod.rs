@@ -0,0 +1,259 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Message catalogs for user-facing text.
+//!
+//! Messages are looked up by stable dotted keys such as
+//! `error.download.suggestion` in the TOML catalogs under `locales/`, which
+//! are compiled into the binary. `{name}` placeholders are filled from the
+//! arguments passed to [`t_with`]. The language comes from `KOPI_LANG`, then
+//! the POSIX locale variables; anything without a catalog, and any key a
+//! catalog lacks, falls back to English.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const EN_CATALOG: &str = include_str!("locales/en.toml");
+const JA_CATALOG: &str = include_str!("locales/ja.toml");
+
+/// Languages with a message catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// Locale for a tag such as `ja`, `ja-JP` or `ja_JP.UTF-8`; `None` when
+    /// there is no catalog for its language
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Self::En),
+            "ja" => Some(Self::Ja),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Ja => "ja",
+        }
+    }
+
+    fn catalog(&self) -> &'static HashMap<String, String> {
+        static EN: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static JA: OnceLock<HashMap<String, String>> = OnceLock::new();
+        match self {
+            Self::En => EN.get_or_init(|| parse_catalog(EN_CATALOG)),
+            Self::Ja => JA.get_or_init(|| parse_catalog(JA_CATALOG)),
+        }
+    }
+}
+
+/// Pick the locale from the first set variable among `KOPI_LANG`, `LC_ALL`,
+/// `LC_MESSAGES` and `LANG`, as read by `var`
+pub fn detect_locale(var: impl Fn(&str) -> Option<String>) -> Locale {
+    ["KOPI_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| var(name).filter(|value| !value.is_empty()))
+        .next()
+        .and_then(|tag| Locale::from_tag(&tag))
+        .unwrap_or(Locale::En)
+}
+
+/// Locale of this process, detected from the environment on first use
+pub fn current_locale() -> Locale {
+    static CURRENT: OnceLock<Locale> = OnceLock::new();
+    *CURRENT.get_or_init(|| detect_locale(|name| std::env::var(name).ok()))
+}
+
+/// The message for `key` in the current locale
+pub fn t(key: &str) -> String {
+    t_with(key, &[])
+}
+
+/// The message for `key` in the current locale with its `{name}`
+/// placeholders replaced by `args`
+pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
+    message(current_locale(), key, args)
+}
+
+/// The message for `key` in `locale`, falling back to English and then to
+/// the key itself so a missing entry is visible rather than blank
+pub fn message(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let template = locale
+        .catalog()
+        .get(key)
+        .or_else(|| Locale::En.catalog().get(key));
+    match template {
+        Some(template) => substitute(template, args),
+        None => {
+            log::debug!("No message for '{key}' in the {} catalog", locale.code());
+            key.to_string()
+        }
+    }
+}
+
+/// Replace `{name}` placeholders; `{{` and `}}` stand for literal braces
+fn substitute(template: &str, args: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        output.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            output.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        let placeholder = tail[1..]
+            .find('}')
+            .filter(|_| tail.starts_with('{'))
+            .map(|end| &tail[1..=end]);
+        match placeholder.and_then(|name| args.iter().find(|(arg, _)| *arg == name)) {
+            Some((name, value)) => {
+                output.push_str(value);
+                rest = &tail[name.len() + 2..];
+            }
+            None => {
+                output.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Flatten a catalog's nested tables into dotted keys
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    fn flatten(prefix: &str, table: &toml::Table, messages: &mut HashMap<String, String>) {
+        for (name, value) in table {
+            let key = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}.{name}")
+            };
+            match value {
+                toml::Value::String(text) => {
+                    messages.insert(key, text.clone());
+                }
+                toml::Value::Table(child) => flatten(&key, child, messages),
+                _ => log::debug!("Ignoring non-string catalog entry '{key}'"),
+            }
+        }
+    }
+
+    let mut messages = HashMap::new();
+    match source.parse::<toml::Table>() {
+        Ok(table) => flatten("", &table, &mut messages),
+        Err(e) => log::warn!("Failed to parse a message catalog: {e}"),
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn placeholders(template: &str) -> BTreeSet<String> {
+        template
+            .replace("{{", "")
+            .replace("}}", "")
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_locale() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(detect_locale(env(&[])), Locale::En);
+        assert_eq!(detect_locale(env(&[("LANG", "ja_JP.UTF-8")])), Locale::Ja);
+        assert_eq!(
+            detect_locale(env(&[("KOPI_LANG", "en"), ("LANG", "ja_JP.UTF-8")])),
+            Locale::En
+        );
+        assert_eq!(
+            detect_locale(env(&[("LC_ALL", ""), ("LANG", "ja-JP")])),
+            Locale::Ja
+        );
+        assert_eq!(
+            detect_locale(env(&[("LC_ALL", "C"), ("LANG", "ja_JP.UTF-8")])),
+            Locale::En
+        );
+        assert_eq!(detect_locale(env(&[("LANG", "fr_FR.UTF-8")])), Locale::En);
+    }
+
+    #[test]
+    fn test_message_substitutes_placeholders() {
+        assert_eq!(
+            substitute("Run 'kopi install {spec}' ({spec})", &[("spec", "21")]),
+            "Run 'kopi install 21' (21)"
+        );
+        assert_eq!(
+            substitute("{{literal}} {missing}", &[("other", "x")]),
+            "{literal} {missing}"
+        );
+        assert_eq!(
+            message(
+                Locale::Ja,
+                "error.shell_not_found.suggestion",
+                &[("shell", "fish")]
+            ),
+            "'fish' がインストールされ、PATH から実行できることを確認してください。"
+        );
+    }
+
+    #[test]
+    fn test_message_falls_back_to_the_key() {
+        assert_eq!(message(Locale::Ja, "no.such.key", &[]), "no.such.key");
+        assert_eq!(message(Locale::En, "error.label", &[]), "Error:");
+        assert_eq!(message(Locale::Ja, "error.label", &[]), "エラー:");
+    }
+
+    #[test]
+    fn test_catalogs_define_the_same_keys_and_placeholders() {
+        let en = Locale::En.catalog();
+        let ja = Locale::Ja.catalog();
+        assert!(!en.is_empty());
+
+        let en_keys: BTreeSet<&String> = en.keys().collect();
+        let ja_keys: BTreeSet<&String> = ja.keys().collect();
+        assert_eq!(en_keys, ja_keys);
+
+        for (key, template) in en {
+            assert_eq!(
+                placeholders(template),
+                placeholders(&ja[key]),
+                "placeholders differ for {key}"
+            );
+        }
+    }
+}
@@ -12,26 +12,57 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::indicator::{IndicatifProgress, ProgressIndicator, SilentProgress, SimpleProgress};
+use crate::config::ProgressRenderer;
+use crate::indicator::{
+    IndicatifProgress, LineProgress, ProgressIndicator, ProgressRendererKind, SilentProgress,
+};
 use std::env;
 use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// Renderer chosen by the `progress.style` setting
+static CONFIGURED_RENDERER: OnceLock<ProgressRenderer> = OnceLock::new();
 
 pub struct ProgressFactory;
 
 impl ProgressFactory {
+    /// Apply the `progress.style` setting to every indicator created afterwards.
+    ///
+    /// Only the first call takes effect.
+    pub fn configure(renderer: ProgressRenderer) {
+        let _ = CONFIGURED_RENDERER.set(renderer);
+    }
+
     pub fn create(no_progress: bool) -> Box<dyn ProgressIndicator> {
+        let configured = CONFIGURED_RENDERER.get().copied().unwrap_or_default();
+        match Self::select_renderer(no_progress, configured) {
+            ProgressRendererKind::Silent => Box::new(SilentProgress),
+            ProgressRendererKind::Tty => Box::new(IndicatifProgress::new()),
+            ProgressRendererKind::NonTty => Box::new(LineProgress::new()),
+        }
+    }
+
+    fn select_renderer(no_progress: bool, configured: ProgressRenderer) -> ProgressRendererKind {
         if no_progress {
             // User explicitly requested no progress output
-            Box::new(SilentProgress)
+            ProgressRendererKind::Silent
         } else if Self::env_flag("KOPI_FORCE_TTY_PROGRESS") {
-            // Force full TTY indicator even if detection would choose simple output
-            Box::new(IndicatifProgress::new())
-        } else if Self::env_flag("KOPI_NO_TTY_PROGRESS") || Self::should_use_simple_progress() {
-            // Non-terminal or CI environment, or explicit opt-out of TTY rendering
-            Box::new(SimpleProgress::new())
+            // Force full TTY indicator even if detection would choose line output
+            ProgressRendererKind::Tty
+        } else if Self::env_flag("KOPI_NO_TTY_PROGRESS") {
+            // Explicit opt-out of TTY rendering
+            ProgressRendererKind::NonTty
         } else {
-            // Terminal environment with full animation support
-            Box::new(IndicatifProgress::new())
+            match configured {
+                ProgressRenderer::Bar => ProgressRendererKind::Tty,
+                ProgressRenderer::Line => ProgressRendererKind::NonTty,
+                // Non-terminal or CI environment
+                ProgressRenderer::Auto if Self::should_use_simple_progress() => {
+                    ProgressRendererKind::NonTty
+                }
+                // Terminal environment with full animation support
+                ProgressRenderer::Auto => ProgressRendererKind::Tty,
+            }
         }
     }
 
@@ -76,7 +107,7 @@ impl ProgressFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::indicator::{ProgressConfig, ProgressRendererKind, ProgressStyle};
+    use crate::indicator::{ProgressConfig, ProgressStyle};
     use std::sync::Mutex;
 
     // Helper to temporarily set environment variables
@@ -141,7 +172,7 @@ mod tests {
 
         let progress = ProgressFactory::create(false);
 
-        // The type should be SimpleProgress but we can't directly check that
+        // The type should be LineProgress but we can't directly check that
         // We can verify it's not silent by the fact it would produce output
         let mut p = progress;
         let config = ProgressConfig::new(ProgressStyle::Count);
@@ -194,7 +225,7 @@ mod tests {
         env_guard.remove("KOPI_FORCE_TTY_PROGRESS");
         env_guard.remove("KOPI_NO_TTY_PROGRESS");
 
-        // Note: This test might still return SimpleProgress if stderr is not a terminal
+        // Note: This test might still return LineProgress if stderr is not a terminal
         // during test execution, which is expected behavior
         let progress = ProgressFactory::create(false);
 
@@ -254,6 +285,35 @@ mod tests {
         assert_eq!(progress.renderer_kind(), ProgressRendererKind::NonTty);
     }
 
+    #[test]
+    fn test_configured_renderer_overrides_detection() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let mut env_guard = EnvGuard::new();
+        env_guard.set("CI", "true");
+        env_guard.remove("KOPI_FORCE_TTY_PROGRESS");
+        env_guard.remove("KOPI_NO_TTY_PROGRESS");
+
+        assert_eq!(
+            ProgressFactory::select_renderer(false, ProgressRenderer::Auto),
+            ProgressRendererKind::NonTty
+        );
+        assert_eq!(
+            ProgressFactory::select_renderer(false, ProgressRenderer::Bar),
+            ProgressRendererKind::Tty
+        );
+        assert_eq!(
+            ProgressFactory::select_renderer(true, ProgressRenderer::Bar),
+            ProgressRendererKind::Silent
+        );
+
+        env_guard.remove("CI");
+        env_guard.set("KOPI_NO_TTY_PROGRESS", "1");
+        assert_eq!(
+            ProgressFactory::select_renderer(false, ProgressRenderer::Bar),
+            ProgressRendererKind::NonTty
+        );
+    }
+
     #[test]
     fn test_should_use_simple_progress_with_no_color() {
         let _guard = ENV_LOCK.lock().unwrap();
@@ -269,7 +329,7 @@ mod tests {
     fn test_no_progress_flag_takes_precedence() {
         let _guard = ENV_LOCK.lock().unwrap();
         let mut env_guard = EnvGuard::new();
-        // Set conditions that would normally trigger SimpleProgress
+        // Set conditions that would normally trigger LineProgress
         env_guard.set("CI", "true");
         env_guard.set("TERM", "dumb");
         env_guard.set("NO_COLOR", "1");
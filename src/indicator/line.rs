@@ -0,0 +1,232 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Line-based renderer for dumb terminals and CI logs.
+//!
+//! Instead of redrawing a bar, progress is reported as one line per 10%
+//! milestone, so the output reads cleanly in logs that cannot handle carriage
+//! returns or cursor movement.
+
+use crate::indicator::{ProgressConfig, ProgressIndicator, ProgressRendererKind, ProgressStyle};
+use crate::storage::formatting::format_size;
+
+/// Percentage step between reported milestones
+const MILESTONE_STEP: u64 = 10;
+
+pub struct LineProgress {
+    style: ProgressStyle,
+    total: Option<u64>,
+    /// Last printed message, so repeated messages are printed once
+    message: Option<String>,
+    milestones: Milestones,
+    /// Children only report milestones; their parent prints the status lines
+    is_child: bool,
+}
+
+impl LineProgress {
+    pub fn new() -> Self {
+        Self {
+            style: ProgressStyle::default(),
+            total: None,
+            message: None,
+            milestones: Milestones::default(),
+            is_child: false,
+        }
+    }
+
+    fn child() -> Self {
+        Self {
+            is_child: true,
+            ..Self::new()
+        }
+    }
+
+    /// Step counters of top-level operations print a message per step, which
+    /// already tells how far they are
+    fn reports_milestones(&self) -> bool {
+        self.is_child || self.style != ProgressStyle::Count
+    }
+
+    /// Byte transfers change their message with every chunk, and children
+    /// leave status lines to their parent
+    fn prints_messages(&self) -> bool {
+        !self.is_child && self.style != ProgressStyle::Bytes
+    }
+
+    fn milestone_line(&self, percent: u64, current: u64) -> String {
+        let detail = match (self.style, self.total) {
+            (ProgressStyle::Bytes, Some(total)) => {
+                format!(" ({} / {})", format_size(current), format_size(total))
+            }
+            (ProgressStyle::Count, Some(total)) => format!(" ({current}/{total})"),
+            _ => String::new(),
+        };
+        format!("  {percent}%{detail}")
+    }
+}
+
+impl Default for LineProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressIndicator for LineProgress {
+    fn start(&mut self, config: ProgressConfig) {
+        // Like SimpleProgress, stay quiet until there is something to report
+        self.style = config.style;
+        self.total = config.total;
+        self.milestones = Milestones::default();
+    }
+
+    fn update(&mut self, current: u64, total: Option<u64>) {
+        if total.is_some() {
+            self.total = total;
+        }
+        if self.reports_milestones()
+            && let Some(percent) = self.milestones.advance(current, self.total)
+        {
+            println!("{}", self.milestone_line(percent, current));
+        }
+    }
+
+    fn set_message(&mut self, message: String) {
+        if self.prints_messages() && self.message.as_deref() != Some(message.as_str()) {
+            println!("{message}");
+        }
+        self.message = Some(message);
+    }
+
+    fn complete(&mut self, message: Option<String>) {
+        if !self.is_child {
+            let msg = message.unwrap_or_else(|| "Complete".to_string());
+            println!("{msg}");
+        }
+        self.message = None;
+    }
+
+    fn success(&self, message: &str) -> std::io::Result<()> {
+        println!("[OK] {message}");
+        Ok(())
+    }
+
+    fn error(&mut self, message: String) {
+        eprintln!("[ERROR] {message}");
+    }
+
+    fn create_child(&mut self) -> Box<dyn ProgressIndicator> {
+        Box::new(LineProgress::child())
+    }
+
+    fn suspend(&self, f: &mut dyn FnMut()) {
+        // Nothing is redrawn, so there is nothing to hide
+        f();
+    }
+
+    fn println(&self, message: &str) -> std::io::Result<()> {
+        println!("{message}");
+        Ok(())
+    }
+
+    fn renderer_kind(&self) -> ProgressRendererKind {
+        ProgressRendererKind::NonTty
+    }
+}
+
+/// Tracks which percentage milestones have been reported
+#[derive(Debug, Default)]
+struct Milestones {
+    reported: Option<u64>,
+}
+
+impl Milestones {
+    /// Return the highest milestone reached by `current` that has not been
+    /// reported yet
+    fn advance(&mut self, current: u64, total: Option<u64>) -> Option<u64> {
+        let total = total.filter(|total| *total > 0)?;
+        let percent = (u128::from(current) * 100 / u128::from(total)).min(100) as u64;
+        let milestone = percent - percent % MILESTONE_STEP;
+        if milestone == 0 || self.reported.is_some_and(|reported| milestone <= reported) {
+            return None;
+        }
+        self.reported = Some(milestone);
+        Some(milestone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_milestones_report_each_step_once() {
+        let mut milestones = Milestones::default();
+        let reported: Vec<u64> = [0, 5, 10, 12, 19, 20, 55, 99, 100, 120]
+            .into_iter()
+            .filter_map(|current| milestones.advance(current, Some(100)))
+            .collect();
+        assert_eq!(reported, vec![10, 20, 50, 90, 100]);
+    }
+
+    #[test]
+    fn test_milestones_need_a_total() {
+        let mut milestones = Milestones::default();
+        assert_eq!(milestones.advance(50, None), None);
+        assert_eq!(milestones.advance(50, Some(0)), None);
+        assert_eq!(milestones.advance(u64::MAX, Some(u64::MAX)), Some(100));
+    }
+
+    #[test]
+    fn test_milestone_line_format() {
+        let mut child = LineProgress::child();
+        child.start(ProgressConfig::new(ProgressStyle::Count).with_total(8));
+        assert_eq!(child.milestone_line(50, 4), "  50% (4/8)");
+
+        let mut progress = LineProgress::new();
+        progress.start(ProgressConfig::new(ProgressStyle::Bytes).with_total(200 * 1024 * 1024));
+        assert_eq!(
+            progress.milestone_line(30, 60 * 1024 * 1024),
+            "  30% (60.0 MB / 200.0 MB)"
+        );
+    }
+
+    #[test]
+    fn test_output_selection() {
+        let mut steps = LineProgress::new();
+        steps.start(ProgressConfig::new(ProgressStyle::Count).with_total(8));
+        assert!(steps.prints_messages());
+        assert!(!steps.reports_milestones());
+
+        let mut download = LineProgress::new();
+        download.start(ProgressConfig::new(ProgressStyle::Bytes).with_total(100));
+        assert!(!download.prints_messages());
+        assert!(download.reports_milestones());
+
+        let mut child = LineProgress::child();
+        child.start(ProgressConfig::new(ProgressStyle::Count).with_total(8));
+        assert!(!child.prints_messages());
+        assert!(child.reports_milestones());
+    }
+
+    #[test]
+    fn test_child_is_line_renderer() {
+        let mut progress = LineProgress::new();
+        let mut child = progress.create_child();
+        assert_eq!(child.renderer_kind(), ProgressRendererKind::NonTty);
+
+        child.start(ProgressConfig::new(ProgressStyle::Bytes).with_total(100));
+        child.update(50, None);
+        child.complete(Some("Download complete".to_string()));
+    }
+}
@@ -14,6 +14,7 @@
 
 pub mod factory;
 pub mod indicatif;
+pub mod line;
 pub mod silent;
 pub mod simple;
 pub mod status;
@@ -21,6 +22,7 @@ pub mod types;
 
 pub use factory::ProgressFactory;
 pub use indicatif::IndicatifProgress;
+pub use line::LineProgress;
 pub use silent::SilentProgress;
 pub use simple::SimpleProgress;
 pub use status::StatusReporter;
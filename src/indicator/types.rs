@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone)]
 pub struct ProgressConfig {
     pub total: Option<u64>,
@@ -29,7 +31,8 @@ impl ProgressConfig {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ProgressStyle {
     Bytes,
     Count,
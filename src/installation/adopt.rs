@@ -0,0 +1,381 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detection of JDKs installed outside kopi.
+//!
+//! A JDK is recognized by the `release` file at its JAVA_HOME, which names
+//! the Java version and usually the vendor. Adopted JDKs are registered as
+//! links in the JDKs directory, so shims and `kopi env` resolve them like any
+//! other installation while their files stay where they are.
+
+use crate::error::{KopiError, Result};
+use crate::models::distribution::Distribution;
+use crate::paths::install;
+use crate::platform::with_executable_extension;
+use crate::storage::JdkRepository;
+use crate::version::Version;
+use log::debug;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const RELEASE_FILE: &str = "release";
+
+/// A JDK found outside kopi's home
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedJdk {
+    pub java_home: PathBuf,
+    pub distribution: Distribution,
+    pub version: Version,
+    pub implementor: Option<String>,
+}
+
+impl DetectedJdk {
+    /// Directory name the JDK is registered under
+    pub fn slug(&self) -> String {
+        format!("{}-{}", self.distribution.id(), self.version)
+    }
+}
+
+/// Outcome of adopting one JDK
+#[derive(Debug, PartialEq)]
+pub enum AdoptOutcome {
+    Adopted(PathBuf),
+    /// The same JDK was adopted before
+    AlreadyAdopted,
+    /// A different installation already uses the JDK's name
+    Conflict(PathBuf),
+}
+
+/// Identify the JDK at `path`, which may be a JAVA_HOME or a macOS bundle
+pub fn detect(path: &Path) -> Option<DetectedJdk> {
+    let bundle_home = install::bundle_java_home(path);
+    let java_home = if bundle_home.join(RELEASE_FILE).is_file() {
+        bundle_home
+    } else {
+        path.to_path_buf()
+    };
+
+    let java = install::bin_directory(&java_home).join(with_executable_extension("java"));
+    if !java.is_file() {
+        return None;
+    }
+
+    let content = fs::read_to_string(java_home.join(RELEASE_FILE)).ok()?;
+    let properties = parse_release(&content);
+    let Some(version) = release_version(&properties) else {
+        debug!("No usable version in {}", java_home.display());
+        return None;
+    };
+    let implementor = properties.get("IMPLEMENTOR").cloned();
+    let distribution = distribution_for(
+        implementor.as_deref(),
+        properties.get("IMPLEMENTOR_VERSION").map(String::as_str),
+    );
+
+    Some(DetectedJdk {
+        java_home: fs::canonicalize(&java_home).unwrap_or(java_home),
+        distribution,
+        version,
+        implementor,
+    })
+}
+
+/// Find JDKs directly under each of `roots`, skipping duplicates reached
+/// through links
+pub fn scan(roots: &[PathBuf]) -> Vec<DetectedJdk> {
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+
+    for root in roots {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+        debug!("Scanning {} for JDKs", root.display());
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+        paths.sort();
+        for path in paths {
+            if let Some(jdk) = detect(&path)
+                && seen.insert(jdk.java_home.clone())
+            {
+                found.push(jdk);
+            }
+        }
+    }
+
+    found
+}
+
+/// Common system locations and SDK manager roots that hold JDKs
+pub fn default_scan_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+
+    #[cfg(target_os = "linux")]
+    roots.extend(["/usr/lib/jvm", "/usr/lib64/jvm", "/usr/java"].map(PathBuf::from));
+
+    #[cfg(target_os = "macos")]
+    roots.push(PathBuf::from("/Library/Java/JavaVirtualMachines"));
+
+    #[cfg(target_os = "windows")]
+    if let Some(program_files) = std::env::var_os("ProgramFiles").map(PathBuf::from) {
+        for vendor in [
+            "Java",
+            "Eclipse Adoptium",
+            "Amazon Corretto",
+            "Zulu",
+            "BellSoft",
+            "Microsoft",
+        ] {
+            roots.push(program_files.join(vendor));
+        }
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        // SDKMAN!, IntelliJ IDEA and asdf
+        roots.push(home.join(".sdkman").join("candidates").join("java"));
+        roots.push(home.join(".jdks"));
+        roots.push(home.join(".asdf").join("installs").join("java"));
+        #[cfg(target_os = "macos")]
+        roots.push(home.join("Library/Java/JavaVirtualMachines"));
+    }
+
+    roots
+}
+
+/// Register `jdk` with kopi without copying it
+pub fn adopt(repository: &JdkRepository, jdk: &DetectedJdk) -> Result<AdoptOutcome> {
+    let slug = jdk.slug();
+    let existing = install::installation_directory(repository.config().kopi_home(), &slug);
+    if fs::symlink_metadata(&existing).is_ok() {
+        let same = fs::canonicalize(&existing).is_ok_and(|target| target == jdk.java_home);
+        return Ok(if same {
+            AdoptOutcome::AlreadyAdopted
+        } else {
+            AdoptOutcome::Conflict(existing)
+        });
+    }
+
+    repository
+        .adopt_external(&slug, &jdk.java_home, jdk.implementor.as_deref())
+        .map(AdoptOutcome::Adopted)
+}
+
+/// Identify the JDK at `path` or explain why it is not one
+pub fn detect_at(path: &Path) -> Result<DetectedJdk> {
+    if !path.is_dir() {
+        return Err(KopiError::NotFound(format!(
+            "Directory not found: {}",
+            path.display()
+        )));
+    }
+    detect(path).ok_or_else(|| {
+        KopiError::ValidationError(format!(
+            "{} is not a JDK: expected bin/java and a 'release' file naming JAVA_VERSION",
+            path.display()
+        ))
+    })
+}
+
+fn parse_release(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.trim().to_string(),
+                value.trim().trim_matches('"').to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Version from `JAVA_RUNTIME_VERSION`, which carries the build number,
+/// falling back to `JAVA_VERSION`
+fn release_version(properties: &HashMap<String, String>) -> Option<Version> {
+    ["JAVA_RUNTIME_VERSION", "JAVA_VERSION"]
+        .iter()
+        .filter_map(|key| properties.get(*key))
+        .find_map(|raw| normalize_version(raw))
+}
+
+fn normalize_version(raw: &str) -> Option<Version> {
+    // Legacy `1.8.0_392-b08` becomes `8.0.392`
+    let modern = match raw.strip_prefix("1.") {
+        Some(legacy) => {
+            let legacy = legacy.split('-').next()?;
+            let (base, update) = legacy.split_once('_').unwrap_or((legacy, "0"));
+            let major = base.split('.').next()?;
+            format!("{major}.0.{update}")
+        }
+        None => raw.to_string(),
+    };
+
+    // Vendor suffixes such as `-LTS` or `-Ubuntu-122.04` follow the build
+    let trimmed = match modern.split_once('+') {
+        Some((base, build)) => {
+            let build: String = build.chars().take_while(char::is_ascii_digit).collect();
+            if build.is_empty() {
+                base.to_string()
+            } else {
+                format!("{base}+{build}")
+            }
+        }
+        None => modern.split('-').next()?.to_string(),
+    };
+    Version::from_str(&trimmed).ok()
+}
+
+fn distribution_for(implementor: Option<&str>, implementor_version: Option<&str>) -> Distribution {
+    let implementor = implementor.unwrap_or_default().to_ascii_lowercase();
+    let implementor_version = implementor_version.unwrap_or_default().to_ascii_lowercase();
+
+    let id = if implementor.contains("adoptium") || implementor.contains("adoptopenjdk") {
+        "temurin"
+    } else if implementor.contains("amazon") {
+        "corretto"
+    } else if implementor.contains("azul") {
+        "zulu"
+    } else if implementor.contains("bellsoft") {
+        "liberica"
+    } else if implementor.contains("sap") {
+        "sapmachine"
+    } else if implementor.contains("graalvm") {
+        "graalvm"
+    } else if implementor.contains("alibaba") {
+        "dragonwell"
+    } else if implementor.contains("tencent") {
+        "kona"
+    } else if implementor.contains("ibm") || implementor.contains("international business") {
+        "semeru"
+    } else if implementor_version.contains("mandrel") {
+        "mandrel"
+    } else if implementor.contains("microsoft") {
+        "microsoft"
+    } else {
+        "openjdk"
+    };
+    Distribution::from_str(id).unwrap_or(Distribution::OpenJdk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fake_jdk(path: &Path, release: &str) {
+        let bin = install::bin_directory(path);
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join(with_executable_extension("java")), "").unwrap();
+        fs::write(path.join(RELEASE_FILE), release).unwrap();
+    }
+
+    #[test]
+    fn test_normalize_version() {
+        let version = |raw| normalize_version(raw).map(|v| v.to_string());
+        assert_eq!(version("21.0.5+11-LTS").as_deref(), Some("21.0.5+11"));
+        assert_eq!(
+            version("17.0.9+9-Ubuntu-122.04").as_deref(),
+            Some("17.0.9+9")
+        );
+        assert_eq!(version("1.8.0_392-b08").as_deref(), Some("8.0.392"));
+        assert_eq!(version("11.0.2").as_deref(), Some("11.0.2"));
+        assert_eq!(version("vendor"), None);
+    }
+
+    #[test]
+    fn test_detect_identifies_vendor_and_version() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path().join("jdk-21");
+        fake_jdk(
+            &home,
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_RUNTIME_VERSION=\"21.0.5+11-LTS\"\n\
+             JAVA_VERSION=\"21.0.5\"\n",
+        );
+
+        let jdk = detect(&home).unwrap();
+        assert_eq!(jdk.distribution, Distribution::Temurin);
+        assert_eq!(jdk.slug(), "temurin-21.0.5+11");
+        assert_eq!(jdk.java_home, fs::canonicalize(&home).unwrap());
+        assert_eq!(jdk.implementor.as_deref(), Some("Eclipse Adoptium"));
+
+        // A directory without a release file is not a JDK
+        fs::create_dir_all(temp.path().join("empty")).unwrap();
+        assert!(detect(&temp.path().join("empty")).is_none());
+        assert!(matches!(
+            detect_at(&temp.path().join("empty")),
+            Err(KopiError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_scan_finds_bundles_and_skips_duplicates() {
+        let temp = TempDir::new().unwrap();
+        let bundle = temp.path().join("zulu-17.jdk");
+        fake_jdk(
+            &install::bundle_java_home(&bundle),
+            "IMPLEMENTOR=\"Azul Systems, Inc.\"\nJAVA_VERSION=\"17.0.13\"\n",
+        );
+        fake_jdk(
+            &temp.path().join("java-11"),
+            "IMPLEMENTOR=\"Oracle Corporation\"\nJAVA_VERSION=\"11.0.2\"\n",
+        );
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(temp.path().join("java-11"), temp.path().join("default"))
+            .unwrap();
+
+        let slugs: Vec<String> = scan(&[temp.path().to_path_buf(), temp.path().join("missing")])
+            .iter()
+            .map(DetectedJdk::slug)
+            .collect();
+        assert_eq!(slugs, vec!["openjdk-11.0.2", "zulu-17.0.13"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_adopt_links_without_copying_and_removal_keeps_files() {
+        use crate::config::KopiConfig;
+
+        let temp = TempDir::new().unwrap();
+        let kopi_home = temp.path().join("kopi");
+        let home = temp.path().join("system").join("jdk-17");
+        fake_jdk(
+            &home,
+            "IMPLEMENTOR=\"Amazon.com Inc.\"\nJAVA_RUNTIME_VERSION=\"17.0.13+11-LTS\"\n",
+        );
+        let config = KopiConfig::new(kopi_home.clone()).unwrap();
+        let repository = JdkRepository::new(&config);
+        let jdk = detect(&home).unwrap();
+
+        let link = match adopt(&repository, &jdk).unwrap() {
+            AdoptOutcome::Adopted(link) => link,
+            other => panic!("unexpected outcome {other:?}"),
+        };
+        assert_eq!(
+            adopt(&repository, &jdk).unwrap(),
+            AdoptOutcome::AlreadyAdopted
+        );
+
+        let installed = repository.list_installed_jdks().unwrap();
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].distribution, "corretto");
+        assert!(repository.is_external(&installed[0]));
+        assert!(installed[0].resolve_bin_path().is_ok());
+        assert_eq!(repository.get_jdk_size(&link).unwrap(), 0);
+
+        repository.remove_jdk(&link).unwrap();
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(home.join(RELEASE_FILE).is_file());
+    }
+}
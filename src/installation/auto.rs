@@ -14,11 +14,13 @@
 
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
+use crate::i18n::t;
 use crate::indicator::StatusReporter;
-use crate::version::VersionRequest;
+use crate::version::{Version, VersionRequest};
 use log::{debug, info, warn};
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::Duration;
 
 /// Result of an installation attempt
@@ -74,20 +76,43 @@ impl<'a> AutoInstaller<'a> {
         Ok(response.is_empty() || response == "y" || response == "yes")
     }
 
+    /// Refuse a request the `[policy]` configuration forbids before prompting or
+    /// spawning an install. Tracks are checked by the install itself once resolved.
+    pub fn check_policy(&self, version_request: &VersionRequest) -> Result<()> {
+        let Ok(version) = Version::from_str(&version_request.version_pattern) else {
+            return Ok(());
+        };
+        let distribution = version_request
+            .distribution
+            .as_deref()
+            .unwrap_or(&self.config.default_distribution);
+
+        match self
+            .config
+            .policy
+            .violation(distribution, &version, version.is_early_access())
+        {
+            Some(violation) => Err(KopiError::PolicyViolation(violation)),
+            None => Ok(()),
+        }
+    }
+
     /// Install a JDK by delegating to the main kopi binary
     pub fn install_jdk(&self, version_request: &VersionRequest) -> Result<()> {
+        self.check_policy(version_request)?;
+
         // Build the version specification for the install command
-        // Append +fx if JavaFX is requested
         let mut version_spec = if let Some(dist) = &version_request.distribution {
             format!("{}@{}", dist, version_request.version_pattern)
         } else {
             version_request.version_pattern.clone()
         };
 
-        // Add +fx suffix if JavaFX is requested
-        if version_request.javafx_bundled == Some(true) {
-            version_spec.push_str("+fx");
-            debug!("Adding +fx suffix for JavaFX bundled version");
+        // Add +crac/+leyden/+fx suffixes for the requested build variant
+        let variant_suffix = version_request.variant_suffix();
+        if !variant_suffix.is_empty() {
+            version_spec.push_str(&variant_suffix);
+            debug!("Adding {variant_suffix} suffix for the requested build variant");
         }
 
         info!("Auto-installing JDK: {version_spec}");
@@ -95,9 +120,14 @@ impl<'a> AutoInstaller<'a> {
         // Find the kopi binary
         let kopi_path = self.find_kopi_binary()?;
 
-        // Build the install command
+        // Build the install command; tracks are materialized by `kopi upgrade`
+        let subcommand = if version_request.track.is_some() {
+            "upgrade"
+        } else {
+            "install"
+        };
         let mut cmd = std::process::Command::new(&kopi_path);
-        cmd.arg("install").arg(&version_spec);
+        cmd.arg(subcommand).arg(&version_spec);
 
         // Set timeout if configured
         let timeout_secs = self.config.auto_install.timeout_secs;
@@ -131,21 +161,24 @@ impl<'a> AutoInstaller<'a> {
         if !self.should_auto_install() {
             return Ok(InstallationResult::AutoInstallDisabled);
         }
+        self.check_policy(version_request)?;
 
         // Prompt user for confirmation
         let user_approved = self.prompt_user(&version_request.version_pattern)?;
 
         if user_approved {
-            self.status.step("Installing JDK");
+            self.status.step(&t("status.auto_install.installing"));
             self.install_jdk(version_request)?;
             Ok(InstallationResult::Installed)
         } else {
-            self.status.step("Skipping installation");
-            self.status.step("You can install this JDK later with:");
-            self.status.step(&format!(
-                "  kopi install {}",
-                version_request.version_pattern
-            ));
+            self.status.step(&t("status.auto_install.skipping"));
+            self.status.step(&t("status.auto_install.install_later"));
+            let hint = if version_request.track.is_some() {
+                format!("  kopi upgrade {version_request}")
+            } else {
+                format!("  kopi install {}", version_request.version_pattern)
+            };
+            self.status.step(&hint);
             Ok(InstallationResult::UserDeclined)
         }
     }
@@ -281,6 +314,31 @@ mod tests {
         assert!(installer.prompt_user("temurin@21").unwrap());
     }
 
+    #[test]
+    fn test_check_policy_rejects_forbidden_request() {
+        let mut config = create_test_config();
+        config.policy.allowed_distributions = vec!["temurin".to_string()];
+        config.policy.allowed_major_versions = vec![21];
+        let installer = AutoInstaller::new(&config, false);
+
+        let allowed = VersionRequest::new("21".to_string()).unwrap();
+        assert!(installer.check_policy(&allowed).is_ok());
+
+        let old = VersionRequest::new("17".to_string()).unwrap();
+        assert!(matches!(
+            installer.prompt_and_install(&old),
+            Err(KopiError::PolicyViolation(_))
+        ));
+
+        let zulu = VersionRequest::new("21".to_string())
+            .unwrap()
+            .with_distribution("zulu".to_string());
+        assert!(matches!(
+            installer.check_policy(&zulu),
+            Err(KopiError::PolicyViolation(_))
+        ));
+    }
+
     #[test]
     fn test_find_kopi_binary_not_found() {
         let config = create_test_config();
@@ -0,0 +1,319 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Trimmed runtime images built with a JDK's `jlink`.
+//!
+//! `kopi jlink create` links the requested modules from an installed JDK into
+//! an image directory, by default under `KOPI_HOME/runtimes`. Every image is
+//! recorded in `KOPI_HOME/runtimes.json` with the JDK and modules it was
+//! built from, so `kopi jlink list` and `kopi jlink remove` can find it again.
+
+use crate::error::{KopiError, Result};
+use crate::paths::home;
+use crate::platform::with_executable_extension;
+use crate::storage::InstalledJdk;
+use chrono::{DateTime, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A runtime image built by `kopi jlink create`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeImage {
+    pub path: PathBuf,
+    /// Distribution and version of the JDK the image was linked from
+    pub distribution: String,
+    pub version: String,
+    pub modules: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Runtime images by name
+pub type RuntimeImages = BTreeMap<String, RuntimeImage>;
+
+/// What `jlink` should produce
+#[derive(Debug, Clone)]
+pub struct JlinkRequest {
+    pub modules: Vec<String>,
+    pub output: PathBuf,
+    /// Keep debug attributes instead of passing `--strip-debug`
+    pub keep_debug: bool,
+}
+
+/// All recorded runtime images; a missing or corrupt registry is empty
+pub fn load_runtime_images(kopi_home: &Path) -> RuntimeImages {
+    fs::read_to_string(home::runtimes_file(kopi_home))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_runtime_images(kopi_home: &Path, images: &RuntimeImages) -> Result<()> {
+    let path = home::runtimes_file(kopi_home);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(images)?)?;
+    Ok(())
+}
+
+/// Check that `name` can be used as a directory name under `runtimes/`
+pub fn validate_image_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with(['.', '-'])
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(KopiError::ValidationError(format!(
+            "Invalid runtime name '{name}': use letters, digits, '-', '_' and '.', \
+             starting with a letter or digit"
+        )))
+    }
+}
+
+/// Split comma-separated module lists into a sorted, de-duplicated set
+pub fn parse_modules<'a>(lists: impl IntoIterator<Item = &'a str>) -> Result<BTreeSet<String>> {
+    let mut modules = BTreeSet::new();
+    for module in lists.into_iter().flat_map(|list| list.split(',')) {
+        let module = module.trim();
+        if module.is_empty() {
+            continue;
+        }
+        let valid = module.split('.').all(|part| {
+            part.chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        if !valid {
+            return Err(KopiError::ValidationError(format!(
+                "Invalid module name '{module}'"
+            )));
+        }
+        modules.insert(module.to_string());
+    }
+    Ok(modules)
+}
+
+/// Path of a JDK tool such as `jlink`, failing when the JDK lacks it (a JRE)
+pub fn jdk_tool(jdk: &InstalledJdk, tool: &str) -> Result<PathBuf> {
+    let path = jdk
+        .resolve_java_home()
+        .join("bin")
+        .join(with_executable_extension(tool));
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(KopiError::ToolNotFound {
+            tool: tool.to_string(),
+            jdk_path: jdk.path.display().to_string(),
+            available_tools: Vec::new(),
+        })
+    }
+}
+
+/// Modules `jar` needs, as reported by `jdeps --print-module-deps`
+pub fn scan_jar_modules(jdk: &InstalledJdk, jar: &Path) -> Result<BTreeSet<String>> {
+    if !jar.is_file() {
+        return Err(KopiError::NotFound(format!(
+            "Jar file not found: {}",
+            jar.display()
+        )));
+    }
+    let jdeps = jdk_tool(jdk, "jdeps")?;
+    let output = Command::new(&jdeps)
+        .arg("--print-module-deps")
+        .arg("--ignore-missing-deps")
+        .arg("--multi-release")
+        .arg(jdk.version.major().to_string())
+        .arg(jar)
+        .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        return Err(KopiError::SystemError(format!(
+            "jdeps failed for {}: {}",
+            jar.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    debug!("jdeps reported {} for {}", stdout.trim(), jar.display());
+    // Warnings precede the module list, which is the last line
+    parse_modules(stdout.lines().last())
+}
+
+/// Run `jlink` from `jdk` to build `request.output`
+pub fn link_runtime(jdk: &InstalledJdk, request: &JlinkRequest) -> Result<()> {
+    if request.modules.is_empty() {
+        return Err(KopiError::ValidationError(
+            "No modules to link; pass --modules or --jar".to_string(),
+        ));
+    }
+    // jlink refuses to write into an existing directory
+    if request.output.exists() {
+        return Err(KopiError::AlreadyExists(format!(
+            "{} already exists; use --force to replace it",
+            request.output.display()
+        )));
+    }
+    if let Some(parent) = request.output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let jlink = jdk_tool(jdk, "jlink")?;
+    let mut command = Command::new(&jlink);
+    command
+        .arg("--add-modules")
+        .arg(request.modules.join(","))
+        .arg("--output")
+        .arg(&request.output)
+        .arg("--no-header-files")
+        .arg("--no-man-pages");
+    if !request.keep_debug {
+        command.arg("--strip-debug");
+    }
+    debug!("Running {command:?}");
+
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(KopiError::SystemError(format!(
+            "jlink failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Delete a runtime image directory. Directories that do not look like a
+/// runtime image (no `release` file) are left alone; returns whether it was
+/// deleted.
+pub fn remove_image_dir(path: &Path) -> Result<bool> {
+    if !path.join("release").is_file() {
+        return Ok(false);
+    }
+    fs::remove_dir_all(path)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_modules() {
+        let modules =
+            parse_modules(["java.sql, java.base", "java.base,jdk.crypto.ec", ""]).unwrap();
+        assert_eq!(
+            modules.into_iter().collect::<Vec<_>>(),
+            ["java.base", "java.sql", "jdk.crypto.ec"]
+        );
+        assert!(parse_modules(["java.base;rm"]).is_err());
+        assert!(parse_modules(["java..base"]).is_err());
+    }
+
+    #[test]
+    fn test_validate_image_name() {
+        assert!(validate_image_name("app-runtime_21.0").is_ok());
+        for name in ["", ".hidden", "-x", "a/b", "..", "a b"] {
+            assert!(validate_image_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_runtime_images_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(load_runtime_images(temp_dir.path()).is_empty());
+
+        let mut images = RuntimeImages::new();
+        images.insert(
+            "app".to_string(),
+            RuntimeImage {
+                path: temp_dir.path().join("runtimes").join("app"),
+                distribution: "temurin".to_string(),
+                version: "21.0.5+11".to_string(),
+                modules: vec!["java.base".to_string()],
+                created_at: Utc::now(),
+            },
+        );
+        save_runtime_images(temp_dir.path(), &images).unwrap();
+        assert_eq!(load_runtime_images(temp_dir.path()), images);
+    }
+
+    #[test]
+    fn test_remove_image_dir_requires_release_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let image = temp_dir.path().join("image");
+        fs::create_dir_all(image.join("bin")).unwrap();
+        assert!(!remove_image_dir(&image).unwrap());
+        assert!(image.exists());
+
+        fs::write(image.join("release"), "JAVA_VERSION=\"21\"\n").unwrap();
+        assert!(remove_image_dir(&image).unwrap());
+        assert!(!image.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_link_runtime_runs_jlink() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let jdk_path = temp_dir.path().join("temurin-21.0.5+11");
+        let bin = jdk_path.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        // Records its arguments and creates the --output directory like jlink
+        let jlink = bin.join("jlink");
+        fs::write(
+            &jlink,
+            "#!/bin/sh\nwhile [ $# -gt 0 ]; do\n  case \"$1\" in --output) out=\"$2\"; shift;; esac\n  \
+             shift\ndone\nmkdir -p \"$out\" && echo \"$0\" > \"$out/release\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&jlink, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let jdk = InstalledJdk::new(
+            "temurin".to_string(),
+            Version::from_str("21.0.5+11").unwrap(),
+            jdk_path,
+            false,
+        );
+        let request = JlinkRequest {
+            modules: vec!["java.base".to_string()],
+            output: temp_dir.path().join("runtimes").join("app"),
+            keep_debug: false,
+        };
+        link_runtime(&jdk, &request).unwrap();
+        assert!(request.output.join("release").is_file());
+
+        let error = link_runtime(&jdk, &request).unwrap_err();
+        assert!(matches!(error, KopiError::AlreadyExists(_)));
+
+        let missing = JlinkRequest {
+            modules: Vec::new(),
+            ..request
+        };
+        assert!(matches!(
+            link_runtime(&jdk, &missing),
+            Err(KopiError::ValidationError(_))
+        ));
+    }
+}
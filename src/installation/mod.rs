@@ -12,6 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod adopt;
 pub mod auto;
+pub mod installer;
+pub mod jlink;
+pub mod observer;
+pub mod overlay;
 
+pub use adopt::{AdoptOutcome, DetectedJdk};
 pub use auto::{AutoInstaller, InstallationResult};
+pub use installer::{InstallSummary, Installer};
+pub use observer::{InstallObserver, InstallPhase};
@@ -0,0 +1,213 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structured progress events for embedding the install pipeline.
+//!
+//! The terminal progress bars are one consumer of an install; GUI wrappers
+//! and the daemon want the same information without parsing CLI output. An
+//! [`InstallObserver`] passed to [`Installer`](super::Installer) receives the
+//! pipeline's phases and byte counts as they happen.
+
+use crate::indicator::{ProgressConfig, ProgressIndicator, ProgressRendererKind, ProgressStyle};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Stage of the install pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallPhase {
+    /// Looking up the package in the metadata cache, refreshing it if needed
+    Resolve,
+    /// Fetching the archive, or reusing a cached or kept one
+    Download,
+    /// Checking the archive against its published checksum
+    Verify,
+    /// Unpacking the archive into the staging directory
+    Extract,
+    /// Moving the JDK into place and recording metadata and shims
+    Finalize,
+}
+
+impl std::fmt::Display for InstallPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Resolve => write!(f, "resolve"),
+            Self::Download => write!(f, "download"),
+            Self::Verify => write!(f, "verify"),
+            Self::Extract => write!(f, "extract"),
+            Self::Finalize => write!(f, "finalize"),
+        }
+    }
+}
+
+/// Receives progress from an [`Installer`](super::Installer).
+///
+/// Every method does nothing by default, so an observer implements only what
+/// it presents. Verification and extraction overlap, so both phases can be
+/// active at once. A failed phase is never reported as finished; the error is
+/// returned by the installer. Callbacks run on the installing thread and
+/// should return quickly.
+pub trait InstallObserver: Send + Sync {
+    /// `phase` has begun
+    fn phase_started(&self, _phase: InstallPhase) {}
+
+    /// `phase` completed successfully
+    fn phase_finished(&self, _phase: InstallPhase) {}
+
+    /// `current` bytes of `total` have been downloaded or extracted in `phase`
+    fn bytes(&self, _phase: InstallPhase, _current: u64, _total: Option<u64>) {}
+}
+
+/// Observer used when the embedder did not register one
+pub(crate) struct NoopObserver;
+
+impl InstallObserver for NoopObserver {}
+
+/// Progress indicator that passes byte counts of the wrapped indicator, and
+/// of every child it creates, on to an observer
+pub(crate) struct ObservedProgress {
+    inner: Box<dyn ProgressIndicator>,
+    observer: Arc<dyn InstallObserver>,
+    phase: InstallPhase,
+    counts_bytes: bool,
+    total: Option<u64>,
+}
+
+impl ObservedProgress {
+    pub(crate) fn new(
+        inner: Box<dyn ProgressIndicator>,
+        observer: Arc<dyn InstallObserver>,
+        phase: InstallPhase,
+    ) -> Self {
+        Self {
+            inner,
+            observer,
+            phase,
+            counts_bytes: false,
+            total: None,
+        }
+    }
+}
+
+impl ProgressIndicator for ObservedProgress {
+    fn start(&mut self, config: ProgressConfig) {
+        self.counts_bytes = config.style == ProgressStyle::Bytes;
+        self.total = config.total;
+        self.inner.start(config);
+    }
+
+    fn update(&mut self, current: u64, total: Option<u64>) {
+        if self.counts_bytes {
+            if total.is_some() {
+                self.total = total;
+            }
+            self.observer.bytes(self.phase, current, self.total);
+        }
+        self.inner.update(current, total);
+    }
+
+    fn set_message(&mut self, message: String) {
+        self.inner.set_message(message);
+    }
+
+    fn complete(&mut self, message: Option<String>) {
+        self.inner.complete(message);
+    }
+
+    fn success(&self, message: &str) -> std::io::Result<()> {
+        self.inner.success(message)
+    }
+
+    fn error(&mut self, message: String) {
+        self.inner.error(message);
+    }
+
+    fn create_child(&mut self) -> Box<dyn ProgressIndicator> {
+        Box::new(ObservedProgress::new(
+            self.inner.create_child(),
+            self.observer.clone(),
+            self.phase,
+        ))
+    }
+
+    fn suspend(&self, f: &mut dyn FnMut()) {
+        self.inner.suspend(f);
+    }
+
+    fn println(&self, message: &str) -> std::io::Result<()> {
+        self.inner.println(message)
+    }
+
+    fn renderer_kind(&self) -> ProgressRendererKind {
+        self.inner.renderer_kind()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicator::SilentProgress;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        bytes: Mutex<Vec<(InstallPhase, u64, Option<u64>)>>,
+    }
+
+    impl InstallObserver for Recorder {
+        fn bytes(&self, phase: InstallPhase, current: u64, total: Option<u64>) {
+            self.bytes.lock().unwrap().push((phase, current, total));
+        }
+    }
+
+    #[test]
+    fn observed_progress_reports_bytes_from_children() {
+        let recorder = Arc::new(Recorder::default());
+        let mut progress = ObservedProgress::new(
+            Box::new(SilentProgress),
+            recorder.clone(),
+            InstallPhase::Download,
+        );
+
+        // The downloader reports bytes on a child of the indicator it is given
+        let mut child = progress.create_child();
+        child.start(ProgressConfig::new(ProgressStyle::Bytes).with_total(100));
+        child.update(40, None);
+        child.update(100, None);
+
+        assert_eq!(
+            *recorder.bytes.lock().unwrap(),
+            vec![
+                (InstallPhase::Download, 40, Some(100)),
+                (InstallPhase::Download, 100, Some(100)),
+            ]
+        );
+    }
+
+    #[test]
+    fn observed_progress_ignores_step_counts() {
+        let recorder = Arc::new(Recorder::default());
+        let mut progress = ObservedProgress::new(
+            Box::new(SilentProgress),
+            recorder.clone(),
+            InstallPhase::Extract,
+        );
+
+        // Zip extraction counts entries, which are not bytes
+        progress.start(ProgressConfig::new(ProgressStyle::Count).with_total(3));
+        progress.update(1, Some(3));
+
+        assert!(recorder.bytes.lock().unwrap().is_empty());
+    }
+}
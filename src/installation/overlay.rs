@@ -0,0 +1,576 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Certificates and security properties applied to every installed JDK.
+//!
+//! `install.certificates` are imported into the JDK's `cacerts` with its own
+//! `keytool` under `kopi-` aliases, and `install.security_properties` go into
+//! a marked block at the end of its `java.security`. The installer applies the
+//! overlay before a JDK is moved into place, so reinstalling or upgrading
+//! keeps imported certificates. Applying an overlay replaces whatever an
+//! earlier one added, so `kopi overlay apply` can be repeated after the
+//! configuration changes.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use log::debug;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefix of the `cacerts` aliases kopi manages
+pub const ALIAS_PREFIX: &str = "kopi-";
+
+const BLOCK_BEGIN: &str = "# BEGIN kopi install.security_properties";
+const BLOCK_END: &str = "# END kopi install.security_properties";
+const DEFAULT_TRUSTSTORE_PASSWORD: &str = "changeit";
+
+/// Where `java.security` lives in JDK 9+ and in JDK 8 images
+const JAVA_SECURITY_CANDIDATES: &[&str] = &[
+    "conf/security/java.security",
+    "jre/lib/security/java.security",
+    "lib/security/java.security",
+];
+const CACERTS_CANDIDATES: &[&str] = &["lib/security/cacerts", "jre/lib/security/cacerts"];
+
+/// Extra trust and security settings for the JDKs of one distribution
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Overlay {
+    /// PEM or DER certificate files, as absolute paths
+    pub certificates: Vec<PathBuf>,
+    pub security_properties: BTreeMap<String, String>,
+    pub truststore_password: String,
+}
+
+impl Overlay {
+    /// The `[install]` overlay for `distribution`: global certificates plus
+    /// the distribution's own, and global properties overridden by the
+    /// distribution's
+    pub fn for_distribution(config: &KopiConfig, distribution: &str) -> Self {
+        let install = &config.install;
+        let specific = install.distributions.get(distribution);
+
+        let certificates = install
+            .certificates
+            .iter()
+            .chain(specific.into_iter().flat_map(|dist| &dist.certificates))
+            .map(|path| config.kopi_home().join(path))
+            .collect();
+        let mut security_properties = install.security_properties.clone();
+        if let Some(dist) = specific {
+            security_properties.extend(dist.security_properties.clone());
+        }
+
+        Self {
+            certificates,
+            security_properties,
+            truststore_password: install
+                .truststore_password
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TRUSTSTORE_PASSWORD.to_string()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.certificates.is_empty() && self.security_properties.is_empty()
+    }
+}
+
+/// What applying an overlay changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OverlaySummary {
+    /// Aliases imported into `cacerts`
+    pub certificates: Vec<String>,
+    /// Properties written to `java.security`
+    pub properties: usize,
+}
+
+/// Bring the JDK at `java_home` in line with `overlay`, removing
+/// certificates and properties an earlier overlay added that it no longer
+/// lists
+pub fn apply_overlay(java_home: &Path, overlay: &Overlay) -> Result<OverlaySummary> {
+    let mut summary = OverlaySummary::default();
+
+    match find_file(java_home, JAVA_SECURITY_CANDIDATES) {
+        Some(path) => {
+            let content = fs::read_to_string(&path)?;
+            let updated = with_properties_block(&content, &overlay.security_properties);
+            if updated != content {
+                fs::write(&path, updated)?;
+            }
+            summary.properties = overlay.security_properties.len();
+        }
+        None if !overlay.security_properties.is_empty() => {
+            return Err(KopiError::ValidationError(format!(
+                "No java.security found in {}; cannot apply install.security_properties",
+                java_home.display()
+            )));
+        }
+        None => {}
+    }
+
+    let cacerts = find_file(java_home, CACERTS_CANDIDATES);
+    let keytool = find_keytool(java_home);
+    let (cacerts, keytool) = match (cacerts, keytool) {
+        (Some(cacerts), Some(keytool)) => (cacerts, keytool),
+        _ if overlay.certificates.is_empty() => return Ok(summary),
+        (None, _) => {
+            return Err(KopiError::ValidationError(format!(
+                "No cacerts truststore found in {}; cannot import install.certificates",
+                java_home.display()
+            )));
+        }
+        (_, None) => {
+            return Err(KopiError::ValidationError(format!(
+                "No keytool found in {}; cannot import install.certificates",
+                java_home.display()
+            )));
+        }
+    };
+
+    let store = Truststore {
+        keytool,
+        cacerts,
+        password: &overlay.truststore_password,
+    };
+    let stale = store.managed_aliases()?;
+    if stale.is_empty() && overlay.certificates.is_empty() {
+        return Ok(summary);
+    }
+    store.make_private()?;
+    for alias in stale {
+        store.run(&["-delete", "-alias", &alias])?;
+    }
+
+    let staging = tempfile::tempdir()?;
+    for certificate in &overlay.certificates {
+        let content = fs::read(certificate).map_err(|e| {
+            KopiError::ConfigError(format!(
+                "Cannot read install.certificates entry {}: {e}",
+                certificate.display()
+            ))
+        })?;
+        let blocks = pem_certificates(&String::from_utf8_lossy(&content));
+        let base = alias_for(certificate);
+
+        if blocks.is_empty() {
+            // Not PEM; let keytool read it as DER
+            store.import(&base, certificate)?;
+            summary.certificates.push(base);
+            continue;
+        }
+        for (index, block) in blocks.iter().enumerate() {
+            let alias = if blocks.len() == 1 {
+                base.clone()
+            } else {
+                format!("{base}-{}", index + 1)
+            };
+            let file = staging.path().join(format!("{alias}.pem"));
+            fs::write(&file, block)?;
+            store.import(&alias, &file)?;
+            summary.certificates.push(alias);
+        }
+    }
+
+    debug!(
+        "Applied overlay to {}: certificates {:?}, {} properties",
+        java_home.display(),
+        summary.certificates,
+        summary.properties
+    );
+    Ok(summary)
+}
+
+/// A JDK's `cacerts` and the `keytool` that edits it
+struct Truststore<'a> {
+    keytool: PathBuf,
+    cacerts: PathBuf,
+    password: &'a str,
+}
+
+impl Truststore<'_> {
+    /// Replace a symlinked `cacerts` (as in distribution packages that share
+    /// the system truststore) with a copy, and make it writable, so the
+    /// overlay only changes this JDK
+    fn make_private(&self) -> Result<()> {
+        if fs::symlink_metadata(&self.cacerts)?
+            .file_type()
+            .is_symlink()
+        {
+            let content = fs::read(&self.cacerts)?;
+            fs::remove_file(&self.cacerts)?;
+            fs::write(&self.cacerts, content)?;
+        }
+
+        let mut permissions = fs::metadata(&self.cacerts)?.permissions();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            permissions.set_mode(permissions.mode() | 0o200);
+        }
+        #[cfg(not(unix))]
+        permissions.set_readonly(false);
+        fs::set_permissions(&self.cacerts, permissions)?;
+        Ok(())
+    }
+
+    fn managed_aliases(&self) -> Result<Vec<String>> {
+        let listing = self.run(&["-list"])?;
+        Ok(listing
+            .lines()
+            .filter_map(|line| line.split_once(','))
+            .map(|(alias, _)| alias.trim().to_string())
+            .filter(|alias| alias.starts_with(ALIAS_PREFIX))
+            .collect())
+    }
+
+    fn import(&self, alias: &str, file: &Path) -> Result<()> {
+        let file = file.to_string_lossy();
+        self.run(&["-importcert", "-noprompt", "-alias", alias, "-file", &file])?;
+        Ok(())
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new(&self.keytool)
+            // Keep the output parseable whatever the user's locale
+            .args(["-J-Duser.language=en", "-J-Duser.country=US"])
+            .args(args)
+            .arg("-keystore")
+            .arg(&self.cacerts)
+            .args(["-storepass", self.password])
+            .output()
+            .map_err(|e| {
+                KopiError::SystemError(format!("Failed to run {}: {e}", self.keytool.display()))
+            })?;
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        if output.status.success() {
+            Ok(stdout)
+        } else {
+            Err(KopiError::SystemError(format!(
+                "keytool {} failed for {}: {}",
+                args[0],
+                self.cacerts.display(),
+                format!("{stdout}{}", String::from_utf8_lossy(&output.stderr)).trim()
+            )))
+        }
+    }
+}
+
+fn find_file(java_home: &Path, candidates: &[&str]) -> Option<PathBuf> {
+    candidates
+        .iter()
+        .map(|candidate| java_home.join(candidate))
+        .find(|path| path.exists())
+}
+
+fn find_keytool(java_home: &Path) -> Option<PathBuf> {
+    let name = if cfg!(windows) {
+        "keytool.exe"
+    } else {
+        "keytool"
+    };
+    ["bin", "jre/bin"]
+        .iter()
+        .map(|dir| java_home.join(dir).join(name))
+        .find(|path| path.is_file())
+}
+
+/// `kopi-<file stem>` with characters keystores may mangle replaced
+fn alias_for(certificate: &Path) -> String {
+    let stem = certificate
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_ascii_lowercase();
+    let stem: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{ALIAS_PREFIX}{stem}")
+}
+
+/// The `CERTIFICATE` blocks of a PEM file, so bundles import every
+/// certificate rather than only the first
+fn pem_certificates(content: &str) -> Vec<String> {
+    const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+    const END: &str = "-----END CERTIFICATE-----";
+
+    let mut blocks = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(BEGIN) {
+        let Some(length) = rest[start..].find(END) else {
+            break;
+        };
+        let end = start + length + END.len();
+        blocks.push(format!("{}\n", &rest[start..end]));
+        rest = &rest[end..];
+    }
+    blocks
+}
+
+/// `content` with kopi's block of `properties` replacing any earlier one;
+/// later entries win when `java.security` is loaded, so the block goes last
+fn with_properties_block(content: &str, properties: &BTreeMap<String, String>) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut inside = false;
+    for line in content.lines() {
+        match line.trim() {
+            BLOCK_BEGIN => inside = true,
+            BLOCK_END if inside => inside = false,
+            _ if inside => {}
+            _ => {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+    }
+
+    if properties.is_empty() {
+        return output;
+    }
+    output.push_str(BLOCK_BEGIN);
+    output.push('\n');
+    for (key, value) in properties {
+        // Backslashes are escapes in the properties format
+        output.push_str(&format!("{key}={}\n", value.replace('\\', "\\\\")));
+    }
+    output.push_str(BLOCK_END);
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_overlay_for_distribution() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        assert!(Overlay::for_distribution(&config, "temurin").is_empty());
+
+        config.install.certificates = vec![PathBuf::from("certs/corp.pem")];
+        config.install.security_properties = BTreeMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ]);
+        let zulu = config
+            .install
+            .distributions
+            .entry("zulu".to_string())
+            .or_default();
+        zulu.certificates = vec![PathBuf::from("/etc/zulu.pem")];
+        zulu.security_properties = BTreeMap::from([("b".to_string(), "3".to_string())]);
+
+        let overlay = Overlay::for_distribution(&config, "zulu");
+        assert_eq!(
+            overlay.certificates,
+            [
+                temp_dir.path().join("certs/corp.pem"),
+                PathBuf::from("/etc/zulu.pem")
+            ]
+        );
+        assert_eq!(overlay.security_properties["b"], "3");
+        assert_eq!(overlay.truststore_password, "changeit");
+        assert_eq!(
+            Overlay::for_distribution(&config, "temurin").security_properties["b"],
+            "2"
+        );
+    }
+
+    #[test]
+    fn test_properties_block_is_replaced() {
+        let properties = BTreeMap::from([
+            (
+                "jdk.tls.disabledAlgorithms".to_string(),
+                "SSLv3, TLSv1".to_string(),
+            ),
+            ("x".to_string(), r"C:\certs".to_string()),
+        ]);
+        let once = with_properties_block("a=1\nb=2\n", &properties);
+        assert_eq!(
+            once,
+            "a=1\nb=2\n# BEGIN kopi install.security_properties\n\
+             jdk.tls.disabledAlgorithms=SSLv3, TLSv1\nx=C:\\\\certs\n\
+             # END kopi install.security_properties\n"
+        );
+        assert_eq!(with_properties_block(&once, &properties), once);
+        assert_eq!(with_properties_block(&once, &BTreeMap::new()), "a=1\nb=2\n");
+    }
+
+    #[test]
+    fn test_pem_certificates_and_aliases() {
+        let bundle = "subject=CN=Root\n-----BEGIN CERTIFICATE-----\nAAA\n-----END CERTIFICATE-----\n\
+                      -----BEGIN CERTIFICATE-----\nBBB\n-----END CERTIFICATE-----\n";
+        assert_eq!(
+            pem_certificates(bundle),
+            [
+                "-----BEGIN CERTIFICATE-----\nAAA\n-----END CERTIFICATE-----\n",
+                "-----BEGIN CERTIFICATE-----\nBBB\n-----END CERTIFICATE-----\n",
+            ]
+        );
+        assert!(pem_certificates("binary").is_empty());
+        assert_eq!(
+            alias_for(Path::new("/etc/Corp Root CA.pem")),
+            "kopi-corp-root-ca"
+        );
+    }
+
+    #[test]
+    fn test_apply_overlay_requires_targets() {
+        let java_home = TempDir::new().unwrap();
+        let overlay = Overlay {
+            security_properties: BTreeMap::from([("a".to_string(), "1".to_string())]),
+            ..Overlay::default()
+        };
+        assert!(matches!(
+            apply_overlay(java_home.path(), &overlay),
+            Err(KopiError::ValidationError(_))
+        ));
+        assert_eq!(
+            apply_overlay(java_home.path(), &Overlay::default()).unwrap(),
+            OverlaySummary::default()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_overlay_imports_into_a_private_cacerts() {
+        // Needs a real keytool to edit the truststore
+        let Some(keytool) = which::which("keytool")
+            .ok()
+            .and_then(|path| path.canonicalize().ok())
+        else {
+            return;
+        };
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let keytool_run = |args: &[&str]| {
+            let status = Command::new(&keytool)
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success(), "keytool {args:?}");
+        };
+
+        let ca_store = root.join("ca.p12").to_string_lossy().into_owned();
+        let pem = root.join("Corp Root.pem");
+        let pem_arg = pem.to_string_lossy().into_owned();
+        keytool_run(&[
+            "-genkeypair",
+            "-alias",
+            "ca",
+            "-keyalg",
+            "RSA",
+            "-dname",
+            "CN=Corp Root",
+            "-validity",
+            "2",
+            "-storetype",
+            "PKCS12",
+            "-keystore",
+            &ca_store,
+            "-storepass",
+            "secret123",
+        ]);
+        keytool_run(&[
+            "-exportcert",
+            "-rfc",
+            "-alias",
+            "ca",
+            "-keystore",
+            &ca_store,
+            "-storepass",
+            "secret123",
+            "-file",
+            &pem_arg,
+        ]);
+
+        // A shared system truststore the JDK links to
+        let shared = root.join("shared-cacerts");
+        let shared_arg = shared.to_string_lossy().into_owned();
+        keytool_run(&[
+            "-importcert",
+            "-noprompt",
+            "-alias",
+            "system",
+            "-file",
+            &pem_arg,
+            "-keystore",
+            &shared_arg,
+            "-storepass",
+            "changeit",
+        ]);
+        let shared_before = fs::read(&shared).unwrap();
+
+        let java_home = root.join("jdk");
+        fs::create_dir_all(java_home.join("bin")).unwrap();
+        fs::create_dir_all(java_home.join("lib/security")).unwrap();
+        fs::create_dir_all(java_home.join("conf/security")).unwrap();
+        std::os::unix::fs::symlink(&keytool, java_home.join("bin/keytool")).unwrap();
+        std::os::unix::fs::symlink(&shared, java_home.join("lib/security/cacerts")).unwrap();
+        fs::write(java_home.join("conf/security/java.security"), "a=1\n").unwrap();
+
+        let overlay = Overlay {
+            certificates: vec![pem.clone()],
+            security_properties: BTreeMap::from([("b".to_string(), "2".to_string())]),
+            truststore_password: "changeit".to_string(),
+        };
+        let summary = apply_overlay(&java_home, &overlay).unwrap();
+        assert_eq!(summary.certificates, ["kopi-corp-root"]);
+        assert_eq!(summary.properties, 1);
+
+        let cacerts = java_home.join("lib/security/cacerts");
+        assert!(
+            !fs::symlink_metadata(&cacerts)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read(&shared).unwrap(), shared_before);
+        let store = Truststore {
+            keytool: java_home.join("bin/keytool"),
+            cacerts,
+            password: "changeit",
+        };
+        assert_eq!(store.managed_aliases().unwrap(), ["kopi-corp-root"]);
+        assert!(
+            fs::read_to_string(java_home.join("conf/security/java.security"))
+                .unwrap()
+                .contains("\nb=2\n")
+        );
+
+        // Dropping the configuration removes what the overlay added
+        let empty = Overlay {
+            truststore_password: "changeit".to_string(),
+            ..Overlay::default()
+        };
+        apply_overlay(&java_home, &empty).unwrap();
+        assert!(store.managed_aliases().unwrap().is_empty());
+        assert_eq!(
+            fs::read_to_string(java_home.join("conf/security/java.security")).unwrap(),
+            "a=1\n"
+        );
+    }
+}
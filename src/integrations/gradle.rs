@@ -0,0 +1,154 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gradle toolchain integration.
+//!
+//! Gradle's toolchain support only auto-detects JDKs in locations it knows
+//! about, so kopi lists its JDK homes in `org.gradle.java.installations.paths`
+//! of the user-wide `gradle.properties`. Paths outside the kopi JDK
+//! directory and every other line of the file are left untouched.
+
+use crate::error::{KopiError, Result};
+use crate::integrations::portable_path;
+use crate::storage::InstalledJdk;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Property listing extra JDK installations for toolchain detection
+pub const INSTALLATIONS_PATHS_KEY: &str = "org.gradle.java.installations.paths";
+const PROPERTIES_FILE: &str = "gradle.properties";
+const USER_HOME_ENV: &str = "GRADLE_USER_HOME";
+
+/// `gradle.properties` of the Gradle user home (`GRADLE_USER_HOME`, or
+/// `~/.gradle`)
+pub fn user_properties_path() -> Result<PathBuf> {
+    let gradle_home = match env::var_os(USER_HOME_ENV) {
+        Some(home) if !home.is_empty() => PathBuf::from(home),
+        _ => dirs::home_dir()
+            .ok_or_else(|| KopiError::ConfigError("Cannot determine home directory".to_string()))?
+            .join(".gradle"),
+    };
+    Ok(gradle_home.join(PROPERTIES_FILE))
+}
+
+/// Whether `path` lists installations kopi should keep up to date
+pub fn is_exported(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| find_property(&content).is_some())
+}
+
+/// Point Gradle's toolchain detection at `jdks`, replacing the kopi paths
+/// previously written. Returns whether the file changed.
+pub fn export_jdks(path: &Path, jdks: &[InstalledJdk], jdks_root: &Path) -> Result<bool> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let homes: Vec<String> = jdks
+        .iter()
+        .map(|jdk| portable_path(&jdk.resolve_java_home()))
+        .collect();
+    let updated = update_properties(&content, &homes, &portable_path(jdks_root));
+    if updated == content {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, updated)?;
+    log::debug!("Wrote Gradle toolchain paths to {path:?}");
+    Ok(true)
+}
+
+fn update_properties(content: &str, homes: &[String], jdks_root: &str) -> String {
+    let Some((start, end, value)) = find_property(content) else {
+        if homes.is_empty() {
+            return content.to_string();
+        }
+        let mut updated = content.to_string();
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str(&format!("{INSTALLATIONS_PATHS_KEY}={}\n", homes.join(",")));
+        return updated;
+    };
+
+    let kopi_prefix = format!("{}/", jdks_root.trim_end_matches('/'));
+    let mut paths: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty() && !path.starts_with(&kopi_prefix))
+        .map(str::to_string)
+        .collect();
+    paths.extend(homes.iter().cloned());
+
+    let line = format!("{INSTALLATIONS_PATHS_KEY}={}", paths.join(","));
+    format!("{}{line}{}", &content[..start], &content[end..])
+}
+
+/// Byte range and value of the `org.gradle.java.installations.paths` line
+fn find_property(content: &str) -> Option<(usize, usize, &str)> {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let text = line.trim_end_matches(['\r', '\n']);
+        let Some(rest) = text.trim_start().strip_prefix(INSTALLATIONS_PATHS_KEY) else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        if let Some(value) = rest.strip_prefix(['=', ':']) {
+            return Some((start, start + text.len(), value.trim()));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_properties_keeps_foreign_paths() {
+        let homes = vec!["/home/u/.kopi/jdks/temurin-21.0.5".to_string()];
+        let content = "org.gradle.jvmargs=-Xmx2g\n\
+                       org.gradle.java.installations.paths=/opt/jdk-17,/home/u/.kopi/jdks/zulu-11\n\
+                       org.gradle.parallel=true\n";
+
+        let updated = update_properties(content, &homes, "/home/u/.kopi/jdks");
+        assert_eq!(
+            updated,
+            "org.gradle.jvmargs=-Xmx2g\n\
+             org.gradle.java.installations.paths=/opt/jdk-17,/home/u/.kopi/jdks/temurin-21.0.5\n\
+             org.gradle.parallel=true\n"
+        );
+        assert_eq!(
+            update_properties(&updated, &homes, "/home/u/.kopi/jdks"),
+            updated
+        );
+    }
+
+    #[test]
+    fn test_update_properties_appends_missing_key() {
+        let homes = vec!["/k/jdks/a".to_string(), "/k/jdks/b".to_string()];
+        assert_eq!(
+            update_properties("org.gradle.daemon=false", &homes, "/k/jdks"),
+            "org.gradle.daemon=false\norg.gradle.java.installations.paths=/k/jdks/a,/k/jdks/b\n"
+        );
+        assert_eq!(update_properties("", &[], "/k/jdks"), "");
+    }
+}
@@ -0,0 +1,491 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! IntelliJ IDEA integration.
+//!
+//! Kopi JDKs are added to the IDE-wide SDK table (`options/jdk.table.xml`
+//! in each IntelliJ IDEA configuration directory) under names starting with
+//! `kopi-`, and a project's `.idea/misc.xml` can be pointed at the pinned
+//! JDK. Both files are edited as text so that everything kopi does not own,
+//! including SDKs added by hand, is left byte-for-byte intact.
+
+use crate::error::{KopiError, Result};
+use crate::integrations::portable_path;
+use crate::integrations::xml::{line_start, xml_escape};
+use crate::storage::{InstalledJdk, ReleaseInfo};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix of every SDK name kopi writes to the JDK table
+pub const SDK_PREFIX: &str = "kopi-";
+
+/// Configuration directory names of IntelliJ IDEA Ultimate and Community
+const PRODUCT_PREFIXES: [&str; 2] = ["IntelliJIdea", "IdeaIC"];
+const OPTIONS_DIR: &str = "options";
+const JDK_TABLE_FILE: &str = "jdk.table.xml";
+const IDEA_DIR: &str = ".idea";
+const MISC_FILE: &str = "misc.xml";
+
+const JDK_TABLE_COMPONENT: &str = r#"<component name="ProjectJdkTable""#;
+const ROOT_MANAGER_COMPONENT: &str = r#"<component name="ProjectRootManager""#;
+
+/// JetBrains configuration root: `~/.config/JetBrains` on Linux,
+/// `~/Library/Application Support/JetBrains` on macOS and
+/// `%APPDATA%\JetBrains` on Windows
+pub fn config_root() -> Result<PathBuf> {
+    dirs::config_dir()
+        .map(|dir| dir.join("JetBrains"))
+        .ok_or_else(|| {
+            KopiError::SystemError("Unable to locate the user configuration directory".to_string())
+        })
+}
+
+/// IntelliJ IDEA configuration directories under `root`, such as
+/// `IntelliJIdea2024.2` and `IdeaIC2024.1`, sorted by name
+pub fn config_dirs(root: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_idea = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                PRODUCT_PREFIXES.iter().any(|prefix| {
+                    name.strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+                })
+            });
+        if is_idea && path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// SDK name for `jdk`, such as `kopi-temurin-21.0.5+11`
+pub fn sdk_name(jdk: &InstalledJdk) -> String {
+    let suffix = if jdk.javafx_bundled { "-fx" } else { "" };
+    format!("{SDK_PREFIX}{}-{}{suffix}", jdk.distribution, jdk.version)
+}
+
+/// Replace the kopi SDKs in `config_dir`'s JDK table with `jdks`, returning
+/// the path of the table
+pub fn register_jdks(config_dir: &Path, jdks: &[InstalledJdk]) -> Result<PathBuf> {
+    let path = config_dir.join(OPTIONS_DIR).join(JDK_TABLE_FILE);
+    let content = read_optional(&path)?;
+    let updated = update_jdk_table(&content, jdks, &path)?;
+    write_file(&path, &updated)?;
+    Ok(path)
+}
+
+/// Point the project at `project_root` to `jdk`'s SDK, returning the path of
+/// the `.idea/misc.xml` that was written
+pub fn set_project_sdk(project_root: &Path, jdk: &InstalledJdk) -> Result<PathBuf> {
+    let path = project_root.join(IDEA_DIR).join(MISC_FILE);
+    let content = read_optional(&path)?;
+    let updated = update_misc(&content, jdk, &path)?;
+    write_file(&path, &updated)?;
+    Ok(path)
+}
+
+fn update_jdk_table(content: &str, jdks: &[InstalledJdk], path: &Path) -> Result<String> {
+    let mut document = if content.trim().is_empty() {
+        "<application>\n  <component name=\"ProjectJdkTable\">\n  </component>\n</application>\n"
+            .to_string()
+    } else {
+        remove_kopi_entries(content)
+    };
+
+    let entries: String = jdks.iter().map(jdk_entry).collect();
+
+    if let Some(start) = document.find(JDK_TABLE_COMPONENT) {
+        let tag_end = start + document[start..].find('>').ok_or_else(|| malformed(path))?;
+        if document[..tag_end].ends_with('/') {
+            // `<component name="ProjectJdkTable" />` holds no SDKs yet
+            let replacement = format!("{JDK_TABLE_COMPONENT}>\n{entries}  </component>");
+            document.replace_range(start..=tag_end, &replacement);
+        } else {
+            let close = tag_end
+                + document[tag_end..]
+                    .find("</component>")
+                    .ok_or_else(|| malformed(path))?;
+            let insert_at = line_start(&document, close);
+            document.insert_str(insert_at, &entries);
+        }
+    } else {
+        let close = document
+            .rfind("</application>")
+            .ok_or_else(|| malformed(path))?;
+        let insert_at = line_start(&document, close);
+        document.insert_str(
+            insert_at,
+            &format!("  {JDK_TABLE_COMPONENT}>\n{entries}  </component>\n"),
+        );
+    }
+    Ok(document)
+}
+
+/// Drop `<jdk>` blocks whose name starts with [`SDK_PREFIX`]
+fn remove_kopi_entries(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find("<jdk ").or_else(|| rest.find("<jdk>")) {
+        let Some(end) = rest[start..]
+            .find("</jdk>")
+            .map(|end| start + end + "</jdk>".len())
+        else {
+            break;
+        };
+
+        let block = &rest[start..end];
+        if attribute_value(block, "<name ", "value")
+            .is_some_and(|name| name.starts_with(SDK_PREFIX))
+        {
+            // Remove the whole line, including indentation and the newline
+            let line = line_start(rest, start);
+            result.push_str(&rest[..line]);
+            rest = rest[end..].strip_prefix('\n').unwrap_or(&rest[end..]);
+        } else {
+            result.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn jdk_entry(jdk: &InstalledJdk) -> String {
+    let java_home = jdk.resolve_java_home();
+    let home = xml_escape(&portable_path(&java_home));
+    let (class_roots, source_roots) = sdk_roots(&java_home, &home);
+
+    format!(
+        r#"    <jdk version="2">
+      <name value="{name}" />
+      <type value="JavaSDK" />
+      <version value="java version &quot;{version}&quot;" />
+      <homePath value="{home}" />
+      <roots>
+        <annotationsPath>
+          <root type="composite" />
+        </annotationsPath>
+        <classPath>
+{class_roots}        </classPath>
+        <javadocPath>
+          <root type="composite" />
+        </javadocPath>
+        <sourcePath>
+{source_roots}        </sourcePath>
+      </roots>
+      <additional />
+    </jdk>
+"#,
+        name = xml_escape(&sdk_name(jdk)),
+        version = xml_escape(&jdk.version.to_string()),
+    )
+}
+
+/// Class and source roots the way IntelliJ records them: one `jrt://` root
+/// per module for modular JDKs, the runtime jars for JDK 8
+fn sdk_roots(java_home: &Path, home: &str) -> (String, String) {
+    let modules = ReleaseInfo::read(java_home)
+        .map(|release| release.modules)
+        .unwrap_or_default();
+    let (classes, sources): (Vec<String>, Vec<String>) = if modules.is_empty() {
+        let jars = runtime_jars(java_home);
+        let src_zip = java_home.join("src.zip");
+        let sources = if src_zip.is_file() {
+            vec![format!("jar://{home}/src.zip!/")]
+        } else {
+            Vec::new()
+        };
+        (
+            jars.iter()
+                .map(|jar| format!("jar://{home}/jre/lib/{}!/", xml_escape(jar)))
+                .collect(),
+            sources,
+        )
+    } else {
+        let has_sources = java_home.join("lib").join("src.zip").is_file();
+        (
+            modules
+                .iter()
+                .map(|module| format!("jrt://{home}!/{module}"))
+                .collect(),
+            if has_sources {
+                modules
+                    .iter()
+                    .map(|module| format!("jar://{home}/lib/src.zip!/{module}"))
+                    .collect()
+            } else {
+                Vec::new()
+            },
+        )
+    };
+
+    (composite_root(&classes), composite_root(&sources))
+}
+
+fn composite_root(urls: &[String]) -> String {
+    if urls.is_empty() {
+        return "          <root type=\"composite\" />\n".to_string();
+    }
+    let mut root = "          <root type=\"composite\">\n".to_string();
+    for url in urls {
+        root.push_str(&format!(
+            "            <root url=\"{url}\" type=\"simple\" />\n"
+        ));
+    }
+    root.push_str("          </root>\n");
+    root
+}
+
+fn runtime_jars(java_home: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(java_home.join("jre").join("lib")) else {
+        return Vec::new();
+    };
+    let mut jars: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".jar"))
+        .collect();
+    jars.sort();
+    jars
+}
+
+fn update_misc(content: &str, jdk: &InstalledJdk, path: &Path) -> Result<String> {
+    let name = xml_escape(&sdk_name(jdk));
+
+    if content.trim().is_empty() {
+        return Ok(format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<project version=\"4\">\n  {}\n</project>\n",
+            root_manager(&name, jdk)
+        ));
+    }
+
+    let mut document = content.to_string();
+    if let Some(start) = document.find(ROOT_MANAGER_COMPONENT) {
+        let end = start + document[start..].find('>').ok_or_else(|| malformed(path))?;
+        let mut tag = document[start..end].to_string();
+        set_attribute(&mut tag, "project-jdk-name", &name);
+        set_attribute(&mut tag, "project-jdk-type", "JavaSDK");
+        document.replace_range(start..end, &tag);
+    } else {
+        let close = document
+            .rfind("</project>")
+            .ok_or_else(|| malformed(path))?;
+        let insert_at = line_start(&document, close);
+        document.insert_str(insert_at, &format!("  {}\n", root_manager(&name, jdk)));
+    }
+    Ok(document)
+}
+
+fn root_manager(name: &str, jdk: &InstalledJdk) -> String {
+    let major = jdk.version.major();
+    let language_level = if major <= 8 {
+        format!("JDK_1_{major}")
+    } else {
+        format!("JDK_{major}")
+    };
+    format!(
+        r#"{ROOT_MANAGER_COMPONENT} version="2" languageLevel="{language_level}" default="true" project-jdk-name="{name}" project-jdk-type="JavaSDK" />"#
+    )
+}
+
+/// Set `attribute` within a start tag (without its closing `>` or `/>`)
+fn set_attribute(tag: &mut String, attribute: &str, value: &str) {
+    let needle = format!(" {attribute}=\"");
+    if let Some(start) = tag.find(&needle).map(|pos| pos + needle.len())
+        && let Some(len) = tag[start..].find('"')
+    {
+        tag.replace_range(start..start + len, value);
+        return;
+    }
+
+    let insert_at = if tag.ends_with('/') {
+        tag.trim_end_matches('/').trim_end().len()
+    } else {
+        tag.trim_end().len()
+    };
+    tag.insert_str(insert_at, &format!(" {attribute}=\"{value}\""));
+}
+
+/// Value of `attribute` on the first `element` tag inside `xml`
+fn attribute_value<'a>(xml: &'a str, element: &str, attribute: &str) -> Option<&'a str> {
+    let tag_start = xml.find(element)?;
+    let tag = &xml[tag_start..tag_start + xml[tag_start..].find('>')?];
+    let needle = format!(" {attribute}=\"");
+    let start = tag.find(&needle)? + needle.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+fn malformed(path: &Path) -> KopiError {
+    KopiError::ConfigError(format!(
+        "Unrecognized IntelliJ IDEA configuration in {}",
+        path.display()
+    ))
+}
+
+fn read_optional(path: &Path) -> Result<String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    log::debug!("Wrote IntelliJ IDEA configuration to {path:?}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn installed(root: &Path, distribution: &str, version: &str) -> InstalledJdk {
+        let path = root.join(format!("{distribution}-{version}"));
+        fs::create_dir_all(path.join("bin")).unwrap();
+        fs::write(
+            path.join("release"),
+            "JAVA_VERSION=\"21.0.5\"\nMODULES=\"java.base java.sql\"\n",
+        )
+        .unwrap();
+        InstalledJdk::new(
+            distribution.to_string(),
+            Version::from_str(version).unwrap(),
+            path,
+            false,
+        )
+    }
+
+    const HAND_ADDED: &str = r#"    <jdk version="2">
+      <name value="corretto-17" />
+      <type value="JavaSDK" />
+      <homePath value="/opt/corretto-17" />
+    </jdk>
+"#;
+
+    #[test]
+    fn test_register_jdks_replaces_only_kopi_entries() {
+        let temp = TempDir::new().unwrap();
+        let config_dir = temp.path().join("IntelliJIdea2024.2");
+        let options = config_dir.join(OPTIONS_DIR);
+        fs::create_dir_all(&options).unwrap();
+        fs::write(
+            options.join(JDK_TABLE_FILE),
+            format!(
+                "<application>\n  <component name=\"ProjectJdkTable\">\n{HAND_ADDED}    <jdk \
+                 version=\"2\">\n      <name value=\"kopi-zulu-11.0.25\" />\n    </jdk>\n  \
+                 </component>\n</application>\n"
+            ),
+        )
+        .unwrap();
+
+        let jdk = installed(temp.path(), "temurin", "21.0.5+11");
+        let path = register_jdks(&config_dir, std::slice::from_ref(&jdk)).unwrap();
+        let table = fs::read_to_string(&path).unwrap();
+
+        assert!(table.contains(HAND_ADDED));
+        assert!(!table.contains("kopi-zulu-11.0.25"));
+        assert!(table.contains(r#"<name value="kopi-temurin-21.0.5+11" />"#));
+        let home = portable_path(&jdk.path);
+        assert!(table.contains(&format!(
+            r#"<root url="jrt://{home}!/java.sql" type="simple" />"#
+        )));
+        assert!(table.ends_with("  </component>\n</application>\n"));
+
+        // Registering again is idempotent
+        register_jdks(&config_dir, std::slice::from_ref(&jdk)).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), table);
+    }
+
+    #[test]
+    fn test_register_jdks_creates_table() {
+        let temp = TempDir::new().unwrap();
+        let jdk = installed(temp.path(), "temurin", "21.0.5");
+        let path = register_jdks(&temp.path().join("IdeaIC2024.1"), &[jdk]).unwrap();
+        let table = fs::read_to_string(path).unwrap();
+        assert!(
+            table.starts_with("<application>\n  <component name=\"ProjectJdkTable\">\n    <jdk")
+        );
+    }
+
+    #[test]
+    fn test_set_project_sdk() {
+        let temp = TempDir::new().unwrap();
+        let jdk = installed(temp.path(), "temurin", "21.0.5");
+        let project = temp.path().join("project");
+
+        let path = set_project_sdk(&project, &jdk).unwrap();
+        let misc = fs::read_to_string(&path).unwrap();
+        assert!(misc.contains(
+            r#"languageLevel="JDK_21" default="true" project-jdk-name="kopi-temurin-21.0.5" project-jdk-type="JavaSDK" />"#
+        ));
+
+        fs::write(
+            &path,
+            "<project version=\"4\">\n  <component name=\"ProjectRootManager\" version=\"2\" \
+             languageLevel=\"JDK_17\" project-jdk-name=\"17\">\n    <output url=\"file://$PROJECT_DIR$/out\" />\n  \
+             </component>\n</project>\n",
+        )
+        .unwrap();
+        set_project_sdk(&project, &jdk).unwrap();
+        let misc = fs::read_to_string(&path).unwrap();
+        assert!(misc.contains(
+            r#"languageLevel="JDK_17" project-jdk-name="kopi-temurin-21.0.5" project-jdk-type="JavaSDK">"#
+        ));
+        assert!(misc.contains("<output url=\"file://$PROJECT_DIR$/out\" />"));
+    }
+
+    #[test]
+    fn test_config_dirs_finds_idea_products() {
+        let temp = TempDir::new().unwrap();
+        for name in [
+            "IntelliJIdea2024.2",
+            "IdeaIC2023.3",
+            "PyCharm2024.1",
+            "IdeaICfoo",
+        ] {
+            fs::create_dir_all(temp.path().join(name)).unwrap();
+        }
+        let dirs = config_dirs(temp.path()).unwrap();
+        let names: Vec<_> = dirs
+            .iter()
+            .map(|dir| dir.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["IdeaIC2023.3", "IntelliJIdea2024.2"]);
+        assert!(
+            config_dirs(&temp.path().join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal editing of JSON with comments, as used by editor settings files.
+//!
+//! Only the top-level members of a document are located; a member's value is
+//! replaced or appended as text so comments and formatting elsewhere survive.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::ops::Range;
+
+/// A member of the top-level object
+#[derive(Debug)]
+pub struct Member {
+    pub key: String,
+    /// Byte offset of the opening quote of the key
+    pub key_start: usize,
+    /// Byte range of the value
+    pub value: Range<usize>,
+}
+
+/// Top-level object of a document
+#[derive(Debug)]
+pub struct Document {
+    pub members: Vec<Member>,
+    /// Byte offset of the opening `{`
+    pub open: usize,
+    /// Byte offset of the closing `}`
+    pub close: usize,
+}
+
+/// Locate the members of the top-level object in `text`
+pub fn parse(text: &str) -> Result<Document, String> {
+    let mut scanner = Scanner {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    scanner.skip_trivia()?;
+    if scanner.peek() != Some(b'{') {
+        return Err("expected a JSON object".to_string());
+    }
+    let open = scanner.pos;
+    scanner.pos += 1;
+
+    let mut members = Vec::new();
+    loop {
+        scanner.skip_trivia()?;
+        match scanner.peek() {
+            Some(b'}') => break,
+            Some(b',') => scanner.pos += 1,
+            Some(b'"') => {
+                let key_start = scanner.pos;
+                scanner.scan_string()?;
+                let key: String = serde_json::from_str(&text[key_start..scanner.pos])
+                    .map_err(|e| format!("invalid key: {e}"))?;
+                scanner.skip_trivia()?;
+                if scanner.peek() != Some(b':') {
+                    return Err(format!("expected ':' after \"{key}\""));
+                }
+                scanner.pos += 1;
+                scanner.skip_trivia()?;
+                let value_start = scanner.pos;
+                scanner.scan_value()?;
+                members.push(Member {
+                    key,
+                    key_start,
+                    value: value_start..scanner.pos,
+                });
+            }
+            Some(other) => {
+                return Err(format!(
+                    "unexpected '{}' at byte {}",
+                    other as char, scanner.pos
+                ));
+            }
+            None => return Err("unterminated object".to_string()),
+        }
+    }
+
+    Ok(Document {
+        members,
+        open,
+        close: scanner.pos,
+    })
+}
+
+/// Parse a JSON-with-comments fragment, ignoring comments and trailing commas
+pub fn parse_value(text: &str) -> Result<Value, String> {
+    serde_json::from_str(&strip(text)).map_err(|e| e.to_string())
+}
+
+/// Set top-level `key` to `value`, replacing an existing value in place or
+/// appending a new member after the last one
+pub fn set_member(text: &str, key: &str, value: &Value) -> Result<String, String> {
+    let document = parse(text)?;
+    let indent = member_indent(text, &document);
+    let rendered = render(value, &indent)?;
+
+    let mut updated = text.to_string();
+    if let Some(member) = document.members.iter().find(|member| member.key == key) {
+        updated.replace_range(member.value.clone(), &rendered);
+        return Ok(updated);
+    }
+
+    let key_json = serde_json::to_string(key).map_err(|e| e.to_string())?;
+    match document.members.last() {
+        Some(last) => {
+            updated.insert_str(
+                last.value.end,
+                &format!(",\n{indent}{key_json}: {rendered}"),
+            );
+        }
+        None => {
+            let inner = &text[document.open + 1..document.close];
+            let member = format!("\n{indent}{key_json}: {rendered}\n");
+            if inner.trim().is_empty() {
+                updated.replace_range(document.open + 1..document.close, &member);
+            } else {
+                // Only comments so far; keep them above the new member
+                updated.insert_str(document.close, &member);
+            }
+        }
+    }
+    Ok(updated)
+}
+
+/// Indentation of existing members, defaulting to four spaces like VS Code
+fn member_indent(text: &str, document: &Document) -> String {
+    document
+        .members
+        .last()
+        .and_then(|member| {
+            let line_start = text[..member.key_start].rfind('\n')? + 1;
+            let indent = &text[line_start..member.key_start];
+            indent
+                .chars()
+                .all(|c| c == ' ' || c == '\t')
+                .then(|| indent.to_string())
+        })
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or_else(|| "    ".to_string())
+}
+
+/// Pretty-print `value` for a member indented by `indent`
+fn render(value: &Value, indent: &str) -> Result<String, String> {
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    value
+        .serialize(&mut serializer)
+        .map_err(|e| e.to_string())?;
+    let rendered = String::from_utf8(buffer).map_err(|e| e.to_string())?;
+    Ok(rendered.replace('\n', &format!("\n{indent}")))
+}
+
+/// Remove comments and trailing commas so the text parses as plain JSON
+fn strip(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    // Position in `out` of a comma followed only by whitespace so far
+    let mut pending_comma: Option<usize> = None;
+
+    while pos < bytes.len() {
+        let rest = &text[pos..];
+        if rest.starts_with("//") {
+            pos += rest.find('\n').unwrap_or(rest.len());
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            pos += comment.find("*/").map_or(rest.len(), |end| end + 4);
+        } else if bytes[pos] == b'"' {
+            let mut scanner = Scanner { bytes, pos };
+            if scanner.scan_string().is_err() {
+                out.push_str(rest);
+                break;
+            }
+            pending_comma = None;
+            out.push_str(&text[pos..scanner.pos]);
+            pos = scanner.pos;
+        } else if bytes[pos] == b',' {
+            pending_comma = Some(out.len());
+            out.push(',');
+            pos += 1;
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            if c == '}' || c == ']' {
+                if let Some(comma) = pending_comma.take() {
+                    out.remove(comma);
+                }
+            } else if !c.is_whitespace() {
+                pending_comma = None;
+            }
+            out.push(c);
+            pos += c.len_utf8();
+        }
+    }
+    out
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            match (self.peek(), self.bytes.get(self.pos + 1)) {
+                (Some(b), _) if b.is_ascii_whitespace() => self.pos += 1,
+                (Some(b'/'), Some(b'/')) => {
+                    while self.peek().is_some_and(|b| b != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => {
+                    self.pos += 2;
+                    loop {
+                        match (self.peek(), self.bytes.get(self.pos + 1)) {
+                            (Some(b'*'), Some(b'/')) => {
+                                self.pos += 2;
+                                break;
+                            }
+                            (Some(_), _) => self.pos += 1,
+                            (None, _) => return Err("unterminated comment".to_string()),
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Advance past a string starting at the current `"`
+    fn scan_string(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'\\' => self.pos += 1,
+                b'"' => return Ok(()),
+                _ => {}
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+
+    /// Advance past one value of any kind
+    fn scan_value(&mut self) -> Result<(), String> {
+        match self.peek() {
+            Some(b'"') => self.scan_string(),
+            Some(b'{') | Some(b'[') => {
+                let mut depth = 0usize;
+                loop {
+                    self.skip_trivia()?;
+                    match self.peek() {
+                        Some(b'"') => self.scan_string()?,
+                        Some(b'{') | Some(b'[') => {
+                            depth += 1;
+                            self.pos += 1;
+                        }
+                        Some(b'}') | Some(b']') => {
+                            depth -= 1;
+                            self.pos += 1;
+                            if depth == 0 {
+                                return Ok(());
+                            }
+                        }
+                        Some(_) => self.pos += 1,
+                        None => return Err("unterminated value".to_string()),
+                    }
+                }
+            }
+            Some(_) => {
+                while self.peek().is_some_and(|b| {
+                    !b.is_ascii_whitespace() && !matches!(b, b',' | b'}' | b']' | b'/')
+                }) {
+                    self.pos += 1;
+                }
+                Ok(())
+            }
+            None => Err("missing value".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SETTINGS: &str = r#"{
+    // Editor settings
+    "editor.fontSize": 14,
+    /* Java */
+    "java.configuration.runtimes": [
+        { "name": "JavaSE-17", "path": "/opt/jdk-17", }, // hand-added
+    ],
+    "files.exclude": { "**/.git": true },
+}
+"#;
+
+    #[test]
+    fn test_parse_top_level_members() {
+        let document = parse(SETTINGS).unwrap();
+        let keys: Vec<_> = document.members.iter().map(|m| m.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "editor.fontSize",
+                "java.configuration.runtimes",
+                "files.exclude"
+            ]
+        );
+        assert_eq!(&SETTINGS[document.members[0].value.clone()], "14");
+        assert!(parse("[]").is_err());
+        assert!(parse("{ \"a\": ").is_err());
+    }
+
+    #[test]
+    fn test_parse_value_ignores_comments_and_trailing_commas() {
+        let document = parse(SETTINGS).unwrap();
+        let runtimes = parse_value(&SETTINGS[document.members[1].value.clone()]).unwrap();
+        assert_eq!(
+            runtimes,
+            json!([{ "name": "JavaSE-17", "path": "/opt/jdk-17" }])
+        );
+        assert_eq!(parse_value("\"a//b\"").unwrap(), json!("a//b"));
+    }
+
+    #[test]
+    fn test_set_member_preserves_comments() {
+        let updated = set_member(SETTINGS, "editor.fontSize", &json!(16)).unwrap();
+        assert!(updated.contains("// Editor settings\n    \"editor.fontSize\": 16,"));
+        assert!(updated.contains("// hand-added"));
+
+        let updated = set_member(SETTINGS, "java.jdt.ls.java.home", &json!("/jdk")).unwrap();
+        assert!(updated.contains(
+            "\"files.exclude\": { \"**/.git\": true },\n    \"java.jdt.ls.java.home\": \"/jdk\",\n}"
+        ));
+        assert_eq!(
+            parse_value(&updated).unwrap()["java.jdt.ls.java.home"],
+            "/jdk"
+        );
+    }
+
+    #[test]
+    fn test_set_member_in_empty_object() {
+        let updated = set_member("{}\n", "a", &json!([1, 2])).unwrap();
+        assert_eq!(
+            updated,
+            "{\n    \"a\": [\n        1,\n        2\n    ]\n}\n"
+        );
+    }
+}
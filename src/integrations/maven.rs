@@ -0,0 +1,185 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Maven toolchains integration.
+//!
+//! Kopi JDKs are written to `~/.m2/toolchains.xml` as `jdk` toolchains
+//! between two marker comments, so builds using the
+//! `maven-toolchains-plugin` (or the compiler's `jdkToolchain`) can select
+//! them by version and vendor. Everything outside the markers, including
+//! toolchains added by hand, is left byte-for-byte intact.
+
+use crate::error::{KopiError, Result};
+use crate::integrations::xml::{line_start, xml_escape};
+use crate::storage::InstalledJdk;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BEGIN_MARKER: &str = "<!-- kopi:begin - managed by kopi, edits are overwritten -->";
+const END_MARKER: &str = "<!-- kopi:end -->";
+const TOOLCHAINS_CLOSE: &str = "</toolchains>";
+
+/// `~/.m2/toolchains.xml`
+pub fn toolchains_path() -> Result<PathBuf> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| KopiError::ConfigError("Cannot determine home directory".to_string()))?;
+    Ok(home.join(".m2").join("toolchains.xml"))
+}
+
+/// Whether `path` holds toolchains kopi should keep up to date
+pub fn is_exported(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(BEGIN_MARKER))
+}
+
+/// Replace the kopi toolchains in `path` with `jdks`. Returns whether the
+/// file changed.
+pub fn export_jdks(path: &Path, jdks: &[InstalledJdk]) -> Result<bool> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let entries: String = jdks.iter().map(toolchain_entry).collect();
+    let updated = update_toolchains(&content, &entries, path)?;
+    if updated == content {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, updated)?;
+    log::debug!("Wrote Maven toolchains to {path:?}");
+    Ok(true)
+}
+
+fn update_toolchains(content: &str, entries: &str, path: &Path) -> Result<String> {
+    let block = format!("  {BEGIN_MARKER}\n{entries}  {END_MARKER}\n");
+
+    if content.trim().is_empty() {
+        return Ok(format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<toolchains>\n{block}{TOOLCHAINS_CLOSE}\n"
+        ));
+    }
+
+    if let Some(begin) = content.find(BEGIN_MARKER) {
+        let end = content[begin..]
+            .find(END_MARKER)
+            .map(|end| begin + end + END_MARKER.len())
+            .ok_or_else(|| malformed(path))?;
+        let start = line_start(content, begin);
+        let end = content[end..]
+            .find('\n')
+            .map_or(content.len(), |newline| end + newline + 1);
+        return Ok(format!("{}{block}{}", &content[..start], &content[end..]));
+    }
+
+    let close = content
+        .rfind(TOOLCHAINS_CLOSE)
+        .ok_or_else(|| malformed(path))?;
+    let insert_at = line_start(content, close);
+    let mut updated = content.to_string();
+    if insert_at == close && close > 0 && !content[..close].ends_with('\n') {
+        // `</toolchains>` shares its line with other content
+        updated.insert(close, '\n');
+        updated.insert_str(close + 1, &block);
+    } else {
+        updated.insert_str(insert_at, &block);
+    }
+    Ok(updated)
+}
+
+fn toolchain_entry(jdk: &InstalledJdk) -> String {
+    let home = xml_escape(&jdk.resolve_java_home().to_string_lossy());
+    let id = format!("kopi-{}-{}", jdk.distribution, jdk.version);
+    format!(
+        "  <toolchain>\n    <type>jdk</type>\n    <provides>\n      <version>{}</version>\n      \
+         <vendor>{}</vendor>\n      <id>{}</id>\n    </provides>\n    <configuration>\n      \
+         <jdkHome>{home}</jdkHome>\n    </configuration>\n  </toolchain>\n",
+        xml_escape(&jdk.version.to_string()),
+        xml_escape(&jdk.distribution),
+        xml_escape(&id),
+    )
+}
+
+fn malformed(path: &Path) -> KopiError {
+    KopiError::ConfigError(format!(
+        "Unrecognized Maven toolchains file {}",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn installed(root: &Path, distribution: &str, version: &str) -> InstalledJdk {
+        let path = root.join(format!("{distribution}-{version}"));
+        fs::create_dir_all(path.join("bin")).unwrap();
+        InstalledJdk::new(
+            distribution.to_string(),
+            Version::from_str(version).unwrap(),
+            path,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_export_jdks_keeps_foreign_toolchains() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("toolchains.xml");
+        let own =
+            "<toolchains>\n  <toolchain>\n    <type>jdk</type>\n  </toolchain>\n</toolchains>\n";
+        fs::write(&path, own).unwrap();
+
+        let temurin = installed(temp_dir.path(), "temurin", "21.0.5");
+        assert!(export_jdks(&path, std::slice::from_ref(&temurin)).unwrap());
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("<toolchains>\n  <toolchain>\n    <type>jdk</type>\n"));
+        assert!(content.contains("<vendor>temurin</vendor>"));
+        assert!(content.contains("<id>kopi-temurin-21.0.5</id>"));
+        assert!(is_exported(&path));
+
+        // Re-exporting replaces the managed block instead of adding another
+        assert!(!export_jdks(&path, std::slice::from_ref(&temurin)).unwrap());
+        let zulu = installed(temp_dir.path(), "zulu", "17.0.13");
+        assert!(export_jdks(&path, &[zulu]).unwrap());
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("temurin"));
+        assert_eq!(content.matches(BEGIN_MARKER).count(), 1);
+        assert!(content.ends_with(&format!("  {END_MARKER}\n</toolchains>\n")));
+    }
+
+    #[test]
+    fn test_export_jdks_creates_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".m2/toolchains.xml");
+        assert!(!is_exported(&path));
+
+        export_jdks(&path, &[]).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("<?xml"));
+        assert!(content.contains(&format!("<toolchains>\n  {BEGIN_MARKER}\n  {END_MARKER}\n")));
+
+        fs::write(&path, "<settings/>").unwrap();
+        assert!(matches!(
+            export_jdks(&path, &[]),
+            Err(KopiError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integrations that make kopi-managed JDKs visible to IDEs, editors, and
+//! build tools.
+
+pub mod gradle;
+pub mod intellij;
+pub(crate) mod jsonc;
+pub mod maven;
+pub mod vscode;
+pub(crate) mod xml;
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::paths::home;
+use crate::storage::{InstalledJdk, JdkRepository};
+use crate::version::resolver::{VersionResolver, VersionSource};
+use log::warn;
+use std::path::{Path, PathBuf};
+use vscode::TrackedSettings;
+
+/// A project's pinned JDK, resolved to an installation
+pub struct PinnedJdk {
+    pub jdk: InstalledJdk,
+    /// Directory holding the version file, or the starting directory when
+    /// the version came from the environment or the global default
+    pub project_root: PathBuf,
+}
+
+/// Resolve the JDK pinned for the project at `dir` to an installed JDK
+pub fn pinned_jdk(config: &KopiConfig, dir: &Path) -> Result<PinnedJdk> {
+    let (request, source) =
+        VersionResolver::with_dir(dir.to_path_buf(), config).resolve_version()?;
+    let project_root = match &source {
+        VersionSource::ProjectFile(path) | VersionSource::ToolOverride(path) => path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| dir.to_path_buf()),
+        _ => dir.to_path_buf(),
+    };
+
+    let jdk = JdkRepository::new(config)
+        .find_matching_jdks(&request)?
+        .pop()
+        .ok_or_else(|| KopiError::JdkNotInstalled {
+            jdk_spec: match &request.distribution {
+                Some(distribution) => format!("{distribution}@{}", request.version_pattern),
+                None => request.version_pattern.clone(),
+            },
+            version: Some(request.version_pattern.clone()),
+            distribution: request.distribution.clone(),
+            auto_install_enabled: false,
+            auto_install_failed: None,
+            user_declined: false,
+            install_in_progress: false,
+        })?;
+
+    Ok(PinnedJdk { jdk, project_root })
+}
+
+/// Bring integrated editor settings up to date after JDKs were installed or
+/// uninstalled. Problems are logged and never fail the command that ran.
+pub fn refresh_after_change(config: &KopiConfig) {
+    if let Err(e) = refresh_vscode(config) {
+        warn!("Failed to update VS Code settings: {e}");
+    }
+    if let Err(e) = refresh_toolchains(config) {
+        warn!("Failed to update build tool toolchains: {e}");
+    }
+}
+
+/// Rewrite the Gradle and Maven toolchain exports that were set up with
+/// `kopi integrate gradle` or `kopi integrate maven`
+pub fn refresh_toolchains(config: &KopiConfig) -> Result<()> {
+    let gradle_properties = gradle::user_properties_path()?;
+    let maven_toolchains = maven::toolchains_path()?;
+    let gradle_exported = gradle::is_exported(&gradle_properties);
+    let maven_exported = maven::is_exported(&maven_toolchains);
+    if !gradle_exported && !maven_exported {
+        return Ok(());
+    }
+
+    let jdks = JdkRepository::new(config).list_installed_jdks()?;
+    if gradle_exported {
+        let jdks_root = home::jdks_dir(config.kopi_home());
+        gradle::export_jdks(&gradle_properties, &jdks, &jdks_root)?;
+    }
+    if maven_exported {
+        maven::export_jdks(&maven_toolchains, &jdks)?;
+    }
+    Ok(())
+}
+
+/// Rewrite every tracked VS Code settings file, forgetting files that no
+/// longer exist, and return the ones that changed
+pub fn refresh_vscode(config: &KopiConfig) -> Result<Vec<PathBuf>> {
+    let kopi_home = config.kopi_home();
+    let mut tracked = TrackedSettings::load(kopi_home)?;
+    if tracked.vscode.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let tracked_count = tracked.vscode.len();
+    tracked.vscode.retain(|path| path.exists());
+    if tracked.vscode.len() != tracked_count {
+        tracked.save(kopi_home)?;
+    }
+
+    let jdks = JdkRepository::new(config).list_installed_jdks()?;
+    let jdks_root = home::jdks_dir(kopi_home);
+    let mut changed = Vec::new();
+    for path in &tracked.vscode {
+        let default = vscode_default_jdk(config, path);
+        if vscode::update_settings(path, &jdks, &jdks_root, default.as_ref())? {
+            changed.push(path.clone());
+        }
+    }
+    Ok(changed)
+}
+
+/// JDK marked as the default runtime in a VS Code settings file: the
+/// workspace's pinned JDK, or the global default for user settings
+pub fn vscode_default_jdk(config: &KopiConfig, settings: &Path) -> Option<InstalledJdk> {
+    let dir = match vscode::workspace_root(settings) {
+        Some(root) => root.to_path_buf(),
+        None => dirs::home_dir()?,
+    };
+    pinned_jdk(config, &dir).ok().map(|pinned| pinned.jdk)
+}
+
+/// Path in the forward-slash form IDE configuration files use on every platform
+pub(crate) fn portable_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
@@ -0,0 +1,348 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! VS Code integration.
+//!
+//! The Java extension maps execution environment names such as `JavaSE-21`
+//! to JDKs through `java.configuration.runtimes`, and runs its language
+//! server on `java.jdt.ls.java.home`. Kopi maintains the entries that point
+//! into its own JDK directory and leaves every other entry alone.
+
+use crate::error::{KopiError, Result};
+use crate::integrations::{jsonc, portable_path};
+use crate::paths::home;
+use crate::storage::InstalledJdk;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RUNTIMES_KEY: &str = "java.configuration.runtimes";
+const JDT_LS_HOME_KEY: &str = "java.jdt.ls.java.home";
+const VSCODE_DIR: &str = ".vscode";
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Oldest Java release the Java extension's language server runs on
+const JDT_LS_MIN_MAJOR: u32 = 21;
+
+/// User settings: `~/.config/Code/User/settings.json` on Linux,
+/// `~/Library/Application Support/Code/User/settings.json` on macOS and
+/// `%APPDATA%\Code\User\settings.json` on Windows
+pub fn user_settings_path() -> Result<PathBuf> {
+    dirs::config_dir()
+        .map(|dir| dir.join("Code").join("User").join(SETTINGS_FILE))
+        .ok_or_else(|| {
+            KopiError::SystemError("Unable to locate the user configuration directory".to_string())
+        })
+}
+
+/// Workspace settings of the project at `project_root`
+pub fn workspace_settings_path(project_root: &Path) -> PathBuf {
+    project_root.join(VSCODE_DIR).join(SETTINGS_FILE)
+}
+
+/// Workspace root of a `.vscode/settings.json`, `None` for user settings
+pub fn workspace_root(settings: &Path) -> Option<&Path> {
+    let vscode_dir = settings.parent()?;
+    (vscode_dir.file_name()? == VSCODE_DIR)
+        .then(|| vscode_dir.parent())
+        .flatten()
+}
+
+/// Execution environment name the Java extension uses for `major`
+pub fn runtime_name(major: u32) -> String {
+    if major <= 8 {
+        format!("JavaSE-1.{major}")
+    } else {
+        format!("JavaSE-{major}")
+    }
+}
+
+/// Rewrite the kopi-managed runtimes in the settings file at `path`.
+///
+/// Returns whether the file changed.
+pub fn update_settings(
+    path: &Path,
+    jdks: &[InstalledJdk],
+    jdks_root: &Path,
+    default: Option<&InstalledJdk>,
+) -> Result<bool> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let updated = update_settings_text(&content, jdks, jdks_root, default)
+        .map_err(|e| KopiError::ConfigError(format!("{}: {e}", path.display())))?;
+    if updated == content {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, updated)?;
+    log::debug!("Wrote VS Code settings to {path:?}");
+    Ok(true)
+}
+
+fn update_settings_text(
+    content: &str,
+    jdks: &[InstalledJdk],
+    jdks_root: &Path,
+    default: Option<&InstalledJdk>,
+) -> std::result::Result<String, String> {
+    let mut text = if content.trim().is_empty() {
+        "{\n}\n".to_string()
+    } else {
+        content.to_string()
+    };
+    let document = jsonc::parse(&text)?;
+    let current = |key: &str| -> std::result::Result<Option<Value>, String> {
+        document
+            .members
+            .iter()
+            .find(|member| member.key == key)
+            .map(|member| jsonc::parse_value(&text[member.value.clone()]))
+            .transpose()
+    };
+
+    let kopi_root = format!("{}/", portable_path(jdks_root));
+    let is_managed = |path: &str| path.replace('\\', "/").starts_with(&kopi_root);
+    let existing = current(RUNTIMES_KEY)?;
+    let jdt_ls_home = current(JDT_LS_HOME_KEY)?;
+
+    // Entries pointing elsewhere belong to the user and take precedence
+    let existing = match existing {
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(format!("{RUNTIMES_KEY} is not an array")),
+        None => Vec::new(),
+    };
+    let mut runtimes: Vec<Value> = existing
+        .into_iter()
+        .filter(|entry| {
+            !entry
+                .get("path")
+                .and_then(Value::as_str)
+                .is_some_and(is_managed)
+        })
+        .collect();
+    let user_names: Vec<String> = runtimes
+        .iter()
+        .filter_map(|entry| entry.get("name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+    let user_default = runtimes
+        .iter()
+        .any(|entry| entry.get("default").and_then(Value::as_bool) == Some(true));
+
+    for (major, jdk) in newest_per_major(jdks, default) {
+        let name = runtime_name(major);
+        if user_names.contains(&name) {
+            continue;
+        }
+        let mut entry = Map::new();
+        entry.insert("name".to_string(), json!(name));
+        entry.insert(
+            "path".to_string(),
+            json!(portable_path(&jdk.resolve_java_home())),
+        );
+        if !user_default && default.is_some_and(|default| default.path == jdk.path) {
+            entry.insert("default".to_string(), json!(true));
+        }
+        runtimes.push(Value::Object(entry));
+    }
+    text = jsonc::set_member(&text, RUNTIMES_KEY, &Value::Array(runtimes))?;
+
+    // The language server home is only replaced while kopi owns it
+    let owned = jdt_ls_home
+        .as_ref()
+        .is_none_or(|home| home.as_str().is_some_and(is_managed));
+    if owned
+        && let Some(jdk) = jdks
+            .iter()
+            .filter(|jdk| jdk.version.major() >= JDT_LS_MIN_MAJOR)
+            .max_by(|a, b| a.version.cmp(&b.version))
+    {
+        let home = json!(portable_path(&jdk.resolve_java_home()));
+        text = jsonc::set_member(&text, JDT_LS_HOME_KEY, &home)?;
+    }
+
+    Ok(text)
+}
+
+/// One JDK per feature release: `preferred` for its own release, otherwise
+/// the newest installed version
+fn newest_per_major<'a>(
+    jdks: &'a [InstalledJdk],
+    preferred: Option<&'a InstalledJdk>,
+) -> BTreeMap<u32, &'a InstalledJdk> {
+    let mut selected: BTreeMap<u32, &InstalledJdk> = BTreeMap::new();
+    for jdk in jdks {
+        let slot = selected.entry(jdk.version.major()).or_insert(jdk);
+        if jdk.version > slot.version {
+            *slot = jdk;
+        }
+    }
+    if let Some(preferred) = preferred {
+        selected.insert(preferred.version.major(), preferred);
+    }
+    selected
+}
+
+/// Settings files `kopi integrate vscode` has written, kept up to date when
+/// JDKs are installed or uninstalled
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TrackedSettings {
+    #[serde(default)]
+    pub vscode: Vec<PathBuf>,
+}
+
+impl TrackedSettings {
+    pub fn load(kopi_home: &Path) -> Result<Self> {
+        match fs::read_to_string(home::integrations_file(kopi_home)) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, kopi_home: &Path) -> Result<()> {
+        fs::write(
+            home::integrations_file(kopi_home),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// Remember `path`, returning whether it was new
+    pub fn track(&mut self, path: &Path) -> bool {
+        if self.vscode.iter().any(|tracked| tracked == path) {
+            return false;
+        }
+        self.vscode.push(path.to_path_buf());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn installed(root: &Path, distribution: &str, version: &str) -> InstalledJdk {
+        let path = root.join(format!("{distribution}-{version}"));
+        fs::create_dir_all(path.join("bin")).unwrap();
+        InstalledJdk::new(
+            distribution.to_string(),
+            Version::from_str(version).unwrap(),
+            path,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_runtime_names() {
+        assert_eq!(runtime_name(8), "JavaSE-1.8");
+        assert_eq!(runtime_name(21), "JavaSE-21");
+    }
+
+    #[test]
+    fn test_update_settings_maps_majors_and_keeps_user_entries() {
+        let temp = TempDir::new().unwrap();
+        let root = home::jdks_dir(temp.path());
+        let jdks = vec![
+            installed(&root, "temurin", "21.0.4"),
+            installed(&root, "temurin", "21.0.5"),
+            installed(&root, "zulu", "17.0.13"),
+            installed(&root, "temurin", "11.0.25"),
+        ];
+        let stale = portable_path(&root.join("temurin-22.0.2"));
+        let settings = format!(
+            r#"{{
+    // Keep this
+    "editor.tabSize": 4,
+    "java.configuration.runtimes": [
+        {{ "name": "JavaSE-11", "path": "/opt/jdk-11" }},
+        {{ "name": "JavaSE-22", "path": "{stale}" }},
+    ],
+}}
+"#
+        );
+
+        let updated = update_settings_text(&settings, &jdks, &root, Some(&jdks[2])).unwrap();
+        assert!(updated.contains("// Keep this"));
+        let value = jsonc::parse_value(&updated).unwrap();
+        assert_eq!(
+            value[RUNTIMES_KEY],
+            json!([
+                { "name": "JavaSE-11", "path": "/opt/jdk-11" },
+                { "name": "JavaSE-17", "path": portable_path(&jdks[2].path), "default": true },
+                { "name": "JavaSE-21", "path": portable_path(&jdks[1].path) },
+            ])
+        );
+        assert_eq!(value[JDT_LS_HOME_KEY], json!(portable_path(&jdks[1].path)));
+
+        // A second pass changes nothing
+        assert_eq!(
+            update_settings_text(&updated, &jdks, &root, Some(&jdks[2])).unwrap(),
+            updated
+        );
+    }
+
+    #[test]
+    fn test_update_settings_respects_user_language_server_home() {
+        let temp = TempDir::new().unwrap();
+        let root = home::jdks_dir(temp.path());
+        let jdks = vec![installed(&root, "temurin", "21.0.5")];
+        let settings = "{ \"java.jdt.ls.java.home\": \"/opt/jdk-21\" }";
+
+        let updated = update_settings_text(settings, &jdks, &root, None).unwrap();
+        let value = jsonc::parse_value(&updated).unwrap();
+        assert_eq!(value[JDT_LS_HOME_KEY], json!("/opt/jdk-21"));
+        assert_eq!(value[RUNTIMES_KEY][0]["name"], json!("JavaSE-21"));
+    }
+
+    #[test]
+    fn test_update_settings_creates_file_and_tracks() {
+        let temp = TempDir::new().unwrap();
+        let root = home::jdks_dir(temp.path());
+        let jdks = vec![installed(&root, "temurin", "17.0.13")];
+        let path = workspace_settings_path(&temp.path().join("project"));
+
+        assert!(update_settings(&path, &jdks, &root, None).unwrap());
+        assert!(!update_settings(&path, &jdks, &root, None).unwrap());
+        assert_eq!(
+            workspace_root(&path),
+            Some(temp.path().join("project").as_path())
+        );
+        assert_eq!(
+            workspace_root(Path::new("/home/u/.config/Code/User/settings.json")),
+            None
+        );
+
+        let mut tracked = TrackedSettings::load(temp.path()).unwrap();
+        assert!(tracked.track(&path));
+        assert!(!tracked.track(&path));
+        tracked.save(temp.path()).unwrap();
+        assert_eq!(
+            TrackedSettings::load(temp.path()).unwrap().vscode,
+            vec![path]
+        );
+    }
+}
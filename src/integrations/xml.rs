@@ -0,0 +1,51 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Text helpers for the XML files kopi edits in place: Maven toolchains,
+//! IntelliJ SDK tables and macOS `Info.plist` files.
+
+/// Escape `value` for use in XML text or a double-quoted attribute
+pub(crate) fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Byte offset of the start of the line containing `pos`
+pub(crate) fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            xml_escape(r#"C:\Program Files\<A & "B">"#),
+            r#"C:\Program Files\&lt;A &amp; &quot;B&quot;&gt;"#
+        );
+    }
+
+    #[test]
+    fn test_line_start() {
+        let text = "<a>\n  <b/>\n</a>";
+        assert_eq!(line_start(text, 0), 0);
+        assert_eq!(line_start(text, text.find("<b").unwrap()), 4);
+        assert_eq!(line_start(text, text.find("</a").unwrap()), 11);
+    }
+}
@@ -12,22 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod advisory;
 pub mod api;
 pub mod archive;
 pub mod cache;
 pub mod commands;
 pub mod config;
+pub mod crash;
+pub mod daemon;
 pub mod doctor;
 pub mod download;
 pub mod error;
+pub mod i18n;
 pub mod indicator;
 pub mod installation;
+pub mod integrations;
+pub mod lockfile;
 pub mod locking;
 pub mod logging;
 pub mod metadata;
 pub mod models;
 pub mod paths;
+pub mod perf;
 pub mod platform;
+pub mod schema;
 pub mod security;
 pub mod shim;
 pub mod storage;
@@ -0,0 +1,287 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `kopi.lock`: a snapshot of the exact packages a project's version spec
+//! resolved to, one entry per target platform, so `kopi install --locked`
+//! can reproduce an installation after newer releases appear.
+
+use crate::error::{KopiError, Result};
+use crate::models::metadata::JdkMetadata;
+use crate::models::package::{ArchiveType, ChecksumType, PackageType};
+use crate::models::platform::{Architecture, OperatingSystem};
+use crate::platform::{get_current_architecture, get_current_os, matches_foojay_libc_type};
+use crate::version::Version;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const LOCK_FILE_NAME: &str = "kopi.lock";
+const LOCK_FORMAT_VERSION: u32 = 1;
+const LOCK_FILE_HEADER: &str = "# Generated by `kopi lock`. Do not edit by hand.\n\n";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectLock {
+    pub version: u32,
+    /// Version specification the packages were resolved from
+    pub spec: String,
+    pub generated_at: DateTime<Utc>,
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A package pinned for one platform
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub os: String,
+    pub arch: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lib_c_type: Option<String>,
+    pub id: String,
+    pub distribution: String,
+    pub java_version: String,
+    pub distribution_version: String,
+    pub package_type: PackageType,
+    pub archive_type: String,
+    pub javafx_bundled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    pub download_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum_type: Option<ChecksumType>,
+    pub size: i64,
+}
+
+impl ProjectLock {
+    pub fn new(spec: String, packages: Vec<LockedPackage>) -> Self {
+        Self {
+            version: LOCK_FORMAT_VERSION,
+            spec,
+            generated_at: Utc::now(),
+            packages,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let lock: ProjectLock = toml::from_str(&content).map_err(|e| {
+            KopiError::ConfigError(format!("Failed to parse {}: {e}", path.display()))
+        })?;
+
+        if lock.version != LOCK_FORMAT_VERSION {
+            return Err(KopiError::ConfigError(format!(
+                "Unsupported {} format version {} in {}; regenerate it with 'kopi lock'",
+                LOCK_FILE_NAME,
+                lock.version,
+                path.display()
+            )));
+        }
+
+        Ok(lock)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| KopiError::ConfigError(format!("Failed to serialize lock file: {e}")))?;
+        fs::write(path, format!("{LOCK_FILE_HEADER}{content}"))?;
+        Ok(())
+    }
+
+    /// The entry that `kopi install --locked` uses on this machine
+    pub fn package_for_current_platform(&self) -> Option<&LockedPackage> {
+        let os = get_current_os();
+        let arch = get_current_architecture();
+        self.packages.iter().find(|package| {
+            package.os == os
+                && package.arch == arch
+                && package
+                    .lib_c_type
+                    .as_deref()
+                    .is_none_or(matches_foojay_libc_type)
+        })
+    }
+}
+
+impl LockedPackage {
+    /// Pin a fully resolved package; metadata without a download URL cannot be
+    /// locked
+    pub fn from_metadata(metadata: &JdkMetadata) -> Result<Self> {
+        let download_url = metadata.download_url.clone().ok_or_else(|| {
+            KopiError::MetadataFetch(format!(
+                "Package {} has no download URL and cannot be locked",
+                metadata.id
+            ))
+        })?;
+
+        Ok(Self {
+            os: metadata.operating_system.to_string(),
+            arch: metadata.architecture.to_string(),
+            lib_c_type: metadata.lib_c_type.clone(),
+            id: metadata.id.clone(),
+            distribution: metadata.distribution.clone(),
+            java_version: metadata.version.to_string(),
+            distribution_version: metadata.distribution_version.to_string(),
+            package_type: metadata.package_type,
+            archive_type: metadata.archive_type.extension().to_string(),
+            javafx_bundled: metadata.javafx_bundled,
+            features: metadata.features.clone(),
+            download_url,
+            checksum: metadata.checksum.clone(),
+            checksum_type: metadata.checksum_type,
+            size: metadata.size,
+        })
+    }
+
+    pub fn to_metadata(&self) -> Result<JdkMetadata> {
+        Ok(JdkMetadata {
+            id: self.id.clone(),
+            distribution: self.distribution.clone(),
+            version: Version::from_str(&self.java_version)?,
+            distribution_version: Version::from_str(&self.distribution_version)?,
+            architecture: Architecture::from_str(&self.arch)?,
+            operating_system: OperatingSystem::from_str(&self.os)?,
+            package_type: self.package_type,
+            archive_type: ArchiveType::from_str(&self.archive_type)?,
+            download_url: Some(self.download_url.clone()),
+            checksum: self.checksum.clone(),
+            checksum_type: self.checksum_type,
+            size: self.size,
+            lib_c_type: self.lib_c_type.clone(),
+            javafx_bundled: self.javafx_bundled,
+            features: self.features.clone(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        })
+    }
+
+    /// Version specification that selects exactly this package
+    pub fn install_spec(&self) -> String {
+        let mut spec = match self.package_type {
+            PackageType::Jdk | PackageType::Sources | PackageType::Javadoc => {
+                format!("{}@{}", self.distribution, self.java_version)
+            }
+            PackageType::Jre => format!("jre@{}@{}", self.java_version, self.distribution),
+        };
+        for feature in &self.features {
+            spec.push_str(&format!("+{feature}"));
+        }
+        if self.javafx_bundled {
+            spec.push_str("+fx");
+        }
+        spec
+    }
+
+    /// Platform label such as `linux-x64-glibc`
+    pub fn platform(&self) -> String {
+        match &self.lib_c_type {
+            Some(libc) => format!("{}-{}-{libc}", self.os, self.arch),
+            None => format!("{}-{}", self.os, self.arch),
+        }
+    }
+}
+
+/// Find the nearest `kopi.lock` in `start` or its parent directories
+pub fn find_lock_file(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(LOCK_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn metadata() -> JdkMetadata {
+        JdkMetadata {
+            id: "pkg-21".to_string(),
+            distribution: "temurin".to_string(),
+            version: Version::from_str("21.0.5+11").unwrap(),
+            distribution_version: Version::from_str("21.0.5+11").unwrap(),
+            architecture: Architecture::from_str(&get_current_architecture()).unwrap(),
+            operating_system: OperatingSystem::from_str(&get_current_os()).unwrap(),
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: Some("https://example.com/jdk.tar.gz".to_string()),
+            checksum: Some("abc123".to_string()),
+            checksum_type: Some(ChecksumType::Sha256),
+            size: 42,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }
+    }
+
+    #[test]
+    fn test_locked_package_round_trip() {
+        let locked = LockedPackage::from_metadata(&metadata()).unwrap();
+        assert_eq!(locked.archive_type, "tar.gz");
+        assert_eq!(locked.install_spec(), "temurin@21.0.5+11");
+
+        let restored = locked.to_metadata().unwrap();
+        assert_eq!(restored.id, "pkg-21");
+        assert_eq!(restored.checksum.as_deref(), Some("abc123"));
+        assert_eq!(restored.archive_type, ArchiveType::TarGz);
+        assert_eq!(restored.version.to_string(), "21.0.5+11");
+    }
+
+    #[test]
+    fn test_incomplete_metadata_cannot_be_locked() {
+        let mut incomplete = metadata();
+        incomplete.download_url = None;
+        assert!(LockedPackage::from_metadata(&incomplete).is_err());
+    }
+
+    #[test]
+    fn test_save_load_and_select_platform() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LOCK_FILE_NAME);
+
+        let mut foreign = LockedPackage::from_metadata(&metadata()).unwrap();
+        foreign.os = "aix".to_string();
+        foreign.id = "pkg-aix".to_string();
+        let native = LockedPackage::from_metadata(&metadata()).unwrap();
+
+        let lock = ProjectLock::new("temurin@21".to_string(), vec![foreign, native]);
+        lock.save(&path).unwrap();
+
+        let loaded = ProjectLock::load(&path).unwrap();
+        assert_eq!(loaded, lock);
+        assert_eq!(loaded.package_for_current_platform().unwrap().id, "pkg-21");
+    }
+
+    #[test]
+    fn test_find_lock_file_walks_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        assert!(find_lock_file(&nested).is_none());
+
+        fs::write(temp_dir.path().join(LOCK_FILE_NAME), "").unwrap();
+        assert_eq!(
+            find_lock_file(&nested).unwrap(),
+            temp_dir.path().join(LOCK_FILE_NAME)
+        );
+    }
+}
@@ -12,6 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Cooperative cancellation on SIGINT/SIGTERM (Ctrl+C, or Ctrl+Break on Windows).
+//!
+//! Once [`global_token`] has been called, the first signal only marks the
+//! token as cancelled so that downloads, extraction, cache refreshes and
+//! uninstalls can stop at a safe point, remove partial files and release
+//! their locks. A second signal exits right away with status 130.
+
 use log::warn;
 use signal_hook::SigId;
 use signal_hook::consts::signal::{SIGINT, SIGTERM};
@@ -67,6 +74,12 @@ impl CancellationRegistry {
         let mut handles = Vec::new();
 
         for signal in registered_signals() {
+            // Registered first, so it sees the flag as it was before this signal
+            if let Err(err) =
+                flag::register_conditional_shutdown(*signal, INTERRUPTED_EXIT_CODE, flag.clone())
+            {
+                warn!("Failed to register forced exit for signal {signal}: {err}");
+            }
             match flag::register(*signal, flag.clone()) {
                 Ok(handle) => handles.push(handle),
                 Err(err) => {
@@ -102,6 +115,9 @@ fn registered_signals() -> &'static [i32] {
 
 static GLOBAL_REGISTRY: OnceLock<CancellationRegistry> = OnceLock::new();
 
+/// Exit status of a command stopped by Ctrl+C, following the shell's 128 + SIGINT
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
 /// Returns a cancellation token backed by global signal handlers.
 pub fn global_token() -> CancellationToken {
     GLOBAL_REGISTRY
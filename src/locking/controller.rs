@@ -20,8 +20,8 @@ use crate::locking::handle::{FallbackHandle, LockBackend, LockHandle};
 use crate::locking::scope::{LockKind, LockScope};
 use crate::locking::wait_observer::LockFeedbackBridge;
 use crate::locking::{
-    AcquireMode, LockAcquisitionRequest, LockStatusSink, LockTimeoutSource, LockTimeoutValue,
-    LockWaitObserver, PollingBackoff, StatusReporterObserver, global_token,
+    AcquireMode, LockAcquisitionRequest, LockStatusSink, LockWaitObserver, PollingBackoff,
+    StatusReporterObserver, global_token,
 };
 use crate::platform::{AdvisorySupport, DefaultFilesystemInspector, FilesystemInspector};
 use log::{debug, info};
@@ -68,9 +68,8 @@ pub struct LockController {
     kopi_home: PathBuf,
     inspector: Arc<dyn FilesystemInspector>,
     preferred_mode: LockingMode,
-    timeout: LockTimeoutValue,
+    locking: LockingConfig,
     backoff_config: BackoffConfig,
-    timeout_source: LockTimeoutSource,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -104,13 +103,12 @@ impl LockController {
             kopi_home: kopi_home.into(),
             inspector,
             preferred_mode: config.mode,
-            timeout: config.timeout_value(),
+            locking: config.clone(),
             backoff_config: BackoffConfig {
                 initial: Duration::from_millis(10),
                 factor: 2,
                 cap: Duration::from_secs(1),
             },
-            timeout_source: config.timeout_source(),
         }
     }
 
@@ -141,7 +139,7 @@ impl LockController {
         scope: LockScope,
         sink: &dyn LockStatusSink,
     ) -> Result<LockAcquisition> {
-        let observer = StatusReporterObserver::new(sink, self.timeout_source);
+        let observer = StatusReporterObserver::new(sink, self.locking.timeout_for(&scope).source);
         let request = self.build_request(scope, AcquireMode::Blocking, Some(&observer));
         self.acquire_with(request)
     }
@@ -151,7 +149,10 @@ impl LockController {
         scope: LockScope,
         indicator: Arc<Mutex<Box<dyn ProgressIndicator>>>,
     ) -> Result<LockAcquisition> {
-        let observer = LockFeedbackBridge::for_handle(indicator.clone(), self.timeout_source);
+        let observer = LockFeedbackBridge::for_handle(
+            indicator.clone(),
+            self.locking.timeout_for(&scope).source,
+        );
         let request = self.build_request(scope, AcquireMode::Blocking, Some(&observer));
         self.acquire_with(request)
     }
@@ -177,11 +178,12 @@ impl LockController {
         mode: AcquireMode,
         observer: Option<&'a dyn LockWaitObserver>,
     ) -> LockAcquisitionRequest<'a> {
-        LockAcquisitionRequest::new(scope, self.timeout)
+        let timeout = self.locking.timeout_for(&scope);
+        LockAcquisitionRequest::new(scope, timeout.value)
             .with_mode(mode)
             .with_backoff(self.backoff_config.polling_backoff())
             .with_cancellation(global_token())
-            .with_timeout_source(self.timeout_source)
+            .with_timeout_source(timeout.source)
             .with_observer(observer)
     }
 
@@ -199,7 +201,8 @@ impl LockController {
         );
         debug!(
             "Lock timeout source for {}: {:?}",
-            scope, self.timeout_source
+            scope,
+            request.timeout_source()
         );
         let lock_path = scope.lock_path(&self.kopi_home);
         let parent = lock_path
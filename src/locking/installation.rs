@@ -1,11 +1,13 @@
 use crate::error::{KopiError, Result};
-use crate::locking::{LockScope, PackageCoordinate, PackageKind};
+use crate::indicator::ProgressIndicator;
+use crate::locking::{LockAcquisition, LockController, LockScope, PackageCoordinate, PackageKind};
 use crate::models::api::Package;
 use crate::storage::{
     InstallationMetadata, InstalledJdk, InstalledMetadataSnapshot, JdkRepository,
 };
-use log::warn;
+use log::{info, warn};
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 pub fn installation_lock_scope_from_package(package: &Package) -> Result<LockScope> {
     let coordinate = PackageCoordinate::try_from_package(package)?;
     let mut tags = coordinate.variant_tags().to_vec();
@@ -14,6 +16,55 @@ pub fn installation_lock_scope_from_package(package: &Package) -> Result<LockSco
     Ok(LockScope::installation(coordinate))
 }
 
+/// Installation lock held by this process, recording whether another process
+/// held it first. That process was installing the same package, so callers
+/// re-check the repository before downloading anything.
+#[derive(Debug)]
+pub struct InstallationLock {
+    acquisition: LockAcquisition,
+    waited_for_peer: bool,
+}
+
+impl InstallationLock {
+    pub fn waited_for_peer(&self) -> bool {
+        self.waited_for_peer
+    }
+
+    pub fn into_acquisition(self) -> LockAcquisition {
+        self.acquisition
+    }
+}
+
+/// Acquire the installation lock for `scope`, telling the user when another
+/// process is already installing the package and this one has to wait.
+pub fn acquire_installation_lock(
+    controller: &LockController,
+    scope: LockScope,
+    feedback: Arc<Mutex<Box<dyn ProgressIndicator>>>,
+) -> Result<InstallationLock> {
+    if let Some(acquisition) = controller.try_acquire(scope.clone())? {
+        return Ok(InstallationLock {
+            acquisition,
+            waited_for_peer: false,
+        });
+    }
+
+    let message = format!(
+        "Another kopi process is installing {}; waiting for it to finish",
+        scope.label()
+    );
+    info!("{message}");
+    if let Ok(indicator) = feedback.lock() {
+        let _ = indicator.println(&message);
+    }
+
+    let acquisition = controller.acquire_with_feedback(scope, feedback)?;
+    Ok(InstallationLock {
+        acquisition,
+        waited_for_peer: true,
+    })
+}
+
 pub struct InstalledScopeResolver<'repo, 'config> {
     repository: &'repo JdkRepository<'config>,
 }
@@ -33,10 +84,14 @@ impl<'repo, 'config> InstalledScopeResolver<'repo, 'config> {
             return installation_lock_scope_from_package(&metadata.package);
         }
 
-        warn!(
-            "Falling back to slug-derived lock scope for {} due to missing or unreadable metadata",
-            installed.path.display()
-        );
+        // Adopted JDKs never had package metadata
+        if !self.repository.is_external(installed) {
+            warn!(
+                "Falling back to slug-derived lock scope for {} due to missing or unreadable \
+                 metadata",
+                installed.path.display()
+            );
+        }
 
         self.fallback_scope(installed, installation_metadata.as_ref())
     }
@@ -171,9 +226,11 @@ mod tests {
             lib_c_type: Some("gnu".to_string()),
             package_type: "JDK".to_string(),
             javafx_bundled: true,
+            feature: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         }
     }
 
@@ -206,6 +263,9 @@ mod tests {
             structure_type: "direct".to_string(),
             platform: "linux_x64".to_string(),
             metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
         };
 
         let metadata_path = crate::paths::install::metadata_file(config.kopi_home(), slug);
@@ -28,11 +28,14 @@ pub mod wait_observer;
 
 pub use acquisition::{AcquireMode, LockAcquisitionRequest, LockTimeoutBudget, PollingBackoff};
 pub use cache_writer::CacheWriterLockGuard;
-pub use cancellation::{CancellationToken, global_token};
+pub use cancellation::{CancellationToken, INTERRUPTED_EXIT_CODE, global_token};
 pub use controller::{LockAcquisition, LockController};
 pub use handle::{FallbackHandle, LockBackend, LockHandle};
 pub use hygiene::{LockHygieneReport, LockHygieneRunner, run_startup_hygiene};
-pub use installation::{InstalledScopeResolver, installation_lock_scope_from_package};
+pub use installation::{
+    InstallationLock, InstalledScopeResolver, acquire_installation_lock,
+    installation_lock_scope_from_package,
+};
 pub use package_coordinate::{PackageCoordinate, PackageKind};
 pub use scope::{LockKind, LockScope};
 pub use scoped_guard::ScopedPackageLockGuard;
@@ -254,9 +254,11 @@ mod tests {
             lib_c_type: Some("gnu".to_string()),
             package_type: "JDK".to_string(),
             javafx_bundled: true,
+            feature: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         }
     }
 
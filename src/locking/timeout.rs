@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use std::borrow::Cow;
 use std::fmt;
 use std::time::Duration;
 
@@ -48,12 +50,32 @@ impl fmt::Display for LockTimeoutValue {
     }
 }
 
+/// Config files accept a number of seconds or the string `infinite`; numeric
+/// strings are accepted too since environment overrides arrive as text.
+impl JsonSchema for LockTimeoutValue {
+    fn schema_name() -> Cow<'static, str> {
+        "LockTimeout".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "anyOf": [
+                { "type": "integer", "minimum": 0 },
+                { "type": "string", "pattern": "^\\s*([0-9]+|[iI][nN][fF][iI][nN][iI][tT][eE])\\s*$" }
+            ],
+            "description": "Seconds to wait, or \"infinite\""
+        })
+    }
+}
+
 /// Source precedence used when resolving the effective timeout.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LockTimeoutSource {
     #[default]
     Default,
     Config,
+    /// A `[locking.timeouts]` entry, named by its key
+    ScopeConfig(&'static str),
     Environment,
     Cli,
 }
@@ -69,6 +91,9 @@ impl fmt::Display for LockTimeoutSource {
         let label = match self {
             LockTimeoutSource::Default => "built-in default",
             LockTimeoutSource::Config => "configuration file",
+            LockTimeoutSource::ScopeConfig(key) => {
+                return write!(f, "configuration file (locking.timeouts.{key})");
+            }
             LockTimeoutSource::Environment => "environment variable",
             LockTimeoutSource::Cli => "CLI flag",
         };
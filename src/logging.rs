@@ -12,7 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use env_logger;
+use crate::config::{LogFormat, LoggingConfig};
+use crate::error::Result;
+use chrono::{SecondsFormat, Utc};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Log target for security audit events; file logs label these `AUDIT`
+pub const AUDIT_TARGET: &str = "kopi::audit";
+
+/// File sink attached once configuration has been loaded
+static FILE_SINK: OnceLock<Mutex<FileSink>> = OnceLock::new();
 
 /// Initialize the logger with the specified verbosity level
 ///
@@ -26,9 +39,283 @@ pub fn setup_logger(verbose: u8) {
         _ => "kopi=trace",
     };
 
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(env_filter))
-        .format_timestamp(None)
-        .format_module_path(false)
-        .format_target(false)
-        .init();
+    let stderr =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(env_filter))
+            .format_timestamp(None)
+            .format_module_path(false)
+            .format_target(false)
+            .build();
+
+    let max_level = stderr.filter();
+    if log::set_boxed_logger(Box::new(KopiLogger { stderr })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Start appending kopi's log records to the configured log file
+///
+/// The file receives info and above (or more when stderr is more verbose),
+/// including security audit events. Does nothing when no file is configured.
+pub fn enable_file_logging(
+    config: &LoggingConfig,
+    kopi_home: &Path,
+    override_path: Option<&Path>,
+) -> Result<()> {
+    let Some(path) = config.resolve_file(kopi_home, override_path) else {
+        return Ok(());
+    };
+
+    let filter = log::max_level().max(LevelFilter::Info);
+    let sink = FileSink::open(path, config, filter)?;
+    if FILE_SINK.set(Mutex::new(sink)).is_ok() {
+        log::set_max_level(filter);
+    }
+    Ok(())
+}
+
+struct KopiLogger {
+    stderr: env_logger::Logger,
+}
+
+impl KopiLogger {
+    fn file_enabled(metadata: &Metadata) -> Option<&'static Mutex<FileSink>> {
+        let sink = FILE_SINK.get()?;
+        let filter = sink.lock().map(|sink| sink.filter).ok()?;
+        (metadata.target().starts_with("kopi") && metadata.level() <= filter).then_some(sink)
+    }
+}
+
+impl Log for KopiLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.stderr.enabled(metadata) || Self::file_enabled(metadata).is_some()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.stderr.matches(record) {
+            self.stderr.log(record);
+        }
+        if let Some(sink) = Self::file_enabled(record.metadata())
+            && let Ok(mut sink) = sink.lock()
+        {
+            sink.write(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+        if let Some(sink) = FILE_SINK.get()
+            && let Ok(mut sink) = sink.lock()
+        {
+            let _ = sink.file.flush();
+        }
+    }
+}
+
+struct FileSink {
+    path: PathBuf,
+    format: LogFormat,
+    max_bytes: u64,
+    max_files: u32,
+    filter: LevelFilter,
+    file: File,
+    size: u64,
+}
+
+impl FileSink {
+    fn open(path: PathBuf, config: &LoggingConfig, filter: LevelFilter) -> Result<Self> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            format: config.format,
+            max_bytes: config.max_size_mb.saturating_mul(1024 * 1024),
+            max_files: config.max_files,
+            filter,
+            file,
+            size,
+        })
+    }
+
+    /// Write one record, rotating first if it would push the file past the
+    /// size limit. Failures are dropped since there is nowhere to report them.
+    fn write(&mut self, record: &Record) {
+        let line = format_record(
+            self.format,
+            &Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            level_label(record),
+            record.target(),
+            &record.args().to_string(),
+        );
+
+        if self.max_bytes > 0
+            && self.size > 0
+            && self.size + line.len() as u64 > self.max_bytes
+            && self.rotate().is_err()
+        {
+            return;
+        }
+
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+
+    /// Shift `<file>.N-1` to `<file>.N`, ..., `<file>` to `<file>.1`, dropping
+    /// the oldest, then start a fresh file
+    fn rotate(&mut self) -> std::io::Result<()> {
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                let from = rotated_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(&from, rotated_path(&self.path, index + 1))?;
+                }
+            }
+            fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+fn level_label(record: &Record) -> &'static str {
+    if record.target() == AUDIT_TARGET {
+        "AUDIT"
+    } else {
+        record.level().as_str()
+    }
+}
+
+fn format_record(
+    format: LogFormat,
+    timestamp: &str,
+    level: &str,
+    target: &str,
+    message: &str,
+) -> String {
+    match format {
+        LogFormat::Text => format!("{timestamp} {level:<5} [{target}] {message}\n"),
+        LogFormat::Json => {
+            let entry = serde_json::json!({
+                "timestamp": timestamp,
+                "level": level,
+                "target": target,
+                "pid": std::process::id(),
+                "message": message,
+            });
+            format!("{entry}\n")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_config(format: LogFormat, max_files: u32) -> LoggingConfig {
+        LoggingConfig {
+            format,
+            max_files,
+            ..LoggingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_format_record_text() {
+        let line = format_record(
+            LogFormat::Text,
+            "2025-01-01T00:00:00.000Z",
+            "AUDIT",
+            AUDIT_TARGET,
+            "SECURITY AUDIT: SECURE_PERMISSIONS - done",
+        );
+        assert_eq!(
+            line,
+            "2025-01-01T00:00:00.000Z AUDIT [kopi::audit] SECURITY AUDIT: SECURE_PERMISSIONS - done\n"
+        );
+    }
+
+    #[test]
+    fn test_format_record_json() {
+        let line = format_record(
+            LogFormat::Json,
+            "2025-01-01T00:00:00.000Z",
+            "WARN",
+            "kopi::cache",
+            "quote \" inside",
+        );
+        assert!(line.ends_with('\n'));
+        let value: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(value["level"], "WARN");
+        assert_eq!(value["target"], "kopi::cache");
+        assert_eq!(value["message"], "quote \" inside");
+        assert_eq!(value["pid"], std::process::id());
+    }
+
+    #[test]
+    fn test_rotation_keeps_max_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("logs").join("kopi.log");
+        let mut sink = FileSink::open(
+            path.clone(),
+            &test_config(LogFormat::Text, 2),
+            LevelFilter::Info,
+        )
+        .unwrap();
+        sink.max_bytes = 100;
+
+        for i in 0..10 {
+            sink.write(
+                &Record::builder()
+                    .target("kopi::test")
+                    .level(log::Level::Info)
+                    .args(format_args!("message number {i} with some padding"))
+                    .build(),
+            );
+        }
+
+        assert!(path.exists());
+        assert!(rotated_path(&path, 1).exists());
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+        for file in [path.clone(), rotated_path(&path, 1)] {
+            assert!(fs::metadata(&file).unwrap().len() <= 100);
+        }
+        let newest = fs::read_to_string(&path).unwrap();
+        assert!(newest.contains("message number 9"));
+    }
+
+    #[test]
+    fn test_audit_records_get_audit_level() {
+        let record = Record::builder()
+            .target(AUDIT_TARGET)
+            .level(log::Level::Info)
+            .build();
+        assert_eq!(level_label(&record), "AUDIT");
+
+        let record = Record::builder()
+            .target("kopi::install")
+            .level(log::Level::Info)
+            .build();
+        assert_eq!(level_label(&record), "INFO");
+    }
 }
@@ -13,23 +13,49 @@
 // limitations under the License.
 
 use clap::{Parser, Subcommand};
-use kopi::commands::cache::CacheCommand;
+use kopi::commands::adopt::AdoptCommand;
+use kopi::commands::audit::{AuditCommand, DEFAULT_FAIL_ON};
+use kopi::commands::cache::{CacheCommand, SearchFilterArgs};
+use kopi::commands::config::ConfigCommand;
 use kopi::commands::current::CurrentCommand;
+use kopi::commands::daemon::DaemonCommand;
 use kopi::commands::doctor::DoctorCommand;
 use kopi::commands::env::EnvCommand;
+use kopi::commands::files::FilesCommand;
 use kopi::commands::global::GlobalCommand;
+use kopi::commands::init::{BuildTool, InitCommand, ProjectTemplate};
 use kopi::commands::install::InstallCommand;
+use kopi::commands::integrate::IntegrateCommand;
+use kopi::commands::jlink::JlinkCommand;
 use kopi::commands::list::ListCommand;
 use kopi::commands::local::LocalCommand;
+use kopi::commands::lock::LockCommand;
+use kopi::commands::macos::MacosCommand;
+use kopi::commands::metadata::MetadataCommand;
+use kopi::commands::notify::NotifyCommand;
+use kopi::commands::outdated::OutdatedCommand;
+use kopi::commands::overlay::OverlayCommand;
+use kopi::commands::pin_protect::PinProtectCommand;
+use kopi::commands::resolve::ResolveCommand;
+use kopi::commands::serve_metadata::ServeMetadataCommand;
 use kopi::commands::setup::SetupCommand;
 use kopi::commands::shell::ShellCommand;
 use kopi::commands::shim::ShimCommand;
-use kopi::commands::uninstall::UninstallCommand;
+use kopi::commands::uninstall::{DEFAULT_UNUSED_AGE, UninstallCommand};
+use kopi::commands::upgrade::UpgradeCommand;
 use kopi::commands::which::WhichCommand;
-use kopi::config::new_kopi_config;
-use kopi::error::{Result, format_error_chain, get_exit_code};
+use kopi::config::{KopiConfig, new_kopi_config};
+use kopi::daemon::DaemonClient;
+use kopi::daemon::protocol::InstallParams;
+use kopi::download;
+use kopi::error::{KopiError, Result, format_error_chain, format_error_json, get_exit_code};
+use kopi::indicator::ProgressFactory;
 use kopi::logging;
+use kopi::models::package::PackageType;
+use kopi::perf::TimingCollector;
+use kopi::storage::shared_home;
 use log::warn;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "kopi")]
@@ -47,6 +73,18 @@ struct Cli {
     #[arg(long, value_name = "SECONDS|infinite", global = true)]
     lock_timeout: Option<String>,
 
+    /// Print a timing breakdown after install, refresh, and search
+    #[arg(long, global = true)]
+    timings: bool,
+
+    /// Route list and install through a running `kopi daemon` when available
+    #[arg(long, global = true)]
+    use_daemon: bool,
+
+    /// Also append log records to this file (overrides `logging.file`)
+    #[arg(long, value_name = "PATH", global = true)]
+    log_file: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -56,8 +94,14 @@ enum Commands {
     /// Install a JDK version
     #[command(visible_alias = "i")]
     Install {
-        /// Version to install (e.g., "21", "17.0.9", "corretto@21")
-        version: String,
+        /// Versions to install (e.g., "21", "17.0.9", "corretto@21"); a failed
+        /// version does not stop the remaining ones
+        #[arg(value_name = "VERSION", required_unless_present = "locked")]
+        versions: Vec<String>,
+
+        /// Install the exact package recorded in kopi.lock for this platform
+        #[arg(long, conflicts_with = "versions")]
+        locked: bool,
 
         /// Force reinstall even if already installed
         #[arg(short, long)]
@@ -70,20 +114,87 @@ enum Commands {
         /// Download timeout in seconds
         #[arg(long, value_name = "SECONDS")]
         timeout: Option<u64>,
+
+        /// Also download the JDK's source archive when the metadata lists one
+        #[arg(long)]
+        with_sources: bool,
+
+        /// Also download the JDK's javadoc archive when the metadata lists one
+        #[arg(long)]
+        with_javadoc: bool,
+
+        /// Print the install summary as JSON instead of progress output
+        #[arg(long, conflicts_with = "dry_run")]
+        json: bool,
+
+        /// Install even if the [policy] configuration forbids this JDK
+        #[arg(long)]
+        ignore_policy: bool,
+
+        /// Cap download bandwidth (e.g., 500K, 2M; 0 for unlimited)
+        #[arg(long, value_name = "RATE", value_parser = download::parse_rate)]
+        limit_rate: Option<u64>,
+
+        /// Keep the verified archive next to the JDK so it can be reinstalled offline
+        #[arg(long)]
+        keep_archive: bool,
+
+        /// Require the archive to have this SHA-256 digest; fails if the published
+        /// checksum or the downloaded file disagrees
+        #[arg(long, value_name = "DIGEST", value_parser = kopi::security::parse_sha256)]
+        sha256: Option<String>,
+
+        /// Install the build for another platform (e.g., linux/aarch64) into --dest
+        /// without registering it with Kopi
+        #[arg(
+            long,
+            value_name = "PLATFORM",
+            requires = "dest",
+            conflicts_with_all = ["locked", "json", "with_sources", "with_javadoc", "keep_archive"]
+        )]
+        platform: Option<String>,
+
+        /// Directory to extract the JDK into; requires --platform
+        #[arg(long, value_name = "DIR", requires = "platform")]
+        dest: Option<PathBuf>,
     },
 
     /// List installed JDK versions
     #[command(visible_alias = "ls")]
-    List,
+    List {
+        /// Output in JSON format, including global/local/active markers
+        #[arg(long)]
+        json: bool,
+
+        /// Show the implementor, Java version, architecture and module count
+        /// recorded in each JDK's release file
+        #[arg(short, long, conflicts_with = "json")]
+        detailed: bool,
+
+        /// List the newest cached build of every release line for this platform
+        #[arg(long, conflicts_with = "detailed")]
+        remote: bool,
+
+        /// With --remote, compare each installed release line with its newest cached build
+        #[arg(long, requires = "remote")]
+        installed_only_diff: bool,
+    },
 
     /// Set JDK version for current shell session
     #[command(visible_alias = "use")]
     Shell {
         /// JDK version to use
-        version: String,
+        #[arg(required_unless_present_any = ["restore", "print_stack"])]
+        version: Option<String>,
         /// Override shell detection
         #[arg(long)]
         shell: Option<String>,
+        /// Launch a shell with the version active before the last `kopi shell`
+        #[arg(long, conflicts_with_all = ["version", "print_stack"])]
+        restore: bool,
+        /// Show the current shell version and the versions --restore returns to
+        #[arg(long, conflicts_with_all = ["version", "shell"])]
+        print_stack: bool,
     },
 
     /// Show currently active JDK version
@@ -116,20 +227,146 @@ Examples:
         /// Output export statements (default: true)
         #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
         export: bool,
+        /// Print only what changed since the shell's current JAVA_HOME (used by `kopi init`)
+        #[arg(long, conflicts_with = "version")]
+        changed_only: bool,
+        /// Print only the JAVA_HOME/PATH changes needed relative to the current environment
+        #[arg(long, conflicts_with_all = ["changed_only", "export"])]
+        diff: bool,
+        /// Under WSL, also set JAVA_HOME_WINDOWS and share JAVA_HOME with Windows programs
+        #[arg(long, conflicts_with_all = ["changed_only", "diff"])]
+        wsl_interop: bool,
+        /// Print a complete minimal environment (JAVA_HOME, a PATH of only the JDK and system
+        /// directories, fixed locale) for hermetic builds
+        #[arg(long, conflicts_with_all = ["changed_only", "diff", "wsl_interop"])]
+        pure: bool,
+        /// Print a JAVA_HOME_<major>_<ARCH> style variable for every installed major version
+        #[arg(
+            long,
+            conflicts_with_all = ["version", "changed_only", "diff", "wsl_interop", "pure"]
+        )]
+        all_installed: bool,
+        /// Run the emitted script in the target shell and check that JAVA_HOME and
+        /// `java -version` select the expected JDK
+        #[arg(
+            long,
+            conflicts_with_all = ["changed_only", "diff", "pure", "all_installed"]
+        )]
+        validate: bool,
+    },
+
+    /// Print a shell hook, or scaffold a project's JDK pin and toolchain setup
+    #[command(
+        long_about = "Print a shell hook that switches JAVA_HOME when changing directories
+
+Add the hook to your shell's startup file:
+  eval \"$(kopi init bash)\"                  # ~/.bashrc
+  eval \"$(kopi init zsh)\"                   # ~/.zshrc
+  kopi init fish | source                     # ~/.config/fish/config.fish
+  kopi init powershell | Out-String | Invoke-Expression   # $PROFILE
+
+Without a shell, set up the project in the current directory instead:
+  kopi init --version 21                      # create .kopi-version
+  kopi init --dist temurin --version 21 --gradle
+  kopi init --from-release-file --maven       # take the version from pom.xml"
+    )]
+    Init {
+        /// Shell to generate the hook for (bash, zsh, fish, powershell)
+        #[arg(conflicts_with_all = ["dist", "version", "from_release_file", "gradle", "maven", "kopi_toml", "force"])]
+        shell: Option<String>,
+
+        /// Distribution to pin (default: none, so default_distribution applies)
+        #[arg(long)]
+        dist: Option<String>,
+
+        /// Java version to pin, e.g. "21" or "17.0.13"
+        #[arg(long, required_unless_present_any = ["shell", "from_release_file"])]
+        version: Option<String>,
+
+        /// Take the version from the Java toolchain declared in pom.xml or build.gradle(.kts)
+        #[arg(long, conflicts_with = "version")]
+        from_release_file: bool,
+
+        /// Expose kopi JDKs to Gradle toolchain detection
+        #[arg(long, conflicts_with = "maven")]
+        gradle: bool,
+
+        /// Add kopi JDKs to the Maven toolchains
+        #[arg(long)]
+        maven: bool,
+
+        /// Also create a starter .kopi.toml
+        #[arg(long)]
+        kopi_toml: bool,
+
+        /// Overwrite an existing .kopi-version or .kopi.toml
+        #[arg(long)]
+        force: bool,
     },
 
     /// Set the global default JDK version
     #[command(visible_alias = "g", alias = "default")]
     Global {
         /// Version to set as global default
-        version: String,
+        #[arg(required_unless_present_any = ["track", "unset", "show"])]
+        version: Option<String>,
+
+        /// Follow a version track instead ("latest-lts", "latest-ga", "21-latest")
+        #[arg(long, conflicts_with = "version")]
+        track: Option<String>,
+
+        /// Remove the global default version
+        #[arg(long, conflicts_with_all = ["version", "track", "show"])]
+        unset: bool,
+
+        /// Print the global default version and the file it is stored in
+        #[arg(long, conflicts_with_all = ["version", "track"])]
+        show: bool,
     },
 
     /// Set the local project JDK version
     #[command(visible_alias = "l", alias = "pin")]
     Local {
         /// Version to set for current project
-        version: String,
+        #[arg(required_unless_present_any = ["track", "unset", "show", "auto"])]
+        version: Option<String>,
+
+        /// Follow a version track instead ("latest-lts", "latest-ga", "21-latest")
+        #[arg(long, conflicts_with = "version")]
+        track: Option<String>,
+
+        /// Remove the .kopi-version file in the current directory
+        #[arg(long, conflicts_with_all = ["version", "track", "show"])]
+        unset: bool,
+
+        /// Print the project version in effect and the file that sets it
+        #[arg(long, conflicts_with_all = ["version", "track"])]
+        show: bool,
+
+        /// Pin the Java version declared in pom.xml or build.gradle(.kts)
+        #[arg(long, conflicts_with_all = ["version", "track", "unset", "show"])]
+        auto: bool,
+    },
+
+    /// Show installed JDKs that have newer builds available
+    Outdated {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check installed JDKs against known vulnerability advisories
+    Audit {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+        /// Download the advisory feed even if the cached copy is fresh
+        #[arg(long)]
+        refresh: bool,
+        /// Exit with status 11 when an advisory at or above this severity applies
+        /// (low, medium, high, critical)
+        #[arg(long, value_name = "SEVERITY", default_value = DEFAULT_FAIL_ON)]
+        fail_on: String,
     },
 
     /// Show installation path for a JDK version
@@ -138,7 +375,7 @@ Examples:
         /// JDK version specification (optional)
         version: Option<String>,
 
-        /// Show path for specific JDK tool
+        /// Show path for specific JDK tool, or 'all' to list every tool of the JDK
         #[arg(long, default_value = "java")]
         tool: String,
 
@@ -149,6 +386,59 @@ Examples:
         /// Output in JSON format
         #[arg(long)]
         json: bool,
+
+        /// Search every installed JDK for the tool, preferring the project's version line
+        #[arg(long)]
+        find_any: bool,
+
+        /// Show the JDK's installed source archive instead of a tool path
+        #[arg(long, conflicts_with_all = ["home", "javadoc", "find_any"])]
+        sources: bool,
+
+        /// Show the JDK's installed javadoc archive instead of a tool path
+        #[arg(long, conflicts_with_all = ["home", "find_any"])]
+        javadoc: bool,
+    },
+
+    /// List the files recorded when a JDK was installed
+    Files {
+        /// JDK version specification matching exactly one installed JDK
+        version: String,
+
+        /// Show files in the JDK directory that the installation did not create
+        #[arg(long)]
+        orphans: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Tell when a pinned JDK has a newer patch, for shell prompts and cron
+    Notify {
+        /// Compare the pins with the metadata cache (at most once a day) and
+        /// record the result; without it, print the last recorded result
+        #[arg(long)]
+        check: bool,
+
+        /// Check even if the last check was less than a day ago
+        #[arg(long, requires = "check")]
+        force: bool,
+
+        /// Only record the result in the state file, printing nothing
+        #[arg(short, long, requires = "check")]
+        quiet: bool,
+    },
+
+    /// Show each step a shim takes to pick the executable for a tool
+    Resolve {
+        /// Tool a shim would run (e.g., java, javac)
+        #[arg(default_value = "java")]
+        tool: String,
+
+        /// Output the resolution trace as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Manage JDK metadata cache
@@ -180,9 +470,16 @@ Examples:
         #[arg(long, conflicts_with_all = ["compact", "detailed"])]
         json: bool,
 
+        /// Show when each package was released (always shown with --detailed)
+        #[arg(long, conflicts_with = "json")]
+        release_date: bool,
+
         /// Show only LTS versions
         #[arg(long)]
         lts_only: bool,
+
+        #[command(flatten)]
+        filters: SearchFilterArgs,
     },
 
     /// Initial setup and configuration
@@ -190,6 +487,42 @@ Examples:
         /// Force recreation of shims even if they exist
         #[arg(short, long)]
         force: bool,
+
+        /// Verify directories, shims and PATH without changing anything
+        #[arg(long, conflicts_with = "force")]
+        check: bool,
+
+        /// Never prompt and print plain output, for scripts and Dockerfiles
+        #[arg(long)]
+        non_interactive: bool,
+
+        /// Also register installed JDKs with /usr/libexec/java_home (macOS)
+        #[arg(long, conflicts_with = "check")]
+        register_java_home: bool,
+    },
+
+    /// Inspect and edit the layered configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Inspect the formats of kopi's metadata files
+    Metadata {
+        #[command(subcommand)]
+        command: MetadataCommand,
+    },
+
+    /// Make kopi JDKs available to IDEs
+    Integrate {
+        #[command(subcommand)]
+        command: IntegrateCommand,
+    },
+
+    /// Integrate kopi JDKs with macOS tools
+    Macos {
+        #[command(subcommand)]
+        command: MacosCommand,
     },
 
     /// Manage tool shims
@@ -219,6 +552,107 @@ Examples:
         /// Clean up failed or partial uninstall operations
         #[arg(long)]
         cleanup: bool,
+
+        /// Uninstall JDKs not used by any shim recently and not pinned anywhere
+        #[arg(long, conflicts_with_all = ["version", "all", "cleanup"])]
+        unused: bool,
+
+        /// How long a JDK must be unused (e.g., 90d, 12w, 36h; default: 90d)
+        #[arg(
+            long,
+            value_name = "AGE",
+            requires = "unused",
+            conflicts_with_all = ["version", "all", "cleanup"]
+        )]
+        older_than: Option<String>,
+
+        /// Leave the kept install archive and companion archives in place for
+        /// offline reinstalls
+        #[arg(long)]
+        keep_archives: bool,
+    },
+
+    /// Register JDKs installed outside kopi so shims and env can use them in place
+    Adopt {
+        /// JAVA_HOME of a JDK to adopt (repeatable)
+        paths: Vec<PathBuf>,
+
+        /// Search common system locations and SDK manager directories
+        #[arg(long)]
+        scan: bool,
+
+        /// Show what would be adopted without registering anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Protect an installed JDK from uninstall and upgrade unless --force is given
+    PinProtect {
+        /// JDK to protect (e.g., "temurin@17.0.9", "corretto@21")
+        version: String,
+
+        /// Remove the protection instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Build trimmed runtime images with a JDK's jlink
+    Jlink {
+        #[command(subcommand)]
+        command: JlinkCommand,
+    },
+
+    /// Re-apply install.certificates and install.security_properties to installed JDKs
+    Overlay {
+        /// JDK to update (defaults to every installed JDK)
+        version: Option<String>,
+    },
+
+    /// Record the exact packages for the project's version in kopi.lock
+    Lock {
+        /// Version to lock (defaults to the current project or global version)
+        version: Option<String>,
+
+        /// Target platform as <os>-<arch>[-<libc>] (repeatable; defaults to this machine)
+        #[arg(long = "platform", value_name = "PLATFORM")]
+        platforms: Vec<String>,
+    },
+
+    /// Install newer releases for a version pinned to a track
+    Upgrade {
+        /// Track to upgrade (defaults to the current project or global pin)
+        version: Option<String>,
+
+        /// Show what would be installed without installing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Upgrade even if the installed release is protected with pin-protect
+        #[arg(short, long)]
+        force: bool,
+
+        /// Cap download bandwidth (e.g., 500K, 2M; 0 for unlimited)
+        #[arg(long, value_name = "RATE", value_parser = download::parse_rate)]
+        limit_rate: Option<u64>,
+    },
+
+    /// Run a long-lived JSON-RPC daemon for editor integrations (Unix only)
+    Daemon {
+        /// Stop the running daemon instead of starting one
+        #[arg(long)]
+        stop: bool,
+    },
+
+    /// Serve the local metadata cache over HTTP as a mirror for other machines
+    ServeMetadata {
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        bind: String,
+
+        /// Also serve cached JDK archives, advertising them under this base URL
+        /// (e.g., https://mirror.lan)
+        #[arg(long, value_name = "URL")]
+        downloads_url: Option<String>,
     },
 
     /// Run diagnostics on kopi installation
@@ -230,15 +664,97 @@ Examples:
         /// Run only specific category of checks
         #[arg(long, value_name = "CATEGORY")]
         check: Option<String>,
+
+        /// Verify every installed JDK against its install manifest
+        #[arg(long)]
+        deep: bool,
+
+        /// Run this tool through its shim (with `--check shims`, default: java)
+        #[arg(long, value_name = "TOOL", requires = "check")]
+        trace: Option<String>,
+
+        /// Seconds each check may run before it is reported as timed out (default: 8)
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
     },
 }
 
+impl Commands {
+    fn is_shell_hook(&self) -> bool {
+        matches!(
+            self,
+            Commands::Init { shell: Some(_), .. }
+                | Commands::Env {
+                    changed_only: true,
+                    ..
+                }
+                | Commands::Env { diff: true, .. }
+                | Commands::Notify { .. }
+        )
+    }
+
+    /// Whether the command inspects or edits the configuration, so it must
+    /// run even when the configuration cannot be applied
+    fn repairs_config(&self) -> bool {
+        matches!(self, Commands::Config { .. } | Commands::Doctor { .. })
+    }
+
+    /// Whether the command prints JSON, so failures are reported as JSON too
+    fn wants_json(&self) -> bool {
+        matches!(
+            self,
+            Commands::Install { json: true, .. }
+                | Commands::List { json: true, .. }
+                | Commands::Current { json: true, .. }
+                | Commands::Outdated { json: true }
+                | Commands::Audit { json: true, .. }
+                | Commands::Which { json: true, .. }
+                | Commands::Files { json: true, .. }
+                | Commands::Resolve { json: true, .. }
+                | Commands::Search { json: true, .. }
+                | Commands::Doctor { json: true, .. }
+                | Commands::Cache {
+                    command: CacheCommand::Search { json: true, .. }
+                }
+                | Commands::Jlink {
+                    command: JlinkCommand::List { json: true }
+                }
+        )
+    }
+}
+
+/// Report `error` and exit with its exit code. Commands run with `--json`
+/// print a `{code, message, suggestion, details}` object on stdout instead
+/// of the text report on stderr.
+fn exit_with_error(error: &KopiError, json: bool) -> ! {
+    if json {
+        println!("{}", format_error_json(error));
+    } else {
+        eprintln!("{}", format_error_chain(error));
+    }
+    std::process::exit(get_exit_code(error));
+}
+
 fn setup_logger(cli: &Cli) {
     logging::setup_logger(cli.verbose);
 }
 
+/// Connect to the daemon when `--use-daemon` is given, falling back to
+/// in-process execution when none is running
+fn daemon_client(use_daemon: bool, config: &KopiConfig) -> Option<DaemonClient> {
+    if !use_daemon {
+        return None;
+    }
+    let client = DaemonClient::connect(config.kopi_home());
+    if client.is_none() {
+        warn!("--use-daemon given but no kopi daemon is running; continuing in-process");
+    }
+    client
+}
+
 fn main() {
     let cli = Cli::parse();
+    let json = cli.command.wants_json();
 
     // Initialize logger based on CLI flags and environment
     setup_logger(&cli);
@@ -246,81 +762,340 @@ fn main() {
     // Load configuration once at startup
     let mut config = match new_kopi_config() {
         Ok(config) => config,
-        Err(e) => {
-            eprintln!("{}", format_error_chain(&e));
-            std::process::exit(get_exit_code(&e));
-        }
+        Err(e) => exit_with_error(&e, json),
     };
+    kopi::crash::install_panic_hook(&config);
+
+    if let Err(err) =
+        logging::enable_file_logging(&config.logging, config.kopi_home(), cli.log_file.as_deref())
+    {
+        warn!("Failed to open log file: {err}");
+    }
 
     if let Err(e) = config.apply_lock_timeout_overrides(cli.lock_timeout.as_deref()) {
-        eprintln!("{}", format_error_chain(&e));
-        std::process::exit(get_exit_code(&e));
+        exit_with_error(&e, json);
     }
 
-    if let Err(err) = kopi::locking::run_startup_hygiene(config.kopi_home(), &config.locking) {
-        warn!("Lock hygiene sweep failed: {err}");
+    ProgressFactory::configure(config.progress.style);
+    download::configure(&config.download);
+
+    // The shell hook runs on every directory change and notify on every
+    // prompt, so skip startup work they never need
+    if !cli.command.is_shell_hook() {
+        // Going online without the configured CA would fail later with an
+        // opaque TLS error, or trust less than the user asked for
+        if let Err(err) = kopi::security::tls::install_trust_store(&config.network) {
+            if cli.command.repairs_config() {
+                warn!("{err}");
+            } else {
+                exit_with_error(&err, json);
+            }
+        }
+
+        if let Err(err) = kopi::locking::run_startup_hygiene(config.kopi_home(), &config.locking) {
+            warn!("Lock hygiene sweep failed: {err}");
+        }
+
+        if let Err(err) = kopi::uninstall::cleanup::run_startup_cleanup(&config) {
+            warn!("Startup cleanup failed: {err}");
+        }
     }
 
+    let timings = TimingCollector::new(cli.timings);
+
     let result: Result<()> = (|| {
         match cli.command {
             Commands::Install {
-                version,
+                versions,
+                locked,
                 force,
                 dry_run,
                 timeout,
+                with_sources,
+                with_javadoc,
+                json,
+                ignore_policy,
+                limit_rate,
+                keep_archive,
+                sha256,
+                platform,
+                dest,
             } => {
-                let command = InstallCommand::new(&config, cli.no_progress)?;
-                command.execute(&version, force, dry_run, timeout)
+                let companions: Vec<PackageType> = [
+                    (with_sources, PackageType::Sources),
+                    (with_javadoc, PackageType::Javadoc),
+                ]
+                .into_iter()
+                .filter_map(|(requested, kind)| requested.then_some(kind))
+                .collect();
+                // With a shared home, JDKs go there for every user; cross-platform
+                // extractions into --dest never touch a Kopi home
+                let shared_config = platform
+                    .is_none()
+                    .then(|| shared_home::install_config(&config))
+                    .flatten();
+                if let Some(shared) = &shared_config
+                    && !dry_run
+                {
+                    shared_home::ensure_install_access(shared.kopi_home())?;
+                }
+                let install_config = shared_config.as_ref().unwrap_or(&config);
+                let command = InstallCommand::new(install_config, cli.no_progress)?
+                    .with_timings(timings.clone())
+                    .with_companions(companions.clone())
+                    .with_json_summary(json)
+                    .with_policy_override(ignore_policy)
+                    .with_rate_limit(limit_rate)
+                    .with_keep_archive(keep_archive)
+                    .with_pinned_sha256(sha256.clone());
+                if locked {
+                    return command.execute_locked(force, dry_run, timeout);
+                }
+                let result = if versions.len() > 1 {
+                    if platform.is_some() || sha256.is_some() {
+                        return Err(KopiError::ValidationError(
+                            "--platform and --sha256 apply to a single version".to_string(),
+                        ));
+                    }
+                    command.execute_batch(&versions, force, dry_run, timeout)
+                } else {
+                    let version = versions.into_iter().next().unwrap_or_default();
+                    if let (Some(platform), Some(dest)) = (platform, dest) {
+                        return command
+                            .execute_cross(&version, &platform, &dest, force, dry_run, timeout);
+                    }
+                    // The daemon only streams progress, so JSON summaries are built in-process;
+                    // policy overrides also stay local so the daemon never relaxes its own policy
+                    if let Some(mut client) =
+                        daemon_client(cli.use_daemon && !json && !ignore_policy, install_config)
+                    {
+                        let params = InstallParams {
+                            version,
+                            force,
+                            dry_run,
+                            timeout,
+                            companions,
+                            limit_rate,
+                            keep_archive,
+                            sha256,
+                        };
+                        return client.install(&params, ProgressFactory::create(cli.no_progress));
+                    }
+                    command.execute(&version, force, dry_run, timeout)
+                };
+                if let Some(shared) = &shared_config
+                    && let Err(err) = shared_home::share_locks(shared.kopi_home())
+                {
+                    warn!("Failed to open the shared lock directories to the group: {err}");
+                }
+                result
             }
-            Commands::List => {
+            Commands::List {
+                json,
+                detailed,
+                remote,
+                installed_only_diff,
+            } => {
                 let command = ListCommand::new(&config)?;
-                command.execute()
+                if remote {
+                    return command.execute_remote(json, installed_only_diff);
+                }
+                if let Some(mut client) = daemon_client(cli.use_daemon, &config) {
+                    return command.render(client.list()?, json, detailed);
+                }
+                command.execute(json, detailed)
             }
-            Commands::Shell { version, shell } => {
+            Commands::Shell {
+                version,
+                shell,
+                restore,
+                print_stack,
+            } => {
                 let command = ShellCommand::new(&config, cli.no_progress)?;
-                command.execute(&version, shell.as_deref())
+                if print_stack {
+                    command.execute_print_stack()
+                } else if restore {
+                    command.execute_restore(shell.as_deref())
+                } else {
+                    command.execute(version.as_deref().unwrap_or_default(), shell.as_deref())
+                }
             }
             Commands::Current { quiet, json } => {
                 let command = CurrentCommand::new(&config)?;
-                command.execute(quiet, json)
+                command.execute(quiet, json, cli.verbose > 0)
             }
             Commands::Env {
                 version,
                 shell,
                 export,
+                changed_only,
+                diff,
+                wsl_interop,
+                pure,
+                all_installed,
+                validate,
+            } => {
+                let command = EnvCommand::new(&config)?.with_wsl_interop(wsl_interop);
+                if validate {
+                    command.execute_validate(version.as_deref(), shell.as_deref())
+                } else if all_installed {
+                    command.execute_all_installed(shell.as_deref(), export)
+                } else if pure {
+                    command.execute_pure(version.as_deref(), shell.as_deref(), export)
+                } else if diff {
+                    command.execute_diff(version.as_deref(), shell.as_deref())
+                } else if changed_only {
+                    command.execute_changed_only(shell.as_deref())
+                } else {
+                    command.execute(version.as_deref(), shell.as_deref(), export)
+                }
+            }
+            Commands::Init {
+                shell,
+                dist,
+                version,
+                from_release_file,
+                gradle,
+                maven,
+                kopi_toml,
+                force,
             } => {
-                let command = EnvCommand::new(&config)?;
-                command.execute(version.as_deref(), shell.as_deref(), export)
+                let command = InitCommand::new(&config, cli.no_progress)?;
+                match shell {
+                    Some(shell) => command.execute(&shell),
+                    None => command.execute_project(&ProjectTemplate {
+                        distribution: dist,
+                        version,
+                        from_release_file,
+                        build_tool: if gradle {
+                            Some(BuildTool::Gradle)
+                        } else if maven {
+                            Some(BuildTool::Maven)
+                        } else {
+                            None
+                        },
+                        kopi_toml,
+                        force,
+                    }),
+                }
             }
-            Commands::Global { version } => {
+            Commands::Global {
+                version,
+                track,
+                unset,
+                show,
+            } => {
                 let command = GlobalCommand::new(&config, cli.no_progress)?;
-                command.execute(&version)
+                if unset {
+                    command.execute_unset()
+                } else if show {
+                    command.execute_show()
+                } else {
+                    match track {
+                        Some(track) => command.execute_track(&track),
+                        None => command.execute(version.as_deref().unwrap_or_default()),
+                    }
+                }
             }
-            Commands::Local { version } => {
+            Commands::Local {
+                version,
+                track,
+                unset,
+                show,
+                auto,
+            } => {
                 let command = LocalCommand::new(&config, cli.no_progress)?;
-                command.execute(&version)
+                if auto {
+                    command.execute_auto()
+                } else if unset {
+                    command.execute_unset()
+                } else if show {
+                    command.execute_show()
+                } else {
+                    match track {
+                        Some(track) => command.execute_track(&track),
+                        None => command.execute(version.as_deref().unwrap_or_default()),
+                    }
+                }
+            }
+            Commands::Outdated { json } => {
+                let command = OutdatedCommand::new(&config)?;
+                command.execute(json)
+            }
+            Commands::Audit {
+                json,
+                refresh,
+                fail_on,
+            } => {
+                let command = AuditCommand::new(&config)?;
+                command.execute(json, refresh, &fail_on)
             }
             Commands::Which {
                 version,
                 tool,
                 home,
                 json,
+                find_any,
+                sources,
+                javadoc,
             } => {
-                let command = WhichCommand::new(&config)?;
-                command.execute(version.as_deref(), &tool, home, json)
+                let companion = if sources {
+                    Some(PackageType::Sources)
+                } else if javadoc {
+                    Some(PackageType::Javadoc)
+                } else {
+                    None
+                };
+                let command = WhichCommand::new(&config)?.with_companion(companion);
+                if find_any {
+                    command.execute_find_any(version.as_deref(), &tool, home, json)
+                } else {
+                    command.execute(version.as_deref(), &tool, home, json)
+                }
+            }
+            Commands::Files {
+                version,
+                orphans,
+                json,
+            } => {
+                let command = FilesCommand::new(&config)?;
+                command.execute(&version, orphans, json)
+            }
+            Commands::Notify {
+                check,
+                force,
+                quiet,
+            } => {
+                let command = NotifyCommand::new(&config)?;
+                if check {
+                    command.execute_check(force, quiet)
+                } else {
+                    command.execute_show()
+                }
+            }
+            Commands::Resolve { tool, json } => {
+                let command = ResolveCommand::new(&config)?;
+                command.execute(&tool, json)
+            }
+            Commands::Cache { command } => {
+                command.execute_with_timings(&config, cli.no_progress, &timings)
             }
-            Commands::Cache { command } => command.execute(&config, cli.no_progress),
             Commands::Refresh => {
                 // Delegate to cache refresh command
-                let cache_cmd = CacheCommand::Refresh;
-                cache_cmd.execute(&config, cli.no_progress)
+                let cache_cmd = CacheCommand::Refresh {
+                    distributions: Vec::new(),
+                    only_installed: false,
+                };
+                cache_cmd.execute_with_timings(&config, cli.no_progress, &timings)
             }
             Commands::Search {
                 version,
                 compact,
                 detailed,
                 json,
+                release_date,
                 lts_only,
+                filters,
             } => {
                 // Delegate to cache search command
                 let cache_cmd = CacheCommand::Search {
@@ -328,16 +1103,33 @@ fn main() {
                     compact,
                     detailed,
                     json,
+                    release_date,
                     lts_only,
                     java_version: false,
                     distribution_version: false,
+                    filters,
                 };
-                cache_cmd.execute(&config, cli.no_progress)
+                cache_cmd.execute_with_timings(&config, cli.no_progress, &timings)
             }
-            Commands::Setup { force } => {
-                let command = SetupCommand::new(&config, cli.no_progress)?;
-                command.execute(force)
+            Commands::Setup {
+                force,
+                check,
+                non_interactive,
+                register_java_home,
+            } => {
+                let command = SetupCommand::new(&config, cli.no_progress)?
+                    .with_non_interactive(non_interactive)
+                    .with_java_home_registration(register_java_home);
+                if check {
+                    command.execute_check()
+                } else {
+                    command.execute(force)
+                }
             }
+            Commands::Config { command } => command.execute(&config),
+            Commands::Metadata { command } => command.execute(),
+            Commands::Integrate { command } => command.execute(&config, cli.no_progress),
+            Commands::Macos { command } => command.execute(&config, cli.no_progress),
             Commands::Shim { command } => command.execute(&config),
             Commands::Uninstall {
                 version,
@@ -345,8 +1137,16 @@ fn main() {
                 dry_run,
                 all,
                 cleanup,
+                unused,
+                older_than,
+                keep_archives,
             } => {
-                let command = UninstallCommand::new(&config, cli.no_progress)?;
+                let command = UninstallCommand::new(&config, cli.no_progress)?
+                    .with_keep_archives(keep_archives);
+                if unused {
+                    let older_than = older_than.as_deref().unwrap_or(DEFAULT_UNUSED_AGE);
+                    return command.execute_unused(older_than, force, dry_run, cli.no_progress);
+                }
                 command.execute(
                     version.as_deref(),
                     force,
@@ -356,15 +1156,69 @@ fn main() {
                     cli.no_progress,
                 )
             }
-            Commands::Doctor { json, check } => {
-                let command = DoctorCommand::new(&config)?;
+            Commands::Adopt {
+                paths,
+                scan,
+                dry_run,
+            } => {
+                let command = AdoptCommand::new(&config, cli.no_progress)?;
+                command.execute(&paths, scan, dry_run)
+            }
+            Commands::PinProtect { version, remove } => {
+                let command = PinProtectCommand::new(&config, cli.no_progress)?;
+                command.execute(&version, remove)
+            }
+            Commands::Jlink { command } => command.execute(&config, cli.no_progress),
+            Commands::Overlay { version } => {
+                let command = OverlayCommand::new(&config, cli.no_progress)?;
+                command.execute(version.as_deref())
+            }
+            Commands::Lock { version, platforms } => {
+                let command = LockCommand::new(&config)?;
+                command.execute(version.as_deref(), &platforms)
+            }
+            Commands::Upgrade {
+                version,
+                dry_run,
+                force,
+                limit_rate,
+            } => {
+                let command =
+                    UpgradeCommand::new(&config, cli.no_progress)?.with_rate_limit(limit_rate);
+                command.execute(version.as_deref(), dry_run, force)
+            }
+            Commands::Daemon { stop } => {
+                let command = DaemonCommand::new(&config)?;
+                command.execute(stop)
+            }
+            Commands::ServeMetadata {
+                bind,
+                downloads_url,
+            } => {
+                let command = ServeMetadataCommand::new(&config)?;
+                command.execute(&bind, downloads_url.as_deref())
+            }
+            Commands::Doctor {
+                json,
+                check,
+                deep,
+                trace,
+                timeout,
+            } => {
+                let command = DoctorCommand::new(&config)?
+                    .with_deep(deep)
+                    .with_trace(trace)
+                    .with_timeout(timeout.map(std::time::Duration::from_secs));
                 command.execute(json, cli.verbose > 0, check.as_deref())
             }
         }
     })();
 
+    if let Some(report) = timings.render() {
+        eprintln!("{report}");
+    }
+
     if let Err(e) = result {
-        eprintln!("{}", format_error_chain(&e));
-        std::process::exit(get_exit_code(&e));
+        exit_with_error(&e, json);
     }
 }
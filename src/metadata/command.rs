@@ -0,0 +1,367 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metadata sources backed by external executables.
+//!
+//! Like git credential helpers, a `type = "command"` source names a program
+//! that kopi runs once per request. The request is written to the program's
+//! stdin as a single JSON object and the program answers on stdout:
+//!
+//! - `{"protocol": 1, "operation": "list", ...}` and
+//!   `{"protocol": 1, "operation": "distribution", "distribution": "acme", ...}`
+//!   expect `{"packages": [...]}` with packages in kopi's metadata format.
+//! - `{"protocol": 1, "operation": "package", "package_id": "...", ...}` expects
+//!   `{"download_url": "...", "checksum": "...", "checksum_type": "sha256"}`.
+//!
+//! Every request also carries the current `os` and `architecture`. A non-zero
+//! exit status fails the request and the program's stderr is included in the
+//! error.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{KopiError, Result};
+use crate::indicator::ProgressIndicator;
+use crate::metadata::source::{MetadataSource, PackageDetails};
+use crate::models::metadata::JdkMetadata;
+use crate::models::package::ChecksumType;
+use crate::platform::{get_current_architecture, get_current_os};
+
+/// Version of the JSON protocol spoken with source executables
+pub const COMMAND_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct CommandRequest<'a> {
+    protocol: u32,
+    operation: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distribution: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package_id: Option<&'a str>,
+    os: String,
+    architecture: String,
+}
+
+#[derive(Deserialize)]
+struct PackagesResponse {
+    packages: Vec<JdkMetadata>,
+}
+
+#[derive(Deserialize)]
+struct PackageDetailsResponse {
+    download_url: String,
+    #[serde(default)]
+    checksum: Option<String>,
+    #[serde(default)]
+    checksum_type: Option<ChecksumType>,
+}
+
+pub struct CommandMetadataSource {
+    name: String,
+    program: PathBuf,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+impl CommandMetadataSource {
+    pub fn new(name: String, program: PathBuf, args: Vec<String>, timeout: Duration) -> Self {
+        Self {
+            name,
+            program,
+            args,
+            timeout,
+        }
+    }
+
+    fn request<T: for<'de> Deserialize<'de>>(
+        &self,
+        operation: &str,
+        distribution: Option<&str>,
+        package_id: Option<&str>,
+    ) -> Result<T> {
+        let request = CommandRequest {
+            protocol: COMMAND_PROTOCOL_VERSION,
+            operation,
+            distribution,
+            package_id,
+            os: get_current_os(),
+            architecture: get_current_architecture(),
+        };
+        let input = serde_json::to_vec(&request)?;
+        let output = self.run(&input)?;
+
+        serde_json::from_slice(&output).map_err(|e| {
+            KopiError::MetadataFetch(format!(
+                "Metadata source '{}' returned an invalid '{operation}' response: {e}",
+                self.name
+            ))
+        })
+    }
+
+    /// Run the executable with `input` on stdin and return its stdout
+    fn run(&self, input: &[u8]) -> Result<Vec<u8>> {
+        log::debug!(
+            "Running metadata source '{}': {} {:?}",
+            self.name,
+            self.program.display(),
+            self.args
+        );
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                KopiError::MetadataFetch(format!(
+                    "Failed to run metadata source '{}' ({}): {e}",
+                    self.name,
+                    self.program.display()
+                ))
+            })?;
+
+        // Drain both pipes on their own threads so a chatty program cannot
+        // block on a full pipe while we wait for it to exit
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stdout_reader = thread::spawn(move || {
+            let mut buffer = Vec::new();
+            stdout.read_to_end(&mut buffer).map(|_| buffer)
+        });
+        let stderr_reader = thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = stderr.read_to_end(&mut buffer);
+            buffer
+        });
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // A program that ignores its input may exit before reading it
+            if let Err(e) = stdin.write_all(input)
+                && e.kind() != std::io::ErrorKind::BrokenPipe
+            {
+                let _ = child.kill();
+                return Err(e.into());
+            }
+        }
+
+        let start = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if start.elapsed() >= self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(KopiError::MetadataFetch(format!(
+                    "Metadata source '{}' timed out after {} seconds",
+                    self.name,
+                    self.timeout.as_secs()
+                )));
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        let stdout = stdout_reader
+            .join()
+            .map_err(|_| KopiError::SystemError("Failed to read command output".to_string()))??;
+        let stderr = stderr_reader.join().unwrap_or_default();
+
+        if !status.success() {
+            let stderr = String::from_utf8_lossy(&stderr);
+            return Err(KopiError::MetadataFetch(format!(
+                "Metadata source '{}' exited with {status}: {}",
+                self.name,
+                stderr.trim()
+            )));
+        }
+
+        Ok(stdout)
+    }
+}
+
+impl MetadataSource for CommandMetadataSource {
+    fn id(&self) -> &str {
+        &self.name
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_available(&self) -> Result<bool> {
+        Ok(which::which(&self.program).is_ok())
+    }
+
+    fn fetch_all(&self, progress: &mut dyn ProgressIndicator) -> Result<Vec<JdkMetadata>> {
+        progress.set_message(format!("Fetching metadata from '{}'", self.name));
+        let response: PackagesResponse = self.request("list", None, None)?;
+        Ok(response.packages)
+    }
+
+    fn fetch_distribution(
+        &self,
+        distribution: &str,
+        progress: &mut dyn ProgressIndicator,
+    ) -> Result<Vec<JdkMetadata>> {
+        progress.set_message(format!(
+            "Fetching {distribution} metadata from '{}'",
+            self.name
+        ));
+        let response: PackagesResponse = self.request("distribution", Some(distribution), None)?;
+        Ok(response
+            .packages
+            .into_iter()
+            .filter(|package| package.distribution == distribution)
+            .collect())
+    }
+
+    fn fetch_package_details(
+        &self,
+        package_id: &str,
+        progress: &mut dyn ProgressIndicator,
+    ) -> Result<PackageDetails> {
+        progress.set_message(format!("Looking up package details: {package_id}"));
+        let response: PackageDetailsResponse = self.request("package", None, Some(package_id))?;
+        Ok(PackageDetails {
+            download_url: response.download_url,
+            checksum: response.checksum,
+            checksum_type: response.checksum_type,
+        })
+    }
+
+    fn last_updated(&self) -> Result<Option<DateTime<Utc>>> {
+        Ok(None)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::indicator::SilentProgress;
+    use crate::models::package::{ArchiveType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::version::Version;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn packages_json() -> String {
+        let package = JdkMetadata {
+            id: "acme-21".to_string(),
+            distribution: "acme".to_string(),
+            version: Version::new(21, 0, 4),
+            distribution_version: Version::new(21, 0, 4),
+            architecture: Architecture::X64,
+            operating_system: OperatingSystem::Linux,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: None,
+            checksum: None,
+            checksum_type: None,
+            size: 190_000_000,
+            lib_c_type: Some("glibc".to_string()),
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        };
+        serde_json::json!({ "packages": [package] }).to_string()
+    }
+
+    /// Write a plugin that answers each operation from the request it reads
+    fn plugin(temp_dir: &TempDir, body: &str) -> PathBuf {
+        let path = temp_dir.path().join("kopi-source-acme");
+        fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn source(program: PathBuf) -> CommandMetadataSource {
+        CommandMetadataSource::new(
+            "acme-plugin".to_string(),
+            program,
+            Vec::new(),
+            Duration::from_secs(10),
+        )
+    }
+
+    #[test]
+    fn test_command_source_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let program = plugin(
+            &temp_dir,
+            &format!(
+                r#"request=$(cat)
+case "$request" in
+  *'"operation":"package"'*) echo '{{"download_url":"https://jdk.acme.example/acme-21.tar.gz","checksum":"abc","checksum_type":"sha256"}}' ;;
+  *'"protocol":1'*) echo '{packages}' ;;
+  *) exit 3 ;;
+esac"#,
+                packages = packages_json()
+            ),
+        );
+        let source = source(program);
+
+        assert!(source.is_available().unwrap());
+        let packages = source.fetch_all(&mut SilentProgress).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].id, "acme-21");
+        assert!(
+            source
+                .fetch_distribution("temurin", &mut SilentProgress)
+                .unwrap()
+                .is_empty()
+        );
+
+        let details = source
+            .fetch_package_details("acme-21", &mut SilentProgress)
+            .unwrap();
+        assert_eq!(details.checksum.as_deref(), Some("abc"));
+        assert_eq!(details.checksum_type, Some(ChecksumType::Sha256));
+    }
+
+    #[test]
+    fn test_command_source_failure_reports_stderr() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = source(plugin(&temp_dir, "echo 'token expired' >&2\nexit 1"));
+
+        let error = source.fetch_all(&mut SilentProgress).unwrap_err();
+        assert!(error.to_string().contains("token expired"), "{error}");
+    }
+
+    #[test]
+    fn test_command_source_times_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut source = source(plugin(&temp_dir, "sleep 5"));
+        source.timeout = Duration::from_millis(200);
+
+        let error = source.fetch_all(&mut SilentProgress).unwrap_err();
+        assert!(error.to_string().contains("timed out"), "{error}");
+    }
+
+    #[test]
+    fn test_missing_command_is_unavailable() {
+        let source = source(PathBuf::from("/nonexistent/kopi-source-acme"));
+        assert!(!source.is_available().unwrap());
+        assert!(source.fetch_all(&mut SilentProgress).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metadata source for `[[custom_distribution]]` entries.
+//!
+//! A custom distribution publishes a single JSON document containing an array
+//! of packages in the same format as kopi's metadata files. Every package in
+//! the document is attributed to the configured distribution name, so the
+//! document itself does not have to agree on naming.
+
+use attohttpc::Session;
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::CustomDistributionConfig;
+use crate::error::{KopiError, Result};
+use crate::indicator::ProgressIndicator;
+use crate::metadata::source::{MetadataSource, PackageDetails};
+use crate::models::metadata::JdkMetadata;
+use crate::security::tls;
+use crate::user_agent;
+
+/// Metadata source serving a single custom distribution
+pub struct CustomDistributionSource {
+    name: String,
+    metadata_url: String,
+    client: Session,
+}
+
+impl CustomDistributionSource {
+    /// Create a source for a custom distribution entry
+    pub fn new(config: &CustomDistributionConfig) -> Result<Self> {
+        config.validate()?;
+
+        let mut client = Session::new();
+        tls::configure_session(&mut client);
+        client.header("User-Agent", user_agent::metadata_client());
+
+        Ok(Self {
+            name: config.name.clone(),
+            metadata_url: config.metadata_url.clone(),
+            client,
+        })
+    }
+
+    /// Load the metadata document and attribute its packages to this distribution
+    fn load_packages(&self) -> Result<Vec<JdkMetadata>> {
+        let content = match self.local_path() {
+            Some(path) => fs::read_to_string(&path).map_err(|e| {
+                KopiError::MetadataFetch(format!(
+                    "Failed to read metadata for '{}' from {}: {e}",
+                    self.name,
+                    path.display()
+                ))
+            })?,
+            None => self.download()?,
+        };
+
+        let mut packages: Vec<JdkMetadata> = serde_json::from_str(&content).map_err(|e| {
+            KopiError::MetadataFetch(format!("Failed to parse metadata for '{}': {e}", self.name))
+        })?;
+
+        for package in &mut packages {
+            package.distribution = self.name.clone();
+        }
+
+        Ok(packages)
+    }
+
+    fn local_path(&self) -> Option<PathBuf> {
+        self.metadata_url.strip_prefix("file://").map(PathBuf::from)
+    }
+
+    fn download(&self) -> Result<String> {
+        let response = self.client.get(&self.metadata_url).send().map_err(|e| {
+            KopiError::MetadataFetch(format!("Failed to fetch metadata for '{}': {e}", self.name))
+        })?;
+
+        if !response.is_success() {
+            return Err(KopiError::MetadataFetch(format!(
+                "Failed to fetch metadata for '{}': HTTP {}",
+                self.name,
+                response.status()
+            )));
+        }
+
+        response.text().map_err(|e| {
+            KopiError::MetadataFetch(format!("Failed to read metadata for '{}': {e}", self.name))
+        })
+    }
+}
+
+impl MetadataSource for CustomDistributionSource {
+    fn id(&self) -> &str {
+        &self.name
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_available(&self) -> Result<bool> {
+        match self.local_path() {
+            Some(path) => Ok(path.is_file()),
+            // Reachability is reported by the fetch itself
+            None => Ok(true),
+        }
+    }
+
+    fn fetch_all(&self, progress: &mut dyn ProgressIndicator) -> Result<Vec<JdkMetadata>> {
+        progress.set_message(format!(
+            "Fetching metadata for custom distribution '{}'",
+            self.name
+        ));
+        let packages = self.load_packages()?;
+        progress.set_message(format!(
+            "Loaded {} packages for '{}'",
+            packages.len(),
+            self.name
+        ));
+        Ok(packages)
+    }
+
+    fn fetch_distribution(
+        &self,
+        distribution: &str,
+        progress: &mut dyn ProgressIndicator,
+    ) -> Result<Vec<JdkMetadata>> {
+        if distribution != self.name {
+            return Ok(Vec::new());
+        }
+        self.fetch_all(progress)
+    }
+
+    fn fetch_package_details(
+        &self,
+        package_id: &str,
+        progress: &mut dyn ProgressIndicator,
+    ) -> Result<PackageDetails> {
+        progress.set_message(format!("Looking up package details: {package_id}"));
+
+        let package = self
+            .load_packages()?
+            .into_iter()
+            .find(|package| package.id == package_id)
+            .ok_or_else(|| KopiError::NotFound(format!("Package '{package_id}' not found")))?;
+
+        let download_url = package.download_url.ok_or_else(|| {
+            KopiError::NotFound(format!("Download URL not found for package '{package_id}'"))
+        })?;
+
+        Ok(PackageDetails {
+            download_url,
+            checksum: package.checksum,
+            checksum_type: package.checksum_type,
+        })
+    }
+
+    fn last_updated(&self) -> Result<Option<DateTime<Utc>>> {
+        let modified = self
+            .local_path()
+            .and_then(|path| fs::metadata(path).ok())
+            .and_then(|metadata| metadata.modified().ok());
+        Ok(modified.map(DateTime::<Utc>::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicator::SilentProgress;
+    use crate::models::package::{ArchiveType, ChecksumType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::version::Version;
+    use tempfile::TempDir;
+
+    fn package() -> JdkMetadata {
+        JdkMetadata {
+            id: "acme-21.0.4-linux-x64".to_string(),
+            // Documents may carry upstream names; the configured name wins
+            distribution: "temurin".to_string(),
+            version: Version::new(21, 0, 4),
+            distribution_version: Version::new(21, 0, 4),
+            architecture: Architecture::X64,
+            operating_system: OperatingSystem::Linux,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: Some("https://jdk.acme.example/acme-21.0.4.tar.gz".to_string()),
+            checksum: Some("abc123".to_string()),
+            checksum_type: Some(ChecksumType::Sha256),
+            size: 190_000_000,
+            lib_c_type: Some("glibc".to_string()),
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }
+    }
+
+    fn source_for(temp_dir: &TempDir) -> CustomDistributionSource {
+        let path = temp_dir.path().join("metadata.json");
+        fs::write(&path, serde_json::to_string(&vec![package()]).unwrap()).unwrap();
+        CustomDistributionSource::new(&CustomDistributionConfig {
+            name: "acme".to_string(),
+            metadata_url: format!("file://{}", path.display()),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_packages_are_attributed_to_custom_distribution() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = source_for(&temp_dir);
+
+        assert!(source.is_available().unwrap());
+        let packages = source.fetch_all(&mut SilentProgress).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].distribution, "acme");
+        assert_eq!(packages[0].version.major(), 21);
+
+        assert!(
+            source
+                .fetch_distribution("temurin", &mut SilentProgress)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            source
+                .fetch_distribution("acme", &mut SilentProgress)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_fetch_package_details() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = source_for(&temp_dir);
+
+        let details = source
+            .fetch_package_details("acme-21.0.4-linux-x64", &mut SilentProgress)
+            .unwrap();
+        assert_eq!(
+            details.download_url,
+            "https://jdk.acme.example/acme-21.0.4.tar.gz"
+        );
+        assert_eq!(details.checksum.as_deref(), Some("abc123"));
+
+        assert!(matches!(
+            source.fetch_package_details("missing", &mut SilentProgress),
+            Err(KopiError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_document_is_unavailable() {
+        let source = CustomDistributionSource::new(&CustomDistributionConfig {
+            name: "acme".to_string(),
+            metadata_url: "file:///nonexistent/kopi/metadata.json".to_string(),
+        })
+        .unwrap();
+
+        assert!(!source.is_available().unwrap());
+        assert!(source.fetch_all(&mut SilentProgress).is_err());
+    }
+}
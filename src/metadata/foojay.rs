@@ -14,13 +14,15 @@
 
 use crate::api::client::ApiClient;
 use crate::api::query::PackageQuery;
+use crate::cache::parse_release_date;
 use crate::error::Result;
 use crate::indicator::ProgressIndicator;
-use crate::metadata::source::{MetadataSource, PackageDetails};
-use crate::models::metadata::JdkMetadata;
+use crate::metadata::source::{MetadataSource, PackageDetails, PartialMetadata};
+use crate::models::metadata::{JdkMetadata, normalize_features};
 use crate::models::package::{ArchiveType, ChecksumType, PackageType};
 use crate::models::platform::{Architecture, OperatingSystem};
 use crate::version::Version;
+use log::warn;
 use std::str::FromStr;
 
 pub struct FoojayMetadataSource {
@@ -52,9 +54,9 @@ impl FoojayMetadataSource {
         let distribution_version =
             Version::from_str(&package.distribution_version).unwrap_or_else(|_| version.clone());
 
-        // Parse architecture from filename
-        let architecture = crate::cache::parse_architecture_from_filename(&package.filename)
-            .unwrap_or(Architecture::X64);
+        // Parse architecture, falling back to the filename
+        let architecture =
+            crate::cache::parse_package_architecture(&package).unwrap_or(Architecture::X64);
 
         // Parse operating system
         let operating_system =
@@ -82,9 +84,11 @@ impl FoojayMetadataSource {
             size: package.size,
             lib_c_type: package.lib_c_type,
             javafx_bundled: package.javafx_bundled,
+            features: normalize_features(&package.feature),
             term_of_support: package.term_of_support,
             release_status: package.release_status,
             latest_build_available: package.latest_build_available,
+            release_date: package.release_date.as_deref().and_then(parse_release_date),
         })
     }
 }
@@ -107,6 +111,11 @@ impl MetadataSource for FoojayMetadataSource {
     }
 
     fn fetch_all(&self, progress: &mut dyn ProgressIndicator) -> Result<Vec<JdkMetadata>> {
+        self.fetch_all_partial(progress)
+            .map(|partial| partial.metadata)
+    }
+
+    fn fetch_all_partial(&self, progress: &mut dyn ProgressIndicator) -> Result<PartialMetadata> {
         // Create child progress for Foojay API operations
         // Foojay always creates child progress according to Phase 5 requirements
         let mut child = progress.create_child();
@@ -132,7 +141,22 @@ impl MetadataSource for FoojayMetadataSource {
 
         child.update(2, Some(4));
         child.set_message("Fetching package list...".to_string());
-        let packages = self.client.get_packages(Some(query))?;
+        let (packages, skipped) = match self.client.get_packages(Some(query.clone())) {
+            Ok(packages) => (packages, Vec::new()),
+            Err(e) => {
+                // During refresh storms the single large request is the first to
+                // be throttled; smaller per-distribution requests keep what succeeds
+                warn!(
+                    "Failed to fetch the package list ({e}); fetching each distribution separately"
+                );
+                child.set_message("Fetching packages per distribution...".to_string());
+                let partial = self
+                    .client
+                    .get_packages_by_distribution(query)
+                    .map_err(|_| e)?;
+                (partial.packages, partial.skipped)
+            }
+        };
 
         // Step 3: Convert to JdkMetadata
         child.update(3, Some(4));
@@ -156,7 +180,10 @@ impl MetadataSource for FoojayMetadataSource {
             child.error("Failed to process Foojay metadata".to_string());
         }
 
-        result
+        Ok(PartialMetadata {
+            metadata: result?,
+            skipped,
+        })
     }
 
     fn fetch_distribution(
@@ -332,9 +359,11 @@ mod tests {
             size: 195000000,
             lib_c_type: Some("glibc".to_string()),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         };
 
         let result = source.convert_package_to_metadata_incomplete(api_package);
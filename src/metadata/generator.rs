@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod publish;
 pub mod state;
 pub mod types;
 pub mod updater;
@@ -20,7 +21,7 @@ pub mod writer;
 
 use crate::error::{KopiError, Result};
 use crate::indicator::SilentProgress;
-use crate::metadata::index::{IndexFile, IndexFileEntry};
+use crate::metadata::index::{DeltaManifest, IndexFile, IndexFileEntry};
 use crate::metadata::{FoojayMetadataSource, MetadataSource};
 use crate::models::metadata::JdkMetadata;
 use chrono::Utc;
@@ -91,14 +92,15 @@ impl MetadataGenerator {
         let organized_files = self.organize_metadata(complete_metadata)?;
         println!("  Organized into {} files", organized_files.len());
 
-        // Step 7: Create index.json
-        let index = self.create_index(&organized_files)?;
+        // Step 7: Create index.json and diff it against the previous run
+        let mut index = self.create_index(&organized_files)?;
+        let deltas = self.prepare_deltas(output_dir, &mut index);
 
         // Step 8: Write files (or show dry run output)
         if self.config.dry_run {
-            self.show_dry_run_output(&index, &organized_files);
+            self.show_dry_run_output(&index, &organized_files, &deltas);
         } else {
-            self.write_output(output_dir, &index, &organized_files)?;
+            self.write_output(output_dir, &index, &organized_files, &deltas)?;
             println!(
                 "✅ Successfully generated metadata in {}",
                 output_dir.display()
@@ -108,6 +110,17 @@ impl MetadataGenerator {
         Ok(())
     }
 
+    /// Lay out already complete `metadata` as the files of an HTTP metadata
+    /// source: one file per platform and distribution plus `index.json`
+    pub fn build_files(
+        &self,
+        metadata: Vec<JdkMetadata>,
+    ) -> Result<(IndexFile, HashMap<String, FileMetadata>)> {
+        let files = self.organize_metadata(metadata)?;
+        let index = self.create_index(&files)?;
+        Ok((index, files))
+    }
+
     /// Filter metadata by distribution
     fn filter_by_distribution(&self, metadata: Vec<JdkMetadata>) -> Vec<JdkMetadata> {
         if let Some(distributions) = &self.config.distributions {
@@ -348,27 +361,57 @@ impl MetadataGenerator {
             updated: Utc::now().to_rfc3339(),
             files: entries,
             generator_config: Some(self.config.clone()),
+            previous_updated: None,
         })
     }
 
+    /// Diff the new index against the one already published in `previous_dir`
+    fn prepare_deltas(&self, previous_dir: &Path, index: &mut IndexFile) -> Vec<DeltaManifest> {
+        let Some(previous) = self::writer::read_existing_index(previous_dir) else {
+            return Vec::new();
+        };
+
+        let deltas = index.delta_from(&previous);
+        index.previous_updated = Some(previous.updated);
+        println!(
+            "  {} distributions changed since previous index",
+            deltas.len()
+        );
+        deltas
+    }
+
     /// Write output files
     fn write_output(
         &self,
         output_dir: &Path,
         index: &IndexFile,
         files: &HashMap<String, FileMetadata>,
+        deltas: &[DeltaManifest],
     ) -> Result<()> {
         // Create output directory
         fs::create_dir_all(output_dir)?;
 
         // Delegate to writer module
-        self::writer::write_output(&self.config, output_dir, index, files)
+        self::writer::write_output(&self.config, output_dir, index, files, deltas)
     }
 
     /// Show dry run output
-    fn show_dry_run_output(&self, index: &IndexFile, files: &HashMap<String, FileMetadata>) {
+    fn show_dry_run_output(
+        &self,
+        index: &IndexFile,
+        files: &HashMap<String, FileMetadata>,
+        deltas: &[DeltaManifest],
+    ) {
         println!("\n📋 Dry run - would create the following files:");
         println!("  index.json ({} entries)", index.files.len());
+        for manifest in deltas {
+            println!(
+                "  {} ({} changed, {} removed)",
+                DeltaManifest::path_for(&manifest.distribution),
+                manifest.changed.len(),
+                manifest.removed.len()
+            );
+        }
 
         // Sort file paths for consistent output
         let mut sorted_paths: Vec<_> = files.keys().cloned().collect();
@@ -379,7 +422,7 @@ impl MetadataGenerator {
             println!("  {} ({} bytes)", path, metadata.content.len());
         }
 
-        println!("\nTotal: {} files", files.len() + 1);
+        println!("\nTotal: {} files", files.len() + deltas.len() + 1);
     }
 
     /// Validate metadata directory structure
@@ -409,13 +452,14 @@ impl MetadataGenerator {
         let organized_files = self.organize_metadata(all_metadata)?;
         println!("  Organized into {} files", organized_files.len());
 
-        let index = self.create_index(&organized_files)?;
+        let mut index = self.create_index(&organized_files)?;
+        let deltas = self.prepare_deltas(input_dir, &mut index);
 
         if self.config.dry_run {
-            self.show_dry_run_output(&index, &organized_files);
+            self.show_dry_run_output(&index, &organized_files, &deltas);
             updater.show_detailed_update_summary(&result.detailed_changes);
         } else {
-            self.write_output(output_dir, &index, &organized_files)?;
+            self.write_output(output_dir, &index, &organized_files, &deltas)?;
             println!(
                 "✅ Successfully updated metadata in {}",
                 output_dir.display()
@@ -282,6 +282,7 @@ impl UpdateHandler {
             || existing.latest_build_available != current.latest_build_available
             || existing.release_status != current.release_status
             || existing.term_of_support != current.term_of_support
+            || existing.release_date != current.release_date
     }
 
     /// Show detailed update summary in dry run mode
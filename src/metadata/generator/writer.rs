@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::error::{KopiError, Result};
-use crate::metadata::index::IndexFile;
+use crate::metadata::index::{DELTA_DIR, DeltaManifest, IndexFile};
 use crate::models::metadata::JdkMetadata;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -29,7 +29,10 @@ pub fn write_output(
     output_dir: &Path,
     index: &IndexFile,
     files: &HashMap<String, FileMetadata>,
+    deltas: &[DeltaManifest],
 ) -> Result<()> {
+    write_delta_manifests(config, output_dir, deltas)?;
+
     // Check if resume is needed based on .state files
     let has_state_files = state::detect_resume_state(output_dir);
     let should_resume = if config.force {
@@ -57,6 +60,45 @@ pub fn write_output(
     }
 }
 
+/// Read the index of a previously generated metadata directory, if any
+pub fn read_existing_index(dir: &Path) -> Option<IndexFile> {
+    let content = fs::read_to_string(dir.join("index.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Replace the delta manifests in `output_dir` with the ones for this run
+fn write_delta_manifests(
+    config: &GeneratorConfig,
+    output_dir: &Path,
+    deltas: &[DeltaManifest],
+) -> Result<()> {
+    let delta_dir = output_dir.join(DELTA_DIR);
+    if delta_dir.exists() {
+        fs::remove_dir_all(&delta_dir)?;
+    }
+
+    for manifest in deltas {
+        let path = output_dir.join(DeltaManifest::path_for(&manifest.distribution));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = if config.minify_json {
+            serde_json::to_string(manifest)?
+        } else {
+            serde_json::to_string_pretty(manifest)?
+        };
+        fs::write(&path, &content)?;
+        report_progress(&format!(
+            "Wrote {} ({} changed, {} removed)",
+            DeltaManifest::path_for(&manifest.distribution),
+            manifest.changed.len(),
+            manifest.removed.len()
+        ));
+    }
+
+    Ok(())
+}
+
 /// Write output files without state management (traditional approach)
 fn write_output_without_state(
     config: &GeneratorConfig,
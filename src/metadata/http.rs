@@ -14,34 +14,64 @@
 
 use attohttpc::Session;
 use chrono::{DateTime, Utc};
-use log::{info, warn};
+use log::{debug, info, warn};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 
 use crate::error::{KopiError, Result};
 use crate::indicator::ProgressIndicator;
 use crate::metadata::index::{IndexFile, IndexFileEntry};
 use crate::metadata::source::{MetadataSource, PackageDetails};
 use crate::models::metadata::JdkMetadata;
+use crate::models::package::ChecksumType;
 use crate::platform::{get_current_architecture, get_current_os, get_foojay_libc_type};
+use crate::security::tls;
 use crate::user_agent;
 
 /// HTTP/Web metadata source that fetches from static web servers
 pub struct HttpMetadataSource {
     base_url: String,
     client: Session,
+    chunk_cache: Option<PathBuf>,
+    checksum_sidecars: bool,
 }
 
+/// Checksum files looked up next to an archive, in order of preference
+const CHECKSUM_SIDECARS: [(&str, ChecksumType); 2] = [
+    ("sha256", ChecksumType::Sha256),
+    ("sha512", ChecksumType::Sha512),
+];
+
 impl HttpMetadataSource {
     /// Create a new HTTP metadata source
     pub fn new(base_url: String) -> Self {
         let mut client = Session::new();
+        tls::configure_session(&mut client);
         client.header("User-Agent", user_agent::metadata_client());
 
         Self {
             base_url: base_url.trim_end_matches('/').to_string(),
             client,
+            chunk_cache: None,
+            checksum_sidecars: false,
         }
     }
 
+    /// Keep downloaded metadata files in `dir` and only re-download files
+    /// whose checksum in the index no longer matches the local copy
+    pub fn with_chunk_cache(mut self, dir: PathBuf) -> Self {
+        self.chunk_cache = Some(dir);
+        self
+    }
+
+    /// Look for `<archive>.sha256`/`.sha512` files next to archives served
+    /// from this source's host when the package metadata has no checksum
+    pub fn with_checksum_sidecars(mut self, enabled: bool) -> Self {
+        self.checksum_sidecars = enabled;
+        self
+    }
+
     /// Fetch the index file
     pub(crate) fn fetch_index(&self) -> Result<IndexFile> {
         let url = format!("{}/index.json", self.base_url);
@@ -101,8 +131,74 @@ impl HttpMetadataSource {
             .collect()
     }
 
-    /// Fetch a metadata file from the server
-    fn fetch_metadata_file(&self, path: &str) -> Result<Vec<JdkMetadata>> {
+    /// Fetch a metadata file, reusing the cached copy when it is unchanged
+    fn fetch_metadata_entry(&self, entry: &IndexFileEntry) -> Result<Vec<JdkMetadata>> {
+        let cached_path = self.cached_chunk_path(&entry.path);
+
+        if let Some(cached_path) = &cached_path
+            && let Some(expected) = entry.checksum.as_deref()
+            && let Ok(content) = fs::read(cached_path)
+            && checksum_matches(&content, expected)
+            && let Ok(metadata) = serde_json::from_slice::<Vec<JdkMetadata>>(&content)
+        {
+            debug!("Using cached metadata chunk for {}", entry.path);
+            return Ok(metadata);
+        }
+
+        let content = self.download_metadata_file(&entry.path)?;
+        let metadata: Vec<JdkMetadata> = serde_json::from_str(&content).map_err(|e| {
+            KopiError::MetadataFetch(format!("Failed to parse {}: {e}", entry.path))
+        })?;
+
+        if let Some(cached_path) = cached_path
+            && let Some(parent) = cached_path.parent()
+            && let Err(e) =
+                fs::create_dir_all(parent).and_then(|_| fs::write(&cached_path, &content))
+        {
+            warn!("Failed to cache metadata chunk {}: {e}", entry.path);
+        }
+
+        Ok(metadata)
+    }
+
+    /// Local path for a cached metadata file, rejecting paths that escape the cache
+    fn cached_chunk_path(&self, path: &str) -> Option<PathBuf> {
+        let cache_dir = self.chunk_cache.as_ref()?;
+        let relative = Path::new(path);
+        if relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            Some(cache_dir.join(relative))
+        } else {
+            None
+        }
+    }
+
+    /// Download and parse one checksum file, treating any failure as absent
+    fn fetch_sidecar(&self, url: &str, checksum_type: ChecksumType) -> Option<String> {
+        let response = match self.client.get(url).send() {
+            Ok(response) if response.is_success() => response,
+            Ok(response) => {
+                debug!("No checksum file at {url}: HTTP {}", response.status());
+                return None;
+            }
+            Err(e) => {
+                debug!("Failed to fetch checksum file {url}: {e}");
+                return None;
+            }
+        };
+
+        let body = response.text().ok()?;
+        let checksum = parse_checksum_file(&body, checksum_type);
+        if checksum.is_none() {
+            warn!("Ignoring malformed checksum file {url}");
+        }
+        checksum
+    }
+
+    /// Download a metadata file body as text
+    fn download_metadata_file(&self, path: &str) -> Result<String> {
         let url = format!("{}/{}", self.base_url, path);
         let response = self
             .client
@@ -118,11 +214,9 @@ impl HttpMetadataSource {
             )));
         }
 
-        let metadata: Vec<JdkMetadata> = response
-            .json()
-            .map_err(|e| KopiError::MetadataFetch(format!("Failed to parse {path}: {e}")))?;
-
-        Ok(metadata)
+        response
+            .text()
+            .map_err(|e| KopiError::MetadataFetch(format!("Failed to read {path}: {e}")))
     }
 }
 
@@ -172,7 +266,7 @@ impl MetadataSource for HttpMetadataSource {
             child.update(idx as u64, Some(platform_files.len() as u64));
             child.set_message(format!("Fetching {}: {}", idx + 1, entry.path));
 
-            match self.fetch_metadata_file(&entry.path) {
+            match self.fetch_metadata_entry(entry) {
                 Ok(metadata) => {
                     // HTTP source provides full metadata with download_url and checksums
                     all_metadata.extend(metadata);
@@ -226,7 +320,7 @@ impl MetadataSource for HttpMetadataSource {
             child.update(idx as u64, Some(filtered_files.len() as u64));
             child.set_message(format!("Fetching {}: {}", idx + 1, entry.path));
 
-            match self.fetch_metadata_file(&entry.path) {
+            match self.fetch_metadata_entry(entry) {
                 Ok(pkg_metadata) => {
                     // HTTP source provides full metadata with download_url and checksums
                     metadata.extend(pkg_metadata);
@@ -268,6 +362,51 @@ impl MetadataSource for HttpMetadataSource {
             .ok();
         Ok(updated)
     }
+
+    fn fetch_checksum(&self, metadata: &JdkMetadata) -> Option<(String, ChecksumType)> {
+        let download_url = metadata.download_url.as_deref()?;
+        // Only archives mirrored alongside this source's metadata are expected to have sidecars
+        if !self.checksum_sidecars || url_origin(download_url) != url_origin(&self.base_url) {
+            return None;
+        }
+
+        CHECKSUM_SIDECARS
+            .iter()
+            .find_map(|(extension, checksum_type)| {
+                let url = format!("{download_url}.{extension}");
+                self.fetch_sidecar(&url, *checksum_type)
+                    .map(|checksum| (checksum, *checksum_type))
+            })
+    }
+}
+
+/// Scheme and host of a URL, e.g. `https://mirror.example.com`
+fn url_origin(url: &str) -> Option<&str> {
+    let scheme_end = url.find("://")? + 3;
+    let host_end = url[scheme_end..]
+        .find('/')
+        .map_or(url.len(), |index| scheme_end + index);
+    Some(&url[..host_end])
+}
+
+/// Read the hex digest from a `sha256sum`-style file (`<hex>  <file name>` or just `<hex>`)
+fn parse_checksum_file(content: &str, checksum_type: ChecksumType) -> Option<String> {
+    let expected_len = match checksum_type {
+        ChecksumType::Sha256 => 64,
+        ChecksumType::Sha512 => 128,
+        ChecksumType::Sha1 => 40,
+        ChecksumType::Md5 => 32,
+    };
+    let digest = content.split_whitespace().next()?;
+    (digest.len() == expected_len && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| digest.to_ascii_lowercase())
+}
+
+/// Compare content against an index checksum (`<hex>` or `sha256:<hex>`)
+fn checksum_matches(content: &[u8], expected: &str) -> bool {
+    let expected = expected.strip_prefix("sha256:").unwrap_or(expected);
+    let actual = format!("{:x}", Sha256::digest(content));
+    actual.eq_ignore_ascii_case(expected)
 }
 
 #[cfg(test)]
@@ -38,9 +38,11 @@ mod http_tests {
             size: 100000,
             lib_c_type: Some("glibc".to_string()),
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         }
     }
 
@@ -83,6 +85,7 @@ mod http_tests {
                 },
             ],
             generator_config: None,
+            previous_updated: None,
         }
     }
 
@@ -321,6 +324,153 @@ mod http_tests {
         assert_eq!(all_metadata.len(), 0); // No files for current platform
     }
 
+    #[test]
+    fn test_chunk_cache_skips_unchanged_files() {
+        let mut server = Server::new();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let body = serde_json::to_string(&vec![create_test_metadata()]).unwrap();
+
+        let mut index = create_test_index();
+        index.files.truncate(1);
+        index.files[0].checksum = Some(format!("{:x}", Sha256::digest(body.as_bytes())));
+
+        let _index_mock = server
+            .mock("GET", "/index.json")
+            .with_status(200)
+            .with_body(serde_json::to_string(&index).unwrap())
+            .expect(2)
+            .create();
+        let file_mock = server
+            .mock("GET", "/jdks/temurin-test.json")
+            .with_status(200)
+            .with_body(&body)
+            .expect(1)
+            .create();
+
+        let source =
+            HttpMetadataSource::new(server.url()).with_chunk_cache(temp_dir.path().to_path_buf());
+        let mut progress = SilentProgress;
+        assert_eq!(source.fetch_all(&mut progress).unwrap().len(), 1);
+        assert_eq!(source.fetch_all(&mut progress).unwrap().len(), 1);
+
+        file_mock.assert();
+        assert!(temp_dir.path().join("jdks/temurin-test.json").exists());
+    }
+
+    #[test]
+    fn test_chunk_cache_refetches_changed_files() {
+        let mut server = Server::new();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let body = serde_json::to_string(&vec![create_test_metadata()]).unwrap();
+
+        // Stale local copy whose checksum does not match the index
+        let stale_file = temp_dir.path().join("jdks/temurin-test.json");
+        fs::create_dir_all(stale_file.parent().unwrap()).unwrap();
+        fs::write(&stale_file, "[]").unwrap();
+
+        let mut index = create_test_index();
+        index.files.truncate(1);
+
+        let _index_mock = server
+            .mock("GET", "/index.json")
+            .with_status(200)
+            .with_body(serde_json::to_string(&index).unwrap())
+            .create();
+        let file_mock = server
+            .mock("GET", "/jdks/temurin-test.json")
+            .with_status(200)
+            .with_body(&body)
+            .expect(1)
+            .create();
+
+        let source =
+            HttpMetadataSource::new(server.url()).with_chunk_cache(temp_dir.path().to_path_buf());
+        let mut progress = SilentProgress;
+        assert_eq!(source.fetch_all(&mut progress).unwrap().len(), 1);
+
+        file_mock.assert();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("jdks/temurin-test.json")).unwrap(),
+            body
+        );
+    }
+
+    #[test]
+    fn test_cached_chunk_path_rejects_escaping_paths() {
+        let source = HttpMetadataSource::new("https://example.com".to_string())
+            .with_chunk_cache(PathBuf::from("/tmp/chunks"));
+
+        assert!(source.cached_chunk_path("linux-x64/temurin.json").is_some());
+        assert!(source.cached_chunk_path("../temurin.json").is_none());
+        assert!(source.cached_chunk_path("/etc/passwd").is_none());
+    }
+
+    #[test]
+    fn test_fetch_checksum_falls_back_to_sha512_sidecar() {
+        let mut server = Server::new();
+        let digest = "ab".repeat(64);
+        let sha256_mock = server
+            .mock("GET", "/archives/jdk.tar.gz.sha256")
+            .with_status(404)
+            .create();
+        let sha512_mock = server
+            .mock("GET", "/archives/jdk.tar.gz.sha512")
+            .with_status(200)
+            .with_body(format!("{digest}  jdk.tar.gz\n"))
+            .create();
+
+        let mut metadata = create_test_metadata();
+        metadata.download_url = Some(format!("{}/archives/jdk.tar.gz", server.url()));
+        metadata.checksum = None;
+
+        let source = HttpMetadataSource::new(format!("{}/metadata", server.url()))
+            .with_checksum_sidecars(true);
+        assert_eq!(
+            source.fetch_checksum(&metadata),
+            Some((digest, ChecksumType::Sha512))
+        );
+        sha256_mock.assert();
+        sha512_mock.assert();
+    }
+
+    #[test]
+    fn test_fetch_checksum_requires_opt_in_and_same_host() {
+        let mut server = Server::new();
+        let sidecar_mock = server
+            .mock("GET", "/jdk.tar.gz.sha256")
+            .with_status(200)
+            .with_body("cd".repeat(32))
+            .expect(0)
+            .create();
+
+        let mut metadata = create_test_metadata();
+        metadata.download_url = Some(format!("{}/jdk.tar.gz", server.url()));
+
+        let disabled = HttpMetadataSource::new(server.url());
+        assert_eq!(disabled.fetch_checksum(&metadata), None);
+
+        let other_host = HttpMetadataSource::new("https://mirror.example.com".to_string())
+            .with_checksum_sidecars(true);
+        assert_eq!(other_host.fetch_checksum(&metadata), None);
+
+        sidecar_mock.assert();
+    }
+
+    #[test]
+    fn test_parse_checksum_file() {
+        let digest = "AB".repeat(32);
+        assert_eq!(
+            parse_checksum_file(&format!("{digest} *jdk.zip"), ChecksumType::Sha256),
+            Some("ab".repeat(32))
+        );
+        assert_eq!(parse_checksum_file(&digest, ChecksumType::Sha512), None);
+        assert_eq!(
+            parse_checksum_file("not a digest", ChecksumType::Sha256),
+            None
+        );
+        assert_eq!(parse_checksum_file("", ChecksumType::Sha256), None);
+    }
+
     // Test JSON serialization/deserialization format
     #[test]
     fn test_metadata_json_serialization() {
@@ -14,6 +14,10 @@
 
 use crate::metadata::GeneratorConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Directory (relative to the metadata root) holding per-distribution delta manifests
+pub const DELTA_DIR: &str = "deltas";
 
 /// Index file structure for metadata repository
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,10 +28,89 @@ pub struct IndexFile {
     /// Generator configuration used to create this metadata (added in version 2)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub generator_config: Option<GeneratorConfig>,
+    /// `updated` timestamp of the index the delta manifests were computed against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_updated: Option<String>,
+}
+
+impl IndexFile {
+    /// Compute per-distribution changes between `previous` and this index.
+    ///
+    /// Files are compared by path and checksum; distributions without any
+    /// change are omitted from the result.
+    pub fn delta_from(&self, previous: &IndexFile) -> Vec<DeltaManifest> {
+        let old_entries: HashMap<&str, &IndexFileEntry> = previous
+            .files
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry))
+            .collect();
+        let new_paths: HashSet<&str> = self.files.iter().map(|entry| entry.path.as_str()).collect();
+
+        let mut changed: BTreeMap<String, Vec<IndexFileEntry>> = BTreeMap::new();
+        let mut removed: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for entry in &self.files {
+            let unchanged = old_entries.get(entry.path.as_str()).is_some_and(|old| {
+                old.checksum.is_some() && old.checksum == entry.checksum && old.size == entry.size
+            });
+            if !unchanged {
+                changed
+                    .entry(entry.distribution.clone())
+                    .or_default()
+                    .push(entry.clone());
+            }
+        }
+
+        for entry in &previous.files {
+            if !new_paths.contains(entry.path.as_str()) {
+                removed
+                    .entry(entry.distribution.clone())
+                    .or_default()
+                    .push(entry.path.clone());
+            }
+        }
+
+        let mut distributions: Vec<String> =
+            changed.keys().chain(removed.keys()).cloned().collect();
+        distributions.sort();
+        distributions.dedup();
+
+        distributions
+            .into_iter()
+            .map(|distribution| DeltaManifest {
+                changed: changed.remove(&distribution).unwrap_or_default(),
+                removed: removed.remove(&distribution).unwrap_or_default(),
+                from: previous.updated.clone(),
+                to: self.updated.clone(),
+                distribution,
+            })
+            .collect()
+    }
+}
+
+/// Files of one distribution that changed between two index revisions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeltaManifest {
+    pub distribution: String,
+    /// `updated` timestamp of the previous index
+    pub from: String,
+    /// `updated` timestamp of the index this manifest belongs to
+    pub to: String,
+    /// Entries that were added or whose content changed
+    pub changed: Vec<IndexFileEntry>,
+    /// Paths that no longer exist
+    pub removed: Vec<String>,
+}
+
+impl DeltaManifest {
+    /// Path of the manifest for a distribution, relative to the metadata root
+    pub fn path_for(distribution: &str) -> String {
+        format!("{DELTA_DIR}/{distribution}.json")
+    }
 }
 
 /// Entry in the index file describing a metadata file
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexFileEntry {
     pub path: String,
     pub distribution: String,
@@ -38,3 +121,72 @@ pub struct IndexFileEntry {
     pub checksum: Option<String>,
     pub last_modified: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, distribution: &str, checksum: &str) -> IndexFileEntry {
+        IndexFileEntry {
+            path: path.to_string(),
+            distribution: distribution.to_string(),
+            architectures: None,
+            operating_systems: None,
+            lib_c_types: None,
+            size: 10,
+            checksum: Some(checksum.to_string()),
+            last_modified: None,
+        }
+    }
+
+    fn index(updated: &str, files: Vec<IndexFileEntry>) -> IndexFile {
+        IndexFile {
+            version: 2,
+            updated: updated.to_string(),
+            files,
+            generator_config: None,
+            previous_updated: None,
+        }
+    }
+
+    #[test]
+    fn test_delta_from_groups_changes_by_distribution() {
+        let previous = index(
+            "2025-01-01T00:00:00Z",
+            vec![
+                entry("linux-x64/temurin.json", "temurin", "aaa"),
+                entry("linux-x64/zulu.json", "zulu", "bbb"),
+                entry("macos-x64/zulu.json", "zulu", "ccc"),
+            ],
+        );
+        let current = index(
+            "2025-01-02T00:00:00Z",
+            vec![
+                entry("linux-x64/temurin.json", "temurin", "aaa"),
+                entry("linux-x64/zulu.json", "zulu", "ddd"),
+                entry("linux-aarch64/corretto.json", "corretto", "eee"),
+            ],
+        );
+
+        let deltas = current.delta_from(&previous);
+        let distributions: Vec<&str> = deltas.iter().map(|d| d.distribution.as_str()).collect();
+        assert_eq!(distributions, vec!["corretto", "zulu"]);
+
+        let zulu = &deltas[1];
+        assert_eq!(zulu.from, "2025-01-01T00:00:00Z");
+        assert_eq!(zulu.to, "2025-01-02T00:00:00Z");
+        assert_eq!(zulu.changed.len(), 1);
+        assert_eq!(zulu.changed[0].path, "linux-x64/zulu.json");
+        assert_eq!(zulu.removed, vec!["macos-x64/zulu.json".to_string()]);
+        assert_eq!(DeltaManifest::path_for("zulu"), "deltas/zulu.json");
+    }
+
+    #[test]
+    fn test_delta_from_identical_index_is_empty() {
+        let files = vec![entry("linux-x64/temurin.json", "temurin", "aaa")];
+        let previous = index("2025-01-01T00:00:00Z", files.clone());
+        let current = index("2025-01-02T00:00:00Z", files);
+
+        assert!(current.delta_from(&previous).is_empty());
+    }
+}
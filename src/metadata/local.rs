@@ -255,9 +255,11 @@ mod tests {
                 package_type: PackageType::Jdk,
                 archive_type: ArchiveType::TarGz,
                 javafx_bundled: false,
+                features: Vec::new(),
                 term_of_support: None,
                 release_status: None,
                 latest_build_available: None,
+                release_date: None,
             },
             JdkMetadata {
                 distribution: "corretto".to_string(),
@@ -274,9 +276,11 @@ mod tests {
                 package_type: PackageType::Jdk,
                 archive_type: ArchiveType::TarGz,
                 javafx_bundled: false,
+                features: Vec::new(),
                 term_of_support: None,
                 release_status: None,
                 latest_build_available: None,
+                release_date: None,
             },
         ]
     }
@@ -365,6 +369,7 @@ mod tests {
                 },
             ],
             generator_config: None,
+            previous_updated: None,
         };
 
         let index_path = metadata_dir.join("index.json");
@@ -816,6 +821,7 @@ mod tests {
                 },
             ],
             generator_config: None,
+            previous_updated: None,
         };
 
         fs::write(
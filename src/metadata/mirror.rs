@@ -0,0 +1,429 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Serve the local metadata cache as an HTTP metadata source.
+//!
+//! The files are laid out by the metadata generator, so other machines can
+//! use the mirror as a `type = "http"` source. Archives from the download
+//! cache can be served as well, under `downloads/`.
+
+use crate::cache::MetadataCache;
+use crate::download::DownloadCache;
+use crate::error::Result;
+use crate::metadata::generator::{GeneratorConfig, MetadataGenerator};
+use log::{debug, warn};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Prefix of the URLs cached archives are served under
+const DOWNLOADS_PREFIX: &str = "downloads/";
+
+/// How long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Connections served at the same time
+const WORKERS: usize = 8;
+
+/// Accepted connections waiting for a worker; further ones get a 503
+const QUEUE_LENGTH: usize = 32;
+
+/// Longest request or header line accepted, in bytes
+const MAX_LINE_LENGTH: u64 = 8 * 1024;
+
+/// Most header lines accepted in one request
+const MAX_HEADERS: usize = 100;
+
+/// Metadata files held in memory, plus the download cache to serve archives from
+pub struct MetadataMirror {
+    files: HashMap<String, Vec<u8>>,
+    downloads: Option<PathBuf>,
+    /// Packages listed in the served metadata
+    pub packages: usize,
+    /// Packages left out because they have no download URL
+    pub incomplete: usize,
+    /// Packages whose download URL points at an archive served by the mirror
+    pub archives: usize,
+}
+
+/// What a request path resolves to
+enum Resource<'a> {
+    Memory(&'a [u8]),
+    File(PathBuf),
+}
+
+impl MetadataMirror {
+    /// Lay out the packages of `cache` that have a download URL. With
+    /// `downloads`, packages whose archive is cached are pointed at
+    /// `<base_url>/downloads/` instead of their original download URL.
+    pub fn new(cache: &MetadataCache, downloads: Option<(&DownloadCache, &str)>) -> Result<Self> {
+        let mut packages = Vec::new();
+        let mut incomplete = 0;
+        let mut archives = 0;
+        for package in cache
+            .distributions
+            .values()
+            .flat_map(|distribution| &distribution.packages)
+        {
+            if !package.is_complete() {
+                incomplete += 1;
+                continue;
+            }
+            let mut package = package.clone();
+            if let Some((download_cache, base_url)) = downloads
+                && let Some(archive) = download_cache.cached_archive(&package)
+            {
+                package.download_url = Some(format!(
+                    "{}/{DOWNLOADS_PREFIX}{}",
+                    base_url.trim_end_matches('/'),
+                    url_path(&archive)
+                ));
+                archives += 1;
+            }
+            packages.push(package);
+        }
+
+        let generator = MetadataGenerator::new(GeneratorConfig {
+            distributions: None,
+            platforms: None,
+            javafx_bundled: false,
+            parallel_requests: 1,
+            dry_run: false,
+            minify_json: true,
+            force: false,
+        });
+        let count = packages.len();
+        let (index, metadata_files) = generator.build_files(packages)?;
+
+        let mut files: HashMap<String, Vec<u8>> = metadata_files
+            .into_iter()
+            .map(|(path, file)| (path, file.content.into_bytes()))
+            .collect();
+        files.insert("index.json".to_string(), serde_json::to_vec_pretty(&index)?);
+
+        Ok(Self {
+            files,
+            downloads: downloads.map(|(download_cache, _)| download_cache.root().to_path_buf()),
+            packages: count,
+            incomplete,
+            archives,
+        })
+    }
+
+    /// Serve requests from `listener` until the process is stopped, with a
+    /// fixed pool of [`WORKERS`] threads. Connections beyond what the pool
+    /// and its queue can hold are answered with `503` straight away.
+    pub fn run(self, listener: TcpListener) -> Result<()> {
+        let mirror = Arc::new(self);
+        let (sender, receiver) = mpsc::sync_channel::<TcpStream>(QUEUE_LENGTH);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..WORKERS {
+            let mirror = mirror.clone();
+            let receiver = receiver.clone();
+            std::thread::spawn(move || mirror.work(&receiver));
+        }
+
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Failed to accept mirror connection: {e}");
+                    continue;
+                }
+            };
+            if let Err(TrySendError::Full(mut stream) | TrySendError::Disconnected(mut stream)) =
+                sender.try_send(stream)
+            {
+                debug!("Mirror busy; turning a connection away");
+                let _ = stream.set_write_timeout(Some(READ_TIMEOUT));
+                let _ = respond(&mut stream, "503 Service Unavailable", b"Busy\n");
+            }
+        }
+        Ok(())
+    }
+
+    /// Serve queued connections until the accept loop goes away
+    fn work(&self, receiver: &Mutex<Receiver<TcpStream>>) {
+        loop {
+            let Ok(stream) = receiver.lock().unwrap().recv() else {
+                return;
+            };
+            if let Err(e) = self.handle(stream) {
+                debug!("Mirror connection failed: {e}");
+            }
+        }
+    }
+
+    /// Answer one `GET` or `HEAD` request and close the connection
+    fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        stream.set_write_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let Some(request_line) = read_request_head(&mut reader)? else {
+            return respond(
+                &mut stream,
+                "431 Request Header Fields Too Large",
+                b"Request header too large\n",
+            );
+        };
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let target = parts.next().unwrap_or_default();
+        let path = target.split('?').next().unwrap_or_default();
+        let path = path.trim_start_matches('/');
+        debug!("Mirror request: {method} /{path}");
+
+        let head = method == "HEAD";
+        if method != "GET" && !head {
+            return respond(
+                &mut stream,
+                "405 Method Not Allowed",
+                b"Method not allowed\n",
+            );
+        }
+
+        match self.lookup(path) {
+            Some(Resource::Memory(body)) => {
+                write_headers(&mut stream, "200 OK", content_type(path), body.len() as u64)?;
+                if !head {
+                    stream.write_all(body)?;
+                }
+            }
+            Some(Resource::File(file_path)) => {
+                let mut file = File::open(&file_path)?;
+                let length = file.metadata()?.len();
+                write_headers(&mut stream, "200 OK", content_type(path), length)?;
+                if !head {
+                    std::io::copy(&mut file, &mut stream)?;
+                }
+            }
+            None => respond(&mut stream, "404 Not Found", b"Not found\n")?,
+        }
+        stream.flush()
+    }
+
+    /// The metadata file or cached archive at `path`, relative to the mirror root
+    fn lookup(&self, path: &str) -> Option<Resource<'_>> {
+        if let Some(body) = self.files.get(path) {
+            return Some(Resource::Memory(body));
+        }
+
+        let root = self.downloads.as_ref()?;
+        let relative = Path::new(path.strip_prefix(DOWNLOADS_PREFIX)?);
+        // Archives live exactly one level down: `<algorithm>-<checksum>/<file name>`
+        let components: Vec<Component> = relative.components().collect();
+        if components.len() != 2
+            || !components
+                .iter()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return None;
+        }
+        let file_path = root.join(relative);
+        file_path.is_file().then_some(Resource::File(file_path))
+    }
+}
+
+/// The request line, after reading and discarding the headers, which carry
+/// nothing the mirror needs. `None` when a line is longer than
+/// [`MAX_LINE_LENGTH`] or there are more than [`MAX_HEADERS`] headers.
+fn read_request_head<R: BufRead>(reader: &mut R) -> std::io::Result<Option<String>> {
+    let Some(request_line) = read_limited_line(reader)? else {
+        return Ok(None);
+    };
+    for _ in 0..=MAX_HEADERS {
+        match read_limited_line(reader)? {
+            Some(header) if header.trim().is_empty() => return Ok(Some(request_line)),
+            Some(_) => {}
+            None => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+/// One line of at most [`MAX_LINE_LENGTH`] bytes; `None` when it is longer
+fn read_limited_line<R: BufRead>(reader: &mut R) -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    reader
+        .by_ref()
+        .take(MAX_LINE_LENGTH + 1)
+        .read_line(&mut line)?;
+    if line.len() as u64 > MAX_LINE_LENGTH {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+/// `a/b` form of a relative path on every platform
+fn url_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn content_type(path: &str) -> &'static str {
+    if path.ends_with(".json") {
+        "application/json"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+fn write_headers(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    length: u64,
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {length}\r\n\
+         Connection: close\r\n\r\n"
+    )
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &[u8]) -> std::io::Result<()> {
+    write_headers(stream, status, "text/plain", body.len() as u64)?;
+    stream.write_all(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::DistributionCache;
+    use crate::indicator::SilentProgress;
+    use crate::metadata::{HttpMetadataSource, MetadataSource};
+    use crate::models::distribution::Distribution;
+    use crate::models::metadata::JdkMetadata;
+    use crate::models::package::{ArchiveType, ChecksumType, PackageType};
+    use crate::models::platform::{Architecture, OperatingSystem};
+    use crate::platform::{get_current_architecture, get_current_os};
+    use crate::version::Version;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn package(version: &str, download_url: Option<&str>) -> JdkMetadata {
+        JdkMetadata {
+            id: format!("temurin-{version}"),
+            distribution: "temurin".to_string(),
+            version: Version::from_str(version).unwrap(),
+            distribution_version: Version::from_str(version).unwrap(),
+            architecture: Architecture::from_str(&get_current_architecture()).unwrap(),
+            operating_system: OperatingSystem::from_str(&get_current_os()).unwrap(),
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            download_url: download_url.map(str::to_string),
+            checksum: Some(format!("{:0>64}", version.replace('.', ""))),
+            checksum_type: Some(ChecksumType::Sha256),
+            size: 4,
+            lib_c_type: None,
+            javafx_bundled: false,
+            features: Vec::new(),
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }
+    }
+
+    fn cache(packages: Vec<JdkMetadata>) -> MetadataCache {
+        let mut cache = MetadataCache::new();
+        cache.distributions.insert(
+            "temurin".to_string(),
+            DistributionCache {
+                distribution: Distribution::Temurin,
+                display_name: "Eclipse Temurin".to_string(),
+                packages,
+            },
+        );
+        cache
+    }
+
+    #[test]
+    fn test_read_request_head_limits_headers() {
+        let head = |text: String| read_request_head(&mut text.as_bytes()).unwrap();
+        assert_eq!(
+            head("GET /index.json HTTP/1.1\r\nHost: a\r\n\r\n".to_string()).as_deref(),
+            Some("GET /index.json HTTP/1.1\r\n")
+        );
+
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(9000));
+        assert_eq!(head(long_line), None);
+        let long_header = format!("GET / HTTP/1.1\r\nX: {}\r\n\r\n", "a".repeat(9000));
+        assert_eq!(head(long_header), None);
+
+        let headers = |count: usize| format!("GET / HTTP/1.1\r\n{}\r\n", "X: a\r\n".repeat(count));
+        assert!(head(headers(MAX_HEADERS)).is_some());
+        assert_eq!(head(headers(MAX_HEADERS + 1)), None);
+    }
+
+    #[test]
+    fn test_mirror_serves_http_metadata_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let download_cache = DownloadCache::new(temp_dir.path().to_path_buf(), None);
+        let archive = temp_dir.path().join("jdk.tar.gz");
+        std::fs::write(&archive, b"data").unwrap();
+        let cached = package("21.0.5", Some("https://example.com/jdk-21.tar.gz"));
+        download_cache.store(&cached, &archive).unwrap();
+
+        let mirror = MetadataMirror::new(
+            &cache(vec![
+                cached,
+                package("17.0.13", Some("https://example.com/jdk-17.tar.gz")),
+                package("11.0.25", None),
+            ]),
+            Some((&download_cache, "https://mirror.lan/")),
+        )
+        .unwrap();
+        assert_eq!(
+            (mirror.packages, mirror.incomplete, mirror.archives),
+            (2, 1, 1)
+        );
+        assert!(mirror.lookup("../index.json").is_none());
+        assert!(mirror.lookup("downloads/../jdk.tar.gz").is_none());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || mirror.run(listener));
+
+        let source = HttpMetadataSource::new(format!("http://{address}"));
+        let mut packages = source.fetch_all(&mut SilentProgress).unwrap();
+        packages.sort_by(|a, b| a.version.cmp(&b.version));
+        assert_eq!(packages.len(), 2);
+        assert_eq!(
+            packages[0].download_url.as_deref(),
+            Some("https://example.com/jdk-17.tar.gz")
+        );
+        let mirrored = packages[1].download_url.as_deref().unwrap();
+        let path = mirrored.strip_prefix("https://mirror.lan/").unwrap();
+        assert!(path.starts_with("downloads/sha256-"), "{mirrored}");
+
+        let response = attohttpc::get(format!("http://{address}/{path}"))
+            .send()
+            .unwrap();
+        assert!(response.is_success());
+        assert_eq!(response.bytes().unwrap(), b"data");
+        let missing = attohttpc::get(format!("http://{address}/nothing.json"))
+            .send()
+            .unwrap();
+        assert_eq!(missing.status().as_u16(), 404);
+    }
+}
@@ -12,20 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod command;
+pub mod custom;
 pub mod foojay;
 pub mod generator;
 pub mod generator_config;
 pub mod http;
 pub mod index;
 pub mod local;
+pub mod mirror;
 pub mod provider;
 pub mod source;
 
+pub use command::CommandMetadataSource;
+pub use custom::CustomDistributionSource;
 pub use foojay::FoojayMetadataSource;
 pub use generator::{GeneratorConfig, MetadataGenerator, Platform};
 pub use generator_config::MetadataGenConfigFile;
 pub use http::HttpMetadataSource;
 pub use index::{IndexFile, IndexFileEntry};
 pub use local::LocalDirectorySource;
+pub use mirror::MetadataMirror;
 pub use provider::{MetadataProvider, SourceHealth};
-pub use source::{MetadataSource, PackageDetails};
+pub use source::{MetadataSource, PackageDetails, PartialMetadata};
@@ -15,9 +15,14 @@
 use crate::config::{KopiConfig, MetadataConfig, SourceConfig};
 use crate::error::{KopiError, Result};
 use crate::indicator::ProgressIndicator;
-use crate::metadata::source::MetadataSource;
-use crate::metadata::{FoojayMetadataSource, HttpMetadataSource, LocalDirectorySource};
+use crate::metadata::source::{MetadataSource, PartialMetadata};
+use crate::metadata::{
+    CommandMetadataSource, CustomDistributionSource, FoojayMetadataSource, HttpMetadataSource,
+    LocalDirectorySource,
+};
 use crate::models::metadata::JdkMetadata;
+use crate::models::package::ChecksumType;
+use crate::paths::cache::http_source_cache_directory;
 use log::{debug, warn};
 use std::collections::HashMap;
 
@@ -25,6 +30,8 @@ use std::collections::HashMap;
 pub struct MetadataProvider {
     /// Ordered list of source names and their implementations
     sources: Vec<(String, Box<dyn MetadataSource>)>,
+    /// Custom distributions keyed by distribution name, consulted alongside `sources`
+    custom_sources: Vec<(String, Box<dyn MetadataSource>)>,
 }
 
 impl MetadataProvider {
@@ -33,12 +40,29 @@ impl MetadataProvider {
         let source_id = source.id().to_string();
         Self {
             sources: vec![(source_id, source)],
+            custom_sources: Vec::new(),
         }
     }
 
     /// Create a provider from configuration
     pub fn from_config(config: &KopiConfig) -> Result<Self> {
-        Self::from_metadata_config(&config.metadata, config.kopi_home())
+        let mut provider = Self::from_metadata_config(&config.metadata, config.kopi_home())?;
+        for custom in &config.custom_distributions {
+            debug!(
+                "Initializing custom distribution '{}' at {}",
+                custom.name, custom.metadata_url
+            );
+            provider.add_custom_source(
+                custom.name.clone(),
+                Box::new(CustomDistributionSource::new(custom)?),
+            );
+        }
+        Ok(provider)
+    }
+
+    /// Register a source that serves exactly one distribution
+    pub fn add_custom_source(&mut self, distribution: String, source: Box<dyn MetadataSource>) {
+        self.custom_sources.push((distribution, source));
     }
 
     /// Create a provider from metadata configuration
@@ -55,10 +79,13 @@ impl MetadataProvider {
                     name,
                     enabled,
                     base_url,
+                    checksum_sidecars,
                     ..
                 } if *enabled => {
                     debug!("Initializing HTTP metadata source '{name}' at {base_url}");
-                    let source = HttpMetadataSource::new(base_url.clone());
+                    let source = HttpMetadataSource::new(base_url.clone())
+                        .with_chunk_cache(http_source_cache_directory(kopi_home, name))
+                        .with_checksum_sidecars(*checksum_sidecars);
                     sources.push((name.clone(), Box::new(source)));
                 }
                 SourceConfig::Local {
@@ -88,6 +115,23 @@ impl MetadataProvider {
                     let source = FoojayMetadataSource::new();
                     sources.push((name.clone(), Box::new(source)));
                 }
+                SourceConfig::Command {
+                    name,
+                    enabled,
+                    command,
+                    args,
+                    timeout_secs,
+                } if *enabled => {
+                    debug!("Initializing command metadata source '{name}' running {command}");
+                    let program = command.replace("${KOPI_HOME}", &kopi_home.to_string_lossy());
+                    let source = CommandMetadataSource::new(
+                        name.clone(),
+                        std::path::PathBuf::from(program),
+                        args.clone(),
+                        std::time::Duration::from_secs(*timeout_secs),
+                    );
+                    sources.push((name.clone(), Box::new(source)));
+                }
                 _ => {
                     // Source is disabled
                 }
@@ -107,11 +151,24 @@ impl MetadataProvider {
             sources.iter().map(|(name, _)| name).collect::<Vec<_>>()
         );
 
-        Ok(Self { sources })
+        Ok(Self {
+            sources,
+            custom_sources: Vec::new(),
+        })
     }
 
     /// Get metadata from sources, trying each in order until one succeeds
     pub fn fetch_all(&self, progress: &mut dyn ProgressIndicator) -> Result<Vec<JdkMetadata>> {
+        self.fetch_all_partial(progress)
+            .map(|partial| partial.metadata)
+    }
+
+    /// [`fetch_all`](Self::fetch_all), also naming the distributions the
+    /// successful source had to skip
+    pub fn fetch_all_partial(
+        &self,
+        progress: &mut dyn ProgressIndicator,
+    ) -> Result<PartialMetadata> {
         let mut errors: Vec<(String, String)> = Vec::new();
 
         for (source_name, source) in &self.sources {
@@ -121,8 +178,8 @@ impl MetadataProvider {
             match source.is_available() {
                 Ok(true) => {
                     // Source is available, try to fetch
-                    match source.fetch_all(progress) {
-                        Ok(metadata) => {
+                    match source.fetch_all_partial(progress) {
+                        Ok(mut partial) => {
                             if errors.is_empty() {
                                 debug!("Successfully fetched metadata from source: {source_name}");
                             } else {
@@ -132,7 +189,10 @@ impl MetadataProvider {
                                     errors.len()
                                 );
                             }
-                            return Ok(metadata);
+                            partial
+                                .metadata
+                                .extend(self.fetch_custom_distributions(progress));
+                            return Ok(partial);
                         }
                         Err(e) => {
                             warn!("Failed to fetch from source '{source_name}': {e}");
@@ -168,12 +228,34 @@ impl MetadataProvider {
         )))
     }
 
+    /// Packages from every custom distribution; failures are logged and skipped
+    /// so one unreachable in-house server does not block a full refresh
+    fn fetch_custom_distributions(&self, progress: &mut dyn ProgressIndicator) -> Vec<JdkMetadata> {
+        let mut metadata = Vec::new();
+        for (distribution, source) in &self.custom_sources {
+            match source.fetch_all(progress) {
+                Ok(packages) => metadata.extend(packages),
+                Err(e) => warn!("Failed to fetch custom distribution '{distribution}': {e}"),
+            }
+        }
+        metadata
+    }
+
     /// Fetch metadata for a specific distribution, trying each source in order
     pub fn fetch_distribution(
         &self,
         distribution: &str,
         progress: &mut dyn ProgressIndicator,
     ) -> Result<Vec<JdkMetadata>> {
+        if let Some((_, source)) = self
+            .custom_sources
+            .iter()
+            .find(|(name, _)| name == distribution)
+        {
+            debug!("Fetching custom distribution '{distribution}'");
+            return source.fetch_distribution(distribution, progress);
+        }
+
         let mut errors: Vec<(String, String)> = Vec::new();
 
         for (source_name, source) in &self.sources {
@@ -259,7 +341,7 @@ impl MetadataProvider {
     ) -> Result<crate::metadata::source::PackageDetails> {
         let mut errors: Vec<(String, String)> = Vec::new();
 
-        for (source_name, source) in &self.sources {
+        for (source_name, source) in self.sources.iter().chain(&self.custom_sources) {
             debug!(
                 "Attempting to fetch package details for '{package_id}' from source: {source_name}"
             );
@@ -335,6 +417,23 @@ impl MetadataProvider {
         Ok(())
     }
 
+    /// Ask each source for a checksum the package metadata lacks
+    pub fn fetch_checksum(&self, metadata: &JdkMetadata) -> Option<(String, ChecksumType)> {
+        self.sources
+            .iter()
+            .chain(&self.custom_sources)
+            .find_map(|(source_name, source)| {
+                let found = source.fetch_checksum(metadata);
+                if found.is_some() {
+                    debug!(
+                        "Found checksum for '{}' via source: {source_name}",
+                        metadata.id
+                    );
+                }
+                found
+            })
+    }
+
     /// Check health of all configured sources
     pub fn check_sources_health(&self) -> HashMap<String, SourceHealth> {
         let mut health_status = HashMap::new();
@@ -421,9 +520,11 @@ mod tests {
             size: 100_000_000,
             lib_c_type: None,
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         };
 
         // ensure_complete should not make any changes
@@ -145,9 +145,11 @@ mod tests {
             size: 100_000_000,
             lib_c_type: None,
             javafx_bundled: false,
+            features: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         }
     }
 
@@ -170,6 +172,7 @@ mod tests {
                 ("primary".to_string(), Box::new(primary.clone())),
                 ("fallback".to_string(), Box::new(fallback.clone())),
             ],
+            custom_sources: Vec::new(),
         };
 
         // Fetch should use fallback
@@ -195,6 +198,7 @@ mod tests {
                 ("primary".to_string(), Box::new(primary.clone())),
                 ("fallback".to_string(), Box::new(fallback.clone())),
             ],
+            custom_sources: Vec::new(),
         };
 
         // Fetch should fail
@@ -214,6 +218,7 @@ mod tests {
         // Create provider without fallback
         let provider = MetadataProvider {
             sources: vec![("primary".to_string(), Box::new(primary.clone()))],
+            custom_sources: Vec::new(),
         };
 
         // Fetch should fail with primary error
@@ -243,6 +248,7 @@ mod tests {
                 ("primary".to_string(), Box::new(primary.clone())),
                 ("fallback".to_string(), Box::new(fallback.clone())),
             ],
+            custom_sources: Vec::new(),
         };
 
         // Fetch distribution should use fallback
@@ -279,6 +285,7 @@ mod tests {
                 ("primary".to_string(), Box::new(primary.clone())),
                 ("fallback".to_string(), Box::new(fallback.clone())),
             ],
+            custom_sources: Vec::new(),
         };
 
         // Ensure complete should use fallback for package details
@@ -308,6 +315,7 @@ mod tests {
                 ("primary".to_string(), Box::new(primary.clone())),
                 ("fallback".to_string(), Box::new(fallback.clone())),
             ],
+            custom_sources: Vec::new(),
         };
 
         // Check health
@@ -340,6 +348,7 @@ mod tests {
                 base_url: "https://example.com/metadata".to_string(),
                 cache_locally: true,
                 timeout_secs: 30,
+                checksum_sidecars: false,
             },
             SourceConfig::Local {
                 name: "local-backup".to_string(),
@@ -417,6 +426,7 @@ mod tests {
                 base_url: "https://cdn1.example.com/metadata".to_string(),
                 cache_locally: true,
                 timeout_secs: 30,
+                checksum_sidecars: false,
             },
             SourceConfig::Http {
                 name: "secondary-cdn".to_string(),
@@ -424,6 +434,7 @@ mod tests {
                 base_url: "https://cdn2.example.com/metadata".to_string(),
                 cache_locally: true,
                 timeout_secs: 30,
+                checksum_sidecars: false,
             },
             SourceConfig::Local {
                 name: "local-fallback".to_string(),
@@ -472,6 +483,7 @@ mod tests {
                 ("primary".to_string(), Box::new(primary.clone())),
                 ("fallback".to_string(), Box::new(fallback.clone())),
             ],
+            custom_sources: Vec::new(),
         };
 
         // Should fallback successfully
@@ -506,6 +518,7 @@ mod tests {
                 ("primary".to_string(), Box::new(primary.clone())),
                 ("fallback".to_string(), Box::new(fallback.clone())),
             ],
+            custom_sources: Vec::new(),
         };
 
         // Fetch initial data from primary
@@ -535,6 +548,7 @@ mod tests {
         // Create provider
         let provider = MetadataProvider {
             sources: vec![("primary".to_string(), Box::new(primary.clone()))],
+            custom_sources: Vec::new(),
         };
 
         // Should return empty vector, not error
@@ -556,6 +570,7 @@ mod tests {
         // Create provider
         let provider = MetadataProvider {
             sources: vec![("primary".to_string(), Box::new(primary.clone()))],
+            custom_sources: Vec::new(),
         };
 
         // Create batch of incomplete metadata
@@ -582,6 +597,7 @@ mod tests {
         // Create provider
         let provider = Arc::new(MetadataProvider {
             sources: vec![("primary".to_string(), Box::new(primary.clone()))],
+            custom_sources: Vec::new(),
         });
 
         // Spawn multiple threads accessing the provider
@@ -632,4 +648,62 @@ mod tests {
         let result = MetadataProvider::from_metadata_config(&metadata_config, kopi_home);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_custom_sources_merge_into_fetch_all() {
+        let primary = Arc::new(MockMetadataSource::new("primary", "Primary Source"));
+        primary.set_fetch_all_result(Ok(vec![create_test_metadata("temurin-1", true)]));
+
+        let acme = Arc::new(MockMetadataSource::new("acme", "acme"));
+        let mut acme_package = create_test_metadata("acme-1", true);
+        acme_package.distribution = "acme".to_string();
+        acme.set_fetch_all_result(Ok(vec![acme_package]));
+
+        let broken = Arc::new(MockMetadataSource::new("broken", "broken"));
+        broken.set_fetch_all_result(Err(KopiError::MetadataFetch("down".to_string())));
+
+        let mut provider = MetadataProvider::new_with_source(Box::new(primary.clone()));
+        provider.add_custom_source("acme".to_string(), Box::new(acme.clone()));
+        provider.add_custom_source("broken".to_string(), Box::new(broken.clone()));
+
+        let result = provider.fetch_all(&mut SilentProgress).unwrap();
+        let ids: Vec<_> = result.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["temurin-1", "acme-1"]);
+    }
+
+    #[test]
+    fn test_custom_distribution_routes_to_custom_source() {
+        let primary = Arc::new(MockMetadataSource::new("primary", "Primary Source"));
+        primary.set_fetch_distribution_result(Err(KopiError::MetadataFetch(
+            "Unknown distribution".to_string(),
+        )));
+
+        let acme = Arc::new(MockMetadataSource::new("acme", "acme"));
+        acme.set_fetch_distribution_result(Ok(vec![create_test_metadata("acme-1", true)]));
+        acme.set_fetch_package_details_result(Ok(PackageDetails {
+            download_url: "https://jdk.acme.example/acme-1.tar.gz".to_string(),
+            checksum: None,
+            checksum_type: None,
+        }));
+        primary.set_fetch_package_details_result(Err(KopiError::NotFound(
+            "Package not found".to_string(),
+        )));
+
+        let mut provider = MetadataProvider::new_with_source(Box::new(primary.clone()));
+        provider.add_custom_source("acme".to_string(), Box::new(acme.clone()));
+
+        let packages = provider
+            .fetch_distribution("acme", &mut SilentProgress)
+            .unwrap();
+        assert_eq!(packages[0].id, "acme-1");
+
+        let mut incomplete = create_test_metadata("acme-1", false);
+        provider
+            .ensure_complete(&mut incomplete, &mut SilentProgress)
+            .unwrap();
+        assert_eq!(
+            incomplete.download_url.as_deref(),
+            Some("https://jdk.acme.example/acme-1.tar.gz")
+        );
+    }
 }
@@ -33,6 +33,16 @@ pub trait MetadataSource: Send + Sync {
     /// For local/GitHub: returns metadata with is_complete=true
     fn fetch_all(&self, progress: &mut dyn ProgressIndicator) -> Result<Vec<JdkMetadata>>;
 
+    /// Like `fetch_all`, but a source that can fetch distributions one at a
+    /// time returns those it got and names the ones it had to skip instead of
+    /// failing the whole refresh
+    fn fetch_all_partial(&self, progress: &mut dyn ProgressIndicator) -> Result<PartialMetadata> {
+        Ok(PartialMetadata {
+            metadata: self.fetch_all(progress)?,
+            skipped: Vec::new(),
+        })
+    }
+
     /// Fetch metadata for a specific distribution
     fn fetch_distribution(
         &self,
@@ -50,6 +60,20 @@ pub trait MetadataSource: Send + Sync {
 
     /// Get the last update time of the source (if applicable)
     fn last_updated(&self) -> Result<Option<chrono::DateTime<chrono::Utc>>>;
+
+    /// Look up a checksum for a complete package whose metadata carries none
+    /// Sources without another place to find checksums return `None`
+    fn fetch_checksum(&self, _metadata: &JdkMetadata) -> Option<(String, ChecksumType)> {
+        None
+    }
+}
+
+/// Result of a refresh that may have skipped some distributions
+#[derive(Debug, Default)]
+pub struct PartialMetadata {
+    pub metadata: Vec<JdkMetadata>,
+    /// Distributions that could not be fetched, with the error
+    pub skipped: Vec<(String, String)>,
 }
 
 /// Details fetched for lazy-loaded fields
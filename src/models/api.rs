@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Package {
     pub id: String,
     pub archive_type: String,
@@ -36,15 +37,21 @@ pub struct Package {
     pub lib_c_type: Option<String>,
     pub package_type: String,
     pub javafx_bundled: bool,
+    /// Build features such as `crac` or `leyden`; empty for standard builds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feature: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub term_of_support: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub release_status: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub latest_build_available: Option<bool>,
+    /// Publication time as reported by foojay, either RFC 3339 or `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_date: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Links {
     pub pkg_download_redirect: String,
     pub pkg_info_uri: Option<String>,
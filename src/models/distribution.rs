@@ -13,10 +13,27 @@
 // limitations under the License.
 
 use crate::error::{KopiError, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Vendor and former project names users type for a distribution
+const DISTRIBUTION_ALIASES: &[(&str, &str)] = &[
+    ("adoptium", "temurin"),
+    ("adoptopenjdk", "temurin"),
+    ("eclipse", "temurin"),
+    ("amazon", "corretto"),
+    ("aws", "corretto"),
+    ("azul", "zulu"),
+    ("graal", "graalvm"),
+    ("alibaba", "dragonwell"),
+    ("sap", "sapmachine"),
+    ("bellsoft", "liberica"),
+    ("tencent", "kona"),
+    ("ibm", "semeru"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Distribution {
     Temurin,
@@ -71,6 +88,16 @@ impl Distribution {
         }
     }
 
+    /// The distribution a vendor or former project name refers to, e.g.
+    /// `adoptium` for Temurin or `amazon` for Corretto
+    pub fn from_alias(alias: &str) -> Option<Distribution> {
+        let alias = alias.to_lowercase();
+        DISTRIBUTION_ALIASES
+            .iter()
+            .find(|(name, _)| *name == alias)
+            .and_then(|(_, id)| Distribution::from_str(id).ok())
+    }
+
     /// Returns the default distribution API parameter.
     /// Eclipse Temurin is used as the default distribution.
     pub fn default_distribution() -> &'static str {
@@ -126,4 +153,17 @@ mod tests {
     fn test_default_distribution() {
         assert_eq!(Distribution::default_distribution(), "temurin");
     }
+
+    #[test]
+    fn test_from_alias() {
+        assert_eq!(
+            Distribution::from_alias("Adoptium"),
+            Some(Distribution::Temurin)
+        );
+        assert_eq!(
+            Distribution::from_alias("amazon"),
+            Some(Distribution::Corretto)
+        );
+        assert_eq!(Distribution::from_alias("temurin"), None);
+    }
 }
@@ -12,13 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::models::package::{ArchiveType, ChecksumType, PackageType};
 use crate::models::platform::{Architecture, OperatingSystem};
 use crate::version::Version;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct JdkMetadata {
     pub id: String,
     pub distribution: String,
@@ -37,9 +39,16 @@ pub struct JdkMetadata {
     pub size: i64,
     pub lib_c_type: Option<String>,
     pub javafx_bundled: bool,
+    /// Special-purpose build features such as `crac` or `leyden`, sorted;
+    /// empty for standard builds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
     pub term_of_support: Option<String>,
     pub release_status: Option<String>,
     pub latest_build_available: Option<bool>,
+    /// When the package was published, if the source reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_date: Option<DateTime<Utc>>,
 }
 
 impl JdkMetadata {
@@ -49,4 +58,22 @@ impl JdkMetadata {
         // Checksum is optional - if not present, download will proceed without verification
         self.download_url.is_some()
     }
+
+    /// Whether the package was built with exactly `features` (sorted), so a
+    /// plain request never picks a CRaC or Leyden build
+    pub fn has_features(&self, features: &[String]) -> bool {
+        self.features == features
+    }
+}
+
+/// Normalize the `feature` list reported by a metadata source
+pub fn normalize_features(features: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = features
+        .iter()
+        .map(|feature| feature.trim().to_ascii_lowercase())
+        .filter(|feature| !feature.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
 }
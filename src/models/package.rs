@@ -13,14 +13,37 @@
 // limitations under the License.
 
 use crate::error::{KopiError, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum PackageType {
     Jdk,
     Jre,
+    /// Source archive (`src.zip`) published alongside a JDK
+    Sources,
+    /// API documentation archive published alongside a JDK
+    Javadoc,
+}
+
+impl PackageType {
+    /// Companion archives are installed next to a JDK, never as one
+    pub fn is_companion(&self) -> bool {
+        matches!(self, PackageType::Sources | PackageType::Javadoc)
+    }
+
+    /// Whether a package of this type satisfies a requested type. Without a
+    /// request any JDK or JRE matches, but companion archives do not.
+    pub fn satisfies(&self, requested: Option<&PackageType>) -> bool {
+        match requested {
+            Some(requested) => self == requested,
+            None => !self.is_companion(),
+        }
+    }
 }
 
 impl FromStr for PackageType {
@@ -30,6 +53,8 @@ impl FromStr for PackageType {
         match s.to_lowercase().as_str() {
             "jdk" => Ok(PackageType::Jdk),
             "jre" => Ok(PackageType::Jre),
+            "sources" => Ok(PackageType::Sources),
+            "javadoc" => Ok(PackageType::Javadoc),
             _ => Err(KopiError::InvalidConfig(format!(
                 "Unknown package type: {s}"
             ))),
@@ -42,12 +67,14 @@ impl std::fmt::Display for PackageType {
         let pkg = match self {
             PackageType::Jdk => "jdk",
             PackageType::Jre => "jre",
+            PackageType::Sources => "sources",
+            PackageType::Javadoc => "javadoc",
         };
         write!(f, "{pkg}")
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ArchiveType {
     TarGz,
@@ -98,7 +125,7 @@ impl std::fmt::Display for ArchiveType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ChecksumType {
     Sha1,
@@ -119,6 +146,21 @@ mod tests {
         assert!(ArchiveType::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_companion_package_types() {
+        assert_eq!(
+            PackageType::from_str("sources").unwrap(),
+            PackageType::Sources
+        );
+        assert_eq!(PackageType::Javadoc.to_string(), "javadoc");
+
+        assert!(PackageType::Jdk.satisfies(None));
+        assert!(PackageType::Jre.satisfies(None));
+        assert!(!PackageType::Sources.satisfies(None));
+        assert!(PackageType::Sources.satisfies(Some(&PackageType::Sources)));
+        assert!(!PackageType::Javadoc.satisfies(Some(&PackageType::Jdk)));
+    }
+
     #[test]
     fn test_checksum_type_serialization() {
         // Test serialization of all checksum types
@@ -13,10 +13,11 @@
 // limitations under the License.
 
 use crate::error::{KopiError, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Architecture {
     X64,
@@ -27,6 +28,7 @@ pub enum Architecture {
     Ppc64le,
     S390x,
     Sparcv9,
+    Riscv64,
 }
 
 impl FromStr for Architecture {
@@ -42,6 +44,7 @@ impl FromStr for Architecture {
             "ppc64le" => Ok(Architecture::Ppc64le),
             "s390x" => Ok(Architecture::S390x),
             "sparcv9" => Ok(Architecture::Sparcv9),
+            "riscv64" | "riscv" => Ok(Architecture::Riscv64),
             _ => Err(KopiError::InvalidConfig(format!(
                 "Unknown architecture: {s}"
             ))),
@@ -60,12 +63,13 @@ impl std::fmt::Display for Architecture {
             Architecture::Ppc64le => "ppc64le",
             Architecture::S390x => "s390x",
             Architecture::Sparcv9 => "sparcv9",
+            Architecture::Riscv64 => "riscv64",
         };
         write!(f, "{arch}")
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum OperatingSystem {
     Linux,
@@ -125,6 +129,11 @@ mod tests {
             Architecture::from_str("arm64").unwrap(),
             Architecture::Aarch64
         );
+        assert_eq!(
+            Architecture::from_str("riscv64").unwrap(),
+            Architecture::Riscv64
+        );
+        assert_eq!(Architecture::Riscv64.to_string(), "riscv64");
         assert!(Architecture::from_str("invalid").is_err());
     }
 
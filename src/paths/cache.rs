@@ -19,6 +19,11 @@ use std::path::{Path, PathBuf};
 
 pub const METADATA_FILE: &str = "metadata.json";
 pub const TEMP_DIR: &str = "tmp";
+pub const HTTP_SOURCES_DIR: &str = "http-sources";
+pub const SHIM_HISTORY_FILE: &str = "shim-history";
+pub const JDK_USAGE_FILE: &str = "jdk-usage.json";
+pub const ADVISORIES_FILE: &str = "advisories.json";
+pub const NOTIFY_STATE_FILE: &str = "notify.json";
 
 pub fn cache_root(kopi_home: &Path) -> PathBuf {
     home::cache_dir(kopi_home)
@@ -36,6 +41,33 @@ pub fn temp_cache_directory(kopi_home: &Path) -> PathBuf {
     cache_root(kopi_home).join(TEMP_DIR)
 }
 
+/// File listing project version files recently resolved by shims
+pub fn shim_history_file(kopi_home: &Path) -> PathBuf {
+    cache_root(kopi_home).join(SHIM_HISTORY_FILE)
+}
+
+/// File recording when shims last ran each installed JDK
+pub fn jdk_usage_file(kopi_home: &Path) -> PathBuf {
+    cache_root(kopi_home).join(JDK_USAGE_FILE)
+}
+
+/// Cached copy of the vulnerability advisory feed used by `kopi audit`
+pub fn advisory_cache_file(kopi_home: &Path) -> PathBuf {
+    cache_root(kopi_home).join(ADVISORIES_FILE)
+}
+
+/// Result of the last `kopi notify --check`, read by shell prompts
+pub fn notify_state_file(kopi_home: &Path) -> PathBuf {
+    cache_root(kopi_home).join(NOTIFY_STATE_FILE)
+}
+
+/// Directory holding the metadata files downloaded from an HTTP metadata source
+pub fn http_source_cache_directory(kopi_home: &Path, source_name: &str) -> PathBuf {
+    cache_root(kopi_home)
+        .join(HTTP_SOURCES_DIR)
+        .join(source_name)
+}
+
 pub fn ensure_temp_cache_directory(kopi_home: &Path) -> Result<PathBuf> {
     ensure_nested_directory(kopi_home, [home::CACHE_DIR, TEMP_DIR])
 }
@@ -58,6 +90,22 @@ mod tests {
             temp_cache_directory(home),
             PathBuf::from("/opt/kopi/cache/tmp")
         );
+        assert_eq!(
+            jdk_usage_file(home),
+            PathBuf::from("/opt/kopi/cache/jdk-usage.json")
+        );
+        assert_eq!(
+            advisory_cache_file(home),
+            PathBuf::from("/opt/kopi/cache/advisories.json")
+        );
+        assert_eq!(
+            notify_state_file(home),
+            PathBuf::from("/opt/kopi/cache/notify.json")
+        );
+        assert_eq!(
+            http_source_cache_directory(home, "mirror"),
+            PathBuf::from("/opt/kopi/cache/http-sources/mirror")
+        );
     }
 
     #[test]
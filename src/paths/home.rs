@@ -21,6 +21,12 @@ pub const CACHE_DIR: &str = "cache";
 pub const SHIMS_DIR: &str = "shims";
 pub const BIN_DIR: &str = "bin";
 pub const LOCKS_DIR: &str = "locks";
+pub const DOWNLOADS_DIR: &str = "downloads";
+pub const CRASH_DIR: &str = "crash";
+pub const RUNTIMES_DIR: &str = "runtimes";
+pub const RUNTIMES_FILE: &str = "runtimes.json";
+pub const DAEMON_SOCKET: &str = "daemon.sock";
+pub const INTEGRATIONS_FILE: &str = "integrations.json";
 
 pub fn kopi_home_root(kopi_home: &Path) -> PathBuf {
     kopi_home.to_path_buf()
@@ -46,6 +52,36 @@ pub fn locks_dir(kopi_home: &Path) -> PathBuf {
     kopi_home.join(LOCKS_DIR)
 }
 
+/// Verified JDK archives kept for reinstalls, keyed by checksum
+pub fn downloads_dir(kopi_home: &Path) -> PathBuf {
+    kopi_home.join(DOWNLOADS_DIR)
+}
+
+/// Crash reports written by the panic hook
+pub fn crash_dir(kopi_home: &Path) -> PathBuf {
+    kopi_home.join(CRASH_DIR)
+}
+
+/// Default location of runtime images built by `kopi jlink create`
+pub fn runtimes_dir(kopi_home: &Path) -> PathBuf {
+    kopi_home.join(RUNTIMES_DIR)
+}
+
+/// Registry of the runtime images built by `kopi jlink create`
+pub fn runtimes_file(kopi_home: &Path) -> PathBuf {
+    kopi_home.join(RUNTIMES_FILE)
+}
+
+/// Local socket the `kopi daemon` listens on
+pub fn daemon_socket_path(kopi_home: &Path) -> PathBuf {
+    kopi_home.join(DAEMON_SOCKET)
+}
+
+/// Editor settings files kopi keeps in sync with installed JDKs
+pub fn integrations_file(kopi_home: &Path) -> PathBuf {
+    kopi_home.join(INTEGRATIONS_FILE)
+}
+
 pub fn ensure_kopi_home(kopi_home: &Path) -> Result<PathBuf> {
     ensure_directory(kopi_home.to_path_buf())
 }
@@ -58,6 +94,10 @@ pub fn ensure_cache_dir(kopi_home: &Path) -> Result<PathBuf> {
     ensure_child_directory(kopi_home, CACHE_DIR)
 }
 
+pub fn ensure_crash_dir(kopi_home: &Path) -> Result<PathBuf> {
+    ensure_child_directory(kopi_home, CRASH_DIR)
+}
+
 pub fn ensure_shims_dir(kopi_home: &Path) -> Result<PathBuf> {
     ensure_child_directory(kopi_home, SHIMS_DIR)
 }
@@ -83,6 +123,11 @@ mod tests {
         assert_eq!(shims_dir(home), PathBuf::from("/tmp/kopi/shims"));
         assert_eq!(bin_dir(home), PathBuf::from("/tmp/kopi/bin"));
         assert_eq!(locks_dir(home), PathBuf::from("/tmp/kopi/locks"));
+        assert_eq!(downloads_dir(home), PathBuf::from("/tmp/kopi/downloads"));
+        assert_eq!(
+            daemon_socket_path(home),
+            PathBuf::from("/tmp/kopi/daemon.sock")
+        );
     }
 
     #[test]
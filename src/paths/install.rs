@@ -18,6 +18,8 @@ use crate::paths::shared::ensure_nested_directory;
 use std::path::{Path, PathBuf};
 
 pub const TEMP_STAGING_DIR: &str = ".tmp";
+/// Prefix of the directories archives are extracted into next to their destination
+pub const EXTRACT_STAGING_PREFIX: &str = ".kopi-";
 pub const BUNDLE_CONTENTS_DIR: &str = "Contents";
 pub const BUNDLE_JAVA_HOME_DIR: &str = "Home";
 pub const BUNDLE_JAVA_HOME_SUFFIX: &str = "Contents/Home";
@@ -38,6 +40,20 @@ pub fn metadata_file<S: AsRef<str>>(kopi_home: &Path, slug: S) -> PathBuf {
     installations_root(kopi_home).join(format!("{}.meta.json", slug.as_ref()))
 }
 
+pub fn manifest_file<S: AsRef<str>>(kopi_home: &Path, slug: S) -> PathBuf {
+    installations_root(kopi_home).join(format!("{}.manifest.json", slug.as_ref()))
+}
+
+/// Companion archive such as `temurin-21.sources.zip` stored next to an installation
+pub fn companion_file<S: AsRef<str>>(
+    kopi_home: &Path,
+    slug: S,
+    kind: &str,
+    extension: &str,
+) -> PathBuf {
+    installations_root(kopi_home).join(format!("{}.{kind}.{extension}", slug.as_ref()))
+}
+
 pub fn temp_staging_directory(kopi_home: &Path) -> PathBuf {
     installations_root(kopi_home).join(TEMP_STAGING_DIR)
 }
@@ -78,6 +94,14 @@ mod tests {
             metadata_file(home, slug),
             PathBuf::from("/opt/kopi/jdks/temurin-21-jdk-x64.meta.json")
         );
+        assert_eq!(
+            manifest_file(home, slug),
+            PathBuf::from("/opt/kopi/jdks/temurin-21-jdk-x64.manifest.json")
+        );
+        assert_eq!(
+            companion_file(home, slug, "sources", "zip"),
+            PathBuf::from("/opt/kopi/jdks/temurin-21-jdk-x64.sources.zip")
+        );
         assert_eq!(
             temp_staging_directory(home),
             PathBuf::from("/opt/kopi/jdks/.tmp")
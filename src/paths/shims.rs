@@ -17,6 +17,9 @@ use crate::paths::home;
 use crate::platform::{shim_binary_name, with_executable_extension};
 use std::path::{Path, PathBuf};
 
+pub const EXTERNAL_TOOLS_FILE: &str = "external-tools.json";
+pub const PATH_WARNING_FILE: &str = "path-shadowing-warned";
+
 pub fn shims_root(kopi_home: &Path) -> PathBuf {
     home::shims_dir(kopi_home)
 }
@@ -33,6 +36,16 @@ pub fn tool_shim_path(kopi_home: &Path, tool_name: &str) -> PathBuf {
     shims_root(kopi_home).join(with_executable_extension(tool_name))
 }
 
+/// Registry of non-JDK executables wrapped by shims, kept outside the shims directory
+pub fn external_tools_file(kopi_home: &Path) -> PathBuf {
+    home::kopi_home_root(kopi_home).join(EXTERNAL_TOOLS_FILE)
+}
+
+/// `PATH` entries last warned about for shadowing the shims
+pub fn path_warning_file(kopi_home: &Path) -> PathBuf {
+    home::kopi_home_root(kopi_home).join(PATH_WARNING_FILE)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,6 +63,14 @@ mod tests {
             tool_shim_path(home, "java"),
             PathBuf::from("/opt/kopi/shims").join(with_executable_extension("java"))
         );
+        assert_eq!(
+            external_tools_file(home),
+            PathBuf::from("/opt/kopi/external-tools.json")
+        );
+        assert_eq!(
+            path_warning_file(home),
+            PathBuf::from("/opt/kopi/path-shadowing-warned")
+        );
     }
 
     #[test]
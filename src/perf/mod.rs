@@ -0,0 +1,200 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lightweight span collection for the `--timings` report.
+//!
+//! Commands create a [`TimingCollector`] and hand clones of it to the layers
+//! they call. When timings are disabled every operation is a no-op, so the
+//! collector can be threaded through unconditionally.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Coarse phases reported by `--timings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    Resolution,
+    Network,
+    Checksum,
+    Extraction,
+    MetadataWrite,
+}
+
+impl Phase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Phase::Resolution => "resolution",
+            Phase::Network => "network",
+            Phase::Checksum => "checksum",
+            Phase::Extraction => "extraction",
+            Phase::MetadataWrite => "metadata write",
+        }
+    }
+}
+
+/// Aggregated duration for a single phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTotal {
+    pub phase: Phase,
+    pub duration: Duration,
+    pub spans: usize,
+}
+
+#[derive(Debug)]
+struct TimingState {
+    started: Instant,
+    spans: Vec<(Phase, Duration)>,
+}
+
+/// Collects timed spans across a command. Cloning shares the same storage.
+#[derive(Debug, Clone, Default)]
+pub struct TimingCollector {
+    state: Option<Arc<Mutex<TimingState>>>,
+}
+
+impl TimingCollector {
+    pub fn new(enabled: bool) -> Self {
+        if !enabled {
+            return Self::disabled();
+        }
+
+        Self {
+            state: Some(Arc::new(Mutex::new(TimingState {
+                started: Instant::now(),
+                spans: Vec::new(),
+            }))),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self { state: None }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.state.is_some()
+    }
+
+    /// Run `operation` and attribute its wall-clock time to `phase`.
+    pub fn time<T>(&self, phase: Phase, operation: impl FnOnce() -> T) -> T {
+        if !self.is_enabled() {
+            return operation();
+        }
+
+        let start = Instant::now();
+        let result = operation();
+        self.record(phase, start.elapsed());
+        result
+    }
+
+    pub fn record(&self, phase: Phase, duration: Duration) {
+        if let Some(state) = &self.state
+            && let Ok(mut state) = state.lock()
+        {
+            state.spans.push((phase, duration));
+        }
+    }
+
+    /// Per-phase totals in phase order, omitting phases that never ran.
+    pub fn totals(&self) -> Vec<PhaseTotal> {
+        let Some(state) = &self.state else {
+            return Vec::new();
+        };
+        let Ok(state) = state.lock() else {
+            return Vec::new();
+        };
+
+        let mut totals: Vec<PhaseTotal> = Vec::new();
+        for (phase, duration) in &state.spans {
+            match totals.iter_mut().find(|total| total.phase == *phase) {
+                Some(total) => {
+                    total.duration += *duration;
+                    total.spans += 1;
+                }
+                None => totals.push(PhaseTotal {
+                    phase: *phase,
+                    duration: *duration,
+                    spans: 1,
+                }),
+            }
+        }
+        totals.sort_by_key(|total| total.phase);
+        totals
+    }
+
+    /// Render the report printed at the end of a command, if enabled.
+    ///
+    /// Phases can nest (a metadata refresh during resolution also records
+    /// network time), so the per-phase values are not expected to add up to
+    /// the total.
+    pub fn render(&self) -> Option<String> {
+        let elapsed = {
+            let state = self.state.as_ref()?.lock().ok()?;
+            state.started.elapsed()
+        };
+
+        let mut report = String::from("Timings:\n");
+        for total in self.totals() {
+            let spans = if total.spans > 1 {
+                format!(" ({} spans)", total.spans)
+            } else {
+                String::new()
+            };
+            report.push_str(&format!(
+                "  {:<16}{:>9.3}s{spans}\n",
+                total.phase.label(),
+                total.duration.as_secs_f64()
+            ));
+        }
+        report.push_str(&format!("  {:<16}{:>9.3}s", "total", elapsed.as_secs_f64()));
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_collector_records_nothing() {
+        let timings = TimingCollector::disabled();
+        let value = timings.time(Phase::Network, || 42);
+
+        assert_eq!(value, 42);
+        assert!(timings.totals().is_empty());
+        assert!(timings.render().is_none());
+    }
+
+    #[test]
+    fn test_totals_are_aggregated_per_phase() {
+        let timings = TimingCollector::new(true);
+        let shared = timings.clone();
+
+        timings.record(Phase::Extraction, Duration::from_millis(30));
+        shared.record(Phase::Network, Duration::from_millis(10));
+        shared.record(Phase::Network, Duration::from_millis(15));
+
+        let totals = timings.totals();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].phase, Phase::Network);
+        assert_eq!(totals[0].duration, Duration::from_millis(25));
+        assert_eq!(totals[0].spans, 2);
+        assert_eq!(totals[1].phase, Phase::Extraction);
+
+        let report = timings.render().unwrap();
+        assert!(report.starts_with("Timings:"));
+        assert!(report.contains("network"));
+        assert!(report.contains("(2 spans)"));
+        assert!(report.contains("total"));
+    }
+}
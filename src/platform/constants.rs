@@ -35,6 +35,38 @@ pub fn with_executable_extension(name: &str) -> String {
     format!("{}{}", name, executable_extension())
 }
 
+/// Extensions Windows tries when `PATHEXT` is unset
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+/// Executable extensions listed in a `PATHEXT` value, lowercased, in order
+/// and without duplicates. Unset or empty values fall back to the Windows
+/// default.
+pub fn parse_pathext(value: Option<&str>) -> Vec<String> {
+    let value = value
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or(DEFAULT_PATHEXT);
+    let mut extensions: Vec<String> = Vec::new();
+    for extension in value.split(';').map(str::trim) {
+        if extension.is_empty() {
+            continue;
+        }
+        let extension = format!(".{}", extension.trim_start_matches('.')).to_ascii_lowercase();
+        if !extensions.contains(&extension) {
+            extensions.push(extension);
+        }
+    }
+    extensions
+}
+
+/// Extensions tried, in order, when a command is looked up by name: those
+/// in `PATHEXT` on Windows, none elsewhere
+pub fn executable_extensions() -> Vec<String> {
+    #[cfg(windows)]
+    return parse_pathext(std::env::var("PATHEXT").ok().as_deref());
+    #[cfg(not(windows))]
+    return Vec::new();
+}
+
 /// Get the shim binary name for the current platform
 pub fn shim_binary_name() -> &'static str {
     #[cfg(windows)]
@@ -113,6 +145,17 @@ mod tests {
         assert_eq!(javac_exe, "javac");
     }
 
+    #[test]
+    fn test_parse_pathext() {
+        assert_eq!(
+            parse_pathext(Some(".COM;.EXE;.BAT;.CMD;.VBS;.exe")),
+            [".com", ".exe", ".bat", ".cmd", ".vbs"]
+        );
+        assert_eq!(parse_pathext(Some(" .Exe ; ;CMD")), [".exe", ".cmd"]);
+        assert_eq!(parse_pathext(None), [".com", ".exe", ".bat", ".cmd"]);
+        assert_eq!(parse_pathext(Some("")), parse_pathext(None));
+    }
+
     #[test]
     fn test_shim_binary_name() {
         let name = shim_binary_name();
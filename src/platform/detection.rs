@@ -124,8 +124,20 @@ pub fn get_current_platform() -> (String, String, String) {
 /// - `arm` → `"arm32"`
 /// - `powerpc64` → `"ppc64le"` (little endian) or `"ppc64"` (big endian)
 /// - `s390x` → `"s390x"`
+/// - `riscv64` → `"riscv64"`
 /// - Others → `"unknown"`
+///
+/// On Windows the native machine wins over the compiled target, so an x64
+/// build running under emulation on ARM64 reports `"aarch64"`.
 pub fn get_current_architecture() -> String {
+    #[cfg(all(
+        target_os = "windows",
+        any(target_arch = "x86_64", target_arch = "x86")
+    ))]
+    if let Some(native) = windows_native_architecture() {
+        return native.to_string();
+    }
+
     #[cfg(target_arch = "x86_64")]
     return "x64".to_string();
 
@@ -149,17 +161,94 @@ pub fn get_current_architecture() -> String {
     #[cfg(target_arch = "s390x")]
     return "s390x".to_string();
 
+    #[cfg(target_arch = "riscv64")]
+    return "riscv64".to_string();
+
     #[cfg(not(any(
         target_arch = "x86_64",
         target_arch = "x86",
         target_arch = "aarch64",
         target_arch = "arm",
         target_arch = "powerpc64",
-        target_arch = "s390x"
+        target_arch = "s390x",
+        target_arch = "riscv64"
     )))]
     return "unknown".to_string();
 }
 
+/// Ask Windows for the native machine type of the host.
+///
+/// `IsWow64Process2` only exists on Windows 10 1709 and later, so it is looked
+/// up at run time rather than linked directly.
+#[cfg(all(
+    target_os = "windows",
+    any(target_arch = "x86_64", target_arch = "x86")
+))]
+fn windows_native_architecture() -> Option<&'static str> {
+    use winapi::shared::minwindef::{BOOL, FARPROC, USHORT};
+    use winapi::um::libloaderapi::{GetModuleHandleA, GetProcAddress};
+    use winapi::um::processthreadsapi::GetCurrentProcess;
+    use winapi::um::winnt::{
+        HANDLE, IMAGE_FILE_MACHINE_AMD64, IMAGE_FILE_MACHINE_ARM64, IMAGE_FILE_MACHINE_I386,
+    };
+
+    type IsWow64Process2Fn = unsafe extern "system" fn(HANDLE, *mut USHORT, *mut USHORT) -> BOOL;
+
+    static NATIVE: OnceLock<Option<&'static str>> = OnceLock::new();
+    *NATIVE.get_or_init(|| {
+        // SAFETY: both names are NUL-terminated, and the resolved symbol has the
+        // documented IsWow64Process2 signature.
+        let native_machine = unsafe {
+            let kernel32 = GetModuleHandleA(c"kernel32.dll".as_ptr());
+            if kernel32.is_null() {
+                return None;
+            }
+            let symbol = GetProcAddress(kernel32, c"IsWow64Process2".as_ptr());
+            if symbol.is_null() {
+                return None;
+            }
+            let is_wow64_process2 = std::mem::transmute::<FARPROC, IsWow64Process2Fn>(symbol);
+
+            let mut process_machine: USHORT = 0;
+            let mut native_machine: USHORT = 0;
+            if is_wow64_process2(
+                GetCurrentProcess(),
+                &mut process_machine,
+                &mut native_machine,
+            ) == 0
+            {
+                return None;
+            }
+            native_machine
+        };
+
+        match native_machine {
+            IMAGE_FILE_MACHINE_ARM64 => Some("aarch64"),
+            IMAGE_FILE_MACHINE_AMD64 => Some("x64"),
+            IMAGE_FILE_MACHINE_I386 => Some("x86"),
+            _ => None,
+        }
+    })
+}
+
+/// Architectures whose builds can still run on `os`/`arch` under emulation,
+/// paired with the mechanism that provides it
+pub fn emulated_architectures(os: &str, arch: &str) -> &'static [(&'static str, &'static str)] {
+    match (os, arch) {
+        ("windows", "aarch64") => &[
+            ("x64", "Windows on Arm x64 emulation (Windows 11)"),
+            ("x86", "Windows on Arm x86 emulation"),
+        ],
+        ("macos", "aarch64") => &[("x64", "Rosetta 2")],
+        ("linux", "aarch64") => &[("x64", "qemu-user or box64")],
+        ("linux", "riscv64") => &[
+            ("x64", "qemu-user with binfmt_misc"),
+            ("aarch64", "qemu-user with binfmt_misc"),
+        ],
+        _ => &[],
+    }
+}
+
 /// Detect the current operating system.
 ///
 /// Maps Rust's target OS to foojay.io's OS naming:
@@ -223,6 +312,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_emulated_architectures() {
+        assert_eq!(emulated_architectures("windows", "aarch64")[0].0, "x64");
+        assert_eq!(emulated_architectures("macos", "aarch64")[0].1, "Rosetta 2");
+        assert!(
+            emulated_architectures("linux", "riscv64")
+                .iter()
+                .any(|(arch, _)| *arch == "x64")
+        );
+        assert!(emulated_architectures("linux", "x64").is_empty());
+    }
+
     #[test]
     fn test_platform_description() {
         let description = get_platform_description();
@@ -18,7 +18,7 @@ use crate::error::Result;
 use log::debug;
 use std::fs::{self, OpenOptions, TryLockError};
 use std::io::{self, ErrorKind};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -102,8 +102,38 @@ pub fn is_executable(path: &Path) -> std::io::Result<bool> {
 
 #[cfg(windows)]
 pub fn is_executable(path: &Path) -> std::io::Result<bool> {
-    // On Windows, check for .exe extension
-    Ok(path.extension().map(|ext| ext == "exe").unwrap_or(false))
+    // On Windows, check for an extension listed in PATHEXT
+    Ok(has_extension_in(
+        path,
+        &crate::platform::executable_extensions(),
+    ))
+}
+
+/// The executable `name` in `dir`, resolved the way the shell would: on
+/// Windows, a name without an executable extension is tried with each
+/// `PATHEXT` extension in turn (`java` finds `java.exe`, then `java.cmd`).
+pub fn find_executable(dir: &Path, name: &str) -> Option<PathBuf> {
+    find_with_extensions(dir, name, &crate::platform::executable_extensions())
+}
+
+fn find_with_extensions(dir: &Path, name: &str, extensions: &[String]) -> Option<PathBuf> {
+    let exact = dir.join(name);
+    if extensions.is_empty() || has_extension_in(&exact, extensions) {
+        return exact.is_file().then_some(exact);
+    }
+    extensions
+        .iter()
+        .map(|extension| dir.join(format!("{name}{extension}")))
+        .find(|candidate| candidate.is_file())
+}
+
+fn has_extension_in(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            let extension = format!(".{}", extension.to_ascii_lowercase());
+            extensions.contains(&extension)
+        })
 }
 
 /// Set file permissions from a Unix mode value.
@@ -149,6 +179,122 @@ pub fn make_writable(path: &Path) -> std::io::Result<()> {
     }
 }
 
+/// Windows registry key holding the system-wide long path opt-in.
+#[cfg(target_os = "windows")]
+const FILESYSTEM_REGISTRY_KEY: &str = r"SYSTEM\CurrentControlSet\Control\FileSystem";
+
+/// Convert `path` to its extended-length (`\\?\`) form so that Win32 calls accept
+/// paths longer than `MAX_PATH` (260 characters) even when long path support is
+/// disabled system-wide.
+///
+/// Relative paths are made absolute first because the prefix turns off Win32 path
+/// normalization. Paths that cannot be converted are returned unchanged. On other
+/// platforms this is the identity function.
+#[cfg(target_os = "windows")]
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match absolute.to_str().and_then(to_extended_length) {
+        Some(extended) => PathBuf::from(extended),
+        None => absolute,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// Prefix an absolute Windows path with `\\?\` (or `\\?\UNC\` for shares),
+/// resolving `.` and `..` and normalizing separators, which the prefix disables.
+///
+/// Returns `None` for paths that are relative, drive-relative (`C:foo`), or already
+/// use a device or extended-length prefix.
+#[cfg(any(target_os = "windows", test))]
+fn to_extended_length(path: &str) -> Option<String> {
+    let normalized = path.replace('/', "\\");
+    if normalized.starts_with(r"\\?\") || normalized.starts_with(r"\\.\") {
+        return None;
+    }
+
+    let (prefix, rest, fixed) = if let Some(unc) = normalized.strip_prefix(r"\\") {
+        // `\\server\share` must survive any amount of `..`
+        (r"\\?\UNC\", unc, 2)
+    } else {
+        let bytes = normalized.as_bytes();
+        let has_drive = bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && bytes[2] == b'\\';
+        if !has_drive {
+            return None;
+        }
+        (r"\\?\", normalized.as_str(), 1)
+    };
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('\\') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if components.len() > fixed {
+                    components.pop();
+                }
+            }
+            other => components.push(other),
+        }
+    }
+    if components.len() < fixed {
+        return None;
+    }
+
+    let mut extended = format!("{prefix}{}", components.join(r"\"));
+    if components.len() == fixed && prefix == r"\\?\" {
+        // The root of a drive keeps its trailing separator
+        extended.push('\\');
+    }
+    Some(extended)
+}
+
+/// Whether Windows is configured to accept paths longer than `MAX_PATH` in
+/// applications that opt in (the `LongPathsEnabled` registry value).
+///
+/// A missing value means the feature is disabled, which is the Windows default.
+#[cfg(target_os = "windows")]
+pub fn windows_long_paths_enabled() -> io::Result<bool> {
+    use winapi::shared::minwindef::DWORD;
+    use winapi::shared::winerror::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use winapi::um::winreg::{HKEY_LOCAL_MACHINE, RRF_RT_REG_DWORD, RegGetValueW};
+
+    let to_wide = |value: &str| -> Vec<u16> {
+        OsStr::new(value)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect()
+    };
+    let key = to_wide(FILESYSTEM_REGISTRY_KEY);
+    let value_name = to_wide("LongPathsEnabled");
+
+    let mut data: DWORD = 0;
+    let mut data_size = std::mem::size_of::<DWORD>() as DWORD;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            key.as_ptr(),
+            value_name.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            (&mut data as *mut DWORD).cast(),
+            &mut data_size,
+        )
+    } as DWORD;
+
+    match status {
+        ERROR_SUCCESS => Ok(data != 0),
+        ERROR_FILE_NOT_FOUND => Ok(false),
+        code => Err(io::Error::from_raw_os_error(code as i32)),
+    }
+}
+
 /// Atomically rename a file from source to destination.
 ///
 /// On Unix systems, rename is atomic by default.
@@ -161,12 +307,12 @@ pub fn atomic_rename(from: &Path, to: &Path) -> std::io::Result<()> {
         Err(err) => return Err(err),
     }
 
-    let to_wide: Vec<u16> = to
+    let to_wide: Vec<u16> = extended_length_path(to)
         .as_os_str()
         .encode_wide()
         .chain(std::iter::once(0u16))
         .collect();
-    let from_wide: Vec<u16> = from
+    let from_wide: Vec<u16> = extended_length_path(from)
         .as_os_str()
         .encode_wide()
         .chain(std::iter::once(0u16))
@@ -192,6 +338,127 @@ pub fn atomic_rename(from: &Path, to: &Path) -> std::io::Result<()> {
     fs::rename(from, to)
 }
 
+/// Replace `path` with `contents` through a temporary file in the same
+/// directory, so readers see either the old or the new file, never a partial
+/// one. The temporary name includes the process id so concurrent writers do
+/// not clobber each other's staging file.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut temp_name = file_name.to_os_string();
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    fs::write(&temp_path, contents)?;
+    atomic_rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+/// How [`clone_file`] produced the destination file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloneMethod {
+    /// Copy-on-write clone sharing the source's data blocks.
+    Reflink,
+    /// Regular byte-for-byte copy.
+    Copy,
+}
+
+/// Copy `from` to a new file at `to`, cloning it copy-on-write (a reflink) on
+/// filesystems that support it, such as btrfs, XFS and APFS.
+///
+/// Falls back silently to a full copy where cloning is unsupported. Fails if
+/// `to` already exists.
+pub fn clone_file(from: &Path, to: &Path) -> io::Result<CloneMethod> {
+    match reflink(from, to) {
+        Ok(()) => return Ok(CloneMethod::Reflink),
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => return Err(err),
+        Err(err) => debug!(
+            "Reflink {} -> {} unavailable ({err}), copying instead",
+            from.display(),
+            to.display()
+        ),
+    }
+
+    if to.symlink_metadata().is_ok() {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    fs::copy(from, to)?;
+    Ok(CloneMethod::Copy)
+}
+
+/// Report whether files in `dir` can be cloned copy-on-write.
+pub fn supports_reflink(dir: &Path) -> bool {
+    let probe = || -> io::Result<()> {
+        let source = tempfile::Builder::new()
+            .prefix(".kopi-reflink-probe")
+            .tempfile_in(dir)?;
+        fs::write(source.path(), b"kopi")?;
+        let target = source.path().with_extension("clone");
+        let result = reflink(source.path(), &target);
+        let _ = fs::remove_file(&target);
+        result
+    };
+
+    match probe() {
+        Ok(()) => true,
+        Err(err) => {
+            debug!("Reflinks unavailable in {}: {err}", dir.display());
+            false
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn reflink(from: &Path, to: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let source = fs::File::open(from)?;
+    let target = OpenOptions::new().write(true).create_new(true).open(to)?;
+
+    // SAFETY: both descriptors are open for the duration of the call.
+    let result = unsafe { libc::ioctl(target.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) };
+    if result != 0 {
+        let err = io::Error::last_os_error();
+        drop(target);
+        let _ = fs::remove_file(to);
+        return Err(err);
+    }
+
+    fs::set_permissions(to, source.metadata()?.permissions())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(from: &Path, to: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let to_c_string = |path: &Path| {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|err| io::Error::new(ErrorKind::InvalidInput, err))
+    };
+    let source = to_c_string(from)?;
+    let target = to_c_string(to)?;
+
+    // SAFETY: both pointers are valid NUL-terminated strings for the call.
+    if unsafe { libc::clonefile(source.as_ptr(), target.as_ptr(), 0) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+fn reflink(_from: &Path, _to: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        ErrorKind::Unsupported,
+        "copy-on-write clones are not supported on this platform",
+    ))
+}
+
 /// Check if any files in the given path are currently in use
 pub fn check_files_in_use(path: &Path) -> Result<Vec<String>> {
     debug!(
@@ -321,7 +588,7 @@ pub fn post_removal_cleanup(path: &Path) -> Result<()> {
 
 #[cfg(target_os = "windows")]
 fn remove_readonly_attribute(path: &Path) -> std::io::Result<()> {
-    let path_wide: Vec<u16> = OsStr::new(path)
+    let path_wide: Vec<u16> = OsStr::new(&extended_length_path(path))
         .encode_wide()
         .chain(std::iter::once(0))
         .collect();
@@ -566,6 +833,119 @@ mod tests {
     use std::thread;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("usage.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // No staging file is left behind
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_find_with_extensions_follows_pathext_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let pathext = crate::platform::parse_pathext(Some(".COM;.EXE;.BAT;.CMD"));
+        fs::write(dir.join("native-image.cmd"), "").unwrap();
+        fs::write(dir.join("java.cmd"), "").unwrap();
+        fs::write(dir.join("java.exe"), "").unwrap();
+
+        assert_eq!(
+            find_with_extensions(dir, "java", &pathext),
+            Some(dir.join("java.exe"))
+        );
+        assert_eq!(
+            find_with_extensions(dir, "native-image", &pathext),
+            Some(dir.join("native-image.cmd"))
+        );
+        // A name with an executable extension is only looked up as given
+        assert_eq!(
+            find_with_extensions(dir, "java.cmd", &pathext),
+            Some(dir.join("java.cmd"))
+        );
+        assert_eq!(find_with_extensions(dir, "javac", &pathext), None);
+
+        // Without PATHEXT, only the exact name matches
+        assert_eq!(find_with_extensions(dir, "java", &[]), None);
+        assert_eq!(
+            find_with_extensions(dir, "java.exe", &[]),
+            Some(dir.join("java.exe"))
+        );
+    }
+
+    #[test]
+    fn test_to_extended_length_drive_paths() {
+        assert_eq!(
+            to_extended_length(r"C:\Users\dev\.kopi\jdks\temurin-21").as_deref(),
+            Some(r"\\?\C:\Users\dev\.kopi\jdks\temurin-21")
+        );
+        assert_eq!(
+            to_extended_length("C:/Users/dev/./.kopi/../.kopi/jdks").as_deref(),
+            Some(r"\\?\C:\Users\dev\.kopi\jdks")
+        );
+        assert_eq!(to_extended_length(r"D:\..\..").as_deref(), Some(r"\\?\D:\"));
+    }
+
+    #[test]
+    fn test_to_extended_length_unc_paths() {
+        assert_eq!(
+            to_extended_length(r"\\server\share\kopi\jdks").as_deref(),
+            Some(r"\\?\UNC\server\share\kopi\jdks")
+        );
+        assert_eq!(
+            to_extended_length(r"\\server\share\..\..\jdks").as_deref(),
+            Some(r"\\?\UNC\server\share\jdks")
+        );
+        assert_eq!(to_extended_length(r"\\server"), None);
+    }
+
+    #[test]
+    fn test_to_extended_length_leaves_other_paths_alone() {
+        assert_eq!(to_extended_length(r"\\?\C:\already\extended"), None);
+        assert_eq!(to_extended_length(r"\\.\pipe\kopi"), None);
+        assert_eq!(to_extended_length(r"relative\path"), None);
+        assert_eq!(to_extended_length("C:drive-relative"), None);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_extended_length_path_is_identity_off_windows() {
+        let path = Path::new("relative/jdks/temurin-21");
+        assert_eq!(extended_length_path(path), path);
+    }
+
+    #[test]
+    fn clone_file_copies_content_and_refuses_to_overwrite() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source.bin");
+        let target = temp_dir.path().join("target.bin");
+        fs::write(&source, b"cloned bytes").unwrap();
+        set_permissions_from_mode(&source, 0o755).unwrap();
+
+        // Either method is fine; tmpfs and ext4 fall back to copying
+        clone_file(&source, &target).unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"cloned bytes");
+        #[cfg(unix)]
+        assert_eq!(
+            fs::metadata(&target).unwrap().permissions().mode() & 0o777,
+            0o755
+        );
+
+        let err = clone_file(&source, &target).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    }
+
+    #[test]
+    fn supports_reflink_leaves_no_probe_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let _ = supports_reflink(temp_dir.path());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
     #[test]
     fn std_lock_adapter_allows_relocking() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,281 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registration of kopi-installed JDKs with macOS `/usr/libexec/java_home`.
+//!
+//! `java_home` and most IDEs discover JDKs as `.jdk` bundles in
+//! `~/Library/Java/JavaVirtualMachines`. A JDK that already ships as a bundle
+//! (`Contents/Info.plist` plus `Contents/Home`) is linked there directly; any
+//! other JDK gets a small wrapper bundle with a generated `Info.plist` and a
+//! `Contents/Home` symlink to its JAVA_HOME. Entries kopi creates are prefixed
+//! with `kopi-` so they are never confused with JDKs installed by other tools.
+
+use crate::error::{KopiError, Result};
+use crate::integrations::xml::xml_escape;
+use crate::paths::install;
+use crate::platform::symlink;
+use crate::storage::InstalledJdk;
+use log::{debug, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix of every bundle kopi creates in the JavaVirtualMachines directory
+pub const BUNDLE_PREFIX: &str = "kopi-";
+
+const BUNDLE_EXTENSION: &str = "jdk";
+const INFO_PLIST: &str = "Info.plist";
+
+/// `~/Library/Java/JavaVirtualMachines`, searched by `java_home` for per-user JDKs
+pub fn user_jvm_dir() -> Result<PathBuf> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| KopiError::SystemError("Unable to locate home directory".to_string()))?;
+    Ok(home
+        .join("Library")
+        .join("Java")
+        .join("JavaVirtualMachines"))
+}
+
+/// Bundle name for `jdk`, such as `kopi-temurin-21.0.5+11.jdk`
+pub fn bundle_name(jdk: &InstalledJdk) -> String {
+    let suffix = if jdk.javafx_bundled { "-fx" } else { "" };
+    format!(
+        "{BUNDLE_PREFIX}{}-{}{suffix}.{BUNDLE_EXTENSION}",
+        jdk.distribution, jdk.version
+    )
+}
+
+/// Make `jdk` visible to `java_home` by creating its bundle in `jvm_dir`,
+/// replacing an earlier registration. Returns the bundle path.
+pub fn register(jdk: &InstalledJdk, jvm_dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(jvm_dir).map_err(|e| {
+        KopiError::SystemError(format!("Failed to create {}: {e}", jvm_dir.display()))
+    })?;
+
+    let bundle = jvm_dir.join(bundle_name(jdk));
+    remove_bundle(&bundle)?;
+
+    let contents = install::bundle_contents_directory(&jdk.path);
+    if contents.join(INFO_PLIST).is_file() {
+        debug!(
+            "Linking bundle {} to {}",
+            bundle.display(),
+            jdk.path.display()
+        );
+        symlink::create_symlink(&jdk.path, &bundle).map_err(|e| {
+            KopiError::SystemError(format!("Failed to link {}: {e}", bundle.display()))
+        })?;
+        return Ok(bundle);
+    }
+
+    debug!("Creating wrapper bundle {}", bundle.display());
+    let wrapper_contents = install::bundle_contents_directory(&bundle);
+    fs::create_dir_all(&wrapper_contents)?;
+    fs::write(wrapper_contents.join(INFO_PLIST), info_plist(jdk))?;
+    let java_home = jdk.resolve_java_home();
+    symlink::create_symlink(&java_home, &install::bundle_java_home(&bundle)).map_err(|e| {
+        KopiError::SystemError(format!(
+            "Failed to link {} to {}: {e}",
+            install::bundle_java_home(&bundle).display(),
+            java_home.display()
+        ))
+    })?;
+    Ok(bundle)
+}
+
+/// Remove the bundle for `jdk` from `jvm_dir`. Returns whether one existed.
+pub fn unregister(jdk: &InstalledJdk, jvm_dir: &Path) -> Result<bool> {
+    remove_bundle(&jvm_dir.join(bundle_name(jdk)))
+}
+
+/// Drop the registration of a JDK that is being uninstalled. Failures are
+/// logged rather than returned so they never block the removal.
+pub fn forget(jdk: &InstalledJdk) {
+    if !cfg!(target_os = "macos") {
+        return;
+    }
+
+    match user_jvm_dir().and_then(|jvm_dir| unregister(jdk, &jvm_dir)) {
+        Ok(true) => debug!(
+            "Removed java_home registration for {}@{}",
+            jdk.distribution, jdk.version
+        ),
+        Ok(false) => {}
+        Err(e) => warn!(
+            "Failed to remove java_home registration for {}@{}: {e}",
+            jdk.distribution, jdk.version
+        ),
+    }
+}
+
+/// Remove kopi bundles whose JDK no longer exists, returning their paths
+pub fn prune(jvm_dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(jvm_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut removed = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_ours = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                name.starts_with(BUNDLE_PREFIX) && name.ends_with(&format!(".{BUNDLE_EXTENSION}"))
+            });
+        // A dangling link, or a wrapper whose Home link is dangling
+        if is_ours && !install::bundle_java_home(&path).exists() {
+            remove_bundle(&path)?;
+            removed.push(path);
+        }
+    }
+    Ok(removed)
+}
+
+fn remove_bundle(bundle: &Path) -> Result<bool> {
+    let Ok(metadata) = fs::symlink_metadata(bundle) else {
+        return Ok(false);
+    };
+
+    if metadata.file_type().is_symlink() || metadata.is_file() {
+        fs::remove_file(bundle)?;
+    } else {
+        // Wrapper bundles hold only Info.plist and the Home symlink
+        fs::remove_dir_all(bundle)?;
+    }
+    debug!("Removed {}", bundle.display());
+    Ok(true)
+}
+
+fn info_plist(jdk: &InstalledJdk) -> String {
+    let version = jdk
+        .version
+        .components
+        .iter()
+        .map(|component| component.to_string())
+        .collect::<Vec<_>>()
+        .join(".");
+    let distribution = xml_escape(&jdk.distribution);
+    let full_version = xml_escape(&jdk.version.to_string());
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>CFBundleIdentifier</key>
+	<string>dev.kopi.{distribution}.{version}</string>
+	<key>CFBundleName</key>
+	<string>{distribution} {full_version} (kopi)</string>
+	<key>CFBundleVersion</key>
+	<string>{version}</string>
+	<key>JavaVM</key>
+	<dict>
+		<key>JVMCapabilities</key>
+		<array>
+			<string>CommandLine</string>
+		</array>
+		<key>JVMName</key>
+		<string>{distribution} {full_version}</string>
+		<key>JVMPlatformVersion</key>
+		<string>{version}</string>
+		<key>JVMVendor</key>
+		<string>{distribution}</string>
+		<key>JVMVersion</key>
+		<string>{version}</string>
+	</dict>
+</dict>
+</plist>
+"#
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn installed(root: &Path, name: &str, bundle: bool) -> InstalledJdk {
+        let path = root.join(name);
+        let java_home = if bundle {
+            let contents = install::bundle_contents_directory(&path);
+            fs::create_dir_all(&contents).unwrap();
+            fs::write(contents.join(INFO_PLIST), "<plist/>").unwrap();
+            install::bundle_java_home(&path)
+        } else {
+            path.clone()
+        };
+        fs::create_dir_all(install::bin_directory(&java_home)).unwrap();
+
+        let (distribution, version) = name.split_once('-').unwrap();
+        InstalledJdk::new(
+            distribution.to_string(),
+            Version::from_str(version).unwrap(),
+            path,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_register_links_existing_bundle() {
+        let temp = TempDir::new().unwrap();
+        let jvm_dir = temp.path().join("JavaVirtualMachines");
+        let jdk = installed(temp.path(), "temurin-21.0.5+11", true);
+
+        let bundle = register(&jdk, &jvm_dir).unwrap();
+        assert_eq!(bundle, jvm_dir.join("kopi-temurin-21.0.5+11.jdk"));
+        assert_eq!(fs::read_link(&bundle).unwrap(), jdk.path);
+
+        // Registering again replaces the link
+        register(&jdk, &jvm_dir).unwrap();
+        assert!(unregister(&jdk, &jvm_dir).unwrap());
+        assert!(!bundle.exists());
+        assert!(!unregister(&jdk, &jvm_dir).unwrap());
+    }
+
+    #[test]
+    fn test_register_wraps_plain_jdk() {
+        let temp = TempDir::new().unwrap();
+        let jvm_dir = temp.path().join("JavaVirtualMachines");
+        let jdk = installed(temp.path(), "corretto-21.0.5.11.1", false);
+
+        let bundle = register(&jdk, &jvm_dir).unwrap();
+        let plist = fs::read_to_string(bundle.join("Contents/Info.plist")).unwrap();
+        assert!(plist.contains("<key>JVMVersion</key>\n\t\t<string>21.0.5.11.1</string>"));
+        assert_eq!(
+            fs::read_link(bundle.join("Contents/Home")).unwrap(),
+            jdk.path
+        );
+        assert!(bundle.join("Contents/Home/bin").is_dir());
+    }
+
+    #[test]
+    fn test_prune_removes_stale_bundles() {
+        let temp = TempDir::new().unwrap();
+        let jvm_dir = temp.path().join("JavaVirtualMachines");
+        let kept = installed(temp.path(), "temurin-21.0.5", true);
+        let gone = installed(temp.path(), "zulu-17.0.13", false);
+        register(&kept, &jvm_dir).unwrap();
+        let stale = register(&gone, &jvm_dir).unwrap();
+        fs::create_dir_all(jvm_dir.join("other-17.jdk")).unwrap();
+        fs::remove_dir_all(&gone.path).unwrap();
+
+        assert_eq!(prune(&jvm_dir).unwrap(), vec![stale]);
+        assert!(jvm_dir.join(bundle_name(&kept)).exists());
+        assert!(jvm_dir.join("other-17.jdk").exists());
+    }
+}
@@ -21,10 +21,12 @@
 // Re-export modules
 pub mod file_ops;
 pub mod filesystem;
+pub mod macos;
 pub mod process;
 pub mod shell;
 pub mod shim;
 pub mod symlink;
+pub mod wsl;
 
 // Internal modules
 mod constants;
@@ -32,14 +34,15 @@ mod detection;
 
 // Re-export detection functions
 pub use detection::{
-    get_current_architecture, get_current_os, get_current_platform, get_foojay_libc_type,
-    get_platform_description, get_platform_libc, get_required_libc_type, matches_foojay_libc_type,
+    emulated_architectures, get_current_architecture, get_current_os, get_current_platform,
+    get_foojay_libc_type, get_platform_description, get_platform_libc, get_required_libc_type,
+    matches_foojay_libc_type,
 };
 
 // Re-export constants
 pub use constants::{
-    executable_extension, is_reserved_name, kopi_binary_name, path_separator, shim_binary_name,
-    uses_symlinks_for_shims, with_executable_extension,
+    executable_extension, executable_extensions, is_reserved_name, kopi_binary_name, parse_pathext,
+    path_separator, shim_binary_name, uses_symlinks_for_shims, with_executable_extension,
 };
 
 pub use filesystem::{
@@ -879,14 +879,25 @@ pub fn exec_replace(program: &Path, args: Vec<OsString>) -> std::io::Error {
     }
 }
 
-/// Launch a shell with environment variable set on Unix
+/// Apply shell environment changes; `None` removes the variable
+fn apply_env(command: &mut Command, vars: &[(&str, Option<&str>)]) {
+    for (name, value) in vars {
+        match value {
+            Some(value) => command.env(name, value),
+            None => command.env_remove(name),
+        };
+    }
+}
+
+/// Launch a shell with environment variables set or removed on Unix
 #[cfg(unix)]
-pub fn launch_shell_with_env(shell_path: &PathBuf, env_name: &str, env_value: &str) -> Result<()> {
+pub fn launch_shell_with_env(shell_path: &PathBuf, vars: &[(&str, Option<&str>)]) -> Result<()> {
     use std::os::unix::process::CommandExt;
 
-    // Build command with environment variable
     // Parent process environment is inherited by default
-    let err = Command::new(shell_path).env(env_name, env_value).exec();
+    let mut command = Command::new(shell_path);
+    apply_env(&mut command, vars);
+    let err = command.exec();
 
     // exec only returns on error
     Err(KopiError::SystemError(format!(
@@ -894,14 +905,15 @@ pub fn launch_shell_with_env(shell_path: &PathBuf, env_name: &str, env_value: &s
     )))
 }
 
-/// Launch a shell with environment variable set on Windows
+/// Launch a shell with environment variables set or removed on Windows
 #[cfg(windows)]
-pub fn launch_shell_with_env(shell_path: &PathBuf, env_name: &str, env_value: &str) -> Result<()> {
+pub fn launch_shell_with_env(shell_path: &PathBuf, vars: &[(&str, Option<&str>)]) -> Result<()> {
     use std::process::Stdio;
 
     // On Windows, we can't replace the process, so spawn and wait
-    let status = Command::new(shell_path)
-        .env(env_name, env_value)
+    let mut command = Command::new(shell_path);
+    apply_env(&mut command, vars);
+    let status = command
         .stdin(Stdio::inherit())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
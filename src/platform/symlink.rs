@@ -44,6 +44,32 @@ pub fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Create a directory symlink at `link` pointing to `target` (Unix)
+#[cfg(unix)]
+pub fn create_dir_link(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Create a directory symlink at `link` pointing to `target` (Windows).
+///
+/// Requires Developer Mode or an elevated prompt.
+#[cfg(windows)]
+pub fn create_dir_link(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+/// Remove a directory symlink without touching its target (Unix)
+#[cfg(unix)]
+pub fn remove_dir_link(link: &Path) -> std::io::Result<()> {
+    fs::remove_file(link)
+}
+
+/// Remove a directory symlink without touching its target (Windows)
+#[cfg(windows)]
+pub fn remove_dir_link(link: &Path) -> std::io::Result<()> {
+    fs::remove_dir(link)
+}
+
 /// Verify a symlink points to the expected target
 #[cfg(unix)]
 pub fn verify_symlink(link: &Path, expected_target: &Path) -> std::io::Result<bool> {
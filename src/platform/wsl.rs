@@ -0,0 +1,158 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Windows Subsystem for Linux interop helpers.
+//!
+//! Inside WSL, Windows programs such as IDEs cannot use POSIX paths to JDKs
+//! installed in the Linux filesystem. These helpers detect WSL and translate
+//! paths to the form Windows understands.
+
+use crate::error::{KopiError, Result};
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// Whether the current process runs inside WSL (version 1 or 2).
+pub fn is_wsl() -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+    if env::var_os("WSL_DISTRO_NAME").is_some()
+        || Path::new("/proc/sys/fs/binfmt_misc/WSLInterop").exists()
+    {
+        return true;
+    }
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .is_ok_and(|release| release.to_ascii_lowercase().contains("microsoft"))
+}
+
+/// Translate a WSL path to the path Windows programs use for the same file.
+///
+/// Drive mounts such as `/mnt/c/...` map to `C:\...` directly. Other paths go
+/// through `wslpath -w`, falling back to the `\\wsl.localhost\<distro>\...`
+/// share when `wslpath` is unavailable.
+pub fn to_windows_path(path: &Path) -> Result<String> {
+    let posix = path.to_str().ok_or_else(|| {
+        KopiError::SystemError(format!(
+            "Cannot translate non-UTF-8 path for Windows: {}",
+            path.display()
+        ))
+    })?;
+
+    if let Some(windows) = drive_mount_path(posix) {
+        return Ok(windows);
+    }
+
+    match Command::new("wslpath").arg("-w").arg(path).output() {
+        Ok(output) if output.status.success() => {
+            let translated = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !translated.is_empty() {
+                return Ok(translated);
+            }
+        }
+        Ok(output) => log::debug!(
+            "wslpath -w {} failed: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => log::debug!("Cannot run wslpath: {e}"),
+    }
+
+    let distro = env::var("WSL_DISTRO_NAME").map_err(|_| {
+        KopiError::SystemError(format!(
+            "Cannot translate {} for Windows: wslpath is unavailable and WSL_DISTRO_NAME is not set",
+            path.display()
+        ))
+    })?;
+    Ok(distro_share_path(&distro, posix))
+}
+
+/// `/mnt/<drive>/rest` to `<DRIVE>:\rest`, or `None` for other paths.
+fn drive_mount_path(posix: &str) -> Option<String> {
+    let rest = posix.strip_prefix("/mnt/")?;
+    let (drive, tail) = rest.split_once('/').unwrap_or((rest, ""));
+    let mut letters = drive.chars();
+    let letter = letters.next().filter(char::is_ascii_alphabetic)?;
+    if letters.next().is_some() {
+        return None;
+    }
+    Some(format!(
+        "{}:\\{}",
+        letter.to_ascii_uppercase(),
+        tail.trim_end_matches('/').replace('/', "\\")
+    ))
+}
+
+/// Path of a Linux file as seen through the distribution's network share.
+fn distro_share_path(distro: &str, posix: &str) -> String {
+    format!(
+        "\\\\wsl.localhost\\{distro}{}",
+        posix.trim_end_matches('/').replace('/', "\\")
+    )
+}
+
+/// Add `entry` (e.g. `JAVA_HOME/p`) to a `WSLENV` value unless a variable of the
+/// same name is already shared, keeping the user's own flags.
+pub fn merge_wslenv(current: Option<&str>, entry: &str) -> String {
+    let name = entry.split('/').next().unwrap_or(entry);
+    let mut entries: Vec<&str> = current
+        .unwrap_or_default()
+        .split(':')
+        .filter(|existing| !existing.is_empty())
+        .collect();
+    if !entries
+        .iter()
+        .any(|existing| existing.split('/').next() == Some(name))
+    {
+        entries.push(entry);
+    }
+    entries.join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drive_mount_path() {
+        assert_eq!(
+            drive_mount_path("/mnt/c/Users/dev/.kopi/jdks/temurin-21").as_deref(),
+            Some(r"C:\Users\dev\.kopi\jdks\temurin-21")
+        );
+        assert_eq!(drive_mount_path("/mnt/d").as_deref(), Some(r"D:\"));
+        assert_eq!(drive_mount_path("/mnt/wsl/shared"), None);
+        assert_eq!(drive_mount_path("/home/dev/.kopi"), None);
+    }
+
+    #[test]
+    fn test_distro_share_path() {
+        assert_eq!(
+            distro_share_path("Ubuntu", "/home/dev/.kopi/jdks/temurin-21/"),
+            r"\\wsl.localhost\Ubuntu\home\dev\.kopi\jdks\temurin-21"
+        );
+    }
+
+    #[test]
+    fn test_merge_wslenv() {
+        assert_eq!(merge_wslenv(None, "JAVA_HOME/p"), "JAVA_HOME/p");
+        assert_eq!(
+            merge_wslenv(Some("USERPROFILE/p:"), "JAVA_HOME/p"),
+            "USERPROFILE/p:JAVA_HOME/p"
+        );
+        assert_eq!(
+            merge_wslenv(Some("JAVA_HOME/up"), "JAVA_HOME/p"),
+            "JAVA_HOME/up"
+        );
+    }
+}
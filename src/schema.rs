@@ -0,0 +1,117 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JSON Schemas for the files kopi reads, and parsing that names the
+//! offending key when a file does not match them.
+
+use crate::cache::MetadataCache;
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::storage::JdkMetadataWithInstallation;
+use schemars::schema_for;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::path::Path;
+
+/// A file format kopi can describe with a JSON Schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaDocument {
+    /// `config.toml` and `.kopi.toml`, with TOML tables as JSON objects
+    Config,
+    /// The `<slug>.meta.json` file written next to each installed JDK
+    Jdk,
+    /// The metadata cache in `cache/metadata.json`
+    Cache,
+}
+
+impl SchemaDocument {
+    pub fn schema(&self) -> serde_json::Value {
+        let schema = match self {
+            SchemaDocument::Config => schema_for!(KopiConfig),
+            SchemaDocument::Jdk => schema_for!(JdkMetadataWithInstallation),
+            SchemaDocument::Cache => schema_for!(MetadataCache),
+        };
+        schema.to_value()
+    }
+}
+
+/// Deserialize `contents` of `file`, failing with the JSON path of the first
+/// value that does not fit `T`
+pub fn from_json_str<T: DeserializeOwned>(file: &Path, contents: &str) -> Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_str(contents);
+    serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|error| schema_violation(&file.display().to_string(), error))
+}
+
+/// Convert a path-tracking deserialization error into
+/// [`KopiError::SchemaViolation`]
+pub fn schema_violation<E: fmt::Display>(
+    file: &str,
+    error: serde_path_to_error::Error<E>,
+) -> KopiError {
+    let path = error.path().to_string();
+    KopiError::SchemaViolation {
+        file: file.to_string(),
+        // An error at the root has no key to name
+        path: if path == "." { String::new() } else { path },
+        message: error.into_inner().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::InstallationMetadata;
+
+    #[test]
+    fn test_schemas_describe_top_level_keys() {
+        let config = SchemaDocument::Config.schema();
+        let properties = &config["properties"];
+        assert!(properties.get("default_distribution").is_some());
+        assert!(properties.get("locking").is_some());
+        assert!(properties.get("kopi_home").is_none());
+
+        let jdk = SchemaDocument::Jdk.schema();
+        assert!(jdk["properties"].get("installation_metadata").is_some());
+        assert!(jdk["properties"].get("java_version").is_some());
+
+        let cache = SchemaDocument::Cache.schema();
+        assert!(cache["properties"].get("distributions").is_some());
+    }
+
+    #[test]
+    fn test_from_json_str_names_offending_path() {
+        let file = Path::new("temurin-21.meta.json");
+        let error = from_json_str::<InstallationMetadata>(
+            file,
+            r#"{"java_home_suffix": "", "structure_type": "direct", "platform": 7}"#,
+        )
+        .unwrap_err();
+        match error {
+            KopiError::SchemaViolation {
+                file,
+                path,
+                message,
+            } => {
+                assert_eq!(file, "temurin-21.meta.json");
+                assert_eq!(path, "platform");
+                assert!(message.contains("expected a string"), "{message}");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        let error = from_json_str::<InstallationMetadata>(file, "[").unwrap_err();
+        assert!(matches!(error, KopiError::SchemaViolation { path, .. } if path.is_empty()));
+    }
+}
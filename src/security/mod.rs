@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod tls;
+
 use crate::error::{KopiError, Result};
 use crate::models::package::ChecksumType;
 use crate::platform::file_ops;
@@ -22,7 +24,9 @@ use std::fs::File;
 use std::io::{self, Read};
 use std::path::Path;
 
-const CHUNK_SIZE: usize = 8192;
+/// Large reads keep the hasher, not the syscalls, the bottleneck on
+/// multi-hundred-megabyte archives
+const CHUNK_SIZE: usize = 1024 * 1024;
 
 pub fn verify_checksum(
     file_path: &Path,
@@ -32,9 +36,9 @@ pub fn verify_checksum(
     let actual = calculate_checksum(file_path, checksum_type)?;
 
     if actual != expected_checksum {
-        return Err(KopiError::ValidationError(format!(
-            "Checksum verification failed for {file_path:?}. Expected: {expected_checksum}, \
-             Actual: {actual}"
+        return Err(KopiError::ChecksumMismatch(format!(
+            "{} (expected {expected_checksum}, got {actual})",
+            file_path.display()
         )));
     }
 
@@ -42,9 +46,22 @@ pub fn verify_checksum(
     Ok(())
 }
 
+/// Parse a SHA-256 digest given on the command line into lowercase hex.
+///
+/// A `sha256:` prefix, as printed by container tooling, is accepted.
+pub fn parse_sha256(value: &str) -> std::result::Result<String, String> {
+    let trimmed = value.trim();
+    let digest = trimmed.strip_prefix("sha256:").unwrap_or(trimmed);
+    if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "invalid SHA-256 digest '{value}'; expected 64 hexadecimal characters"
+        ));
+    }
+    Ok(digest.to_ascii_lowercase())
+}
+
 pub fn calculate_checksum(file_path: &Path, checksum_type: ChecksumType) -> Result<String> {
-    let mut file = File::open(file_path)?;
-    let mut buffer = vec![0; CHUNK_SIZE];
+    let file = File::open(file_path)?;
 
     // Create appropriate hasher based on checksum type
     let mut hasher: Box<dyn DynDigest> = match checksum_type {
@@ -52,27 +69,31 @@ pub fn calculate_checksum(file_path: &Path, checksum_type: ChecksumType) -> Resu
         ChecksumType::Sha256 => Box::new(Sha256::new()),
         ChecksumType::Sha512 => Box::new(Sha512::new()),
         ChecksumType::Md5 => {
-            // MD5 requires special handling because md5 crate doesn't implement DynDigest
-            let mut file_contents = Vec::new();
-            file.read_to_end(&mut file_contents)?;
-            let digest = md5::compute(&file_contents);
-            return Ok(hex::encode(digest.0));
+            // The md5 crate doesn't implement DynDigest, but it can still stream
+            let mut context = md5::Context::new();
+            read_chunks(file, |chunk| context.consume(chunk))?;
+            return Ok(hex::encode(context.compute().0));
         }
     };
 
-    // Process file in chunks
+    read_chunks(file, |chunk| DynDigest::update(&mut *hasher, chunk))?;
+
+    // Finalize and format the digest
+    let result = hasher.finalize();
+    Ok(hex::encode(result))
+}
+
+/// Feed `file` to `consume` in `CHUNK_SIZE` pieces
+fn read_chunks(mut file: File, mut consume: impl FnMut(&[u8])) -> Result<()> {
+    let mut buffer = vec![0; CHUNK_SIZE];
     loop {
         match file.read(&mut buffer) {
-            Ok(0) => break,
-            Ok(n) => DynDigest::update(&mut *hasher, &buffer[..n]),
+            Ok(0) => return Ok(()),
+            Ok(n) => consume(&buffer[..n]),
             Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
             Err(e) => return Err(e.into()),
         }
     }
-
-    // Finalize and format the digest
-    let result = hasher.finalize();
-    Ok(hex::encode(result))
 }
 
 pub fn verify_https_security(url: &str) -> Result<()> {
@@ -121,8 +142,8 @@ pub fn is_trusted_domain(url: &str) -> bool {
 }
 
 pub fn audit_log(action: &str, details: &str) {
-    // In production, this would write to a secure audit log
-    log::info!("SECURITY AUDIT: {action} - {details}");
+    // Audit events share the regular log stream; file logs label them AUDIT
+    log::info!(target: crate::logging::AUDIT_TARGET, "SECURITY AUDIT: {action} - {details}");
 }
 
 pub fn verify_file_permissions(path: &Path) -> Result<()> {
@@ -225,6 +246,35 @@ mod tests {
         assert_eq!(md5_checksum, "65a8e27d8879283831b664bd8b7f0ad4");
     }
 
+    #[test]
+    fn test_parse_sha256() {
+        let digest = "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f";
+        assert_eq!(parse_sha256(digest).unwrap(), digest);
+        assert_eq!(
+            parse_sha256(&format!("sha256:{}", digest.to_uppercase())).unwrap(),
+            digest
+        );
+        assert!(parse_sha256(&digest[1..]).is_err());
+        assert!(parse_sha256(&digest.replace('d', "z")).is_err());
+    }
+
+    #[test]
+    fn test_calculate_checksum_spanning_chunks() {
+        let content: Vec<u8> = (0..CHUNK_SIZE * 2 + 123).map(|i| (i % 251) as u8).collect();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+
+        assert_eq!(
+            calculate_checksum(temp_file.path(), ChecksumType::Sha512).unwrap(),
+            hex::encode(Sha512::digest(&content))
+        );
+        assert_eq!(
+            calculate_checksum(temp_file.path(), ChecksumType::Md5).unwrap(),
+            hex::encode(md5::compute(&content).0)
+        );
+    }
+
     #[test]
     fn test_verify_checksum_success() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -244,7 +294,11 @@ mod tests {
 
         let wrong_checksum = "0000000000000000000000000000000000000000000000000000000000000000";
 
-        assert!(verify_checksum(temp_file.path(), wrong_checksum, ChecksumType::Sha256).is_err());
+        // A mismatch carries its own error code, not a generic validation one
+        assert!(matches!(
+            verify_checksum(temp_file.path(), wrong_checksum, ChecksumType::Sha256),
+            Err(KopiError::ChecksumMismatch(_))
+        ));
     }
 
     #[test]
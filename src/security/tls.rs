@@ -0,0 +1,187 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Trust store handling for outbound HTTPS connections.
+//!
+//! The rustls backend always trusts the operating system certificate store.
+//! A PEM bundle configured through `network.ca_bundle` is layered on top of it,
+//! which is what corporate TLS-inspecting proxies need. There is no switch to
+//! drop the system store: the backend cannot do it.
+
+use crate::config::NetworkConfig;
+use crate::error::{KopiError, Result};
+use rustls_pki_types::CertificateDer;
+use rustls_pki_types::pem::PemObject;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static TRUST_STORE: OnceLock<TrustStore> = OnceLock::new();
+
+/// Root certificates applied to every HTTP session kopi creates.
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    ca_bundle: Option<PathBuf>,
+    certificates: Vec<CertificateDer<'static>>,
+}
+
+impl TrustStore {
+    /// Build the trust store described by the `[network]` configuration section.
+    pub fn from_config(config: &NetworkConfig) -> Result<Self> {
+        let certificates = match &config.ca_bundle {
+            Some(path) => load_pem_bundle(path)?,
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            ca_bundle: config.ca_bundle.clone(),
+            certificates,
+        })
+    }
+
+    pub fn ca_bundle(&self) -> Option<&Path> {
+        self.ca_bundle.as_deref()
+    }
+
+    pub fn certificate_count(&self) -> usize {
+        self.certificates.len()
+    }
+
+    /// Human-readable summary of the roots that will be trusted.
+    pub fn describe(&self) -> String {
+        match &self.ca_bundle {
+            Some(path) => format!(
+                "system certificate store + {} certificate(s) from {}",
+                self.certificates.len(),
+                path.display()
+            ),
+            None => "system certificate store".to_string(),
+        }
+    }
+
+    /// Add the configured bundle certificates to an HTTP session.
+    pub fn apply(&self, session: &mut attohttpc::Session) {
+        for certificate in &self.certificates {
+            session.add_root_certificate(certificate.clone());
+        }
+    }
+}
+
+/// Install the process-wide trust store from configuration.
+///
+/// Only the first call takes effect; later calls are ignored so that library
+/// consumers constructing several configs do not fight over the global.
+pub fn install_trust_store(config: &NetworkConfig) -> Result<()> {
+    if TRUST_STORE.get().is_some() {
+        return Ok(());
+    }
+
+    let store = TrustStore::from_config(config)?;
+    log::debug!("TLS trust store: {}", store.describe());
+    let _ = TRUST_STORE.set(store);
+    Ok(())
+}
+
+/// Apply the installed trust store, if any, to an HTTP session.
+pub fn configure_session(session: &mut attohttpc::Session) {
+    if let Some(store) = TRUST_STORE.get() {
+        store.apply(session);
+    }
+}
+
+/// Read every certificate from a PEM bundle.
+pub fn load_pem_bundle(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let invalid = |reason: String| {
+        KopiError::InvalidConfig(format!(
+            "Failed to load CA bundle {}: {reason}",
+            path.display()
+        ))
+    };
+
+    let certificates = CertificateDer::pem_file_iter(path)
+        .map_err(|e| invalid(e.to_string()))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| invalid(e.to_string()))?;
+
+    if certificates.is_empty() {
+        return Err(invalid("no certificates found".to_string()));
+    }
+
+    Ok(certificates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    // Self-signed test CA certificate.
+    const TEST_CERT: &str = "-----BEGIN CERTIFICATE-----
+MIIBgjCCASmgAwIBAgIUGBJ0kVSNa6BBZnhlCFeSaZY9R44wCgYIKoZIzj0EAwIw
+FzEVMBMGA1UEAwwMa29waS10ZXN0LWNhMB4XDTI2MTAxNjEzMjEwM1oXDTM2MTAx
+MzEzMjEwM1owFzEVMBMGA1UEAwwMa29waS10ZXN0LWNhMFkwEwYHKoZIzj0CAQYI
+KoZIzj0DAQcDQgAEXlrJtXD2zpmahlWJKw2tL2I215S77hEHa6XmErriWY3LljRb
+DuuukapjUwqfsIlvp8F86M8Dn8BF0SSezIECoqNTMFEwHQYDVR0OBBYEFOLGxt/N
+ueBbRdM8TXKDTUCpgmeDMB8GA1UdIwQYMBaAFOLGxt/NueBbRdM8TXKDTUCpgmeD
+MA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDRwAwRAIgVA+MIQR716V1H/ck
+spvvs+ekQDvujBFHruUtiTFd6UYCIHTaugb7BPR6nWxj+n8unn59/o0JqhAqg+hL
+lgqJTVnN
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_load_pem_bundle_reads_all_certificates() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ca.pem");
+        fs::write(&path, format!("{TEST_CERT}{TEST_CERT}")).unwrap();
+
+        let certificates = load_pem_bundle(&path).unwrap();
+        assert_eq!(certificates.len(), 2);
+    }
+
+    #[test]
+    fn test_load_pem_bundle_rejects_empty_or_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let empty = temp_dir.path().join("empty.pem");
+        fs::write(&empty, "not a certificate\n").unwrap();
+
+        assert!(matches!(
+            load_pem_bundle(&empty),
+            Err(KopiError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            load_pem_bundle(&temp_dir.path().join("missing.pem")),
+            Err(KopiError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_trust_store_from_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ca.pem");
+        fs::write(&path, TEST_CERT).unwrap();
+
+        let store = TrustStore::from_config(&NetworkConfig {
+            ca_bundle: Some(path.clone()),
+        })
+        .unwrap();
+        assert_eq!(store.certificate_count(), 1);
+        assert_eq!(store.ca_bundle(), Some(path.as_path()));
+        assert!(store.describe().contains("1 certificate(s)"));
+
+        let default_store = TrustStore::from_config(&NetworkConfig::default()).unwrap();
+        assert_eq!(default_store.certificate_count(), 0);
+        assert_eq!(default_store.describe(), "system certificate store");
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::path::Path;
 
 use log::debug;
@@ -20,6 +21,7 @@ use crate::error::Result;
 use crate::paths::install;
 use crate::platform::file_ops::is_executable;
 use crate::platform::with_executable_extension;
+use crate::storage::InstalledJdk;
 
 use super::tools::ToolRegistry;
 
@@ -96,6 +98,24 @@ pub fn discover_jdk_tools(jdk_path: &Path) -> Result<Vec<String>> {
     Ok(discovered_tools)
 }
 
+/// Collects every tool provided by at least one of the given JDK installations.
+///
+/// Combines standard tool discovery with distribution-specific tools so callers
+/// can decide whether a shim still has a JDK behind it.
+pub fn discover_installed_tools(jdks: &[InstalledJdk]) -> Result<HashSet<String>> {
+    let mut tools = HashSet::new();
+
+    for jdk in jdks {
+        tools.extend(discover_jdk_tools(&jdk.path)?);
+        tools.extend(discover_distribution_tools(
+            &jdk.path,
+            Some(&jdk.distribution),
+        )?);
+    }
+
+    Ok(tools)
+}
+
 /// Discovers distribution-specific tools that may not be in the standard JDK.
 ///
 /// Some distributions include additional tools:
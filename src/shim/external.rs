@@ -0,0 +1,184 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Executables outside the JDK that run under the resolved JDK.
+//!
+//! `kopi shim add spring --exec /usr/local/bin/spring` records the target
+//! here. When the shim runs, it resolves the project JDK as usual, points
+//! `JAVA_HOME` and `PATH` at it, and then hands off to the recorded executable.
+
+use crate::error::{KopiError, Result};
+use crate::paths::shims::{external_tools_file, shims_root};
+use crate::platform::file_ops::check_executable_permissions;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Wrapped executables by tool name
+pub type ExternalTools = BTreeMap<String, PathBuf>;
+
+/// All registered external tools; a missing or corrupt registry is empty
+pub fn load_external_tools(kopi_home: &Path) -> ExternalTools {
+    fs::read_to_string(external_tools_file(kopi_home))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Executable wrapped by the shim named `tool_name`, if it is an external tool
+pub fn find_external_tool(kopi_home: &Path, tool_name: &str) -> Option<PathBuf> {
+    load_external_tools(kopi_home).remove(tool_name)
+}
+
+/// Record that the shim `tool_name` should delegate to `executable`.
+///
+/// The executable is validated and stored as an absolute path.
+pub fn register_external_tool(
+    kopi_home: &Path,
+    tool_name: &str,
+    executable: &Path,
+) -> Result<PathBuf> {
+    let executable = validate_executable(kopi_home, executable)?;
+    let mut tools = load_external_tools(kopi_home);
+    tools.insert(tool_name.to_string(), executable.clone());
+    save_external_tools(kopi_home, &tools)?;
+    Ok(executable)
+}
+
+/// Forget an external tool; returns whether it was registered
+pub fn unregister_external_tool(kopi_home: &Path, tool_name: &str) -> Result<bool> {
+    let mut tools = load_external_tools(kopi_home);
+    if tools.remove(tool_name).is_none() {
+        return Ok(false);
+    }
+    save_external_tools(kopi_home, &tools)?;
+    Ok(true)
+}
+
+/// First executable named `tool_name` on `PATH` that is not a kopi shim
+pub fn find_on_path(kopi_home: &Path, tool_name: &str) -> Result<PathBuf> {
+    let shims_dir = canonical_or_self(&shims_root(kopi_home));
+    which::which_all(tool_name)
+        .ok()
+        .into_iter()
+        .flatten()
+        .find(|candidate| {
+            candidate
+                .parent()
+                .is_none_or(|dir| canonical_or_self(dir) != shims_dir)
+        })
+        .ok_or_else(|| {
+            KopiError::NotFound(format!(
+                "No '{tool_name}' executable found on PATH outside the kopi shims directory"
+            ))
+        })
+}
+
+/// Home of the `java` that runs when kopi steps aside: the first one on
+/// `PATH` outside the shims directory, with symlinks such as
+/// `/usr/bin/java` followed to the JDK they belong to
+pub fn system_java_home(kopi_home: &Path) -> Option<PathBuf> {
+    let java = find_on_path(kopi_home, "java").ok()?;
+    let java = fs::canonicalize(&java).unwrap_or(java);
+    java.parent()?.parent().map(Path::to_path_buf)
+}
+
+fn validate_executable(kopi_home: &Path, executable: &Path) -> Result<PathBuf> {
+    let resolved = fs::canonicalize(executable).map_err(|e| {
+        KopiError::ValidationError(format!(
+            "Cannot use '{}' as a shim target: {e}",
+            executable.display()
+        ))
+    })?;
+
+    // A target inside the shims directory would re-enter the shim forever
+    if resolved.starts_with(canonical_or_self(&shims_root(kopi_home))) {
+        return Err(KopiError::ValidationError(format!(
+            "'{}' is a kopi shim and cannot be wrapped by another shim",
+            executable.display()
+        )));
+    }
+
+    check_executable_permissions(&resolved)?;
+    Ok(resolved)
+}
+
+fn save_external_tools(kopi_home: &Path, tools: &ExternalTools) -> Result<()> {
+    let path = external_tools_file(kopi_home);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(tools)?)?;
+    Ok(())
+}
+
+fn canonical_or_self(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::shims::ensure_shims_root;
+    use tempfile::TempDir;
+
+    fn make_executable(path: &Path) {
+        fs::write(path, "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_register_and_unregister() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = temp_dir.path().join(if cfg!(windows) {
+            "spring.exe"
+        } else {
+            "spring"
+        });
+        make_executable(&tool);
+
+        let stored = register_external_tool(temp_dir.path(), "spring", &tool).unwrap();
+        assert_eq!(stored, fs::canonicalize(&tool).unwrap());
+        assert_eq!(find_external_tool(temp_dir.path(), "spring"), Some(stored));
+
+        assert!(unregister_external_tool(temp_dir.path(), "spring").unwrap());
+        assert!(!unregister_external_tool(temp_dir.path(), "spring").unwrap());
+        assert_eq!(find_external_tool(temp_dir.path(), "spring"), None);
+    }
+
+    #[test]
+    fn test_register_rejects_missing_executable() {
+        let temp_dir = TempDir::new().unwrap();
+        let result =
+            register_external_tool(temp_dir.path(), "spring", &temp_dir.path().join("missing"));
+
+        assert!(matches!(result, Err(KopiError::ValidationError(_))));
+        assert!(load_external_tools(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_register_rejects_shim_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let shims = ensure_shims_root(temp_dir.path()).unwrap();
+        let shim = shims.join("java");
+        make_executable(&shim);
+
+        let result = register_external_tool(temp_dir.path(), "java2", &shim);
+        assert!(matches!(result, Err(KopiError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Record of project version files recently resolved by shims.
+//!
+//! Uninstall safety checks consult this list so that a JDK pinned by a project
+//! outside the current directory is not removed by accident.
+
+use crate::paths::cache::shim_history_file;
+use crate::platform::file_ops;
+use log::debug;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Maximum number of distinct version files remembered
+const MAX_ENTRIES: usize = 100;
+
+/// Remember that a shim resolved its version from `version_file`.
+///
+/// Failures are logged and ignored; history must never break tool execution.
+pub fn record_project_resolution(kopi_home: &Path, version_file: &Path) {
+    if let Err(e) = try_record(kopi_home, version_file) {
+        debug!(
+            "Failed to record shim resolution for {}: {e}",
+            version_file.display()
+        );
+    }
+}
+
+fn try_record(kopi_home: &Path, version_file: &Path) -> std::io::Result<()> {
+    let history_path = shim_history_file(kopi_home);
+    // An unreadable file is left alone rather than replaced with one entry
+    let mut entries = try_read_entries(&history_path)?;
+
+    // Fast path: already the most recent entry, nothing to write
+    if entries.last().is_some_and(|entry| entry == version_file) {
+        return Ok(());
+    }
+
+    if let Some(parent) = history_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let known = entries.iter().any(|entry| entry == version_file);
+    if !known && entries.len() < MAX_ENTRIES {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&history_path)?;
+        return writeln!(file, "{}", version_file.display());
+    }
+
+    // Move a re-used entry to the end so that eviction drops the projects
+    // that have gone unused the longest
+    entries.retain(|entry| entry != version_file);
+    entries.push(version_file.to_path_buf());
+    let keep = entries.split_off(entries.len().saturating_sub(MAX_ENTRIES));
+    let content: String = keep
+        .iter()
+        .map(|entry| format!("{}\n", entry.display()))
+        .collect();
+    file_ops::write_atomic(&history_path, content)
+}
+
+/// Version files recently used by shims, least recently used first
+pub fn recent_project_files(kopi_home: &Path) -> Vec<PathBuf> {
+    try_read_entries(&shim_history_file(kopi_home)).unwrap_or_default()
+}
+
+/// Entries in `history_path`; a missing file has none
+fn try_read_entries(history_path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    match fs::read_to_string(history_path) {
+        Ok(content) => Ok(content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_deduplicates_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("a/.kopi-version");
+        let second = temp_dir.path().join("b/.java-version");
+
+        record_project_resolution(temp_dir.path(), &first);
+        record_project_resolution(temp_dir.path(), &second);
+        record_project_resolution(temp_dir.path(), &first);
+
+        assert_eq!(recent_project_files(temp_dir.path()), vec![second, first]);
+    }
+
+    #[test]
+    fn test_record_keeps_most_recent_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        for index in 0..=MAX_ENTRIES {
+            let path = temp_dir.path().join(format!("p{index}/.kopi-version"));
+            record_project_resolution(temp_dir.path(), &path);
+        }
+
+        let entries = recent_project_files(temp_dir.path());
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0], temp_dir.path().join("p1/.kopi-version"));
+    }
+
+    #[test]
+    fn test_record_evicts_least_recently_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let daily = temp_dir.path().join("daily/.kopi-version");
+        record_project_resolution(temp_dir.path(), &daily);
+        for index in 0..MAX_ENTRIES {
+            let path = temp_dir.path().join(format!("p{index}/.kopi-version"));
+            record_project_resolution(temp_dir.path(), &path);
+            // The daily project is used between every other resolution
+            record_project_resolution(temp_dir.path(), &daily);
+        }
+
+        let entries = recent_project_files(temp_dir.path());
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries.last(), Some(&daily));
+        assert_eq!(entries[0], temp_dir.path().join("p1/.kopi-version"));
+    }
+}
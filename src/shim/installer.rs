@@ -12,16 +12,41 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::config::{ShimWrapper, ShimsConfig};
 use crate::error::{KopiError, Result};
+use crate::models::package::ChecksumType;
 use crate::paths::shims;
 use crate::platform::{self, shim_binary_name};
+use crate::security::calculate_checksum;
+use crate::shim::tools::default_shim_tools;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Problem detected while inspecting an installed shim
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShimIssue {
+    /// The shim is dangling or is not a kopi-shim launcher
+    Broken(String),
+    /// The shim launches a kopi-shim binary other than the current one
+    Outdated,
+    /// No installed JDK provides the tool anymore
+    Orphaned,
+}
+
+/// Inspection result for a single shim
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShimInspection {
+    pub tool: String,
+    pub issue: Option<ShimIssue>,
+}
+
 /// Installs, removes, and verifies shims for JDK tools
 pub struct ShimInstaller {
     shims_dir: PathBuf,
     kopi_bin_path: PathBuf,
+    /// Script wrappers written next to each shim; always empty off Windows
+    wrappers: Vec<ShimWrapper>,
 }
 
 impl ShimInstaller {
@@ -30,9 +55,23 @@ impl ShimInstaller {
         Self {
             shims_dir: shims::shims_root(kopi_home),
             kopi_bin_path: std::env::current_exe().unwrap_or_else(|_| PathBuf::from("kopi")),
+            wrappers: if cfg!(windows) {
+                ShimsConfig::default().windows_wrappers
+            } else {
+                Vec::new()
+            },
         }
     }
 
+    /// Write these script wrappers next to each shim on Windows
+    /// (`shims.windows_wrappers`); ignored on other platforms
+    pub fn with_wrappers(mut self, wrappers: &[ShimWrapper]) -> Self {
+        if cfg!(windows) {
+            self.wrappers = wrappers.to_vec();
+        }
+        self
+    }
+
     /// Get the shims directory path
     pub fn shims_dir(&self) -> &Path {
         &self.shims_dir
@@ -70,13 +109,15 @@ impl ShimInstaller {
     pub fn remove_shim(&self, tool_name: &str) -> Result<()> {
         let shim_path = self.get_shim_path(tool_name);
 
-        if !shim_path.exists() {
+        // Use symlink_metadata so dangling symlinks can still be removed
+        if fs::symlink_metadata(&shim_path).is_err() {
             return Err(KopiError::SystemError(format!(
                 "Shim for '{tool_name}' does not exist"
             )));
         }
 
         fs::remove_file(&shim_path)?;
+        self.remove_wrappers(tool_name)?;
         log::info!("Removed shim for '{tool_name}' from {shim_path:?}");
         Ok(())
     }
@@ -94,6 +135,7 @@ impl ShimInstaller {
             let path = entry.path();
 
             if path.is_file()
+                && !is_wrapper(&path)
                 && let Some(name) = path.file_stem()
                 && let Some(name_str) = name.to_str()
             {
@@ -118,6 +160,7 @@ impl ShimInstaller {
             let path = entry.path();
 
             if path.is_file()
+                && !is_wrapper(&path)
                 && let Some(name) = path.file_stem()
                 && let Some(name_str) = name.to_str()
                 && let Err(e) = platform::shim::verify_shim(&path)
@@ -129,12 +172,93 @@ impl ShimInstaller {
         Ok(broken_shims)
     }
 
+    /// Inspect every shim against the current kopi-shim binary and the tools
+    /// provided by installed JDKs.
+    ///
+    /// Default shims are never reported as orphaned because they are needed for
+    /// auto-installation even when no JDK is present.
+    pub fn inspect_shims(&self, provided_tools: &HashSet<String>) -> Result<Vec<ShimInspection>> {
+        if !self.shims_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let launcher = self.find_kopi_shim_binary()?;
+        let mut launcher_digest = None;
+        let default_tools = default_shim_tools();
+        let mut inspections = Vec::new();
+
+        for entry in fs::read_dir(&self.shims_dir)? {
+            let path = entry?.path();
+
+            // symlink_metadata keeps dangling symlinks visible
+            if fs::symlink_metadata(&path)?.is_dir() || is_wrapper(&path) {
+                continue;
+            }
+
+            let Some(tool) = path.file_stem().and_then(|name| name.to_str()) else {
+                continue;
+            };
+
+            let issue = if !provided_tools.contains(tool) && !default_tools.contains(&tool) {
+                Some(ShimIssue::Orphaned)
+            } else if let Err(e) = platform::shim::verify_shim(&path) {
+                Some(ShimIssue::Broken(e.to_string()))
+            } else if !self.matches_launcher(&path, &launcher, &mut launcher_digest)? {
+                Some(ShimIssue::Outdated)
+            } else {
+                self.missing_wrapper(tool).map(|wrapper| {
+                    ShimIssue::Broken(format!("missing {} wrapper", wrapper.file_name(tool)))
+                })
+            };
+
+            inspections.push(ShimInspection {
+                tool: tool.to_string(),
+                issue,
+            });
+        }
+
+        inspections.sort_by(|a, b| a.tool.cmp(&b.tool));
+        Ok(inspections)
+    }
+
+    /// Check whether a shim launches the given kopi-shim binary.
+    ///
+    /// Symlinked shims are compared by resolved target; copied shims (Windows)
+    /// are compared by SHA-256 digest.
+    fn matches_launcher(
+        &self,
+        shim_path: &Path,
+        launcher: &Path,
+        launcher_digest: &mut Option<String>,
+    ) -> Result<bool> {
+        if platform::symlink::is_symlink(shim_path)? {
+            let target = fs::read_link(shim_path)?;
+            let target = if target.is_relative() {
+                self.shims_dir.join(target)
+            } else {
+                target
+            };
+            return Ok(fs::canonicalize(&target)? == fs::canonicalize(launcher)?);
+        }
+
+        let expected = match launcher_digest {
+            Some(digest) => digest.clone(),
+            None => {
+                let digest = calculate_checksum(launcher, ChecksumType::Sha256)?;
+                *launcher_digest = Some(digest.clone());
+                digest
+            }
+        };
+
+        Ok(calculate_checksum(shim_path, ChecksumType::Sha256)? == expected)
+    }
+
     /// Repair a broken shim
     pub fn repair_shim(&self, tool_name: &str) -> Result<()> {
         let shim_path = self.get_shim_path(tool_name);
 
-        // Remove the broken shim
-        if shim_path.exists() {
+        // Remove the broken shim, including dangling symlinks
+        if fs::symlink_metadata(&shim_path).is_ok() {
             fs::remove_file(&shim_path)?;
         }
 
@@ -154,9 +278,12 @@ impl ShimInstaller {
         for tool in tools {
             let shim_path = self.get_shim_path(tool);
 
-            // Skip if shim already exists
+            // Skip if shim already exists, adding wrappers it predates
             if shim_path.exists() {
                 log::debug!("Shim for '{tool}' already exists");
+                if self.missing_wrapper(tool).is_some() {
+                    self.write_wrappers(tool)?;
+                }
                 continue;
             }
 
@@ -180,8 +307,41 @@ impl ShimInstaller {
         self.shims_dir.join(shim_name)
     }
 
+    /// First configured wrapper of `tool` that is missing from the shims
+    /// directory
+    fn missing_wrapper(&self, tool: &str) -> Option<ShimWrapper> {
+        self.wrappers
+            .iter()
+            .copied()
+            .find(|wrapper| !self.shims_dir.join(wrapper.file_name(tool)).is_file())
+    }
+
+    /// Write the configured script wrappers for `tool`, replacing old ones
+    fn write_wrappers(&self, tool: &str) -> Result<()> {
+        for wrapper in &self.wrappers {
+            let path = self.shims_dir.join(wrapper.file_name(tool));
+            fs::write(&path, wrapper.script(tool))?;
+            log::debug!("Wrote shim wrapper {path:?}");
+        }
+        Ok(())
+    }
+
+    /// Remove every script wrapper of `tool`, including kinds no longer
+    /// configured
+    fn remove_wrappers(&self, tool: &str) -> Result<()> {
+        for wrapper in [ShimWrapper::Cmd, ShimWrapper::Ps1] {
+            let path = self.shims_dir.join(wrapper.file_name(tool));
+            match fs::remove_file(&path) {
+                Ok(()) => log::debug!("Removed shim wrapper {path:?}"),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
     /// Internal method to create a shim with platform-specific implementation
-    fn create_shim_internal(&self, _tool_name: &str, shim_path: &Path) -> Result<()> {
+    fn create_shim_internal(&self, tool_name: &str, shim_path: &Path) -> Result<()> {
         // Find the kopi-shim binary
         let kopi_shim_path = self.find_kopi_shim_binary()?;
 
@@ -199,7 +359,7 @@ impl ShimInstaller {
             platform::symlink::create_symlink(&kopi_shim_path, shim_path)?;
         }
 
-        Ok(())
+        self.write_wrappers(tool_name)
     }
 
     fn find_kopi_shim_binary(&self) -> Result<PathBuf> {
@@ -220,6 +380,14 @@ impl ShimInstaller {
     }
 }
 
+/// Whether `path` is a script wrapper rather than a shim
+fn is_wrapper(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(ShimWrapper::from_file_name)
+        .is_some()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,6 +452,106 @@ mod tests {
         assert!(matches!(result, Err(KopiError::SystemError(_))));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_inspect_shims_detects_issues() {
+        let temp_dir = TempDir::new().unwrap();
+        let bin_dir = temp_dir.path().join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let launcher = bin_dir.join(shim_binary_name());
+        fs::write(&launcher, b"current").unwrap();
+        let old_launcher = temp_dir.path().join("old").join(shim_binary_name());
+        fs::create_dir_all(old_launcher.parent().unwrap()).unwrap();
+        fs::write(&old_launcher, b"old").unwrap();
+
+        let installer = ShimInstaller {
+            shims_dir: shims::shims_root(temp_dir.path()),
+            kopi_bin_path: bin_dir.join("kopi"),
+            wrappers: Vec::new(),
+        };
+        installer.init_shims_directory().unwrap();
+        let shims_dir = installer.shims_dir().to_path_buf();
+
+        platform::symlink::create_symlink(&launcher, &shims_dir.join("java")).unwrap();
+        platform::symlink::create_symlink(&old_launcher, &shims_dir.join("javac")).unwrap();
+        platform::symlink::create_symlink(&launcher, &shims_dir.join("jconsole")).unwrap();
+        platform::symlink::create_symlink(
+            &temp_dir.path().join("missing").join("kopi-shim"),
+            &shims_dir.join("jar"),
+        )
+        .unwrap();
+
+        let provided: HashSet<String> = ["java", "javac", "jar"]
+            .iter()
+            .map(|tool| tool.to_string())
+            .collect();
+        let inspections = installer.inspect_shims(&provided).unwrap();
+
+        let issue_for = |tool: &str| {
+            inspections
+                .iter()
+                .find(|inspection| inspection.tool == tool)
+                .unwrap()
+                .issue
+                .clone()
+        };
+        assert_eq!(issue_for("java"), None);
+        assert_eq!(issue_for("javac"), Some(ShimIssue::Outdated));
+        assert_eq!(issue_for("jconsole"), Some(ShimIssue::Orphaned));
+        assert!(matches!(issue_for("jar"), Some(ShimIssue::Broken(_))));
+
+        installer.repair_shim("javac").unwrap();
+        installer.repair_shim("jar").unwrap();
+        installer.remove_shim("jconsole").unwrap();
+        let inspections = installer.inspect_shims(&provided).unwrap();
+        assert_eq!(inspections.len(), 3);
+        assert!(
+            inspections
+                .iter()
+                .all(|inspection| inspection.issue.is_none())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wrappers_follow_their_shim() {
+        let temp_dir = TempDir::new().unwrap();
+        let bin_dir = temp_dir.path().join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        fs::write(bin_dir.join(shim_binary_name()), b"current").unwrap();
+
+        let installer = ShimInstaller {
+            shims_dir: shims::shims_root(temp_dir.path()),
+            kopi_bin_path: bin_dir.join("kopi"),
+            wrappers: vec![ShimWrapper::Cmd, ShimWrapper::Ps1],
+        };
+        let shims_dir = installer.shims_dir().to_path_buf();
+        installer.create_shim("java").unwrap();
+        assert!(shims_dir.join("java.cmd").is_file());
+        assert!(shims_dir.join("java.ps1").is_file());
+        assert_eq!(installer.list_shims().unwrap(), ["java"]);
+        assert!(installer.verify_shims().unwrap().is_empty());
+
+        // A shim created before wrappers existed is reported and repaired
+        fs::remove_file(shims_dir.join("java.ps1")).unwrap();
+        let provided: HashSet<String> = HashSet::from(["java".to_string()]);
+        let inspections = installer.inspect_shims(&provided).unwrap();
+        assert_eq!(
+            inspections[0].issue,
+            Some(ShimIssue::Broken("missing java.ps1 wrapper".to_string()))
+        );
+        assert!(
+            installer
+                .create_missing_shims(&["java".to_string()])
+                .unwrap()
+                .is_empty()
+        );
+        assert!(shims_dir.join("java.ps1").is_file());
+
+        installer.remove_shim("java").unwrap();
+        assert!(fs::read_dir(&shims_dir).unwrap().next().is_none());
+    }
+
     // Note: More comprehensive tests for create_shim, verify_shims, etc.
     // would require mocking the kopi-shim binary existence and filesystem
     // operations, which will be done in the integration tests
@@ -0,0 +1,165 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-version `JAVA_HOME` variables such as `JAVA_HOME_17_X64`.
+//!
+//! CI runners and toolchain-aware builds look for one variable per installed
+//! major version. The names come from `env.java_home_template`; when several
+//! installed JDKs render to the same name, the default distribution wins and
+//! then the newest version.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::platform::get_current_architecture;
+use crate::storage::{InstalledJdk, JdkRepository};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A variable name and the `JAVA_HOME` it holds, ordered by name
+pub fn java_home_variables(config: &KopiConfig) -> Result<Vec<(String, PathBuf)>> {
+    let template = &config.env.java_home_template;
+    let arch = architecture_label(&get_current_architecture());
+    let repository = JdkRepository::new(config);
+
+    let mut chosen: BTreeMap<String, InstalledJdk> = BTreeMap::new();
+    for jdk in repository.list_installed_jdks()? {
+        let name = render_name(template, &jdk, &arch)?;
+        let replace = chosen.get(&name).is_none_or(|current| {
+            preference(&jdk, current, &config.default_distribution) == Ordering::Greater
+        });
+        if replace {
+            chosen.insert(name, jdk);
+        }
+    }
+
+    Ok(chosen
+        .into_iter()
+        .map(|(name, jdk)| (name, jdk.resolve_java_home()))
+        .collect())
+}
+
+/// Expand `{major}`, `{arch}`, `{ARCH}` and `{distribution}` in `template`
+fn render_name(template: &str, jdk: &InstalledJdk, arch: &str) -> Result<String> {
+    let distribution: String = jdk
+        .distribution
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let name = template
+        .replace("{major}", &jdk.version.major().to_string())
+        .replace("{arch}", &arch.to_ascii_lowercase())
+        .replace("{ARCH}", &arch.to_ascii_uppercase())
+        .replace("{distribution}", &distribution);
+
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(KopiError::InvalidConfig(format!(
+            "env.java_home_template '{template}' yields '{name}', which is not a valid \
+             environment variable name"
+        )));
+    }
+    Ok(name)
+}
+
+/// Architecture names as CI runners spell them, e.g. `ARM64` rather than `aarch64`
+fn architecture_label(arch: &str) -> String {
+    match arch {
+        "aarch64" => "arm64".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// How `candidate` compares to `current` as the JDK behind one variable
+fn preference(candidate: &InstalledJdk, current: &InstalledJdk, default: &str) -> Ordering {
+    let is_default = |jdk: &InstalledJdk| jdk.distribution == default;
+    is_default(candidate)
+        .cmp(&is_default(current))
+        .then_with(|| candidate.version.cmp(&current.version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn jdk(distribution: &str, version: &str) -> InstalledJdk {
+        InstalledJdk::new(
+            distribution.to_string(),
+            Version::from_str(version).unwrap(),
+            PathBuf::from(format!("/jdks/{distribution}-{version}")),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_render_name() {
+        let temurin = jdk("temurin", "17.0.13");
+        assert_eq!(
+            render_name("JAVA_HOME_{major}_{ARCH}", &temurin, "x64").unwrap(),
+            "JAVA_HOME_17_X64"
+        );
+        assert_eq!(
+            render_name(
+                "{distribution}_{major}_{arch}",
+                &jdk("sap-machine", "21"),
+                "arm64"
+            )
+            .unwrap(),
+            "sap_machine_21_arm64"
+        );
+        assert!(matches!(
+            render_name("{major}_HOME", &temurin, "x64"),
+            Err(KopiError::InvalidConfig(_))
+        ));
+        assert!(render_name("JAVA HOME {major}", &temurin, "x64").is_err());
+    }
+
+    #[test]
+    fn test_default_distribution_then_newest_wins() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let jdks_dir = config.jdks_dir().unwrap();
+        for dir in [
+            "corretto-21.0.6.7.1",
+            "temurin-21.0.5",
+            "corretto-17.0.13.11.1",
+            "zulu-17.0.14",
+        ] {
+            std::fs::create_dir_all(jdks_dir.join(dir)).unwrap();
+        }
+
+        let arch = architecture_label(&get_current_architecture()).to_ascii_uppercase();
+        let variables = java_home_variables(&config).unwrap();
+        assert_eq!(
+            variables,
+            [
+                (
+                    format!("JAVA_HOME_17_{arch}"),
+                    jdks_dir.join("zulu-17.0.14")
+                ),
+                (
+                    format!("JAVA_HOME_21_{arch}"),
+                    jdks_dir.join("temurin-21.0.5")
+                ),
+            ]
+        );
+    }
+}
@@ -12,24 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::config::new_kopi_config;
+use crate::config::{KopiConfig, new_kopi_config};
 use crate::error::{KopiError, Result};
 use crate::models::distribution::Distribution;
 use crate::storage::{InstalledJdk, JdkRepository};
-use crate::version::VersionRequest;
+use crate::version::file::VersionTrack;
+use crate::version::{VersionRequest, build_file, matching};
 use std::env;
 use std::ffi::OsString;
 use std::io::IsTerminal;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 pub mod discovery;
+pub mod external;
+pub mod history;
 pub mod installer;
+pub mod java_homes;
 pub mod security;
+pub mod shadowing;
 pub mod tools;
+pub mod usage;
+pub mod wrappers;
 use crate::error::format_error_with_color;
 use crate::installation::AutoInstaller;
-use crate::version::resolver::VersionResolver;
+use crate::version::resolver::{VersionResolver, VersionSource};
 use security::SecurityValidator;
 
 /// Run the shim with the provided arguments
@@ -45,36 +52,61 @@ pub fn run_shim() -> Result<()> {
 
     // Load configuration once
     let config = new_kopi_config()?;
+    crate::crash::install_panic_hook(&config);
+    if let Err(err) = crate::logging::enable_file_logging(&config.logging, config.kopi_home(), None)
+    {
+        log::warn!("Failed to open log file: {err}");
+    }
     let security_validator = SecurityValidator::new(&config);
 
     // Get tool name from argv[0]
     let tool_name = get_tool_name()?;
     log::debug!("Shim invoked as: {tool_name}");
 
-    // Validate tool name
-    security_validator.validate_tool(&tool_name)?;
+    // External tools run under the JDK rather than from it, so they are not in the registry
+    let external_tool = external::find_external_tool(config.kopi_home(), &tool_name);
+    if external_tool.is_none() {
+        security_validator.validate_tool(&tool_name)?;
+    }
 
     // Resolve JDK version
     let resolver = VersionResolver::new(&config);
-    let (version_request, version_source) = match resolver.resolve_version() {
+    let (version_request, version_source) = match resolver.resolve_tool_version(&tool_name) {
         Ok((req, source)) => (req, source),
         Err(e @ KopiError::NoLocalVersion { .. }) => {
             eprintln!(
                 "{}",
                 format_error_with_color(&e, std::io::stderr().is_terminal())
             );
+            if let Some(note) = build_file_note(None) {
+                eprintln!("{note}");
+            }
             std::process::exit(crate::error::get_exit_code(&e));
         }
         Err(e) => return Err(e),
     };
     log::debug!("Resolved version: {version_request:?} from {version_source:?}");
 
+    if let VersionSource::ProjectFile(path) | VersionSource::ToolOverride(path) = &version_source {
+        history::record_project_resolution(config.kopi_home(), path);
+    }
+
     // Validate version string
     security_validator.validate_version(&version_request.version_pattern)?;
     if let Some(dist) = &version_request.distribution {
         security_validator.validate_version(dist)?;
     }
 
+    // The system JDK is whatever the shell would run without kopi on PATH
+    if version_request.is_system() {
+        let tool_path = match external_tool {
+            Some(executable) => executable,
+            None => external::find_on_path(config.kopi_home(), &tool_name)?,
+        };
+        log::debug!("Tool path (system): {tool_path:?}");
+        return exec_tool(&security_validator, &tool_path, start);
+    }
+
     // Find JDK installation
     let repository = JdkRepository::new(&config);
     let installed_jdk = match find_jdk_installation(&repository, &version_request) {
@@ -92,12 +124,19 @@ pub fn run_shim() -> Result<()> {
                 *enabled = auto_install_enabled;
 
                 if auto_install_enabled {
+                    // Flag a pin that disagrees with the build before installing it
+                    let requested_major =
+                        crate::version::Version::from_str(&version_request.version_pattern)
+                            .ok()
+                            .map(|version| version.major());
+                    if let Some(note) =
+                        requested_major.and_then(|major| build_file_note(Some(major)))
+                    {
+                        eprintln!("{note}");
+                    }
+
                     // Check if we should prompt the user
-                    let version_spec = if let Some(dist) = &version_request.distribution {
-                        format!("{}@{}", dist, version_request.version_pattern)
-                    } else {
-                        version_request.version_pattern.clone()
-                    };
+                    let version_spec = version_request.to_string();
 
                     let should_install = match auto_installer.prompt_user(&version_spec) {
                         Ok(approved) => approved,
@@ -143,8 +182,10 @@ pub fn run_shim() -> Result<()> {
                                 }
                             }
                             Err(e) => {
-                                // Check if it's specifically a kopi not found error
-                                if let KopiError::KopiNotFound { .. } = &e {
+                                // Missing kopi binaries and policy violations are reported as is
+                                if let KopiError::KopiNotFound { .. }
+                                | KopiError::PolicyViolation(_) = &e
+                                {
                                     eprintln!(
                                         "{}",
                                         format_error_with_color(
@@ -211,23 +252,62 @@ pub fn run_shim() -> Result<()> {
         installed_jdk.path
     );
 
+    usage::record_jdk_use(config.kopi_home(), &installed_jdk.path);
+    if config.env.shim_java_homes {
+        export_java_home_variables(&config);
+    }
+
     // Build tool path
-    let tool_path = build_tool_path(&installed_jdk, &tool_name)?;
+    let tool_path = match external_tool {
+        Some(executable) => {
+            export_jdk_environment(&installed_jdk)?;
+            executable
+        }
+        None => {
+            let tool_path = build_tool_path(&installed_jdk, &tool_name)?;
+            security_validator.validate_path(&tool_path)?;
+            tool_path
+        }
+    };
     log::debug!("Tool path: {tool_path:?}");
 
+    exec_tool(&security_validator, &tool_path, start)
+}
+
+/// Mention the Java version the project's build file declares when nothing
+/// is pinned (`pinned_major` is `None`) or the pin asks for another release
+fn build_file_note(pinned_major: Option<u32>) -> Option<String> {
+    let current_dir = env::current_dir().ok()?;
+    let detected = build_file::find_java_version(&current_dir).ok()??;
+    match pinned_major {
+        None => Some(format!(
+            "Note: the build declares {detected}; pin it with 'kopi local --auto'"
+        )),
+        Some(major) if major != detected.major => Some(format!(
+            "Note: the build declares {detected}, but Java {major} is pinned"
+        )),
+        Some(_) => None,
+    }
+}
+
+/// Replace the shim with `tool_path`, passing the shim's arguments through
+fn exec_tool(
+    security_validator: &SecurityValidator,
+    tool_path: &Path,
+    start: std::time::Instant,
+) -> Result<()> {
     // Collect arguments (skip argv[0])
     let args: Vec<OsString> = env::args_os().skip(1).collect();
 
-    // Validate tool path and permissions before execution
-    security_validator.validate_path(&tool_path)?;
-    security_validator.check_permissions(&tool_path)?;
+    // Validate permissions before execution
+    security_validator.check_permissions(tool_path)?;
 
     // Log performance
     let elapsed = start.elapsed();
     log::debug!("Shim resolution completed in {elapsed:?}");
 
     // Execute the tool
-    let err = crate::platform::process::exec_replace(&tool_path, args);
+    let err = crate::platform::process::exec_replace(tool_path, args);
 
     // exec_replace only returns on error
     Err(KopiError::SystemError(format!(
@@ -235,6 +315,36 @@ pub fn run_shim() -> Result<()> {
     )))
 }
 
+/// Point `JAVA_HOME` and `PATH` at the resolved JDK for an external tool
+fn export_jdk_environment(installed_jdk: &InstalledJdk) -> Result<()> {
+    let java_home = installed_jdk.resolve_java_home();
+    let bin_path = installed_jdk.resolve_bin_path()?;
+    let path_entries = env::var_os("PATH").unwrap_or_default();
+    let path = env::join_paths(std::iter::once(bin_path).chain(env::split_paths(&path_entries)))
+        .map_err(|e| KopiError::SystemError(format!("Failed to build PATH: {e}")))?;
+
+    // SAFETY: the shim is single-threaded and replaces itself with the tool right after.
+    unsafe {
+        env::set_var("JAVA_HOME", java_home);
+        env::set_var("PATH", path);
+    }
+    Ok(())
+}
+
+/// Set the per-version `JAVA_HOME` variables for the tool; failures are
+/// logged so that a bad template never stops the tool from running
+fn export_java_home_variables(config: &KopiConfig) {
+    match java_homes::java_home_variables(config) {
+        // SAFETY: the shim is single-threaded and replaces itself with the tool right after.
+        Ok(variables) => unsafe {
+            for (name, java_home) in variables {
+                env::set_var(name, java_home);
+            }
+        },
+        Err(e) => log::warn!("Failed to set per-version JAVA_HOME variables: {e}"),
+    }
+}
+
 fn get_tool_name() -> Result<String> {
     let arg0 = env::args_os()
         .next()
@@ -249,12 +359,39 @@ fn get_tool_name() -> Result<String> {
     Ok(tool_name.to_string())
 }
 
-fn find_jdk_installation(
+/// Installed JDK a shim runs for `version_request`
+pub fn find_jdk_installation(
     repository: &JdkRepository,
     version_request: &VersionRequest,
 ) -> Result<InstalledJdk> {
     log::debug!("Finding JDK for version request: {version_request:?}");
 
+    let (request, candidates) = installation_candidates(repository, version_request)?;
+    if let Some((jdk, _)) = candidates.into_iter().find(|(_, matches)| *matches) {
+        return Ok(jdk);
+    }
+
+    // No matching JDK found
+    Err(KopiError::JdkNotInstalled {
+        jdk_spec: request.to_string(),
+        version: Some(version_request.version_pattern.clone()),
+        distribution: request.distribution,
+        auto_install_enabled: false, // Will be updated by caller
+        auto_install_failed: None,
+        user_declined: false,
+        install_in_progress: false,
+    })
+}
+
+/// Installed JDKs in the order a shim considers them for `version_request`,
+/// each flagged with whether it matches.
+///
+/// Also returns the request with the default distribution applied; the first
+/// matching JDK is the one the shim runs.
+pub fn installation_candidates(
+    repository: &JdkRepository,
+    version_request: &VersionRequest,
+) -> Result<(VersionRequest, Vec<(InstalledJdk, bool)>)> {
     // Parse distribution from version request
     let distribution = if let Some(dist_name) = &version_request.distribution {
         Distribution::from_str(dist_name)?
@@ -265,63 +402,44 @@ fn find_jdk_installation(
     log::debug!("Using distribution: {}", distribution.id());
 
     // List installed JDKs
-    let installed_jdks = repository.list_installed_jdks()?;
+    let mut installed_jdks = repository.list_installed_jdks()?;
     log::debug!("Found {} installed JDKs", installed_jdks.len());
 
-    // Find matching JDK
-    for jdk in installed_jdks {
-        log::debug!(
-            "Checking JDK: distribution={}, version={} against request: distribution={}, \
-             version={}",
-            jdk.distribution,
-            jdk.version,
-            distribution.id(),
-            version_request.version_pattern
-        );
+    // A track resolves to the newest matching installation
+    if version_request.track.is_some() {
+        installed_jdks.sort_by(|a, b| VersionTrack::compare(&b.version, &a.version));
+    }
 
-        if jdk.distribution.to_lowercase() == distribution.id() {
-            // Check if the installed JDK version matches the requested pattern
-            let matches = jdk
-                .version
-                .matches_pattern(&version_request.version_pattern);
+    // Match each JDK, applying the default distribution when none was requested
+    let request = version_request
+        .clone()
+        .with_distribution(distribution.id().to_string());
+    let candidates = installed_jdks
+        .into_iter()
+        .map(|jdk| {
+            let matches = matching::matches_installed(&request, &jdk);
             log::debug!(
-                "Version matching: installed {} matches pattern {}? {}",
+                "Checking JDK: distribution={}, version={} against request: distribution={}, \
+                 version={}: {}",
+                jdk.distribution,
                 jdk.version,
+                distribution.id(),
                 version_request.version_pattern,
-                matches
+                if matches { "match" } else { "no match" }
             );
-            if matches {
-                return Ok(jdk);
-            }
-        }
-    }
-
-    // No matching JDK found
-    Err(KopiError::JdkNotInstalled {
-        jdk_spec: format!("{}@{}", distribution.id(), version_request.version_pattern),
-        version: Some(version_request.version_pattern.clone()),
-        distribution: Some(distribution.id().to_string()),
-        auto_install_enabled: false, // Will be updated by caller
-        auto_install_failed: None,
-        user_declined: false,
-        install_in_progress: false,
-    })
+            (jdk, matches)
+        })
+        .collect();
+    Ok((request, candidates))
 }
 
 fn build_tool_path(installed_jdk: &InstalledJdk, tool_name: &str) -> Result<PathBuf> {
     // Use the resolved bin path from InstalledJdk
     let bin_dir = installed_jdk.resolve_bin_path()?;
 
-    let tool_filename = if crate::platform::executable_extension().is_empty() {
-        tool_name.to_string()
-    } else {
-        format!("{}{}", tool_name, crate::platform::executable_extension())
-    };
-
-    let tool_path = bin_dir.join(tool_filename);
-
-    // Verify the tool exists
-    if !tool_path.exists() {
+    // Windows resolves the tool through PATHEXT, so `.cmd` launchers such as
+    // GraalVM's are found too
+    let Some(tool_path) = crate::platform::file_ops::find_executable(&bin_dir, tool_name) else {
         // Only exit in production code, not during tests
         #[cfg(not(test))]
         {
@@ -333,9 +451,15 @@ fn build_tool_path(installed_jdk: &InstalledJdk, tool_name: &str) -> Result<Path
             {
                 for entry in entries.flatten() {
                     if let Some(name) = entry.file_name().to_str() {
-                        // Remove .exe extension on Windows
-                        let tool_name_clean = if cfg!(windows) && name.ends_with(".exe") {
-                            &name[..name.len() - 4]
+                        // Remove the PATHEXT extension on Windows
+                        let tool_name_clean = if cfg!(windows)
+                            && crate::platform::file_ops::is_executable(&entry.path())
+                                .unwrap_or(false)
+                        {
+                            Path::new(name)
+                                .file_stem()
+                                .and_then(|stem| stem.to_str())
+                                .unwrap_or(name)
                         } else {
                             name
                         };
@@ -371,7 +495,7 @@ fn build_tool_path(installed_jdk: &InstalledJdk, tool_name: &str) -> Result<Path
             "Tool '{tool_name}' not found in JDK at {:?}",
             installed_jdk.path
         )));
-    }
+    };
 
     Ok(tool_path)
 }
@@ -422,12 +546,16 @@ mod tests {
 
             let java_path = bin_dir.join("java.exe");
             fs::write(&java_path, "").unwrap();
+            let launcher_path = bin_dir.join("native-image.cmd");
+            fs::write(&launcher_path, "").unwrap();
 
             let installed_jdk =
                 InstalledJdk::new("test".to_string(), Version::new(21, 0, 1), jdk_path, false);
 
             let result = build_tool_path(&installed_jdk, "java").unwrap();
             assert_eq!(result, java_path);
+            let result = build_tool_path(&installed_jdk, "native-image").unwrap();
+            assert_eq!(result, launcher_path);
         }
     }
 
@@ -487,6 +615,35 @@ mod tests {
         assert!(matches!(result, Err(KopiError::JdkNotInstalled { .. })));
     }
 
+    #[test]
+    fn test_find_jdk_installation_requires_javafx_build() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let repository = JdkRepository::new(&config);
+        install::ensure_installations_root(temp_dir.path()).unwrap();
+        fs::create_dir_all(install::installation_directory(
+            temp_dir.path(),
+            "zulu-21.0.5",
+        ))
+        .unwrap();
+
+        let request = VersionRequest::from_str("zulu@21+fx").unwrap();
+        match find_jdk_installation(&repository, &request) {
+            Err(KopiError::JdkNotInstalled { jdk_spec, .. }) => {
+                assert_eq!(jdk_spec, "zulu@21+fx");
+            }
+            other => panic!("expected JdkNotInstalled, got {other:?}"),
+        }
+
+        fs::create_dir_all(install::installation_directory(
+            temp_dir.path(),
+            "zulu-21.0.5-fx",
+        ))
+        .unwrap();
+        let jdk = find_jdk_installation(&repository, &request).unwrap();
+        assert!(jdk.javafx_bundled);
+    }
+
     #[test]
     fn test_version_matching_logic() {
         // Test that version matching works correctly
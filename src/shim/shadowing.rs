@@ -0,0 +1,198 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detection of `java` executables that run instead of the kopi shims.
+//!
+//! When a directory such as `/usr/bin` precedes `~/.kopi/shims` in `PATH`,
+//! the shell finds its `java` first and the shims silently never run.
+
+use crate::paths::shims::{path_warning_file, shims_root};
+use crate::platform::file_ops::find_executable;
+use crate::platform::shell::Shell;
+use log::debug;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A `PATH` entry whose `java` shadows the shims
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowingEntry {
+    /// One-based position in `PATH`
+    pub position: usize,
+    pub directory: PathBuf,
+    pub java: PathBuf,
+}
+
+/// Entries before the shims directory in `path` that contain `java`.
+///
+/// Empty when the shims directory is not in `path` at all, which is a
+/// different problem with its own check.
+pub fn find_shadowing(shims_dir: &Path, path: &OsStr) -> Vec<ShadowingEntry> {
+    let entries: Vec<PathBuf> = env::split_paths(path).collect();
+    let Some(shims_index) = entries.iter().position(|dir| same_dir(dir, shims_dir)) else {
+        return Vec::new();
+    };
+
+    let mut shadowing: Vec<ShadowingEntry> = Vec::new();
+    for (index, directory) in entries[..shims_index].iter().enumerate() {
+        if directory.as_os_str().is_empty() {
+            continue;
+        }
+        // Any PATHEXT extension shadows the shim, e.g. a `java.bat` on Windows
+        let Some(java) = find_executable(directory, "java") else {
+            continue;
+        };
+        if shadowing
+            .iter()
+            .any(|entry| same_dir(&entry.directory, directory))
+        {
+            continue;
+        }
+        shadowing.push(ShadowingEntry {
+            position: index + 1,
+            directory: directory.clone(),
+            java,
+        });
+    }
+    shadowing
+}
+
+/// `path` with the shims directory moved to the front
+pub fn reordered_path(shims_dir: &Path, path: &OsStr) -> Option<OsString> {
+    let rest = env::split_paths(path).filter(|dir| !same_dir(dir, shims_dir));
+    env::join_paths(std::iter::once(shims_dir.to_path_buf()).chain(rest)).ok()
+}
+
+/// Command that sets `PATH` to `value` in `shell`
+pub fn set_path_command(shell: &Shell, value: &OsStr) -> String {
+    let value = value.to_string_lossy();
+    match shell {
+        Shell::Fish => {
+            let entries: Vec<String> = env::split_paths(value.as_ref())
+                .map(|dir| format!("\"{}\"", dir.display()))
+                .collect();
+            format!("set -gx PATH {}", entries.join(" "))
+        }
+        Shell::PowerShell => format!("$env:Path = \"{value}\""),
+        Shell::Cmd => format!("set PATH={value}"),
+        Shell::Bash | Shell::Zsh | Shell::Unknown(_) => format!("export PATH=\"{value}\""),
+    }
+}
+
+/// Print a warning on stderr when `java` in `PATH` shadows the shims.
+///
+/// The warning is shown once per set of offending entries; the last set
+/// warned about is remembered in kopi's home.
+pub fn warn_once(kopi_home: &Path) {
+    let Some(path) = env::var_os("PATH") else {
+        return;
+    };
+    let shadowing = find_shadowing(&shims_root(kopi_home), &path);
+    let marker = path_warning_file(kopi_home);
+    if shadowing.is_empty() {
+        let _ = fs::remove_file(&marker);
+        return;
+    }
+
+    let fingerprint: String = shadowing
+        .iter()
+        .map(|entry| format!("{}\n", entry.directory.display()))
+        .collect();
+    if fs::read_to_string(&marker).is_ok_and(|warned| warned == fingerprint) {
+        return;
+    }
+
+    for entry in &shadowing {
+        eprintln!(
+            "kopi: warning: {} (PATH entry {}) runs instead of the kopi shims",
+            entry.java.display(),
+            entry.position
+        );
+    }
+    eprintln!("kopi: run 'kopi doctor --check shell' for the PATH change that fixes this");
+
+    if let Err(e) = fs::write(&marker, fingerprint) {
+        debug!("Failed to record PATH warning in {}: {e}", marker.display());
+    }
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    a == b
+        || match (fs::canonicalize(a), fs::canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::shims::ensure_shims_root;
+    use crate::platform::with_executable_extension;
+    use tempfile::TempDir;
+
+    fn java_dir(root: &Path, name: &str) -> PathBuf {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(with_executable_extension("java")), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_find_shadowing_names_earlier_java_directories() {
+        let temp = TempDir::new().unwrap();
+        let shims = ensure_shims_root(temp.path()).unwrap();
+        let system = java_dir(temp.path(), "system");
+        let empty = temp.path().join("empty");
+        fs::create_dir_all(&empty).unwrap();
+        let later = java_dir(temp.path(), "later");
+
+        let path = env::join_paths([&empty, &system, &system, &shims, &later]).unwrap();
+        let shadowing = find_shadowing(&shims, &path);
+        assert_eq!(
+            shadowing,
+            vec![ShadowingEntry {
+                position: 2,
+                directory: system.clone(),
+                java: system.join(with_executable_extension("java")),
+            }]
+        );
+
+        // Shims first, or shims missing, means nothing shadows them
+        let fixed = reordered_path(&shims, &path).unwrap();
+        assert_eq!(env::split_paths(&fixed).next(), Some(shims.clone()));
+        assert!(find_shadowing(&shims, &fixed).is_empty());
+        let without = env::join_paths([&system, &later]).unwrap();
+        assert!(find_shadowing(&shims, &without).is_empty());
+    }
+
+    #[test]
+    fn test_set_path_command_per_shell() {
+        let value = env::join_paths(["/k/shims", "/usr/bin"]).unwrap();
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        assert_eq!(
+            set_path_command(&Shell::Bash, &value),
+            format!("export PATH=\"/k/shims{separator}/usr/bin\"")
+        );
+        assert_eq!(
+            set_path_command(&Shell::Fish, &value),
+            "set -gx PATH \"/k/shims\" \"/usr/bin\""
+        );
+        assert_eq!(
+            set_path_command(&Shell::Cmd, &value),
+            format!("set PATH=/k/shims{separator}/usr/bin")
+        );
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Last time each installed JDK was run through a shim.
+//!
+//! `kopi uninstall --unused` reads these timestamps to find JDKs nobody has
+//! used recently. Entries are keyed by the JDK directory name.
+
+use crate::paths::cache::jdk_usage_file;
+use crate::platform::file_ops;
+use chrono::{DateTime, TimeDelta, Utc};
+use log::debug;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Skip rewriting the usage file when the recorded use is this recent
+const RECORD_INTERVAL: TimeDelta = TimeDelta::hours(1);
+
+/// Last-use timestamps by JDK directory name
+pub type UsageRecords = BTreeMap<String, DateTime<Utc>>;
+
+/// Remember that a shim just ran a tool from the JDK at `jdk_path`.
+///
+/// Failures are logged and ignored; usage tracking must never break tool
+/// execution.
+pub fn record_jdk_use(kopi_home: &Path, jdk_path: &Path) {
+    if let Err(e) = try_record(kopi_home, jdk_path, Utc::now()) {
+        debug!("Failed to record JDK use for {}: {e}", jdk_path.display());
+    }
+}
+
+fn try_record(kopi_home: &Path, jdk_path: &Path, now: DateTime<Utc>) -> std::io::Result<()> {
+    let Some(name) = jdk_path.file_name().and_then(|name| name.to_str()) else {
+        return Ok(());
+    };
+
+    let usage_path = jdk_usage_file(kopi_home);
+    // An unreadable or corrupt file is left alone rather than replaced with
+    // this one record, which would lose every other JDK's last use
+    let mut records = try_read_records(&usage_path)?;
+
+    // Fast path: recorded recently enough, nothing to write
+    if records
+        .get(name)
+        .is_some_and(|last_used| now - *last_used < RECORD_INTERVAL)
+    {
+        return Ok(());
+    }
+
+    records.insert(name.to_string(), now);
+
+    if let Some(parent) = usage_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(&records).map_err(std::io::Error::other)?;
+    file_ops::write_atomic(&usage_path, content)
+}
+
+/// Recorded last-use timestamps for all JDKs ever run through a shim
+pub fn load_usage(kopi_home: &Path) -> UsageRecords {
+    read_records(&jdk_usage_file(kopi_home))
+}
+
+fn read_records(usage_path: &Path) -> UsageRecords {
+    try_read_records(usage_path).unwrap_or_default()
+}
+
+/// Records in `usage_path`; a missing file has none
+fn try_read_records(usage_path: &Path) -> std::io::Result<UsageRecords> {
+    match fs::read_to_string(usage_path) {
+        Ok(content) => serde_json::from_str(&content).map_err(std::io::Error::other),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(UsageRecords::new()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::home::jdks_dir;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_load_usage() {
+        let temp_dir = TempDir::new().unwrap();
+        let jdk_path = jdks_dir(temp_dir.path()).join("temurin-21.0.1");
+        let now = Utc::now();
+
+        try_record(temp_dir.path(), &jdk_path, now).unwrap();
+
+        let records = load_usage(temp_dir.path());
+        assert_eq!(records.get("temurin-21.0.1"), Some(&now));
+    }
+
+    #[test]
+    fn test_record_is_throttled() {
+        let temp_dir = TempDir::new().unwrap();
+        let jdk_path = jdks_dir(temp_dir.path()).join("temurin-21.0.1");
+        let first = Utc::now();
+
+        try_record(temp_dir.path(), &jdk_path, first).unwrap();
+        try_record(temp_dir.path(), &jdk_path, first + TimeDelta::minutes(5)).unwrap();
+        assert_eq!(load_usage(temp_dir.path())["temurin-21.0.1"], first);
+
+        let later = first + TimeDelta::hours(2);
+        try_record(temp_dir.path(), &jdk_path, later).unwrap();
+        assert_eq!(load_usage(temp_dir.path())["temurin-21.0.1"], later);
+    }
+
+    #[test]
+    fn test_load_usage_ignores_corrupt_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let usage_path = jdk_usage_file(temp_dir.path());
+        fs::create_dir_all(usage_path.parent().unwrap()).unwrap();
+        fs::write(&usage_path, "not json").unwrap();
+
+        assert!(load_usage(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_record_leaves_corrupt_file_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        let usage_path = jdk_usage_file(temp_dir.path());
+        fs::create_dir_all(usage_path.parent().unwrap()).unwrap();
+        // Truncated mid-write by an older kopi
+        let corrupt = "{\n  \"temurin-21.0.1\": \"2025-06-01T12:00:00Z\",\n  \"zulu-17";
+        fs::write(&usage_path, corrupt).unwrap();
+
+        let jdk_path = jdks_dir(temp_dir.path()).join("corretto-17.0.9");
+        assert!(try_record(temp_dir.path(), &jdk_path, Utc::now()).is_err());
+        assert_eq!(fs::read_to_string(&usage_path).unwrap(), corrupt);
+    }
+
+    #[test]
+    fn test_record_keeps_other_jdks() {
+        let temp_dir = TempDir::new().unwrap();
+        let jdks = jdks_dir(temp_dir.path());
+        let now = Utc::now();
+
+        try_record(temp_dir.path(), &jdks.join("temurin-21.0.1"), now).unwrap();
+        try_record(temp_dir.path(), &jdks.join("zulu-17.0.9"), now).unwrap();
+
+        let records = load_usage(temp_dir.path());
+        assert_eq!(records.len(), 2);
+        assert_eq!(records["temurin-21.0.1"], now);
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Script wrappers written next to Windows shims.
+//!
+//! Some build tools and IDE launchers run `java.cmd` or a PowerShell script
+//! instead of `java.exe`. Each wrapper forwards its arguments to the
+//! `<tool>.exe` shim in the same directory, so the shim still sees its own
+//! name in `argv[0]` and the exit code is passed back to the caller.
+
+use crate::config::ShimWrapper;
+
+impl ShimWrapper {
+    /// Extension of the wrapper file, including the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            ShimWrapper::Cmd => ".cmd",
+            ShimWrapper::Ps1 => ".ps1",
+        }
+    }
+
+    /// Wrapper file name for `tool`, e.g. `java.cmd`
+    pub fn file_name(self, tool: &str) -> String {
+        format!("{tool}{}", self.extension())
+    }
+
+    /// Script that runs the `tool` shim with the wrapper's arguments
+    pub fn script(self, tool: &str) -> String {
+        match self {
+            // CRLF keeps cmd.exe from misparsing labels and long lines
+            ShimWrapper::Cmd => format!(
+                "@echo off\r\n\
+                 rem Generated by kopi: forwards to the {tool} shim\r\n\
+                 \"%~dp0{tool}.exe\" %*\r\n\
+                 exit /b %ERRORLEVEL%\r\n"
+            ),
+            ShimWrapper::Ps1 => format!(
+                "# Generated by kopi: forwards to the {tool} shim\n\
+                 & \"$PSScriptRoot\\{tool}.exe\" @args\n\
+                 exit $LASTEXITCODE\n"
+            ),
+        }
+    }
+
+    /// The wrapper kind a shims directory entry belongs to, if any
+    pub fn from_file_name(file_name: &str) -> Option<ShimWrapper> {
+        let lower = file_name.to_ascii_lowercase();
+        [ShimWrapper::Cmd, ShimWrapper::Ps1]
+            .into_iter()
+            .find(|wrapper| lower.ends_with(wrapper.extension()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrapper_scripts_forward_to_exe_shim() {
+        let cmd = ShimWrapper::Cmd.script("java");
+        assert!(cmd.starts_with("@echo off\r\n"));
+        assert!(cmd.contains("\"%~dp0java.exe\" %*\r\n"));
+        assert!(cmd.ends_with("exit /b %ERRORLEVEL%\r\n"));
+
+        let ps1 = ShimWrapper::Ps1.script("native-image");
+        assert!(ps1.contains("& \"$PSScriptRoot\\native-image.exe\" @args\n"));
+        assert!(ps1.ends_with("exit $LASTEXITCODE\n"));
+
+        assert_eq!(ShimWrapper::Cmd.file_name("javac"), "javac.cmd");
+        assert_eq!(
+            ShimWrapper::from_file_name("JAVA.CMD"),
+            Some(ShimWrapper::Cmd)
+        );
+        assert_eq!(
+            ShimWrapper::from_file_name("java.ps1"),
+            Some(ShimWrapper::Ps1)
+        );
+        assert_eq!(ShimWrapper::from_file_name("java.exe"), None);
+    }
+}
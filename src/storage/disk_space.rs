@@ -14,7 +14,7 @@
 
 use crate::error::{KopiError, Result};
 use crate::storage::disk_probe;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct DiskSpaceChecker {
     min_disk_space_mb: u64,
@@ -26,24 +26,11 @@ impl DiskSpaceChecker {
     }
 
     pub fn check_disk_space(&self, path: &Path, kopi_home: &Path) -> Result<()> {
-        let mut target_dir = path.to_path_buf();
-        while !target_dir.exists() {
-            if let Some(parent) = target_dir.parent() {
-                target_dir = parent.to_path_buf();
-            } else {
-                target_dir = kopi_home.to_path_buf();
-                break;
-            }
-        }
+        let target_dir = existing_ancestor(path, kopi_home);
 
         log::debug!("Checking disk space for path {path:?} (using {target_dir:?})");
 
-        let available_bytes = disk_probe::available_bytes(&target_dir).map_err(|err| {
-            log::error!("Failed to check disk space at {target_dir:?}: {err}");
-            err
-        })?;
-
-        let available_mb = available_bytes / (1024 * 1024);
+        let available_mb = available_at(&target_dir)? / BYTES_PER_MB;
         log::debug!(
             "Disk space check: available={available_mb}MB, required={}MB",
             self.min_disk_space_mb
@@ -59,6 +46,95 @@ impl DiskSpaceChecker {
 
         Ok(())
     }
+
+    /// Verifies, before downloading, that there is room for both the archive in
+    /// `download_dir` and its extracted contents (plus the configured minimum
+    /// free space) under `install_dir`. When both directories live on the same
+    /// filesystem the two amounts are checked together.
+    pub fn check_install_space(
+        &self,
+        download_dir: &Path,
+        install_dir: &Path,
+        kopi_home: &Path,
+        archive_bytes: u64,
+        extracted_bytes: u64,
+    ) -> Result<()> {
+        let download_dir = existing_ancestor(download_dir, kopi_home);
+        let install_dir = existing_ancestor(install_dir, kopi_home);
+        let install_bytes =
+            extracted_bytes.saturating_add(self.min_disk_space_mb.saturating_mul(BYTES_PER_MB));
+
+        if same_filesystem(&download_dir, &install_dir) {
+            return require_bytes(
+                &install_dir,
+                archive_bytes.saturating_add(install_bytes),
+                "the download and extracted JDK",
+            );
+        }
+
+        require_bytes(&download_dir, archive_bytes, "the download")?;
+        require_bytes(&install_dir, install_bytes, "the extracted JDK")
+    }
+}
+
+/// Estimates the extracted size of an archive from its compressed size.
+pub fn estimate_extracted_size(archive_bytes: u64, expansion_factor: f64) -> u64 {
+    (archive_bytes as f64 * expansion_factor.max(1.0)).ceil() as u64
+}
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+fn existing_ancestor(path: &Path, kopi_home: &Path) -> PathBuf {
+    let mut target_dir = path.to_path_buf();
+    while !target_dir.exists() {
+        if let Some(parent) = target_dir.parent() {
+            target_dir = parent.to_path_buf();
+        } else {
+            return kopi_home.to_path_buf();
+        }
+    }
+    target_dir
+}
+
+fn available_at(dir: &Path) -> Result<u64> {
+    disk_probe::available_bytes(dir).map_err(|err| {
+        log::error!("Failed to check disk space at {dir:?}: {err}");
+        err
+    })
+}
+
+fn require_bytes(dir: &Path, required_bytes: u64, purpose: &str) -> Result<()> {
+    let available_bytes = available_at(dir)?;
+    log::debug!(
+        "Disk space check for {purpose} at {dir:?}: available={available_bytes}B, \
+         required={required_bytes}B"
+    );
+
+    if available_bytes < required_bytes {
+        return Err(KopiError::DiskSpaceError(format!(
+            "Insufficient disk space at {dir:?} for {purpose}. Needed: {}MB, Available: {}MB",
+            required_bytes.div_ceil(BYTES_PER_MB),
+            available_bytes / BYTES_PER_MB
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn same_filesystem(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (std::fs::metadata(a), std::fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_filesystem(a: &Path, b: &Path) -> bool {
+    // Compare drive prefixes (e.g. `C:`); good enough for the common layouts.
+    a.components().next() == b.components().next()
 }
 
 #[cfg(test)]
@@ -76,4 +152,40 @@ mod tests {
 
         assert!(result.is_ok() || matches!(result.unwrap_err(), KopiError::DiskSpaceError(_)));
     }
+
+    #[test]
+    fn test_estimate_extracted_size() {
+        assert_eq!(estimate_extracted_size(100, 3.0), 300);
+        assert_eq!(estimate_extracted_size(100, 2.5), 250);
+        // Factors below 1.0 never estimate less than the archive itself
+        assert_eq!(estimate_extracted_size(100, 0.5), 100);
+    }
+
+    #[test]
+    fn test_check_install_space_reports_needed_and_available() {
+        let temp_dir = TempDir::new().unwrap();
+        let checker = DiskSpaceChecker::new(0);
+        let install_dir = crate::paths::home::jdks_dir(temp_dir.path());
+
+        checker
+            .check_install_space(temp_dir.path(), &install_dir, temp_dir.path(), 1, 1)
+            .unwrap();
+
+        let err = checker
+            .check_install_space(
+                temp_dir.path(),
+                &install_dir,
+                temp_dir.path(),
+                u64::MAX / 4,
+                u64::MAX / 4,
+            )
+            .unwrap_err();
+        match err {
+            KopiError::DiskSpaceError(msg) => {
+                assert!(msg.contains("Needed:"), "{msg}");
+                assert!(msg.contains("Available:"), "{msg}");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
 }
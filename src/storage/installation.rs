@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use crate::error::{KopiError, Result};
+use crate::platform::file_ops;
+use crate::storage::JdkRepository;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -22,6 +24,73 @@ pub struct InstallationContext {
     pub temp_path: PathBuf,
 }
 
+/// An installation moved aside while `kopi install --force` replaces it.
+///
+/// Dropping it without [`ReplacedInstallation::commit`] moves it back, so a
+/// reinstall that fails or is interrupted leaves the previous JDK in place.
+#[derive(Debug)]
+pub struct ReplacedInstallation {
+    original: PathBuf,
+    aside: Option<PathBuf>,
+}
+
+impl ReplacedInstallation {
+    /// Rename `path` to `.<name>.removing` next to it, the name uninstall
+    /// uses for directories on their way out
+    pub fn set_aside(path: &Path) -> Result<Self> {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| {
+                KopiError::SystemError(format!("Invalid installation path {}", path.display()))
+            })?;
+        let aside = path.with_file_name(format!(".{name}.removing"));
+        fs::rename(
+            file_ops::extended_length_path(path),
+            file_ops::extended_length_path(&aside),
+        )?;
+        Ok(Self {
+            original: path.to_path_buf(),
+            aside: Some(aside),
+        })
+    }
+
+    /// The replacement is in place; delete the previous installation
+    pub fn commit(mut self, repository: &JdkRepository) -> Result<()> {
+        match self.aside.take() {
+            Some(aside) => repository.remove_jdk(&aside),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for ReplacedInstallation {
+    fn drop(&mut self) {
+        let Some(aside) = self.aside.take() else {
+            return;
+        };
+        if fs::symlink_metadata(&self.original).is_ok() {
+            log::warn!(
+                "Cannot restore {} from {}: the path is taken",
+                self.original.display(),
+                aside.display()
+            );
+            return;
+        }
+        match fs::rename(&aside, &self.original) {
+            Ok(()) => log::info!(
+                "Restored previous installation at {}",
+                self.original.display()
+            ),
+            Err(e) => log::warn!(
+                "Failed to restore {} from {}: {e}",
+                self.original.display(),
+                aside.display()
+            ),
+        }
+    }
+}
+
 pub struct JdkInstaller;
 
 impl JdkInstaller {
@@ -68,7 +137,11 @@ impl JdkInstaller {
             context.temp_path.clone()
         };
 
-        fs::rename(&source_path, &context.final_path).inspect_err(|_| {
+        fs::rename(
+            file_ops::extended_length_path(&source_path),
+            file_ops::extended_length_path(&context.final_path),
+        )
+        .inspect_err(|_| {
             let _ = fs::remove_dir_all(&context.temp_path);
         })?;
 
@@ -81,7 +154,7 @@ impl JdkInstaller {
 
     pub fn cleanup_failed_installation(context: &InstallationContext) -> Result<()> {
         if context.temp_path.exists() {
-            fs::remove_dir_all(&context.temp_path)?;
+            fs::remove_dir_all(file_ops::extended_length_path(&context.temp_path))?;
         }
         Ok(())
     }
@@ -130,6 +203,40 @@ mod tests {
         assert!(matches!(result.unwrap_err(), KopiError::AlreadyExists(_)));
     }
 
+    #[test]
+    fn test_replaced_installation_restored_unless_committed() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = crate::config::KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let repository = JdkRepository::new(&config);
+        let jdks_dir = install::ensure_installations_root(temp_dir.path()).unwrap();
+        let install_path = jdks_dir.join("temurin-21.0.1");
+        fs::create_dir_all(install_path.join("bin")).unwrap();
+        fs::write(install_path.join("bin/java"), "old").unwrap();
+        let aside = jdks_dir.join(".temurin-21.0.1.removing");
+
+        // A reinstall that fails before the new JDK is in place
+        let replaced = ReplacedInstallation::set_aside(&install_path).unwrap();
+        assert!(!install_path.exists());
+        assert!(aside.exists());
+        drop(replaced);
+        assert_eq!(
+            fs::read_to_string(install_path.join("bin/java")).unwrap(),
+            "old"
+        );
+        assert!(!aside.exists());
+
+        // A reinstall that succeeds
+        let replaced = ReplacedInstallation::set_aside(&install_path).unwrap();
+        fs::create_dir_all(install_path.join("bin")).unwrap();
+        fs::write(install_path.join("bin/java"), "new").unwrap();
+        replaced.commit(&repository).unwrap();
+        assert_eq!(
+            fs::read_to_string(install_path.join("bin/java")).unwrap(),
+            "new"
+        );
+        assert!(!aside.exists());
+    }
+
     #[test]
     fn test_finalize_installation() {
         let temp_dir = TempDir::new().unwrap();
@@ -12,16 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::config::{PinConfig, PinVersionFormat};
 use crate::error::{KopiError, Result};
 use crate::paths::install;
-use crate::storage::{InstallationMetadata, JdkMetadataWithInstallation};
-use crate::version::Version;
+use crate::storage::InstallationMetadata;
+use crate::version::file::write_pin;
+use crate::version::{Version, format_version_minimal};
+use rayon::prelude::*;
+use serde::Deserialize;
 use std::cell::RefCell;
 use std::fs;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+/// The part of a `.meta.json` needed to locate JAVA_HOME; adopted JDKs
+/// record no package details
+#[derive(Deserialize)]
+struct InstallationSection {
+    installation_metadata: InstallationMetadata,
+}
+
 #[derive(Debug, Clone)]
 pub struct InstalledJdk {
     pub distribution: String,
@@ -62,7 +72,7 @@ impl InstalledJdk {
         }
 
         match std::fs::read_to_string(&metadata_path) {
-            Ok(content) => match serde_json::from_str::<JdkMetadataWithInstallation>(&content) {
+            Ok(content) => match serde_json::from_str::<InstallationSection>(&content) {
                 Ok(metadata) => {
                     log::debug!("Loaded metadata from: {}", metadata_path.display());
                     Some(metadata.installation_metadata)
@@ -87,6 +97,11 @@ impl InstalledJdk {
         }
     }
 
+    /// Installation metadata recorded at install time, if present and valid
+    pub fn installation_metadata(&self) -> Option<InstallationMetadata> {
+        self.get_cached_metadata()
+    }
+
     /// Get cached metadata, loading it if necessary
     fn get_cached_metadata(&self) -> Option<InstallationMetadata> {
         let mut cache = self.metadata_cache.borrow_mut();
@@ -135,68 +150,41 @@ impl InstalledJdk {
         true
     }
 
-    pub fn write_to(&self, path: &Path) -> Result<()> {
-        // Ensure parent directory exists
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|e| {
-                KopiError::SystemError(format!(
-                    "Failed to create directory {}: {}",
-                    parent.display(),
-                    e
-                ))
-            })?;
-        }
+    /// Version spec that pins this JDK, e.g. `temurin@21.0.5+11` or
+    /// `liberica@21.0.5+fx`
+    pub fn pin_spec(&self) -> String {
+        self.pin_spec_with(&PinConfig::default())
+    }
 
-        // Try to format the version in a more user-friendly way
-        // If the version has 4 components and no build (e.g., 24.0.2.12),
-        // try to extract the build number for a cleaner format (e.g., 24.0.2+12)
-        let formatted_version =
-            if self.version.components.len() == 4 && self.version.build.is_none() {
-                // Try to extract build from the 4th component
-                if let Some(extracted) = self.version.try_extract_build() {
-                    extracted.to_string()
-                } else {
-                    self.version.to_string()
+    /// Version spec that pins this JDK, shaped by the `[pin]` settings
+    pub fn pin_spec_with(&self, pin: &PinConfig) -> String {
+        let formatted_version = match pin.version_format {
+            // If the version has 4 components and no build (e.g., 24.0.2.12),
+            // try to extract the build number for a cleaner format (e.g., 24.0.2+12)
+            PinVersionFormat::Full
+                if self.version.components.len() == 4 && self.version.build.is_none() =>
+            {
+                match self.version.try_extract_build() {
+                    Some(extracted) => extracted.to_string(),
+                    None => self.version.to_string(),
                 }
-            } else {
-                self.version.to_string()
-            };
+            }
+            PinVersionFormat::Full => self.version.to_string(),
+            PinVersionFormat::Minimal => format_version_minimal(&self.version),
+            PinVersionFormat::Major => self.version.major().to_string(),
+        };
 
         // Format version string with JavaFX suffix if needed
         let javafx_suffix = if self.javafx_bundled { "+fx" } else { "" };
-        let version_string = format!(
-            "{}@{}{}",
-            self.distribution, formatted_version, javafx_suffix
-        );
-
-        // Write atomically using a temporary file
-        let temp_path = path.with_extension("tmp");
-
-        {
-            let mut file = fs::File::create(&temp_path).map_err(|e| {
-                KopiError::SystemError(format!("Failed to create {}: {}", temp_path.display(), e))
-            })?;
-
-            file.write_all(version_string.as_bytes()).map_err(|e| {
-                KopiError::SystemError(format!("Failed to write to {}: {}", temp_path.display(), e))
-            })?;
-
-            file.flush().map_err(|e| {
-                KopiError::SystemError(format!("Failed to flush {}: {}", temp_path.display(), e))
-            })?;
+        if pin.include_distribution {
+            format!("{}@{formatted_version}{javafx_suffix}", self.distribution)
+        } else {
+            format!("{formatted_version}{javafx_suffix}")
         }
+    }
 
-        // Rename temp file to final location
-        fs::rename(&temp_path, path).map_err(|e| {
-            KopiError::SystemError(format!(
-                "Failed to rename {} to {}: {}",
-                temp_path.display(),
-                path.display(),
-                e
-            ))
-        })?;
-
-        log::debug!("Wrote version file: {path:?}");
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        write_pin(path, &self.pin_spec())?;
         Ok(())
     }
 
@@ -385,20 +373,42 @@ impl JdkLister {
         ))
     }
 
+    /// Total size of the regular files under `path`.
+    ///
+    /// Subdirectories are walked in parallel on the rayon pool, which keeps
+    /// JDKs with tens of thousands of files from dominating `kopi list`.
     pub fn get_jdk_size(path: &Path) -> Result<u64> {
-        let mut total_size = 0u64;
-
-        for entry in walkdir::WalkDir::new(path) {
-            let entry = entry?;
-            if entry.file_type().is_file() {
-                total_size += entry.metadata()?.len();
-            }
+        // Adopted JDKs are links and take no space under kopi's home
+        if path.is_symlink() {
+            return Ok(0);
         }
 
-        Ok(total_size)
+        directory_size(path)
     }
 }
 
+fn directory_size(dir: &Path) -> Result<u64> {
+    let mut total_size = 0u64;
+    let mut subdirs = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        // Symlinks are neither followed nor counted
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            subdirs.push(entry.path());
+        } else if file_type.is_file() {
+            total_size += entry.metadata()?.len();
+        }
+    }
+
+    let nested = subdirs
+        .par_iter()
+        .map(|subdir| directory_size(subdir))
+        .try_reduce(|| 0, |a, b| Ok(a + b))?;
+    Ok(total_size + nested)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -414,6 +424,21 @@ mod tests {
         install::ensure_installations_root(temp_dir.path()).unwrap()
     }
 
+    #[test]
+    fn test_get_jdk_size_walks_nested_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let jdk_path = temp_dir.path().join("temurin-21.0.5");
+        fs::create_dir_all(jdk_path.join("lib/server")).unwrap();
+        fs::create_dir_all(jdk_path.join("bin")).unwrap();
+        fs::write(jdk_path.join("release"), vec![0u8; 10]).unwrap();
+        fs::write(jdk_path.join("bin/java"), vec![0u8; 20]).unwrap();
+        fs::write(jdk_path.join("lib/server/libjvm.so"), vec![0u8; 300]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("server/libjvm.so", jdk_path.join("lib/libjvm.so")).unwrap();
+
+        assert_eq!(JdkLister::get_jdk_size(&jdk_path).unwrap(), 330);
+    }
+
     #[test]
     fn test_list_installed_jdks() {
         let temp_dir = TempDir::new().unwrap();
@@ -1272,6 +1297,45 @@ mod tests {
         assert_eq!(content_fx, "liberica@21.0.5+fx");
     }
 
+    #[test]
+    fn test_pin_spec_with_pin_config() {
+        let jdk = InstalledJdk::new(
+            "temurin".to_string(),
+            Version::from_str("21.0.0+35").unwrap(),
+            PathBuf::from("/jdks/temurin-21.0.0+35"),
+            false,
+        );
+        let pin = |include_distribution, version_format| PinConfig {
+            include_distribution,
+            version_format,
+        };
+
+        assert_eq!(jdk.pin_spec(), "temurin@21.0.0+35");
+        assert_eq!(
+            jdk.pin_spec_with(&pin(true, PinVersionFormat::Minimal)),
+            "temurin@21"
+        );
+        assert_eq!(
+            jdk.pin_spec_with(&pin(false, PinVersionFormat::Full)),
+            "21.0.0+35"
+        );
+
+        let fx = InstalledJdk::new(
+            "liberica".to_string(),
+            Version::from_str("17.0.13").unwrap(),
+            PathBuf::from("/jdks/liberica-17.0.13-fx"),
+            true,
+        );
+        let major = fx.pin_spec_with(&pin(false, PinVersionFormat::Major));
+        assert_eq!(major, "17+fx");
+        assert_eq!(
+            crate::version::VersionRequest::from_str(&major)
+                .unwrap()
+                .javafx_bundled,
+            Some(true)
+        );
+    }
+
     #[test]
     fn test_path_resolution_performance_regression() {
         // This test ensures that path resolution performance doesn't regress
@@ -1304,15 +1368,20 @@ mod tests {
                 lib_c_type: None,
                 package_type: "jdk".to_string(),
                 javafx_bundled: false,
+                feature: Vec::new(),
                 term_of_support: None,
                 release_status: None,
                 latest_build_available: Some(true),
+                release_date: None,
             },
             installation_metadata: InstallationMetadata {
                 java_home_suffix: "Contents/Home".to_string(),
                 structure_type: "bundle".to_string(),
                 platform: "macos".to_string(),
                 metadata_version: 1,
+                stripped: Vec::new(),
+                release: None,
+                pinned_sha256: None,
             },
         };
 
@@ -1427,9 +1496,11 @@ mod tests {
                     lib_c_type: None,
                     package_type: "jdk".to_string(),
                     javafx_bundled: false,
+                    feature: Vec::new(),
                     term_of_support: None,
                     release_status: None,
                     latest_build_available: Some(true),
+                    release_date: None,
                 },
                 installation_metadata: InstallationMetadata {
                     java_home_suffix: if i % 2 == 0 {
@@ -1444,6 +1515,9 @@ mod tests {
                     },
                     platform: "macos".to_string(),
                     metadata_version: 1,
+                    stripped: Vec::new(),
+                    release: None,
+                    pinned_sha256: None,
                 },
             };
 
@@ -1526,12 +1600,16 @@ mod tests {
                 term_of_support: Some("sts".to_string()),
                 release_status: None,
                 latest_build_available: Some(true),
+                release_date: None,
             },
             installation_metadata: InstallationMetadata {
                 java_home_suffix: "Contents/Home".to_string(),
                 structure_type: "bundle".to_string(),
                 platform: "macos".to_string(),
                 metadata_version: 1,
+                stripped: Vec::new(),
+                release: None,
+                pinned_sha256: None,
             },
         };
 
@@ -1600,6 +1678,9 @@ mod tests {
                 structure_type: "bundle".to_string(),
                 platform: "macos".to_string(),
                 metadata_version: 1,
+                stripped: Vec::new(),
+                release: None,
+                pinned_sha256: None,
             },
         };
 
@@ -1753,9 +1834,11 @@ mod tests {
             architecture: Some("aarch64".to_string()),
             package_type: "jdk".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: Some("sts".to_string()),
             release_status: None,
             latest_build_available: Some(true),
+            release_date: None,
         }
     }
 }
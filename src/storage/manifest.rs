@@ -0,0 +1,323 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! File manifest captured when a JDK is installed.
+//!
+//! The manifest lists every regular file of the installation with its size
+//! and SHA-256 digest, plus every symlink with its target, relative to the
+//! installation directory. `kopi doctor --check jdks --deep` compares the
+//! tree on disk against it to find missing, modified or truncated files, and
+//! `kopi files` lists it or reports files the installation did not create.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path};
+use walkdir::WalkDir;
+
+use crate::error::{KopiError, Result};
+use crate::models::package::ChecksumType;
+use crate::security::calculate_checksum;
+
+const MANIFEST_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub size: u64,
+    pub sha256: String,
+    /// Unix permission bits; absent on Windows and in manifests written
+    /// before modes were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallManifest {
+    pub format: u32,
+    pub created_at: DateTime<Utc>,
+    /// Regular files keyed by `/`-separated path relative to the installation
+    pub files: BTreeMap<String, ManifestFile>,
+    /// Symlinks and their targets, keyed like `files`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub symlinks: BTreeMap<String, String>,
+}
+
+/// Differences between an installation and its manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ManifestVerification {
+    pub files_checked: usize,
+    pub missing: Vec<String>,
+    /// Files whose size shrank, which usually means an interrupted copy
+    pub truncated: Vec<String>,
+    pub modified: Vec<String>,
+    /// Symlinks that disappeared or now point somewhere else
+    pub changed_symlinks: Vec<String>,
+}
+
+impl ManifestVerification {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty()
+            && self.truncated.is_empty()
+            && self.modified.is_empty()
+            && self.changed_symlinks.is_empty()
+    }
+}
+
+impl InstallManifest {
+    /// Record every file and symlink below `root`
+    pub fn capture(root: &Path) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        let mut symlinks = BTreeMap::new();
+
+        for entry in WalkDir::new(root).follow_links(false) {
+            let entry = entry.map_err(|e| {
+                KopiError::SystemError(format!("Failed to walk {}: {e}", root.display()))
+            })?;
+            let Some(key) = manifest_key(root, entry.path()) else {
+                continue;
+            };
+
+            let file_type = entry.file_type();
+            if file_type.is_symlink() {
+                let target = fs::read_link(entry.path())?;
+                symlinks.insert(key, target.to_string_lossy().replace('\\', "/"));
+            } else if file_type.is_file() {
+                let metadata = entry
+                    .metadata()
+                    .map_err(|e| KopiError::SystemError(e.to_string()))?;
+                let sha256 = calculate_checksum(entry.path(), ChecksumType::Sha256)?;
+                files.insert(
+                    key,
+                    ManifestFile {
+                        size: metadata.len(),
+                        sha256,
+                        mode: file_mode(&metadata),
+                    },
+                );
+            }
+        }
+
+        Ok(Self {
+            format: MANIFEST_FORMAT_VERSION,
+            created_at: Utc::now(),
+            files,
+            symlinks,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| {
+            KopiError::ValidationError(format!("Invalid install manifest {}: {e}", path.display()))
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Compare the installation at `root` against this manifest. Files added
+    /// since installation are not reported.
+    pub fn verify(&self, root: &Path) -> ManifestVerification {
+        let mut result = ManifestVerification::default();
+
+        for (key, expected) in &self.files {
+            result.files_checked += 1;
+
+            let path = root.join(key);
+            let size = match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.is_file() => metadata.len(),
+                _ => {
+                    result.missing.push(key.clone());
+                    continue;
+                }
+            };
+            if size < expected.size {
+                result.truncated.push(key.clone());
+            } else if size != expected.size
+                || !calculate_checksum(&path, ChecksumType::Sha256)
+                    .is_ok_and(|digest| digest.eq_ignore_ascii_case(&expected.sha256))
+            {
+                result.modified.push(key.clone());
+            }
+        }
+
+        for (key, expected) in &self.symlinks {
+            let target = fs::read_link(root.join(key))
+                .ok()
+                .map(|target| target.to_string_lossy().replace('\\', "/"));
+            if target.as_deref() != Some(expected.as_str()) {
+                result.changed_symlinks.push(key.clone());
+            }
+        }
+
+        result
+    }
+
+    /// Files and symlinks below `root` that the manifest does not list, such
+    /// as files added or left behind after installation
+    pub fn orphans(&self, root: &Path) -> Vec<String> {
+        WalkDir::new(root)
+            .follow_links(false)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| !entry.file_type().is_dir())
+            .filter_map(|entry| manifest_key(root, entry.path()))
+            .filter(|key| !self.files.contains_key(key) && !self.symlinks.contains_key(key))
+            .collect()
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+/// Symlinks below `root` whose target does not exist, as manifest keys
+pub fn find_broken_symlinks(root: &Path) -> Vec<String> {
+    WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_symlink() && !entry.path().exists())
+        .filter_map(|entry| manifest_key(root, entry.path()))
+        .collect()
+}
+
+fn manifest_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_jdk(root: &Path) {
+        fs::create_dir_all(root.join("bin")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(root.join("bin/java"), b"java launcher").unwrap();
+        fs::write(root.join("lib/modules"), vec![7u8; 4096]).unwrap();
+        fs::write(root.join("release"), b"JAVA_VERSION=\"21\"").unwrap();
+    }
+
+    #[test]
+    fn test_capture_and_verify_clean_installation() {
+        let temp_dir = TempDir::new().unwrap();
+        sample_jdk(temp_dir.path());
+
+        let manifest = InstallManifest::capture(temp_dir.path()).unwrap();
+        assert_eq!(manifest.files.len(), 3);
+        assert_eq!(manifest.files["lib/modules"].size, 4096);
+
+        let manifest_path = temp_dir.path().join("manifest.json");
+        manifest.save(&manifest_path).unwrap();
+        let loaded = InstallManifest::load(&manifest_path).unwrap();
+        assert_eq!(loaded, manifest);
+
+        let result = manifest.verify(temp_dir.path());
+        assert!(result.is_clean(), "{result:?}");
+        assert_eq!(result.files_checked, 3);
+    }
+
+    #[test]
+    fn test_verify_reports_missing_truncated_and_modified_files() {
+        let temp_dir = TempDir::new().unwrap();
+        sample_jdk(temp_dir.path());
+        let manifest = InstallManifest::capture(temp_dir.path()).unwrap();
+
+        fs::remove_file(temp_dir.path().join("bin/java")).unwrap();
+        fs::write(temp_dir.path().join("lib/modules"), vec![7u8; 100]).unwrap();
+        fs::write(temp_dir.path().join("release"), b"JAVA_VERSION=\"17\"").unwrap();
+
+        let result = manifest.verify(temp_dir.path());
+        assert_eq!(result.missing, vec!["bin/java"]);
+        assert_eq!(result.truncated, vec!["lib/modules"]);
+        assert_eq!(result.modified, vec!["release"]);
+    }
+
+    #[test]
+    fn test_orphans_lists_files_missing_from_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        sample_jdk(root);
+        let manifest = InstallManifest::capture(root).unwrap();
+        assert!(manifest.orphans(root).is_empty());
+
+        fs::create_dir_all(root.join("lib/ext")).unwrap();
+        fs::write(root.join("lib/ext/extra.jar"), b"jar").unwrap();
+        fs::write(root.join("bin/java.bak"), b"old").unwrap();
+        fs::remove_file(root.join("release")).unwrap();
+
+        assert_eq!(
+            manifest.orphans(root),
+            vec!["bin/java.bak", "lib/ext/extra.jar"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_records_file_modes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        sample_jdk(temp_dir.path());
+        let java = temp_dir.path().join("bin/java");
+        fs::set_permissions(&java, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let manifest = InstallManifest::capture(temp_dir.path()).unwrap();
+        assert_eq!(manifest.files["bin/java"].mode, Some(0o755));
+
+        // Manifests written before modes were recorded still load
+        let legacy = r#"{"format":1,"created_at":"2025-01-01T00:00:00Z","files":{"bin/java":{"size":4,"sha256":"ab"}}}"#;
+        let loaded: InstallManifest = serde_json::from_str(legacy).unwrap();
+        assert_eq!(loaded.files["bin/java"].mode, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_recorded_and_checked() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("Contents/Home/bin")).unwrap();
+        fs::write(root.join("Contents/Home/bin/java"), b"java").unwrap();
+        std::os::unix::fs::symlink("Contents/Home/bin", root.join("bin")).unwrap();
+        std::os::unix::fs::symlink("Contents/Home/missing", root.join("lib")).unwrap();
+
+        let manifest = InstallManifest::capture(root).unwrap();
+        assert_eq!(manifest.symlinks["bin"], "Contents/Home/bin");
+        assert_eq!(find_broken_symlinks(root), vec!["lib"]);
+
+        fs::remove_file(root.join("bin")).unwrap();
+        let result = manifest.verify(root);
+        assert_eq!(result.changed_symlinks, vec!["bin"]);
+    }
+}
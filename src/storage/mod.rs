@@ -17,21 +17,27 @@ mod disk_space;
 pub mod formatting;
 mod installation;
 mod listing;
+pub mod manifest;
+pub mod release;
 mod repository;
+pub mod shared_home;
 
 use crate::error::Result;
 use crate::models::api::Package;
 use crate::models::distribution::Distribution;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
-pub use installation::InstallationContext;
+pub use installation::{InstallationContext, ReplacedInstallation};
 pub use listing::{InstalledJdk, JdkLister};
+pub use manifest::InstallManifest;
+pub use release::ReleaseInfo;
 pub use repository::{InstalledMetadataSnapshot, JdkRepository};
 
 /// Installation metadata containing platform-specific JDK structure information
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct InstallationMetadata {
     /// The suffix to append to the installation directory to get JAVA_HOME
     /// For example: "Contents/Home" for macOS bundle structure, or "" for direct structure
@@ -46,6 +52,28 @@ pub struct InstallationMetadata {
     /// Metadata version for future compatibility
     #[serde(default = "default_metadata_version")]
     pub metadata_version: u32,
+
+    /// Paths relative to JAVA_HOME removed after extraction per `install.strip`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stripped: Vec<String>,
+
+    /// Properties read from the JDK's `release` file at install time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release: Option<ReleaseInfo>,
+
+    /// SHA-256 digest pinned with `kopi install --sha256`, which the archive
+    /// was verified against instead of the published checksum
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_sha256: Option<String>,
+}
+
+impl InstallationMetadata {
+    /// Whether `relative` (relative to JAVA_HOME) was deliberately removed at install time
+    pub fn was_stripped(&self, relative: &Path) -> bool {
+        self.stripped
+            .iter()
+            .any(|entry| relative.starts_with(Path::new(entry)))
+    }
 }
 
 fn default_metadata_version() -> u32 {
@@ -53,7 +81,7 @@ fn default_metadata_version() -> u32 {
 }
 
 /// Complete JDK metadata including API data and installation information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JdkMetadataWithInstallation {
     /// All fields from the API Package
     #[serde(flatten)]
@@ -154,9 +182,11 @@ mod metadata_tests {
             lib_c_type: Some("glibc".to_string()),
             package_type: "jdk".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         };
 
         let result = save_jdk_metadata(&jdks_dir, &distribution, "21.0.1+35.1", &package);
@@ -190,6 +220,9 @@ mod metadata_tests {
             structure_type: "bundle".to_string(),
             platform: "macos_aarch64".to_string(),
             metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
         };
 
         let json = serde_json::to_string_pretty(&metadata).unwrap();
@@ -266,9 +299,11 @@ mod metadata_tests {
             lib_c_type: None,
             package_type: "jdk".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         };
 
         let installation_metadata = InstallationMetadata {
@@ -276,6 +311,9 @@ mod metadata_tests {
             structure_type: "bundle".to_string(),
             platform: "macos_aarch64".to_string(),
             metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
         };
 
         let result = save_jdk_metadata_with_installation(
@@ -421,9 +459,11 @@ mod metadata_tests {
             lib_c_type: Some("glibc".to_string()),
             package_type: "jdk".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         };
 
         let result = save_jdk_metadata(&jdks_dir, &distribution, "21.0.1+35.1", &package);
@@ -464,9 +504,11 @@ mod metadata_tests {
             lib_c_type: None,
             package_type: "jdk".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         };
 
         let installation_metadata = InstallationMetadata {
@@ -474,6 +516,9 @@ mod metadata_tests {
             structure_type: "bundle".to_string(),
             platform: "macos_aarch64".to_string(),
             metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
         };
 
         // Save metadata
@@ -535,9 +580,11 @@ mod metadata_tests {
             lib_c_type: Some("glibc".to_string()),
             package_type: "jdk".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         };
 
         let installation_metadata = InstallationMetadata {
@@ -545,6 +592,9 @@ mod metadata_tests {
             structure_type: "direct".to_string(),
             platform: "linux_x64".to_string(),
             metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
         };
 
         // Make directory read-only
@@ -0,0 +1,115 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Properties from the `release` file at the root of a JAVA_HOME.
+//!
+//! The file is written by the JDK build itself, so it describes what was
+//! actually installed even when the package metadata is imprecise.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Selected keys of a JDK's `release` file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ReleaseInfo {
+    /// `IMPLEMENTOR`, e.g. "Eclipse Adoptium"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implementor: Option<String>,
+
+    /// `JAVA_VERSION`, e.g. "21.0.5"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub java_version: Option<String>,
+
+    /// `OS_ARCH`, e.g. "x86_64" or "aarch64"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_arch: Option<String>,
+
+    /// `MODULES`, empty for JDK 8
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modules: Vec<String>,
+}
+
+impl ReleaseInfo {
+    /// Read `java_home/release`, or `None` if it is missing or has none of
+    /// the recorded keys
+    pub fn read(java_home: &Path) -> Option<Self> {
+        let content = fs::read_to_string(java_home.join("release")).ok()?;
+        let info = Self::parse(&content);
+        (info != Self::default()).then_some(info)
+    }
+
+    /// Parse `KEY="value"` lines, ignoring keys that are not recorded
+    pub fn parse(content: &str) -> Self {
+        let mut info = Self::default();
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"').to_string();
+            match key.trim() {
+                "IMPLEMENTOR" => info.implementor = Some(value),
+                "JAVA_VERSION" => info.java_version = Some(value),
+                "OS_ARCH" => info.os_arch = Some(value),
+                "MODULES" => {
+                    info.modules = value.split_whitespace().map(str::to_string).collect();
+                }
+                _ => {}
+            }
+        }
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_release_file() {
+        let info = ReleaseInfo::parse(
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nIMPLEMENTOR_VERSION=\"Temurin-21.0.5+11\"\n\
+             JAVA_VERSION=\"21.0.5\"\nMODULES=\"java.base java.logging jdk.jshell\"\n\
+             OS_ARCH=\"x86_64\"\nOS_NAME=\"Linux\"\n",
+        );
+
+        assert_eq!(info.implementor.as_deref(), Some("Eclipse Adoptium"));
+        assert_eq!(info.java_version.as_deref(), Some("21.0.5"));
+        assert_eq!(info.os_arch.as_deref(), Some("x86_64"));
+        assert_eq!(
+            info.modules,
+            vec!["java.base", "java.logging", "jdk.jshell"]
+        );
+    }
+
+    #[test]
+    fn test_read_release_file() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(ReleaseInfo::read(temp_dir.path()), None);
+
+        fs::write(temp_dir.path().join("release"), "OS_NAME=\"Linux\"\n").unwrap();
+        assert_eq!(ReleaseInfo::read(temp_dir.path()), None);
+
+        fs::write(
+            temp_dir.path().join("release"),
+            "JAVA_VERSION=\"1.8.0_432\"\nOS_ARCH=\"amd64\"\n",
+        )
+        .unwrap();
+        let info = ReleaseInfo::read(temp_dir.path()).unwrap();
+        assert_eq!(info.java_version.as_deref(), Some("1.8.0_432"));
+        assert!(info.modules.is_empty());
+    }
+}
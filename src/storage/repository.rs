@@ -16,16 +16,34 @@ use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
 use crate::models::api::Package;
 use crate::models::distribution::Distribution;
+use crate::models::package::{ArchiveType, PackageType};
 use crate::paths::install;
-use crate::storage::disk_space::DiskSpaceChecker;
+use crate::platform::{file_ops, get_current_architecture, get_current_os, symlink};
+use crate::schema;
+use crate::storage::disk_space::{DiskSpaceChecker, estimate_extracted_size};
 use crate::storage::installation::{InstallationContext, JdkInstaller};
 use crate::storage::listing::{InstalledJdk, JdkLister};
+use crate::storage::manifest::InstallManifest;
 use crate::storage::{InstallationMetadata, JdkMetadataWithInstallation};
-use crate::version::{Version, VersionRequest};
+use crate::version::file::VersionTrack;
+use crate::version::{Version, VersionRequest, matching};
 use log::{debug, warn};
 use serde_json::Value;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Key in a JDK's `.meta.json` marking it as protected
+const PROTECTED_KEY: &str = "protected";
+
+/// Key in a JDK's `.meta.json` marking it as adopted from outside kopi
+const EXTERNAL_KEY: &str = "external";
+
+/// Key in a JDK's `.meta.json` caching its disk usage
+const SIZE_KEY: &str = "size_cache";
+
+/// Name part of the kept install archive, e.g. `temurin-21.0.5.archive.tar.gz`
+const INSTALL_ARCHIVE_KIND: &str = "archive";
 
 pub struct JdkRepository<'a> {
     config: &'a KopiConfig,
@@ -71,6 +89,27 @@ impl<'a> JdkRepository<'a> {
         JdkInstaller::prepare_installation(&jdks_dir, &install_path)
     }
 
+    /// Checks, before downloading, that the temp directory can hold an archive of
+    /// `archive_bytes` and the JDKs directory can hold its extracted contents.
+    pub fn check_download_space(&self, archive_bytes: u64) -> Result<()> {
+        let extracted_bytes =
+            estimate_extracted_size(archive_bytes, self.config.storage.expansion_factor);
+        self.check_extraction_space(archive_bytes, extracted_bytes)
+    }
+
+    /// Checks that the JDKs directory can hold `extracted_bytes` of extracted files
+    /// in addition to an archive of `archive_bytes` in the temp directory.
+    pub fn check_extraction_space(&self, archive_bytes: u64, extracted_bytes: u64) -> Result<()> {
+        let disk_checker = DiskSpaceChecker::new(self.config.storage.min_disk_space_mb);
+        disk_checker.check_install_space(
+            &std::env::temp_dir(),
+            &self.config.jdks_dir()?,
+            self.config.kopi_home(),
+            archive_bytes,
+            extracted_bytes,
+        )
+    }
+
     pub fn finalize_installation(&self, context: InstallationContext) -> Result<PathBuf> {
         JdkInstaller::finalize_installation(context)
     }
@@ -84,7 +123,8 @@ impl<'a> JdkRepository<'a> {
         installed: &InstalledJdk,
     ) -> Result<InstalledMetadataSnapshot> {
         let jdks_dir = self.config.jdks_dir()?;
-        if !installed.path.starts_with(&jdks_dir) {
+        let system = self.is_system(installed);
+        if !system && !installed.path.starts_with(&jdks_dir) {
             return Err(KopiError::SecurityError(format!(
                 "Refusing to read metadata outside of the JDKs directory: {:?}",
                 installed.path
@@ -102,7 +142,12 @@ impl<'a> JdkRepository<'a> {
                 ))
             })?;
 
-        let metadata_path = install::metadata_file(self.config.kopi_home(), slug);
+        // System JDKs keep their metadata beside them, like user installs do
+        let metadata_path = if system {
+            installed.path.with_file_name(format!("{slug}.meta.json"))
+        } else {
+            install::metadata_file(self.config.kopi_home(), slug)
+        };
         if !metadata_path.exists() {
             return Ok(InstalledMetadataSnapshot::missing());
         }
@@ -114,7 +159,7 @@ impl<'a> JdkRepository<'a> {
             ))
         })?;
 
-        match serde_json::from_str::<JdkMetadataWithInstallation>(&contents) {
+        match schema::from_json_str::<JdkMetadataWithInstallation>(&metadata_path, &contents) {
             Ok(metadata) => {
                 let installation_metadata = metadata.installation_metadata.clone();
                 Ok(InstalledMetadataSnapshot::complete(
@@ -123,14 +168,17 @@ impl<'a> JdkRepository<'a> {
                 ))
             }
             Err(parse_error) => {
-                warn!(
-                    "Failed to parse installed metadata {}: {}",
-                    metadata_path.display(),
-                    parse_error
-                );
+                let value = serde_json::from_str::<Value>(&contents).ok();
+                // Adopted JDKs record no package details
+                let external = value
+                    .as_ref()
+                    .and_then(|value| value.get(EXTERNAL_KEY)?.as_bool())
+                    .unwrap_or(false);
+                if !external {
+                    warn!("Failed to parse installed metadata: {parse_error}");
+                }
 
-                let installation_metadata = serde_json::from_str::<Value>(&contents)
-                    .ok()
+                let installation_metadata = value
                     .and_then(|value| value.get("installation_metadata").cloned())
                     .and_then(|value| serde_json::from_value(value).ok());
 
@@ -142,9 +190,69 @@ impl<'a> JdkRepository<'a> {
         }
     }
 
+    /// JDKs in the user's jdks directory followed by those in the system
+    /// directory, if configured, that no user install shadows
     pub fn list_installed_jdks(&self) -> Result<Vec<InstalledJdk>> {
         let jdks_dir = self.config.jdks_dir()?;
-        JdkLister::list_installed_jdks(&jdks_dir)
+        let mut installed = JdkLister::list_installed_jdks(&jdks_dir)?;
+
+        let Some(system_dir) = self.system_jdks_dir(&jdks_dir) else {
+            return Ok(installed);
+        };
+        let system = match JdkLister::list_installed_jdks(&system_dir) {
+            Ok(system) => system,
+            Err(e) => {
+                warn!(
+                    "Failed to list system JDKs in {}: {e}",
+                    system_dir.display()
+                );
+                return Ok(installed);
+            }
+        };
+        let shadowed = |jdk: &InstalledJdk| {
+            installed.iter().any(|user| {
+                user.distribution == jdk.distribution
+                    && user.version == jdk.version
+                    && user.javafx_bundled == jdk.javafx_bundled
+            })
+        };
+        let system: Vec<_> = system.into_iter().filter(|jdk| !shadowed(jdk)).collect();
+        installed.extend(system);
+        installed.sort_by(|a, b| {
+            a.distribution
+                .cmp(&b.distribution)
+                .then(b.version.cmp(&a.version))
+        });
+        Ok(installed)
+    }
+
+    /// Whether `installed` comes from the read-only system JDK directory
+    pub fn is_system(&self, installed: &InstalledJdk) -> bool {
+        self.config
+            .jdks_dir()
+            .ok()
+            .and_then(|jdks_dir| self.system_jdks_dir(&jdks_dir))
+            .is_some_and(|system_dir| installed.path.starts_with(system_dir))
+    }
+
+    /// Fail for JDKs from the system directory, which kopi never modifies
+    pub fn ensure_not_system(&self, installed: &InstalledJdk) -> Result<()> {
+        if self.is_system(installed) {
+            return Err(KopiError::ValidationError(format!(
+                "{}@{} is a read-only system JDK at {}",
+                installed.distribution,
+                installed.version,
+                installed.path.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// The configured system JDK directory, unless it is the user's own
+    fn system_jdks_dir(&self, jdks_dir: &Path) -> Option<PathBuf> {
+        self.config
+            .system_jdks_dir()
+            .filter(|system_dir| system_dir != jdks_dir)
     }
 
     /// Check if a specific JDK version is installed
@@ -170,7 +278,7 @@ impl<'a> JdkRepository<'a> {
                     jdk.distribution, jdk.version, jdk.path
                 );
 
-                if jdk.distribution == distribution.id() {
+                if matching::distribution_matches(&jdk.distribution, distribution.id()) {
                     debug!(
                         "Distribution matches. Checking if search version {} matches installed \
                          version {}",
@@ -179,7 +287,7 @@ impl<'a> JdkRepository<'a> {
 
                     // Check if the installed version matches the search pattern
                     // For example: installed "17.0.15" matches search pattern "17"
-                    if jdk.version.matches_pattern(&version.to_string()) {
+                    if matching::matches(&jdk.version, version) {
                         debug!(
                             "Found matching JDK: {} {} (matched pattern {})",
                             distribution.name(),
@@ -206,19 +314,289 @@ impl<'a> JdkRepository<'a> {
         Ok(false)
     }
 
+    /// Disk usage of the JDK at `path`.
+    ///
+    /// The result is cached in the JDK's `.meta.json` together with the
+    /// installation directory's mtime, and recomputed once that changes.
     pub fn get_jdk_size(&self, path: &Path) -> Result<u64> {
-        JdkLister::get_jdk_size(path)
+        let metadata_path = self.size_cache_file(path);
+        let modified = directory_modified(path);
+        if let (Some(metadata_path), Some(modified)) = (&metadata_path, &modified)
+            && let Some(size) = read_cached_size(metadata_path, modified)
+        {
+            debug!("Using cached size {size} for {}", path.display());
+            return Ok(size);
+        }
+
+        let size = JdkLister::get_jdk_size(path)?;
+        if let (Some(metadata_path), Some(modified)) = (metadata_path, modified)
+            && let Err(e) = write_cached_size(&metadata_path, size, modified)
+        {
+            debug!("Failed to cache size of {}: {e}", path.display());
+        }
+        Ok(size)
+    }
+
+    /// The `.meta.json` that can hold the size of `path`; none for adopted
+    /// JDKs, system JDKs and JDKs installed without metadata
+    fn size_cache_file(&self, path: &Path) -> Option<PathBuf> {
+        let jdks_dir = self.config.jdks_dir().ok()?;
+        if !path.starts_with(&jdks_dir) || path.is_symlink() {
+            return None;
+        }
+        let slug = installation_slug(path).ok()?;
+        let metadata_path = install::metadata_file(self.config.kopi_home(), slug);
+        metadata_path.is_file().then_some(metadata_path)
     }
 
     pub fn remove_jdk(&self, path: &Path) -> Result<()> {
         let jdks_dir = self.config.jdks_dir()?;
+        if self
+            .system_jdks_dir(&jdks_dir)
+            .is_some_and(|system_dir| path.starts_with(system_dir))
+        {
+            return Err(KopiError::ValidationError(format!(
+                "{} is a read-only system JDK",
+                path.display()
+            )));
+        }
         if !path.starts_with(&jdks_dir) {
             return Err(KopiError::SecurityError(format!(
                 "Refusing to remove directory outside of JDKs directory: {path:?}"
             )));
         }
 
-        fs::remove_dir_all(path)?;
+        // Adopted JDKs are links to files kopi does not own
+        if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+            debug!("Removing link to adopted JDK at {path:?}");
+            symlink::remove_dir_link(path)?;
+            return Ok(());
+        }
+
+        fs::remove_dir_all(file_ops::extended_length_path(path))?;
+        Ok(())
+    }
+
+    /// Record the file manifest of a freshly installed JDK for later
+    /// integrity checks
+    pub fn save_install_manifest(&self, installed_path: &Path) -> Result<()> {
+        let slug = installation_slug(installed_path)?;
+        let manifest = InstallManifest::capture(installed_path)?;
+        manifest.save(&install::manifest_file(self.config.kopi_home(), slug))
+    }
+
+    /// Load the manifest captured when `installed` was installed, if any
+    pub fn load_install_manifest(
+        &self,
+        installed: &InstalledJdk,
+    ) -> Result<Option<InstallManifest>> {
+        let slug = installation_slug(&installed.path)?;
+        let path = install::manifest_file(self.config.kopi_home(), slug);
+        if !path.exists() {
+            return Ok(None);
+        }
+        InstallManifest::load(&path).map(Some)
+    }
+
+    /// Store a downloaded companion archive (sources, javadoc) next to
+    /// `installed_path`, replacing any earlier copy
+    pub fn save_companion_archive(
+        &self,
+        installed_path: &Path,
+        kind: PackageType,
+        archive_type: ArchiveType,
+        archive: &Path,
+    ) -> Result<PathBuf> {
+        self.save_archive(installed_path, &kind.to_string(), archive_type, archive)
+    }
+
+    /// The `kind` companion archive installed next to `installed_path`, if any
+    pub fn find_companion_archive(
+        &self,
+        installed_path: &Path,
+        kind: PackageType,
+    ) -> Result<Option<PathBuf>> {
+        self.find_archive(installed_path, &kind.to_string())
+    }
+
+    /// Keep the verified archive `installed_path` was extracted from next to
+    /// it, so the JDK can be reinstalled without downloading it again
+    pub fn save_install_archive(
+        &self,
+        installed_path: &Path,
+        archive_type: ArchiveType,
+        archive: &Path,
+    ) -> Result<PathBuf> {
+        self.save_archive(installed_path, INSTALL_ARCHIVE_KIND, archive_type, archive)
+    }
+
+    /// The archive kept by `kopi install --keep-archive` for `installed_path`,
+    /// if any. It outlives the installation when uninstalled with
+    /// `--keep-archives`.
+    pub fn find_install_archive(&self, installed_path: &Path) -> Result<Option<PathBuf>> {
+        self.find_archive(installed_path, INSTALL_ARCHIVE_KIND)
+    }
+
+    fn save_archive(
+        &self,
+        installed_path: &Path,
+        kind: &str,
+        archive_type: ArchiveType,
+        archive: &Path,
+    ) -> Result<PathBuf> {
+        let slug = installation_slug(installed_path)?;
+        if let Some(existing) = self.find_archive(installed_path, kind)? {
+            fs::remove_file(existing)?;
+        }
+
+        let target = install::companion_file(
+            self.config.kopi_home(),
+            slug,
+            kind,
+            archive_type.extension(),
+        );
+        fs::copy(archive, &target).map_err(|e| {
+            KopiError::SystemError(format!(
+                "Failed to store {kind} archive at {}: {e}",
+                target.display()
+            ))
+        })?;
+        Ok(target)
+    }
+
+    fn find_archive(&self, installed_path: &Path, kind: &str) -> Result<Option<PathBuf>> {
+        let slug = installation_slug(installed_path)?;
+        Ok([ArchiveType::Zip, ArchiveType::TarGz]
+            .iter()
+            .map(|archive_type| {
+                install::companion_file(
+                    self.config.kopi_home(),
+                    slug,
+                    kind,
+                    archive_type.extension(),
+                )
+            })
+            .find(|path| path.is_file()))
+    }
+
+    /// Whether `installed` was protected with `kopi pin-protect`
+    pub fn is_protected(&self, installed: &InstalledJdk) -> bool {
+        self.metadata_flag(installed, PROTECTED_KEY)
+    }
+
+    /// Whether `installed` was adopted with `kopi adopt` and lives outside
+    /// the JDKs directory
+    pub fn is_external(&self, installed: &InstalledJdk) -> bool {
+        self.metadata_flag(installed, EXTERNAL_KEY)
+            || fs::symlink_metadata(&installed.path)
+                .is_ok_and(|metadata| metadata.file_type().is_symlink())
+    }
+
+    fn metadata_flag(&self, installed: &InstalledJdk, key: &str) -> bool {
+        let Ok(slug) = installation_slug(&installed.path) else {
+            return false;
+        };
+        fs::read_to_string(install::metadata_file(self.config.kopi_home(), slug))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Value>(&contents).ok())
+            .and_then(|metadata| metadata.get(key)?.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Register the JDK at `java_home` as `slug` by linking to it from the
+    /// JDKs directory, without copying any files.
+    ///
+    /// The `.meta.json` is flagged as external so uninstall only removes the
+    /// link.
+    pub fn adopt_external(
+        &self,
+        slug: &str,
+        java_home: &Path,
+        implementor: Option<&str>,
+    ) -> Result<PathBuf> {
+        let kopi_home = self.config.kopi_home();
+        install::ensure_installations_root(kopi_home)?;
+        let link = install::installation_directory(kopi_home, slug);
+        if fs::symlink_metadata(&link).is_ok() {
+            return Err(KopiError::ValidationError(format!(
+                "{slug} is already installed at {}",
+                link.display()
+            )));
+        }
+
+        symlink::create_dir_link(java_home, &link).map_err(|e| {
+            KopiError::SystemError(format!(
+                "Failed to link {} to {}: {e}",
+                link.display(),
+                java_home.display()
+            ))
+        })?;
+
+        let installation_metadata = InstallationMetadata {
+            java_home_suffix: String::new(),
+            structure_type: "direct".to_string(),
+            platform: format!("{}_{}", get_current_os(), get_current_architecture()),
+            metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
+        };
+        let mut metadata = serde_json::json!({
+            EXTERNAL_KEY: true,
+            "external_path": java_home,
+            "installation_metadata": installation_metadata,
+        });
+        if let Some(implementor) = implementor {
+            metadata["implementor"] = Value::from(implementor);
+        }
+
+        let metadata_path = install::metadata_file(kopi_home, slug);
+        let written = serde_json::to_string_pretty(&metadata)
+            .map_err(KopiError::from)
+            .and_then(|json| Ok(fs::write(&metadata_path, format!("{json}\n"))?));
+        if let Err(e) = written {
+            let _ = symlink::remove_dir_link(&link);
+            return Err(e);
+        }
+
+        debug!("Adopted {} as {slug}", java_home.display());
+        Ok(link)
+    }
+
+    /// Set or clear the protection flag in the `.meta.json` of `installed`
+    pub fn set_protected(&self, installed: &InstalledJdk, protected: bool) -> Result<()> {
+        let slug = installation_slug(&installed.path)?;
+        let metadata_path = install::metadata_file(self.config.kopi_home(), slug);
+        let contents = match fs::read_to_string(&metadata_path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(KopiError::NotFound(format!(
+                    "No metadata recorded for {}@{}. Reinstall it with 'kopi install --force' \
+                     to enable protection",
+                    installed.distribution, installed.version
+                )));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut metadata: Value = serde_json::from_str(&contents)?;
+        let fields = metadata.as_object_mut().ok_or_else(|| {
+            KopiError::SystemError(format!(
+                "Metadata file {} is not a JSON object",
+                metadata_path.display()
+            ))
+        })?;
+        if protected {
+            fields.insert(PROTECTED_KEY.to_string(), Value::Bool(true));
+        } else {
+            fields.remove(PROTECTED_KEY);
+        }
+
+        replace_metadata(&metadata_path, &metadata)?;
+        debug!(
+            "Set protected={protected} for {}@{}",
+            installed.distribution, installed.version
+        );
         Ok(())
     }
 
@@ -271,32 +649,38 @@ impl<'a> JdkRepository<'a> {
         // Filter JDKs based on distribution, version pattern, and JavaFX
         let mut matching_jdks: Vec<InstalledJdk> = all_jdks
             .into_iter()
-            .filter(|jdk| {
-                // Check distribution filter if specified
-                if let Some(dist) = &request.distribution
-                    && &jdk.distribution != dist
-                {
-                    return false;
-                }
-
-                // Check JavaFX filter if specified
-                if let Some(javafx) = request.javafx_bundled
-                    && jdk.javafx_bundled != javafx
-                {
-                    return false;
-                }
-
-                // Check version pattern
-                jdk.version.matches_pattern(&request.version_pattern)
-            })
+            .filter(|jdk| matching::matches_installed(request, jdk))
             .collect();
 
         // Sort by version (oldest first)
         // When versions are equal, maintain stable sort order (which preserves distribution order from list_installed_jdks)
-        matching_jdks.sort_by(|a, b| a.version.cmp(&b.version));
+        // Tracks rank GA builds above early-access builds of the same version
+        if request.track.is_some() {
+            matching_jdks.sort_by(|a, b| VersionTrack::compare(&a.version, &b.version));
+        } else {
+            matching_jdks.sort_by(|a, b| a.version.cmp(&b.version));
+        }
 
         Ok(matching_jdks)
     }
+
+    /// Installed JDKs to suggest when nothing matches `request`: those of the
+    /// requested distribution or feature release, newest first
+    pub fn pin_candidates(&self, request: &VersionRequest) -> Result<Vec<InstalledJdk>> {
+        let major = Version::from_str(&request.version_pattern)
+            .ok()
+            .map(|version| version.major());
+        let mut candidates: Vec<InstalledJdk> = self
+            .list_installed_jdks()?
+            .into_iter()
+            .filter(|jdk| {
+                request.distribution.as_deref() == Some(jdk.distribution.as_str())
+                    || major == Some(jdk.version.major())
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.version.cmp(&a.version));
+        Ok(candidates)
+    }
 }
 
 #[derive(Debug, Default)]
@@ -321,6 +705,55 @@ impl InstalledMetadataSnapshot {
     }
 }
 
+/// Directory name of an installation, which names its sidecar files
+/// Write `metadata` to `metadata_path`, replacing the file so the JDKs
+/// directory's mtime reflects the change
+fn replace_metadata(metadata_path: &Path, metadata: &Value) -> Result<()> {
+    let temp_path = metadata_path.with_extension("tmp");
+    fs::write(&temp_path, serde_json::to_string_pretty(metadata)?)?;
+    fs::rename(&temp_path, metadata_path)?;
+    Ok(())
+}
+
+/// Modification time of an installation directory as `[seconds, nanoseconds]`
+/// since the Unix epoch
+fn directory_modified(path: &Path) -> Option<Value> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let since_epoch = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some(serde_json::json!([
+        since_epoch.as_secs(),
+        since_epoch.subsec_nanos()
+    ]))
+}
+
+fn read_cached_size(metadata_path: &Path, modified: &Value) -> Option<u64> {
+    let contents = fs::read_to_string(metadata_path).ok()?;
+    let metadata: Value = serde_json::from_str(&contents).ok()?;
+    let cache = metadata.get(SIZE_KEY)?;
+    if cache.get("modified")? != modified {
+        return None;
+    }
+    cache.get("bytes")?.as_u64()
+}
+
+fn write_cached_size(metadata_path: &Path, size: u64, modified: Value) -> Result<()> {
+    let mut metadata: Value = serde_json::from_str(&fs::read_to_string(metadata_path)?)?;
+    let Some(fields) = metadata.as_object_mut() else {
+        return Ok(());
+    };
+    fields.insert(
+        SIZE_KEY.to_string(),
+        serde_json::json!({ "bytes": size, "modified": modified }),
+    );
+    replace_metadata(metadata_path, &metadata)
+}
+
+fn installation_slug(path: &Path) -> Result<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| KopiError::ValidationError(format!("Invalid installation path: {path:?}")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -377,6 +810,108 @@ mod tests {
         assert!(matches!(result.unwrap_err(), KopiError::SecurityError(_)));
     }
 
+    #[test]
+    fn test_system_jdks_layered_under_user_jdks() {
+        let mut test_storage = TestStorage::new();
+        let system_dir = test_storage._temp_dir.path().join("system");
+        fs::create_dir_all(system_dir.join("temurin-21.0.5")).unwrap();
+        fs::create_dir_all(system_dir.join("temurin-17.0.13")).unwrap();
+        test_storage.config.storage.system_jdks_dir = Some(system_dir.clone());
+        let manager = test_storage.manager();
+        let jdks_dir = manager.jdks_dir().unwrap();
+        fs::create_dir_all(jdks_dir.join("temurin-21.0.5")).unwrap();
+        fs::create_dir_all(jdks_dir.join("corretto-21.0.5")).unwrap();
+
+        let installed = manager.list_installed_jdks().unwrap();
+        let listed: Vec<_> = installed
+            .iter()
+            .map(|jdk| (jdk.distribution.as_str(), jdk.version.to_string()))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("corretto", "21.0.5".to_string()),
+                ("temurin", "21.0.5".to_string()),
+                ("temurin", "17.0.13".to_string()),
+            ]
+        );
+        // The user install shadows the system one
+        assert_eq!(installed[1].path, jdks_dir.join("temurin-21.0.5"));
+        assert!(!manager.is_system(&installed[1]));
+        assert!(manager.is_system(&installed[2]));
+
+        let result = manager.remove_jdk(&installed[2].path);
+        assert!(matches!(result, Err(KopiError::ValidationError(_))));
+        assert!(system_dir.join("temurin-17.0.13").exists());
+    }
+
+    #[test]
+    fn test_save_and_find_companion_archive() {
+        let test_storage = TestStorage::new();
+        let manager = test_storage.manager();
+        let jdk_path = manager
+            .jdk_install_path(&Distribution::Temurin, "21.0.1+35.1", false)
+            .unwrap();
+        fs::create_dir_all(&jdk_path).unwrap();
+
+        assert_eq!(
+            manager
+                .find_companion_archive(&jdk_path, PackageType::Sources)
+                .unwrap(),
+            None
+        );
+
+        let download = test_storage._temp_dir.path().join("src.zip");
+        fs::write(&download, b"sources").unwrap();
+        let stored = manager
+            .save_companion_archive(&jdk_path, PackageType::Sources, ArchiveType::Zip, &download)
+            .unwrap();
+
+        assert!(stored.ends_with("jdks/temurin-21.0.1+35.1.sources.zip"));
+        assert_eq!(
+            manager
+                .find_companion_archive(&jdk_path, PackageType::Sources)
+                .unwrap(),
+            Some(stored)
+        );
+        assert_eq!(
+            manager
+                .find_companion_archive(&jdk_path, PackageType::Javadoc)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_install_archive_outlives_installation() {
+        let test_storage = TestStorage::new();
+        let manager = test_storage.manager();
+        let jdk_path = manager
+            .jdk_install_path(&Distribution::Temurin, "21.0.1+35.1", false)
+            .unwrap();
+        fs::create_dir_all(&jdk_path).unwrap();
+
+        let download = test_storage._temp_dir.path().join("jdk.tar.gz");
+        fs::write(&download, b"archive").unwrap();
+        let stored = manager
+            .save_install_archive(&jdk_path, ArchiveType::TarGz, &download)
+            .unwrap();
+        assert!(stored.ends_with("jdks/temurin-21.0.1+35.1.archive.tar.gz"));
+
+        // The archive is found by slug, so a later reinstall can reuse it
+        manager.remove_jdk(&jdk_path).unwrap();
+        assert_eq!(
+            manager.find_install_archive(&jdk_path).unwrap(),
+            Some(stored)
+        );
+        assert_eq!(
+            manager
+                .find_companion_archive(&jdk_path, PackageType::Sources)
+                .unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_min_disk_space_from_config() {
         let temp_dir = TempDir::new().unwrap();
@@ -554,6 +1089,33 @@ min_disk_space_mb = 1024
         assert_eq!(matches.len(), 0);
     }
 
+    #[test]
+    fn test_find_matching_jdks_for_track() {
+        let test_storage = TestStorage::new();
+        let manager = test_storage.manager();
+        let jdks_dir = test_storage.config.jdks_dir().unwrap();
+
+        fs::create_dir_all(jdks_dir.join("temurin-17.0.9")).unwrap();
+        fs::create_dir_all(jdks_dir.join("temurin-21.0.5")).unwrap();
+        fs::create_dir_all(jdks_dir.join("temurin-23.0.1")).unwrap();
+        fs::create_dir_all(jdks_dir.join("temurin-25-ea")).unwrap();
+
+        let request = VersionRequest::new("latest-lts".to_string()).unwrap();
+        let matches = manager.find_matching_jdks(&request).unwrap();
+        let versions: Vec<String> = matches.iter().map(|j| j.version.to_string()).collect();
+        assert_eq!(versions, vec!["17.0.9", "21.0.5"]);
+
+        let request = VersionRequest::new("latest-ga".to_string()).unwrap();
+        let matches = manager.find_matching_jdks(&request).unwrap();
+        assert_eq!(matches.last().unwrap().version.to_string(), "23.0.1");
+
+        fs::create_dir_all(jdks_dir.join("temurin-25")).unwrap();
+        let request = VersionRequest::new("25-latest".to_string()).unwrap();
+        let matches = manager.find_matching_jdks(&request).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches.last().unwrap().version.to_string(), "25");
+    }
+
     #[test]
     fn test_find_matching_jdks_sorting() {
         let test_storage = TestStorage::new();
@@ -597,6 +1159,83 @@ min_disk_space_mb = 1024
         assert!(result.is_err());
     }
 
+    #[test]
+    fn set_protected_round_trips_through_metadata_file() {
+        let test_storage = TestStorage::new();
+        let manager = test_storage.manager();
+        let jdks_dir = test_storage.config.jdks_dir().unwrap();
+
+        let slug = "temurin-17.0.9";
+        let install_path = jdks_dir.join(slug);
+        fs::create_dir_all(&install_path).unwrap();
+        let installed = InstalledJdk::new(
+            "temurin".to_string(),
+            Version::from_str("17.0.9").unwrap(),
+            install_path,
+            false,
+        );
+
+        let result = manager.set_protected(&installed, true);
+        assert!(matches!(result, Err(KopiError::NotFound(_))));
+
+        let metadata_path = install::metadata_file(test_storage.config.kopi_home(), slug);
+        fs::write(
+            &metadata_path,
+            r#"{"id": "pkg-id", "installation_metadata": {}}"#,
+        )
+        .unwrap();
+        assert!(!manager.is_protected(&installed));
+
+        manager.set_protected(&installed, true).unwrap();
+        assert!(manager.is_protected(&installed));
+        let metadata: Value =
+            serde_json::from_str(&fs::read_to_string(&metadata_path).unwrap()).unwrap();
+        assert_eq!(metadata["id"], "pkg-id");
+
+        manager.set_protected(&installed, false).unwrap();
+        assert!(!manager.is_protected(&installed));
+        let metadata: Value =
+            serde_json::from_str(&fs::read_to_string(&metadata_path).unwrap()).unwrap();
+        assert!(metadata.get("protected").is_none());
+    }
+
+    #[test]
+    fn jdk_size_is_cached_until_the_directory_changes() {
+        let test_storage = TestStorage::new();
+        let manager = test_storage.manager();
+        let jdks_dir = test_storage.config.jdks_dir().unwrap();
+
+        let slug = "temurin-21.0.5";
+        let install_path = jdks_dir.join(slug);
+        fs::create_dir_all(install_path.join("lib")).unwrap();
+        fs::write(install_path.join("lib/modules"), vec![0u8; 100]).unwrap();
+        let set_modified = |secs: u64| {
+            fs::File::open(&install_path)
+                .unwrap()
+                .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+                .unwrap();
+        };
+        set_modified(1_000_000);
+
+        // Without a .meta.json there is nowhere to cache the size
+        assert_eq!(manager.get_jdk_size(&install_path).unwrap(), 100);
+        let metadata_path = install::metadata_file(test_storage.config.kopi_home(), slug);
+        fs::write(&metadata_path, r#"{"id": "pkg-id"}"#).unwrap();
+
+        assert_eq!(manager.get_jdk_size(&install_path).unwrap(), 100);
+        let metadata: Value =
+            serde_json::from_str(&fs::read_to_string(&metadata_path).unwrap()).unwrap();
+        assert_eq!(metadata["size_cache"]["bytes"], 100);
+        assert_eq!(metadata["id"], "pkg-id");
+
+        // Nested changes keep the directory's mtime, so the cache still answers
+        fs::write(install_path.join("lib/modules"), vec![0u8; 150]).unwrap();
+        assert_eq!(manager.get_jdk_size(&install_path).unwrap(), 100);
+
+        set_modified(2_000_000);
+        assert_eq!(manager.get_jdk_size(&install_path).unwrap(), 150);
+    }
+
     #[test]
     fn load_installed_metadata_returns_complete_snapshot() {
         let test_storage = TestStorage::new();
@@ -636,9 +1275,11 @@ min_disk_space_mb = 1024
             lib_c_type: Some("gnu".to_string()),
             package_type: "JDK".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         };
 
         let installation_metadata = InstallationMetadata {
@@ -646,6 +1287,9 @@ min_disk_space_mb = 1024
             structure_type: "direct".to_string(),
             platform: "linux_x64".to_string(),
             metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
         };
 
         let complete_metadata = JdkMetadataWithInstallation {
@@ -751,9 +1395,11 @@ min_disk_space_mb = 1024
             lib_c_type: None,
             package_type: "jdk".to_string(),
             javafx_bundled: false,
+            feature: Vec::new(),
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         };
 
         let installation_metadata = crate::storage::InstallationMetadata {
@@ -761,6 +1407,9 @@ min_disk_space_mb = 1024
             structure_type: "bundle".to_string(),
             platform: "macos_aarch64".to_string(),
             metadata_version: 1,
+            stripped: Vec::new(),
+            release: None,
+            pinned_sha256: None,
         };
 
         // Save metadata with installation info
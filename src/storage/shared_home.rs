@@ -0,0 +1,208 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Kopi home shared by every user of a machine (`storage.shared_home`).
+//!
+//! An administrator installs JDKs into the shared home, typically a
+//! root-owned `/opt/kopi`, and every user's kopi lists them beneath their
+//! own through [`KopiConfig::system_jdks_dir`]. Its lock directories are
+//! group-writable and set-group-ID so members of the owning group can take
+//! the same locks as root.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::paths::home;
+use log::debug;
+use std::fs;
+use std::path::Path;
+
+/// Group permission bits (rwx and set-group-ID) of shared lock directories
+#[cfg(unix)]
+pub const SHARED_LOCK_DIR_BITS: u32 = 0o2070;
+/// Group permission bits (rw) of shared lock files
+#[cfg(unix)]
+const SHARED_LOCK_FILE_BITS: u32 = 0o060;
+
+/// Configuration for installing into the shared home, or `None` when no
+/// shared home is configured or it is already this configuration's home
+pub fn install_config(config: &KopiConfig) -> Option<KopiConfig> {
+    let shared_home = config.shared_home()?;
+    if shared_home == config.kopi_home() {
+        return None;
+    }
+    Some(config.with_kopi_home(shared_home))
+}
+
+/// Make sure this process may install into `shared_home`, creating its
+/// layout when allowed; fails with [`KopiError::ElevationRequired`] when
+/// the JDK directory is not writable
+pub fn ensure_install_access(shared_home: &Path) -> Result<()> {
+    match prepare_layout(shared_home) {
+        Ok(()) if is_writable(&home::jdks_dir(shared_home)) => Ok(()),
+        Ok(()) => Err(elevation_required(shared_home)),
+        Err(KopiError::Io(e)) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            debug!(
+                "Cannot prepare shared Kopi home {}: {e}",
+                shared_home.display()
+            );
+            Err(elevation_required(shared_home))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Create the shared home's JDK, cache and lock directories and open the
+/// lock directories to the owning group
+pub fn prepare_layout(shared_home: &Path) -> Result<()> {
+    fs::create_dir_all(home::jdks_dir(shared_home))?;
+    fs::create_dir_all(home::cache_dir(shared_home))?;
+    fs::create_dir_all(home::locks_dir(shared_home))?;
+    share_locks(shared_home)
+}
+
+/// Give the owning group write access to every lock directory and lock
+/// file of `shared_home`, including those created since the last call
+pub fn share_locks(shared_home: &Path) -> Result<()> {
+    let locks = home::locks_dir(shared_home);
+    if locks.is_dir() {
+        #[cfg(unix)]
+        share_lock_tree(&locks)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn share_lock_tree(dir: &Path) -> Result<()> {
+    add_mode_bits(dir, SHARED_LOCK_DIR_BITS)?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            share_lock_tree(&entry.path())?;
+        } else if file_type.is_file() {
+            add_mode_bits(&entry.path(), SHARED_LOCK_FILE_BITS)?;
+        }
+    }
+    Ok(())
+}
+
+/// Set `bits` on `path`, leaving it untouched when they are already set so
+/// group members who do not own it can still run this
+#[cfg(unix)]
+fn add_mode_bits(path: &Path, bits: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)?.permissions().mode() & 0o7777;
+    if mode & bits != bits {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode | bits))?;
+    }
+    Ok(())
+}
+
+fn is_writable(dir: &Path) -> bool {
+    tempfile::Builder::new()
+        .prefix(".kopi-write-test")
+        .tempfile_in(dir)
+        .is_ok()
+}
+
+fn elevation_required(shared_home: &Path) -> KopiError {
+    let command = std::iter::once("kopi".to_string())
+        .chain(std::env::args().skip(1))
+        .collect::<Vec<_>>()
+        .join(" ");
+    KopiError::ElevationRequired {
+        shared_home: shared_home.display().to_string(),
+        command,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_install_config_targets_the_shared_home() {
+        let temp_dir = TempDir::new().unwrap();
+        let shared_home = temp_dir.path().join("shared");
+        let mut config = KopiConfig::new(temp_dir.path().join("user")).unwrap();
+        assert!(install_config(&config).is_none());
+
+        config.storage.shared_home = Some(shared_home.clone());
+        let shared = install_config(&config).unwrap();
+        assert_eq!(shared.kopi_home(), shared_home);
+        assert_eq!(config.system_jdks_dir(), Some(home::jdks_dir(&shared_home)));
+
+        // Administrators running with the shared home as KOPI_HOME install as usual
+        assert!(install_config(&shared).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_prepare_layout_shares_lock_directories() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let shared_home = temp_dir.path();
+        let lock_dir = home::locks_dir(shared_home).join("install/temurin");
+        fs::create_dir_all(&lock_dir).unwrap();
+        let lock_file = lock_dir.join("temurin-21.lock");
+        fs::write(&lock_file, "").unwrap();
+        fs::set_permissions(&lock_file, fs::Permissions::from_mode(0o644)).unwrap();
+
+        ensure_install_access(shared_home).unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+        assert!(home::jdks_dir(shared_home).is_dir());
+        assert_eq!(
+            mode(&home::locks_dir(shared_home)) & SHARED_LOCK_DIR_BITS,
+            SHARED_LOCK_DIR_BITS
+        );
+        assert_eq!(mode(&lock_dir) & SHARED_LOCK_DIR_BITS, SHARED_LOCK_DIR_BITS);
+        assert_eq!(mode(&lock_file), 0o664);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ensure_install_access_requires_a_writable_jdk_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let shared_home = temp_dir.path();
+        prepare_layout(shared_home).unwrap();
+        let jdks_dir = home::jdks_dir(shared_home);
+        fs::set_permissions(&jdks_dir, fs::Permissions::from_mode(0o555)).unwrap();
+
+        // Root can write regardless of the mode
+        let writable = is_writable(&jdks_dir);
+        let result = ensure_install_access(shared_home);
+        fs::set_permissions(&jdks_dir, fs::Permissions::from_mode(0o755)).unwrap();
+
+        if writable {
+            assert!(result.is_ok());
+        } else {
+            match result {
+                Err(KopiError::ElevationRequired {
+                    shared_home: home,
+                    command,
+                }) => {
+                    assert_eq!(home, shared_home.display().to_string());
+                    assert!(command.starts_with("kopi"));
+                }
+                other => panic!("expected ElevationRequired, got {other:?}"),
+            }
+        }
+    }
+}
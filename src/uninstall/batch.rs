@@ -13,10 +13,14 @@
 // limitations under the License.
 
 use crate::config::KopiConfig;
-use crate::error::{KopiError, Result};
+use crate::error::{BatchErrors, KopiError, Result};
+use crate::i18n::{t, t_with};
 use crate::indicator::{ProgressConfig, ProgressFactory, ProgressStyle, StatusReporter};
-use crate::locking::{InstalledScopeResolver, LockBackend, LockController, ScopedPackageLockGuard};
+use crate::locking::{
+    InstalledScopeResolver, LockBackend, LockController, ScopedPackageLockGuard, global_token,
+};
 use crate::models::distribution::Distribution;
+use crate::platform;
 use crate::storage::formatting::format_size;
 use crate::storage::{InstalledJdk, JdkRepository};
 use crate::uninstall::feedback::{
@@ -49,12 +53,12 @@ impl<'a> BatchUninstaller<'a> {
 
     pub fn uninstall_all(&self, spec: Option<&str>, force: bool, dry_run: bool) -> Result<()> {
         let jdks = if let Some(spec_str) = spec {
-            // Build the list of all known distributions (built-in + additional)
+            // Build the list of all known distributions (built-in + additional + custom)
             let mut all_distributions: Vec<String> = Distribution::known_distributions()
                 .into_iter()
                 .map(|s| s.to_string())
                 .collect();
-            all_distributions.extend(self.config.additional_distributions.clone());
+            all_distributions.extend(self.config.extra_distribution_names().map(String::from));
 
             // Check if spec is a distribution name (case-insensitive)
             let is_distribution = all_distributions
@@ -101,6 +105,27 @@ impl<'a> BatchUninstaller<'a> {
         force: bool,
         dry_run: bool,
     ) -> Result<()> {
+        // System JDKs always stay; protected ones stay unless --force is given
+        let (system, jdks): (Vec<_>, Vec<_>) = jdks
+            .into_iter()
+            .partition(|jdk| self.repository.is_system(jdk));
+        for jdk in &system {
+            println!(
+                "Skipping read-only system JDK {}@{}",
+                jdk.distribution, jdk.version
+            );
+        }
+
+        let (protected, jdks): (Vec<_>, Vec<_>) = jdks
+            .into_iter()
+            .partition(|jdk| !force && self.repository.is_protected(jdk));
+        for jdk in &protected {
+            println!(
+                "Skipping protected {}@{} (use --force to remove it)",
+                jdk.distribution, jdk.version
+            );
+        }
+
         if jdks.is_empty() {
             return Ok(());
         }
@@ -143,7 +168,7 @@ impl<'a> BatchUninstaller<'a> {
         }
         let reporter = StatusReporter::with_shared_indicator(lock_feedback.clone());
 
-        reporter.operation("JDKs to be removed", "");
+        reporter.operation(&t("status.uninstall.batch_header"), "");
 
         for jdk in jdks {
             let size = self.repository.get_jdk_size(&jdk.path)?;
@@ -155,10 +180,12 @@ impl<'a> BatchUninstaller<'a> {
             ));
         }
 
-        reporter.step(&format!(
-            "Total: {} JDKs, {}",
-            jdks.len(),
-            format_size(total_size)
+        reporter.step(&t_with(
+            "status.uninstall.batch_total",
+            &[
+                ("count", &jdks.len().to_string()),
+                ("size", &format_size(total_size)),
+            ],
         ));
 
         Ok(())
@@ -178,8 +205,7 @@ impl<'a> BatchUninstaller<'a> {
         let mut progress_reporter = ProgressReporter::new_batch(self.no_progress);
         let overall_pb = progress_reporter.create_batch_removal_bar(jdks.len() as u64);
 
-        let mut removed_count = 0;
-        let mut failed_jdks = Vec::new();
+        let mut batch = BatchErrors::new("uninstall");
         let mut removed_jdks = Vec::new();
         let mut log_messages = Vec::new(); // Collect log messages to output after progress
 
@@ -193,8 +219,15 @@ impl<'a> BatchUninstaller<'a> {
             &self.config.locking,
         );
         let scope_resolver = InstalledScopeResolver::new(self.repository);
+        let cancellation = global_token();
+        let mut interrupted = false;
 
         for jdk in &jdks {
+            // Finish the JDK being removed, but start no new removal after Ctrl+C
+            if cancellation.is_cancelled() {
+                interrupted = true;
+                break;
+            }
             log_messages.push(format!("Removing {}@{}", jdk.distribution, jdk.version));
 
             // Update overall progress bar message for current JDK
@@ -205,7 +238,10 @@ impl<'a> BatchUninstaller<'a> {
                 let scope_label = scope.label().to_string();
 
                 progress_reporter.suspend(|| {
-                    reporter.step(&format!("Acquiring uninstall lock for {scope_label}"));
+                    reporter.step(&t_with(
+                        "status.uninstall.acquiring_lock",
+                        &[("scope", &scope_label.to_string())],
+                    ));
                 });
                 let acquisition =
                     controller.acquire_with_feedback(scope.clone(), lock_feedback.clone())?;
@@ -216,7 +252,13 @@ impl<'a> BatchUninstaller<'a> {
                 };
 
                 progress_reporter.suspend(|| {
-                    reporter.step(&format!("Using {backend_label} backend for {scope_label}"));
+                    reporter.step(&t_with(
+                        "status.uninstall.lock_backend",
+                        &[
+                            ("backend", backend_label),
+                            ("scope", &scope_label.to_string()),
+                        ],
+                    ));
                 });
                 info!("Acquired uninstall lock for {scope_label} using {backend_label} backend");
 
@@ -228,6 +270,20 @@ impl<'a> BatchUninstaller<'a> {
                 )?;
 
                 if force && active_summary.has_active_use() {
+                    if active_summary.protected {
+                        warn!(
+                            "--force removing protected {}@{}",
+                            jdk.distribution, jdk.version
+                        );
+                        log_messages.push(format!(
+                            "Force removing protected {}@{}",
+                            jdk.distribution, jdk.version
+                        ));
+                        progress_reporter.suspend(|| {
+                            reporter.step(&t("status.uninstall.force_protected"));
+                        });
+                    }
+
                     if let Some(global) = &active_summary.global {
                         warn!(
                             "--force removing {}@{} despite active global configuration {}",
@@ -238,8 +294,9 @@ impl<'a> BatchUninstaller<'a> {
                             global, jdk.distribution, jdk.version
                         ));
                         progress_reporter.suspend(|| {
-                            reporter.step(&format!(
-                                "Proceeding with --force: global default set via {global}"
+                            reporter.step(&t_with(
+                                "status.uninstall.force_global",
+                                &[("source", &global.to_string())],
                             ));
                         });
                     }
@@ -254,8 +311,26 @@ impl<'a> BatchUninstaller<'a> {
                             project, jdk.distribution, jdk.version
                         ));
                         progress_reporter.suspend(|| {
-                            reporter.step(&format!(
-                                "Proceeding with --force: project default set via {project}"
+                            reporter.step(&t_with(
+                                "status.uninstall.force_project",
+                                &[("source", &project.to_string())],
+                            ));
+                        });
+                    }
+
+                    for recent in &active_summary.recent_projects {
+                        warn!(
+                            "--force removing {}@{} despite recently used project configuration {}",
+                            jdk.distribution, jdk.version, recent
+                        );
+                        log_messages.push(format!(
+                            "Force removing JDK pinned by recently used project via {} for {}@{}",
+                            recent, jdk.distribution, jdk.version
+                        ));
+                        progress_reporter.suspend(|| {
+                            reporter.step(&t_with(
+                                "status.uninstall.force_recent",
+                                &[("source", &recent.to_string())],
                             ));
                         });
                     }
@@ -269,9 +344,11 @@ impl<'a> BatchUninstaller<'a> {
 
             overall_pb.inc(1);
 
+            let label = format!("{}@{}", jdk.distribution, jdk.version);
             match removal_result {
                 Ok(()) => {
-                    removed_count += 1;
+                    platform::macos::forget(jdk);
+                    batch.record_success(label);
                     removed_jdks.push(jdk.clone());
                 }
                 Err(err) => {
@@ -284,7 +361,7 @@ impl<'a> BatchUninstaller<'a> {
                         "Failed to remove {}@{}: {err_string}",
                         jdk.distribution, jdk.version
                     );
-                    failed_jdks.push((jdk.clone(), err));
+                    batch.record_failure(label, err);
                 }
             }
         }
@@ -293,13 +370,16 @@ impl<'a> BatchUninstaller<'a> {
 
         // Show status messages after progress bar is done using StatusReporter
         for jdk in &removed_jdks {
-            reporter.success(&format!("Removed {}@{}", jdk.distribution, jdk.version));
+            reporter.success(&t_with(
+                "status.uninstall.removed",
+                &[("jdk", &format!("{}@{}", jdk.distribution, jdk.version))],
+            ));
         }
 
-        for (jdk, _) in &failed_jdks {
-            reporter.error(&format!(
-                "Failed to remove {}@{}",
-                jdk.distribution, jdk.version
+        for failure in batch.failures() {
+            reporter.error(&t_with(
+                "status.uninstall.remove_failed",
+                &[("jdk", &failure.item)],
             ));
         }
 
@@ -313,22 +393,18 @@ impl<'a> BatchUninstaller<'a> {
             }
         }
 
-        // Report results
-        let failed_with_messages: Vec<(InstalledJdk, String)> = failed_jdks
-            .into_iter()
-            .map(|(jdk, err)| (jdk, err.to_string()))
-            .collect();
-
-        display_batch_uninstall_summary(&removed_jdks, &failed_with_messages, total_size);
+        display_batch_uninstall_summary(&removed_jdks, batch.failures(), total_size);
 
-        // Return error if all removals failed
-        if removed_count == 0 {
-            return Err(KopiError::SystemError(
-                "All JDK removals failed".to_string(),
-            ));
+        if interrupted {
+            return Err(KopiError::Cancelled(format!(
+                "uninstall stopped after {} of {} JDKs",
+                removed_jdks.len(),
+                jdks.len()
+            )));
         }
 
-        Ok(())
+        // Failed removals did not stop the batch; report them all at once
+        batch.into_result()
     }
 }
 
@@ -437,10 +513,18 @@ mod tests {
             true,
             false,
         );
-        assert!(
-            result.is_ok(),
-            "batch uninstall should continue despite lock contention"
-        );
+        match result {
+            Err(KopiError::BatchFailed {
+                succeeded,
+                failures,
+                ..
+            }) => {
+                assert_eq!(succeeded, ["corretto@17.0.9"]);
+                assert_eq!(failures.len(), 1);
+                assert_eq!(failures[0].item, "temurin@21.0.5+11");
+            }
+            other => panic!("Expected partial batch failure, got {other:?}"),
+        }
         assert!(
             locked_jdk.path.exists(),
             "locked JDK should remain when lock acquisition times out"
@@ -12,14 +12,64 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::config::KopiConfig;
+use crate::download::DownloadCache;
 use crate::error::{KopiError, Result};
 use crate::locking::{InstalledScopeResolver, LockBackend, LockController, ScopedPackageLockGuard};
-use crate::paths::install;
+use crate::paths::{cache, install};
 use crate::platform;
 use crate::storage::{JdkLister, JdkRepository};
 use log::{debug, info, warn};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Temporary files younger than this may belong to a running install or
+/// download, so they are left alone
+pub const STALE_TEMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Metadata files written next to each installation directory
+const METADATA_SUFFIXES: [&str; 2] = [".meta.json", ".manifest.json"];
+
+/// Clean up leftovers of interrupted installs and uninstalls when
+/// `storage.cleanup_on_startup` is enabled. Only entries older than
+/// [`STALE_TEMP_AGE`] are touched, and partially removed JDKs are left for
+/// `kopi uninstall --cleanup`. Returns the number of entries removed.
+pub fn run_startup_cleanup(config: &KopiConfig) -> Result<usize> {
+    if !config.storage.cleanup_on_startup {
+        return Ok(0);
+    }
+
+    let repository = JdkRepository::new(config);
+    let cleanup = UninstallCleanup::new(&repository);
+    let now = SystemTime::now();
+    let actions: Vec<CleanupAction> = cleanup
+        .detect_and_cleanup_partial_removals()?
+        .into_iter()
+        .filter(|action| {
+            !matches!(action, CleanupAction::CompleteRemoval(_)) && is_stale(action.path(), now)
+        })
+        .collect();
+    if actions.is_empty() {
+        return Ok(0);
+    }
+
+    let result = cleanup.execute_cleanup(actions, false)?;
+    for failure in &result.failures {
+        warn!("Startup cleanup: {failure}");
+    }
+    info!("Startup cleanup: {}", result.summary());
+    Ok(result.successes.len())
+}
+
+fn is_stale(path: &Path, now: SystemTime) -> bool {
+    fs::symlink_metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| {
+            now.duration_since(modified)
+                .is_ok_and(|age| age >= STALE_TEMP_AGE)
+        })
+}
 
 /// Handles cleanup of failed uninstall operations
 pub struct UninstallCleanup<'a> {
@@ -37,11 +87,22 @@ impl<'a> UninstallCleanup<'a> {
 
         let mut cleanup_actions = Vec::new();
         let jdks_dir = self.repository.jdks_dir()?;
+        let now = SystemTime::now();
+
+        // Look for stale download temp files
+        for temp_file in self.find_stale_download_temps(now) {
+            cleanup_actions.push(CleanupAction::CleanupDownloadTemp(temp_file));
+        }
 
         if !jdks_dir.exists() {
             return Ok(cleanup_actions);
         }
 
+        // Look for extraction directories left by interrupted installs
+        for staging_dir in self.find_stale_staging_dirs(&jdks_dir, now) {
+            cleanup_actions.push(CleanupAction::CleanupStagingDir(staging_dir));
+        }
+
         // Look for temporary removal directories
         let temp_dirs = self.find_temp_removal_dirs(&jdks_dir)?;
         for temp_dir in temp_dirs {
@@ -175,11 +236,12 @@ impl<'a> UninstallCleanup<'a> {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if let Some(name) = path.file_name().and_then(|n| n.to_str())
-                    && name.ends_with(".meta.json")
+                    && let Some(jdk_name) = METADATA_SUFFIXES
+                        .iter()
+                        .find_map(|suffix| name.strip_suffix(suffix))
                 {
                     // Check if corresponding JDK directory exists
-                    let jdk_name = name.replace(".meta.json", "");
-                    let jdk_path = jdks_dir.join(&jdk_name);
+                    let jdk_path = jdks_dir.join(jdk_name);
                     if !jdk_path.exists() {
                         orphaned_metadata.push(path);
                     }
@@ -190,6 +252,39 @@ impl<'a> UninstallCleanup<'a> {
         Ok(orphaned_metadata)
     }
 
+    /// Extraction directories under `jdks/.tmp` and `jdks/.kopi-*` that
+    /// outlived the install that created them
+    fn find_stale_staging_dirs(&self, jdks_dir: &Path, now: SystemTime) -> Vec<PathBuf> {
+        let mut staging_dirs = Vec::new();
+
+        if let Ok(entries) = fs::read_dir(jdks_dir.join(install::TEMP_STAGING_DIR)) {
+            staging_dirs.extend(entries.flatten().map(|entry| entry.path()));
+        }
+        if let Ok(entries) = fs::read_dir(jdks_dir) {
+            staging_dirs.extend(entries.flatten().map(|entry| entry.path()).filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| name.starts_with(install::EXTRACT_STAGING_PREFIX))
+            }));
+        }
+
+        staging_dirs.retain(|path| is_stale(path, now));
+        staging_dirs
+    }
+
+    /// Unpublished download cache entries and files in `cache/tmp`
+    fn find_stale_download_temps(&self, now: SystemTime) -> Vec<PathBuf> {
+        let config = self.repository.config();
+        let mut temp_files = DownloadCache::new(config.download_cache_dir(), None).staging_dirs();
+
+        if let Ok(entries) = fs::read_dir(cache::temp_cache_directory(config.kopi_home())) {
+            temp_files.extend(entries.flatten().map(|entry| entry.path()));
+        }
+
+        temp_files.retain(|path| is_stale(path, now));
+        temp_files
+    }
+
     fn is_partial_removal(&self, path: &Path) -> Result<bool> {
         // Check if directory exists but is missing essential files
         if !path.is_dir() {
@@ -253,9 +348,34 @@ impl<'a> UninstallCleanup<'a> {
                     Ok(format!("Cleaned up orphaned metadata: {}", path.display()))
                 })
             }
+            CleanupAction::CleanupStagingDir(path) => {
+                info!("Cleaning up extraction directory: {}", path.display());
+                self.remove_leftover(&path, force)?;
+                Ok(format!(
+                    "Cleaned up extraction directory: {}",
+                    path.display()
+                ))
+            }
+            CleanupAction::CleanupDownloadTemp(path) => {
+                info!("Cleaning up download temp file: {}", path.display());
+                self.remove_leftover(&path, force)?;
+                Ok(format!("Cleaned up download temp file: {}", path.display()))
+            }
         }
     }
 
+    /// Remove a temporary file or directory that no installation lock covers
+    fn remove_leftover(&self, path: &Path, force: bool) -> Result<()> {
+        if !fs::symlink_metadata(path)?.is_dir() {
+            fs::remove_file(path)?;
+        } else if force {
+            self.force_cleanup_jdk(path)?;
+        } else {
+            fs::remove_dir_all(path)?;
+        }
+        Ok(())
+    }
+
     fn run_with_lock<F>(
         &self,
         path: &Path,
@@ -319,8 +439,11 @@ impl<'a> UninstallCleanup<'a> {
                 .trim_start_matches('.')
                 .trim_end_matches(".removing")
                 .to_string()
-        } else if file_name.ends_with(".meta.json") {
-            file_name.trim_end_matches(".meta.json").to_string()
+        } else if let Some(slug) = METADATA_SUFFIXES
+            .iter()
+            .find_map(|suffix| file_name.strip_suffix(suffix))
+        {
+            slug.to_string()
         } else if file_name.starts_with('.') {
             return Ok(None);
         } else {
@@ -396,6 +519,21 @@ pub enum CleanupAction {
     CleanupTempDir(PathBuf),
     CompleteRemoval(PathBuf),
     CleanupOrphanedMetadata(PathBuf),
+    CleanupStagingDir(PathBuf),
+    CleanupDownloadTemp(PathBuf),
+}
+
+impl CleanupAction {
+    /// The file or directory the action removes
+    pub fn path(&self) -> &Path {
+        match self {
+            CleanupAction::CleanupTempDir(path)
+            | CleanupAction::CompleteRemoval(path)
+            | CleanupAction::CleanupOrphanedMetadata(path)
+            | CleanupAction::CleanupStagingDir(path)
+            | CleanupAction::CleanupDownloadTemp(path) => path,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -486,6 +624,13 @@ mod tests {
         }
     }
 
+    fn backdate(path: &Path) {
+        fs::File::open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - STALE_TEMP_AGE * 2)
+            .unwrap();
+    }
+
     #[test]
     fn test_detect_temp_cleanup_dirs() {
         let setup = TestSetup::new();
@@ -644,4 +789,68 @@ mod tests {
 
         guard.release().unwrap();
     }
+
+    #[test]
+    fn test_detect_stale_install_and_download_temps() {
+        let setup = TestSetup::new();
+        let repository = JdkRepository::new(&setup.config);
+        let cleanup = UninstallCleanup::new(&repository);
+        let kopi_home = setup.config.kopi_home();
+
+        let install_temp = install::ensure_temp_staging_directory(kopi_home)
+            .unwrap()
+            .join("install-1");
+        let extract_temp = setup.config.jdks_dir().unwrap().join(".kopi-abc123");
+        let download_staging = setup.config.download_cache_dir().join(".staging-abc123");
+        let fresh_download = cache::ensure_temp_cache_directory(kopi_home)
+            .unwrap()
+            .join("fresh.part");
+        for dir in [&install_temp, &extract_temp, &download_staging] {
+            fs::create_dir_all(dir).unwrap();
+            backdate(dir);
+        }
+        fs::write(&fresh_download, b"partial").unwrap();
+        let manifest = setup
+            .config
+            .jdks_dir()
+            .unwrap()
+            .join("zulu-17.0.1.manifest.json");
+        fs::write(&manifest, "{}").unwrap();
+
+        let actions = cleanup.detect_and_cleanup_partial_removals().unwrap();
+        let paths: Vec<&Path> = actions.iter().map(CleanupAction::path).collect();
+        assert!(paths.contains(&install_temp.as_path()));
+        assert!(paths.contains(&extract_temp.as_path()));
+        assert!(paths.contains(&download_staging.as_path()));
+        assert!(paths.contains(&manifest.as_path()));
+        // Files of a download that may still be running are left alone
+        assert!(!paths.contains(&fresh_download.as_path()));
+
+        let result = cleanup.execute_cleanup(actions, false).unwrap();
+        assert!(result.is_success(), "{:?}", result.failures);
+        assert!(!install_temp.exists());
+        assert!(!extract_temp.exists());
+        assert!(!download_staging.exists());
+        assert!(fresh_download.exists());
+    }
+
+    #[test]
+    fn test_startup_cleanup_requires_opt_in_and_keeps_recent_entries() {
+        let mut setup = TestSetup::new();
+        let stale = setup.create_temp_removal_dir("temurin-21.0.1");
+        backdate(&stale);
+        let recent = setup.create_orphaned_metadata("corretto-17.0.1");
+        let partial = setup.create_partial_jdk("zulu-11.0.1");
+        backdate(&partial);
+
+        assert_eq!(run_startup_cleanup(&setup.config).unwrap(), 0);
+        assert!(stale.exists());
+
+        setup.config.storage.cleanup_on_startup = true;
+        assert_eq!(run_startup_cleanup(&setup.config).unwrap(), 1);
+        assert!(!stale.exists());
+        assert!(recent.exists());
+        // Partial removals need an explicit `kopi uninstall --cleanup`
+        assert!(partial.exists());
+    }
 }
@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::Result;
+use crate::error::{ItemFailure, Result, failure_table};
+use crate::i18n::{t, t_with};
 use crate::indicator::StatusReporter;
 use crate::storage::InstalledJdk;
 use crate::storage::formatting::format_size;
@@ -89,29 +90,30 @@ pub fn display_uninstall_summary(jdks: &[InstalledJdk], total_disk_space: u64) {
 /// Display batch uninstall progress summary
 pub fn display_batch_uninstall_summary(
     succeeded: &[InstalledJdk],
-    failed: &[(InstalledJdk, String)],
+    failed: &[ItemFailure],
     total_disk_space: u64,
 ) {
     let reporter = StatusReporter::new(false);
 
-    reporter.operation("Batch uninstall summary", "");
-    reporter.step(&format!("Succeeded: {}", succeeded.len()));
-    reporter.step(&format!("Failed: {}", failed.len()));
+    reporter.operation(&t("status.uninstall.summary_header"), "");
+    reporter.step(&t_with(
+        "status.uninstall.summary_succeeded",
+        &[("count", &succeeded.len().to_string())],
+    ));
+    reporter.step(&t_with(
+        "status.uninstall.summary_failed",
+        &[("count", &failed.len().to_string())],
+    ));
 
     if !failed.is_empty() {
-        reporter.error("Failed uninstalls:");
-        for (jdk, error) in failed {
-            reporter.step(&format!(
-                "- {}@{}: {}",
-                jdk.distribution, jdk.version, error
-            ));
-        }
+        reporter.error(&t("status.uninstall.summary_failures"));
+        println!("{}", failure_table(failed));
     }
 
     if !succeeded.is_empty() {
-        reporter.success(&format!(
-            "Disk space freed: {}",
-            format_size(total_disk_space)
+        reporter.success(&t_with(
+            "status.uninstall.summary_freed",
+            &[("size", &format_size(total_disk_space))],
         ));
     }
 }
@@ -148,10 +150,10 @@ mod tests {
             create_test_jdk("temurin", "21.0.5+11"),
             create_test_jdk("corretto", "17.0.13.11.1"),
         ];
-        let failed = vec![(
-            create_test_jdk("zulu", "11.0.25"),
-            "Permission denied".to_string(),
-        )];
+        let failed = vec![ItemFailure {
+            item: "zulu@11.0.25".to_string(),
+            error: crate::error::KopiError::PermissionDenied("/opt/zulu".to_string()),
+        }];
         let disk_space = 1024 * 1024 * 1024; // 1 GB
 
         // This test just ensures the function doesn't panic
@@ -14,8 +14,12 @@
 
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
+use crate::i18n::{t, t_with};
 use crate::indicator::{ProgressConfig, ProgressFactory, ProgressStyle, StatusReporter};
-use crate::locking::{InstalledScopeResolver, LockBackend, LockController, ScopedPackageLockGuard};
+use crate::locking::{
+    InstalledScopeResolver, LockBackend, LockController, ScopedPackageLockGuard, global_token,
+};
+use crate::models::package::PackageType;
 use crate::platform;
 use crate::storage::formatting::format_size;
 use crate::storage::{InstalledJdk, JdkRepository};
@@ -36,11 +40,13 @@ pub mod post_check;
 pub mod progress;
 pub mod safety;
 pub mod selection;
+pub mod unused;
 
 pub struct UninstallHandler<'a> {
     config: &'a KopiConfig,
     repository: &'a JdkRepository<'a>,
     no_progress: bool,
+    keep_archives: bool,
 }
 
 impl<'a> UninstallHandler<'a> {
@@ -50,9 +56,16 @@ impl<'a> UninstallHandler<'a> {
             config,
             repository,
             no_progress,
+            keep_archives: false,
         }
     }
 
+    /// Leave the kept install archive and companion archives in place
+    pub fn with_keep_archives(mut self, keep_archives: bool) -> Self {
+        self.keep_archives = keep_archives;
+        self
+    }
+
     /// Perform cleanup operations for failed uninstalls
     pub fn recover_from_failures(&self, force: bool) -> Result<()> {
         let lock_feedback = Arc::new(Mutex::new(ProgressFactory::create(self.no_progress)));
@@ -65,13 +78,16 @@ impl<'a> UninstallHandler<'a> {
         let actions = cleanup.detect_and_cleanup_partial_removals()?;
 
         if actions.is_empty() {
-            reporter.step("No recovery actions needed.");
+            reporter.step(&t("status.uninstall.no_recovery_actions"));
             return Ok(());
         }
 
         reporter.operation(
-            "Found recovery actions",
-            &format!("{} actions", actions.len()),
+            &t("status.uninstall.recovery_found"),
+            &t_with(
+                "status.uninstall.recovery_count",
+                &[("count", &actions.len().to_string())],
+            ),
         );
         for action in &actions {
             reporter.step(&format!("- {action:?}"));
@@ -80,12 +96,12 @@ impl<'a> UninstallHandler<'a> {
         let result = cleanup.execute_cleanup(actions, force)?;
 
         if result.is_success() {
-            reporter.success("Recovery completed successfully");
+            reporter.success(&t("status.uninstall.recovery_succeeded"));
             for success in result.successes {
                 reporter.step(&format!("✓ {success}"));
             }
         } else {
-            reporter.error("Recovery completed with errors");
+            reporter.error(&t("status.uninstall.recovery_failed"));
             for success in result.successes {
                 reporter.step(&format!("✓ {success}"));
             }
@@ -129,14 +145,21 @@ impl<'a> UninstallHandler<'a> {
         } else {
             jdks_to_remove.into_iter().next().unwrap()
         };
+        self.repository.ensure_not_system(&jdk)?;
         let jdk_size = self.repository.get_jdk_size(&jdk.path)?;
+        let external_home = self
+            .repository
+            .is_external(&jdk)
+            .then(|| std::fs::read_link(&jdk.path).ok())
+            .flatten();
 
         if dry_run {
-            reporter.step(&format!(
-                "Would remove {}@{} ({})",
-                jdk.distribution,
-                jdk.version,
-                format_size(jdk_size)
+            reporter.step(&t_with(
+                "status.uninstall.would_remove",
+                &[
+                    ("jdk", &format!("{}@{}", jdk.distribution, jdk.version)),
+                    ("size", &format_size(jdk_size)),
+                ],
             ));
             return Ok(());
         }
@@ -149,7 +172,10 @@ impl<'a> UninstallHandler<'a> {
         let lock_scope = scope_resolver.resolve(&jdk)?;
         let scope_label = lock_scope.label();
 
-        reporter.step(&format!("Acquiring uninstall lock for {scope_label}"));
+        reporter.step(&t_with(
+            "status.uninstall.acquiring_lock",
+            &[("scope", &scope_label.to_string())],
+        ));
         let acquisition =
             controller.acquire_with_feedback(lock_scope.clone(), lock_feedback.clone())?;
         let uninstall_lock_guard = ScopedPackageLockGuard::new(&controller, acquisition);
@@ -158,20 +184,35 @@ impl<'a> UninstallHandler<'a> {
             LockBackend::Fallback => "fallback",
         };
         info!("Uninstall lock acquired for {scope_label} using {backend_label} backend");
-        reporter.step(&format!("Using {backend_label} backend for {scope_label}"));
+        reporter.step(&t_with(
+            "status.uninstall.lock_backend",
+            &[
+                ("backend", backend_label),
+                ("scope", &scope_label.to_string()),
+            ],
+        ));
 
         // Perform safety checks
         let active_summary =
             safety::perform_safety_checks(self.config, self.repository, &jdk, force)?;
 
         if force && active_summary.has_active_use() {
+            if active_summary.protected {
+                warn!(
+                    "--force removing protected {}@{}",
+                    jdk.distribution, jdk.version
+                );
+                reporter.step(&t("status.uninstall.force_protected"));
+            }
+
             if let Some(global) = &active_summary.global {
                 warn!(
                     "--force removing {}@{} despite active global configuration {}",
                     jdk.distribution, jdk.version, global
                 );
-                reporter.step(&format!(
-                    "Proceeding with --force: global default set via {global}"
+                reporter.step(&t_with(
+                    "status.uninstall.force_global",
+                    &[("source", &global.to_string())],
                 ));
             }
 
@@ -180,8 +221,20 @@ impl<'a> UninstallHandler<'a> {
                     "--force removing {}@{} despite active project configuration {}",
                     jdk.distribution, jdk.version, project
                 );
-                reporter.step(&format!(
-                    "Proceeding with --force: project default set via {project}"
+                reporter.step(&t_with(
+                    "status.uninstall.force_project",
+                    &[("source", &project.to_string())],
+                ));
+            }
+
+            for recent in &active_summary.recent_projects {
+                warn!(
+                    "--force removing {}@{} despite recently used project configuration {}",
+                    jdk.distribution, jdk.version, recent
+                );
+                reporter.step(&t_with(
+                    "status.uninstall.force_recent",
+                    &[("source", &recent.to_string())],
                 ));
             }
 
@@ -223,19 +276,23 @@ impl<'a> UninstallHandler<'a> {
                         process.exe_path.display(),
                         handle_summary
                     );
-                    reporter.step(&format!(
-                        "Proceeding with --force: PID {} ({}) still open: {}",
-                        process.pid,
-                        process.exe_path.display(),
-                        handle_summary
+                    reporter.step(&t_with(
+                        "status.uninstall.force_process",
+                        &[
+                            ("pid", &process.pid.to_string()),
+                            ("exe", &process.exe_path.display().to_string()),
+                            ("handles", &handle_summary),
+                        ],
                     ));
 
                     match platform::process::terminate_process(process.pid) {
                         Ok(()) => {
-                            reporter.step(&format!(
-                                "Terminated PID {} ({})",
-                                process.pid,
-                                process.exe_path.display()
+                            reporter.step(&t_with(
+                                "status.uninstall.terminated",
+                                &[
+                                    ("pid", &process.pid.to_string()),
+                                    ("exe", &process.exe_path.display().to_string()),
+                                ],
                             ));
                         }
                         Err(err) => {
@@ -244,10 +301,13 @@ impl<'a> UninstallHandler<'a> {
                                 process.pid,
                                 process.exe_path.display()
                             );
-                            reporter.step(&format!(
-                                "Failed to terminate PID {} ({}): {err}",
-                                process.pid,
-                                process.exe_path.display()
+                            reporter.step(&t_with(
+                                "status.uninstall.terminate_failed",
+                                &[
+                                    ("pid", &process.pid.to_string()),
+                                    ("exe", &process.exe_path.display().to_string()),
+                                    ("error", &err.to_string()),
+                                ],
                             ));
                         }
                     }
@@ -258,15 +318,33 @@ impl<'a> UninstallHandler<'a> {
             }
         }
 
+        // Last point at which Ctrl+C leaves the JDK untouched; the lock guard
+        // releases the uninstall lock on the way out
+        if global_token().is_cancelled() {
+            return Err(KopiError::Cancelled(format!(
+                "{}@{} was not removed",
+                jdk.distribution, jdk.version
+            )));
+        }
+
         // Remove with progress
         match self.remove_jdk_with_progress(&jdk, jdk_size) {
             Ok(()) => {
                 uninstall_lock_guard.release()?;
-                reporter.success(&format!(
-                    "Successfully uninstalled {}@{}",
-                    jdk.distribution, jdk.version
+                reporter.success(&t_with(
+                    "status.uninstall.succeeded",
+                    &[("jdk", &format!("{}@{}", jdk.distribution, jdk.version))],
                 ));
-                reporter.step(&format!("Freed {} of disk space", format_size(jdk_size)));
+                match &external_home {
+                    Some(home) => reporter.step(&t_with(
+                        "status.uninstall.left_adopted",
+                        &[("path", &home.display().to_string())],
+                    )),
+                    None => reporter.step(&t_with(
+                        "status.uninstall.freed",
+                        &[("size", &format_size(jdk_size))],
+                    )),
+                }
                 Ok(())
             }
             Err(e) => {
@@ -290,8 +368,15 @@ impl<'a> UninstallHandler<'a> {
     fn remove_jdk_with_progress(&self, jdk: &InstalledJdk, size: u64) -> Result<()> {
         info!("Removing JDK at {}", jdk.path.display());
 
+        // Only the link to an adopted JDK is removed; its files stay untouched
+        let external = self.repository.is_external(jdk);
+
         // Check for files in use before removal
-        let files_in_use = platform::file_ops::check_files_in_use(&jdk.path)?;
+        let files_in_use = if external {
+            Vec::new()
+        } else {
+            platform::file_ops::check_files_in_use(&jdk.path)?
+        };
         if !files_in_use.is_empty() {
             warn!("Files may be in use:");
             for file in &files_in_use {
@@ -300,20 +385,38 @@ impl<'a> UninstallHandler<'a> {
             // Continue with removal but warn user
         }
 
-        // Remove metadata file before atomic removal
+        // Remove metadata and archives before atomic removal
         if let Some(parent) = jdk.path.parent()
             && let Some(jdk_dir_name) = jdk.path.file_name().and_then(|n| n.to_str())
         {
             let meta_file = parent.join(format!("{jdk_dir_name}.meta.json"));
-            if meta_file.exists()
-                && let Err(e) = std::fs::remove_file(&meta_file)
-            {
-                debug!(
-                    "Failed to remove metadata file {}: {}",
-                    meta_file.display(),
-                    e
-                );
-                // Don't fail the operation if metadata removal fails
+            let manifest_file = parent.join(format!("{jdk_dir_name}.manifest.json"));
+            let archives = if self.keep_archives {
+                Vec::new()
+            } else {
+                [PackageType::Sources, PackageType::Javadoc]
+                    .into_iter()
+                    .filter_map(|kind| {
+                        self.repository
+                            .find_companion_archive(&jdk.path, kind)
+                            .ok()
+                            .flatten()
+                    })
+                    .chain(
+                        self.repository
+                            .find_install_archive(&jdk.path)
+                            .ok()
+                            .flatten(),
+                    )
+                    .collect()
+            };
+            for file in [meta_file, manifest_file].into_iter().chain(archives) {
+                if file.exists()
+                    && let Err(e) = std::fs::remove_file(&file)
+                {
+                    debug!("Failed to remove metadata file {}: {}", file.display(), e);
+                    // Don't fail the operation if metadata removal fails
+                }
             }
         }
 
@@ -329,7 +432,9 @@ impl<'a> UninstallHandler<'a> {
         };
 
         // Prepare platform-specific removal
-        platform::file_ops::prepare_for_removal(&jdk.path)?;
+        if !external {
+            platform::file_ops::prepare_for_removal(&jdk.path)?;
+        }
 
         // Atomic removal with rollback capability
         let temp_path = self.prepare_atomic_removal(&jdk.path)?;
@@ -340,6 +445,7 @@ impl<'a> UninstallHandler<'a> {
                 if let Err(e) = platform::file_ops::post_removal_cleanup(&jdk.path) {
                     debug!("Post-removal cleanup failed: {e}");
                 }
+                platform::macos::forget(jdk);
 
                 if let Some(pb) = pb {
                     pb.finish_and_clear();
@@ -544,6 +650,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn uninstall_keeps_archives_on_request() {
+        use crate::models::package::ArchiveType;
+
+        let setup = TestSetup::new();
+        let repository = JdkRepository::new(&setup.config);
+        let archive = setup._temp_dir.path().join("jdk.tar.gz");
+        fs::write(&archive, b"archive").unwrap();
+
+        let jdk_path = setup.create_mock_jdk("temurin", "21.0.5+11");
+        let kept = repository
+            .save_install_archive(&jdk_path, ArchiveType::TarGz, &archive)
+            .unwrap();
+        UninstallHandler::new(&repository, true)
+            .with_keep_archives(true)
+            .uninstall_jdk("temurin@21.0.5+11", false, false)
+            .unwrap();
+        assert!(!jdk_path.exists());
+        assert!(kept.exists());
+
+        setup.create_mock_jdk("temurin", "21.0.5+11");
+        UninstallHandler::new(&repository, true)
+            .uninstall_jdk("temurin@21.0.5+11", false, false)
+            .unwrap();
+        assert!(!kept.exists());
+    }
+
     #[test]
     fn uninstall_errors_when_lock_times_out() {
         let mut setup = TestSetup::new();
@@ -128,7 +128,7 @@ impl<'a> PostUninstallChecker<'a> {
                 let path = entry.path();
 
                 if let Some(file_name) = path.file_name().and_then(|n| n.to_str())
-                    && file_name.ends_with(".meta.json")
+                    && (file_name.ends_with(".meta.json") || file_name.ends_with(".manifest.json"))
                     && path.is_file()
                 {
                     // Check if this metadata file references the removed JDK
@@ -15,8 +15,11 @@
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
 use crate::platform::{ProcessInfo, processes_using_path};
+use crate::shim::history;
 use crate::storage::{InstalledJdk, JdkRepository};
-use crate::version::VersionRequest;
+use crate::version::file::version_file_pin;
+use crate::version::resolver::{PROJECT_CONFIG_FILE, read_tool_pins};
+use crate::version::{VersionRequest, matching};
 use log::{debug, trace, warn};
 use std::env;
 use std::fmt::{self, Write};
@@ -30,12 +33,17 @@ const JAVA_VERSION_FILE: &str = ".java-version";
 
 /// Perform safety checks before uninstalling a JDK.
 ///
-/// The new active-use detection deliberately ignores the `KOPI_JAVA_VERSION`
-/// environment variable for now (see T-s2g7h Phase 1 decision); only global and
-/// project version files participate in uninstall blocking.
+/// The JDK is considered in use when it was protected with
+/// `kopi pin-protect`, or when the global default, a project version file
+/// above the current directory, a project recently resolved by shims, or a
+/// running process references it. Without `force`, every blocking reference
+/// is listed in the returned error.
+///
+/// The active-use detection deliberately ignores the `KOPI_JAVA_VERSION`
+/// environment variable for now (see T-s2g7h Phase 1 decision).
 pub fn perform_safety_checks(
     config: &KopiConfig,
-    _repository: &JdkRepository,
+    repository: &JdkRepository,
     jdk: &InstalledJdk,
     force: bool,
 ) -> Result<ActiveUseSummary> {
@@ -44,50 +52,40 @@ pub fn perform_safety_checks(
         jdk.distribution, jdk.version
     );
 
-    let global_active = detect_global_active_jdk(config, jdk)?;
-    let project_active = detect_project_active_jdk(jdk)?;
-
-    if !force {
-        if let Some(active) = &global_active {
-            return Err(KopiError::ValidationError(format!(
-                "Cannot uninstall {dist}@{ver} - it is currently active globally via {} \
-                 (configured as {}). Use --force to override this check or run \
-                 'kopi global unset' before uninstalling.",
-                active.version_file.display(),
-                active.request,
-                dist = jdk.distribution,
-                ver = jdk.version
-            )));
-        }
-
-        if let Some(active) = &project_active {
-            return Err(KopiError::ValidationError(format!(
-                "Cannot uninstall {dist}@{ver} - it is configured for this project via {} \
-                 (configured as {}). Use --force to override this check or update the \
-                 project version file.",
-                active.version_file.display(),
-                active.request,
-                dist = jdk.distribution,
-                ver = jdk.version
-            )));
-        }
-    }
-
+    let global = detect_global_active_jdk(config, jdk)?;
+    let project = detect_project_active_jdk(jdk)?;
+    let recent_projects = detect_recent_project_references(config, jdk, project.as_ref());
     let processes = detect_running_processes(jdk)?;
 
-    if !force && !processes.is_empty() {
-        return Err(build_running_process_error(jdk, &processes));
-    }
-
     let summary = ActiveUseSummary {
-        global: global_active,
-        project: project_active,
+        protected: repository.is_protected(jdk),
+        global,
+        project,
+        recent_projects,
         processes,
     };
 
+    if !force && summary.has_active_use() {
+        return Err(build_active_use_error(jdk, &summary));
+    }
+
     Ok(summary)
 }
 
+/// Version files that pin `jdk`: the global default, a project version file
+/// above the current directory, and projects recently resolved by shims.
+pub fn pinned_references(config: &KopiConfig, jdk: &InstalledJdk) -> Result<Vec<ActiveUse>> {
+    let global = detect_global_active_jdk(config, jdk)?;
+    let project = detect_project_active_jdk(jdk)?;
+    let recent_projects = detect_recent_project_references(config, jdk, project.as_ref());
+
+    Ok(global
+        .into_iter()
+        .chain(project)
+        .chain(recent_projects)
+        .collect())
+}
+
 fn detect_global_active_jdk(config: &KopiConfig, jdk: &InstalledJdk) -> Result<Option<ActiveUse>> {
     let version_file = config.kopi_home().join(GLOBAL_VERSION_FILENAME);
     if !version_file.exists() {
@@ -96,7 +94,7 @@ fn detect_global_active_jdk(config: &KopiConfig, jdk: &InstalledJdk) -> Result<O
 
     match read_kopi_version_request(&version_file)? {
         Some(request) => {
-            if request_matches_jdk(&request, jdk) {
+            if matching::matches_installed(&request, jdk) {
                 debug!(
                     "Global version file {} matches target {}@{} (request: {})",
                     version_file.display(),
@@ -122,7 +120,7 @@ fn detect_project_active_jdk(jdk: &InstalledJdk) -> Result<Option<ActiveUse>> {
     loop {
         let kopi_version_file = current.join(KOPI_VERSION_FILE);
         if let Some(request) = read_kopi_version_request(&kopi_version_file)?
-            && request_matches_jdk(&request, jdk)
+            && matching::matches_installed(&request, jdk)
         {
             debug!(
                 "Project version file {} matches target {}@{} (request: {})",
@@ -136,7 +134,7 @@ fn detect_project_active_jdk(jdk: &InstalledJdk) -> Result<Option<ActiveUse>> {
 
         let java_version_file = current.join(JAVA_VERSION_FILE);
         if let Some(request) = read_java_version_request(&java_version_file)?
-            && request_matches_jdk(&request, jdk)
+            && matching::matches_installed(&request, jdk)
         {
             debug!(
                 "Java version file {} matches target {}@{} (request: {})",
@@ -157,6 +155,64 @@ fn detect_project_active_jdk(jdk: &InstalledJdk) -> Result<Option<ActiveUse>> {
     Ok(None)
 }
 
+fn detect_recent_project_references(
+    config: &KopiConfig,
+    jdk: &InstalledJdk,
+    current_project: Option<&ActiveUse>,
+) -> Vec<ActiveUse> {
+    let mut references = Vec::new();
+
+    for version_file in history::recent_project_files(config.kopi_home()) {
+        if current_project.is_some_and(|active| active.version_file == version_file)
+            || !version_file.exists()
+        {
+            continue;
+        }
+
+        let file_name = version_file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let requests = match file_name {
+            KOPI_VERSION_FILE => read_kopi_version_request(&version_file)
+                .ok()
+                .flatten()
+                .into_iter()
+                .collect(),
+            JAVA_VERSION_FILE => read_java_version_request(&version_file)
+                .ok()
+                .flatten()
+                .into_iter()
+                .collect(),
+            PROJECT_CONFIG_FILE => read_tool_pins(&version_file)
+                .map(|tools| {
+                    tools
+                        .values()
+                        .filter_map(|spec| VersionRequest::from_str(spec.trim()).ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        if let Some(request) = requests
+            .into_iter()
+            .find(|request| matching::matches_installed(request, jdk))
+        {
+            debug!(
+                "Recently used project file {} matches target {}@{} (request: {})",
+                version_file.display(),
+                jdk.distribution,
+                jdk.version,
+                request
+            );
+            references.push(ActiveUse::new(version_file, request));
+        }
+    }
+
+    references
+}
+
 fn read_kopi_version_request(path: &Path) -> Result<Option<VersionRequest>> {
     read_version_request(path, VersionFileKind::Kopi)
 }
@@ -181,14 +237,13 @@ fn read_version_request(path: &Path, kind: VersionFileKind) -> Result<Option<Ver
         }
     };
 
-    let trimmed = content.trim();
-    if trimmed.is_empty() {
+    let Some(trimmed) = version_file_pin(&content) else {
         trace!(
             "Version file {} is empty; ignoring for active-use detection",
             path.display()
         );
         return Ok(None);
-    }
+    };
 
     let request_result = match kind {
         VersionFileKind::Kopi => VersionRequest::from_str(trimmed),
@@ -207,45 +262,62 @@ fn read_version_request(path: &Path, kind: VersionFileKind) -> Result<Option<Ver
     }
 }
 
-fn request_matches_jdk(request: &VersionRequest, jdk: &InstalledJdk) -> bool {
-    if request
-        .distribution
-        .as_ref()
-        .is_some_and(|distribution| !distribution.eq_ignore_ascii_case(&jdk.distribution))
-    {
-        return false;
-    }
-
-    if request
-        .javafx_bundled
-        .is_some_and(|javafx| javafx != jdk.javafx_bundled)
-    {
-        return false;
-    }
-
-    jdk.version.matches_pattern(&request.version_pattern)
-}
-
 fn detect_running_processes(jdk: &InstalledJdk) -> Result<Vec<ProcessInfo>> {
     processes_using_path(&jdk.path)
 }
 
-fn build_running_process_error(jdk: &InstalledJdk, processes: &[ProcessInfo]) -> KopiError {
-    let canonical_root = jdk.path.canonicalize().unwrap_or_else(|_| jdk.path.clone());
-
+fn build_active_use_error(jdk: &InstalledJdk, summary: &ActiveUseSummary) -> KopiError {
     let mut message = format!(
-        "Cannot uninstall {dist}@{ver} - running processes are using {jdk_path}:",
+        "Cannot uninstall {dist}@{ver} - it is still referenced:",
         dist = jdk.distribution,
-        ver = jdk.version,
-        jdk_path = jdk.path.display()
+        ver = jdk.version
+    );
+
+    if summary.protected {
+        let _ = write!(
+            message,
+            "\n  - protected; run 'kopi pin-protect --remove {}@{}' to allow removal",
+            jdk.distribution, jdk.version
+        );
+    }
+    if let Some(active) = &summary.global {
+        let _ = write!(message, "\n  - currently active globally via {active}");
+    }
+    if let Some(active) = &summary.project {
+        let _ = write!(message, "\n  - configured for this project via {active}");
+    }
+    for active in &summary.recent_projects {
+        let _ = write!(
+            message,
+            "\n  - configured for a project recently used by shims via {active}"
+        );
+    }
+    if !summary.processes.is_empty() {
+        let _ = write!(
+            message,
+            "\n  - running processes are using {}:",
+            jdk.path.display()
+        );
+        write_process_details(&mut message, jdk, &summary.processes);
+    }
+
+    message.push_str(
+        "\nUse --force to override these checks, or run 'kopi global unset', update the \
+         listed version files, and close the processes before uninstalling.",
     );
 
+    KopiError::ValidationError(message)
+}
+
+fn write_process_details(message: &mut String, jdk: &InstalledJdk, processes: &[ProcessInfo]) {
+    let canonical_root = jdk.path.canonicalize().unwrap_or_else(|_| jdk.path.clone());
+
     for process in processes {
         let exe_display = process.exe_path.display();
-        let _ = writeln!(message, "\n  PID {} ({exe_display})", process.pid);
+        let _ = write!(message, "\n      PID {} ({exe_display})", process.pid);
 
         if process.handles.is_empty() {
-            let _ = writeln!(message, "    - <no open handles reported>");
+            let _ = write!(message, "\n        - <no open handles reported>");
             continue;
         }
 
@@ -262,14 +334,9 @@ fn build_running_process_error(jdk: &InstalledJdk, processes: &[ProcessInfo]) ->
                     }
                 })
                 .unwrap_or_else(|| handle.display().to_string());
-            let _ = writeln!(message, "    - {handle_display}");
+            let _ = write!(message, "\n        - {handle_display}");
         }
     }
-
-    message
-        .push_str("\nClose these processes or re-run with --force to proceed with the uninstall.");
-
-    KopiError::ValidationError(message)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -300,14 +367,21 @@ impl fmt::Display for ActiveUse {
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct ActiveUseSummary {
+    /// Protected with `kopi pin-protect`
+    pub protected: bool,
     pub global: Option<ActiveUse>,
     pub project: Option<ActiveUse>,
+    pub recent_projects: Vec<ActiveUse>,
     pub processes: Vec<ProcessInfo>,
 }
 
 impl ActiveUseSummary {
     pub fn has_active_use(&self) -> bool {
-        self.global.is_some() || self.project.is_some() || !self.processes.is_empty()
+        self.protected
+            || self.global.is_some()
+            || self.project.is_some()
+            || !self.recent_projects.is_empty()
+            || !self.processes.is_empty()
     }
 }
 
@@ -443,6 +517,29 @@ mod tests {
         assert!(summary.processes.is_empty());
     }
 
+    #[test]
+    fn safety_checks_block_protected_jdk() {
+        let fixture = TestFixture::new();
+        let repository = fixture.repository();
+        let jdk = fixture.create_installed_jdk("temurin", "17.0.9");
+        fs::write(
+            install::metadata_file(fixture.config.kopi_home(), "temurin-17.0.9"),
+            "{}",
+        )
+        .unwrap();
+        repository.set_protected(&jdk, true).unwrap();
+
+        match perform_safety_checks(&fixture.config, &repository, &jdk, false) {
+            Err(KopiError::ValidationError(message)) => {
+                assert!(message.contains("kopi pin-protect --remove temurin@17.0.9"));
+            }
+            other => panic!("expected protected JDK to be blocked, got {other:?}"),
+        }
+
+        let summary = perform_safety_checks(&fixture.config, &repository, &jdk, true).unwrap();
+        assert!(summary.protected);
+    }
+
     #[test]
     #[serial]
     fn safety_checks_block_project_kopi_version() {
@@ -502,6 +599,100 @@ mod tests {
         assert!(summary.processes.is_empty());
     }
 
+    #[test]
+    fn safety_checks_block_recently_used_project() {
+        let fixture = TestFixture::new();
+        let repository = fixture.repository();
+        let jdk = fixture.create_installed_jdk("temurin", "21.0.5+11");
+
+        let project_dir = fixture.temp_dir.path().join("workspace/recent");
+        fs::create_dir_all(&project_dir).unwrap();
+        let project_config = project_dir.join(PROJECT_CONFIG_FILE);
+        fs::write(&project_config, "[tools]\njavac = \"temurin@21\"\n").unwrap();
+        history::record_project_resolution(fixture.config.kopi_home(), &project_config);
+
+        let result = perform_safety_checks(&fixture.config, &repository, &jdk, false);
+        match result {
+            Err(KopiError::ValidationError(message)) => {
+                assert!(message.contains("recently used by shims"), "{message}");
+                assert!(message.contains(".kopi.toml"), "{message}");
+            }
+            other => panic!("expected validation error, got {other:?}"),
+        }
+
+        let summary = perform_safety_checks(&fixture.config, &repository, &jdk, true).unwrap();
+        assert_eq!(summary.recent_projects.len(), 1);
+        assert_eq!(summary.recent_projects[0].version_file, project_config);
+    }
+
+    #[test]
+    fn safety_checks_skip_stale_history_entries() {
+        let fixture = TestFixture::new();
+        let repository = fixture.repository();
+        let jdk = fixture.create_installed_jdk("temurin", "21.0.5+11");
+
+        let project_dir = fixture.temp_dir.path().join("workspace/stale");
+        fs::create_dir_all(&project_dir).unwrap();
+        let version_file = project_dir.join(KOPI_VERSION_FILE);
+        fs::write(&version_file, "temurin@17").unwrap();
+        history::record_project_resolution(fixture.config.kopi_home(), &version_file);
+        history::record_project_resolution(
+            fixture.config.kopi_home(),
+            &fixture
+                .temp_dir
+                .path()
+                .join("missing")
+                .join(KOPI_VERSION_FILE),
+        );
+
+        let summary = perform_safety_checks(&fixture.config, &repository, &jdk, false).unwrap();
+        assert!(summary.recent_projects.is_empty());
+    }
+
+    #[test]
+    fn safety_checks_list_every_blocker() {
+        let fixture = TestFixture::new();
+        let repository = fixture.repository();
+        let jdk = fixture.create_installed_jdk("temurin", "21.0.5+11");
+
+        let global_path = fixture.config.kopi_home().join(GLOBAL_VERSION_FILENAME);
+        jdk.write_to(&global_path).unwrap();
+
+        let project_dir = fixture.temp_dir.path().join("workspace/pinned");
+        fs::create_dir_all(&project_dir).unwrap();
+        let version_file = project_dir.join(JAVA_VERSION_FILE);
+        fs::write(&version_file, "21\n").unwrap();
+        history::record_project_resolution(fixture.config.kopi_home(), &version_file);
+
+        match perform_safety_checks(&fixture.config, &repository, &jdk, false) {
+            Err(KopiError::ValidationError(message)) => {
+                assert!(message.contains("currently active globally"), "{message}");
+                assert!(message.contains("recently used by shims"), "{message}");
+                assert!(message.contains("Use --force"), "{message}");
+            }
+            other => panic!("expected validation error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn pinned_references_ignore_running_processes() {
+        let fixture = TestFixture::new();
+        let pinned = fixture.create_installed_jdk("temurin", "21.0.5+11");
+        let unpinned = fixture.create_installed_jdk("temurin", "17.0.9");
+
+        let global_path = fixture.config.kopi_home().join(GLOBAL_VERSION_FILENAME);
+        pinned.write_to(&global_path).unwrap();
+
+        let references = pinned_references(&fixture.config, &pinned).unwrap();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].version_file, global_path);
+        assert!(
+            pinned_references(&fixture.config, &unpinned)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     #[cfg(target_os = "linux")]
     fn safety_checks_detect_running_processes() {
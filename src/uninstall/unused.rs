@@ -0,0 +1,285 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Selection of installed JDKs that have gone unused for a while.
+
+use crate::config::KopiConfig;
+use crate::error::{KopiError, Result};
+use crate::paths::install;
+use crate::shim::usage::{self, UsageRecords};
+use crate::storage::{InstalledJdk, JdkRepository};
+use crate::uninstall::safety::pinned_references;
+use chrono::{DateTime, TimeDelta, Utc};
+use log::debug;
+use std::fmt;
+use std::fs;
+use std::time::SystemTime;
+
+/// Where the last-used time of a JDK came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastUsedSource {
+    /// Recorded by a shim when it ran a tool from the JDK
+    Shim,
+    /// Access or modification time of the JDK's `java` binary
+    Filesystem,
+}
+
+impl fmt::Display for LastUsedSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LastUsedSource::Shim => write!(f, "shim"),
+            LastUsedSource::Filesystem => write!(f, "file times"),
+        }
+    }
+}
+
+/// An installed JDK that has not been used within the requested age
+#[derive(Debug)]
+pub struct UnusedJdk {
+    pub jdk: InstalledJdk,
+    pub last_used: Option<DateTime<Utc>>,
+    pub source: Option<LastUsedSource>,
+}
+
+/// Parse an age such as `90d`, `12w`, or `36h`; a bare number means days
+pub fn parse_age(spec: &str) -> Result<TimeDelta> {
+    let spec = spec.trim();
+    let (digits, unit) = match spec.char_indices().last() {
+        Some((index, unit)) if unit.is_ascii_alphabetic() => (&spec[..index], Some(unit)),
+        _ => (spec, None),
+    };
+
+    let invalid = || {
+        KopiError::ValidationError(format!(
+            "Invalid age '{spec}'. Use a number followed by h, d, or w (e.g., 90d)"
+        ))
+    };
+    let amount: i64 = digits.parse().map_err(|_| invalid())?;
+    if amount < 0 {
+        return Err(invalid());
+    }
+
+    match unit.map(|unit| unit.to_ascii_lowercase()) {
+        Some('h') => TimeDelta::try_hours(amount),
+        Some('d') | None => TimeDelta::try_days(amount),
+        Some('w') => TimeDelta::try_weeks(amount),
+        _ => None,
+    }
+    .ok_or_else(invalid)
+}
+
+/// Find installed JDKs last used before `now - older_than` that no version
+/// file pins, oldest first. JDKs with no known last-used time are included.
+pub fn find_unused_jdks(
+    config: &KopiConfig,
+    repository: &JdkRepository,
+    older_than: TimeDelta,
+    now: DateTime<Utc>,
+) -> Result<Vec<UnusedJdk>> {
+    let cutoff = now - older_than;
+    let records = usage::load_usage(config.kopi_home());
+    let mut unused = Vec::new();
+
+    for jdk in repository.list_installed_jdks()? {
+        let (last_used, source) = match last_used(&records, &jdk) {
+            Some((time, source)) => (Some(time), Some(source)),
+            None => (None, None),
+        };
+        if last_used.is_some_and(|time| time >= cutoff) {
+            continue;
+        }
+
+        let pins = pinned_references(config, &jdk)?;
+        if !pins.is_empty() {
+            debug!(
+                "Keeping {}@{}: pinned by {}",
+                jdk.distribution, jdk.version, pins[0]
+            );
+            continue;
+        }
+
+        unused.push(UnusedJdk {
+            jdk,
+            last_used,
+            source,
+        });
+    }
+
+    unused.sort_by_key(|candidate| candidate.last_used);
+    Ok(unused)
+}
+
+fn last_used(
+    records: &UsageRecords,
+    jdk: &InstalledJdk,
+) -> Option<(DateTime<Utc>, LastUsedSource)> {
+    let recorded = jdk
+        .path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| records.get(name));
+    if let Some(time) = recorded {
+        return Some((*time, LastUsedSource::Shim));
+    }
+
+    filesystem_last_used(jdk).map(|time| (time, LastUsedSource::Filesystem))
+}
+
+/// Latest of the access and modification times of the JDK's `java` binary,
+/// falling back to the JDK directory itself
+fn filesystem_last_used(jdk: &InstalledJdk) -> Option<DateTime<Utc>> {
+    let java_home = jdk.resolve_java_home();
+    let java =
+        install::bin_directory(&java_home).join(if cfg!(windows) { "java.exe" } else { "java" });
+
+    let metadata = fs::metadata(&java)
+        .or_else(|_| fs::metadata(&jdk.path))
+        .ok()?;
+    let times: Vec<SystemTime> = [metadata.accessed().ok(), metadata.modified().ok()]
+        .into_iter()
+        .flatten()
+        .collect();
+    times.into_iter().max().map(DateTime::<Utc>::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::cache::jdk_usage_file;
+    use crate::version::Version;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    struct TestFixture {
+        _temp_dir: TempDir,
+        config: KopiConfig,
+    }
+
+    impl TestFixture {
+        fn new() -> Self {
+            let temp_dir = TempDir::new().unwrap();
+            let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+            fs::create_dir_all(config.jdks_dir().unwrap()).unwrap();
+            Self {
+                _temp_dir: temp_dir,
+                config,
+            }
+        }
+
+        fn install(&self, name: &str) {
+            let jdk_path = self.config.jdks_dir().unwrap().join(name);
+            let bin_dir = install::bin_directory(&jdk_path);
+            fs::create_dir_all(&bin_dir).unwrap();
+            fs::write(bin_dir.join("java"), "").unwrap();
+        }
+
+        fn record_usage(&self, entries: &[(&str, DateTime<Utc>)]) {
+            let records: UsageRecords = entries
+                .iter()
+                .map(|(name, time)| (name.to_string(), *time))
+                .collect();
+            let path = jdk_usage_file(self.config.kopi_home());
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, serde_json::to_string(&records).unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90d").unwrap(), TimeDelta::days(90));
+        assert_eq!(parse_age("90").unwrap(), TimeDelta::days(90));
+        assert_eq!(parse_age("2w").unwrap(), TimeDelta::weeks(2));
+        assert_eq!(parse_age("36H").unwrap(), TimeDelta::hours(36));
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("-1d").is_err());
+        assert!(parse_age("3m").is_err());
+        assert!(parse_age("").is_err());
+    }
+
+    #[test]
+    fn test_find_unused_jdks_uses_shim_records() {
+        let fixture = TestFixture::new();
+        fixture.install("temurin-21.0.1");
+        fixture.install("temurin-17.0.9");
+        fixture.install("zulu-11.0.21");
+
+        let now = Utc::now();
+        fixture.record_usage(&[
+            ("temurin-21.0.1", now - TimeDelta::days(3)),
+            ("temurin-17.0.9", now - TimeDelta::days(200)),
+            ("zulu-11.0.21", now - TimeDelta::days(120)),
+        ]);
+
+        let repository = JdkRepository::new(&fixture.config);
+        let unused =
+            find_unused_jdks(&fixture.config, &repository, TimeDelta::days(90), now).unwrap();
+
+        let names: Vec<String> = unused
+            .iter()
+            .map(|candidate| format!("{}@{}", candidate.jdk.distribution, candidate.jdk.version))
+            .collect();
+        assert_eq!(names, vec!["temurin@17.0.9", "zulu@11.0.21"]);
+        assert!(
+            unused
+                .iter()
+                .all(|candidate| candidate.source == Some(LastUsedSource::Shim))
+        );
+    }
+
+    #[test]
+    fn test_find_unused_jdks_falls_back_to_file_times() {
+        let fixture = TestFixture::new();
+        fixture.install("temurin-21.0.1");
+        let repository = JdkRepository::new(&fixture.config);
+        let now = Utc::now();
+
+        // Freshly written binary counts as recently used
+        assert!(
+            find_unused_jdks(&fixture.config, &repository, TimeDelta::days(1), now)
+                .unwrap()
+                .is_empty()
+        );
+
+        let later = now + TimeDelta::days(30);
+        let unused =
+            find_unused_jdks(&fixture.config, &repository, TimeDelta::days(1), later).unwrap();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].source, Some(LastUsedSource::Filesystem));
+        assert!(unused[0].last_used.is_some());
+    }
+
+    #[test]
+    fn test_find_unused_jdks_skips_pinned() {
+        let fixture = TestFixture::new();
+        fixture.install("temurin-21.0.1");
+        let now = Utc::now();
+        fixture.record_usage(&[("temurin-21.0.1", now - TimeDelta::days(365))]);
+
+        let repository = JdkRepository::new(&fixture.config);
+        let jdk = InstalledJdk::new(
+            "temurin".to_string(),
+            Version::from_str("21.0.1").unwrap(),
+            fixture.config.jdks_dir().unwrap().join("temurin-21.0.1"),
+            false,
+        );
+        jdk.write_to(&fixture.config.kopi_home().join("version"))
+            .unwrap();
+
+        assert!(
+            find_unused_jdks(&fixture.config, &repository, TimeDelta::days(90), now)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Java versions declared in Maven and Gradle build files.
+//!
+//! Build files are scanned as text rather than evaluated, so only literal
+//! declarations are recognised. In order of preference: the `<jdk><version>`
+//! of a Maven toolchains requirement (`maven-toolchains-plugin` or the
+//! compiler's `jdkToolchain`), `maven.compiler.release`, and `java.version`
+//! in `pom.xml`; Gradle's `java.toolchain.languageVersion` and
+//! `sourceCompatibility` in `build.gradle.kts` or `build.gradle`. Property
+//! references such as `${java.version}` are resolved within the same POM.
+
+use crate::error::{KopiError, Result};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const POM_FILE: &str = "pom.xml";
+const GRADLE_FILES: [&str; 2] = ["build.gradle.kts", "build.gradle"];
+/// Elements holding a Maven JDK toolchain requirement
+const MAVEN_TOOLCHAIN_ELEMENTS: [&str; 2] = ["jdk", "jdkToolchain"];
+const MAVEN_VERSION_PROPERTIES: [&str; 2] = ["maven.compiler.release", "java.version"];
+const GRADLE_LANGUAGE_VERSION: &str = "languageVersion";
+const GRADLE_VERSION_FACTORY: &str = "JavaLanguageVersion.of(";
+const GRADLE_SOURCE_COMPATIBILITY: &str = "sourceCompatibility";
+/// Name under which toolchain requirements are reported
+const TOOLCHAIN: &str = "toolchain";
+
+/// A Java feature release required by a build file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildFileVersion {
+    pub major: u32,
+    pub file: PathBuf,
+    /// What declared it: `toolchain`, a POM property, or `sourceCompatibility`
+    pub declaration: &'static str,
+}
+
+impl fmt::Display for BuildFileVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Java {} ({} in {})",
+            self.major,
+            self.declaration,
+            self.file.display()
+        )
+    }
+}
+
+/// Error for a project at `dir` whose build files declare no Java version
+pub fn no_declaration(dir: &Path) -> KopiError {
+    KopiError::NotFound(format!(
+        "No Java version declared in pom.xml, build.gradle.kts, or build.gradle in {} or its \
+         parent builds",
+        dir.display()
+    ))
+}
+
+/// Find the Java version declared by the build file in `dir`, preferring
+/// `pom.xml` over the Gradle build scripts
+pub fn detect_java_version(dir: &Path) -> Result<Option<BuildFileVersion>> {
+    let pom = dir.join(POM_FILE);
+    if let Some(content) = read_optional(&pom)?
+        && let Some((major, declaration)) = maven_version(&content)
+    {
+        return Ok(Some(BuildFileVersion {
+            major,
+            file: pom,
+            declaration,
+        }));
+    }
+
+    for name in GRADLE_FILES {
+        let script = dir.join(name);
+        if let Some(content) = read_optional(&script)?
+            && let Some((major, declaration)) = gradle_version(&content)
+        {
+            return Ok(Some(BuildFileVersion {
+                major,
+                file: script,
+                declaration,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// Like [`detect_java_version`], walking up from `start` through the
+/// directories that hold build files, so modules inherit the version their
+/// parent build declares
+pub fn find_java_version(start: &Path) -> Result<Option<BuildFileVersion>> {
+    let mut in_project = false;
+    for dir in start.ancestors() {
+        let has_build_file = std::iter::once(POM_FILE)
+            .chain(GRADLE_FILES)
+            .any(|name| dir.join(name).is_file());
+        if !has_build_file {
+            if in_project {
+                break;
+            }
+            continue;
+        }
+        in_project = true;
+        if let Some(version) = detect_java_version(dir)? {
+            return Ok(Some(version));
+        }
+    }
+    Ok(None)
+}
+
+fn maven_version(pom: &str) -> Option<(u32, &'static str)> {
+    if let Some(major) = maven_toolchain(pom) {
+        return Some((major, TOOLCHAIN));
+    }
+    MAVEN_VERSION_PROPERTIES.iter().find_map(|property| {
+        let value = resolve_property(pom, element_text(pom, property)?)?;
+        Some((parse_major(value)?, *property))
+    })
+}
+
+/// Follow a `${name}` reference to the property it names, once
+fn resolve_property<'a>(pom: &'a str, value: &'a str) -> Option<&'a str> {
+    match value
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))
+    {
+        Some(name) => element_text(pom, name).filter(|value| !value.starts_with("${")),
+        None => Some(value),
+    }
+}
+
+fn gradle_version(script: &str) -> Option<(u32, &'static str)> {
+    if let Some(major) = gradle_toolchain(script) {
+        return Some((major, TOOLCHAIN));
+    }
+    gradle_source_compatibility(script).map(|major| (major, GRADLE_SOURCE_COMPATIBILITY))
+}
+
+fn maven_toolchain(pom: &str) -> Option<u32> {
+    MAVEN_TOOLCHAIN_ELEMENTS.iter().find_map(|element| {
+        let open = format!("<{element}>");
+        let close = format!("</{element}>");
+        pom.match_indices(&open).find_map(|(start, _)| {
+            let body = &pom[start + open.len()..];
+            let body = &body[..body.find(&close)?];
+            parse_major(element_text(body, "version")?)
+        })
+    })
+}
+
+/// `languageVersion = JavaLanguageVersion.of(21)` and its `.set(...)` forms
+fn gradle_toolchain(script: &str) -> Option<u32> {
+    script
+        .match_indices(GRADLE_LANGUAGE_VERSION)
+        .find_map(|(start, _)| {
+            let rest =
+                script[start + GRADLE_LANGUAGE_VERSION.len()..].trim_start_matches(|c: char| {
+                    c.is_whitespace() || c.is_ascii_lowercase() || matches!(c, '.' | '=' | '(')
+                });
+            let argument = rest.strip_prefix(GRADLE_VERSION_FACTORY)?;
+            let argument = &argument[..argument.find(')')?];
+            argument.trim().trim_matches('"').parse().ok()
+        })
+}
+
+/// `sourceCompatibility = JavaVersion.VERSION_17`, `= '1.8'`, `= 21`, or
+/// `JavaVersion.toVersion(17)`
+fn gradle_source_compatibility(script: &str) -> Option<u32> {
+    script
+        .match_indices(GRADLE_SOURCE_COMPATIBILITY)
+        .find_map(|(start, _)| {
+            let rest = script[start + GRADLE_SOURCE_COMPATIBILITY.len()..]
+                .trim_start_matches([' ', '\t', '=']);
+            let value = rest.strip_prefix("JavaVersion.").unwrap_or(rest);
+            let value = value
+                .strip_prefix("VERSION_")
+                .or_else(|| value.strip_prefix("toVersion("))
+                .unwrap_or(value);
+            let value = value.trim_start_matches(['\'', '"']);
+            let end = value
+                .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '_'))
+                .unwrap_or(value.len());
+            parse_major(&value[..end].replace('_', "."))
+        })
+}
+
+fn element_text<'a>(xml: &'a str, element: &str) -> Option<&'a str> {
+    let open = format!("<{element}>");
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&format!("</{element}>"))?;
+    Some(xml[start..end].trim())
+}
+
+/// Feature release of a Maven version requirement such as `21`, `1.8`, or
+/// the range `[17,18)`
+fn parse_major(requirement: &str) -> Option<u32> {
+    let lower = requirement
+        .trim_start_matches(['[', '('])
+        .split([',', ')', ']'])
+        .next()?
+        .trim();
+    let mut parts = lower.split('.');
+    match parts.next()?.parse().ok()? {
+        1 => parts.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_maven_toolchain_requirements() {
+        let pom = r#"<plugin>
+  <artifactId>maven-toolchains-plugin</artifactId>
+  <configuration>
+    <toolchains>
+      <jdk>
+        <version>[17,18)</version>
+        <vendor>temurin</vendor>
+      </jdk>
+    </toolchains>
+  </configuration>
+</plugin>"#;
+        assert_eq!(maven_toolchain(pom), Some(17));
+        assert_eq!(
+            maven_toolchain("<jdkToolchain><version>1.8</version></jdkToolchain>"),
+            Some(8)
+        );
+        assert_eq!(
+            maven_toolchain("<properties><java.version>21</java.version></properties>"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_gradle_language_version() {
+        assert_eq!(
+            gradle_toolchain(
+                "java {\n    toolchain {\n        languageVersion = JavaLanguageVersion.of(21)\n    }\n}"
+            ),
+            Some(21)
+        );
+        assert_eq!(
+            gradle_toolchain("java.toolchain.languageVersion.set(JavaLanguageVersion.of(\"17\"))"),
+            Some(17)
+        );
+        assert_eq!(gradle_toolchain("sourceCompatibility = '11'"), None);
+    }
+
+    #[test]
+    fn test_maven_properties() {
+        let pom = "<properties>\n  <java.version>17</java.version>\n  \
+                   <maven.compiler.release>${java.version}</maven.compiler.release>\n</properties>";
+        assert_eq!(maven_version(pom), Some((17, "maven.compiler.release")));
+        assert_eq!(
+            maven_version("<java.version>1.8</java.version>"),
+            Some((8, "java.version"))
+        );
+        assert_eq!(
+            maven_version("<maven.compiler.release>${unset}</maven.compiler.release>"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_gradle_source_compatibility() {
+        for (script, major) in [
+            ("sourceCompatibility = JavaVersion.VERSION_17", 17),
+            ("java { sourceCompatibility = JavaVersion.VERSION_1_8 }", 8),
+            ("sourceCompatibility = '11'", 11),
+            ("sourceCompatibility = \"1.8\"", 8),
+            ("sourceCompatibility = 21", 21),
+            ("sourceCompatibility JavaVersion.toVersion(17)", 17),
+        ] {
+            assert_eq!(
+                gradle_version(script),
+                Some((major, "sourceCompatibility")),
+                "{script}"
+            );
+        }
+        assert_eq!(
+            gradle_version(
+                "sourceCompatibility = '11'\njava.toolchain.languageVersion = JavaLanguageVersion.of(21)"
+            ),
+            Some((21, "toolchain"))
+        );
+    }
+
+    #[test]
+    fn test_find_java_version_walks_up_to_the_parent_build() {
+        let temp_dir = TempDir::new().unwrap();
+        let module = temp_dir.path().join("app/core");
+        fs::create_dir_all(&module).unwrap();
+        fs::write(module.join("pom.xml"), "<project/>").unwrap();
+        assert_eq!(find_java_version(&module).unwrap(), None);
+
+        fs::write(
+            temp_dir.path().join("app/pom.xml"),
+            "<properties><java.version>21</java.version></properties>",
+        )
+        .unwrap();
+        let detected = find_java_version(&module).unwrap().unwrap();
+        assert_eq!(detected.major, 21);
+        assert_eq!(detected.file, temp_dir.path().join("app/pom.xml"));
+
+        // Directories without build files end the project
+        fs::write(
+            temp_dir.path().join("pom.xml"),
+            "<java.version>17</java.version>",
+        )
+        .unwrap();
+        fs::remove_file(temp_dir.path().join("app/pom.xml")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("other/src")).unwrap();
+        assert_eq!(
+            find_java_version(&temp_dir.path().join("other/src"))
+                .unwrap()
+                .unwrap()
+                .major,
+            17
+        );
+    }
+
+    #[test]
+    fn test_detect_java_version_prefers_pom() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(detect_java_version(temp_dir.path()).unwrap(), None);
+
+        fs::write(
+            temp_dir.path().join("build.gradle.kts"),
+            "java { toolchain { languageVersion.set(JavaLanguageVersion.of(21)) } }",
+        )
+        .unwrap();
+        let detected = detect_java_version(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(detected.major, 21);
+        assert_eq!(detected.declaration, "toolchain");
+        assert!(detected.file.ends_with("build.gradle.kts"));
+
+        fs::write(
+            temp_dir.path().join("pom.xml"),
+            "<jdk><version>11</version></jdk>",
+        )
+        .unwrap();
+        let detected = detect_java_version(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(detected.major, 11);
+        assert!(detected.file.ends_with("pom.xml"));
+    }
+}
@@ -14,12 +14,96 @@
 
 use crate::error::{KopiError, Result};
 use crate::models::package::PackageType;
-use crate::version::format_version_minimal;
+use crate::platform::file_ops;
 use crate::version::parser::ParsedVersionRequest;
+use crate::version::{Version, format_version_minimal};
 use log::debug;
+use std::cmp::Ordering;
+use std::fmt;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const LATEST_LTS: &str = "latest-lts";
+const LATEST_GA: &str = "latest-ga";
+const FEATURE_LATEST_SUFFIX: &str = "-latest";
+
+/// Symbolic pin that follows new releases instead of naming one version.
+///
+/// Version files may contain a track in place of a version, optionally with a
+/// distribution (`temurin@latest-lts`). Shims resolve tracks against installed
+/// JDKs; `kopi upgrade` installs newer releases that match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionTrack {
+    /// Newest GA release of a long-term-support feature version
+    LatestLts,
+    /// Newest GA release of any feature version
+    LatestGa,
+    /// Newest release of one feature version; early-access builds match until
+    /// a GA release of that version is available
+    FeatureLatest(u32),
+}
+
+impl VersionTrack {
+    /// Whether `version` belongs to this track
+    pub fn matches(&self, version: &Version) -> bool {
+        match self {
+            VersionTrack::LatestLts => {
+                !version.is_early_access() && is_lts_feature_release(version.major())
+            }
+            VersionTrack::LatestGa => !version.is_early_access(),
+            VersionTrack::FeatureLatest(major) => version.major() == *major,
+        }
+    }
+
+    /// Order candidates so the preferred release sorts last: higher versions
+    /// first, and a GA build beats an early-access build of the same version.
+    pub fn compare(a: &Version, b: &Version) -> Ordering {
+        a.components
+            .cmp(&b.components)
+            .then_with(|| b.is_early_access().cmp(&a.is_early_access()))
+            .then_with(|| a.build.cmp(&b.build))
+    }
+}
+
+impl fmt::Display for VersionTrack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionTrack::LatestLts => write!(f, "{LATEST_LTS}"),
+            VersionTrack::LatestGa => write!(f, "{LATEST_GA}"),
+            VersionTrack::FeatureLatest(major) => write!(f, "{major}{FEATURE_LATEST_SUFFIX}"),
+        }
+    }
+}
+
+impl FromStr for VersionTrack {
+    type Err = KopiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let value = s.trim().to_ascii_lowercase();
+        match value.as_str() {
+            LATEST_LTS => Ok(VersionTrack::LatestLts),
+            LATEST_GA => Ok(VersionTrack::LatestGa),
+            _ => value
+                .strip_suffix(FEATURE_LATEST_SUFFIX)
+                .and_then(|major| major.parse::<u32>().ok())
+                .map(VersionTrack::FeatureLatest)
+                .ok_or_else(|| {
+                    KopiError::InvalidVersionFormat(format!(
+                        "'{s}' is not a version track (expected 'latest-lts', 'latest-ga', or \
+                         '<major>-latest')"
+                    ))
+                }),
+        }
+    }
+}
+
+/// Java feature releases with long-term support: 8, 11, then every fourth
+/// release starting at 17
+pub fn is_lts_feature_release(major: u32) -> bool {
+    major == 8 || major == 11 || (major >= 17 && (major - 17) % 4 == 0)
+}
 
 /// Write a version file atomically to the specified path
 pub fn write_version_file(path: &PathBuf, version_request: &ParsedVersionRequest) -> Result<()> {
@@ -90,13 +174,191 @@ pub fn write_version_file(path: &PathBuf, version_request: &ParsedVersionRequest
     Ok(())
 }
 
+/// The pin in the contents of a version file: its first line that is
+/// neither blank nor a `#` comment
+pub fn version_file_pin(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// `previous` with its pin line replaced by `pin`, keeping comments and
+/// blank lines; a file without a pin gets `pin` appended
+pub fn render_pin(previous: Option<&str>, pin: &str) -> String {
+    let Some(previous) = previous.filter(|previous| !previous.trim().is_empty()) else {
+        return pin.to_string();
+    };
+
+    let mut rendered = String::with_capacity(previous.len() + pin.len());
+    let mut replaced = false;
+    for line in previous.split_inclusive('\n') {
+        let content = line.trim();
+        if !replaced && !content.is_empty() && !content.starts_with('#') {
+            rendered.push_str(pin);
+            rendered.push_str(&line[line.trim_end().len()..]);
+            replaced = true;
+        } else {
+            rendered.push_str(line);
+        }
+    }
+    if !replaced {
+        if !rendered.ends_with('\n') {
+            rendered.push('\n');
+        }
+        rendered.push_str(pin);
+        rendered.push('\n');
+    }
+    rendered
+}
+
+/// Atomically point the version file at `path` to `pin`, keeping its
+/// comments. Returns the previous content like [`replace_version_file`].
+pub fn write_pin(path: &Path, pin: &str) -> Result<Option<String>> {
+    let current = match fs::read_to_string(path) {
+        Ok(current) => Some(current),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            return Err(KopiError::SystemError(format!(
+                "Failed to read {}: {e}",
+                path.display()
+            )));
+        }
+    };
+    replace_version_file(path, &render_pin(current.as_deref(), pin))
+}
+
+/// Atomically replace the version file at `path` with `content`.
+///
+/// Returns what the file held before, so a caller that finds the new pin
+/// unusable can put it back with [`restore_version_file`].
+pub fn replace_version_file(path: &Path, content: &str) -> Result<Option<String>> {
+    let previous = match fs::read_to_string(path) {
+        Ok(previous) => Some(previous),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            return Err(KopiError::SystemError(format!(
+                "Failed to read {}: {e}",
+                path.display()
+            )));
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            KopiError::SystemError(format!(
+                "Failed to create directory {}: {e}",
+                parent.display()
+            ))
+        })?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content).map_err(|e| {
+        KopiError::SystemError(format!("Failed to write {}: {e}", temp_path.display()))
+    })?;
+    file_ops::atomic_rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        KopiError::SystemError(format!(
+            "Failed to rename {} to {}: {e}",
+            temp_path.display(),
+            path.display()
+        ))
+    })?;
+
+    debug!("Wrote version file: {path:?}");
+    Ok(previous)
+}
+
+/// Undo [`replace_version_file`]: write back the previous content, or
+/// remove the file if there was none
+pub fn restore_version_file(path: &Path, previous: Option<String>) -> Result<()> {
+    match previous {
+        Some(previous) => replace_version_file(path, &previous).map(|_| ()),
+        None => remove_version_file(path).map(|_| ()),
+    }
+}
+
+/// Delete a version file, refusing anything that is not a plain file or a
+/// symlink. Returns `false` when there was nothing to remove.
+pub fn remove_version_file(path: &Path) -> Result<bool> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    if !metadata.is_file() && !metadata.file_type().is_symlink() {
+        return Err(KopiError::SystemError(format!(
+            "Refusing to remove {}: not a version file",
+            path.display()
+        )));
+    }
+
+    // Removing a symlink deletes the link, never the file it points to
+    fs::remove_file(path)
+        .map_err(|e| KopiError::SystemError(format!("Failed to remove {}: {e}", path.display())))?;
+    debug!("Removed version file {}", path.display());
+    Ok(true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::distribution::Distribution;
-    use crate::version::Version;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_parse_version_tracks() {
+        assert_eq!(
+            VersionTrack::from_str("latest-lts").unwrap(),
+            VersionTrack::LatestLts
+        );
+        assert_eq!(
+            VersionTrack::from_str("LATEST-GA").unwrap(),
+            VersionTrack::LatestGa
+        );
+        assert_eq!(
+            VersionTrack::from_str("21-latest").unwrap(),
+            VersionTrack::FeatureLatest(21)
+        );
+        assert!(VersionTrack::from_str("21").is_err());
+        assert!(VersionTrack::from_str("x-latest").is_err());
+        assert_eq!(VersionTrack::FeatureLatest(25).to_string(), "25-latest");
+    }
+
+    #[test]
+    fn test_track_matching() {
+        let ea = Version::from_str("25-ea").unwrap();
+        let ga = Version::from_str("21.0.5").unwrap();
+        let non_lts = Version::from_str("23.0.1").unwrap();
+
+        assert!(VersionTrack::LatestLts.matches(&ga));
+        assert!(!VersionTrack::LatestLts.matches(&non_lts));
+        assert!(!VersionTrack::LatestLts.matches(&ea));
+        assert!(VersionTrack::LatestGa.matches(&non_lts));
+        assert!(!VersionTrack::LatestGa.matches(&ea));
+        assert!(VersionTrack::FeatureLatest(25).matches(&ea));
+        assert!(!VersionTrack::FeatureLatest(25).matches(&ga));
+    }
+
+    #[test]
+    fn test_track_prefers_ga_over_early_access() {
+        let ea = Version::from_str("25-ea").unwrap();
+        let ga = Version::from_str("25").unwrap();
+        assert_eq!(VersionTrack::compare(&ea, &ga), Ordering::Less);
+
+        let older = Version::from_str("21.0.4").unwrap();
+        let newer = Version::from_str("21.0.5").unwrap();
+        assert_eq!(VersionTrack::compare(&older, &newer), Ordering::Less);
+    }
+
+    #[test]
+    fn test_lts_feature_releases() {
+        let lts: Vec<u32> = (8..=29).filter(|m| is_lts_feature_release(*m)).collect();
+        assert_eq!(lts, vec![8, 11, 17, 21, 25, 29]);
+    }
+
     #[test]
     fn test_write_version_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -109,6 +371,7 @@ mod tests {
             package_type: Some(PackageType::Jdk),
             latest: false,
             javafx_bundled: None,
+            features: Vec::new(),
         };
 
         write_version_file(&version_file, &version_request).unwrap();
@@ -123,6 +386,7 @@ mod tests {
             package_type: Some(PackageType::Jdk),
             latest: false,
             javafx_bundled: None,
+            features: Vec::new(),
         };
 
         write_version_file(&version_file, &version_request2).unwrap();
@@ -142,6 +406,7 @@ mod tests {
             package_type: Some(PackageType::Jdk),
             latest: false,
             javafx_bundled: None,
+            features: Vec::new(),
         };
 
         write_version_file(&version_file, &version_request).unwrap();
@@ -161,6 +426,7 @@ mod tests {
             package_type: Some(PackageType::Jdk),
             latest: false,
             javafx_bundled: None,
+            features: Vec::new(),
         };
 
         write_version_file(&nested_path, &version_request).unwrap();
@@ -182,6 +448,7 @@ mod tests {
             package_type: Some(PackageType::Jre),
             latest: false,
             javafx_bundled: None,
+            features: Vec::new(),
         };
 
         write_version_file(&version_file, &version_request).unwrap();
@@ -196,6 +463,7 @@ mod tests {
             package_type: Some(PackageType::Jre),
             latest: false,
             javafx_bundled: None,
+            features: Vec::new(),
         };
 
         write_version_file(&version_file, &version_request2).unwrap();
@@ -203,4 +471,59 @@ mod tests {
         let content2 = fs::read_to_string(&version_file).unwrap();
         assert_eq!(content2, "jre@17");
     }
+
+    #[test]
+    fn test_render_pin_keeps_comments() {
+        assert_eq!(render_pin(None, "temurin@21"), "temurin@21");
+        assert_eq!(render_pin(Some("\n"), "temurin@21"), "temurin@21");
+        assert_eq!(
+            render_pin(Some("# CI uses this\n\ncorretto@17\n"), "temurin@21"),
+            "# CI uses this\n\ntemurin@21\n"
+        );
+        assert_eq!(
+            render_pin(Some("  corretto@17\r\n# trailing\n"), "21"),
+            "21\r\n# trailing\n"
+        );
+        assert_eq!(
+            render_pin(Some("# no pin yet"), "temurin@21"),
+            "# no pin yet\ntemurin@21\n"
+        );
+
+        let pinned = "# CI uses this\n\ntemurin@21\n";
+        assert_eq!(version_file_pin(pinned), Some("temurin@21"));
+        assert_eq!(version_file_pin("# only a comment\n"), None);
+    }
+
+    #[test]
+    fn test_write_pin_preserves_existing_comments() {
+        let temp_dir = TempDir::new().unwrap();
+        let version_file = temp_dir.path().join(".kopi-version");
+        fs::write(&version_file, "# Keep in sync with CI\ncorretto@17\n").unwrap();
+
+        let previous = write_pin(&version_file, "temurin@21").unwrap();
+        assert_eq!(
+            previous.as_deref(),
+            Some("# Keep in sync with CI\ncorretto@17\n")
+        );
+        assert_eq!(
+            fs::read_to_string(&version_file).unwrap(),
+            "# Keep in sync with CI\ntemurin@21\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_version_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let version_file = temp_dir.path().join(".kopi-version");
+
+        assert!(!remove_version_file(&version_file).unwrap());
+
+        fs::write(&version_file, "temurin@21").unwrap();
+        assert!(remove_version_file(&version_file).unwrap());
+        assert!(!version_file.exists());
+
+        fs::create_dir(&version_file).unwrap();
+        assert!(remove_version_file(&version_file).is_err());
+        assert!(version_file.is_dir());
+    }
 }
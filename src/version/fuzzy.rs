@@ -0,0 +1,196 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Correction of misspelled or aliased distribution names in version specs.
+
+use crate::models::distribution::Distribution;
+use crate::version::strip_variant_suffixes;
+
+/// Outcome of looking for an unknown distribution name in a version spec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DistributionCorrection {
+    /// `from` is an alias or a close misspelling of exactly one distribution;
+    /// `spec` is the version spec with `from` replaced by `to`
+    Resolved {
+        from: String,
+        to: String,
+        spec: String,
+    },
+    /// Several distributions are equally close to `name`
+    Ambiguous {
+        name: String,
+        candidates: Vec<String>,
+    },
+}
+
+/// Check the distribution part of `spec` against the `known` distribution
+/// names. Returns `None` when the spec names a known distribution, names none
+/// at all, or is too far from every known name to guess.
+pub fn correct_distribution(spec: &str, known: &[String]) -> Option<DistributionCorrection> {
+    let spec = spec.trim();
+    let (prefix, rest) = ["jre@", "jdk@"]
+        .into_iter()
+        .find_map(|prefix| spec.strip_prefix(prefix).map(|rest| (prefix, rest)))
+        .unwrap_or(("", spec));
+
+    let name = match rest.split_once('@') {
+        Some((name, _)) => name,
+        None => strip_variant_suffixes(rest).0,
+    };
+    let suffix = &rest[name.len()..];
+
+    if name.is_empty()
+        || name.chars().next().is_some_and(|c| c.is_ascii_digit())
+        || name.eq_ignore_ascii_case("latest")
+        || known.iter().any(|known| known.eq_ignore_ascii_case(name))
+    {
+        return None;
+    }
+
+    let resolved = |to: String| DistributionCorrection::Resolved {
+        from: name.to_string(),
+        spec: format!("{prefix}{to}{suffix}"),
+        to,
+    };
+
+    if let Some(distribution) = Distribution::from_alias(name) {
+        return Some(resolved(distribution.id().to_string()));
+    }
+
+    let lower = name.to_lowercase();
+    let max_distance = if lower.chars().count() <= 4 { 1 } else { 2 };
+    let mut best = max_distance + 1;
+    let mut candidates: Vec<String> = Vec::new();
+    for candidate in known {
+        let distance = edit_distance(&lower, &candidate.to_lowercase());
+        if distance < best {
+            best = distance;
+            candidates.clear();
+        }
+        if distance == best && !candidates.contains(candidate) {
+            candidates.push(candidate.clone());
+        }
+    }
+
+    match candidates.len() {
+        0 => None,
+        1 => Some(resolved(candidates.remove(0))),
+        _ => Some(DistributionCorrection::Ambiguous {
+            name: name.to_string(),
+            candidates,
+        }),
+    }
+}
+
+/// Number of single-character insertions, deletions, substitutions and
+/// adjacent transpositions that turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=b.len() {
+        rows[0][j] = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known() -> Vec<String> {
+        Distribution::known_distributions()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn resolved(from: &str, to: &str, spec: &str) -> Option<DistributionCorrection> {
+        Some(DistributionCorrection::Resolved {
+            from: from.to_string(),
+            to: to.to_string(),
+            spec: spec.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_correct_distribution_typos_and_aliases() {
+        let known = known();
+        assert_eq!(
+            correct_distribution("temurn@21", &known),
+            resolved("temurn", "temurin", "temurin@21")
+        );
+        assert_eq!(
+            correct_distribution("jre@corretoo@17+fx", &known),
+            resolved("corretoo", "corretto", "jre@corretto@17+fx")
+        );
+        assert_eq!(
+            correct_distribution("tmeurin", &known),
+            resolved("tmeurin", "temurin", "temurin")
+        );
+        assert_eq!(
+            correct_distribution("Adoptium@21", &known),
+            resolved("Adoptium", "temurin", "temurin@21")
+        );
+        assert_eq!(
+            correct_distribution("amazon", &known),
+            resolved("amazon", "corretto", "corretto")
+        );
+    }
+
+    #[test]
+    fn test_correct_distribution_leaves_valid_specs_alone() {
+        let known = known();
+        for spec in ["21", "temurin@21", "Zulu", "latest", "jre@17", "21+fx", ""] {
+            assert_eq!(correct_distribution(spec, &known), None, "{spec}");
+        }
+        assert_eq!(correct_distribution("xyzzy@21", &known), None);
+    }
+
+    #[test]
+    fn test_correct_distribution_ambiguous() {
+        let known = vec!["kona".to_string(), "nona".to_string()];
+        assert_eq!(
+            correct_distribution("oona@21", &known),
+            Some(DistributionCorrection::Ambiguous {
+                name: "oona".to_string(),
+                candidates: known.clone(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("temurin", "temurin"), 0);
+        assert_eq!(edit_distance("temurn", "temurin"), 1);
+        assert_eq!(edit_distance("tmeurin", "temurin"), 1);
+        assert_eq!(edit_distance("", "zulu"), 4);
+    }
+}
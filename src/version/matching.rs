@@ -0,0 +1,270 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Version matching shared by cache search, installation lookup, shims,
+//! `which`, and uninstall safety checks.
+//!
+//! Every place that decides whether a JDK satisfies a user's request goes
+//! through this module, so a version that `kopi install` can find in the
+//! cache is also found once it is installed.
+
+use crate::storage::InstalledJdk;
+use crate::version::{Version, VersionRequest};
+use std::str::FromStr;
+
+/// Whether `version` satisfies the user-supplied `pattern`.
+///
+/// Patterns that fail to parse never match. See [`matches`] for the rules.
+pub fn matches_pattern(version: &Version, pattern: &str) -> bool {
+    match Version::from_str(pattern) {
+        Ok(pattern_version) => {
+            log::trace!("Matching version {version} against pattern {pattern}");
+            matches(version, &pattern_version)
+        }
+        Err(_) => {
+            log::trace!("Failed to parse pattern: {pattern}");
+            false
+        }
+    }
+}
+
+/// Whether `version` satisfies `pattern`.
+///
+/// - The pattern's components are a prefix: "21" matches "21.0" and "21.0.5+11", but
+///   "21.0.0" does not match "21".
+/// - A build or pre-release in the pattern must match exactly.
+/// - Some distributions fold the build number into the components (Corretto's "21.0.5.11.1",
+///   Dragonwell's "21.0.7.0.7.6"), so "X.Y.Z+B" also matches "X.Y.Z.B" and "X.Y.Z.B.*".
+/// - Conversely, "X.Y.Z.B" matches "X.Y.Z+B".
+pub fn matches(version: &Version, pattern: &Version) -> bool {
+    if matches_standard(version, pattern) {
+        log::trace!("Standard match succeeded");
+        return true;
+    }
+
+    if matches_build_in_components(version, pattern) {
+        log::trace!("Flexible build match succeeded");
+        return true;
+    }
+
+    if matches_components_as_build(version, pattern) {
+        log::trace!("Reverse flexible build match succeeded");
+        return true;
+    }
+
+    log::trace!("No match found");
+    false
+}
+
+/// Whether `version` satisfies `request`'s version pattern or track
+pub fn matches_request(request: &VersionRequest, version: &Version) -> bool {
+    match &request.track {
+        Some(track) => track.matches(version),
+        None => matches_pattern(version, &request.version_pattern),
+    }
+}
+
+/// Whether an installed distribution directory name refers to `requested`.
+///
+/// Names are compared case-insensitively, matching how distributions are parsed.
+pub fn distribution_matches(installed: &str, requested: &str) -> bool {
+    installed.eq_ignore_ascii_case(requested)
+}
+
+/// Whether an installed JDK satisfies `request`'s distribution, JavaFX, and
+/// version constraints. Constraints the request leaves unset match anything.
+pub fn matches_installed(request: &VersionRequest, jdk: &InstalledJdk) -> bool {
+    if request
+        .distribution
+        .as_deref()
+        .is_some_and(|distribution| !distribution_matches(&jdk.distribution, distribution))
+    {
+        return false;
+    }
+
+    if request
+        .javafx_bundled
+        .is_some_and(|javafx| javafx != jdk.javafx_bundled)
+    {
+        return false;
+    }
+
+    matches_request(request, &jdk.version)
+}
+
+/// Prefix matching of components plus exact build and pre-release matching
+fn matches_standard(version: &Version, pattern: &Version) -> bool {
+    if pattern.components.len() > version.components.len()
+        || !version.components.starts_with(&pattern.components)
+    {
+        return false;
+    }
+
+    if let Some(pattern_build) = &pattern.build
+        && version.build.as_ref() != Some(pattern_build)
+    {
+        return false;
+    }
+
+    if let Some(pattern_pre) = &pattern.pre_release
+        && version.pre_release.as_ref() != Some(pattern_pre)
+    {
+        return false;
+    }
+
+    true
+}
+
+/// Pattern "24.0.2+12" against a version that carries the build as a component, "24.0.2.12.1"
+fn matches_build_in_components(version: &Version, pattern: &Version) -> bool {
+    let Some([build]) = pattern.build.as_deref() else {
+        return false;
+    };
+    let prefix_len = pattern.components.len();
+
+    version.components.len() > prefix_len
+        && version.components.starts_with(&pattern.components)
+        && version.components[prefix_len] == *build
+}
+
+/// Pattern "21.0.5.11" against a version with a separate build, "21.0.5+11"
+fn matches_components_as_build(version: &Version, pattern: &Version) -> bool {
+    let Some([build]) = version.build.as_deref() else {
+        return false;
+    };
+
+    pattern.build.is_none()
+        && pattern.components.len() == version.components.len() + 1
+        && pattern.components.starts_with(&version.components)
+        && pattern.components.last() == Some(build)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn v(s: &str) -> Version {
+        Version::from_str(s).unwrap()
+    }
+
+    fn installed(distribution: &str, version: &str, javafx: bool) -> InstalledJdk {
+        InstalledJdk::new(
+            distribution.to_string(),
+            v(version),
+            PathBuf::from(format!("/jdks/{distribution}-{version}")),
+            javafx,
+        )
+    }
+
+    #[test]
+    fn test_component_prefix() {
+        assert!(matches_pattern(&v("21.0.5"), "21"));
+        assert!(matches_pattern(&v("21.0.5"), "21.0"));
+        assert!(matches_pattern(&v("21.0.5"), "21.0.5"));
+        assert!(!matches_pattern(&v("21.0.5"), "21.0.4"));
+        assert!(!matches_pattern(&v("21.0.5"), "22"));
+        assert!(!matches_pattern(&v("21"), "21.0"));
+        assert!(!matches_pattern(&v("21.0"), "21.0.0"));
+        assert!(!matches_pattern(&v("21.0.5"), "not-a-version"));
+    }
+
+    #[test]
+    fn test_build_and_pre_release_are_exact() {
+        assert!(matches_pattern(&v("21.0.5+11"), "21.0.5"));
+        assert!(matches_pattern(&v("21.0.5+11"), "21.0.5+11"));
+        assert!(!matches_pattern(&v("21.0.5+11"), "21.0.5+12"));
+        assert!(!matches_pattern(&v("21.0.5"), "21.0.5+11"));
+        assert!(matches_pattern(&v("21.0.5+13.674.11"), "21.0.5+13.674.11"));
+        assert!(!matches_pattern(&v("21.0.5+13.674.11"), "21.0.5+13.674"));
+
+        assert!(matches_pattern(&v("21.0.5-ea"), "21"));
+        assert!(matches_pattern(&v("21.0.5-ea"), "21.0.5-ea"));
+        assert!(!matches_pattern(&v("21.0.5-ea"), "21.0.5-beta"));
+        assert!(!matches_pattern(&v("21.0.5"), "21.0.5-ea"));
+    }
+
+    #[test]
+    fn test_build_folded_into_components() {
+        // Corretto and Dragonwell report the build as a version component
+        assert!(matches_pattern(&v("21.0.5.11.1"), "21.0.5+11"));
+        assert!(matches_pattern(&v("24.0.2.12"), "24.0.2+12"));
+        assert!(matches_pattern(&v("21.0.7.0.7.6"), "21.0.7.0.7"));
+        assert!(!matches_pattern(&v("21.0.5.12.1"), "21.0.5+11"));
+        assert!(!matches_pattern(&v("21.0.6.11.1"), "21.0.5+11"));
+        // The build must sit right after the pattern's components
+        assert!(matches_pattern(&v("21.0.5"), "21.0+5"));
+        assert!(!matches_pattern(&v("21.0.5.11"), "21.0+11"));
+        // Multi-part builds are never folded
+        assert!(!matches_pattern(&v("21.0.5.11.1"), "21.0.5+11.1"));
+    }
+
+    #[test]
+    fn test_components_as_build() {
+        assert!(matches_pattern(&v("21.0.5+11"), "21.0.5.11"));
+        assert!(!matches_pattern(&v("21.0.5+11"), "21.0.5.12"));
+        assert!(!matches_pattern(&v("21.0.5+11"), "21.0.4.11"));
+        assert!(!matches_pattern(&v("21.0.5+11"), "21.0.5.11.1"));
+        assert!(!matches_pattern(&v("21.0.5"), "21.0.5.11"));
+    }
+
+    #[test]
+    fn test_matches_request_uses_track() {
+        let request = VersionRequest::new("21-latest".to_string()).unwrap();
+        assert!(matches_request(&request, &v("21.0.5+11")));
+        assert!(!matches_request(&request, &v("17.0.13")));
+
+        let request = VersionRequest::new("17.0".to_string()).unwrap();
+        assert!(matches_request(&request, &v("17.0.13")));
+        assert!(!matches_request(&request, &v("17.1")));
+    }
+
+    #[test]
+    fn test_matches_installed() {
+        let jdk = installed("temurin", "21.0.5+11", false);
+
+        let request = VersionRequest::new("21".to_string()).unwrap();
+        assert!(matches_installed(&request, &jdk));
+
+        let request = request.with_distribution("Temurin".to_string());
+        assert!(matches_installed(&request, &jdk));
+
+        let request = VersionRequest::new("21".to_string())
+            .unwrap()
+            .with_distribution("zulu".to_string());
+        assert!(!matches_installed(&request, &jdk));
+
+        let request = VersionRequest::new("21".to_string())
+            .unwrap()
+            .with_javafx_bundled(true);
+        assert!(!matches_installed(&request, &jdk));
+        assert!(matches_installed(
+            &request,
+            &installed("temurin", "21.0.5+11", true)
+        ));
+    }
+
+    #[test]
+    fn test_installed_version_found_with_install_pattern() {
+        // A pattern that selects a cached Corretto package must also find it once installed
+        let request = VersionRequest::new("21.0.5+11".to_string())
+            .unwrap()
+            .with_distribution("corretto".to_string());
+        assert!(matches_pattern(&v("21.0.5.11.1"), &request.version_pattern));
+        assert!(matches_installed(
+            &request,
+            &installed("corretto", "21.0.5.11.1", false)
+        ));
+    }
+}
@@ -13,14 +13,53 @@
 // limitations under the License.
 
 use crate::error::{KopiError, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
+pub mod build_file;
 pub mod file;
+pub mod fuzzy;
+pub mod matching;
 pub mod parser;
 pub mod resolver;
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+/// Version alias that hands the tools back to the first JDK on `PATH` outside kopi
+pub const SYSTEM_VERSION: &str = "system";
+
+/// Build features a version spec can ask for with a `+<feature>` suffix,
+/// e.g. `21+crac`
+pub const FEATURE_SUFFIXES: [&str; 2] = ["crac", "leyden"];
+
+/// Split the trailing `+fx` and `+<feature>` suffixes off `spec`, in any
+/// order. Returns the rest of the spec, whether `+fx` was given, and the
+/// requested features sorted.
+pub fn strip_variant_suffixes(spec: &str) -> (&str, Option<bool>, Vec<String>) {
+    let mut remaining = spec;
+    let mut javafx_bundled = None;
+    let mut features = Vec::new();
+    loop {
+        if let Some(stripped) = remaining.strip_suffix("+fx") {
+            javafx_bundled = Some(true);
+            remaining = stripped;
+        } else if let Some((stripped, feature)) = FEATURE_SUFFIXES.iter().find_map(|feature| {
+            remaining
+                .strip_suffix(feature)
+                .and_then(|rest| rest.strip_suffix('+'))
+                .map(|rest| (rest, feature))
+        }) {
+            features.push(feature.to_string());
+            remaining = stripped;
+        } else {
+            break;
+        }
+    }
+    features.sort();
+    features.dedup();
+    (remaining, javafx_bundled, features)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
 pub struct Version {
     pub components: Vec<u32>,        // All numeric components
     pub build: Option<Vec<u32>>,     // Build numbers as numeric array
@@ -126,134 +165,20 @@ impl Version {
         self.components.get(2).copied()
     }
 
+    /// Whether this is an early-access build (pre-release such as `ea`)
+    pub fn is_early_access(&self) -> bool {
+        self.pre_release
+            .as_deref()
+            .is_some_and(|pre| pre.to_ascii_lowercase().contains("ea"))
+    }
+
     /// Matches a version string against this version.
     /// When the user specifies "21", it matches cache entries like "21.0" and "21.0.0".
     /// When the user specifies "21.0.0", it does NOT match cache entries like "21".
     /// When the user specifies "21.0", it matches cache entries like "21.0.0" and "21.0+32".
     /// When the user specifies "X.Y.Z+B", it also matches "X.Y.Z.B" or "X.Y.Z.B.*" (build incorporated into components).
     pub fn matches_pattern(&self, pattern: &str) -> bool {
-        if let Ok(pattern_version) = Version::from_str(pattern) {
-            log::trace!("Matching version {self} against pattern {pattern}");
-
-            // First try standard matching
-            if self.matches_standard(&pattern_version) {
-                log::trace!("Standard match succeeded");
-                return true;
-            }
-
-            // If pattern has a build number, try flexible build matching
-            // This handles cases where build numbers are incorporated into version components
-            // e.g., pattern "24.0.2+12" matches "24.0.2.12.1"
-            if let Some(pattern_build) = &pattern_version.build
-                && pattern_build.len() == 1
-            {
-                let build_num = pattern_build[0];
-                let pattern_comp_len = pattern_version.components.len();
-
-                log::trace!(
-                    "Trying flexible build matching: pattern has build {build_num}, self has {} components",
-                    self.components.len()
-                );
-
-                // Check if self has the pattern components followed by the build number
-                if self.components.len() > pattern_comp_len {
-                    // Check that initial components match
-                    for (i, pattern_comp) in pattern_version.components.iter().enumerate() {
-                        if self.components.get(i) != Some(pattern_comp) {
-                            log::trace!(
-                                "Component mismatch at index {i}: {pattern_comp} != {:?}",
-                                self.components.get(i)
-                            );
-                            return false;
-                        }
-                    }
-
-                    // Check if the next component matches the build number
-                    if self.components.get(pattern_comp_len) == Some(&build_num) {
-                        // This handles cases like:
-                        // pattern "24.0.2+12" matches "24.0.2.12" or "24.0.2.12.1"
-                        log::trace!("Flexible build match succeeded");
-                        return true;
-                    } else {
-                        log::trace!(
-                            "Build number mismatch: expected {build_num}, got {:?}",
-                            self.components.get(pattern_comp_len)
-                        );
-                    }
-                }
-            }
-
-            // Also handle the reverse case: pattern without build but self has build
-            // e.g., pattern "21.0.5.11" should match self "21.0.5+11"
-            if self.build.is_some()
-                && self.build.as_ref().unwrap().len() == 1
-                && pattern_version.build.is_none()
-                && pattern_version.components.len() == self.components.len() + 1
-            {
-                // Check if pattern's last component matches our build number
-                let build_num = self.build.as_ref().unwrap()[0];
-                let pattern_last_comp = pattern_version.components.last().unwrap();
-
-                if *pattern_last_comp == build_num {
-                    // Check that all other components match
-                    for i in 0..self.components.len() {
-                        if self.components[i] != pattern_version.components[i] {
-                            return false;
-                        }
-                    }
-                    log::trace!("Reverse flexible build match succeeded");
-                    return true;
-                }
-            }
-
-            log::trace!("No match found");
-            false
-        } else {
-            log::trace!("Failed to parse pattern: {pattern}");
-            false
-        }
-    }
-
-    /// Standard version matching without flexible build handling
-    fn matches_standard(&self, pattern_version: &Version) -> bool {
-        // Compare components up to the length specified in pattern
-        for (i, pattern_comp) in pattern_version.components.iter().enumerate() {
-            match self.components.get(i) {
-                Some(self_comp) => {
-                    if pattern_comp != self_comp {
-                        return false;
-                    }
-                }
-                None => {
-                    // Pattern specifies more components than self has
-                    return false;
-                }
-            }
-        }
-
-        // Build matching if specified
-        if let Some(pattern_build) = &pattern_version.build {
-            if let Some(self_build) = &self.build {
-                if pattern_build != self_build {
-                    return false;
-                }
-            } else {
-                return false;
-            }
-        }
-
-        // Pre-release matching if specified
-        if let Some(pattern_pre) = &pattern_version.pre_release {
-            if let Some(self_pre) = &self.pre_release {
-                if pattern_pre != self_pre {
-                    return false;
-                }
-            } else {
-                return false;
-            }
-        }
-
-        true
+        matching::matches_pattern(self, pattern)
     }
 }
 
@@ -416,6 +341,10 @@ pub struct VersionRequest {
     pub distribution: Option<String>,
     pub package_type: Option<crate::models::package::PackageType>,
     pub javafx_bundled: Option<bool>,
+    /// Build features requested with `+crac` or `+leyden`, sorted
+    pub features: Vec<String>,
+    /// Symbolic track (e.g. `latest-lts`) when the pattern is not a version
+    pub track: Option<file::VersionTrack>,
 }
 
 impl VersionRequest {
@@ -427,6 +356,14 @@ impl VersionRequest {
             ));
         }
 
+        if let Ok(track) = file::VersionTrack::from_str(&version_pattern) {
+            return Ok(Self::for_track(track));
+        }
+
+        if version_pattern.eq_ignore_ascii_case(SYSTEM_VERSION) {
+            return Ok(Self::system());
+        }
+
         // Validate that the pattern can be parsed as a version
         Version::from_str(&version_pattern)?;
         Ok(Self {
@@ -434,9 +371,57 @@ impl VersionRequest {
             distribution: None,
             package_type: None,
             javafx_bundled: None,
+            features: Vec::new(),
+            track: None,
         })
     }
 
+    /// Request that follows a version track instead of a fixed version
+    pub fn for_track(track: file::VersionTrack) -> Self {
+        Self {
+            version_pattern: track.to_string(),
+            distribution: None,
+            package_type: None,
+            javafx_bundled: None,
+            features: Vec::new(),
+            track: Some(track),
+        }
+    }
+
+    /// Request for the JDK found on `PATH` outside the kopi shims directory
+    pub fn system() -> Self {
+        Self {
+            version_pattern: SYSTEM_VERSION.to_string(),
+            distribution: None,
+            package_type: None,
+            javafx_bundled: None,
+            features: Vec::new(),
+            track: None,
+        }
+    }
+
+    /// Whether this request opts out of kopi-managed JDKs
+    pub fn is_system(&self) -> bool {
+        self.version_pattern == SYSTEM_VERSION
+    }
+
+    /// Parse a specification that must name a track (e.g. `temurin@latest-lts`)
+    pub fn parse_track(spec: &str) -> Result<Self> {
+        let request = Self::from_str(spec)?;
+        if request.track.is_none() {
+            return Err(KopiError::InvalidVersionFormat(format!(
+                "'{spec}' is not a version track (expected 'latest-lts', 'latest-ga', or \
+                 '<major>-latest')"
+            )));
+        }
+        Ok(request)
+    }
+
+    /// Whether an installed version satisfies this request's pattern or track
+    pub fn matches_version(&self, version: &Version) -> bool {
+        matching::matches_request(self, version)
+    }
+
     pub fn with_distribution(mut self, distribution: String) -> Self {
         self.distribution = Some(distribution);
         self
@@ -451,6 +436,24 @@ impl VersionRequest {
         self.javafx_bundled = Some(javafx_bundled);
         self
     }
+
+    pub fn with_features(mut self, features: Vec<String>) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// The `+crac`, `+leyden`, and `+fx` suffixes of this request
+    pub fn variant_suffix(&self) -> String {
+        let mut suffix: String = self
+            .features
+            .iter()
+            .map(|feature| format!("+{feature}"))
+            .collect();
+        if self.javafx_bundled == Some(true) {
+            suffix.push_str("+fx");
+        }
+        suffix
+    }
 }
 
 impl std::fmt::Display for VersionRequest {
@@ -460,12 +463,8 @@ impl std::fmt::Display for VersionRequest {
             None => self.version_pattern.clone(),
         };
 
-        // Append JavaFX suffix if specified
-        if self.javafx_bundled == Some(true) {
-            write!(f, "{base}+fx")
-        } else {
-            write!(f, "{base}")
-        }
+        // Append feature and JavaFX suffixes if specified
+        write!(f, "{base}{}", self.variant_suffix())
     }
 }
 
@@ -473,12 +472,8 @@ impl FromStr for VersionRequest {
     type Err = KopiError;
 
     fn from_str(s: &str) -> Result<Self> {
-        // Check for JavaFX suffix (+fx at the end)
-        let (javafx_bundled, remaining) = if let Some(stripped) = s.strip_suffix("+fx") {
-            (Some(true), stripped)
-        } else {
-            (None, s)
-        };
+        // Check for JavaFX and feature suffixes (+fx, +crac, +leyden at the end)
+        let (remaining, javafx_bundled, features) = strip_variant_suffixes(s);
 
         let mut request = if remaining.contains('@') {
             let parts: Vec<&str> = remaining.split('@').collect();
@@ -501,10 +496,24 @@ impl FromStr for VersionRequest {
             VersionRequest::new(remaining.to_string())?
         };
 
+        // The system JDK is not kopi's to qualify
+        if request.is_system()
+            && (request.distribution.is_some()
+                || request.package_type.is_some()
+                || javafx_bundled.is_some()
+                || !features.is_empty())
+        {
+            return Err(KopiError::InvalidVersionFormat(format!(
+                "'{s}': '{SYSTEM_VERSION}' cannot be combined with a distribution, package type, \
+                 or a '+fx', '+crac', or '+leyden' suffix"
+            )));
+        }
+
         // Apply JavaFX bundled flag if present
         if let Some(javafx) = javafx_bundled {
             request = request.with_javafx_bundled(javafx);
         }
+        request = request.with_features(features);
 
         Ok(request)
     }
@@ -549,6 +558,8 @@ pub fn build_install_request(
         version_pattern: version.to_string(),
         package_type: None,
         javafx_bundled: None,
+        features: Vec::new(),
+        track: None,
     }
 }
 
@@ -742,6 +753,42 @@ mod tests {
         assert!(VersionRequest::from_str("invalid_type@21@temurin").is_err()); // Invalid package type
     }
 
+    #[test]
+    fn test_system_version_request() {
+        let req = VersionRequest::from_str("system").unwrap();
+        assert!(req.is_system());
+        assert_eq!(req.to_string(), "system");
+        assert!(
+            VersionRequest::new("System".to_string())
+                .unwrap()
+                .is_system()
+        );
+        assert!(!VersionRequest::from_str("21").unwrap().is_system());
+
+        assert!(VersionRequest::from_str("temurin@system").is_err());
+        assert!(VersionRequest::from_str("jre@system@temurin").is_err());
+        assert!(VersionRequest::from_str("system+fx").is_err());
+    }
+
+    #[test]
+    fn test_version_request_with_features() {
+        let req = VersionRequest::from_str("zulu@21+crac").unwrap();
+        assert_eq!(req.version_pattern, "21");
+        assert_eq!(req.features, vec!["crac"]);
+        assert_eq!(req.javafx_bundled, None);
+        assert_eq!(req.to_string(), "zulu@21+crac");
+
+        // Suffixes combine in any order and print in a fixed one
+        let req = VersionRequest::from_str("21.0.5+11+fx+leyden+crac").unwrap();
+        assert_eq!(req.version_pattern, "21.0.5+11");
+        assert_eq!(req.features, vec!["crac", "leyden"]);
+        assert_eq!(req.javafx_bundled, Some(true));
+        assert_eq!(req.to_string(), "21.0.5+11+crac+leyden+fx");
+
+        assert!(VersionRequest::from_str("21").unwrap().features.is_empty());
+        assert!(VersionRequest::from_str("system+crac").is_err());
+    }
+
     #[test]
     fn test_version_request_with_javafx() {
         // Test version with JavaFX
@@ -16,7 +16,7 @@ use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
 use crate::models::distribution::Distribution;
 use crate::models::package::PackageType;
-use crate::version::Version;
+use crate::version::{Version, strip_variant_suffixes};
 use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -26,6 +26,8 @@ pub struct ParsedVersionRequest {
     pub package_type: Option<PackageType>,
     pub latest: bool,
     pub javafx_bundled: Option<bool>,
+    /// Build features requested with `+crac` or `+leyden`, sorted
+    pub features: Vec<String>,
 }
 
 pub struct VersionParser<'a> {
@@ -55,12 +57,8 @@ impl<'a> VersionParser<'a> {
             (Some(PackageType::Jdk), trimmed)
         };
 
-        // Check for JavaFX suffix (+fx at the end)
-        let (javafx_bundled, remaining) = if let Some(stripped) = remaining.strip_suffix("+fx") {
-            (Some(true), stripped)
-        } else {
-            (None, remaining)
-        };
+        // Check for JavaFX and feature suffixes (+fx, +crac, +leyden at the end)
+        let (remaining, javafx_bundled, features) = strip_variant_suffixes(remaining);
 
         // Check for "latest" keyword
         if remaining.eq_ignore_ascii_case("latest") {
@@ -70,6 +68,7 @@ impl<'a> VersionParser<'a> {
                 package_type,
                 latest: true,
                 javafx_bundled,
+                features,
             });
         }
 
@@ -106,6 +105,7 @@ impl<'a> VersionParser<'a> {
                     package_type,
                     latest: false,
                     javafx_bundled,
+                    features,
                 });
             }
 
@@ -117,6 +117,7 @@ impl<'a> VersionParser<'a> {
                     package_type,
                     latest: true,
                     javafx_bundled,
+                    features,
                 });
             }
 
@@ -141,6 +142,7 @@ impl<'a> VersionParser<'a> {
                     package_type,
                     latest: false,
                     javafx_bundled,
+                    features,
                 });
             } else {
                 // It's a version string
@@ -157,6 +159,7 @@ impl<'a> VersionParser<'a> {
             package_type,
             latest: false,
             javafx_bundled,
+            features,
         })
     }
 
@@ -244,6 +247,16 @@ impl<'a> VersionParser<'a> {
         )
     }
 
+    /// Built-in distribution ids followed by the configured additional and
+    /// custom distributions
+    pub fn known_distribution_names(&self) -> Vec<String> {
+        Distribution::known_distributions()
+            .into_iter()
+            .chain(self.config.extra_distribution_names())
+            .map(str::to_string)
+            .collect()
+    }
+
     fn is_known_distribution(&self, name: &str) -> bool {
         // First check if it looks like a version (starts with a digit)
         if name.chars().next().is_some_and(|c| c.is_ascii_digit()) {
@@ -258,10 +271,9 @@ impl<'a> VersionParser<'a> {
             return true;
         }
 
-        // Check against additional distributions from config
+        // Check against additional and custom distributions from config
         self.config
-            .additional_distributions
-            .iter()
+            .extra_distribution_names()
             .any(|dist| dist.eq_ignore_ascii_case(name))
     }
 }
@@ -667,6 +679,43 @@ additional_distributions = ["mycustom", "private-jdk", "company-build"]
         }
     }
 
+    #[test]
+    fn test_custom_distribution_names() {
+        let mut config = create_test_config();
+        config
+            .custom_distributions
+            .push(crate::config::CustomDistributionConfig {
+                name: "acme".to_string(),
+                metadata_url: "https://jdk.acme.example/metadata.json".to_string(),
+            });
+        let parser = VersionParser::new(&config);
+
+        let result = parser.parse("acme@21").unwrap();
+        assert_eq!(
+            result.distribution,
+            Some(Distribution::Other("acme".to_string()))
+        );
+        assert_eq!(result.version.unwrap().major(), 21);
+    }
+
+    #[test]
+    fn test_parse_with_features() {
+        let config = create_test_config();
+        let parser = VersionParser::new(&config);
+
+        let result = parser.parse("zulu@21+crac").unwrap();
+        assert_eq!(result.features, vec!["crac"]);
+        assert_eq!(result.distribution, Some(Distribution::Zulu));
+        assert_eq!(result.version.unwrap().to_string(), "21");
+
+        let result = parser.parse("jre@latest+fx+leyden").unwrap();
+        assert!(result.latest);
+        assert_eq!(result.javafx_bundled, Some(true));
+        assert_eq!(result.features, vec!["leyden"]);
+
+        assert!(parser.parse("21").unwrap().features.is_empty());
+    }
+
     #[test]
     fn test_parse_with_javafx() {
         let config = create_test_config();
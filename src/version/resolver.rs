@@ -14,7 +14,11 @@
 
 use crate::config::KopiConfig;
 use crate::error::{KopiError, Result};
+use crate::shim::security::SecurityValidator;
 use crate::version::VersionRequest;
+use crate::version::file::version_file_pin;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -22,21 +26,57 @@ use std::str::FromStr;
 
 const KOPI_VERSION_FILE: &str = ".kopi-version";
 const JAVA_VERSION_FILE: &str = ".java-version";
+pub const PROJECT_CONFIG_FILE: &str = ".kopi.toml";
 const VERSION_ENV_VAR: &str = "KOPI_JAVA_VERSION";
+const OVERRIDE_ENV_VAR: &str = "KOPI_USE";
 
 // Type alias to simplify complex return type
 type VersionSearchResult = (Option<(VersionRequest, PathBuf)>, Vec<String>);
 
+/// Where a resolved version came from.
+///
+/// Variants are listed in precedence order: an explicit CLI argument (handled
+/// by the individual commands) wins over a one-off `KOPI_USE` override, which
+/// wins over `KOPI_JAVA_VERSION`, which wins over a per-tool pin (only
+/// consulted when resolving for a specific tool), which wins over project
+/// files, which win over the global default.
 #[derive(Debug, Clone, PartialEq)]
 pub enum VersionSource {
+    Override(String),       // KOPI_USE
     Environment(String),    // KOPI_JAVA_VERSION
+    ToolOverride(PathBuf),  // [tools] entry in .kopi.toml
     ProjectFile(PathBuf),   // .kopi-version or .java-version
     GlobalDefault(PathBuf), // ~/.kopi/version
 }
 
+/// Project-level settings read from `.kopi.toml`
+#[derive(Debug, Default, Deserialize)]
+struct ProjectConfig {
+    /// Tool name to version spec, e.g. `native-image = "graalvm@21"`
+    #[serde(default)]
+    tools: HashMap<String, String>,
+}
+
+/// Read the `[tools]` pins from a `.kopi.toml` file
+pub(crate) fn read_tool_pins(path: &Path) -> Result<HashMap<String, String>> {
+    let content = fs::read_to_string(path)?;
+    let project_config: ProjectConfig = toml::from_str(&content)
+        .map_err(|e| KopiError::ConfigError(format!("Failed to parse {}: {e}", path.display())))?;
+    Ok(project_config.tools)
+}
+
+/// `KOPI_USE` and `KOPI_JAVA_VERSION` as seen by a daemon client, used
+/// instead of this process's environment
+#[derive(Default)]
+struct ClientEnvironment {
+    kopi_use: Option<String>,
+    java_version: Option<String>,
+}
+
 pub struct VersionResolver<'a> {
     current_dir: PathBuf,
     config: &'a KopiConfig,
+    environment: Option<ClientEnvironment>,
 }
 
 impl<'a> VersionResolver<'a> {
@@ -44,6 +84,7 @@ impl<'a> VersionResolver<'a> {
         Self {
             current_dir: env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             config,
+            environment: None,
         }
     }
 
@@ -51,18 +92,33 @@ impl<'a> VersionResolver<'a> {
         Self {
             current_dir: dir,
             config,
+            environment: None,
         }
     }
 
+    /// Use the given value instead of this process's `KOPI_JAVA_VERSION`,
+    /// e.g. when resolving on behalf of a daemon client. This process's
+    /// `KOPI_USE` is then ignored too; pass the client's with
+    /// [`Self::with_override_version`].
+    pub fn with_environment_version(mut self, value: Option<String>) -> Self {
+        self.environment.get_or_insert_default().java_version = value;
+        self
+    }
+
+    /// Use the given value instead of this process's `KOPI_USE`
+    pub fn with_override_version(mut self, value: Option<String>) -> Self {
+        self.environment.get_or_insert_default().kopi_use = value;
+        self
+    }
+
     pub fn resolve_version(&self) -> Result<(VersionRequest, VersionSource)> {
-        // Check environment variable first (fastest)
-        if let Ok(env_version) = env::var(VERSION_ENV_VAR) {
-            log::debug!("Checking KOPI_JAVA_VERSION environment variable...");
-            log::debug!("Found KOPI_JAVA_VERSION: {env_version}");
-            let version_request = VersionRequest::from_str(&env_version)?;
-            return Ok((version_request, VersionSource::Environment(env_version)));
+        // Check environment variables first (fastest)
+        if let Some(result) = self.resolve_from_override()? {
+            return Ok(result);
+        }
+        if let Some(result) = self.resolve_from_environment()? {
+            return Ok(result);
         }
-        log::debug!("KOPI_JAVA_VERSION not set");
 
         // Search for version files
         let current_dir = self.current_dir.clone();
@@ -83,20 +139,117 @@ impl<'a> VersionResolver<'a> {
         Err(KopiError::NoLocalVersion { searched_paths })
     }
 
-    fn read_version_file(&self, path: &Path) -> Result<String> {
-        // Use a small buffer for efficiency
-        let content = fs::read_to_string(path)?;
+    /// Resolve the version for a specific tool, honouring `[tools]` pins in
+    /// `.kopi.toml` before falling back to the project version.
+    pub fn resolve_tool_version(&self, tool: &str) -> Result<(VersionRequest, VersionSource)> {
+        if let Some(result) = self.resolve_from_override()? {
+            return Ok(result);
+        }
+        if let Some(result) = self.resolve_from_environment()? {
+            return Ok(result);
+        }
+
+        if let Some((version_request, path)) = self.search_tool_override(tool)? {
+            log::debug!("Using tool override for {tool} from {path:?}");
+            return Ok((version_request, VersionSource::ToolOverride(path)));
+        }
+
+        self.resolve_version()
+    }
+
+    /// Collect every configured version source in precedence order.
+    ///
+    /// Unlike `resolve_version`, this does not stop at the first match so that
+    /// diagnostics can show which lower-precedence sources are being overridden.
+    /// The first entry, if any, is the one `resolve_version` would return.
+    pub fn resolve_all_sources(&self) -> Result<Vec<(VersionRequest, VersionSource)>> {
+        let mut sources = Vec::new();
+
+        if let Some(result) = self.resolve_from_override()? {
+            sources.push(result);
+        }
+
+        if let Some(result) = self.resolve_from_environment()? {
+            sources.push(result);
+        }
+
+        if let (Some((version_request, path)), _) = self.search_version_files()? {
+            sources.push((version_request, VersionSource::ProjectFile(path)));
+        }
 
-        // Trim whitespace and newlines
-        let version = content.trim().to_string();
+        if let Some((version_request, path)) = self.get_global_default()? {
+            sources.push((version_request, VersionSource::GlobalDefault(path)));
+        }
+
+        Ok(sources)
+    }
+
+    /// Read the per-invocation `KOPI_USE` override, e.g. `KOPI_USE=corretto@17 mvn package`.
+    ///
+    /// With a fixed environment only the forwarded value is consulted, so a
+    /// daemon never applies its own `KOPI_USE` to a client's request.
+    fn resolve_from_override(&self) -> Result<Option<(VersionRequest, VersionSource)>> {
+        let value = match &self.environment {
+            None => env::var(OVERRIDE_ENV_VAR).ok(),
+            Some(client) => client.kopi_use.clone(),
+        };
+        let Some(value) = value.map(|value| value.trim().to_string()) else {
+            return Ok(None);
+        };
+        if value.is_empty() {
+            log::debug!("KOPI_USE is empty, ignoring");
+            return Ok(None);
+        }
+
+        log::debug!("Found KOPI_USE: {value}");
+        SecurityValidator::new(self.config)
+            .validate_version(&value)
+            .map_err(|e| match e {
+                KopiError::ValidationError(msg) => {
+                    KopiError::ValidationError(format!("Invalid KOPI_USE value: {msg}"))
+                }
+                other => other,
+            })?;
+        let version_request = VersionRequest::from_str(&value)?;
+        Ok(Some((version_request, VersionSource::Override(value))))
+    }
+
+    fn resolve_from_environment(&self) -> Result<Option<(VersionRequest, VersionSource)>> {
+        log::debug!("Checking KOPI_JAVA_VERSION environment variable...");
+        let env_version = match &self.environment {
+            None => env::var(VERSION_ENV_VAR).ok(),
+            Some(client) => client.java_version.clone(),
+        };
+        let env_version = match env_version {
+            Some(value) => value.trim().to_string(),
+            None => {
+                log::debug!("KOPI_JAVA_VERSION not set");
+                return Ok(None);
+            }
+        };
 
-        if version.is_empty() {
-            return Err(KopiError::InvalidVersionFormat(
-                "Version file is empty".to_string(),
-            ));
+        // CI systems often export empty variables; treat them as unset
+        if env_version.is_empty() {
+            log::debug!("KOPI_JAVA_VERSION is empty, ignoring");
+            return Ok(None);
         }
 
-        Ok(version)
+        log::debug!("Found KOPI_JAVA_VERSION: {env_version}");
+        let version_request = VersionRequest::from_str(&env_version)?;
+        Ok(Some((
+            version_request,
+            VersionSource::Environment(env_version),
+        )))
+    }
+
+    fn read_version_file(&self, path: &Path) -> Result<String> {
+        // Use a small buffer for efficiency
+        let content = fs::read_to_string(path)?;
+
+        // Comment lines and surrounding whitespace are ignored
+        version_file_pin(&content)
+            .map(str::to_string)
+            .ok_or_else(|| KopiError::InvalidVersionFormat("Version file is empty".to_string()))
     }
 
     fn search_version_files(&self) -> Result<VersionSearchResult> {
@@ -140,7 +293,47 @@ impl<'a> VersionResolver<'a> {
         Ok((None, searched_paths))
     }
 
-    fn get_global_default(&self) -> Result<Option<(VersionRequest, PathBuf)>> {
+    /// The nearest `.kopi-version` or `.java-version` from the current
+    /// directory upwards, without considering environment overrides
+    pub fn find_project_version(&self) -> Result<Option<(VersionRequest, PathBuf)>> {
+        Ok(self.search_version_files()?.0)
+    }
+
+    /// Find the nearest `.kopi.toml` that pins `tool` to a version. The walk
+    /// stops at the directory holding the nearest `.kopi-version` or
+    /// `.java-version`, so a pin in an enclosing directory does not override
+    /// the project's own version file.
+    fn search_tool_override(&self, tool: &str) -> Result<Option<(VersionRequest, PathBuf)>> {
+        let mut current = self.current_dir.clone();
+
+        loop {
+            let config_path = current.join(PROJECT_CONFIG_FILE);
+            log::trace!("Checking {config_path:?}");
+            if config_path.exists() {
+                let tools = read_tool_pins(&config_path)?;
+
+                if let Some(spec) = tools.get(tool) {
+                    let version_request = VersionRequest::from_str(spec.trim())?;
+                    return Ok(Some((version_request, config_path)));
+                }
+            }
+
+            if current.join(KOPI_VERSION_FILE).exists() || current.join(JAVA_VERSION_FILE).exists()
+            {
+                log::trace!("Stopping tool pin search at project root {current:?}");
+                break;
+            }
+
+            match current.parent() {
+                Some(parent) => current = parent.to_path_buf(),
+                None => break,
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn get_global_default(&self) -> Result<Option<(VersionRequest, PathBuf)>> {
         let global_version_path = self.config.kopi_home().join("version");
 
         if global_version_path.exists() {
@@ -179,6 +372,31 @@ mod tests {
         }
     }
 
+    #[test]
+    #[serial]
+    fn test_fixed_environment_version_overrides_process() {
+        unsafe {
+            env::set_var(VERSION_ENV_VAR, "temurin@21");
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        fs::write(temp_dir.path().join(KOPI_VERSION_FILE), "zulu@17").unwrap();
+
+        let resolver = VersionResolver::with_dir(temp_dir.path().to_path_buf(), &config)
+            .with_environment_version(Some("corretto@11".to_string()));
+        let (result, _) = resolver.resolve_version().unwrap();
+        assert_eq!(result.distribution, Some("corretto".to_string()));
+
+        let resolver = VersionResolver::with_dir(temp_dir.path().to_path_buf(), &config)
+            .with_environment_version(None);
+        let (result, source) = resolver.resolve_version().unwrap();
+        assert_eq!(result.distribution, Some("zulu".to_string()));
+        assert!(matches!(source, VersionSource::ProjectFile(_)));
+        unsafe {
+            env::remove_var(VERSION_ENV_VAR);
+        }
+    }
+
     #[test]
     #[serial]
     fn test_resolve_from_kopi_version_file() {
@@ -201,6 +419,23 @@ mod tests {
         assert_eq!(source, VersionSource::ProjectFile(version_file));
     }
 
+    #[test]
+    #[serial]
+    fn test_resolve_system_from_kopi_version_file() {
+        unsafe {
+            env::remove_var(VERSION_ENV_VAR);
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().to_path_buf();
+        fs::write(temp_path.join(KOPI_VERSION_FILE), "system\n").unwrap();
+
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = VersionResolver::with_dir(temp_path, &config);
+        let (result, _source) = resolver.resolve_version().unwrap();
+        assert!(result.is_system());
+    }
+
     #[test]
     #[serial]
     fn test_resolve_from_java_version_file() {
@@ -432,4 +667,266 @@ mod tests {
             env::remove_var(VERSION_ENV_VAR);
         }
     }
+
+    #[test]
+    #[serial]
+    fn test_empty_env_var_is_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().to_path_buf();
+
+        let version_file = temp_path.join(KOPI_VERSION_FILE);
+        fs::write(&version_file, "corretto@17").unwrap();
+
+        unsafe {
+            env::set_var(VERSION_ENV_VAR, "  ");
+        }
+
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = VersionResolver::with_dir(temp_path.clone(), &config);
+        let (version_request, source) = resolver.resolve_version().unwrap();
+
+        assert_eq!(version_request.version_pattern, "17");
+        assert_eq!(source, VersionSource::ProjectFile(version_file));
+
+        unsafe {
+            env::remove_var(VERSION_ENV_VAR);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_resolve_all_sources_in_precedence_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().to_path_buf();
+
+        let version_file = temp_path.join(KOPI_VERSION_FILE);
+        fs::write(&version_file, "corretto@17").unwrap();
+        let global_version_path = temp_dir.path().join("version");
+        fs::write(&global_version_path, "zulu@11").unwrap();
+
+        unsafe {
+            env::set_var(VERSION_ENV_VAR, "temurin@21");
+        }
+
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = VersionResolver::with_dir(temp_path.clone(), &config);
+        let sources = resolver.resolve_all_sources().unwrap();
+
+        unsafe {
+            env::remove_var(VERSION_ENV_VAR);
+        }
+
+        let kinds: Vec<_> = sources.iter().map(|(_, source)| source.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                VersionSource::Environment("temurin@21".to_string()),
+                VersionSource::ProjectFile(version_file),
+                VersionSource::GlobalDefault(global_version_path),
+            ]
+        );
+        assert_eq!(sources[1].0.version_pattern, "17");
+        assert_eq!(sources[2].0.distribution, Some("zulu".to_string()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_resolve_tool_version_uses_tool_pin() {
+        unsafe {
+            env::remove_var(VERSION_ENV_VAR);
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        let nested_dir = project_dir.join("src");
+        fs::create_dir_all(&nested_dir).unwrap();
+
+        fs::write(project_dir.join(KOPI_VERSION_FILE), "temurin@17").unwrap();
+        let project_config = project_dir.join(PROJECT_CONFIG_FILE);
+        fs::write(
+            &project_config,
+            "[tools]\njshell = \"temurin@21\"\nnative-image = \"graalvm@21\"\n",
+        )
+        .unwrap();
+
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = VersionResolver::with_dir(nested_dir, &config);
+
+        let (request, source) = resolver.resolve_tool_version("native-image").unwrap();
+        assert_eq!(request.distribution, Some("graalvm".to_string()));
+        assert_eq!(source, VersionSource::ToolOverride(project_config));
+
+        // Tools without a pin fall back to the project version
+        let (request, source) = resolver.resolve_tool_version("java").unwrap();
+        assert_eq!(request.version_pattern, "17");
+        assert!(matches!(source, VersionSource::ProjectFile(_)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ancestor_tool_pin_does_not_override_nested_version_file() {
+        unsafe {
+            env::remove_var(VERSION_ENV_VAR);
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("work").join("project");
+        let nested_dir = project_dir.join("src");
+        fs::create_dir_all(&nested_dir).unwrap();
+
+        // An outer `.kopi.toml`, e.g. in the home directory
+        fs::write(
+            temp_dir.path().join(PROJECT_CONFIG_FILE),
+            "[tools]\njava = \"temurin@21\"\n",
+        )
+        .unwrap();
+        let version_file = project_dir.join(KOPI_VERSION_FILE);
+        fs::write(&version_file, "zulu@17").unwrap();
+
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = VersionResolver::with_dir(nested_dir, &config);
+        let (request, source) = resolver.resolve_tool_version("java").unwrap();
+        assert_eq!(request.distribution, Some("zulu".to_string()));
+        assert_eq!(source, VersionSource::ProjectFile(version_file));
+
+        // Outside the project the outer pin still applies
+        let resolver = VersionResolver::with_dir(temp_dir.path().join("work"), &config);
+        let (_, source) = resolver.resolve_tool_version("java").unwrap();
+        assert_eq!(
+            source,
+            VersionSource::ToolOverride(temp_dir.path().join(PROJECT_CONFIG_FILE))
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_env_var_overrides_tool_pin() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(PROJECT_CONFIG_FILE),
+            "[tools]\njava = \"temurin@21\"\n",
+        )
+        .unwrap();
+
+        unsafe {
+            env::set_var(VERSION_ENV_VAR, "corretto@11");
+        }
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = VersionResolver::with_dir(temp_dir.path().to_path_buf(), &config);
+        let result = resolver.resolve_tool_version("java");
+        unsafe {
+            env::remove_var(VERSION_ENV_VAR);
+        }
+
+        let (request, source) = result.unwrap();
+        assert_eq!(request.distribution, Some("corretto".to_string()));
+        assert_eq!(
+            source,
+            VersionSource::Environment("corretto@11".to_string())
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_override_wins_over_env_var_and_project_file() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(KOPI_VERSION_FILE), "zulu@17").unwrap();
+        fs::write(
+            temp_dir.path().join(PROJECT_CONFIG_FILE),
+            "[tools]\nmvn = \"temurin@21\"\n",
+        )
+        .unwrap();
+
+        unsafe {
+            env::set_var(OVERRIDE_ENV_VAR, "corretto@17");
+            env::set_var(VERSION_ENV_VAR, "temurin@11");
+        }
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = VersionResolver::with_dir(temp_dir.path().to_path_buf(), &config);
+        let tool_result = resolver.resolve_tool_version("mvn");
+        let sources = resolver.resolve_all_sources();
+        let fixed = VersionResolver::with_dir(temp_dir.path().to_path_buf(), &config)
+            .with_environment_version(None)
+            .resolve_version();
+        unsafe {
+            env::remove_var(OVERRIDE_ENV_VAR);
+            env::remove_var(VERSION_ENV_VAR);
+        }
+
+        let (request, source) = tool_result.unwrap();
+        assert_eq!(request.distribution, Some("corretto".to_string()));
+        assert_eq!(source, VersionSource::Override("corretto@17".to_string()));
+
+        let sources = sources.unwrap();
+        assert_eq!(
+            sources[0].1,
+            VersionSource::Override("corretto@17".to_string())
+        );
+        assert_eq!(
+            sources[1].1,
+            VersionSource::Environment("temurin@11".to_string())
+        );
+
+        // Daemon-style resolution ignores this process's KOPI_USE
+        let (_, source) = fixed.unwrap();
+        assert!(matches!(source, VersionSource::ProjectFile(_)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_forwarded_override_is_validated_and_honoured() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(KOPI_VERSION_FILE), "zulu@17").unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = || VersionResolver::with_dir(temp_dir.path().to_path_buf(), &config);
+
+        let (request, source) = resolver()
+            .with_environment_version(Some("temurin@11".to_string()))
+            .with_override_version(Some("corretto@17".to_string()))
+            .resolve_tool_version("java")
+            .unwrap();
+        assert_eq!(request.distribution, Some("corretto".to_string()));
+        assert_eq!(source, VersionSource::Override("corretto@17".to_string()));
+
+        let error = resolver()
+            .with_override_version(Some("../../etc@17".to_string()))
+            .resolve_version()
+            .unwrap_err();
+        assert!(error.to_string().contains("KOPI_USE"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_invalid_override_is_rejected() {
+        unsafe {
+            env::set_var(OVERRIDE_ENV_VAR, "temurin@21;rm -rf /");
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let result =
+            VersionResolver::with_dir(temp_dir.path().to_path_buf(), &config).resolve_version();
+        unsafe {
+            env::remove_var(OVERRIDE_ENV_VAR);
+        }
+
+        match result {
+            Err(KopiError::ValidationError(msg)) => assert!(msg.contains("KOPI_USE"), "{msg}"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_invalid_project_config_is_reported() {
+        unsafe {
+            env::remove_var(VERSION_ENV_VAR);
+        }
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(PROJECT_CONFIG_FILE), "[tools\n").unwrap();
+
+        let config = KopiConfig::new(temp_dir.path().to_path_buf()).unwrap();
+        let resolver = VersionResolver::with_dir(temp_dir.path().to_path_buf(), &config);
+        assert!(matches!(
+            resolver.resolve_tool_version("java"),
+            Err(KopiError::ConfigError(_))
+        ));
+    }
 }
@@ -0,0 +1,100 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+use common::TestHomeGuard;
+use common::fixtures::create_test_jdk_fs;
+
+const FEED: &str = r#"{
+    "advisories": [
+        {
+            "id": "CVE-2025-0001",
+            "severity": "critical",
+            "summary": "Remote code execution in Hotspot",
+            "affected": [{"major": 21, "fixed": "21.0.6"}]
+        },
+        {
+            "id": "CVE-2025-0002",
+            "severity": "medium",
+            "summary": "TLS handshake issue",
+            "affected": [{"major": 17, "fixed": "17.0.14"}]
+        }
+    ]
+}"#;
+
+/// Point the audit feed at a local file so the tests stay offline
+fn setup_feed(test_home: &TestHomeGuard) {
+    let feed = test_home.path().join("advisories.json");
+    fs::write(&feed, FEED).unwrap();
+    let feed_url = format!("file://{}", feed.display()).replace('\\', "/");
+    fs::write(
+        test_home.kopi_home().join("config.toml"),
+        format!("[audit]\nfeed_url = \"{feed_url}\"\n"),
+    )
+    .unwrap();
+}
+
+fn audit(test_home: &TestHomeGuard) -> Command {
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home()).arg("audit");
+    cmd
+}
+
+#[test]
+fn test_audit_flags_critical_advisory() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    setup_feed(&test_home);
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "21.0.5");
+
+    audit(&test_home)
+        .assert()
+        .code(11)
+        .stdout(predicate::str::contains("CVE-2025-0001"))
+        .stdout(predicate::str::contains("kopi install temurin@21.0.6"));
+
+    audit(&test_home)
+        .arg("--json")
+        .assert()
+        .code(11)
+        .stdout(predicate::str::contains("\"fixed_in\": \"21.0.6\""));
+}
+
+#[test]
+fn test_audit_fail_on_threshold() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    setup_feed(&test_home);
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "17.0.13");
+
+    // A medium advisory does not fail the default critical gate
+    audit(&test_home)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CVE-2025-0002"));
+
+    audit(&test_home)
+        .args(["--fail-on", "medium"])
+        .assert()
+        .code(11);
+
+    audit(&test_home)
+        .args(["--fail-on", "severe"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Unknown severity"));
+}
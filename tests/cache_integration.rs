@@ -106,6 +106,7 @@ fn test_cache_offline_mode() {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/download".to_string()),
         checksum: None,
         checksum_type: Some(ChecksumType::Sha256),
@@ -114,6 +115,7 @@ fn test_cache_offline_mode() {
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     };
 
     let dist = DistributionCache {
@@ -172,6 +174,7 @@ fn test_find_package_in_cache() {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/download".to_string()),
         checksum: None,
         checksum_type: Some(ChecksumType::Sha256),
@@ -180,6 +183,7 @@ fn test_find_package_in_cache() {
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     };
 
     let dist = DistributionCache {
@@ -253,6 +257,7 @@ fn test_cache_with_install_command() {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/temurin-21.tar.gz".to_string()),
         checksum: Some("abc123def456".to_string()),
         checksum_type: Some(ChecksumType::Sha256),
@@ -261,6 +266,7 @@ fn test_cache_with_install_command() {
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     };
 
     let dist = DistributionCache {
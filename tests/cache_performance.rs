@@ -80,6 +80,7 @@ fn create_large_test_cache() -> MetadataCache {
                                         ArchiveType::TarGz
                                     },
                                     javafx_bundled: false,
+                                    features: Vec::new(),
                                     download_url: Some(format!(
                                         "https://example.com/{dist_id}/jdk-{major}.{minor}.{patch}.tar.gz"
                                     )),
@@ -106,6 +107,7 @@ fn create_large_test_cache() -> MetadataCache {
                                         Some("ea".to_string())
                                     },
                                     latest_build_available: Some(patch == 10),
+                                    release_date: None,
                                 });
                             }
                         }
@@ -41,6 +41,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/temurin-21.tar.gz".to_string()),
         checksum: None,
         checksum_type: Some(ChecksumType::Sha256),
@@ -49,6 +50,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         term_of_support: Some("lts".to_string()),
         release_status: Some("ga".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     };
 
     // Add STS version (22)
@@ -62,6 +64,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/temurin-22.tar.gz".to_string()),
         checksum: None,
         checksum_type: Some(ChecksumType::Sha256),
@@ -70,6 +73,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         term_of_support: Some("sts".to_string()),
         release_status: Some("ga".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     };
 
     // Add EA version (23)
@@ -83,6 +87,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/temurin-23.tar.gz".to_string()),
         checksum: None,
         checksum_type: Some(ChecksumType::Sha256),
@@ -91,6 +96,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         term_of_support: Some("sts".to_string()),
         release_status: Some("ea".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     };
 
     // Add JRE package
@@ -104,6 +110,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         package_type: PackageType::Jre,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/temurin-21-jre.tar.gz".to_string()),
         checksum: None,
         checksum_type: Some(ChecksumType::Sha256),
@@ -112,6 +119,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         term_of_support: Some("lts".to_string()),
         release_status: Some("ga".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     };
 
     // Add JavaFX bundled package
@@ -125,6 +133,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: true,
+        features: Vec::new(),
         download_url: Some("https://example.com/liberica-21-javafx.tar.gz".to_string()),
         checksum: None,
         checksum_type: Some(ChecksumType::Sha256),
@@ -133,6 +142,7 @@ fn create_test_cache_with_lts_data() -> (TestHomeGuard, MetadataCache) {
         term_of_support: Some("lts".to_string()),
         release_status: Some("ga".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     };
 
     // Create distribution caches
@@ -298,6 +308,7 @@ fn test_compact_mode_deduplication() {
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some(format!("https://example.com/zulu-21-{arch}.tar.gz")),
             checksum: None,
             checksum_type: Some(ChecksumType::Sha256),
@@ -306,6 +317,7 @@ fn test_compact_mode_deduplication() {
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         });
     }
 
@@ -351,6 +363,7 @@ fn test_detailed_mode_deduplication_keeps_smallest() {
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some(format!("https://example.com/temurin-21-{i}.tar.gz")),
             checksum: None,
             checksum_type: Some(ChecksumType::Sha256),
@@ -359,6 +372,7 @@ fn test_detailed_mode_deduplication_keeps_smallest() {
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         });
     }
 
@@ -15,7 +15,7 @@
 mod common;
 use common::TestHomeGuard;
 use kopi::cache::{DistributionCache, MetadataCache};
-use kopi::commands::cache::CacheCommand;
+use kopi::commands::cache::{CacheCommand, SearchFilterArgs};
 use kopi::config::KopiConfig;
 use kopi::models::distribution::Distribution;
 use kopi::models::metadata::JdkMetadata;
@@ -50,6 +50,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             lib_c_type: Some("glibc".to_string()),
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/temurin-21.tar.gz".to_string()),
             checksum: Some("abc123".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -57,6 +58,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         JdkMetadata {
             id: "temurin-22-sts".to_string(),
@@ -69,6 +71,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             lib_c_type: Some("glibc".to_string()),
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/temurin-22.tar.gz".to_string()),
             checksum: Some("def456".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -76,6 +79,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             term_of_support: Some("sts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         JdkMetadata {
             id: "temurin-23-ea".to_string(),
@@ -88,6 +92,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             lib_c_type: Some("glibc".to_string()),
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/temurin-23.tar.gz".to_string()),
             checksum: Some("ghi789".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -95,6 +100,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             term_of_support: Some("sts".to_string()),
             release_status: Some("ea".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
     ];
 
@@ -120,6 +126,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             lib_c_type: Some("glibc".to_string()),
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/corretto-17.tar.gz".to_string()),
             checksum: Some("jkl012".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -127,6 +134,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         JdkMetadata {
             id: "corretto-11-lts".to_string(),
@@ -139,6 +147,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             lib_c_type: Some("glibc".to_string()),
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/corretto-11.tar.gz".to_string()),
             checksum: Some("mno345".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -146,6 +155,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(false),
+            release_date: None,
         },
     ];
 
@@ -171,6 +181,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             lib_c_type: Some("glibc".to_string()),
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/zulu-21-fx.tar.gz".to_string()),
             checksum: Some("pqr678".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -178,6 +189,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         JdkMetadata {
             id: "zulu-21".to_string(),
@@ -190,6 +202,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             lib_c_type: Some("glibc".to_string()),
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/zulu-21.tar.gz".to_string()),
             checksum: Some("stu901".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -197,6 +210,7 @@ fn create_comprehensive_test_cache() -> (TestHomeGuard, KopiConfig, MetadataCach
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
     ];
 
@@ -229,9 +243,11 @@ fn test_integration_compact_display_with_lts_filter() {
         compact: true,
         detailed: false,
         json: false,
+        release_date: false,
         lts_only: true,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -249,9 +265,11 @@ fn test_integration_detailed_display_with_distribution_search() {
         compact: false,
         detailed: true,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -269,9 +287,14 @@ fn test_integration_json_output_with_javafx_filter() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: SearchFilterArgs {
+            javafx: true,
+            ..Default::default()
+        },
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -289,9 +312,11 @@ fn test_integration_latest_search_with_lts_filter() {
         compact: true,
         detailed: false,
         json: false,
+        release_date: false,
         lts_only: true,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -309,9 +334,11 @@ fn test_integration_version_specific_search_across_distributions() {
         compact: false,
         detailed: true,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -329,9 +356,11 @@ fn test_integration_multiple_filters_combined() {
         compact: false,
         detailed: true,
         json: false,
+        release_date: false,
         lts_only: true,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -349,9 +378,11 @@ fn test_integration_edge_case_no_matching_results() {
         compact: true,
         detailed: false,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     // Should execute successfully but show no results
@@ -369,9 +400,11 @@ fn test_integration_edge_case_conflicting_display_modes() {
         compact: true,
         detailed: true,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -399,9 +432,11 @@ fn test_integration_search_with_distribution_version_format() {
         compact: false,
         detailed: true,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -419,9 +454,11 @@ fn test_integration_backward_compatibility_default_behavior() {
         compact: false, // Default when no flags
         detailed: false,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -453,6 +490,7 @@ fn test_integration_platform_specific_filtering() {
             lib_c_type: Some("glibc".to_string()),
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/21-linux.tar.gz".to_string()),
             checksum: Some("linux123".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -460,6 +498,7 @@ fn test_integration_platform_specific_filtering() {
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         JdkMetadata {
             id: "temurin-21-windows".to_string(),
@@ -472,6 +511,7 @@ fn test_integration_platform_specific_filtering() {
             lib_c_type: None,
             archive_type: ArchiveType::Zip,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/21-windows.zip".to_string()),
             checksum: Some("win123".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -479,6 +519,7 @@ fn test_integration_platform_specific_filtering() {
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         JdkMetadata {
             id: "temurin-21-mac".to_string(),
@@ -491,6 +532,7 @@ fn test_integration_platform_specific_filtering() {
             lib_c_type: None,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://test.com/21-mac.tar.gz".to_string()),
             checksum: Some("mac123".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -498,6 +540,7 @@ fn test_integration_platform_specific_filtering() {
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
     ];
 
@@ -524,9 +567,11 @@ fn test_integration_platform_specific_filtering() {
         compact: false,
         detailed: true,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -544,9 +589,11 @@ fn test_integration_regression_old_search_patterns() {
         compact: false,
         detailed: false,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -578,9 +625,11 @@ fn test_integration_empty_cache_handling() {
         compact: true,
         detailed: false,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -598,9 +647,11 @@ fn test_integration_json_output_structure_validation() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -636,6 +687,7 @@ fn test_integration_performance_large_cache() {
                 lib_c_type: Some("glibc".to_string()),
                 archive_type: ArchiveType::TarGz,
                 javafx_bundled: false,
+                features: Vec::new(),
                 download_url: Some(format!("https://test.com/{major}-{minor}.tar.gz")),
                 checksum: Some(format!("checksum{major}_{minor}")),
                 checksum_type: Some(ChecksumType::Sha256),
@@ -647,6 +699,7 @@ fn test_integration_performance_large_cache() {
                 },
                 release_status: Some("ga".to_string()),
                 latest_build_available: Some(minor == 4),
+                release_date: None,
             });
         }
     }
@@ -675,9 +728,11 @@ fn test_integration_performance_large_cache() {
         compact: false,
         detailed: true,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     assert!(cmd.execute(&config, false).is_ok());
@@ -59,6 +59,7 @@ fn setup_test_cache() -> (
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://example.com/temurin-21.tar.gz".to_string()),
             checksum: None,
             checksum_type: Some(ChecksumType::Sha256),
@@ -67,6 +68,7 @@ fn setup_test_cache() -> (
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         // Temurin 17.0.9
         JdkMetadata {
@@ -79,6 +81,7 @@ fn setup_test_cache() -> (
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://example.com/temurin-17.tar.gz".to_string()),
             checksum: None,
             checksum_type: Some(ChecksumType::Sha256),
@@ -87,6 +90,7 @@ fn setup_test_cache() -> (
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         // Temurin 11.0.21
         JdkMetadata {
@@ -99,6 +103,7 @@ fn setup_test_cache() -> (
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://example.com/temurin-11.tar.gz".to_string()),
             checksum: None,
             checksum_type: Some(ChecksumType::Sha256),
@@ -107,6 +112,7 @@ fn setup_test_cache() -> (
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
     ];
 
@@ -132,6 +138,7 @@ fn setup_test_cache() -> (
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://example.com/corretto-21.tar.gz".to_string()),
             checksum: None,
             checksum_type: Some(ChecksumType::Sha256),
@@ -140,6 +147,7 @@ fn setup_test_cache() -> (
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
         // Corretto 17.0.10
         JdkMetadata {
@@ -152,6 +160,7 @@ fn setup_test_cache() -> (
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://example.com/corretto-17.tar.gz".to_string()),
             checksum: None,
             checksum_type: Some(ChecksumType::Sha256),
@@ -160,6 +169,7 @@ fn setup_test_cache() -> (
             term_of_support: Some("lts".to_string()),
             release_status: Some("ga".to_string()),
             latest_build_available: Some(true),
+            release_date: None,
         },
     ];
 
@@ -196,9 +206,11 @@ fn test_search_distribution_only() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     // This should succeed and return all Corretto versions
@@ -216,9 +228,11 @@ fn test_search_latest_all_distributions() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     // This should succeed and return the latest version from each distribution
@@ -236,9 +250,11 @@ fn test_search_latest_specific_distribution() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     // This should succeed and return only the latest Temurin version
@@ -256,9 +272,11 @@ fn test_search_backward_compatibility() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     // This should succeed and return version 21 (defaulting to Temurin)
@@ -276,9 +294,11 @@ fn test_search_distribution_with_version() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     // This should succeed and return Corretto 17
@@ -296,9 +316,11 @@ fn test_search_invalid_distribution() {
         compact: false,
         detailed: false,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     // The command returns Ok but prints an error message
@@ -306,6 +328,27 @@ fn test_search_invalid_distribution() {
     assert!(cmd.execute(&config, false).is_ok());
 }
 
+#[test]
+#[serial]
+fn test_search_corrects_distribution_typos_and_aliases() {
+    let (test_home, _config, _guard) = setup_test_cache();
+
+    for (query, from, to) in [
+        ("temurn@21", "temurn", "temurin"),
+        ("amazon@17", "amazon", "corretto"),
+    ] {
+        assert_cmd::Command::cargo_bin("kopi")
+            .unwrap()
+            .args(["cache", "search", query, "--json"])
+            .env("KOPI_HOME", test_home.kopi_home())
+            .assert()
+            .success()
+            .stderr(predicates::str::contains(format!(
+                "Unknown distribution '{from}', showing results for '{to}'"
+            )));
+    }
+}
+
 #[test]
 #[serial]
 fn test_search_jre_latest() {
@@ -317,9 +360,11 @@ fn test_search_jre_latest() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
 
     // This should succeed (even if no JRE packages exist, it should return empty results)
@@ -337,9 +382,11 @@ fn test_search_display_modes() {
         compact: true,
         detailed: false,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
     assert!(cmd_compact.execute(&config, false).is_ok());
 
@@ -349,9 +396,11 @@ fn test_search_display_modes() {
         compact: false,
         detailed: true,
         json: false,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
     assert!(cmd_detailed.execute(&config, false).is_ok());
 
@@ -361,9 +410,11 @@ fn test_search_display_modes() {
         compact: false,
         detailed: false,
         json: true,
+        release_date: false,
         lts_only: false,
         java_version: false,
         distribution_version: false,
+        filters: Default::default(),
     };
     assert!(cmd_json.execute(&config, false).is_ok());
 }
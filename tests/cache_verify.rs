@@ -0,0 +1,50 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::TestHomeGuard;
+use std::process::Command;
+
+fn run_verify(test_home: &TestHomeGuard, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_kopi"))
+        .args(["cache", "verify"])
+        .args(args)
+        .env("KOPI_HOME", test_home.kopi_home())
+        .output()
+        .expect("Failed to execute kopi")
+}
+
+#[test]
+fn test_verify_without_cache_suggests_refresh() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+
+    let output = run_verify(&test_home, &["--no-progress"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("kopi cache refresh"), "{stderr}");
+}
+
+#[test]
+fn test_verify_sample_conflicts_with_all() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+
+    let output = run_verify(&test_home, &["--sample", "3", "--all"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
@@ -130,14 +130,15 @@ fn test_current_json_error_output() {
 
     // Parse JSON error output
     let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON output");
-    assert_eq!(json["error"], "no_version_configured");
+    assert_eq!(json["code"], "KOPI_E_NO_VERSION_CONFIGURED");
     assert!(
         json["message"]
             .as_str()
             .unwrap()
-            .contains("No JDK version configured")
+            .contains("No JDK configured")
     );
-    assert!(json["hints"].is_array());
+    assert!(json["suggestion"].as_str().unwrap().contains("kopi local"));
+    assert!(json["details"].as_str().unwrap().contains("Searched in"));
 }
 
 #[test]
@@ -294,3 +295,71 @@ fn test_current_shows_not_installed_warning() {
     assert!(stderr.contains("Warning"));
     assert!(stderr.contains("kopi install"));
 }
+
+#[test]
+fn test_current_verbose_shows_resolution_chain() {
+    let temp_home = TestHomeGuard::new();
+    temp_home.setup_kopi_structure();
+    fs::write(temp_home.kopi_home().join("version"), "zulu@11").unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join(".kopi-version"), "corretto@17").unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_kopi"));
+    cmd.args(["current", "--verbose"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.env("KOPI_HOME", temp_home.kopi_home());
+    cmd.env("KOPI_JAVA_VERSION", "temurin@21");
+
+    let output = cmd.output().expect("Failed to execute kopi");
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Resolution order"));
+    assert!(stdout.contains("temurin@21 [active]"));
+    assert!(stdout.contains("corretto@17"));
+    assert!(stdout.contains("zulu@11"));
+    assert_eq!(stdout.matches("[overridden]").count(), 2);
+}
+
+#[test]
+fn test_current_verbose_shows_kopi_use_override() {
+    let temp_home = TestHomeGuard::new();
+    temp_home.setup_kopi_structure();
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join(".kopi-version"), "corretto@17").unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_kopi"));
+    cmd.args(["current", "--verbose"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.env("KOPI_HOME", temp_home.kopi_home());
+    cmd.env("KOPI_JAVA_VERSION", "temurin@21");
+    cmd.env("KOPI_USE", "zulu@11");
+
+    let output = cmd.output().expect("Failed to execute kopi");
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("set by KOPI_USE"));
+    assert!(stdout.contains("zulu@11 [active]"));
+    assert!(stdout.contains("temurin@21 [overridden]"));
+    assert!(stdout.contains("corretto@17"));
+}
+
+#[test]
+fn test_current_rejects_invalid_kopi_use() {
+    let temp_home = TestHomeGuard::new();
+    temp_home.setup_kopi_structure();
+
+    let (_, stderr, success) = run_kopi_with_env(
+        &["current"],
+        &[
+            ("KOPI_HOME", temp_home.kopi_home().to_str().unwrap()),
+            ("KOPI_USE", "temurin@21$(id)"),
+        ],
+    );
+
+    assert!(!success);
+    assert!(stderr.contains("KOPI_USE"));
+}
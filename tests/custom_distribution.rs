@@ -0,0 +1,101 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use assert_cmd::Command;
+use kopi::models::metadata::JdkMetadata;
+use kopi::models::package::{ArchiveType, ChecksumType, PackageType};
+use kopi::models::platform::{Architecture, OperatingSystem};
+use kopi::platform::{get_current_architecture, get_current_os};
+use kopi::version::Version;
+use predicates::prelude::*;
+use std::fs;
+use std::str::FromStr;
+
+mod common;
+use common::TestHomeGuard;
+
+/// Publish a one-package `acme` metadata document and register it in config.toml
+fn setup_acme(test_home: &TestHomeGuard) {
+    let package = JdkMetadata {
+        id: "acme-21.0.4".to_string(),
+        distribution: "acme".to_string(),
+        version: Version::from_str("21.0.4").unwrap(),
+        distribution_version: Version::from_str("21.0.4+7").unwrap(),
+        architecture: Architecture::from_str(&get_current_architecture()).unwrap(),
+        operating_system: OperatingSystem::from_str(&get_current_os()).unwrap(),
+        package_type: PackageType::Jdk,
+        archive_type: ArchiveType::TarGz,
+        download_url: Some("https://jdk.acme.example/acme-21.0.4.tar.gz".to_string()),
+        checksum: Some("0".repeat(64)),
+        checksum_type: Some(ChecksumType::Sha256),
+        size: 190_000_000,
+        lib_c_type: None,
+        javafx_bundled: false,
+        features: Vec::new(),
+        term_of_support: Some("lts".to_string()),
+        release_status: Some("ga".to_string()),
+        latest_build_available: Some(true),
+        release_date: None,
+    };
+
+    let document = test_home.path().join("acme-metadata.json");
+    fs::write(&document, serde_json::to_string(&vec![package]).unwrap()).unwrap();
+
+    let metadata_url = format!("file://{}", document.display()).replace('\\', "/");
+    fs::write(
+        test_home.kopi_home().join("config.toml"),
+        format!("[[custom_distribution]]\nname = \"acme\"\nmetadata_url = \"{metadata_url}\"\n"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_refresh_and_search_custom_distribution() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    setup_acme(&test_home);
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["cache", "refresh", "acme", "--no-progress"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["cache", "search", "acme@21", "--json", "--no-progress"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("acme-21.0.4"));
+}
+
+#[test]
+fn test_invalid_custom_distribution_name_is_rejected() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    fs::write(
+        test_home.kopi_home().join("config.toml"),
+        "[[custom_distribution]]\nname = \"temurin\"\nmetadata_url = \"https://example.com/m.json\"\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["cache", "search", "temurin@21", "--no-progress"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("built-in distribution"));
+}
@@ -0,0 +1,101 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::process::Command;
+
+mod common;
+use common::TestHomeGuard;
+
+fn run_kopi(args: &[&str], kopi_home: &std::path::Path) -> (String, String, bool) {
+    let output = Command::new(env!("CARGO_BIN_EXE_kopi"))
+        .args(args)
+        .env("KOPI_HOME", kopi_home)
+        .output()
+        .expect("Failed to execute kopi");
+
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        output.status.success(),
+    )
+}
+
+#[test]
+fn test_daemon_help() {
+    let temp_home = TestHomeGuard::new();
+    temp_home.setup_kopi_structure();
+
+    let (stdout, _, success) = run_kopi(&["daemon", "--help"], temp_home.kopi_home().as_path());
+    assert!(success);
+    assert!(stdout.contains("--stop"));
+}
+
+#[test]
+fn test_daemon_stop_without_running_daemon() {
+    let temp_home = TestHomeGuard::new();
+    temp_home.setup_kopi_structure();
+
+    let (stdout, _, success) = run_kopi(&["daemon", "--stop"], temp_home.kopi_home().as_path());
+    assert!(success);
+    assert!(stdout.contains("No kopi daemon is running"));
+}
+
+#[test]
+fn test_use_daemon_falls_back_in_process() {
+    let temp_home = TestHomeGuard::new();
+    temp_home.setup_kopi_structure();
+
+    let (stdout, _, success) = run_kopi(&["--use-daemon", "list"], temp_home.kopi_home().as_path());
+    assert!(success);
+    assert!(stdout.contains("No JDKs installed"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_use_daemon_routes_list_through_daemon() {
+    use std::process::Stdio;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    let temp_home = TestHomeGuard::new();
+    temp_home.setup_kopi_structure();
+    let kopi_home = temp_home.kopi_home();
+
+    let mut daemon = Command::new(env!("CARGO_BIN_EXE_kopi"))
+        .arg("daemon")
+        .env("KOPI_HOME", &kopi_home)
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("Failed to start daemon");
+
+    let socket = kopi_home.join("daemon.sock");
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !socket.exists() {
+        assert!(Instant::now() < deadline, "daemon socket never appeared");
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let (stdout, stderr, success) = run_kopi(&["-vv", "--use-daemon", "list"], &kopi_home);
+    assert!(success, "stderr: {stderr}");
+    assert!(stdout.contains("No JDKs installed"));
+    assert!(!stderr.contains("no kopi daemon is running"));
+
+    let (stdout, _, success) = run_kopi(&["daemon", "--stop"], &kopi_home);
+    assert!(success);
+    assert!(stdout.contains("Stopped kopi daemon"));
+
+    let status = daemon.wait().expect("daemon exit status");
+    assert!(status.success());
+    assert!(!socket.exists());
+}
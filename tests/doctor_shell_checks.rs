@@ -34,8 +34,8 @@ fn test_shell_checks_full_suite() {
     let engine = DiagnosticEngine::new(&config);
     let results = engine.run_checks(Some(vec![CheckCategory::Shell]), false);
 
-    // Should have exactly 4 shell checks
-    assert_eq!(results.len(), 4);
+    // Should have exactly 5 shell checks
+    assert_eq!(results.len(), 5);
 
     // Check that all results are from Shell category
     for result in &results {
@@ -48,6 +48,7 @@ fn test_shell_checks_full_suite() {
     assert!(check_names.contains(&"PATH Configuration"));
     assert!(check_names.contains(&"Shell Configuration"));
     assert!(check_names.contains(&"Shim Functionality"));
+    assert!(check_names.contains(&"Environment Script"));
 }
 
 #[test]
@@ -625,3 +625,172 @@ fn test_env_multiple_matching_jdks() {
         .stdout(predicate::str::contains(expected))
         .stdout(predicate::str::contains("temurin-21.0.2"));
 }
+
+/// Test that --changed-only prints nothing once JAVA_HOME already matches
+#[test]
+#[serial]
+#[cfg(not(target_os = "windows"))]
+fn test_env_changed_only() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let kopi_home = test_home.kopi_home();
+
+    setup_test_environment(&test_home, "temurin@21.0.1");
+    let jdk_path = kopi_home.join("jdks").join("temurin-21.0.1");
+    fs::create_dir_all(&jdk_path).unwrap();
+
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.env_remove("JAVA_HOME").env("PATH", "/usr/bin").args([
+        "env",
+        "--changed-only",
+        "--shell",
+        "bash",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("export JAVA_HOME="))
+        .stdout(predicate::str::contains(format!(
+            "export PATH=\"{}:/usr/bin\"",
+            jdk_path.join("bin").display()
+        )));
+
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.env("JAVA_HOME", &jdk_path)
+        .args(["env", "--changed-only", "--shell", "bash"]);
+    cmd.assert().success().stdout(predicate::str::is_empty());
+}
+
+/// Test that --changed-only warns instead of failing for missing JDKs
+#[test]
+#[serial]
+#[cfg(not(target_os = "windows"))]
+fn test_env_changed_only_not_installed() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let kopi_home = test_home.kopi_home();
+
+    setup_test_environment(&test_home, "temurin@22");
+
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.args(["env", "--changed-only", "--shell", "bash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("is configured but not installed"));
+}
+
+/// Test that --diff emits only the statements that differ from the live environment
+#[test]
+#[serial]
+#[cfg(not(target_os = "windows"))]
+fn test_env_diff() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let kopi_home = test_home.kopi_home();
+
+    setup_test_environment(&test_home, "temurin@21.0.1");
+    let jdk_path = kopi_home.join("jdks").join("temurin-21.0.1");
+    fs::create_dir_all(&jdk_path).unwrap();
+    let bin_path = jdk_path.join("bin");
+
+    // JAVA_HOME already matches, only PATH is missing the JDK bin directory
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.env("JAVA_HOME", &jdk_path)
+        .env("PATH", "/usr/bin")
+        .args(["env", "--diff", "--shell", "bash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("JAVA_HOME").not())
+        .stdout(predicate::str::contains(format!(
+            "export PATH=\"{}:/usr/bin\"",
+            bin_path.display()
+        )));
+
+    // Nothing to do once both match
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.env("JAVA_HOME", &jdk_path)
+        .env("PATH", format!("{}:/usr/bin", bin_path.display()))
+        .args(["env", "--diff", "--shell", "bash"]);
+    cmd.assert().success().stdout(predicate::str::is_empty());
+}
+
+/// Test that --diff unsets Kopi's JAVA_HOME when no version is configured
+#[test]
+#[serial]
+#[cfg(not(target_os = "windows"))]
+fn test_env_diff_unsets_when_leaving_project() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let kopi_home = test_home.kopi_home();
+
+    let jdk_path = kopi_home.join("jdks").join("temurin-21.0.1");
+    fs::create_dir_all(&jdk_path).unwrap();
+
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.env("JAVA_HOME", &jdk_path)
+        .env(
+            "PATH",
+            format!("{}:/usr/bin", jdk_path.join("bin").display()),
+        )
+        .args(["env", "--diff", "--shell", "bash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("unset JAVA_HOME"))
+        .stdout(predicate::str::contains("export PATH=\"/usr/bin\""));
+}
+
+/// Test that --wsl-interop adds the Windows form of JAVA_HOME under WSL
+#[test]
+#[serial]
+#[cfg(target_os = "linux")]
+fn test_env_wsl_interop() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let kopi_home = test_home.kopi_home();
+
+    setup_test_environment(&test_home, "temurin@21.0.1");
+    let jdk_path = kopi_home.join("jdks").join("temurin-21.0.1");
+    fs::create_dir_all(&jdk_path).unwrap();
+
+    // Without wslpath on PATH the distribution share is used
+    let windows_path = format!(
+        r"\\wsl.localhost\Ubuntu{}",
+        jdk_path.display().to_string().replace('/', r"\")
+    );
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.env("WSL_DISTRO_NAME", "Ubuntu")
+        .env("WSLENV", "USERPROFILE/p")
+        .env("PATH", test_home.path())
+        .args(["env", "--wsl-interop", "--shell", "bash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "export JAVA_HOME=\"{}\"",
+            jdk_path.display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "export JAVA_HOME_WINDOWS=\"{}\"",
+            windows_path.replace('\\', r"\\")
+        )))
+        .stdout(predicate::str::contains(
+            "export WSLENV=\"USERPROFILE/p:JAVA_HOME/p\"",
+        ));
+}
+
+/// Test that kopi init emits a hook calling env --changed-only
+#[test]
+fn test_init_bash_hook() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let kopi_home = test_home.kopi_home();
+
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.args(["init", "bash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("env --changed-only --shell bash"));
+
+    let mut cmd = get_test_command(&kopi_home);
+    cmd.args(["init", "cmd"]);
+    cmd.assert().failure();
+}
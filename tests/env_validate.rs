@@ -0,0 +1,146 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `kopi env --validate` runs the emitted script in a real shell, so these
+//! tests double as a harness for the quoting of every shell that is installed.
+
+#![cfg(unix)]
+
+mod common;
+use assert_cmd::Command;
+use common::TestHomeGuard;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::ffi::OsString;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// A fake JDK under `jdks_dir` whose `java` reports its own `java.home`
+fn create_fake_jdk(jdks_dir: &Path, name: &str) -> PathBuf {
+    let java_home = jdks_dir.join(name);
+    let bin = java_home.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let java = bin.join("java");
+    fs::write(
+        &java,
+        "#!/bin/sh\n\
+         home=$(cd \"$(dirname \"$0\")/..\" && pwd)\n\
+         echo \"    java.home = $home\" >&2\n\
+         echo 'openjdk version \"21.0.1\"' >&2\n",
+    )
+    .unwrap();
+    fs::set_permissions(&java, fs::Permissions::from_mode(0o755)).unwrap();
+    java_home
+}
+
+/// A hermetic `PATH`: `first`, then a directory linking only the shells and
+/// tools the probe needs, so a system `java` never leaks in
+fn path_with(test_home: &TestHomeGuard, first: &[&Path]) -> OsString {
+    let tools = test_home.path().join("tools");
+    fs::create_dir_all(&tools).unwrap();
+    for tool in ["sh", "bash", "zsh", "fish", "dirname"] {
+        let link = tools.join(tool);
+        if let Ok(target) = which::which(tool)
+            && !link.exists()
+        {
+            std::os::unix::fs::symlink(target, link).unwrap();
+        }
+    }
+    let entries = first.iter().map(|dir| dir.to_path_buf()).chain([tools]);
+    std::env::join_paths(entries).unwrap()
+}
+
+fn validate_command(kopi_home: &Path, cwd: &Path, path: OsString, shell: &str) -> Command {
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", kopi_home)
+        .env("HOME", cwd)
+        .env("PATH", path)
+        .env_remove("KOPI_JAVA_VERSION")
+        .env_remove("JAVA_HOME")
+        .current_dir(cwd)
+        .args(["env", "--validate", "--shell", shell]);
+    cmd
+}
+
+fn shell_available(shell: &str) -> bool {
+    which::which(shell).is_ok()
+}
+
+#[test]
+#[serial]
+fn test_validate_passes_in_every_installed_shell() {
+    let test_home = TestHomeGuard::new();
+    // Spaces, `$` and quotes in the path catch quoting bugs in the emitted script
+    let kopi_home = test_home.path().join("it's a $HOME").join(".kopi");
+    let java_home = create_fake_jdk(&kopi_home.join("jdks"), "temurin-21.0.1");
+    fs::write(test_home.path().join(".kopi-version"), "temurin@21.0.1").unwrap();
+
+    for shell in ["bash", "zsh", "fish"] {
+        if !shell_available(shell) {
+            continue;
+        }
+        let path = path_with(&test_home, &[&java_home.join("bin")]);
+        validate_command(&kopi_home, test_home.path(), path, shell)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "JAVA_HOME:  {}",
+                java_home.display()
+            )))
+            .stdout(predicate::str::contains("OK: the"));
+    }
+}
+
+#[test]
+#[serial]
+fn test_validate_reports_shadowing_java() {
+    let test_home = TestHomeGuard::new();
+    let kopi_home = test_home.kopi_home();
+    let java_home = create_fake_jdk(&kopi_home.join("jdks"), "temurin-21.0.1");
+    let system_jdk = create_fake_jdk(&test_home.path().join("system"), "jdk-17");
+    fs::write(test_home.path().join(".kopi-version"), "temurin@21.0.1").unwrap();
+
+    let path = path_with(
+        &test_home,
+        &[&system_jdk.join("bin"), &java_home.join("bin")],
+    );
+    validate_command(&kopi_home, test_home.path(), path, "bash")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(format!(
+            "java.home:  {}",
+            system_jdk.display()
+        )))
+        .stderr(predicate::str::contains("comes first on PATH"));
+}
+
+#[test]
+#[serial]
+fn test_validate_reports_missing_java() {
+    let test_home = TestHomeGuard::new();
+    let kopi_home = test_home.kopi_home();
+    create_fake_jdk(&kopi_home.join("jdks"), "temurin-21.0.1");
+    fs::write(test_home.path().join(".kopi-version"), "temurin@21.0.1").unwrap();
+
+    validate_command(
+        &kopi_home,
+        test_home.path(),
+        path_with(&test_home, &[]),
+        "bash",
+    )
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("No java on PATH"));
+}
@@ -111,3 +111,32 @@ fn test_version_file_format() {
     let content = fs::read_to_string(&version_file).unwrap();
     assert_eq!(content, "17");
 }
+
+#[test]
+fn test_global_show_and_unset() {
+    let temp_home = setup_test_home();
+    let version_file = temp_home.path().join("version");
+    fs::write(&version_file, "corretto@17").unwrap();
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", temp_home.path())
+        .args(["global", "--show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("corretto@17"));
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", temp_home.path())
+        .args(["global", "--unset"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Global JDK version unset"));
+    assert!(!version_file.exists());
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", temp_home.path())
+        .args(["global", "--show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No global JDK version is set"));
+}
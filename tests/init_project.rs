@@ -0,0 +1,122 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+mod common;
+use common::TestHomeGuard;
+
+fn kopi(test_home: &TestHomeGuard, project: &TempDir, user_home: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .env("HOME", user_home.path())
+        .env("GRADLE_USER_HOME", user_home.path().join(".gradle"))
+        .current_dir(project.path());
+    cmd
+}
+
+#[test]
+fn test_init_pins_version_and_prints_next_steps() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let project = TempDir::new().unwrap();
+    let user_home = TempDir::new().unwrap();
+
+    kopi(&test_home, &project, &user_home)
+        .args([
+            "init",
+            "--dist",
+            "temurin",
+            "--version",
+            "21",
+            "--kopi-toml",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Next steps:"))
+        .stdout(predicate::str::contains("kopi install temurin@21"));
+
+    assert_eq!(
+        fs::read_to_string(project.path().join(".kopi-version")).unwrap(),
+        "temurin@21"
+    );
+    let kopi_toml = fs::read_to_string(project.path().join(".kopi.toml")).unwrap();
+    assert!(kopi_toml.contains("default_distribution = \"temurin\""));
+
+    // Existing files are only replaced with --force
+    kopi(&test_home, &project, &user_home)
+        .args(["init", "--version", "17"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    kopi(&test_home, &project, &user_home)
+        .args(["init", "--version", "17", "--force"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(project.path().join(".kopi-version")).unwrap(),
+        "17"
+    );
+}
+
+#[test]
+fn test_init_from_release_file_exports_maven_toolchains() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let project = TempDir::new().unwrap();
+    let user_home = TempDir::new().unwrap();
+    fs::write(
+        project.path().join("pom.xml"),
+        "<project><build><plugins><plugin>\
+         <artifactId>maven-toolchains-plugin</artifactId>\
+         <configuration><toolchains><jdk><version>17</version></jdk></toolchains></configuration>\
+         </plugin></plugins></build></project>",
+    )
+    .unwrap();
+
+    kopi(&test_home, &project, &user_home)
+        .args(["init", "--from-release-file", "--maven"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created .kopi-version for 17"));
+
+    assert_eq!(
+        fs::read_to_string(project.path().join(".kopi-version")).unwrap(),
+        "17"
+    );
+    let toolchains = fs::read_to_string(user_home.path().join(".m2/toolchains.xml")).unwrap();
+    assert!(toolchains.contains("<!-- kopi:begin"));
+}
+
+#[test]
+fn test_init_requires_a_version_source() {
+    let project = TempDir::new().unwrap();
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .current_dir(project.path())
+        .arg("init")
+        .assert()
+        .failure();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .current_dir(project.path())
+        .args(["init", "bash", "--version", "21"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
@@ -68,6 +68,23 @@ fn test_install_unknown_distribution() {
     assert!(stderr.contains("Unknown distribution"));
 }
 
+#[test]
+fn test_install_batch_reports_every_failure() {
+    let (stdout, _, success, _temp_dir) =
+        run_kopi_with_test_home(&["install", "invalid", "unknown@21", "--json"]);
+    assert!(!success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON output");
+    assert_eq!(json["code"], "KOPI_E_BATCH_FAILED");
+    assert_eq!(json["message"], "install failed for 2 of 2 items");
+    let failures = json["failures"].as_array().unwrap();
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0]["item"], "invalid");
+    assert_eq!(failures[0]["code"], "KOPI_E_INVALID_VERSION");
+    assert_eq!(failures[1]["item"], "unknown@21");
+    assert!(failures[1]["code"].as_str().unwrap().starts_with("KOPI_E_"));
+}
+
 #[test]
 fn test_install_distribution_without_version() {
     let (_, stderr, success) = run_kopi(&["install", "temurin"]);
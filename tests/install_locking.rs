@@ -1,11 +1,14 @@
 use std::ffi::OsString;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 use kopi::config::LockingConfig;
 use kopi::error::KopiError;
+use kopi::indicator::{ProgressIndicator, SilentProgress};
 use kopi::locking::{
     LockBackend, LockController, LockTimeoutValue, ScopedPackageLockGuard,
-    installation_lock_scope_from_package,
+    acquire_installation_lock, installation_lock_scope_from_package,
 };
 use kopi::models::api::{Links, Package};
 use kopi::platform::{AdvisorySupport, FilesystemInfo, FilesystemInspector, FilesystemKind};
@@ -34,9 +37,11 @@ fn sample_package() -> Package {
         lib_c_type: Some("gnu".to_string()),
         package_type: "JDK".to_string(),
         javafx_bundled: false,
+        feature: Vec::new(),
         term_of_support: Some("lts".to_string()),
         release_status: Some("ga".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     }
 }
 
@@ -101,6 +106,46 @@ fn installation_lock_honours_timeout() {
     drop(guard);
 }
 
+fn silent_feedback() -> Arc<Mutex<Box<dyn ProgressIndicator>>> {
+    Arc::new(Mutex::new(Box::new(SilentProgress)))
+}
+
+#[test]
+fn installation_lock_reports_uncontended_acquisition() {
+    let temp = TempDir::new().unwrap();
+    let controller = LockController::with_default_inspector(
+        temp.path().to_path_buf(),
+        &LockingConfig::default(),
+    );
+    let scope = installation_lock_scope_from_package(&sample_package()).unwrap();
+
+    let lock = acquire_installation_lock(&controller, scope, silent_feedback()).unwrap();
+    assert!(!lock.waited_for_peer());
+    lock.into_acquisition().release().unwrap();
+}
+
+#[test]
+fn installation_lock_reports_waiting_for_peer() {
+    let temp = TempDir::new().unwrap();
+    let controller = LockController::with_default_inspector(
+        temp.path().to_path_buf(),
+        &LockingConfig::default(),
+    );
+    let scope = installation_lock_scope_from_package(&sample_package()).unwrap();
+
+    let peer = controller.acquire(scope.clone()).unwrap();
+    let releaser = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        peer.release().unwrap();
+    });
+
+    let lock = acquire_installation_lock(&controller, scope, silent_feedback()).unwrap();
+    releaser.join().unwrap();
+
+    assert!(lock.waited_for_peer());
+    lock.into_acquisition().release().unwrap();
+}
+
 #[derive(Debug)]
 struct FallbackInspector;
 
@@ -0,0 +1,236 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The fake jlink and jdeps are shell scripts
+#![cfg(unix)]
+
+mod common;
+
+use assert_cmd::Command;
+use common::{TestHomeGuard, fixtures};
+use predicates::prelude::*;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+fn write_script(path: &Path, body: &str) {
+    fs::write(path, format!("#!/bin/sh\n{body}")).unwrap();
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// Install a fake temurin 21 whose jlink writes its arguments into the
+/// image's `release` file and whose jdeps reports `java.base,java.sql`
+fn setup_jdk(guard: &TestHomeGuard) {
+    guard.setup_kopi_structure();
+    let jdk = fixtures::create_test_jdk_fs(&guard.kopi_home(), "temurin", "21.0.5+11");
+    write_script(
+        &jdk.join("bin").join("jlink"),
+        "args=\"$*\"\n\
+         while [ $# -gt 0 ]; do\n  case \"$1\" in --output) out=\"$2\"; shift;; esac\n  shift\ndone\n\
+         mkdir -p \"$out/bin\" && echo \"$args\" > \"$out/release\"\n",
+    );
+    write_script(
+        &jdk.join("bin").join("jdeps"),
+        "echo 'Warning: split package'\necho 'java.base,java.sql'\n",
+    );
+}
+
+fn kopi(guard: &TestHomeGuard) -> Command {
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", guard.kopi_home())
+        .current_dir(guard.path());
+    cmd
+}
+
+#[test]
+fn test_jlink_create_list_remove() {
+    let guard = TestHomeGuard::new();
+    setup_jdk(&guard);
+    fs::write(guard.path().join("app.jar"), "jar").unwrap();
+
+    kopi(&guard)
+        .args([
+            "jlink",
+            "create",
+            "app",
+            "--jdk",
+            "temurin@21",
+            "--modules",
+            "java.logging",
+            "--jar",
+            "app.jar",
+        ])
+        .assert()
+        .success();
+
+    let image = guard.kopi_home().join("runtimes").join("app");
+    let release = fs::read_to_string(image.join("release")).unwrap();
+    assert!(release.contains("--add-modules java.base,java.logging,java.sql"));
+    assert!(release.contains("--strip-debug"));
+
+    let output = kopi(&guard)
+        .args(["jlink", "list", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let list: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(list[0]["name"], "app");
+    assert_eq!(list[0]["distribution"], "temurin");
+    assert_eq!(list[0]["version"], "21.0.5+11");
+    assert_eq!(list[0]["exists"], true);
+
+    // The name is taken until --force replaces the image
+    kopi(&guard)
+        .args([
+            "jlink",
+            "create",
+            "app",
+            "--jdk",
+            "temurin@21",
+            "--modules",
+            "java.base",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+    kopi(&guard)
+        .args([
+            "jlink",
+            "create",
+            "app",
+            "--jdk",
+            "temurin@21",
+            "--modules",
+            "java.base",
+            "--keep-debug",
+            "--force",
+        ])
+        .assert()
+        .success();
+    let release = fs::read_to_string(image.join("release")).unwrap();
+    assert!(release.contains("--add-modules java.base "));
+    assert!(!release.contains("--strip-debug"));
+
+    kopi(&guard)
+        .args(["jlink", "remove", "app"])
+        .assert()
+        .success();
+    assert!(!image.exists());
+    kopi(&guard)
+        .args(["jlink", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No runtime images"));
+}
+
+#[test]
+fn test_jlink_create_uses_pinned_jdk_and_output_dir() {
+    let guard = TestHomeGuard::new();
+    setup_jdk(&guard);
+    fs::write(guard.path().join(".kopi-version"), "temurin@21.0.5+11").unwrap();
+
+    kopi(&guard)
+        .args([
+            "jlink",
+            "create",
+            "slim",
+            "--modules",
+            "java.base",
+            "--output",
+            "build/runtime",
+        ])
+        .assert()
+        .success();
+    assert!(guard.path().join("build/runtime/release").is_file());
+
+    kopi(&guard)
+        .args(["jlink", "list"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("slim").and(predicate::str::contains("temurin@21.0.5+11")),
+        );
+}
+
+#[test]
+fn test_jlink_create_rejects_bad_input() {
+    let guard = TestHomeGuard::new();
+    setup_jdk(&guard);
+
+    kopi(&guard)
+        .args([
+            "jlink",
+            "create",
+            "../escape",
+            "--jdk",
+            "temurin@21",
+            "--modules",
+            "java.base",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid runtime name"));
+    kopi(&guard)
+        .args(["jlink", "remove", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No runtime named 'missing'"));
+}
+
+#[test]
+fn test_jlink_create_force_never_replaces_jdks_or_foreign_dirs() {
+    let guard = TestHomeGuard::new();
+    setup_jdk(&guard);
+    let jdk = guard.kopi_home().join("jdks").join("temurin-21.0.5+11");
+
+    kopi(&guard)
+        .args([
+            "jlink",
+            "create",
+            "app",
+            "--jdk",
+            "temurin@21",
+            "--modules",
+            "java.base",
+        ])
+        .arg("--output")
+        .arg(&jdk)
+        .arg("--force")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("overlaps the installed JDKs"));
+    assert!(jdk.join("bin").join("jlink").is_file());
+
+    // A directory that merely looks like an image was not created by kopi
+    let foreign = guard.path().join("foreign");
+    fs::create_dir_all(&foreign).unwrap();
+    fs::write(foreign.join("release"), "JAVA_VERSION=\"21\"").unwrap();
+    kopi(&guard)
+        .args([
+            "jlink",
+            "create",
+            "app",
+            "--jdk",
+            "temurin@21",
+            "--modules",
+            "java.base",
+        ])
+        .args(["--output", "foreign", "--force"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "not a runtime image created by kopi",
+        ));
+    assert!(foreign.join("release").is_file());
+}
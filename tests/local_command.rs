@@ -97,6 +97,43 @@ fn test_local_with_distribution() {
     assert_eq!(content, "temurin@17");
 }
 
+#[test]
+fn test_pin_track_writes_track_to_version_file() {
+    let (temp_dir, test_home) = setup_test_environment();
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .env("KOPI_AUTO_INSTALL__ENABLED", "false")
+        .current_dir(temp_dir.path())
+        .arg("pin")
+        .arg("--track")
+        .arg("temurin@latest-lts")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("kopi upgrade"));
+
+    let content = fs::read_to_string(temp_dir.path().join(".kopi-version")).unwrap();
+    assert_eq!(content, "temurin@latest-lts");
+}
+
+#[test]
+fn test_pin_track_rejects_fixed_version() {
+    let (temp_dir, test_home) = setup_test_environment();
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .env("KOPI_AUTO_INSTALL__ENABLED", "false")
+        .current_dir(temp_dir.path())
+        .arg("pin")
+        .arg("--track")
+        .arg("21")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a version track"));
+
+    assert!(!temp_dir.path().join(".kopi-version").exists());
+}
+
 #[test]
 fn test_local_shows_install_hint_when_not_installed() {
     let (temp_dir, test_home) = setup_test_environment();
@@ -265,3 +302,97 @@ fn test_local_with_auto_install_prompt() {
         assert!(version_file.exists());
     }
 }
+
+#[test]
+fn test_local_show_and_unset() {
+    let (temp_dir, test_home) = setup_test_environment();
+    let version_file = temp_dir.path().join(".kopi-version");
+    fs::write(&version_file, "temurin@21").unwrap();
+    let sub_dir = temp_dir.path().join("module");
+    fs::create_dir(&sub_dir).unwrap();
+
+    // --show finds the pin in a parent directory
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(&sub_dir)
+        .args(["local", "--show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("temurin@21"))
+        .stdout(predicate::str::contains(".kopi-version"));
+
+    // --unset only touches the current directory
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(&sub_dir)
+        .args(["local", "--unset"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("project version comes from"));
+    assert!(version_file.exists());
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(temp_dir.path())
+        .args(["local", "--unset"])
+        .assert()
+        .success();
+    assert!(!version_file.exists());
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(temp_dir.path())
+        .args(["local", "--show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No local JDK version is set"));
+}
+
+#[test]
+fn test_local_unset_conflicts_with_version() {
+    let (temp_dir, test_home) = setup_test_environment();
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(temp_dir.path())
+        .args(["local", "21", "--unset"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_local_auto_pins_the_build_file_version() {
+    let (temp_dir, test_home) = setup_test_environment();
+    fs::write(
+        temp_dir.path().join("build.gradle"),
+        "java {\n    sourceCompatibility = JavaVersion.VERSION_17\n}\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .env("KOPI_AUTO_INSTALL__ENABLED", "false")
+        .current_dir(temp_dir.path())
+        .args(["local", "--auto"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Detected Java 17 (sourceCompatibility in",
+        ))
+        .stdout(predicate::str::contains(
+            "Created .kopi-version file for 17",
+        ));
+
+    let content = fs::read_to_string(temp_dir.path().join(".kopi-version")).unwrap();
+    assert_eq!(content, "17");
+
+    // Nothing to detect
+    fs::remove_file(temp_dir.path().join("build.gradle")).unwrap();
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(temp_dir.path())
+        .args(["local", "--auto"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No Java version declared"));
+}
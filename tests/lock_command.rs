@@ -0,0 +1,121 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+mod common;
+use common::TestHomeGuard;
+
+fn current_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        other => other,
+    }
+}
+
+fn write_lock(dir: &std::path::Path, os: &str, arch: &str) {
+    let content = format!(
+        r#"version = 1
+spec = "temurin@21"
+generated_at = "2025-01-01T00:00:00Z"
+
+[[package]]
+os = "{os}"
+arch = "{arch}"
+id = "locked-package-id"
+distribution = "temurin"
+java_version = "21.0.5+11"
+distribution_version = "21.0.5+11"
+package_type = "jdk"
+archive_type = "tar.gz"
+javafx_bundled = false
+download_url = "https://example.com/temurin-21.0.5.tar.gz"
+checksum = "0000"
+checksum_type = "sha256"
+size = 1
+"#
+    );
+    fs::write(dir.join("kopi.lock"), content).unwrap();
+}
+
+#[test]
+fn test_install_locked_requires_lock_file() {
+    let project = TempDir::new().unwrap();
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(project.path())
+        .args(["install", "--locked"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("kopi lock"));
+}
+
+#[test]
+fn test_install_locked_dry_run_uses_locked_package() {
+    let project = TempDir::new().unwrap();
+    let nested = project.path().join("module");
+    fs::create_dir_all(&nested).unwrap();
+    write_lock(project.path(), std::env::consts::OS, current_arch());
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+
+    let output = Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(&nested)
+        .args(["install", "--locked", "--dry-run"])
+        .output()
+        .unwrap();
+
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(output.status.success(), "{combined}");
+    assert!(combined.contains("21.0.5+11"), "{combined}");
+}
+
+#[test]
+fn test_install_locked_rejects_missing_platform() {
+    let project = TempDir::new().unwrap();
+    write_lock(project.path(), "aix", "ppc64");
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(project.path())
+        .args(["install", "--locked"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("kopi lock --platform"));
+}
+
+#[test]
+fn test_install_locked_conflicts_with_version() {
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .args(["install", "21", "--locked"])
+        .assert()
+        .failure();
+}
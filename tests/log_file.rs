@@ -0,0 +1,65 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use assert_cmd::Command;
+use std::fs;
+
+mod common;
+use common::TestHomeGuard;
+
+#[test]
+fn test_log_file_flag_writes_text_records() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let log_path = test_home.path().join("logs").join("kopi.log");
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["-vv", "--log-file"])
+        .arg(&log_path)
+        .arg("list")
+        .assert()
+        .success();
+
+    let contents = fs::read_to_string(&log_path).unwrap();
+    assert!(contents.lines().any(|line| line.contains(" DEBUG [kopi::")));
+}
+
+#[test]
+fn test_logging_config_writes_json_records() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    fs::write(
+        test_home.kopi_home().join("config.toml"),
+        "[logging]\nfile = \"kopi.log\"\nformat = \"json\"\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["-vv", "list"])
+        .assert()
+        .success();
+
+    let contents = fs::read_to_string(test_home.kopi_home().join("kopi.log")).unwrap();
+    let first = contents
+        .lines()
+        .next()
+        .expect("log file should not be empty");
+    let entry: serde_json::Value = serde_json::from_str(first).unwrap();
+    assert_eq!(entry["level"], "DEBUG");
+    assert!(entry["target"].as_str().unwrap().starts_with("kopi"));
+    assert!(entry["timestamp"].is_string());
+}
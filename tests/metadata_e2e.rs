@@ -150,6 +150,7 @@ fn test_metadata_provider_basic_search() {
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://example.com/temurin-21.tar.gz".to_string()),
             checksum: Some("abc123".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -158,6 +159,7 @@ fn test_metadata_provider_basic_search() {
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         },
         JdkMetadata {
             id: "zulu-21.0.2".to_string(),
@@ -169,6 +171,7 @@ fn test_metadata_provider_basic_search() {
             package_type: PackageType::Jdk,
             archive_type: ArchiveType::TarGz,
             javafx_bundled: false,
+            features: Vec::new(),
             download_url: Some("https://example.com/zulu-21.tar.gz".to_string()),
             checksum: Some("def456".to_string()),
             checksum_type: Some(ChecksumType::Sha256),
@@ -177,6 +180,7 @@ fn test_metadata_provider_basic_search() {
             term_of_support: None,
             release_status: None,
             latest_build_available: None,
+            release_date: None,
         },
     ];
 
@@ -210,6 +214,7 @@ fn test_metadata_provider_fallback() {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/primary.tar.gz".to_string()),
         checksum: Some("primary123".to_string()),
         checksum_type: Some(ChecksumType::Sha256),
@@ -218,6 +223,7 @@ fn test_metadata_provider_fallback() {
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     }];
 
     let fallback_metadata = vec![JdkMetadata {
@@ -230,6 +236,7 @@ fn test_metadata_provider_fallback() {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/fallback.tar.gz".to_string()),
         checksum: Some("fallback456".to_string()),
         checksum_type: Some(ChecksumType::Sha256),
@@ -238,6 +245,7 @@ fn test_metadata_provider_fallback() {
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     }];
 
     let _primary = Arc::new(MockMetadataSource::new(primary_metadata));
@@ -393,6 +401,7 @@ fn test_metadata_cache_integration() {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/cached.tar.gz".to_string()),
         checksum: Some("cached123".to_string()),
         checksum_type: Some(ChecksumType::Sha256),
@@ -401,6 +410,7 @@ fn test_metadata_cache_integration() {
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     };
 
     // MetadataCache doesn't have add_packages method, it stores data differently
@@ -422,6 +432,7 @@ fn test_concurrent_metadata_access() {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/concurrent.tar.gz".to_string()),
         checksum: Some("concurrent123".to_string()),
         checksum_type: Some(ChecksumType::Sha256),
@@ -430,6 +441,7 @@ fn test_concurrent_metadata_access() {
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     }];
 
     let source = MockMetadataSource::new(metadata);
@@ -480,6 +492,7 @@ fn test_error_handling_and_recovery() {
         package_type: PackageType::Jdk,
         archive_type: ArchiveType::TarGz,
         javafx_bundled: false,
+        features: Vec::new(),
         download_url: Some("https://example.com/recovery.tar.gz".to_string()),
         checksum: Some("recovery123".to_string()),
         checksum_type: Some(ChecksumType::Sha256),
@@ -488,6 +501,7 @@ fn test_error_handling_and_recovery() {
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     }];
 
     let source = MockMetadataSource::new(metadata.clone());
@@ -573,3 +587,63 @@ base_url = "https://api.foojay.io"
     assert!(sources.contains(&"github"));
     assert!(sources.contains(&"foojay-api"));
 }
+
+/// Test that a `type = "command"` source runs its executable and feeds the
+/// packages it prints into the provider
+#[cfg(unix)]
+#[test]
+fn test_command_source_from_config() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let kopi_home = temp_dir.path().to_path_buf();
+
+    let packages = serde_json::json!({
+        "packages": [JdkMetadata {
+            id: "plugin-21".to_string(),
+            distribution: "acme".to_string(),
+            version: Version::new(21, 0, 4),
+            distribution_version: Version::new(21, 0, 4),
+            architecture: Architecture::X64,
+            operating_system: OperatingSystem::Linux,
+            package_type: PackageType::Jdk,
+            archive_type: ArchiveType::TarGz,
+            javafx_bundled: false,
+            features: Vec::new(),
+            download_url: Some("https://example.com/acme-21.tar.gz".to_string()),
+            checksum: None,
+            checksum_type: None,
+            size: 100_000_000,
+            lib_c_type: None,
+            term_of_support: None,
+            release_status: None,
+            latest_build_available: None,
+            release_date: None,
+        }]
+    });
+    let plugin = kopi_home.join("kopi-source-acme");
+    std::fs::write(
+        &plugin,
+        format!("#!/bin/sh\ncat > /dev/null\necho '{packages}'\n"),
+    )
+    .unwrap();
+    std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let config_content = r#"
+[[metadata.sources]]
+type = "command"
+name = "acme-plugin"
+command = "${KOPI_HOME}/kopi-source-acme"
+"#;
+    std::fs::write(kopi_home.join("config.toml"), config_content).unwrap();
+
+    let config = KopiConfig::new(kopi_home).unwrap();
+    let provider = MetadataProvider::from_config(&config).unwrap();
+    assert_eq!(provider.list_sources(), vec!["acme-plugin"]);
+
+    let mut progress = SilentProgress;
+    let fetched = provider.fetch_all(&mut progress).unwrap();
+    assert_eq!(fetched.len(), 1);
+    assert_eq!(fetched[0].id, "plugin-21");
+    assert_eq!(fetched[0].distribution, "acme");
+}
@@ -44,9 +44,11 @@ fn create_test_metadata() -> JdkMetadata {
         size: 100_000_000,
         lib_c_type: Some("glibc".to_string()),
         javafx_bundled: false,
+        features: Vec::new(),
         term_of_support: Some("lts".to_string()),
         release_status: Some("ga".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     }
 }
 
@@ -94,6 +96,7 @@ fn create_test_index_with_size(total_size: u64) -> IndexFile {
             },
         ],
         generator_config: None,
+        previous_updated: None,
     }
 }
 
@@ -280,6 +283,7 @@ fn test_local_never_creates_child_progress() {
         updated: "2024-01-15T10:00:00Z".to_string(),
         files: vec![], // Empty for this test
         generator_config: None,
+        previous_updated: None,
     };
 
     let index_path = metadata_dir.join("index.json");
@@ -328,6 +332,7 @@ fn test_http_distribution_with_large_files() {
             last_modified: Some("2024-01-15T09:00:00Z".to_string()),
         }],
         generator_config: None,
+        previous_updated: None,
     };
 
     let metadata = vec![create_test_metadata()];
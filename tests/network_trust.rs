@@ -0,0 +1,54 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestHomeGuard;
+use predicates::prelude::*;
+
+fn kopi(guard: &TestHomeGuard) -> Command {
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", guard.kopi_home())
+        .env(
+            "KOPI_NETWORK__CA_BUNDLE",
+            guard.path().join("missing-ca.pem"),
+        )
+        .current_dir(guard.path());
+    cmd
+}
+
+#[test]
+fn test_unreadable_ca_bundle_fails_the_command() {
+    let guard = TestHomeGuard::new();
+    guard.setup_kopi_structure();
+
+    kopi(&guard)
+        .args(["list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing-ca.pem"));
+}
+
+#[test]
+fn test_unreadable_ca_bundle_still_allows_fixing_the_config() {
+    let guard = TestHomeGuard::new();
+    guard.setup_kopi_structure();
+
+    kopi(&guard)
+        .args(["config", "get", "network.ca_bundle"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("missing-ca.pem"));
+}
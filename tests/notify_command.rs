@@ -0,0 +1,127 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_cmd::Command;
+use kopi::cache::{DistributionCache, MetadataCache};
+use kopi::config::KopiConfig;
+use kopi::models::distribution::Distribution;
+use kopi::models::metadata::JdkMetadata;
+use kopi::models::package::{ArchiveType, PackageType};
+use kopi::models::platform::{Architecture, OperatingSystem};
+use kopi::paths::cache::notify_state_file;
+use kopi::platform::{get_current_architecture, get_current_os};
+use kopi::version::Version;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+mod common;
+use common::TestHomeGuard;
+use common::fixtures::create_test_jdk_fs;
+
+fn write_cache(test_home: &TestHomeGuard, latest: &str) {
+    let package = JdkMetadata {
+        id: format!("temurin-{latest}"),
+        distribution: "temurin".to_string(),
+        version: Version::from_str(latest).unwrap(),
+        distribution_version: Version::from_str(latest).unwrap(),
+        architecture: Architecture::from_str(&get_current_architecture()).unwrap(),
+        operating_system: OperatingSystem::from_str(&get_current_os()).unwrap(),
+        package_type: PackageType::Jdk,
+        archive_type: ArchiveType::TarGz,
+        download_url: None,
+        checksum: None,
+        checksum_type: None,
+        size: 0,
+        lib_c_type: None,
+        javafx_bundled: false,
+        features: Vec::new(),
+        term_of_support: None,
+        release_status: None,
+        latest_build_available: Some(true),
+        release_date: None,
+    };
+
+    let mut cache = MetadataCache::new();
+    cache.distributions.insert(
+        "temurin".to_string(),
+        DistributionCache {
+            distribution: Distribution::Temurin,
+            display_name: "Eclipse Temurin".to_string(),
+            packages: vec![package],
+        },
+    );
+
+    let config = KopiConfig::new(test_home.kopi_home()).unwrap();
+    let path = config.metadata_cache_path().unwrap();
+    fs::write(path, serde_json::to_string(&cache).unwrap()).unwrap();
+}
+
+fn notify(test_home: &TestHomeGuard, args: &[&str]) -> String {
+    let output = Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .env_remove("KOPI_JAVA_VERSION")
+        .current_dir(test_home.path())
+        .arg("notify")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn pin_globally(kopi_home: &Path, spec: &str) {
+    fs::write(kopi_home.join("version"), spec).unwrap();
+}
+
+#[test]
+fn test_notify_check_reports_newer_patch_once_a_day() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "21.0.4");
+    pin_globally(&test_home.kopi_home(), "temurin@21.0.4");
+    write_cache(&test_home, "21.0.5");
+
+    let first = notify(&test_home, &["--check"]);
+    assert!(first.contains("temurin@21.0.5 is available"), "{first}");
+    assert!(first.contains("21.0.4 is pinned by"), "{first}");
+
+    // Rate-limited: a second check the same day prints nothing
+    assert_eq!(notify(&test_home, &["--check"]), "");
+
+    // The recorded result stays readable without checking again
+    assert_eq!(notify(&test_home, &[]), first);
+    assert_eq!(notify(&test_home, &["--check", "--force"]), first);
+
+    let state: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(notify_state_file(&test_home.kopi_home())).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(state["updates"][0]["latest"], "21.0.5");
+}
+
+#[test]
+fn test_notify_check_is_silent_when_up_to_date_or_uncached() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "21.0.5");
+    pin_globally(&test_home.kopi_home(), "temurin@21");
+
+    // Without a metadata cache there is nothing to compare against
+    assert_eq!(notify(&test_home, &["--check"]), "");
+
+    write_cache(&test_home, "21.0.5");
+    assert_eq!(notify(&test_home, &["--check", "--force"]), "");
+}
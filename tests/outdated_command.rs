@@ -0,0 +1,157 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_cmd::Command;
+use kopi::cache::{DistributionCache, MetadataCache};
+use kopi::config::KopiConfig;
+use kopi::models::distribution::Distribution;
+use kopi::models::metadata::JdkMetadata;
+use kopi::models::package::{ArchiveType, PackageType};
+use kopi::models::platform::{Architecture, OperatingSystem};
+use kopi::platform::{get_current_architecture, get_current_os};
+use kopi::version::Version;
+use predicates::prelude::*;
+use std::fs;
+use std::str::FromStr;
+
+mod common;
+use common::TestHomeGuard;
+use common::fixtures::create_test_jdk_fs;
+
+fn write_cache(test_home: &TestHomeGuard, latest: &str) {
+    let package = JdkMetadata {
+        id: format!("temurin-{latest}"),
+        distribution: "temurin".to_string(),
+        version: Version::from_str(latest).unwrap(),
+        distribution_version: Version::from_str(latest).unwrap(),
+        architecture: Architecture::from_str(&get_current_architecture()).unwrap(),
+        operating_system: OperatingSystem::from_str(&get_current_os()).unwrap(),
+        package_type: PackageType::Jdk,
+        archive_type: ArchiveType::TarGz,
+        download_url: None,
+        checksum: None,
+        checksum_type: None,
+        size: 0,
+        lib_c_type: None,
+        javafx_bundled: false,
+        features: Vec::new(),
+        term_of_support: None,
+        release_status: None,
+        latest_build_available: Some(true),
+        release_date: None,
+    };
+
+    let mut cache = MetadataCache::new();
+    cache.distributions.insert(
+        "temurin".to_string(),
+        DistributionCache {
+            distribution: Distribution::Temurin,
+            display_name: "Eclipse Temurin".to_string(),
+            packages: vec![package],
+        },
+    );
+
+    let config = KopiConfig::new(test_home.kopi_home()).unwrap();
+    let path = config.metadata_cache_path().unwrap();
+    fs::write(path, serde_json::to_string(&cache).unwrap()).unwrap();
+}
+
+#[test]
+fn test_outdated_without_installed_jdks() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["outdated", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[]"));
+}
+
+#[test]
+fn test_outdated_reports_newer_build_with_exit_code() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "21.0.4");
+    write_cache(&test_home, "21.0.5");
+
+    let output = Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["outdated", "--json"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(10));
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entries[0]["distribution"], "temurin");
+    assert_eq!(entries[0]["current"], "21.0.4");
+    assert_eq!(entries[0]["latest"], "21.0.5");
+    assert_eq!(entries[0]["outdated"], true);
+}
+
+#[test]
+fn test_outdated_up_to_date_succeeds() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "21.0.5");
+    write_cache(&test_home, "21.0.5");
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .arg("outdated")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("up to date"));
+}
+
+#[test]
+fn test_list_remote_installed_only_diff() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "21.0.4");
+    write_cache(&test_home, "21.0.5");
+
+    // Unlike `kopi outdated`, the listing succeeds when updates are available
+    let output = Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["list", "--remote", "--installed-only-diff", "--json"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entries[0]["current"], "21.0.4");
+    assert_eq!(entries[0]["latest"], "21.0.5");
+    assert_eq!(entries[0]["releases_behind"], 1);
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["list", "--remote"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("21.0.5").and(predicate::str::contains("21.0.4")));
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["list", "--installed-only-diff"])
+        .assert()
+        .failure();
+}
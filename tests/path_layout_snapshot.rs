@@ -10,6 +10,7 @@ fn path_layout_snapshot_matches_expected_structure() {
     assert_eq!(home::cache_dir(kopi_home), kopi_home.join("cache"));
     assert_eq!(home::shims_dir(kopi_home), kopi_home.join("shims"));
     assert_eq!(home::bin_dir(kopi_home), kopi_home.join("bin"));
+    assert_eq!(home::downloads_dir(kopi_home), kopi_home.join("downloads"));
     let locks_dir = home::locks_dir(kopi_home);
     assert_eq!(locks_dir.parent(), Some(kopi_home));
     assert_eq!(
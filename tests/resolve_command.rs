@@ -0,0 +1,83 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use assert_cmd::Command as AssertCommand;
+use common::{TestHomeGuard, fixtures};
+use predicates::prelude::*;
+
+#[test]
+fn test_resolve_traces_project_version() {
+    let guard = TestHomeGuard::new();
+    let _guard = guard.setup_kopi_structure();
+
+    fixtures::create_test_jdk_fs(&_guard.kopi_home(), "temurin", "21.0.5+11");
+    fixtures::create_test_jdk_fs(&_guard.kopi_home(), "temurin", "17.0.11+9");
+    std::fs::write(_guard.path().join(".kopi-version"), "temurin@21").unwrap();
+
+    let output = AssertCommand::new(env!("CARGO_BIN_EXE_kopi"))
+        .env("KOPI_HOME", _guard.kopi_home())
+        .current_dir(_guard.path())
+        .args(["resolve", "javac", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let trace: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(trace["tool"], "javac");
+    assert_eq!(trace["tool_kind"], "jdk");
+    assert!(
+        trace["version_source"]
+            .as_str()
+            .unwrap()
+            .starts_with("project file:")
+    );
+    assert_eq!(trace["version_request"], "temurin@21");
+    assert_eq!(trace["candidates"].as_array().unwrap().len(), 2);
+    assert_eq!(trace["chosen"]["version"], "21.0.5+11");
+    assert!(trace["tool_path"].as_str().unwrap().contains("javac"));
+    assert!(trace.get("error").is_none());
+}
+
+#[test]
+fn test_resolve_reports_where_resolution_stops() {
+    let guard = TestHomeGuard::new();
+    let _guard = guard.setup_kopi_structure();
+
+    fixtures::create_test_jdk_fs(&_guard.kopi_home(), "temurin", "17.0.11+9");
+    std::fs::write(_guard.path().join(".kopi-version"), "temurin@21").unwrap();
+
+    AssertCommand::new(env!("CARGO_BIN_EXE_kopi"))
+        .env("KOPI_HOME", _guard.kopi_home())
+        .current_dir(_guard.path())
+        .args(["resolve", "java"])
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("Candidates:")
+                .and(predicate::str::contains("temurin@17.0.11+9"))
+                .and(predicate::str::contains("Stopped:")),
+        );
+
+    AssertCommand::new(env!("CARGO_BIN_EXE_kopi"))
+        .env("KOPI_HOME", _guard.kopi_home())
+        .args(["resolve", "not-a-tool"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("not a recognized JDK tool"));
+}
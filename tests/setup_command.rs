@@ -0,0 +1,44 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+#[test]
+fn test_setup_check_reports_incomplete_setup_without_changes() {
+    let temp_home = TempDir::new().unwrap();
+    let kopi_home = temp_home.path().join("kopi");
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", &kopi_home)
+        .args(["setup", "--check"])
+        .assert()
+        .code(12)
+        .stdout(predicate::str::contains("Default shims"))
+        .stdout(predicate::str::contains("Setup is incomplete"));
+
+    assert!(!kopi_home.join("shims").exists());
+}
+
+#[test]
+fn test_setup_check_conflicts_with_force() {
+    let temp_home = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("kopi").unwrap();
+    cmd.env("KOPI_HOME", temp_home.path())
+        .args(["setup", "--check", "--force"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
@@ -157,4 +157,37 @@ mod shell_command_tests {
                 "Set JDK version for current shell session",
             ));
     }
+
+    #[test]
+    #[serial]
+    fn test_shell_print_stack() {
+        let _temp_dir = setup_test_env();
+
+        Command::cargo_bin("kopi")
+            .unwrap()
+            .args(["shell", "--print-stack"])
+            .env("KOPI_JAVA_VERSION", "temurin@17.0.9")
+            .env("KOPI_SHELL_STACK", "-,corretto@21.0.1")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "* temurin@17.0.9 (current)\n  corretto@21.0.1\n  (no shell version)",
+            ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_shell_restore_without_stack() {
+        let _temp_dir = setup_test_env();
+
+        Command::cargo_bin("kopi")
+            .unwrap()
+            .args(["shell", "--restore"])
+            .env_remove("KOPI_SHELL_STACK")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "No previous shell version to restore",
+            ));
+    }
 }
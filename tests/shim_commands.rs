@@ -243,6 +243,47 @@ mod shim_command_tests {
             .success();
     }
 
+    #[test]
+    fn test_shim_repair_removes_orphaned_shims() {
+        let test_home = TestHomeGuard::new();
+        let kopi_home = test_home.kopi_home();
+
+        get_test_command(&kopi_home).arg("setup").assert().success();
+
+        // No JDK is installed, so a non-default shim has nothing behind it
+        get_test_command(&kopi_home)
+            .arg("shim")
+            .arg("add")
+            .arg("jconsole")
+            .assert()
+            .success();
+
+        get_test_command(&kopi_home)
+            .arg("shim")
+            .arg("verify")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "No installed JDK provides this tool",
+            ))
+            .stdout(predicate::str::contains("kopi shim repair"));
+
+        get_test_command(&kopi_home)
+            .arg("shim")
+            .arg("repair")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Removed"));
+
+        // Default shims survive the repair
+        get_test_command(&kopi_home)
+            .arg("shim")
+            .arg("verify")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("All shims are valid!"));
+    }
+
     #[test]
     fn test_help_messages() {
         let test_home = TestHomeGuard::new();
@@ -276,6 +317,7 @@ mod shim_command_tests {
             .stdout(predicate::str::contains("add"))
             .stdout(predicate::str::contains("remove"))
             .stdout(predicate::str::contains("list"))
-            .stdout(predicate::str::contains("verify"));
+            .stdout(predicate::str::contains("verify"))
+            .stdout(predicate::str::contains("repair"));
     }
 }
@@ -0,0 +1,151 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shimmed tools run from cmd.exe, PowerShell and Git Bash.
+#![cfg(windows)]
+
+mod common;
+
+use common::TestHomeGuard;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Install a fake `temurin-21.0.5` whose `java` is a batch file, pin it with
+/// `.kopi-version` and create the `java` shim with both wrappers.
+fn setup_java_shim(guard: &TestHomeGuard) -> PathBuf {
+    guard.setup_kopi_structure();
+    let kopi_home = guard.kopi_home();
+
+    // No java.exe, so the shim has to find java.cmd through PATHEXT
+    let bin_dir = kopi_home.join("jdks").join("temurin-21.0.5").join("bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    fs::write(
+        bin_dir.join("java.cmd"),
+        "@echo off\r\necho fake-java %*\r\nexit /b 3\r\n",
+    )
+    .unwrap();
+
+    fs::write(guard.path().join(".kopi-version"), "temurin@21.0.5").unwrap();
+    fs::write(
+        kopi_home.join("config.toml"),
+        "[shims]\nwindows_wrappers = [\"cmd\", \"ps1\"]\n",
+    )
+    .unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_kopi"))
+        .env("KOPI_HOME", &kopi_home)
+        .args(["shim", "add", "java"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let shims_dir = kopi_home.join("shims");
+    for file in ["java.exe", "java.cmd", "java.ps1"] {
+        assert!(shims_dir.join(file).exists(), "{file} was not created");
+    }
+    shims_dir
+}
+
+/// `program` run from the project directory with the shims first on PATH
+fn launch(guard: &TestHomeGuard, shims_dir: &Path, program: &Path, args: &[&str]) -> Output {
+    let path = std::env::join_paths(std::iter::once(shims_dir.to_path_buf()).chain(
+        std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default()),
+    ))
+    .unwrap();
+
+    Command::new(program)
+        .args(args)
+        .current_dir(guard.path())
+        .env("KOPI_HOME", guard.kopi_home())
+        .env("PATH", path)
+        .output()
+        .unwrap()
+}
+
+fn assert_forwarded(output: &Output) {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("fake-java -version"),
+        "unexpected output: {stdout}{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cmd_runs_cmd_wrapper() {
+    let guard = TestHomeGuard::new();
+    let shims_dir = setup_java_shim(&guard);
+
+    let output = launch(
+        &guard,
+        &shims_dir,
+        Path::new("cmd"),
+        &["/C", "java.cmd", "-version"],
+    );
+    assert_forwarded(&output);
+}
+
+#[test]
+fn test_cmd_resolves_java_through_pathext() {
+    let guard = TestHomeGuard::new();
+    let shims_dir = setup_java_shim(&guard);
+
+    let output = launch(
+        &guard,
+        &shims_dir,
+        Path::new("cmd"),
+        &["/C", "java", "-version"],
+    );
+    assert_forwarded(&output);
+}
+
+#[test]
+fn test_powershell_runs_ps1_wrapper() {
+    let guard = TestHomeGuard::new();
+    let shims_dir = setup_java_shim(&guard);
+    let script = shims_dir.join("java.ps1");
+
+    let output = launch(
+        &guard,
+        &shims_dir,
+        Path::new("powershell"),
+        &[
+            "-NoProfile",
+            "-ExecutionPolicy",
+            "Bypass",
+            "-File",
+            script.to_str().unwrap(),
+            "-version",
+        ],
+    );
+    assert_forwarded(&output);
+}
+
+#[test]
+fn test_git_bash_runs_exe_shim() {
+    let git_bash = Path::new(r"C:\Program Files\Git\bin\bash.exe");
+    if !git_bash.exists() {
+        eprintln!("Skipping: Git Bash not found at {}", git_bash.display());
+        return;
+    }
+
+    let guard = TestHomeGuard::new();
+    let shims_dir = setup_java_shim(&guard);
+
+    // Bash does not consult PATHEXT, but it finds `java.exe` for `java`
+    let output = launch(&guard, &shims_dir, git_bash, &["-c", "java -version"]);
+    assert_forwarded(&output);
+}
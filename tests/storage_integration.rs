@@ -62,9 +62,11 @@ fn create_test_package() -> Package {
         lib_c_type: Some("glibc".to_string()),
         package_type: "jdk".to_string(),
         javafx_bundled: false,
+        feature: Vec::new(),
         term_of_support: None,
         release_status: None,
         latest_build_available: None,
+        release_date: None,
     }
 }
 
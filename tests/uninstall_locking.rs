@@ -329,9 +329,11 @@ fn build_metadata(distribution: &str, version: &str) -> JdkMetadataWithInstallat
         lib_c_type: Some("gnu".to_string()),
         package_type: "JDK".to_string(),
         javafx_bundled: false,
+        feature: Vec::new(),
         term_of_support: Some("lts".to_string()),
         release_status: Some("ga".to_string()),
         latest_build_available: Some(true),
+        release_date: None,
     };
 
     let installation_metadata = InstallationMetadata {
@@ -339,6 +341,9 @@ fn build_metadata(distribution: &str, version: &str) -> JdkMetadataWithInstallat
         structure_type: "direct".to_string(),
         platform: "linux_x64".to_string(),
         metadata_version: 1,
+        stripped: Vec::new(),
+        release: None,
+        pinned_sha256: None,
     };
 
     JdkMetadataWithInstallation {
@@ -0,0 +1,84 @@
+// Copyright 2025 dentsusoken
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+use common::TestHomeGuard;
+use common::fixtures::create_test_jdk_fs;
+
+fn write_usage(test_home: &TestHomeGuard, content: &str) {
+    let cache_dir = test_home.kopi_home().join("cache");
+    fs::create_dir_all(&cache_dir).unwrap();
+    fs::write(cache_dir.join("jdk-usage.json"), content).unwrap();
+}
+
+#[test]
+fn test_uninstall_unused_dry_run_lists_last_used_dates() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    let stale = create_test_jdk_fs(&test_home.kopi_home(), "temurin", "17.0.9");
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "21.0.5");
+    write_usage(
+        &test_home,
+        r#"{"temurin-17.0.9":"2020-01-01T00:00:00Z","temurin-21.0.5":"2999-01-01T00:00:00Z"}"#,
+    );
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(test_home.path())
+        .args(["uninstall", "--unused", "--older-than", "90d", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "temurin@17.0.9  last used 2020-01-01 (shim)",
+        ))
+        .stdout(predicate::str::contains("temurin@21.0.5").not());
+
+    assert!(stale.exists());
+}
+
+#[test]
+fn test_uninstall_unused_keeps_pinned_jdks() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+    create_test_jdk_fs(&test_home.kopi_home(), "temurin", "17.0.9");
+    write_usage(&test_home, r#"{"temurin-17.0.9":"2020-01-01T00:00:00Z"}"#);
+    fs::write(test_home.kopi_home().join("version"), "temurin@17.0.9").unwrap();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .current_dir(test_home.path())
+        .args(["uninstall", "--unused", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No unpinned JDKs unused for 90d"));
+}
+
+#[test]
+fn test_older_than_requires_unused() {
+    let test_home = TestHomeGuard::new();
+    test_home.setup_kopi_structure();
+
+    Command::cargo_bin("kopi")
+        .unwrap()
+        .env("KOPI_HOME", test_home.kopi_home())
+        .args(["uninstall", "--older-than", "30d"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--unused"));
+}
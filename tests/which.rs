@@ -125,6 +125,32 @@ fn test_which_json_format() {
     assert!(json["tool_path"].as_str().unwrap().contains("java"));
 }
 
+#[test]
+fn test_which_all_tools_json() {
+    let guard = TestHomeGuard::new();
+    let _guard = guard.setup_kopi_structure();
+
+    // Create a fake installed JDK
+    fixtures::create_test_jdk_fs(&_guard.kopi_home(), "temurin", "21.0.5+11");
+
+    let output = AssertCommand::new(env!("CARGO_BIN_EXE_kopi"))
+        .env("KOPI_HOME", _guard.kopi_home())
+        .args(["which", "--tool", "all", "--json", "temurin@21"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["distribution"], "temurin");
+    let tools = json["tools"].as_array().unwrap();
+    let java = tools.iter().find(|tool| tool["name"] == "java").unwrap();
+    assert!(java["path"].as_str().unwrap().contains("bin"));
+    assert!(java["size"].is_u64());
+    assert!(java["shimmed"].is_boolean());
+}
+
 #[test]
 fn test_which_not_installed() {
     let guard = TestHomeGuard::new();
@@ -139,6 +165,33 @@ fn test_which_not_installed() {
         .stderr(predicate::str::contains("not installed"));
 }
 
+#[test]
+fn test_which_not_installed_json_error() {
+    let guard = TestHomeGuard::new();
+    let _guard = guard.setup_kopi_structure();
+
+    let output = AssertCommand::new(env!("CARGO_BIN_EXE_kopi"))
+        .env("KOPI_HOME", _guard.kopi_home())
+        .args(["which", "--json", "temurin@22"])
+        .assert()
+        .failure()
+        .code(4)
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["code"], "KOPI_E_JDK_NOT_INSTALLED");
+    assert!(json["message"].as_str().unwrap().contains("not installed"));
+    assert!(
+        json["suggestion"]
+            .as_str()
+            .unwrap()
+            .contains("kopi install temurin@22")
+    );
+    assert!(json["details"].is_null());
+}
+
 #[test]
 fn test_which_tool_not_found() {
     let guard = TestHomeGuard::new();